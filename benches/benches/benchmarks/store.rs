@@ -0,0 +1,77 @@
+use crate::benchmarks::util::{gen_always_success_block, new_always_success_chain};
+use ckb_shared::Shared;
+use ckb_store::ChainStore;
+use ckb_types::packed::Byte32;
+use ckb_verification_traits::Switch;
+use criterion::{criterion_group, BenchmarkId, Criterion};
+use std::sync::Arc;
+
+#[cfg(not(feature = "ci"))]
+const SIZES: &[usize] = &[100usize, 500];
+
+#[cfg(feature = "ci")]
+const SIZES: &[usize] = &[5usize];
+
+// The count of the blocks read in one batch, which is the max count of the blocks requested by
+// a GetBlocks message
+const BLOCKS: usize = 32;
+
+// Sets up the chain of the blocks with the transactions, returns the hashes from the tip.
+fn setup_blocks(txs_size: usize) -> (Shared, Vec<Byte32>) {
+    let chains = new_always_success_chain(txs_size, 1);
+    let (ref chain, ref shared) = chains.0[0];
+    let mut blocks = vec![shared.snapshot().get_block(&shared.genesis_hash()).unwrap()];
+    let mut parent = blocks[0].clone();
+    (0..BLOCKS).for_each(|_| {
+        let block = gen_always_success_block(&mut blocks, &parent, shared);
+        chain
+            .internal_process_block(Arc::new(block.clone()), Switch::DISABLE_ALL)
+            .expect("process block OK");
+        parent = block;
+    });
+    let hashes = blocks
+        .iter()
+        .skip(1)
+        .rev()
+        .map(|block| block.hash())
+        .collect();
+    (shared.clone(), hashes)
+}
+
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("store_get_blocks");
+
+    for txs_size in SIZES.iter() {
+        let (shared, hashes) = setup_blocks(*txs_size);
+
+        group.bench_with_input(
+            BenchmarkId::new("get_block one by one", txs_size),
+            &hashes,
+            |b, hashes| {
+                b.iter(|| {
+                    let snapshot = shared.snapshot();
+                    for hash in hashes {
+                        snapshot.get_block(hash).expect("block stored");
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("get_blocks in batch", txs_size),
+            &hashes,
+            |b, hashes| {
+                b.iter(|| {
+                    let blocks = shared.snapshot().get_blocks(hashes);
+                    assert!(blocks.iter().all(Option::is_some));
+                })
+            },
+        );
+    }
+}
+
+criterion_group!(
+    name = store;
+    config = Criterion::default().sample_size(10);
+    targets = bench
+);
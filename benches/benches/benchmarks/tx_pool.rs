@@ -0,0 +1,90 @@
+use crate::benchmarks::overall::{gen_txs_from_block, setup_chain};
+use ckb_chain::chain::ChainController;
+use ckb_shared::{Shared, Snapshot};
+use ckb_store::ChainStore;
+use ckb_types::core::TransactionView;
+use criterion::{criterion_group, BatchSize, BenchmarkId, Criterion};
+use std::thread;
+
+#[cfg(not(feature = "ci"))]
+const SIZES: &[usize] = &[100, 500];
+
+#[cfg(feature = "ci")]
+const SIZES: &[usize] = &[2usize];
+
+// The count of the peers relaying the same transactions at the same time
+const RELAYERS: usize = 4;
+
+// Sets up the chain and the transactions spending the genesis cells.
+fn setup_txs(txs_size: usize) -> (Shared, ChainController, Vec<TransactionView>) {
+    let (shared, chain) = setup_chain(txs_size);
+    let txs = {
+        let snapshot: &Snapshot = &shared.snapshot();
+        let genesis = snapshot
+            .get_block(&snapshot.tip_hash())
+            .expect("genesis exist");
+        gen_txs_from_block(&genesis)
+    };
+    (shared, chain, txs)
+}
+
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tx_pool_submit");
+
+    for txs_size in SIZES.iter() {
+        group.bench_with_input(
+            BenchmarkId::new("submit_local_tx", txs_size),
+            txs_size,
+            |b, txs_size| {
+                b.iter_batched(
+                    || setup_txs(*txs_size),
+                    |(shared, _chain, txs)| {
+                        let tx_pool = shared.tx_pool_controller();
+                        for tx in txs {
+                            tx_pool.submit_local_tx(tx).unwrap().expect("submit_tx");
+                        }
+                    },
+                    BatchSize::PerIteration,
+                )
+            },
+        );
+
+        // Every relayer submits all the transactions concurrently, only one submission of each
+        // transaction is accepted and the others are rejected as duplicated.
+        group.bench_with_input(
+            BenchmarkId::new("submit_local_tx_concurrent_duplicated", txs_size),
+            txs_size,
+            |b, txs_size| {
+                b.iter_batched(
+                    || setup_txs(*txs_size),
+                    |(shared, _chain, txs)| {
+                        let relayers: Vec<_> = (0..RELAYERS)
+                            .map(|i| {
+                                let tx_pool = shared.tx_pool_controller().clone();
+                                let mut txs = txs.clone();
+                                txs.rotate_left(i * txs.len() / RELAYERS);
+                                thread::spawn(move || {
+                                    for tx in txs {
+                                        let _ = tx_pool.submit_local_tx(tx).unwrap();
+                                    }
+                                })
+                            })
+                            .collect();
+                        for relayer in relayers {
+                            relayer.join().expect("relayer thread");
+                        }
+                        let info = shared.tx_pool_controller().get_tx_pool_info().unwrap();
+                        assert_eq!(info.pending_size, txs.len());
+                    },
+                    BatchSize::PerIteration,
+                )
+            },
+        );
+    }
+}
+
+criterion_group!(
+    name = tx_pool;
+    config = Criterion::default().sample_size(10);
+    targets = bench
+);
@@ -1,14 +1,16 @@
 //! CKB chain service.
 #![allow(missing_docs)]
 
+use crate::event::{ChainEvent, ChainEventSubscribers};
 use ckb_channel::{self as channel, select, Sender};
 use ckb_error::{Error, InternalErrorKind};
 use ckb_logger::{self, debug, error, info, log_enabled, trace, warn};
 use ckb_metrics::{metrics, Timer};
+use ckb_notify::LocalAlert;
 use ckb_proposal_table::ProposalTable;
 #[cfg(debug_assertions)]
 use ckb_rust_unstable_port::IsSorted;
-use ckb_shared::shared::Shared;
+use ckb_shared::{shared::Shared, IbdPhase};
 use ckb_stop_handler::{SignalSender, StopHandler};
 use ckb_store::{attach_block_cell, detach_block_cell, ChainStore, StoreTransaction};
 use ckb_types::{
@@ -18,21 +20,33 @@ use ckb_types::{
             ResolveOptions, ResolvedTransaction,
         },
         service::{Request, DEFAULT_CHANNEL_SIZE, SIGNAL_CHANNEL_SIZE},
-        BlockExt, BlockNumber, BlockView, HeaderView,
+        BlockExt, BlockNumber, BlockView, Cycle, HeaderView,
     },
     packed::{Byte32, ProposalShortId},
+    prelude::*,
     U256,
 };
+use ckb_util::Mutex;
+use ckb_verification::cache::Completed;
 use ckb_verification::{BlockVerifier, InvalidParentError, NonContextualBlockTxsVerifier};
 use ckb_verification_contextual::{ContextualBlockVerifier, VerifyContext};
 use ckb_verification_traits::{Switch, Verifier};
 use faketime::unix_time_as_millis;
+use lru::LruCache;
 use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
 use std::{cmp, thread};
+use tokio_stream::wrappers::ReceiverStream;
 
 type ProcessBlockRequest = Request<(Arc<BlockView>, Switch), Result<bool, Error>>;
 type TruncateRequest = Request<Byte32, Result<(), Error>>;
+type ApproveReorgRequest = Request<Byte32, Result<bool, Error>>;
+
+// The capacity of the cache of verified blocks, it only needs to cover the blocks in the
+// competing forks.
+const VERIFIED_BLOCKS_CACHE_SIZE: usize = 128;
+// The alert of a paused reorg is noticed for 1 day
+const REORG_ALERT_NOTICE_DURATION: u64 = 24 * 60 * 60 * 1000;
 
 /// Controller to the chain service.
 ///
@@ -44,6 +58,9 @@ type TruncateRequest = Request<Byte32, Result<(), Error>>;
 pub struct ChainController {
     process_block_sender: Sender<ProcessBlockRequest>,
     truncate_sender: Sender<TruncateRequest>, // Used for testing only
+    approve_reorg_sender: Sender<ApproveReorgRequest>,
+    pending_reorg: Arc<Mutex<Option<PendingReorg>>>,
+    event_subscribers: ChainEventSubscribers,
     stop: StopHandler<()>,
 }
 
@@ -55,14 +72,20 @@ impl Drop for ChainController {
 
 #[cfg_attr(feature = "mock", faux::methods)]
 impl ChainController {
-    pub fn new(
+    pub(crate) fn new(
         process_block_sender: Sender<ProcessBlockRequest>,
         truncate_sender: Sender<TruncateRequest>,
+        approve_reorg_sender: Sender<ApproveReorgRequest>,
+        pending_reorg: Arc<Mutex<Option<PendingReorg>>>,
+        event_subscribers: ChainEventSubscribers,
         stop: StopHandler<()>,
     ) -> Self {
         ChainController {
             process_block_sender,
             truncate_sender,
+            approve_reorg_sender,
+            pending_reorg,
+            event_subscribers,
             stop,
         }
     }
@@ -103,9 +126,62 @@ impl ChainController {
         })
     }
 
+    /// Returns the reorg paused because it exceeds the max reorg depth.
+    pub fn pending_reorg(&self) -> Option<PendingReorg> {
+        self.pending_reorg.lock().clone()
+    }
+
+    /// Approves the pending reorg to the block and switches the main chain to it.
+    ///
+    /// Returns an error if the block is not the tip of the pending reorg.
+    pub fn approve_reorg(&self, block_hash: Byte32) -> Result<bool, Error> {
+        Request::call(&self.approve_reorg_sender, block_hash).unwrap_or_else(|| {
+            Err(InternalErrorKind::System
+                .other("Chain service has gone")
+                .into())
+        })
+    }
+
+    /// Subscribes to the chain events.
+    ///
+    /// The stream receives the events emitted after the subscription. When the subscriber falls
+    /// behind by [`CHAIN_EVENT_CHANNEL_SIZE`] events, the new events are dropped until it catches
+    /// up.
+    ///
+    /// [`CHAIN_EVENT_CHANNEL_SIZE`]: ../event/constant.CHAIN_EVENT_CHANNEL_SIZE.html
+    pub fn event_stream(&self) -> ReceiverStream<ChainEvent> {
+        self.event_subscribers.subscribe()
+    }
+
     pub fn stop(&mut self) {
         self.stop.try_send(());
     }
+
+    /// Stops the chain service and waits for it, even if the controller is still cloned
+    /// elsewhere.
+    pub fn force_stop(&self) {
+        self.stop.force_send(());
+    }
+}
+
+/// A reorg paused because it rolls back more blocks than the max reorg depth.
+///
+/// The main chain does not switch to the fork until the reorg is approved via
+/// [`ChainController::approve_reorg`].
+///
+/// [`ChainController::approve_reorg`]: struct.ChainController.html#method.approve_reorg
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingReorg {
+    /// The hash of the best block in the fork
+    pub block_hash: Byte32,
+    /// The number of the best block in the fork
+    pub block_number: BlockNumber,
+    /// The total difficulty of the best block in the fork
+    pub total_difficulty: U256,
+    /// The number of the main chain blocks to roll back
+    pub depth: BlockNumber,
+    // The switch the block is processed with
+    switch: Switch,
 }
 
 /// The struct represent fork
@@ -179,12 +255,29 @@ impl GlobalIndex {
     }
 }
 
+/// The outcome of a successful contextual block verification.
+///
+/// The result only depends on the block and its ancestors, which are fixed by the block hash,
+/// so it can be reused when the block is attached again after a reorg.
+#[derive(Clone, Debug)]
+pub(crate) struct VerifiedBlock {
+    // The checks skipped during the verification
+    pub(crate) switch: Switch,
+    pub(crate) cycles: Cycle,
+    pub(crate) txs: Vec<Completed>,
+}
+
 /// Chain background service
 ///
 /// The ChainService provides a single-threaded background executor.
 pub struct ChainService {
     shared: Shared,
     proposal_table: ProposalTable,
+    verified_blocks: LruCache<Byte32, VerifiedBlock>,
+    event_subscribers: ChainEventSubscribers,
+    max_reorg_depth: Option<BlockNumber>,
+    pending_reorg: Arc<Mutex<Option<PendingReorg>>>,
+    approved_reorg: Option<Byte32>,
 }
 
 impl ChainService {
@@ -193,9 +286,23 @@ impl ChainService {
         ChainService {
             shared,
             proposal_table,
+            verified_blocks: LruCache::new(VERIFIED_BLOCKS_CACHE_SIZE),
+            event_subscribers: ChainEventSubscribers::default(),
+            max_reorg_depth: None,
+            pending_reorg: Arc::new(Mutex::new(None)),
+            approved_reorg: None,
         }
     }
 
+    /// Pauses the reorgs which roll back more blocks than the depth, until they are approved via
+    /// [`ChainController::approve_reorg`]. Default is `None`, which does not limit the depth.
+    ///
+    /// [`ChainController::approve_reorg`]: struct.ChainController.html#method.approve_reorg
+    pub fn with_max_reorg_depth(mut self, max_reorg_depth: Option<BlockNumber>) -> Self {
+        self.max_reorg_depth = max_reorg_depth;
+        self
+    }
+
     // remove `allow` tag when https://github.com/crossbeam-rs/crossbeam/issues/404 is solved
     /// start background single-threaded service with specified thread_name.
     #[allow(clippy::zero_ptr, clippy::drop_copy)]
@@ -203,6 +310,7 @@ impl ChainService {
         let (signal_sender, signal_receiver) = channel::bounded::<()>(SIGNAL_CHANNEL_SIZE);
         let (process_block_sender, process_block_receiver) = channel::bounded(DEFAULT_CHANNEL_SIZE);
         let (truncate_sender, truncate_receiver) = channel::bounded(1);
+        let (approve_reorg_sender, approve_reorg_receiver) = channel::bounded(1);
 
         // Mainly for test: give an empty thread_name
         let mut thread_builder = thread::Builder::new();
@@ -210,6 +318,8 @@ impl ChainService {
             thread_builder = thread_builder.name(name.to_string());
         }
         let tx_control = self.shared.tx_pool_controller().clone();
+        let event_subscribers = self.event_subscribers.clone();
+        let pending_reorg = Arc::clone(&self.pending_reorg);
 
         let thread = thread_builder
             .spawn(move || loop {
@@ -238,13 +348,31 @@ impl ChainService {
                             error!("truncate_receiver closed");
                             break;
                         },
+                    },
+                    recv(approve_reorg_receiver) -> msg => match msg {
+                        Ok(Request { responder, arguments: block_hash }) => {
+                            let _ = tx_control.suspend_chunk_process();
+                            let _ = responder.send(self.approve_reorg(block_hash));
+                            let _ = tx_control.continue_chunk_process();
+                        },
+                        _ => {
+                            error!("approve_reorg_receiver closed");
+                            break;
+                        },
                     }
                 }
             })
             .expect("Start ChainService failed");
         let stop = StopHandler::new(SignalSender::Crossbeam(signal_sender), Some(thread));
 
-        ChainController::new(process_block_sender, truncate_sender, stop)
+        ChainController::new(
+            process_block_sender,
+            truncate_sender,
+            approve_reorg_sender,
+            pending_reorg,
+            event_subscribers,
+            stop,
+        )
     }
 
     fn make_fork_for_truncate(&self, target: &HeaderView, current_tip: &HeaderView) -> ForkChanges {
@@ -317,13 +445,128 @@ impl ChainService {
         }
 
         let timer = Timer::start();
-        self.insert_block(block, switch).map(|ret| {
-            metrics!(timing, "ckb.processed_block", timer.stop());
-            debug!("finish processing block");
-            ret
+        self.insert_block(block, switch)
+            .map(|ret| {
+                metrics!(timing, "ckb.processed_block", timer.stop());
+                debug!("finish processing block");
+                ret
+            })
+            .map_err(|err| {
+                self.event_subscribers
+                    .notify(ChainEvent::VerificationFailed {
+                        block_hash,
+                        error: err.to_string(),
+                    });
+                err
+            })
+    }
+
+    fn approve_reorg(&mut self, block_hash: Byte32) -> Result<bool, Error> {
+        let pending = self
+            .pending_reorg
+            .lock()
+            .clone()
+            .filter(|pending| pending.block_hash == block_hash)
+            .ok_or_else(|| {
+                InternalErrorKind::System.other(format!("no pending reorg to block {}", block_hash))
+            })?;
+        let block = self.shared.store().get_block(&block_hash).ok_or_else(|| {
+            InternalErrorKind::System.other(format!(
+                "the block {} of the pending reorg is not stored",
+                block_hash
+            ))
+        })?;
+        info!(
+            "reorg to block {}-{} with depth {} is approved",
+            pending.block_number, pending.block_hash, pending.depth
+        );
+
+        self.approved_reorg = Some(block_hash);
+        let ret = self.process_block(Arc::new(block), pending.switch);
+        self.approved_reorg = None;
+        ret
+    }
+
+    // Returns the reorg to pause when switching to the fork rolls back too many blocks
+    fn deep_reorg_to_pause(
+        &self,
+        fork: &ForkChanges,
+        block: &BlockView,
+        total_difficulty: &U256,
+        switch: Switch,
+    ) -> Option<PendingReorg> {
+        let depth = fork.detached_blocks().len() as BlockNumber;
+        match self.max_reorg_depth {
+            Some(max_reorg_depth) if depth > max_reorg_depth => {}
+            _ => return None,
+        }
+        if self.approved_reorg.as_ref() == Some(&block.hash()) {
+            return None;
+        }
+        Some(PendingReorg {
+            block_hash: block.hash(),
+            block_number: block.number(),
+            total_difficulty: total_difficulty.to_owned(),
+            depth,
+            switch,
         })
     }
 
+    // Pauses the reorg after its block is committed, the block is announced only when the reorg
+    // is approved or superseded
+    fn pause_reorg(&self, pending: PendingReorg) {
+        warn!(
+            "reorg to block {}-{} rolls back {} blocks, which exceeds the max reorg depth {}, \
+             it is paused until approved",
+            pending.block_number,
+            pending.block_hash,
+            pending.depth,
+            self.max_reorg_depth.unwrap_or_default(),
+        );
+        let replaced = self.pending_reorg.lock().replace(pending.clone());
+        if let Some(replaced) = replaced {
+            self.release_paused_block(&replaced);
+        }
+
+        let message = format!(
+            "A reorg rolling back {} blocks to block {} {:#x} is paused, \
+             approve it via the RPC approve_reorg to switch the chain",
+            pending.depth, pending.block_number, pending.block_hash
+        );
+        self.shared
+            .notify_controller()
+            .notify_local_alert(LocalAlert {
+                message,
+                notice_until: unix_time_as_millis() + REORG_ALERT_NOTICE_DURATION,
+            });
+        self.event_subscribers
+            .notify(ChainEvent::ReorgPaused { pending });
+    }
+
+    // Announces the block of the reorg which will not be approved as an uncle
+    fn release_paused_block(&self, pending: &PendingReorg) {
+        match self.shared.store().get_block(&pending.block_hash) {
+            Some(block) => self.notify_uncle(block),
+            None => error!(
+                "the block {} of the pending reorg is not stored",
+                pending.block_hash
+            ),
+        }
+    }
+
+    fn notify_uncle(&self, block: BlockView) {
+        let tx_pool_controller = self.shared.tx_pool_controller();
+        if tx_pool_controller.service_started() {
+            if let Err(e) = tx_pool_controller.notify_new_uncle(block.as_uncle()) {
+                error!("notify new_uncle error {}", e);
+            }
+        }
+        self.event_subscribers.notify(ChainEvent::BlockAccepted {
+            block,
+            is_tip: false,
+        });
+    }
+
     fn non_contextual_verify(&self, block: &BlockView) -> Result<(), Error> {
         let consensus = self.shared.consensus();
         BlockVerifier::new(consensus).verify(&block).map_err(|e| {
@@ -349,12 +592,17 @@ impl ChainService {
         let _snapshot_tip_hash = db_txn.get_update_for_tip_hash(&txn_snapshot);
 
         // insert_block are assumed be executed in single thread
-        if txn_snapshot.block_exists(&block.header().hash()) {
+        // The block of an approved reorg is stored, process it again to switch the main chain
+        if txn_snapshot.block_exists(&block.header().hash())
+            && self.approved_reorg.as_ref() != Some(&block.hash())
+        {
             return Ok(false);
         }
         // non-contextual verify
         if !switch.disable_non_contextual() {
+            let timer = Timer::start();
             self.non_contextual_verify(&block)?;
+            self.shared.record_ibd_phase(IbdPhase::Verify, timer.stop());
         }
 
         let mut total_difficulty = U256::zero();
@@ -415,7 +663,17 @@ impl ChainService {
         );
 
         // is_better_than
-        let new_best_block = cannon_total_difficulty > current_total_difficulty;
+        let mut new_best_block = cannon_total_difficulty > current_total_difficulty;
+        let mut paused_reorg = None;
+        if new_best_block {
+            self.find_fork(&mut fork, current_tip_header.number(), &block, ext.clone());
+            paused_reorg =
+                self.deep_reorg_to_pause(&fork, &block, &cannon_total_difficulty, switch);
+            if paused_reorg.is_some() {
+                fork = ForkChanges::default();
+                new_best_block = false;
+            }
+        }
 
         if new_best_block {
             debug!(
@@ -424,12 +682,13 @@ impl ChainService {
                 block.header().hash(),
                 &cannon_total_difficulty - &current_total_difficulty
             );
-            self.find_fork(&mut fork, current_tip_header.number(), &block, ext);
             self.rollback(&fork, &db_txn)?;
 
             // update and verify chain root
             // MUST update index before reconcile_main_chain
+            let timer = Timer::start();
             self.reconcile_main_chain(&db_txn, &mut fork, switch)?;
+            self.shared.record_ibd_phase(IbdPhase::Verify, timer.stop());
 
             db_txn.insert_tip_header(&block.header())?;
             if new_epoch || fork.has_detached() {
@@ -439,7 +698,9 @@ impl ChainService {
         } else {
             db_txn.insert_block_ext(&block.header().hash(), &ext)?;
         }
+        let timer = Timer::start();
         db_txn.commit()?;
+        self.shared.record_ibd_phase(IbdPhase::Commit, timer.stop());
 
         if new_best_block {
             let tip_header = block.header();
@@ -463,6 +724,19 @@ impl ChainService {
                     .new_snapshot(tip_header, total_difficulty, epoch, new_proposals);
 
             self.shared.store_snapshot(Arc::clone(&new_snapshot));
+            // The pending reorg is resolved once the main chain has more work
+            let superseded = {
+                let mut pending_reorg = self.pending_reorg.lock();
+                if pending_reorg
+                    .as_ref()
+                    .map(|pending| pending.total_difficulty <= cannon_total_difficulty)
+                    .unwrap_or(false)
+                {
+                    pending_reorg.take()
+                } else {
+                    None
+                }
+            };
 
             let tx_pool_controller = self.shared.tx_pool_controller();
             if tx_pool_controller.service_started() {
@@ -482,9 +756,30 @@ impl ChainService {
             }
 
             let block_ref: &BlockView = &block;
+            if fork.has_detached() {
+                self.shared.notify_controller().notify_reorg(
+                    fork.detached_blocks().iter().map(|b| b.header()).collect(),
+                    fork.attached_blocks().iter().map(|b| b.header()).collect(),
+                );
+            }
             self.shared
                 .notify_controller()
                 .notify_new_block(block_ref.clone());
+            if fork.has_detached() {
+                self.event_subscribers.notify(ChainEvent::Reorg {
+                    detached: fork.detached_blocks().iter().map(|b| b.header()).collect(),
+                    attached: fork.attached_blocks().iter().map(|b| b.header()).collect(),
+                });
+            }
+            self.event_subscribers.notify(ChainEvent::BlockAccepted {
+                block: block_ref.clone(),
+                is_tip: true,
+            });
+            if let Some(superseded) = superseded {
+                if superseded.block_hash != block.hash() {
+                    self.release_paused_block(&superseded);
+                }
+            }
             if log_enabled!(ckb_logger::Level::Debug) {
                 self.print_chain(10);
             }
@@ -500,12 +795,9 @@ impl ChainService {
                 block.transactions().len()
             );
 
-            let tx_pool_controller = self.shared.tx_pool_controller();
-            if tx_pool_controller.service_started() {
-                let block_ref: &BlockView = &block;
-                if let Err(e) = tx_pool_controller.notify_new_uncle(block_ref.as_uncle()) {
-                    error!("notify new_uncle error {}", e);
-                }
+            match paused_reorg {
+                Some(pending) => self.pause_reorg(pending),
+                None => self.notify_uncle(BlockView::clone(&block)),
             }
         }
 
@@ -691,7 +983,7 @@ impl ChainService {
 
     // we found new best_block
     pub(crate) fn reconcile_main_chain(
-        &self,
+        &mut self,
         txn: &StoreTransaction,
         fork: &mut ForkChanges,
         switch: Switch,
@@ -715,6 +1007,31 @@ impl ChainService {
         {
             if !switch.disable_all() {
                 if found_error.is_none() {
+                    // Reuse the outcome if the block has been verified with the same or stricter
+                    // checks. The `verified` flag in the block ext is lost when switching to the
+                    // fork fails at a later block, since the whole db transaction is discarded.
+                    let cached = self
+                        .verified_blocks
+                        .get(&b.hash())
+                        .filter(|verified| switch.contains(verified.switch))
+                        .cloned();
+                    if let Some(verified) = cached {
+                        metrics!(counter, "ckb.verified_block_cache_hit", 1);
+                        debug!(
+                            "reuse verified block number: {}, hash: {}, cycles: {}",
+                            b.number(),
+                            b.hash(),
+                            verified.cycles
+                        );
+                        txn.attach_block(b)?;
+                        attach_block_cell(txn, b)?;
+                        let mut mut_ext = ext.clone();
+                        mut_ext.verified = Some(true);
+                        mut_ext.txs_fees = verified.txs.iter().map(|entry| entry.fee).collect();
+                        txn.insert_block_ext(&b.header().hash(), &mut_ext)?;
+                        continue;
+                    }
+
                     let contextual_block_verifier = ContextualBlockVerifier::new(&verify_context);
                     let mut seen_inputs = HashSet::new();
                     let block_cp = match BlockCellProvider::new(b) {
@@ -763,7 +1080,15 @@ impl ChainService {
                             ) {
                                 Ok((cycles, cache_entries)) => {
                                     let txs_fees =
-                                        cache_entries.into_iter().map(|entry| entry.fee).collect();
+                                        cache_entries.iter().map(|entry| entry.fee).collect();
+                                    self.verified_blocks.put(
+                                        b.hash(),
+                                        VerifiedBlock {
+                                            switch,
+                                            cycles,
+                                            txs: cache_entries,
+                                        },
+                                    );
                                     txn.attach_block(b)?;
                                     attach_block_cell(txn, b)?;
                                     let mut mut_ext = ext.clone();
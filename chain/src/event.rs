@@ -0,0 +1,78 @@
+//! The events emitted by the chain service.
+use crate::chain::PendingReorg;
+use ckb_logger::warn;
+use ckb_types::{
+    core::{BlockView, HeaderView},
+    packed::Byte32,
+};
+use ckb_util::Mutex;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_stream::wrappers::ReceiverStream;
+
+/// The number of the events buffered for a subscriber, the events are dropped when the buffer
+/// is full.
+pub const CHAIN_EVENT_CHANNEL_SIZE: usize = 128;
+
+/// An event emitted by the chain service.
+#[derive(Clone, Debug)]
+pub enum ChainEvent {
+    /// A block is accepted and stored.
+    BlockAccepted {
+        /// The accepted block
+        block: BlockView,
+        /// Whether the block becomes the new tip, otherwise it is stored as an uncle
+        is_tip: bool,
+    },
+    /// The main chain switches to another fork.
+    ///
+    /// It is emitted before the `BlockAccepted` event of the new tip.
+    Reorg {
+        /// The headers of the blocks removed from the main chain, in ascending order
+        detached: Vec<HeaderView>,
+        /// The headers of the blocks added to the main chain, in ascending order
+        attached: Vec<HeaderView>,
+    },
+    /// The main chain does not switch to a better fork because it rolls back more blocks than
+    /// the max reorg depth, the reorg is paused until it is approved.
+    ReorgPaused {
+        /// The paused reorg
+        pending: PendingReorg,
+    },
+    /// A block fails to be processed, usually because of a verification error.
+    VerificationFailed {
+        /// The hash of the block
+        block_hash: Byte32,
+        /// The error message
+        error: String,
+    },
+}
+
+/// The subscribers of the chain events, shared by the chain service and its controllers.
+#[derive(Clone, Default)]
+pub(crate) struct ChainEventSubscribers {
+    inner: Arc<Mutex<Vec<mpsc::Sender<ChainEvent>>>>,
+}
+
+impl ChainEventSubscribers {
+    pub(crate) fn subscribe(&self) -> ReceiverStream<ChainEvent> {
+        let (sender, receiver) = mpsc::channel(CHAIN_EVENT_CHANNEL_SIZE);
+        self.inner.lock().push(sender);
+        ReceiverStream::new(receiver)
+    }
+
+    pub(crate) fn notify(&self, event: ChainEvent) {
+        let mut subscribers = self.inner.lock();
+        if subscribers.is_empty() {
+            return;
+        }
+        subscribers.retain(|sender| match sender.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                warn!("chain event subscriber is lagging, the event is dropped");
+                true
+            }
+            Err(TrySendError::Closed(_)) => false,
+        });
+    }
+}
@@ -7,5 +7,6 @@
 //! [`ChainController`]: chain/struct.ChainController.html
 
 pub mod chain;
+pub mod event;
 #[cfg(test)]
 mod tests;
@@ -1,8 +1,9 @@
 use crate::chain::ChainController;
+use crate::event::ChainEvent;
 use crate::tests::util::{
     create_always_success_tx, create_cellbase, create_multi_outputs_transaction,
-    create_transaction, create_transaction_with_out_point, dao_data, start_chain, MockChain,
-    MockStore,
+    create_transaction, create_transaction_with_out_point, dao_data, start_chain,
+    start_chain_with_max_reorg_depth, MockChain, MockStore,
 };
 use ckb_chain_spec::consensus::{Consensus, ConsensusBuilder};
 use ckb_dao_utils::genesis_dao_data;
@@ -418,6 +419,149 @@ fn test_chain_fork_by_total_difficulty() {
     );
 }
 
+#[test]
+fn test_chain_event_stream() {
+    let (chain_controller, shared, parent) = start_chain(None);
+    let mut events = chain_controller.event_stream().into_inner();
+
+    let mock_store = MockStore::new(&parent, shared.store());
+    let mut chain1 = MockChain::new(parent.clone(), shared.consensus());
+    let mut chain2 = MockChain::new(parent, shared.consensus());
+    for _ in 0..2 {
+        chain1.gen_empty_block_with_diff(100u64, &mock_store);
+        chain2.gen_empty_block_with_diff(150u64, &mock_store);
+    }
+    for block in chain1.blocks().iter().chain(chain2.blocks()) {
+        chain_controller
+            .internal_process_block(Arc::new(block.clone()), Switch::DISABLE_ALL)
+            .expect("process block ok");
+    }
+
+    let hashes = |blocks: &[BlockView]| blocks.iter().map(|b| b.hash()).collect::<Vec<_>>();
+    let mut accepted = Vec::new();
+    let mut reorgs = Vec::new();
+    for _ in 0..5 {
+        match events.blocking_recv().expect("chain event") {
+            ChainEvent::BlockAccepted { block, is_tip } => accepted.push((block.hash(), is_tip)),
+            ChainEvent::Reorg { detached, attached } => reorgs.push((
+                detached.iter().map(|h| h.hash()).collect::<Vec<_>>(),
+                attached.iter().map(|h| h.hash()).collect::<Vec<_>>(),
+            )),
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+    assert_eq!(
+        accepted,
+        vec![
+            (chain1.blocks()[0].hash(), true),
+            (chain1.blocks()[1].hash(), true),
+            (chain2.blocks()[0].hash(), false),
+            (chain2.blocks()[1].hash(), true),
+        ]
+    );
+    assert_eq!(
+        reorgs,
+        vec![(hashes(chain1.blocks()), hashes(chain2.blocks()))]
+    );
+}
+
+#[test]
+fn test_pause_deep_reorg() {
+    let (chain_controller, shared, parent) = start_chain_with_max_reorg_depth(None, Some(2));
+    let mut events = chain_controller.event_stream().into_inner();
+
+    let mock_store = MockStore::new(&parent, shared.store());
+    let mut chain1 = MockChain::new(parent.clone(), shared.consensus());
+    let mut chain2 = MockChain::new(parent, shared.consensus());
+    for _ in 0..3 {
+        chain1.gen_empty_block_with_diff(100u64, &mock_store);
+        chain2.gen_empty_block_with_diff(150u64, &mock_store);
+    }
+    for block in chain1.blocks().iter().chain(chain2.blocks()) {
+        chain_controller
+            .internal_process_block(Arc::new(block.clone()), Switch::DISABLE_ALL)
+            .expect("process block ok");
+    }
+
+    // The fork rolls back 3 blocks, which exceeds the max reorg depth 2
+    let fork_tip = chain2.tip_header();
+    assert_eq!(shared.snapshot().tip_hash(), chain1.tip_header().hash());
+    let pending = chain_controller.pending_reorg().expect("pending reorg");
+    assert_eq!(pending.block_hash, fork_tip.hash());
+    assert_eq!(pending.depth, 3);
+    let mut paused = Vec::new();
+    let mut accepted = Vec::new();
+    for _ in 0..6 {
+        match events.blocking_recv().expect("chain event") {
+            ChainEvent::ReorgPaused { pending } => paused.push(pending),
+            ChainEvent::BlockAccepted { block, .. } => accepted.push(block.hash()),
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+    assert_eq!(paused, vec![pending]);
+    // The paused block is not announced until the reorg is approved
+    assert!(!accepted.contains(&fork_tip.hash()));
+
+    assert!(chain_controller
+        .approve_reorg(chain1.tip_header().hash())
+        .is_err());
+    assert!(chain_controller
+        .approve_reorg(fork_tip.hash())
+        .expect("approve reorg"));
+    assert_eq!(shared.snapshot().tip_hash(), fork_tip.hash());
+    assert!(chain_controller.pending_reorg().is_none());
+    let accepted = (0..2)
+        .filter_map(|_| match events.blocking_recv().expect("chain event") {
+            ChainEvent::BlockAccepted { block, is_tip } => Some((block.hash(), is_tip)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(accepted, vec![(fork_tip.hash(), true)]);
+}
+
+#[test]
+fn test_superseded_paused_reorg() {
+    let (chain_controller, shared, parent) = start_chain_with_max_reorg_depth(None, Some(2));
+    let mut events = chain_controller.event_stream().into_inner();
+
+    let mock_store = MockStore::new(&parent, shared.store());
+    let mut chain1 = MockChain::new(parent.clone(), shared.consensus());
+    let mut chain2 = MockChain::new(parent, shared.consensus());
+    for _ in 0..3 {
+        chain1.gen_empty_block_with_diff(100u64, &mock_store);
+        chain2.gen_empty_block_with_diff(150u64, &mock_store);
+    }
+    for block in chain1.blocks().iter().chain(chain2.blocks()) {
+        chain_controller
+            .internal_process_block(Arc::new(block.clone()), Switch::DISABLE_ALL)
+            .expect("process block ok");
+    }
+    let fork_tip = chain2.tip_header();
+    assert!(chain_controller.pending_reorg().is_some());
+
+    // The main chain gains more work than the fork
+    for _ in 0..2 {
+        chain1.gen_empty_block_with_diff(100u64, &mock_store);
+        let block = chain1.blocks().last().unwrap().clone();
+        chain_controller
+            .internal_process_block(Arc::new(block), Switch::DISABLE_ALL)
+            .expect("process block ok");
+    }
+    assert_eq!(shared.snapshot().tip_hash(), chain1.tip_header().hash());
+    assert!(chain_controller.pending_reorg().is_none());
+
+    // The paused block is announced as an uncle once the reorg is superseded
+    let accepted = (0..9)
+        .filter_map(|_| match events.blocking_recv().expect("chain event") {
+            ChainEvent::BlockAccepted { block, is_tip } if block.hash() == fork_tip.hash() => {
+                Some(is_tip)
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(accepted, vec![false]);
+}
+
 #[test]
 fn test_chain_fork_by_first_received() {
     let (chain_controller, shared, parent) = start_chain(None);
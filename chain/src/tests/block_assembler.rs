@@ -13,6 +13,7 @@ use ckb_tx_pool::{PlugTarget, TxEntry};
 use ckb_types::{
     bytes::Bytes,
     core::{
+        cell::{CellMetaBuilder, ResolvedTransaction},
         BlockBuilder, BlockNumber, BlockView, Capacity, EpochExt, HeaderBuilder, HeaderView,
         TransactionBuilder, TransactionView,
     },
@@ -280,9 +281,29 @@ fn test_prepare_uncles() {
     assert!(block_template.uncles.is_empty());
 }
 
-fn build_tx(parent_tx: &TransactionView, inputs: &[u32], outputs_len: usize) -> TransactionView {
-    let per_output_capacity =
-        Capacity::shannons(parent_tx.outputs_capacity().unwrap().as_u64() / outputs_len as u64);
+// Builds a transaction spending the `inputs` of `parent_tx`, which pays `fee` and splits the
+// rest into `outputs_len` outputs
+fn build_tx(
+    parent_tx: &TransactionView,
+    inputs: &[u32],
+    outputs_len: usize,
+    fee: u64,
+) -> TransactionView {
+    let inputs_capacity: u64 = inputs
+        .iter()
+        .map(|index| {
+            let capacity: Capacity = parent_tx
+                .outputs()
+                .get(*index as usize)
+                .unwrap()
+                .capacity()
+                .unpack();
+            capacity.as_u64()
+        })
+        .sum();
+    let outputs_capacity = inputs_capacity - fee;
+    let per_output_capacity = outputs_capacity / outputs_len as u64;
+    let remainder = outputs_capacity % outputs_len as u64;
     TransactionBuilder::default()
         .inputs(
             inputs
@@ -291,9 +312,14 @@ fn build_tx(parent_tx: &TransactionView, inputs: &[u32], outputs_len: usize) ->
         )
         .outputs(
             (0..outputs_len)
-                .map(|_| {
+                .map(|i| {
+                    let capacity = if i == 0 {
+                        per_output_capacity + remainder
+                    } else {
+                        per_output_capacity
+                    };
                     CellOutputBuilder::default()
-                        .capacity(per_output_capacity.pack())
+                        .capacity(Capacity::shannons(capacity).pack())
                         .build()
                 })
                 .collect::<Vec<CellOutput>>(),
@@ -302,6 +328,36 @@ fn build_tx(parent_tx: &TransactionView, inputs: &[u32], outputs_len: usize) ->
         .build()
 }
 
+// The pool entry of `tx` whose inputs are resolved from `parent_tx`
+fn resolved_entry(tx: &TransactionView, parent_tx: &TransactionView, size: usize) -> TxEntry {
+    let resolved_inputs = tx
+        .input_pts_iter()
+        .map(|out_point| {
+            let (output, data) = parent_tx
+                .output_with_data(out_point.index().unpack())
+                .unwrap();
+            CellMetaBuilder::from_cell_output(output, data)
+                .out_point(out_point)
+                .build()
+        })
+        .collect::<Vec<_>>();
+    let inputs_capacity = resolved_inputs
+        .iter()
+        .map(|cell_meta| Unpack::<Capacity>::unpack(&cell_meta.cell_output.capacity()))
+        .try_fold(Capacity::zero(), |total, capacity| total.safe_add(capacity))
+        .unwrap();
+    let fee = inputs_capacity
+        .safe_sub(tx.outputs_capacity().unwrap())
+        .unwrap();
+    let rtx = ResolvedTransaction {
+        transaction: tx.clone(),
+        resolved_cell_deps: vec![],
+        resolved_inputs,
+        resolved_dep_groups: vec![],
+    };
+    TxEntry::new(rtx, 0, fee, size)
+}
+
 fn check_txs(block_template: &BlockTemplate, expect_txs: Vec<&TransactionView>, format_arg: &str) {
     assert_eq!(
         block_template
@@ -342,25 +398,25 @@ fn test_package_basic() {
     }
 
     let tx0 = &blocks[0].transactions()[0];
-    let tx1 = build_tx(tx0, &[0], 2);
-    let tx2 = build_tx(&tx1, &[0], 2);
-    let tx3 = build_tx(&tx2, &[0], 2);
-    let tx4 = build_tx(&tx3, &[0], 2);
+    let tx1 = build_tx(tx0, &[0], 2, 100);
+    let tx2 = build_tx(&tx1, &[0], 2, 100);
+    let tx3 = build_tx(&tx2, &[0], 2, 100);
+    let tx4 = build_tx(&tx3, &[0], 2, 1500);
 
     let tx2_0 = &blocks[1].transactions()[0];
-    let tx2_1 = build_tx(tx2_0, &[0], 2);
-    let tx2_2 = build_tx(&tx2_1, &[0], 2);
-    let tx2_3 = build_tx(&tx2_2, &[0], 2);
+    let tx2_1 = build_tx(tx2_0, &[0], 2, 150);
+    let tx2_2 = build_tx(&tx2_1, &[0], 2, 150);
+    let tx2_3 = build_tx(&tx2_2, &[0], 2, 150);
 
     let tx_pool = shared.tx_pool_controller();
     let entries = vec![
-        TxEntry::dummy_resolve(tx1.clone(), 0, Capacity::shannons(100), 100),
-        TxEntry::dummy_resolve(tx2.clone(), 0, Capacity::shannons(100), 100),
-        TxEntry::dummy_resolve(tx3.clone(), 0, Capacity::shannons(100), 100),
-        TxEntry::dummy_resolve(tx4.clone(), 0, Capacity::shannons(1500), 500),
-        TxEntry::dummy_resolve(tx2_1.clone(), 0, Capacity::shannons(150), 100),
-        TxEntry::dummy_resolve(tx2_2.clone(), 0, Capacity::shannons(150), 100),
-        TxEntry::dummy_resolve(tx2_3.clone(), 0, Capacity::shannons(150), 100),
+        resolved_entry(&tx1, tx0, 100),
+        resolved_entry(&tx2, &tx1, 100),
+        resolved_entry(&tx3, &tx2, 100),
+        resolved_entry(&tx4, &tx3, 500),
+        resolved_entry(&tx2_1, tx2_0, 100),
+        resolved_entry(&tx2_2, &tx2_1, 100),
+        resolved_entry(&tx2_3, &tx2_2, 100),
     ];
     tx_pool.plug_entry(entries, PlugTarget::Proposed).unwrap();
 
@@ -472,35 +528,35 @@ fn test_package_multi_best_scores() {
     }
 
     let tx0 = &blocks[0].transactions()[0];
-    let tx1 = build_tx(tx0, &[0], 2);
-    let tx2 = build_tx(&tx1, &[0], 2);
-    let tx3 = build_tx(&tx2, &[0], 2);
-    let tx4 = build_tx(&tx3, &[0], 2);
+    let tx1 = build_tx(tx0, &[0], 2, 200);
+    let tx2 = build_tx(&tx1, &[0], 2, 200);
+    let tx3 = build_tx(&tx2, &[0], 2, 50);
+    let tx4 = build_tx(&tx3, &[0], 2, 1500);
 
     let tx2_0 = &blocks[1].transactions()[0];
-    let tx2_1 = build_tx(tx2_0, &[0], 2);
-    let tx2_2 = build_tx(&tx2_1, &[0], 2);
-    let tx2_3 = build_tx(&tx2_2, &[0], 2);
-    let tx2_4 = build_tx(&tx2_3, &[0], 2);
+    let tx2_1 = build_tx(tx2_0, &[0], 2, 150);
+    let tx2_2 = build_tx(&tx2_1, &[0], 2, 150);
+    let tx2_3 = build_tx(&tx2_2, &[0], 2, 150);
+    let tx2_4 = build_tx(&tx2_3, &[0], 2, 150);
 
     let tx3_0 = &blocks[2].transactions()[0];
-    let tx3_1 = build_tx(tx3_0, &[0], 1);
+    let tx3_1 = build_tx(tx3_0, &[0], 1, 1000);
 
     let tx4_0 = &blocks[3].transactions()[0];
-    let tx4_1 = build_tx(tx4_0, &[0], 1);
+    let tx4_1 = build_tx(tx4_0, &[0], 1, 300);
 
     let tx_pool = shared.tx_pool_controller();
     let entries = vec![
-        TxEntry::dummy_resolve(tx1.clone(), 0, Capacity::shannons(200), 100),
-        TxEntry::dummy_resolve(tx2.clone(), 0, Capacity::shannons(200), 100),
-        TxEntry::dummy_resolve(tx3.clone(), 0, Capacity::shannons(50), 50),
-        TxEntry::dummy_resolve(tx4.clone(), 0, Capacity::shannons(1500), 500),
-        TxEntry::dummy_resolve(tx2_1.clone(), 0, Capacity::shannons(150), 100),
-        TxEntry::dummy_resolve(tx2_2.clone(), 0, Capacity::shannons(150), 100),
-        TxEntry::dummy_resolve(tx2_3.clone(), 0, Capacity::shannons(150), 100),
-        TxEntry::dummy_resolve(tx2_4.clone(), 0, Capacity::shannons(150), 100),
-        TxEntry::dummy_resolve(tx3_1.clone(), 0, Capacity::shannons(1000), 1000),
-        TxEntry::dummy_resolve(tx4_1.clone(), 0, Capacity::shannons(300), 250),
+        resolved_entry(&tx1, tx0, 100),
+        resolved_entry(&tx2, &tx1, 100),
+        resolved_entry(&tx3, &tx2, 50),
+        resolved_entry(&tx4, &tx3, 500),
+        resolved_entry(&tx2_1, tx2_0, 100),
+        resolved_entry(&tx2_2, &tx2_1, 100),
+        resolved_entry(&tx2_3, &tx2_2, 100),
+        resolved_entry(&tx2_4, &tx2_3, 100),
+        resolved_entry(&tx3_1, tx3_0, 1000),
+        resolved_entry(&tx4_1, tx4_0, 250),
     ];
     tx_pool.plug_entry(entries, PlugTarget::Proposed).unwrap();
 
@@ -593,19 +649,19 @@ fn test_package_low_fee_decendants() {
     }
 
     let tx0 = &blocks[0].transactions()[0];
-    let tx1 = build_tx(tx0, &[0], 2);
-    let tx2 = build_tx(&tx1, &[0], 2);
-    let tx3 = build_tx(&tx2, &[0], 2);
-    let tx4 = build_tx(&tx3, &[0], 2);
-    let tx5 = build_tx(&tx4, &[0], 2);
+    let tx1 = build_tx(tx0, &[0], 2, 1000);
+    let tx2 = build_tx(&tx1, &[0], 2, 100);
+    let tx3 = build_tx(&tx2, &[0], 2, 100);
+    let tx4 = build_tx(&tx3, &[0], 2, 100);
+    let tx5 = build_tx(&tx4, &[0], 2, 100);
 
     let tx_pool = shared.tx_pool_controller();
     let entries = vec![
-        TxEntry::dummy_resolve(tx1.clone(), 0, Capacity::shannons(1000), 100),
-        TxEntry::dummy_resolve(tx2.clone(), 0, Capacity::shannons(100), 100),
-        TxEntry::dummy_resolve(tx3.clone(), 0, Capacity::shannons(100), 100),
-        TxEntry::dummy_resolve(tx4.clone(), 0, Capacity::shannons(100), 100),
-        TxEntry::dummy_resolve(tx5.clone(), 0, Capacity::shannons(100), 100),
+        resolved_entry(&tx1, tx0, 100),
+        resolved_entry(&tx2, &tx1, 100),
+        resolved_entry(&tx3, &tx2, 100),
+        resolved_entry(&tx4, &tx3, 100),
+        resolved_entry(&tx5, &tx4, 100),
     ];
     tx_pool.plug_entry(entries, PlugTarget::Proposed).unwrap();
 
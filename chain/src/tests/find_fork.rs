@@ -4,11 +4,12 @@ use ckb_chain_spec::consensus::{Consensus, ProposalWindow};
 use ckb_launcher::SharedBuilder;
 use ckb_store::ChainStore;
 use ckb_types::{
-    core::{BlockBuilder, BlockExt, BlockView},
+    core::{BlockBuilder, BlockExt, BlockView, Capacity},
     packed,
     prelude::Pack,
     U256,
 };
+use ckb_verification::cache::Completed;
 use ckb_verification_traits::Switch;
 use faketime::unix_time_as_millis;
 use std::collections::HashSet;
@@ -295,6 +296,139 @@ fn test_find_fork_case4() {
     );
 }
 
+#[test]
+fn test_cache_verified_blocks() {
+    let builder = SharedBuilder::with_temp_db();
+    let (shared, mut pack) = builder.consensus(Consensus::default()).build().unwrap();
+    let mut chain_service = ChainService::new(shared.clone(), pack.take_proposal_table());
+    let genesis = shared
+        .store()
+        .get_block_header(&shared.store().get_block_hash(0).unwrap())
+        .unwrap();
+
+    let mock_store = MockStore::new(&genesis, shared.store());
+    let mut fork1 = MockChain::new(genesis.clone(), shared.consensus());
+    let mut fork2 = MockChain::new(genesis, shared.consensus());
+    for _ in 0..2 {
+        fork1.gen_empty_block(&mock_store);
+    }
+    for _ in 0..2 {
+        fork2.gen_empty_block_with_nonce(2u128, &mock_store);
+    }
+
+    for blk in fork1.blocks() {
+        chain_service
+            .process_block(Arc::new(blk.clone()), Switch::DISABLE_EPOCH)
+            .unwrap();
+    }
+    for blk in fork1.blocks() {
+        let verified = chain_service
+            .verified_blocks
+            .get(&blk.hash())
+            .expect("verified block cached");
+        assert_eq!(verified.switch, Switch::DISABLE_EPOCH);
+        assert_eq!(verified.txs.len(), 0);
+    }
+
+    // blocks which skip all the verifications are not cached
+    for blk in fork2.blocks() {
+        chain_service
+            .process_block(Arc::new(blk.clone()), Switch::DISABLE_ALL)
+            .unwrap();
+    }
+    for blk in fork2.blocks() {
+        assert!(chain_service.verified_blocks.get(&blk.hash()).is_none());
+    }
+}
+
+// 0--1--2--3
+//  \
+//   1--2--3--4 (invalid)
+//          \
+//           4
+//
+// The switch to the lower fork fails at the invalid block, the verified flags of the blocks 1..=3
+// are discarded with the db transaction. The next switch over the same range reuses the cached
+// outcomes instead of verifying them again.
+#[test]
+fn test_reuse_verified_blocks_after_failed_switch() {
+    let builder = SharedBuilder::with_temp_db();
+    let (shared, mut pack) = builder.consensus(Consensus::default()).build().unwrap();
+    let mut chain_service = ChainService::new(shared.clone(), pack.take_proposal_table());
+    let genesis = shared
+        .store()
+        .get_block_header(&shared.store().get_block_hash(0).unwrap())
+        .unwrap();
+
+    let mock_store = MockStore::new(&genesis, shared.store());
+    let mut fork1 = MockChain::new(genesis.clone(), shared.consensus());
+    let mut fork2 = MockChain::new(genesis, shared.consensus());
+    for _ in 0..3 {
+        fork1.gen_empty_block(&mock_store);
+    }
+    for _ in 0..3 {
+        fork2.gen_empty_block_with_nonce(2u128, &mock_store);
+    }
+    let mut invalid_fork = fork2.clone();
+    invalid_fork.gen_empty_block_with_nonce(3u128, &mock_store);
+    let invalid_block = invalid_fork
+        .tip()
+        .as_advanced_builder()
+        .dao(packed::Byte32::default())
+        .build();
+    fork2.gen_empty_block_with_nonce(4u128, &mock_store);
+
+    for blk in fork1.blocks().iter().chain(&fork2.blocks()[..3]) {
+        chain_service
+            .process_block(Arc::new(blk.clone()), Switch::DISABLE_EPOCH)
+            .unwrap();
+    }
+    assert_eq!(shared.snapshot().tip_hash(), fork1.tip().hash());
+
+    assert!(chain_service
+        .process_block(Arc::new(invalid_block), Switch::DISABLE_EPOCH)
+        .is_err());
+    assert_eq!(shared.snapshot().tip_hash(), fork1.tip().hash());
+    for blk in &fork2.blocks()[..3] {
+        let ext = shared.store().get_block_ext(&blk.hash()).unwrap();
+        assert_eq!(ext.verified, None);
+        // Marks the cached outcomes, a fresh verification of an empty block records no fees
+        chain_service
+            .verified_blocks
+            .get_mut(&blk.hash())
+            .expect("verified block cached")
+            .txs
+            .push(Completed {
+                cycles: 0,
+                fee: Capacity::shannons(1),
+            });
+    }
+
+    chain_service
+        .process_block(Arc::new(fork2.tip().clone()), Switch::DISABLE_EPOCH)
+        .unwrap();
+    assert_eq!(shared.snapshot().tip_hash(), fork2.tip().hash());
+    for blk in fork2.blocks() {
+        assert_eq!(
+            shared.store().get_block_hash(blk.number()),
+            Some(blk.hash())
+        );
+        assert_eq!(
+            shared
+                .store()
+                .get_block_ext(&blk.hash())
+                .and_then(|ext| ext.verified),
+            Some(true)
+        );
+    }
+    for blk in &fork2.blocks()[..3] {
+        let ext = shared.store().get_block_ext(&blk.hash()).unwrap();
+        assert_eq!(ext.txs_fees, vec![Capacity::shannons(1)]);
+    }
+    let ext = shared.store().get_block_ext(&fork2.tip().hash()).unwrap();
+    assert!(ext.txs_fees.is_empty());
+}
+
 // this case is create for issuse from https://github.com/nervosnetwork/ckb/pull/1470
 #[test]
 fn repeatedly_switch_fork() {
@@ -18,8 +18,8 @@ use ckb_types::{
     core::{
         capacity_bytes,
         cell::{resolve_transaction, OverlayCellProvider, TransactionsProvider},
-        BlockBuilder, BlockView, Capacity, EpochNumberWithFraction, HeaderView, TransactionBuilder,
-        TransactionView,
+        BlockBuilder, BlockNumber, BlockView, Capacity, EpochNumberWithFraction, HeaderView,
+        TransactionBuilder, TransactionView,
     },
     h256,
     packed::{self, Byte32, CellDep, CellInput, CellOutput, CellOutputBuilder, OutPoint},
@@ -78,6 +78,13 @@ pub(crate) fn create_always_success_out_point() -> OutPoint {
 }
 
 pub(crate) fn start_chain(consensus: Option<Consensus>) -> (ChainController, Shared, HeaderView) {
+    start_chain_with_max_reorg_depth(consensus, None)
+}
+
+pub(crate) fn start_chain_with_max_reorg_depth(
+    consensus: Option<Consensus>,
+    max_reorg_depth: Option<BlockNumber>,
+) -> (ChainController, Shared, HeaderView) {
     let builder = SharedBuilder::with_temp_db();
     let (_, _, always_success_script) = always_success_cell();
     let consensus = consensus.unwrap_or_else(|| {
@@ -129,7 +136,8 @@ pub(crate) fn start_chain(consensus: Option<Consensus>) -> (ChainController, Sha
     let network = dummy_network(&shared);
     pack.take_tx_pool_builder().start(network);
 
-    let chain_service = ChainService::new(shared.clone(), pack.take_proposal_table());
+    let chain_service = ChainService::new(shared.clone(), pack.take_proposal_table())
+        .with_max_reorg_depth(max_reorg_depth);
     let chain_controller = chain_service.start::<&str>(None);
     let parent = {
         let snapshot = shared.snapshot();
@@ -42,6 +42,21 @@ pub fn run_app(version: Version) -> Result<(), ExitCode> {
             }
             _ => {}
         },
+        (cli::CMD_CONFIG, Some(matches)) => {
+            if let (cli::CMD_DUMP, Some(matches)) = matches.subcommand() {
+                return subcommand::config_dump(Setup::config_dump(&matches)?);
+            }
+        }
+        (cli::CMD_ALERT, Some(matches)) => {
+            if let (cli::CMD_SIGN, Some(matches)) = matches.subcommand() {
+                return subcommand::alert_sign(Setup::alert_sign(&matches)?);
+            }
+        }
+        (cli::CMD_SCHEMA, Some(matches)) => {
+            if let (cli::CMD_DUMP, Some(matches)) = matches.subcommand() {
+                return subcommand::schema_dump(Setup::schema_dump(&matches)?);
+            }
+        }
         _ => {
             // continue
         }
@@ -63,6 +78,17 @@ pub fn run_app(version: Version) -> Result<(), ExitCode> {
         (cli::CMD_RESET_DATA, Some(matches)) => subcommand::reset_data(setup.reset_data(&matches)?),
         (cli::CMD_MIGRATE, Some(matches)) => subcommand::migrate(setup.migrate(&matches)?),
         (cli::CMD_DB_REPAIR, Some(matches)) => subcommand::db_repair(setup.db_repair(&matches)?),
+        (cli::CMD_RECOVER, Some(matches)) => subcommand::recover(setup.recover(&matches)?, handle),
+        (cli::CMD_DB, Some(matches)) => match matches.subcommand() {
+            (cli::CMD_COMPACT, Some(matches)) => {
+                subcommand::db_compact(setup.db_compact(&matches)?)
+            }
+            (cli::CMD_STATS, Some(matches)) => subcommand::db_stats(setup.db_stats(&matches)?),
+            _ => unreachable!(),
+        },
+        (cli::CMD_MOVE_ANCIENT, Some(matches)) => {
+            subcommand::move_ancient(setup.move_ancient(&matches)?)
+        }
         _ => unreachable!(),
     }
 }
@@ -0,0 +1,32 @@
+use ckb_app_config::{AlertSignArgs, ExitCode};
+use ckb_crypto::secp::Privkey;
+use ckb_network_alert::signer::sign_alert;
+use ckb_types::{packed, H256};
+use std::str::FromStr;
+
+pub fn alert_sign(args: AlertSignArgs) -> Result<(), ExitCode> {
+    let content = std::fs::read_to_string(&args.privkey_path)?;
+    let privkey = H256::from_str(content.trim().trim_start_matches("0x"))
+        .map(Privkey::from)
+        .map_err(|err| {
+            eprintln!(
+                "Invalid private key file {}: {}",
+                args.privkey_path.display(),
+                err
+            );
+            ExitCode::Cli
+        })?;
+
+    let alert = sign_alert(&packed::Alert::from(args.alert), &privkey).map_err(|err| {
+        eprintln!("Sign alert error: {}", err);
+        ExitCode::Failure
+    })?;
+    let alert = ckb_jsonrpc_types::Alert::from(alert);
+    let content = serde_json::to_string_pretty(&alert).map_err(|err| {
+        eprintln!("Serialize alert error: {}", err);
+        ExitCode::Failure
+    })?;
+    println!("{}", content);
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use ckb_app_config::{CKBAppConfig, ConfigDumpArgs, ExitCode};
+use ckb_resource::Resource;
+
+pub fn config_dump(args: ConfigDumpArgs) -> Result<(), ExitCode> {
+    if args.effective {
+        let value = CKBAppConfig::load_layered(&args.root_dir)?;
+        // Reports the invalid options before printing.
+        CKBAppConfig::load_from_value(value.clone())?;
+        let content = toml::to_string_pretty(&value).map_err(|err| {
+            eprintln!("Config Error: {:?}", err);
+            ExitCode::Config
+        })?;
+        print!("{}", content);
+    } else {
+        let content = Resource::ckb_config(&args.root_dir).get()?;
+        print!("{}", String::from_utf8_lossy(&content));
+    }
+
+    Ok(())
+}
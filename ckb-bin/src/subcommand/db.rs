@@ -0,0 +1,114 @@
+use ckb_app_config::{DBCompactArgs, DBStatsArgs, ExitCode};
+use ckb_db::{ReadOnlyDB, RocksDB};
+use ckb_db_schema::{
+    Col, COLUMNS, COLUMN_BLOCK_BODY, COLUMN_BLOCK_EPOCH, COLUMN_BLOCK_EXT, COLUMN_BLOCK_EXTENSION,
+    COLUMN_BLOCK_HEADER, COLUMN_BLOCK_PROPOSAL_IDS, COLUMN_BLOCK_UNCLE, COLUMN_CELL,
+    COLUMN_CELL_DATA, COLUMN_CELL_DATA_HASH, COLUMN_EPOCH, COLUMN_INDEX, COLUMN_META,
+    COLUMN_NUMBER_HASH, COLUMN_TRANSACTION_INFO, COLUMN_UNCLES,
+};
+
+const COLUMN_NAMES: [(Col, &str); COLUMNS as usize] = [
+    (COLUMN_INDEX, "index"),
+    (COLUMN_BLOCK_HEADER, "block_header"),
+    (COLUMN_BLOCK_BODY, "block_body"),
+    (COLUMN_BLOCK_UNCLE, "block_uncle"),
+    (COLUMN_META, "meta"),
+    (COLUMN_TRANSACTION_INFO, "transaction_info"),
+    (COLUMN_BLOCK_EXT, "block_ext"),
+    (COLUMN_BLOCK_PROPOSAL_IDS, "block_proposal_ids"),
+    (COLUMN_BLOCK_EPOCH, "block_epoch"),
+    (COLUMN_EPOCH, "epoch"),
+    (COLUMN_CELL, "cell"),
+    (COLUMN_UNCLES, "uncles"),
+    (COLUMN_CELL_DATA, "cell_data"),
+    (COLUMN_NUMBER_HASH, "number_hash"),
+    (COLUMN_CELL_DATA_HASH, "cell_data_hash"),
+    (COLUMN_BLOCK_EXTENSION, "block_extension"),
+];
+
+const STATS_PROPERTIES: [&str; 3] = [
+    "rocksdb.estimate-num-keys",
+    "rocksdb.estimate-live-data-size",
+    "rocksdb.total-sst-files-size",
+];
+
+pub fn db_compact(args: DBCompactArgs) -> Result<(), ExitCode> {
+    let columns: Vec<(Col, &str)> = match args.column {
+        Some(ref name) => {
+            let column = COLUMN_NAMES
+                .iter()
+                .find(|(col, col_name)| *col == name.as_str() || *col_name == name.as_str())
+                .ok_or_else(|| {
+                    eprintln!("unknown column family {}, see `ckb db stats`", name);
+                    ExitCode::Cli
+                })?;
+            vec![*column]
+        }
+        None => COLUMN_NAMES.to_vec(),
+    };
+
+    // Fails when the database is locked by a running node.
+    let db = RocksDB::open_with_check(&args.config.db, COLUMNS).map_err(|err| {
+        eprintln!(
+            "failed to open the database, please stop the node first: {}",
+            err
+        );
+        ExitCode::Failure
+    })?;
+    for (col, name) in columns {
+        println!("compacting column family {} ({}) ...", col, name);
+        db.compact_range(col, None, None).map_err(|err| {
+            eprintln!("compact error: {}", err);
+            ExitCode::Failure
+        })?;
+    }
+    println!("done");
+
+    Ok(())
+}
+
+pub fn db_stats(args: DBStatsArgs) -> Result<(), ExitCode> {
+    let cf_names = COLUMN_NAMES.iter().map(|(col, _)| *col);
+    let db = ReadOnlyDB::open_cf(&args.config.db.path, cf_names)
+        .map_err(|err| {
+            eprintln!("failed to open the database: {}", err);
+            ExitCode::Failure
+        })?
+        .ok_or_else(|| {
+            eprintln!("the database does not exist: {:?}", args.config.db.path);
+            ExitCode::Failure
+        })?;
+
+    println!(
+        "{:<24} {:>16} {:>20} {:>20}",
+        "column family", "estimated keys", "live data (bytes)", "sst files (bytes)"
+    );
+    let mut totals = [0u64; STATS_PROPERTIES.len()];
+    for (col, name) in COLUMN_NAMES.iter() {
+        let mut values = Vec::with_capacity(STATS_PROPERTIES.len());
+        for (property, total) in STATS_PROPERTIES.iter().zip(totals.iter_mut()) {
+            let value = db.property_int_value(*col, property).map_err(|err| {
+                eprintln!(
+                    "failed to read {} of column family {}: {}",
+                    property, col, err
+                );
+                ExitCode::Failure
+            })?;
+            *total += value.unwrap_or(0);
+            values.push(value.map_or_else(|| "-".to_owned(), |v| v.to_string()));
+        }
+        println!(
+            "{:<24} {:>16} {:>20} {:>20}",
+            format!("{} ({})", col, name),
+            values[0],
+            values[1],
+            values[2]
+        );
+    }
+    println!(
+        "{:<24} {:>16} {:>20} {:>20}",
+        "total", totals[0], totals[1], totals[2]
+    );
+
+    Ok(())
+}
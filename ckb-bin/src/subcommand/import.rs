@@ -8,14 +8,15 @@ pub fn import(args: ImportArgs, async_handle: Handle) -> Result<(), ExitCode> {
     let builder = SharedBuilder::new(&args.config.db, None, async_handle)?;
     let (shared, mut pack) = builder.consensus(args.consensus).build()?;
 
-    let chain_service = ChainService::new(shared, pack.take_proposal_table());
+    let chain_service = ChainService::new(shared.clone(), pack.take_proposal_table());
     let chain_controller = chain_service.start::<&str>(Some("ImportChainService"));
 
-    // manual drop tx_pool_builder and relay_tx_receiver
+    // manual drop tx_pool_builder, relay_tx_receiver and ask_for_txs_receiver
     pack.take_tx_pool_builder();
     pack.take_relay_tx_receiver();
+    pack.take_ask_for_txs_receiver();
 
-    Import::new(chain_controller, args.source)
+    Import::new(chain_controller, shared, args.source)
         .execute()
         .map_err(|err| {
             eprintln!("Import error: {:?}", err);
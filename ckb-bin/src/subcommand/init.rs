@@ -1,13 +1,14 @@
 use std::fs;
 use std::io::{self, Read};
+use std::path::Path;
 
 use crate::helper::prompt;
-use ckb_app_config::{cli, AppConfig, ExitCode, InitArgs};
+use ckb_app_config::{cli, merge_fragments, AppConfig, ExitCode, InitArgs};
 use ckb_chain_spec::ChainSpec;
 use ckb_jsonrpc_types::ScriptHashType;
 use ckb_resource::{
-    Resource, TemplateContext, AVAILABLE_SPECS, CKB_CONFIG_FILE_NAME, DB_OPTIONS_FILE_NAME,
-    MINER_CONFIG_FILE_NAME, SPEC_DEV_FILE_NAME,
+    Resource, Template, TemplateContext, AVAILABLE_SPECS, CKB_CONFIG_FILE_NAME,
+    CKB_LOCAL_CONFIG_FILE_NAME, DB_OPTIONS_FILE_NAME, MINER_CONFIG_FILE_NAME, SPEC_DEV_FILE_NAME,
 };
 use ckb_types::{prelude::*, H256};
 
@@ -44,6 +45,15 @@ pub fn init(args: InitArgs) -> Result<(), ExitCode> {
         }
     }
 
+    let local_config_file = args.root_dir.join(CKB_LOCAL_CONFIG_FILE_NAME);
+    if args.config_dropin.is_some() && !args.force && local_config_file.exists() {
+        eprintln!(
+            "{} already exists, use --force to overwrite it with the drop-in fragments.",
+            CKB_LOCAL_CONFIG_FILE_NAME
+        );
+        return Err(ExitCode::Failure);
+    }
+
     if args.interactive {
         let in_block_assembler_code_hash = prompt("code hash: ");
         let in_args = prompt("args: ");
@@ -155,6 +165,14 @@ pub fn init(args: InitArgs) -> Result<(), ExitCode> {
 
     let log_to_file = args.log_to_file.to_string();
     let log_to_stdout = args.log_to_stdout.to_string();
+    let rpc_modules = format!(
+        "[{}]",
+        args.rpc_modules
+            .iter()
+            .map(|module| format!("\"{}\"", module))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
     let mut context = TemplateContext::new(
         &args.chain,
         vec![
@@ -166,6 +184,12 @@ pub fn init(args: InitArgs) -> Result<(), ExitCode> {
             ("spec_source", "bundled"),
         ],
     );
+    if !args.rpc_modules.is_empty() {
+        context.insert("rpc_modules", rpc_modules.as_str());
+    }
+    if let Some(metrics_exporter) = &args.metrics_exporter {
+        context.insert("metrics_exporter", metrics_exporter.as_str());
+    }
 
     if let Some(spec_file) = args.import_spec {
         context.insert("spec_source", "file");
@@ -202,6 +226,11 @@ pub fn init(args: InitArgs) -> Result<(), ExitCode> {
 
     println!("create {}", CKB_CONFIG_FILE_NAME);
     Resource::bundled_ckb_config().export(&context, &args.root_dir)?;
+    if let Some(dropin_dir) = &args.config_dropin {
+        println!("create {}", CKB_LOCAL_CONFIG_FILE_NAME);
+        let local_config = render_dropin(dropin_dir, &context)?;
+        fs::write(&local_config_file, local_config)?;
+    }
     println!("create {}", MINER_CONFIG_FILE_NAME);
     Resource::bundled_miner_config().export(&context, &args.root_dir)?;
     println!("create {}", DB_OPTIONS_FILE_NAME);
@@ -222,3 +251,27 @@ pub fn init(args: InitArgs) -> Result<(), ExitCode> {
 
     Ok(())
 }
+
+// Renders the `*.toml` fragments in the drop-in directory and merges them in the file name order.
+fn render_dropin(dropin_dir: &Path, context: &TemplateContext) -> Result<String, ExitCode> {
+    let mut fragment_files = Vec::new();
+    for entry in fs::read_dir(dropin_dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().map_or(false, |ext| ext == "toml") {
+            fragment_files.push(path);
+        }
+    }
+    fragment_files.sort();
+
+    let mut fragments = Vec::with_capacity(fragment_files.len());
+    for path in fragment_files {
+        println!("merge {}", path.display());
+        let template = Template::new(fs::read_to_string(&path)?);
+        fragments.push(template.render(context)?);
+    }
+    let value = merge_fragments(fragments)?;
+    toml::to_string_pretty(&value).map_err(|err| {
+        eprintln!("Config Error: {:?}", err);
+        ExitCode::Config
+    })
+}
@@ -17,7 +17,8 @@ pub fn miner(args: MinerArgs, async_handle: Handle) -> Result<(), ExitCode> {
         args.limit,
     );
 
-    ckb_memory_tracker::track_current_process_simple(args.memory_tracker.interval);
+    let _memory_tracker =
+        ckb_memory_tracker::track_current_process_simple(args.memory_tracker.interval);
 
     thread::Builder::new()
         .name("client".to_string())
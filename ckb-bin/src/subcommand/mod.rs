@@ -1,3 +1,6 @@
+mod alert;
+mod config_dump;
+mod db;
 mod db_repair;
 mod export;
 mod import;
@@ -5,12 +8,18 @@ mod init;
 mod list_hashes;
 mod migrate;
 mod miner;
+mod move_ancient;
 mod peer_id;
+mod recover;
 mod replay;
 mod reset_data;
 mod run;
+mod schema_dump;
 mod stats;
 
+pub use self::alert::alert_sign;
+pub use self::config_dump::config_dump;
+pub use self::db::{db_compact, db_stats};
 pub use self::db_repair::db_repair;
 pub use self::export::export;
 pub use self::import::import;
@@ -18,8 +27,11 @@ pub use self::init::init;
 pub use self::list_hashes::list_hashes;
 pub use self::migrate::migrate;
 pub use self::miner::miner;
+pub use self::move_ancient::move_ancient;
 pub use self::peer_id::peer_id;
+pub use self::recover::recover;
 pub use self::replay::replay;
 pub use self::reset_data::reset_data;
 pub use self::run::run;
+pub use self::schema_dump::schema_dump;
 pub use self::stats::stats;
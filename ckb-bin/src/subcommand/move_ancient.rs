@@ -0,0 +1,23 @@
+use ckb_app_config::{ExitCode, MoveAncientArgs};
+
+pub fn move_ancient(args: MoveAncientArgs) -> Result<(), ExitCode> {
+    let source = args.config.ancient;
+    println!(
+        "moving the freezer data from {} to {} ...",
+        source.display(),
+        args.target.display()
+    );
+    // Fails when the freezer is locked by a running node.
+    let moved = ckb_freezer::relocate(&source, &args.target).map_err(|err| {
+        eprintln!("failed to move the freezer data: {}", err);
+        ExitCode::Failure
+    })?;
+    let target = args.target.canonicalize().unwrap_or(args.target);
+    println!("moved {} bytes, all the checksums are verified", moved);
+    println!(
+        "please set `ancient = {:?}` in ckb.toml before starting the node",
+        target.display().to_string()
+    );
+
+    Ok(())
+}
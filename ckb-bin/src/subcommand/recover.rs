@@ -0,0 +1,428 @@
+use crate::helper::prompt;
+use ckb_app_config::{ExitCode, RecoverArgs};
+use ckb_async_runtime::Handle;
+use ckb_chain::chain::ChainService;
+use ckb_db::RocksDB;
+use ckb_db_schema::COLUMNS;
+use ckb_instrument::{ProgressBar, ProgressStyle};
+use ckb_launcher::SharedBuilder;
+use ckb_shared::Shared;
+use ckb_store::ChainStore;
+use ckb_types::{core::BlockNumber, packed, prelude::*};
+
+pub fn recover(args: RecoverArgs, async_handle: Handle) -> Result<(), ExitCode> {
+    // Fails when the database is locked by a running node.
+    if let Err(err) = RocksDB::open_with_check(&args.config.db, COLUMNS) {
+        eprintln!("failed to open the database: {}", err);
+        if !confirm(
+            args.force,
+            "Repair the database? The node must be stopped, \
+             and the writes not flushed to the disk may be lost. ",
+        ) {
+            return Err(ExitCode::Failure);
+        }
+        println!(
+            "repairing the database {} ...",
+            args.config.db.path.display()
+        );
+        RocksDB::repair(&args.config.db.path).map_err(|err| {
+            eprintln!("repair error: {}", err);
+            ExitCode::Failure
+        })?;
+    }
+
+    let shared_builder = SharedBuilder::new(
+        &args.config.db,
+        Some(args.config.ancient.clone()),
+        async_handle,
+    )?;
+    let (shared, mut pack) = shared_builder
+        .consensus(args.consensus)
+        .tx_pool_config(args.config.tx_pool)
+        .build()?;
+
+    let tip_number = shared.snapshot().tip_number();
+    let (inconsistent_number, reason) = match check_chain(&shared)? {
+        Some(inconsistent) => inconsistent,
+        None => {
+            println!("the chain is consistent, the tip is {}", tip_number);
+            return Ok(());
+        }
+    };
+    println!("block {} is inconsistent: {}", inconsistent_number, reason);
+
+    let target_number = inconsistent_number - 1;
+    if let Err(reason) = check_rollback(&shared, inconsistent_number, tip_number) {
+        eprintln!(
+            "{}, the chain can not be truncated to block {}.\n\
+             Please run `ckb reset-data --database` and resync from the network, \
+             or `ckb import` the blocks exported from another node.",
+            reason, target_number
+        );
+        return Err(ExitCode::Failure);
+    }
+
+    if !confirm(
+        args.force,
+        &format!(
+            "Truncate the chain from the tip {} to block {}? ",
+            tip_number, target_number
+        ),
+    ) {
+        return Err(ExitCode::Failure);
+    }
+    let target_hash = shared
+        .store()
+        .get_block_hash(target_number)
+        .expect("checked");
+    let chain_controller =
+        ChainService::new(shared.clone(), pack.take_proposal_table()).start(Some("ChainService"));
+    chain_controller.truncate(target_hash).map_err(|err| {
+        eprintln!("truncate error: {}", err);
+        ExitCode::Failure
+    })?;
+    println!(
+        "truncated the chain to block {}, start the node with `ckb run` to resume syncing",
+        target_number
+    );
+
+    Ok(())
+}
+
+fn confirm(force: bool, msg: &str) -> bool {
+    force || ["y", "Y"].contains(&prompt(msg).trim())
+}
+
+// Checks the chain from the last frozen block to the tip, returns the first inconsistent block
+// number and the reason.
+//
+// The frozen blocks are immutable, the last one must be in the main chain of the database.
+fn check_chain(shared: &Shared) -> Result<Option<(BlockNumber, String)>, ExitCode> {
+    let store = shared.store();
+    let tip_number = shared.snapshot().tip_number();
+    let frozen_number = store.freezer().map(|freezer| freezer.number()).unwrap_or(0);
+
+    if frozen_number > 0 {
+        let last_frozen = frozen_number - 1;
+        let frozen_hash = store
+            .freezer()
+            .expect("checked")
+            .retrieve(last_frozen)
+            .map_err(|err| {
+                eprintln!("failed to read the freezer: {}", err);
+                ExitCode::Failure
+            })?
+            .and_then(|raw_block| {
+                packed::BlockReader::from_compatible_slice(&raw_block)
+                    .ok()
+                    .map(|block| block.header().calc_header_hash())
+            });
+        if last_frozen > tip_number || frozen_hash != store.get_block_hash(last_frozen) {
+            eprintln!(
+                "the frozen block {} does not match the database, the chain can not be recovered.\n\
+                 Please run `ckb reset-data --database` and resync from the network.",
+                last_frozen
+            );
+            return Err(ExitCode::Failure);
+        }
+    }
+
+    let from = frozen_number.max(1);
+    let base = store.get_block_hash(from - 1);
+    if let Err(reason) = base
+        .as_ref()
+        .ok_or_else(|| "the block number index is missing".to_string())
+        .and_then(|hash| check_block_meta(shared, hash))
+    {
+        eprintln!(
+            "block {} is inconsistent: {}, the chain can not be recovered.\n\
+             Please run `ckb reset-data --database` and resync from the network.",
+            from - 1,
+            reason
+        );
+        return Err(ExitCode::Failure);
+    }
+
+    println!("checking blocks {}..={} ...", from, tip_number);
+    let pb = ProgressBar::new((tip_number + 1).saturating_sub(from));
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+            )
+            .progress_chars("#>-"),
+    );
+    let mut parent_hash = base;
+    for number in from..=tip_number {
+        if let Err(reason) = check_block(shared, number, parent_hash.as_ref()) {
+            pb.finish_with_message("check finish");
+            return Ok(Some((number, reason)));
+        }
+        parent_hash = store.get_block_hash(number);
+        pb.inc(1);
+    }
+    pb.finish_with_message("check finish");
+
+    Ok(None)
+}
+
+fn check_block(
+    shared: &Shared,
+    number: BlockNumber,
+    parent_hash: Option<&packed::Byte32>,
+) -> Result<(), String> {
+    let store = shared.store();
+    let hash = store
+        .get_block_hash(number)
+        .ok_or_else(|| "the block number index is missing".to_string())?;
+    let block = store
+        .get_block(&hash)
+        .ok_or_else(|| "the block is missing".to_string())?;
+    if Some(&block.parent_hash()) != parent_hash {
+        return Err("the parent hash does not match the previous block".to_string());
+    }
+    if block.calc_transactions_root() != block.transactions_root() {
+        return Err("the transactions do not match the header".to_string());
+    }
+    check_block_meta(shared, &hash)
+}
+
+// The blocks `from..=to` are rolled back by detaching their transactions from the cell set, which
+// requires the blocks to be readable. The transactions must also match the headers, otherwise the
+// outputs never attached are deleted and the inputs are revived from the corrupted data.
+fn check_rollback(shared: &Shared, from: BlockNumber, to: BlockNumber) -> Result<(), String> {
+    let store = shared.store();
+    for number in from..=to {
+        let block = store
+            .get_block_hash(number)
+            .and_then(|hash| store.get_block(&hash))
+            .ok_or_else(|| format!("block {} is unreadable", number))?;
+        if block.calc_transactions_root() != block.transactions_root() {
+            return Err(format!(
+                "the transactions of block {} do not match the header",
+                number
+            ));
+        }
+    }
+    Ok(())
+}
+
+// The truncation target requires the block ext and the epoch.
+fn check_block_meta(shared: &Shared, hash: &packed::Byte32) -> Result<(), String> {
+    let store = shared.store();
+    if store.get_block_ext(hash).is_none() {
+        return Err("the block ext is missing".to_string());
+    }
+    if store
+        .get_block_epoch_index(hash)
+        .and_then(|index| store.get_epoch_ext(&index))
+        .is_none()
+    {
+        return Err("the block epoch is missing".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_app_config::{DBConfig, StoreConfig};
+    use ckb_async_runtime::new_global_runtime;
+    use ckb_db_schema::{COLUMN_BLOCK_EXT, COLUMN_INDEX};
+    use ckb_test_chain_utils::{always_success_cellbase, always_success_consensus};
+    use ckb_types::core::{BlockBuilder, BlockView, Capacity, HeaderView};
+    use ckb_verification_traits::Switch;
+    use std::sync::Arc;
+
+    const TIP: BlockNumber = 5;
+
+    fn new_block(shared: &Shared, parent: &HeaderView) -> BlockView {
+        let number = parent.number() + 1;
+        let epoch = shared
+            .consensus()
+            .next_epoch_ext(parent, &shared.snapshot().as_data_provider())
+            .unwrap()
+            .epoch();
+        BlockBuilder::default()
+            .transaction(always_success_cellbase(
+                number,
+                Capacity::zero(),
+                shared.consensus(),
+            ))
+            .parent_hash(parent.hash())
+            .number(number.pack())
+            .epoch(epoch.number_with_fraction(number).pack())
+            .timestamp((parent.timestamp() + 1).pack())
+            .compact_target(parent.compact_target().pack())
+            .build()
+    }
+
+    fn build_chain(builder: SharedBuilder) -> Shared {
+        let (shared, mut pack) = builder
+            .consensus(always_success_consensus())
+            .build()
+            .unwrap();
+        let chain_controller =
+            ChainService::new(shared.clone(), pack.take_proposal_table()).start::<&str>(None);
+        let mut parent = shared.consensus().genesis_block().header();
+        for _ in 0..TIP {
+            let block = new_block(&shared, &parent);
+            chain_controller
+                .internal_process_block(Arc::new(block.clone()), Switch::DISABLE_ALL)
+                .expect("process block ok");
+            parent = block.header();
+        }
+        assert_eq!(shared.snapshot().tip_number(), TIP);
+        shared
+    }
+
+    fn delete_block_ext(shared: &Shared, number: BlockNumber) {
+        let hash = shared.store().get_block_hash(number).unwrap();
+        let txn = shared.store().begin_transaction();
+        txn.delete(COLUMN_BLOCK_EXT, hash.as_slice()).unwrap();
+        txn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_check_consistent_chain() {
+        let shared = build_chain(SharedBuilder::with_temp_db());
+        assert_eq!(check_chain(&shared), Ok(None));
+    }
+
+    #[test]
+    fn test_check_missing_block_ext() {
+        let shared = build_chain(SharedBuilder::with_temp_db());
+        delete_block_ext(&shared, 3);
+
+        assert_eq!(
+            check_block(&shared, 3, shared.store().get_block_hash(2).as_ref()),
+            Err("the block ext is missing".to_string())
+        );
+        assert_eq!(
+            check_chain(&shared),
+            Ok(Some((3, "the block ext is missing".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_check_broken_number_index() {
+        let shared = build_chain(SharedBuilder::with_temp_db());
+        let key: packed::Uint64 = 3u64.pack();
+        let txn = shared.store().begin_transaction();
+        txn.delete(COLUMN_INDEX, key.as_slice()).unwrap();
+        txn.commit().unwrap();
+
+        assert_eq!(
+            check_block(&shared, 3, shared.store().get_block_hash(2).as_ref()),
+            Err("the block number index is missing".to_string())
+        );
+        assert_eq!(
+            check_chain(&shared),
+            Ok(Some((3, "the block number index is missing".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_check_wrong_parent_hash() {
+        let shared = build_chain(SharedBuilder::with_temp_db());
+        // A block at height 3 on top of the genesis block, indexed as if it were in the main chain.
+        let store = shared.store();
+        let block = store
+            .get_block(&store.get_block_hash(3).unwrap())
+            .unwrap()
+            .as_advanced_builder()
+            .parent_hash(shared.consensus().genesis_block().hash())
+            .build();
+        let key: packed::Uint64 = 3u64.pack();
+        let txn = shared.store().begin_transaction();
+        txn.insert_block(&block).unwrap();
+        txn.insert_raw(COLUMN_INDEX, key.as_slice(), block.hash().as_slice())
+            .unwrap();
+        txn.commit().unwrap();
+
+        let reason = "the parent hash does not match the previous block".to_string();
+        assert_eq!(
+            check_block(&shared, 3, shared.store().get_block_hash(2).as_ref()),
+            Err(reason.clone())
+        );
+        assert_eq!(check_chain(&shared), Ok(Some((3, reason))));
+    }
+
+    #[test]
+    fn test_check_corrupted_transactions() {
+        let shared = build_chain(SharedBuilder::with_temp_db());
+        // Block 3 with an extra transaction stored under the same header.
+        let store = shared.store();
+        let block = store.get_block(&store.get_block_hash(3).unwrap()).unwrap();
+        let corrupted = block
+            .as_advanced_builder()
+            .transaction(always_success_cellbase(
+                3,
+                Capacity::zero(),
+                shared.consensus(),
+            ))
+            .build_unchecked();
+        assert_eq!(corrupted.hash(), block.hash());
+        let txn = store.begin_transaction();
+        txn.insert_block(&corrupted).unwrap();
+        txn.commit().unwrap();
+
+        assert_eq!(
+            check_chain(&shared),
+            Ok(Some((
+                3,
+                "the transactions do not match the header".to_string()
+            )))
+        );
+        // The corrupted transactions can not be detached from the cell set.
+        assert_eq!(
+            check_rollback(&shared, 3, TIP),
+            Err("the transactions of block 3 do not match the header".to_string())
+        );
+        assert_eq!(check_rollback(&shared, 4, TIP), Ok(()));
+    }
+
+    #[test]
+    fn test_check_after_last_frozen_block() {
+        let (handle, _stop) = new_global_runtime();
+        let db_dir = tempfile::tempdir().unwrap();
+        let ancient_dir = tempfile::tempdir().unwrap();
+        let db_config = DBConfig {
+            path: db_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let builder =
+            SharedBuilder::new(&db_config, Some(ancient_dir.path().to_path_buf()), handle)
+                .unwrap()
+                .store_config(StoreConfig {
+                    freezer_enable: true,
+                    ..Default::default()
+                });
+        let shared = build_chain(builder);
+
+        // Freezes the blocks 0..=2
+        let store = shared.store();
+        let freezer = store.freezer().unwrap();
+        freezer
+            .freeze(3, |number| {
+                store
+                    .get_block_hash(number)
+                    .and_then(|hash| store.get_unfrozen_block(&hash))
+            })
+            .unwrap();
+        assert_eq!(freezer.number(), 3);
+
+        delete_block_ext(&shared, 3);
+
+        let (inconsistent_number, reason) = check_chain(&shared).unwrap().unwrap();
+        assert_eq!(inconsistent_number, 3);
+        assert_eq!(reason, "the block ext is missing");
+        // The chain is truncated to the last frozen block, which stays in the main chain.
+        let target_number = inconsistent_number - 1;
+        assert_eq!(target_number, freezer.number() - 1);
+        assert!(store
+            .get_block_hash(target_number)
+            .and_then(|hash| store.get_block(&hash))
+            .is_some());
+    }
+}
@@ -1,12 +1,20 @@
-use ckb_app_config::{ExitCode, ReplayArgs};
+use ckb_app_config::{DivergenceCheckArgs, ExitCode, ReplayArgs};
 use ckb_async_runtime::Handle;
 use ckb_chain::chain::ChainService;
 use ckb_chain_iter::ChainIterator;
+use ckb_dao::DaoCalculator;
 use ckb_instrument::{ProgressBar, ProgressStyle};
 use ckb_launcher::SharedBuilder;
-use ckb_shared::Shared;
+use ckb_shared::{Shared, Snapshot};
 use ckb_store::ChainStore;
+use ckb_types::core::{
+    cell::{
+        resolve_transaction_with_options, BlockCellProvider, OverlayCellProvider, ResolveOptions,
+    },
+    BlockView,
+};
 use ckb_verification_traits::Switch;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 pub fn replay(args: ReplayArgs, async_handle: Handle) -> Result<(), ExitCode> {
@@ -36,12 +44,20 @@ pub fn replay(args: ReplayArgs, async_handle: Handle) -> Result<(), ExitCode> {
             .consensus(args.consensus)
             .tx_pool_config(args.config.tx_pool)
             .build()?;
-        let chain = ChainService::new(tmp_shared, pack.take_proposal_table());
+        let chain = ChainService::new(tmp_shared.clone(), pack.take_proposal_table());
 
         if let Some((from, to)) = args.profile {
             profile(shared, chain, from, to);
         } else if args.sanity_check {
             sanity_check(shared, chain, args.full_verification);
+        } else if args.divergence_check.enabled() {
+            divergence_check(
+                shared,
+                tmp_shared,
+                chain,
+                &args.divergence_check,
+                args.full_verification,
+            );
         }
     }
     tmp_db_dir.close().map_err(|err| {
@@ -58,10 +74,10 @@ fn profile(shared: Shared, mut chain: ChainService, from: Option<u64>, to: Optio
     let to = to
         .map(|v| std::cmp::min(v, tip_number))
         .unwrap_or(tip_number);
-    process_range_block(&shared, &mut chain, 1..from);
+    process_range_block(&shared, &mut chain, 1..from, Switch::NONE);
     println!("start profiling, re-process blocks {}..{}:", from, to);
     let now = std::time::Instant::now();
-    let tx_count = process_range_block(&shared, &mut chain, from..=to);
+    let tx_count = process_range_block(&shared, &mut chain, from..=to, Switch::NONE);
     let duration = now.elapsed();
     println!(
         "end profiling, duration {:?} txs {} tps {}",
@@ -75,6 +91,7 @@ fn process_range_block(
     shared: &Shared,
     chain: &mut ChainService,
     range: impl Iterator<Item = u64>,
+    switch: Switch,
 ) -> usize {
     let mut tx_count = 0;
     let snapshot = shared.snapshot();
@@ -84,7 +101,7 @@ fn process_range_block(
             .and_then(|hash| snapshot.get_block(&hash))
             .expect("read block from store");
         tx_count += block.transactions().len().saturating_sub(1);
-        chain.process_block(Arc::new(block), Switch::NONE).unwrap();
+        chain.process_block(Arc::new(block), switch).unwrap();
     }
     tx_count
 }
@@ -142,3 +159,193 @@ fn sanity_check(shared: Shared, mut chain: ChainService, full_verification: bool
 
     println!("replay finishing, please wait...");
 }
+
+fn divergence_check(
+    shared: Shared,
+    tmp_shared: Shared,
+    mut chain: ChainService,
+    args: &DivergenceCheckArgs,
+    full_verification: bool,
+) {
+    let tip_number = shared.snapshot().tip_number();
+    let from = args.from.map(|v| std::cmp::max(1, v)).unwrap_or(1);
+    let to = args
+        .to
+        .map(|v| std::cmp::min(v, tip_number))
+        .unwrap_or(tip_number);
+    // The blocks before the range are trusted, skip the verification to rebuild the state quickly
+    process_range_block(&shared, &mut chain, 1..from, Switch::DISABLE_ALL);
+
+    // The DAO field is recomputed in `check_dao`, the DAO header verifier only tells whether it
+    // matches.
+    let switch = if full_verification {
+        Switch::DISABLE_DAOHEADER
+    } else {
+        Switch::DISABLE_DAOHEADER | Switch::DISABLE_SCRIPT
+    };
+    println!("start checking, re-process blocks {}..{}:", from, to);
+    let pb = ProgressBar::new(to.saturating_sub(from) + 1);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+            )
+            .progress_chars("#>-"),
+    );
+    let snapshot = shared.snapshot();
+    for number in from..=to {
+        let block = snapshot
+            .get_block_hash(number)
+            .and_then(|hash| snapshot.get_block(&hash))
+            .expect("read block from store");
+        let mut result = Ok(());
+        if args.verify_dao {
+            result = check_dao(&tmp_shared, &block);
+        }
+        if result.is_ok() {
+            result = chain
+                .process_block(Arc::new(block.clone()), switch)
+                .map(|_| ())
+                .map_err(|err| format!("verification error: {}", err));
+        }
+        if result.is_ok() && args.verify_cells {
+            result = check_cells(&snapshot, &tmp_shared.snapshot(), &block);
+        }
+        if let Err(divergence) = result {
+            pb.finish_with_message("replay finish");
+            eprintln!(
+                "replay divergence at block({}-{}): {}",
+                number,
+                block.hash(),
+                divergence
+            );
+            return;
+        }
+        pb.inc(1);
+    }
+    pb.finish_with_message("finish");
+    println!("divergence check pass, blocks {}..{}", from, to);
+
+    println!("replay finishing, please wait...");
+}
+
+// Recomputes the DAO field of the block on the replayed chain and compares it with the stored
+// header.
+fn check_dao(tmp_shared: &Shared, block: &BlockView) -> Result<(), String> {
+    let consensus = tmp_shared.consensus();
+    let snapshot = tmp_shared.snapshot();
+    let parent = snapshot
+        .get_block_header(&block.parent_hash())
+        .ok_or_else(|| "the parent block is not replayed".to_owned())?;
+    let block_cp =
+        BlockCellProvider::new(block).map_err(|err| format!("invalid block: {}", err))?;
+    let cell_provider = OverlayCellProvider::new(&block_cp, snapshot.as_ref());
+    let resolve_opts = ResolveOptions::empty().set_skip_immature_header_deps_check(
+        consensus
+            .hardfork_switch()
+            .is_remove_header_deps_immature_rule_enabled(block.epoch().number()),
+    );
+    let mut seen_inputs = HashSet::new();
+    let resolved = block
+        .transactions()
+        .into_iter()
+        .map(|tx| {
+            resolve_transaction_with_options(
+                tx,
+                &mut seen_inputs,
+                &cell_provider,
+                snapshot.as_ref(),
+                resolve_opts,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("failed to resolve the transactions: {}", err))?;
+    let data_loader = snapshot.as_data_provider();
+    let dao = DaoCalculator::new(consensus, &data_loader)
+        .dao_field(&resolved, &parent)
+        .map_err(|err| format!("failed to calculate the DAO field: {}", err))?;
+    if dao != block.dao() {
+        return Err(format!(
+            "DAO field mismatch, recomputed {}, stored {}",
+            dao,
+            block.dao()
+        ));
+    }
+    Ok(())
+}
+
+// Compares the block extras and the cell set changes of the replayed block with the stored ones.
+fn check_cells(stored: &Snapshot, replayed: &Snapshot, block: &BlockView) -> Result<(), String> {
+    let block_hash = block.hash();
+    let stored_ext = stored
+        .get_block_ext(&block_hash)
+        .ok_or_else(|| "the block ext is missing in the store".to_owned())?;
+    let replayed_ext = replayed
+        .get_block_ext(&block_hash)
+        .ok_or_else(|| "the block ext is missing in the replayed chain".to_owned())?;
+    if stored_ext.total_difficulty != replayed_ext.total_difficulty {
+        return Err(format!(
+            "total difficulty mismatch, replayed {:#x}, stored {:#x}",
+            replayed_ext.total_difficulty, stored_ext.total_difficulty
+        ));
+    }
+    if stored_ext.total_uncles_count != replayed_ext.total_uncles_count {
+        return Err(format!(
+            "total uncles count mismatch, replayed {}, stored {}",
+            replayed_ext.total_uncles_count, stored_ext.total_uncles_count
+        ));
+    }
+    // The fees are absent when the block is stored or replayed without the contextual
+    // verification.
+    if !stored_ext.txs_fees.is_empty()
+        && !replayed_ext.txs_fees.is_empty()
+        && stored_ext.txs_fees != replayed_ext.txs_fees
+    {
+        return Err(format!(
+            "transaction fees mismatch, replayed {:?}, stored {:?}",
+            replayed_ext.txs_fees, stored_ext.txs_fees
+        ));
+    }
+
+    let consumed: HashSet<_> = block
+        .transactions()
+        .iter()
+        .skip(1)
+        .flat_map(|tx| tx.input_pts_iter())
+        .collect();
+    for out_point in &consumed {
+        if stored.get_cell(out_point).is_some() {
+            return Err(format!(
+                "the consumed cell {} is still live in the store",
+                out_point
+            ));
+        }
+    }
+    for tx in block.transactions() {
+        let tx_hash = tx.hash();
+        if stored.get_transaction_info(&tx_hash) != replayed.get_transaction_info(&tx_hash) {
+            return Err(format!("transaction info of {} mismatch", tx_hash));
+        }
+        for out_point in tx.output_pts_iter() {
+            if consumed.contains(&out_point) {
+                continue;
+            }
+            let replayed_cell = replayed.get_cell(&out_point).ok_or_else(|| {
+                format!(
+                    "the created cell {} is missing in the replayed chain",
+                    out_point
+                )
+            })?;
+            // The stored cell is absent when it is consumed by a later block
+            if let Some(stored_cell) = stored.get_cell(&out_point) {
+                if stored_cell.cell_output != replayed_cell.cell_output
+                    || stored.get_cell_data_hash(&out_point)
+                        != replayed.get_cell_data_hash(&out_point)
+                {
+                    return Err(format!("the created cell {} mismatch", out_point));
+                }
+            }
+        }
+    }
+    Ok(())
+}
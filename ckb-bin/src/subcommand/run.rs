@@ -5,8 +5,6 @@ use ckb_build_info::Version;
 use ckb_launcher::Launcher;
 use ckb_logger::info;
 use ckb_network::{DefaultExitHandler, ExitHandler};
-use ckb_store::ChainStore;
-use ckb_types::core::cell::setup_system_cell_cache;
 
 pub fn run(args: RunArgs, version: Version, async_handle: Handle) -> Result<(), ExitCode> {
     deadlock_detection();
@@ -15,46 +13,33 @@ pub fn run(args: RunArgs, version: Version, async_handle: Handle) -> Result<(),
 
     let mut launcher = Launcher::new(args, version, async_handle);
 
-    let block_assembler_config = launcher.sanitize_block_assembler_config()?;
-    let miner_enable = block_assembler_config.is_some();
-    let exit_handler = DefaultExitHandler::default();
-
-    let (shared, mut pack) = launcher.build_shared(block_assembler_config)?;
-
-    // spawn freezer background process
-    let _freezer = shared.spawn_freeze();
-
-    setup_system_cell_cache(
-        shared.consensus().genesis_block(),
-        &shared.store().cell_provider(),
-    )
-    .expect("SYSTEM_CELL cache init once");
+    if launcher.args.check {
+        let report = launcher.check();
+        println!("{}", report);
+        return if report.is_ok() {
+            Ok(())
+        } else {
+            Err(ExitCode::Config)
+        };
+    }
 
     rayon::ThreadPoolBuilder::new()
         .thread_name(|i| format!("RayonGlobal-{}", i))
         .build_global()
         .expect("Init the global thread pool for rayon failed");
 
-    ckb_memory_tracker::track_current_process(
-        launcher.args.config.memory_tracker.interval,
-        Some(shared.store().db().inner()),
-    );
-
-    launcher.check_assume_valid_target(&shared);
-
-    let chain_controller = launcher.start_chain_service(&shared, pack.take_proposal_table());
-
-    let (network_controller, rpc_server) = launcher.start_network_and_rpc(
-        &shared,
-        chain_controller,
-        &exit_handler,
-        miner_enable,
-        pack.take_relay_tx_receiver(),
+    let exit_handler = DefaultExitHandler::default();
+    let services = launcher.start(&exit_handler)?;
+
+    let shutdown_coordinator = Launcher::shutdown_coordinator(
+        &services.shared,
+        &services.chain_controller,
+        &services.network_controller,
+        Some(services.rpc_server),
+        services.background,
+        services.freezer,
     );
 
-    let tx_pool_builder = pack.take_tx_pool_builder();
-    tx_pool_builder.start(network_controller);
-
     let exit_handler_clone = exit_handler.clone();
     ctrlc::set_handler(move || {
         exit_handler_clone.notify_exit();
@@ -63,7 +48,7 @@ pub fn run(args: RunArgs, version: Version, async_handle: Handle) -> Result<(),
     exit_handler.wait_for_exit();
 
     info!("Finishing work, please wait...");
-    drop(rpc_server);
+    shutdown_coordinator.shutdown();
 
     Ok(())
 }
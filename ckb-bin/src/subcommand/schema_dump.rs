@@ -0,0 +1,26 @@
+use ckb_app_config::{ExitCode, SchemaDumpArgs};
+use ckb_jsonrpc_types::schema::entity_mappings;
+use ckb_types::schemas;
+use std::fs;
+
+const MAPPINGS_FILE_NAME: &str = "json-mappings.json";
+
+pub fn schema_dump(args: SchemaDumpArgs) -> Result<(), ExitCode> {
+    fs::create_dir_all(&args.target)?;
+
+    for (file_name, content) in schemas::ALL {
+        let path = args.target.join(file_name);
+        fs::write(&path, content)?;
+        println!("{}", path.display());
+    }
+
+    let content = serde_json::to_string_pretty(&entity_mappings()).map_err(|err| {
+        eprintln!("Serialize the JSON mappings error: {}", err);
+        ExitCode::Failure
+    })?;
+    let path = args.target.join(MAPPINGS_FILE_NAME);
+    fs::write(&path, content)?;
+    println!("{}", path.display());
+
+    Ok(())
+}
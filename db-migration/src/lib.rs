@@ -32,6 +32,22 @@ impl Migrations {
             .insert(migration.version().to_string(), migration);
     }
 
+    /// Check whether the database is created by a newer client
+    ///
+    /// Return true if the database version is newer than the last migration
+    pub fn is_downgrade(&self, db: &ReadOnlyDB) -> bool {
+        let db_version = db
+            .get_pinned_default(MIGRATION_VERSION_KEY)
+            .expect("get the version of database")
+            .map(|version_bytes| {
+                String::from_utf8(version_bytes.to_vec()).expect("version bytes to utf8")
+            });
+        match (db_version, self.migrations.values().last()) {
+            (Some(db_version), Some(m)) => m.version() < db_version.as_str(),
+            _ => false,
+        }
+    }
+
     /// Check whether database requires migration
     ///
     /// Return true if migration is required
@@ -43,6 +43,15 @@ pub const COLUMN_BLOCK_EXTENSION: Col = "15";
 pub const META_TIP_HEADER_KEY: &[u8] = b"TIP_HEADER";
 /// META_CURRENT_EPOCH_KEY tracks the latest known epoch
 pub const META_CURRENT_EPOCH_KEY: &[u8] = b"CURRENT_EPOCH";
+/// META_HEADER_CHECKPOINT_KEY tracks the best header verified by the header sync
+pub const META_HEADER_CHECKPOINT_KEY: &[u8] = b"HEADER_CHECKPOINT";
+/// META_VERIFIED_HEADER_PREFIX is the key prefix of the headers verified by the header sync,
+/// followed by the block number in big endian, the value is the header hash
+pub const META_VERIFIED_HEADER_PREFIX: &[u8] = b"VERIFIED_HEADER";
+/// META_INVALID_HEADER_PREFIX is the key prefix of the roots of the invalid header branches
+pub const META_INVALID_HEADER_PREFIX: &[u8] = b"INVALID_HEADER";
+/// META_INVALID_HEADERS_VERSION_KEY tracks the client version which recorded the invalid headers
+pub const META_INVALID_HEADERS_VERSION_KEY: &[u8] = b"VERSION_OF_INVALID_HEADERS";
 
 /// CHAIN_SPEC_HASH_KEY tracks the hash of chain spec which created current database
 pub const CHAIN_SPEC_HASH_KEY: &[u8] = b"chain-spec-hash";
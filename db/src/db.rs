@@ -11,8 +11,9 @@ use rocksdb::ops::{
     Put, SetOptions, WriteOps,
 };
 use rocksdb::{
-    ffi, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice, FullOptions, IteratorMode,
-    OptimisticTransactionDB, OptimisticTransactionOptions, Options, WriteBatch, WriteOptions,
+    ffi, ffi_util, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice, FullOptions, Handle,
+    IteratorMode, OptimisticTransactionDB, OptimisticTransactionOptions, Options, WriteBatch,
+    WriteOptions,
 };
 use std::path::Path;
 use std::sync::Arc;
@@ -28,7 +29,9 @@ pub struct RocksDB {
 const DEFAULT_CACHE_SIZE: usize = 128 << 20;
 
 impl RocksDB {
-    pub(crate) fn open_with_check(config: &DBConfig, columns: u32) -> Result<Self> {
+    /// Open a database with the given configuration and columns count, returns the error
+    /// instead of panicking, such as when the database is locked by a running node.
+    pub fn open_with_check(config: &DBConfig, columns: u32) -> Result<Self> {
         let cf_names: Vec<_> = (0..columns).map(|c| c.to_string()).collect();
 
         let (mut opts, cf_descriptors) = if let Some(ref file) = config.options_file {
@@ -64,6 +67,9 @@ impl RocksDB {
 
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
+        if config.statistics {
+            opts.enable_statistics();
+        }
 
         let db = OptimisticTransactionDB::open_cf_descriptors(&opts, &config.path, cf_descriptors)
             .map_err(|err| internal_error(format!("failed to open database: {}", err)))?;
@@ -290,6 +296,36 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Flushes the memtables of the columns to the SST files and waits for the flush.
+    ///
+    /// It is the barrier before the process exits, so the recovery on the next startup does not
+    /// need to replay the WAL.
+    pub fn flush(&self, cols: &[&str]) -> Result<()> {
+        let cfs = cols
+            .iter()
+            .map(|col| {
+                self.inner
+                    .cf_handle(col)
+                    .ok_or_else(|| internal_error(format!("column {} not found", col)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        unsafe {
+            let opts = ffi::rocksdb_flushoptions_create();
+            ffi::rocksdb_flushoptions_set_wait(opts, 1);
+            let mut result = Ok(());
+            for cf in cfs {
+                let mut err: *mut ::libc::c_char = ::std::ptr::null_mut();
+                ffi::rocksdb_flush_cf(self.inner.base_db_ptr(), opts, cf.handle(), &mut err);
+                if !err.is_null() {
+                    result = Err(internal_error(ffi_util::error_message(err)));
+                    break;
+                }
+            }
+            ffi::rocksdb_flushoptions_destroy(opts);
+            result
+        }
+    }
+
     /// Return `RocksDBSnapshot`.
     pub fn get_snapshot(&self) -> RocksDBSnapshot {
         unsafe {
@@ -395,6 +431,21 @@ mod tests {
         RocksDB::open(&config, 2); // panic
     }
 
+    #[test]
+    fn flush() {
+        let db = setup_db("flush", 2);
+        let txn = db.transaction();
+        txn.put("0", &[0, 0], &[0, 0, 0]).unwrap();
+        txn.put("1", &[1, 1], &[1, 1, 1]).unwrap();
+        txn.commit().unwrap();
+
+        db.flush(&["0", "1"]).unwrap();
+        assert!(
+            vec![0u8, 0, 0].as_slice() == db.get_pinned("0", &[0, 0]).unwrap().unwrap().as_ref()
+        );
+        assert!(db.flush(&["2"]).is_err());
+    }
+
     #[test]
     fn write_and_read() {
         let db = setup_db("write_and_read", 2);
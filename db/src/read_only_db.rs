@@ -2,7 +2,7 @@
 use crate::{internal_error, Result};
 use ckb_db_schema::Col;
 use ckb_logger::info;
-use rocksdb::ops::{GetColumnFamilys, GetPinned, GetPinnedCF, OpenCF};
+use rocksdb::ops::{GetColumnFamilys, GetPinned, GetPinnedCF, GetPropertyCF, OpenCF};
 use rocksdb::{DBPinnableSlice, Options, ReadOnlyDB as RawReadOnlyDB};
 use std::path::Path;
 use std::sync::Arc;
@@ -72,11 +72,25 @@ impl ReadOnlyDB {
             .ok_or_else(|| internal_error(format!("column {} not found", col)))?;
         self.inner.get_pinned_cf(cf, &key).map_err(internal_error)
     }
+
+    /// Return the integer value of the RocksDB property of the given column, such as
+    /// `rocksdb.estimate-num-keys`.
+    pub fn property_int_value(&self, col: Col, name: &str) -> Result<Option<u64>> {
+        let cf = self
+            .inner
+            .cf_handle(col)
+            .ok_or_else(|| internal_error(format!("column {} not found", col)))?;
+        self.inner
+            .property_int_value_cf(cf, name)
+            .map_err(internal_error)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::ReadOnlyDB;
+    use crate::RocksDB;
+    use ckb_app_config::DBConfig;
 
     #[test]
     fn test_open_read_only_not_exist() {
@@ -93,4 +107,31 @@ mod tests {
         let db = ReadOnlyDB::open_cf(&tmp_dir, cfs);
         assert!(matches!(db, Ok(x) if x.is_none()));
     }
+
+    #[test]
+    fn test_property_int_value() {
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("test_property_int_value")
+            .tempdir()
+            .unwrap();
+        let config = DBConfig {
+            path: tmp_dir.as_ref().to_path_buf(),
+            ..Default::default()
+        };
+        {
+            let db = RocksDB::open(&config, 1);
+            let mut batch = db.new_write_batch();
+            batch.put("0", &[0], &[0]).unwrap();
+            db.write(&batch).unwrap();
+        }
+
+        let db = ReadOnlyDB::open_cf(&tmp_dir, vec!["0"]).unwrap().unwrap();
+        assert!(db
+            .property_int_value("0", "rocksdb.estimate-num-keys")
+            .unwrap()
+            .is_some());
+        assert!(db
+            .property_int_value("1", "rocksdb.estimate-num-keys")
+            .is_err());
+    }
 }
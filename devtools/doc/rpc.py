@@ -36,6 +36,10 @@ Once a deprecated method is disabled, the CKB dev team will remove it in a futur
 
 For example, a method is marked as deprecated in 0.35.0, it can be disabled in 0.36.0 and removed in 0.37.0. The minor versions are released monthly, so there's at least a two-month buffer for a deprecated RPC method.
 
+## Schema Discovery
+
+The method `rpc.discover` returns an [OpenRPC](https://spec.open-rpc.org) document of the methods in the enabled modules. The document is generated from the RPC definitions when building CKB, so it always matches the running node. Client SDK generators and documentation sites can consume it as a machine-readable schema.
+
 ## Minimum Supported Rust Version policy (MSRV)
 
 The crate `ckb-rpc`'s minimum supported rustc version is 1.51.0.
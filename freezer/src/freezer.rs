@@ -1,4 +1,4 @@
-use crate::freezer_files::FreezerFiles;
+use crate::freezer_files::{helper, FreezerFiles, FreezerFilesBuilder};
 use crate::internal_error;
 use ckb_error::Error;
 use ckb_types::{
@@ -8,13 +8,18 @@ use ckb_types::{
 };
 use ckb_util::Mutex;
 use fs2::FileExt;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use std::borrow::Cow;
+use std::cmp;
 use std::collections::BTreeMap;
 use std::fs::{File, OpenOptions};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
-const LOCKNAME: &str = "FLOCK";
+pub(crate) const LOCKNAME: &str = "FLOCK";
+/// The number of blocks each worker thread prepares per round.
+const FREEZE_BATCH_PER_THREAD: usize = 32;
 
 /// freeze result represent blkhash -> (blknum, txsnum) btree-map
 /// sorted blkhash for making ranges for compaction
@@ -34,11 +39,25 @@ pub struct Freezer {
     pub(crate) lock: Arc<File>,
     /// stop flag
     pub stopped: Arc<AtomicBool>,
+    /// worker pool which loads and compresses blocks, `None` means freezing in the caller thread
+    pool: Option<Arc<ThreadPool>>,
 }
 
 impl Freezer {
     /// creates a freezer at specified path
     pub fn open(path: PathBuf) -> Result<Freezer, Error> {
+        Self::open_with_options(path, 1, false)
+    }
+
+    /// creates a freezer at specified path
+    ///
+    /// `threads` bounds the number of threads used to load and compress blocks while freezing,
+    /// and `enable_checksum` stores a checksum alongside every frozen block which is verified on read.
+    pub fn open_with_options(
+        path: PathBuf,
+        threads: usize,
+        enable_checksum: bool,
+    ) -> Result<Freezer, Error> {
         let lock_path = path.join(LOCKNAME);
         let lock = OpenOptions::new()
             .write(true)
@@ -46,7 +65,11 @@ impl Freezer {
             .open(lock_path)
             .map_err(internal_error)?;
         lock.try_lock_exclusive().map_err(internal_error)?;
-        let mut files = FreezerFiles::open(path).map_err(internal_error)?;
+        let mut files = FreezerFilesBuilder::new(path)
+            .enable_checksum(enable_checksum)
+            .build()
+            .map_err(internal_error)?;
+        files.preopen().map_err(internal_error)?;
         let freezer_number = files.number();
 
         let mut tip = None;
@@ -64,62 +87,103 @@ impl Freezer {
             tip = Some(block.header().into_view());
         }
 
+        let pool = if threads > 1 {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|i| format!("FreezerWorker-{}", i))
+                .build()
+                .map_err(internal_error)?;
+            Some(Arc::new(pool))
+        } else {
+            None
+        };
+
         let inner = Inner { files, tip };
         Ok(Freezer {
             number: Arc::clone(&inner.files.number),
             inner: Arc::new(Mutex::new(inner)),
             lock: Arc::new(lock),
             stopped: Arc::new(AtomicBool::new(false)),
+            pool,
         })
     }
 
     /// freeze background process that periodically checks the chain data for any
     /// import progress and moves ancient data from the kv-db into the freezer.
+    ///
+    /// Blocks are loaded and compressed by the worker pool in batches,
+    /// then appended into the files in order.
     pub fn freeze<F>(
         &self,
         threshold: BlockNumber,
         get_block_by_number: F,
     ) -> Result<FreezeResult, Error>
     where
-        F: Fn(BlockNumber) -> Option<BlockView>,
+        F: Fn(BlockNumber) -> Option<BlockView> + Sync,
     {
         let number = self.number();
         let mut guard = self.inner.lock();
         let mut ret = BTreeMap::new();
         ckb_logger::trace!("freezer freeze start {} threshold {}", number, threshold);
 
-        for number in number..threshold {
-            if self.stopped.load(Ordering::SeqCst) {
-                guard.files.sync_all().map_err(internal_error)?;
-                return Ok(ret);
-            }
+        let enable_compression = guard.files.enable_compression;
+        let prepare_block = |number: &BlockNumber| {
+            get_block_by_number(*number).map(|block| {
+                let data = helper::prepare(enable_compression, block.data().as_slice())
+                    .map(Cow::into_owned);
+                (block, data)
+            })
+        };
+        let batch_size = self
+            .pool
+            .as_ref()
+            .map(|pool| pool.current_num_threads() * FREEZE_BATCH_PER_THREAD)
+            .unwrap_or(1);
+
+        let mut start = number;
+        while start < threshold {
+            let end = cmp::min(start + batch_size as BlockNumber, threshold);
+            let numbers: Vec<BlockNumber> = (start..end).collect();
+            let prepared: Vec<_> = match self.pool {
+                Some(ref pool) => pool.install(|| numbers.par_iter().map(&prepare_block).collect()),
+                None => numbers.iter().map(&prepare_block).collect(),
+            };
 
-            if let Some(block) = get_block_by_number(number) {
-                if let Some(ref header) = guard.tip {
-                    if header.hash() != block.header().parent_hash() {
-                        return Err(internal_error(format!(
-                            "appending unexpected block expected parent_hash {} have {}",
-                            header.hash(),
-                            block.header().parent_hash()
-                        )));
+            for (number, item) in numbers.into_iter().zip(prepared) {
+                if self.stopped.load(Ordering::SeqCst) {
+                    guard.files.sync_all().map_err(internal_error)?;
+                    return Ok(ret);
+                }
+
+                if let Some((block, data)) = item {
+                    if let Some(ref header) = guard.tip {
+                        if header.hash() != block.header().parent_hash() {
+                            return Err(internal_error(format!(
+                                "appending unexpected block expected parent_hash {} have {}",
+                                header.hash(),
+                                block.header().parent_hash()
+                            )));
+                        }
                     }
+                    let data = data.map_err(internal_error)?;
+                    guard
+                        .files
+                        .append_prepared(number, &data)
+                        .map_err(internal_error)?;
+
+                    ret.insert(
+                        block.header().hash(),
+                        (number, block.transactions().len() as u32),
+                    );
+                    guard.tip = Some(block.header());
+                    ckb_logger::trace!("freezer block append {}", number);
+                } else {
+                    ckb_logger::error!("freezer block missing {}", number);
+                    guard.files.sync_all().map_err(internal_error)?;
+                    return Ok(ret);
                 }
-                let raw_block = block.data();
-                guard
-                    .files
-                    .append(number, raw_block.as_slice())
-                    .map_err(internal_error)?;
-
-                ret.insert(
-                    block.header().hash(),
-                    (number, block.transactions().len() as u32),
-                );
-                guard.tip = Some(block.header());
-                ckb_logger::trace!("freezer block append {}", number);
-            } else {
-                ckb_logger::error!("freezer block missing {}", number);
-                break;
             }
+            start = end;
         }
         guard.files.sync_all().map_err(internal_error)?;
         Ok(ret)
@@ -1,7 +1,9 @@
+use ckb_hash::blake2b_256;
 use ckb_metrics::metrics;
 use fail::fail_point;
 use lru::LruCache;
 use snap::raw::{Decoder as SnappyDecoder, Encoder as SnappyEncoder};
+use std::borrow::Cow;
 use std::convert::TryInto;
 use std::fs::{self, File};
 use std::io::{Error as IoError, ErrorKind as IoErrorKind};
@@ -14,7 +16,9 @@ use std::sync::Arc;
 const MAX_FILE_SIZE: u64 = 2 * 1_000 * 1_000 * 1_000; // 2G
 const OPEN_FILES_LIMIT: usize = 256;
 const INDEX_FILE_NAME: &str = "INDEX";
+pub(crate) const CHECKSUM_FILE_NAME: &str = "CHECKSUM";
 pub(crate) const INDEX_ENTRY_SIZE: u64 = 12;
+pub(crate) const CHECKSUM_ENTRY_SIZE: u64 = 8;
 
 /// File id alias
 pub type FileId = u32;
@@ -59,6 +63,8 @@ pub struct FreezerFiles {
     pub(crate) index: File,
     // enable compression
     pub(crate) enable_compression: bool,
+    // per item checksum file, only present when checksum is enabled
+    pub(crate) checksum: Option<File>,
 }
 
 /// An instance of IndexEntry represents an entry inside of a index files
@@ -121,6 +127,13 @@ impl FreezerFiles {
 
     /// Append item into freezer files
     pub fn append(&mut self, number: u64, input: &[u8]) -> Result<(), IoError> {
+        let data = helper::prepare(self.enable_compression, input)?;
+        self.append_prepared(number, &data)
+    }
+
+    /// Append item which is already prepared by `helper::prepare`,
+    /// this allows the costly compression to be done outside of the freezer lock.
+    pub(crate) fn append_prepared(&mut self, number: u64, data: &[u8]) -> Result<(), IoError> {
         let expected = self.number.load(Ordering::SeqCst);
         fail_point!("append-unexpected-number");
         if expected != number {
@@ -133,17 +146,6 @@ impl FreezerFiles {
             ));
         }
 
-        // https://github.com/rust-lang/rust/issues/49171
-        #[allow(unused_mut)]
-        let mut compressed_data;
-        let mut data = input;
-        if self.enable_compression {
-            compressed_data = SnappyEncoder::new()
-                .compress_vec(data)
-                .map_err(|e| IoError::new(IoErrorKind::Other, format!("compress error {}", e)))?;
-            data = &compressed_data;
-        };
-
         let data_size = data.len();
         // open a new file
         if self.head.bytes + data_size as u64 > self.max_size {
@@ -161,6 +163,7 @@ impl FreezerFiles {
 
         self.head.write(data)?;
         self.write_index(self.head_id, self.head.bytes)?;
+        self.write_checksum(number, data)?;
         self.number.fetch_add(1, Ordering::SeqCst);
 
         //Gauge for tracking the size of all frozen data
@@ -176,6 +179,9 @@ impl FreezerFiles {
     pub fn sync_all(&self) -> Result<(), IoError> {
         self.head.file.sync_all()?;
         self.index.sync_all()?;
+        if let Some(ref checksum) = self.checksum {
+            checksum.sync_all()?;
+        }
         Ok(())
     }
 
@@ -204,6 +210,8 @@ impl FreezerFiles {
             file.seek(SeekFrom::Start(start_offset))?;
             file.read_exact(&mut data)?;
 
+            self.verify_checksum(item, &data)?;
+
             if self.enable_compression {
                 data = SnappyDecoder::new().decompress_vec(&data).map_err(|e| {
                     IoError::new(
@@ -296,6 +304,9 @@ impl FreezerFiles {
         }
         helper::truncate_file(&mut self.head.file, new_index.offset)?;
         self.head.bytes = new_index.offset;
+        if let Some(ref mut checksum) = self.checksum {
+            helper::truncate_file(checksum, (item + 1) * CHECKSUM_ENTRY_SIZE)?;
+        }
         self.number.store(item + 1, Ordering::SeqCst);
         Ok(())
     }
@@ -319,6 +330,44 @@ impl FreezerFiles {
         Ok(())
     }
 
+    fn write_checksum(&mut self, item: u64, data: &[u8]) -> Result<(), IoError> {
+        if let Some(ref mut checksum) = self.checksum {
+            checksum.seek(SeekFrom::Start(item * CHECKSUM_ENTRY_SIZE))?;
+            checksum.write_all(&helper::checksum(data).to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    // Entries filled with zero were written before checksum was enabled, skip them.
+    // The checksum file is resized to the index on open, a missing entry means it is corrupted.
+    fn verify_checksum(&self, item: u64, data: &[u8]) -> Result<(), IoError> {
+        if let Some(mut checksum) = self.checksum.as_ref() {
+            let mut buffer = [0; CHECKSUM_ENTRY_SIZE as usize];
+            checksum.seek(SeekFrom::Start(item * CHECKSUM_ENTRY_SIZE))?;
+            if let Err(e) = checksum.read_exact(&mut buffer) {
+                if e.kind() != IoErrorKind::UnexpectedEof {
+                    return Err(e);
+                }
+                // Counter for tracking detected corruptions of frozen data
+                metrics!(counter, "ckb-freezer.corruption", 1);
+                return Err(IoError::new(
+                    IoErrorKind::InvalidData,
+                    format!("freezer item {} checksum missing", item),
+                ));
+            }
+            let expected = u64::from_le_bytes(buffer);
+            if expected != 0 && expected != helper::checksum(data) {
+                // Counter for tracking detected corruptions of frozen data
+                metrics!(counter, "ckb-freezer.corruption", 1);
+                return Err(IoError::new(
+                    IoErrorKind::InvalidData,
+                    format!("freezer item {} checksum mismatch", item),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn release(&mut self, id: FileId) {
         self.files.pop(&id);
     }
@@ -384,6 +433,7 @@ pub struct FreezerFilesBuilder {
     file_path: PathBuf,
     max_file_size: u64,
     enable_compression: bool,
+    enable_checksum: bool,
     open_files_limit: usize,
 }
 
@@ -394,6 +444,7 @@ impl FreezerFilesBuilder {
             file_path,
             max_file_size: MAX_FILE_SIZE,
             enable_compression: true,
+            enable_checksum: false,
             open_files_limit: OPEN_FILES_LIMIT,
         }
     }
@@ -424,6 +475,12 @@ impl FreezerFilesBuilder {
         self
     }
 
+    /// Sets whether to store a checksum for every item and verify it on read.
+    pub fn enable_checksum(mut self, enable_checksum: bool) -> Self {
+        self.enable_checksum = enable_checksum;
+        self
+    }
+
     /// Creates the freezer with the options configured in this builder.
     pub fn build(self) -> Result<FreezerFiles, IoError> {
         fs::create_dir_all(&self.file_path)?;
@@ -490,6 +547,11 @@ impl FreezerFilesBuilder {
         index.sync_all()?;
 
         let number = index_size / INDEX_ENTRY_SIZE;
+        let checksum = if self.enable_checksum {
+            Some(self.open_checksum(number)?)
+        } else {
+            None
+        };
 
         Ok(FreezerFiles {
             files: LruCache::new(self.open_files_limit),
@@ -501,6 +563,7 @@ impl FreezerFilesBuilder {
             file_path: self.file_path,
             index,
             enable_compression: self.enable_compression,
+            checksum,
         })
     }
 
@@ -519,6 +582,19 @@ impl FreezerFilesBuilder {
         Ok((file, offset))
     }
 
+    // Keep the checksum file in sync with the index, items frozen before the checksum
+    // was enabled are filled with zero which means unknown.
+    fn open_checksum(&self, number: u64) -> Result<File, IoError> {
+        let (mut checksum, size) = self.open_append(self.file_path.join(CHECKSUM_FILE_NAME))?;
+        let expected = number * CHECKSUM_ENTRY_SIZE;
+        if size != expected {
+            ckb_logger::warn!("Resizing freezer checksum {} {}", size, expected);
+            helper::truncate_file(&mut checksum, expected)?;
+            checksum.sync_all()?;
+        }
+        Ok(checksum)
+    }
+
     fn open_index(&self) -> Result<(File, u64), IoError> {
         let (mut index, mut size) = self.open_append(self.file_path.join(INDEX_FILE_NAME))?;
         // fill a default entry within empty index
@@ -550,4 +626,24 @@ pub(crate) mod helper {
     pub(crate) fn file_name(file_id: FileId) -> String {
         format!("blk{:06}", file_id)
     }
+
+    /// Compresses the item if required, the result is what will be written into data files.
+    pub(crate) fn prepare(enable_compression: bool, input: &[u8]) -> Result<Cow<[u8]>, IoError> {
+        if enable_compression {
+            SnappyEncoder::new()
+                .compress_vec(input)
+                .map(Cow::Owned)
+                .map_err(|e| IoError::new(IoErrorKind::Other, format!("compress error {}", e)))
+        } else {
+            Ok(Cow::Borrowed(input))
+        }
+    }
+
+    /// Checksum of the stored item, zero is reserved for the missing checksum.
+    pub(crate) fn checksum(data: &[u8]) -> u64 {
+        let hash = blake2b_256(data);
+        let mut buffer = [0u8; CHECKSUM_ENTRY_SIZE as usize];
+        buffer.copy_from_slice(&hash[..CHECKSUM_ENTRY_SIZE as usize]);
+        u64::from_le_bytes(buffer).max(1)
+    }
 }
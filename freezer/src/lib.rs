@@ -2,6 +2,7 @@
 
 mod freezer;
 mod freezer_files;
+mod relocate;
 #[cfg(test)]
 mod tests;
 
@@ -14,3 +15,4 @@ fn internal_error<S: Display + Debug + Sync + Send + 'static>(reason: S) -> Erro
 
 pub use freezer::Freezer;
 pub use freezer_files::FreezerFilesBuilder;
+pub use relocate::relocate;
@@ -0,0 +1,153 @@
+//! Relocates the freezer files of a stopped node to another directory.
+use crate::freezer::LOCKNAME;
+use crate::internal_error;
+use ckb_error::Error;
+use ckb_hash::new_blake2b;
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Moves the freezer at `from` to the directory `to`.
+///
+/// Every file is copied and then compared with the original one by the blake2b checksum, the
+/// original files are removed only after all the files are verified. The freezer must not be
+/// opened by any process, and `to` must either not exist or be an empty directory outside `from`.
+///
+/// Returns the total number of bytes moved.
+pub fn relocate(from: &Path, to: &Path) -> Result<u64, Error> {
+    if !from.join(LOCKNAME).exists() {
+        return Err(internal_error(format!(
+            "{} is not a freezer directory",
+            from.display()
+        )));
+    }
+    let lock = OpenOptions::new()
+        .write(true)
+        .open(from.join(LOCKNAME))
+        .map_err(internal_error)?;
+    lock.try_lock_exclusive().map_err(|err| {
+        internal_error(format!(
+            "failed to lock {}, the freezer is in use: {}",
+            from.display(),
+            err
+        ))
+    })?;
+
+    let from = from.canonicalize().map_err(internal_error)?;
+    let to = absolute(to)?;
+    if to.starts_with(&from) || from.starts_with(&to) {
+        return Err(internal_error(format!(
+            "the target {} overlaps with the freezer directory {}",
+            to.display(),
+            from.display()
+        )));
+    }
+    if to.exists() {
+        if !to.is_dir() || fs::read_dir(&to).map_err(internal_error)?.next().is_some() {
+            return Err(internal_error(format!(
+                "the target {} is not an empty directory",
+                to.display()
+            )));
+        }
+    } else {
+        fs::create_dir_all(&to).map_err(internal_error)?;
+    }
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(&from).map_err(internal_error)? {
+        let entry = entry.map_err(internal_error)?;
+        if !entry.file_type().map_err(internal_error)?.is_file() {
+            return Err(internal_error(format!(
+                "unexpected entry {} in the freezer directory",
+                entry.path().display()
+            )));
+        }
+        if entry.file_name() != LOCKNAME {
+            names.push(entry.file_name());
+        }
+    }
+
+    let mut moved = 0;
+    for name in &names {
+        let (size, checksum) = copy_with_checksum(&from.join(name), &to.join(name))?;
+        if checksum != file_checksum(&to.join(name))? {
+            return Err(internal_error(format!(
+                "checksum mismatch after copying {}",
+                from.join(name).display()
+            )));
+        }
+        moved += size;
+    }
+    File::create(to.join(LOCKNAME)).map_err(internal_error)?;
+    File::open(&to)
+        .and_then(|dir| dir.sync_all())
+        .map_err(internal_error)?;
+
+    for name in &names {
+        fs::remove_file(from.join(name)).map_err(internal_error)?;
+    }
+    drop(lock);
+    fs::remove_file(from.join(LOCKNAME)).map_err(internal_error)?;
+    // Leaves the directory in place if it contains something else.
+    let _ = fs::remove_dir(&from);
+
+    Ok(moved)
+}
+
+// Resolves the path which may not exist yet against its nearest existing ancestor.
+fn absolute(path: &Path) -> Result<PathBuf, Error> {
+    if path.exists() {
+        return path.canonicalize().map_err(internal_error);
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            Ok(absolute(parent)?.join(name))
+        }
+        (_, Some(name)) => Ok(std::env::current_dir().map_err(internal_error)?.join(name)),
+        _ => Err(internal_error(format!("invalid path {}", path.display()))),
+    }
+}
+
+fn copy_with_checksum(from: &Path, to: &Path) -> Result<(u64, [u8; 32]), Error> {
+    let mut source = File::open(from).map_err(internal_error)?;
+    let mut target = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)
+        .map_err(internal_error)?;
+    let mut hasher = new_blake2b();
+    let mut buffer = vec![0; COPY_BUFFER_SIZE];
+    let mut size = 0;
+    loop {
+        let n = source.read(&mut buffer).map_err(internal_error)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        target.write_all(&buffer[..n]).map_err(internal_error)?;
+        size += n as u64;
+    }
+    target.sync_all().map_err(internal_error)?;
+    let mut checksum = [0u8; 32];
+    hasher.finalize(&mut checksum);
+    Ok((size, checksum))
+}
+
+fn file_checksum(path: &Path) -> Result<[u8; 32], Error> {
+    let mut file = File::open(path).map_err(internal_error)?;
+    let mut hasher = new_blake2b();
+    let mut buffer = vec![0; COPY_BUFFER_SIZE];
+    loop {
+        let n = file.read(&mut buffer).map_err(internal_error)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    let mut checksum = [0u8; 32];
+    hasher.finalize(&mut checksum);
+    Ok(checksum)
+}
@@ -1,5 +1,14 @@
-use crate::freezer_files::helper::truncate_file;
-use crate::freezer_files::{FreezerFilesBuilder, INDEX_ENTRY_SIZE};
+use crate::freezer_files::helper::{file_name, truncate_file};
+use crate::freezer_files::{
+    FreezerFilesBuilder, CHECKSUM_ENTRY_SIZE, CHECKSUM_FILE_NAME, INDEX_ENTRY_SIZE,
+};
+use crate::Freezer;
+use ckb_types::{
+    core::{BlockBuilder, BlockNumber, BlockView},
+    prelude::*,
+};
+use std::fs::OpenOptions;
+use std::io::{Seek, SeekFrom, Write};
 
 fn make_bytes(size: usize, byte: u8) -> Vec<u8> {
     let mut ret = Vec::with_capacity(size);
@@ -285,3 +294,177 @@ fn open_files_limit_retrieve() {
         assert_eq!(Some(expect), actual);
     }
 }
+
+#[test]
+fn checksum_detects_corruption() {
+    let tempdir = tempfile::Builder::new().tempdir().unwrap();
+
+    {
+        let mut freezer = FreezerFilesBuilder::new(tempdir.path().to_path_buf())
+            .enable_compression(false)
+            .enable_checksum(true)
+            .max_file_size(50)
+            .build()
+            .unwrap();
+        freezer.preopen().unwrap();
+        for i in 1..10 {
+            let data = make_bytes(15, i);
+            freezer.append(i.into(), &data).unwrap();
+        }
+    }
+
+    // flip the first byte of item 1
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(tempdir.path().join(file_name(0)))
+        .unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.write_all(&[0xff]).unwrap();
+    file.sync_all().unwrap();
+
+    let mut freezer = FreezerFilesBuilder::new(tempdir.path().to_path_buf())
+        .enable_compression(false)
+        .enable_checksum(true)
+        .max_file_size(50)
+        .build()
+        .unwrap();
+    freezer.preopen().unwrap();
+
+    assert!(freezer.retrieve(1).is_err());
+    assert_eq!(freezer.retrieve(2).unwrap(), Some(make_bytes(15, 2)));
+}
+
+#[test]
+fn checksum_missing_entry() {
+    let tempdir = tempfile::Builder::new().tempdir().unwrap();
+
+    let mut freezer = FreezerFilesBuilder::new(tempdir.path().to_path_buf())
+        .enable_checksum(true)
+        .max_file_size(50)
+        .build()
+        .unwrap();
+    freezer.preopen().unwrap();
+    for i in 1..10 {
+        let data = make_bytes(15, i);
+        freezer.append(i.into(), &data).unwrap();
+    }
+
+    // cut the checksum entries from item 5 while the freezer is open
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(tempdir.path().join(CHECKSUM_FILE_NAME))
+        .unwrap();
+    truncate_file(&mut file, 5 * CHECKSUM_ENTRY_SIZE).unwrap();
+
+    assert_eq!(freezer.retrieve(4).unwrap(), Some(make_bytes(15, 4)));
+    assert!(freezer.retrieve(5).is_err());
+}
+
+#[test]
+fn checksum_enabled_on_existing_data() {
+    let tempdir = tempfile::Builder::new().tempdir().unwrap();
+
+    {
+        let mut freezer = FreezerFilesBuilder::new(tempdir.path().to_path_buf())
+            .max_file_size(50)
+            .build()
+            .unwrap();
+        freezer.preopen().unwrap();
+        for i in 1..10 {
+            let data = make_bytes(15, i);
+            freezer.append(i.into(), &data).unwrap();
+        }
+    }
+
+    let mut freezer = FreezerFilesBuilder::new(tempdir.path().to_path_buf())
+        .enable_checksum(true)
+        .max_file_size(50)
+        .build()
+        .unwrap();
+    freezer.preopen().unwrap();
+    for i in 10..20 {
+        let data = make_bytes(15, i);
+        freezer.append(i.into(), &data).unwrap();
+    }
+
+    for i in 1..20 {
+        let expect = make_bytes(15, i);
+        let actual = freezer.retrieve(i.into()).unwrap();
+        assert_eq!(Some(expect), actual);
+    }
+}
+
+#[test]
+fn relocate() {
+    let tempdir = tempfile::Builder::new().tempdir().unwrap();
+    let source = tempdir.path().join("ancient");
+    let target = tempdir.path().join("moved");
+    std::fs::create_dir_all(&source).unwrap();
+
+    {
+        // creates the lock file and keeps it locked
+        let _freezer = crate::Freezer::open(source.clone()).unwrap();
+        assert!(crate::relocate(&source, &target).is_err());
+    }
+    {
+        let mut freezer = FreezerFilesBuilder::new(source.clone())
+            .max_file_size(50)
+            .build()
+            .unwrap();
+        freezer.preopen().unwrap();
+        for i in 1..20 {
+            let data = make_bytes(15, i);
+            freezer.append(i.into(), &data).unwrap();
+        }
+    }
+
+    // the target must not be inside the source
+    assert!(crate::relocate(&source, &source.join("nested")).is_err());
+    assert!(!source.join("nested").exists());
+
+    assert!(crate::relocate(&source, &target).unwrap() > 0);
+    assert!(!source.exists());
+
+    let mut freezer = FreezerFilesBuilder::new(target)
+        .max_file_size(50)
+        .build()
+        .unwrap();
+    freezer.preopen().unwrap();
+    for i in 1..20 {
+        let expect = make_bytes(15, i);
+        let actual = freezer.retrieve(i.into()).unwrap();
+        assert_eq!(Some(expect), actual);
+    }
+}
+
+#[test]
+fn freeze_with_worker_threads() {
+    let tempdir = tempfile::Builder::new().tempdir().unwrap();
+
+    let mut blocks: Vec<BlockView> = vec![BlockBuilder::default().build()];
+    // spans several batches of the worker pool
+    for number in 1..300u64 {
+        let parent = blocks.last().unwrap();
+        let block = BlockBuilder::default()
+            .parent_hash(parent.hash())
+            .number(number.pack())
+            .build();
+        blocks.push(block);
+    }
+
+    {
+        let freezer = Freezer::open_with_options(tempdir.path().to_path_buf(), 4, true).unwrap();
+        freezer
+            .freeze(blocks.len() as BlockNumber, |number| {
+                blocks.get(number as usize).cloned()
+            })
+            .unwrap();
+        assert_eq!(freezer.number(), blocks.len() as BlockNumber);
+    }
+
+    let freezer = Freezer::open_with_options(tempdir.path().to_path_buf(), 4, true).unwrap();
+    for (number, block) in blocks.iter().enumerate().skip(1) {
+        let raw_block = freezer.retrieve(number as BlockNumber).unwrap();
+        assert_eq!(raw_block, Some(block.data().as_slice().to_vec()));
+    }
+}
@@ -0,0 +1,17 @@
+#![no_main]
+use ckb_network::{decode_guard::check_message, SupportProtocols};
+use ckb_types::{packed, prelude::*};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let guarded = check_message(&SupportProtocols::Ping, data);
+    // the guard never rejects a message without the extra fields
+    if packed::PingMessageReader::verify(data, false).is_ok() {
+        assert!(guarded.is_ok(), "{:?}", guarded);
+    }
+    if guarded.is_ok() {
+        if let Ok(reader) = packed::PingMessageReader::from_compatible_slice(data) {
+            let _ = reader.to_entity();
+        }
+    }
+});
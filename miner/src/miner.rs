@@ -1,5 +1,5 @@
 use crate::client::Client;
-use crate::worker::{start_worker, WorkerController, WorkerMessage};
+use crate::worker::{nonce_ranges, start_worker, HashRates, WorkerController, WorkerMessage};
 use crate::Work;
 use ckb_app_config::MinerWorkerConfig;
 use ckb_channel::{select, unbounded, Receiver};
@@ -39,6 +39,8 @@ pub struct Miner {
     pub stderr_is_tty: bool,
     /// TODO(doc): @quake
     pub limit: u128,
+    /// The hash rates reported by the workers.
+    pub hash_rates: HashRates,
 }
 
 impl Miner {
@@ -53,9 +55,23 @@ impl Miner {
         let (nonce_tx, nonce_rx) = unbounded();
         let mp = MultiProgress::new();
 
+        let hash_rates = HashRates::default();
+
         let worker_controllers = workers
             .iter()
-            .map(|config| start_worker(Arc::clone(&pow), config, nonce_tx.clone(), &mp))
+            .zip(nonce_ranges(workers))
+            .enumerate()
+            .map(|(index, (config, nonce_range))| {
+                start_worker(
+                    Arc::clone(&pow),
+                    index,
+                    config,
+                    nonce_range,
+                    nonce_tx.clone(),
+                    hash_rates.clone(),
+                    &mp,
+                )
+            })
             .collect();
 
         let pb = mp.add(ProgressBar::new(100));
@@ -78,6 +94,7 @@ impl Miner {
             pb,
             stderr_is_tty,
             limit,
+            hash_rates,
         }
     }
 
@@ -144,8 +161,11 @@ impl Miner {
                 self.nonces_found += 1;
                 self.pb
                     .println(format!("Found! #{} {:#x}", block.number(), block_hash));
-                self.pb
-                    .set_message(format!("Total nonces found: {:>3}", self.nonces_found));
+                self.pb.set_message(format!(
+                    "Total nonces found: {:>3} / total hash rate: {:>10.3}",
+                    self.nonces_found,
+                    self.hash_rates.total()
+                ));
                 self.pb.inc(1);
             }
         }
@@ -1,4 +1,4 @@
-use super::{Worker, WorkerMessage};
+use super::{HashRates, Worker, WorkerMessage};
 use ckb_app_config::ExtraHashFunction;
 use ckb_channel::{Receiver, Sender};
 use ckb_hash::blake2b_256;
@@ -11,6 +11,7 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 pub struct EaglesongSimple {
+    name: String,
     start: bool,
     pow_hash: Option<Byte32>,
     target: U256,
@@ -18,15 +19,19 @@ pub struct EaglesongSimple {
     worker_rx: Receiver<WorkerMessage>,
     nonces_found: u128,
     pub(crate) extra_hash_function: Option<ExtraHashFunction>,
+    hash_rates: HashRates,
 }
 
 impl EaglesongSimple {
     pub fn new(
+        name: String,
         nonce_tx: Sender<(Byte32, u128)>,
         worker_rx: Receiver<WorkerMessage>,
         extra_hash_function: Option<ExtraHashFunction>,
+        hash_rates: HashRates,
     ) -> Self {
         Self {
+            name,
             start: true,
             pow_hash: None,
             target: U256::zero(),
@@ -34,6 +39,7 @@ impl EaglesongSimple {
             worker_rx,
             nonces_found: 0,
             extra_hash_function,
+            hash_rates,
         }
     }
 
@@ -97,10 +103,11 @@ impl Worker for EaglesongSimple {
                             + u64::from(elapsed.subsec_nanos()))
                             as f64
                             / 1_000_000_000.0;
+                        let hash_rate = state_update_counter as f64 / elapsed_nanos;
+                        self.hash_rates.report(&self.name, hash_rate);
                         progress_bar.set_message(format!(
                             "hash rate: {:>10.3} / nonces found: {:>10}",
-                            state_update_counter as f64 / elapsed_nanos,
-                            self.nonces_found,
+                            hash_rate, self.nonces_found,
                         ));
                         progress_bar.inc(1);
                         state_update_counter = 0;
@@ -0,0 +1,167 @@
+use super::{HashRates, WorkerMessage};
+use ckb_app_config::ExternalWorkerConfig;
+use ckb_channel::{Receiver, Sender};
+use ckb_jsonrpc_types::Uint128;
+use ckb_logger::{debug, error};
+use ckb_types::{packed::Byte32, prelude::*, H256, U256};
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::ops::Range;
+use std::process::{ChildStdin, ChildStdout, Command, Stdio};
+use std::thread;
+
+// The requests written to the stdin of the worker process, one JSON per line
+#[derive(Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum Request {
+    NewWork {
+        pow_hash: H256,
+        target: U256,
+        nonce_start: Uint128,
+        nonce_end: Uint128,
+    },
+    Stop,
+    Start,
+}
+
+// The responses read from the stdout of the worker process, one JSON per line
+#[derive(Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum Response {
+    Nonce { pow_hash: H256, nonce: Uint128 },
+    Hashrate { value: f64 },
+}
+
+/// Worker running in an external process, such as a GPU miner.
+pub struct External {
+    name: String,
+    nonce_range: Range<u128>,
+    nonce_tx: Sender<(Byte32, u128)>,
+    worker_rx: Receiver<WorkerMessage>,
+    hash_rates: HashRates,
+}
+
+impl External {
+    pub fn new(
+        name: String,
+        nonce_range: Range<u128>,
+        nonce_tx: Sender<(Byte32, u128)>,
+        worker_rx: Receiver<WorkerMessage>,
+        hash_rates: HashRates,
+    ) -> Self {
+        Self {
+            name,
+            nonce_range,
+            nonce_tx,
+            worker_rx,
+            hash_rates,
+        }
+    }
+
+    /// Starts the worker process, and the threads forwarding the requests and the responses.
+    pub fn spawn(self, config: &ExternalWorkerConfig, progress_bar: ProgressBar) -> io::Result<()> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("piped stdin");
+        let stdout = child.stdout.take().expect("piped stdout");
+
+        let External {
+            name,
+            nonce_range,
+            nonce_tx,
+            worker_rx,
+            hash_rates,
+        } = self;
+        {
+            let name = name.clone();
+            thread::Builder::new()
+                .name(format!("{}-requests", name))
+                .spawn(move || forward_requests(&name, stdin, &worker_rx, &nonce_range))?;
+        }
+        thread::Builder::new()
+            .name(format!("{}-responses", name))
+            .spawn(move || {
+                read_responses(&name, stdout, &nonce_tx, &hash_rates, &progress_bar);
+                match child.wait() {
+                    Ok(status) => error!("external worker {} exited with {}", name, status),
+                    Err(err) => error!("external worker {} wait error {:?}", name, err),
+                }
+            })?;
+        Ok(())
+    }
+}
+
+fn forward_requests(
+    name: &str,
+    mut stdin: ChildStdin,
+    worker_rx: &Receiver<WorkerMessage>,
+    nonce_range: &Range<u128>,
+) {
+    for message in worker_rx.iter() {
+        let request = match message {
+            WorkerMessage::NewWork { pow_hash, target } => Request::NewWork {
+                pow_hash: pow_hash.unpack(),
+                target,
+                nonce_start: nonce_range.start.into(),
+                nonce_end: nonce_range.end.into(),
+            },
+            WorkerMessage::Stop => Request::Stop,
+            WorkerMessage::Start => Request::Start,
+        };
+        let line = serde_json::to_string(&request).expect("serialize the worker request");
+        if let Err(err) = writeln!(stdin, "{}", line).and_then(|_| stdin.flush()) {
+            error!("external worker {} write error {:?}", name, err);
+            break;
+        }
+    }
+}
+
+fn read_responses(
+    name: &str,
+    stdout: ChildStdout,
+    nonce_tx: &Sender<(Byte32, u128)>,
+    hash_rates: &HashRates,
+    progress_bar: &ProgressBar,
+) {
+    let mut nonces_found = 0u128;
+    let mut hash_rate = 0f64;
+    for line in BufReader::new(stdout).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                error!("external worker {} read error {:?}", name, err);
+                break;
+            }
+        };
+        match serde_json::from_str::<Response>(&line) {
+            Ok(Response::Nonce { pow_hash, nonce }) => {
+                let nonce: u128 = nonce.into();
+                debug!(
+                    "send new found nonce, pow_hash {:#x}, nonce {:?}",
+                    pow_hash, nonce
+                );
+                if let Err(err) = nonce_tx.send((pow_hash.pack(), nonce)) {
+                    error!("nonce_tx send error {:?}", err);
+                }
+                nonces_found += 1;
+            }
+            Ok(Response::Hashrate { value }) => {
+                hash_rate = value;
+                hash_rates.report(name, value);
+            }
+            Err(err) => {
+                debug!("external worker {} output {}: {}", name, line, err);
+                continue;
+            }
+        }
+        progress_bar.set_message(format!(
+            "hash rate: {:>10.3} / nonces found: {:>10}",
+            hash_rate, nonces_found,
+        ));
+        progress_bar.inc(1);
+    }
+}
@@ -0,0 +1,28 @@
+use ckb_metrics::metrics;
+use ckb_util::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The hash rates reported by the workers, in hashes per second.
+///
+/// Each report also updates the gauges `ckb-miner.hash_rate` of the worker and of the total.
+#[derive(Clone, Default)]
+pub struct HashRates {
+    inner: Arc<Mutex<HashMap<String, f64>>>,
+}
+
+impl HashRates {
+    pub fn report(&self, worker: &str, hash_rate: f64) {
+        let total = {
+            let mut rates = self.inner.lock();
+            rates.insert(worker.to_owned(), hash_rate);
+            rates.values().sum::<f64>()
+        };
+        metrics!(gauge, "ckb-miner.hash_rate", hash_rate as i64, "worker" => worker.to_owned());
+        metrics!(gauge, "ckb-miner.hash_rate", total as i64, "worker" => "total");
+    }
+
+    pub fn total(&self) -> f64 {
+        self.inner.lock().values().sum()
+    }
+}
@@ -1,5 +1,7 @@
 mod dummy;
 mod eaglesong_simple;
+mod external;
+mod hash_rate;
 
 use ckb_app_config::MinerWorkerConfig;
 use ckb_channel::{unbounded, Sender};
@@ -8,12 +10,15 @@ use ckb_pow::{DummyPowEngine, EaglesongBlake2bPowEngine, EaglesongPowEngine, Pow
 use ckb_types::{packed::Byte32, U256};
 use dummy::Dummy;
 use eaglesong_simple::EaglesongSimple;
+use external::External;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use rand::{random, Rng};
 use std::ops::Range;
 use std::sync::Arc;
 use std::thread;
 
+pub use hash_rate::HashRates;
+
 #[derive(Clone)]
 pub enum WorkerMessage {
     Stop,
@@ -39,17 +44,51 @@ impl WorkerController {
     }
 }
 
-fn partition_nonce(id: u128, total: u128) -> Range<u128> {
-    let span = u128::max_value() / total;
-    let start = span * id;
+fn partition_nonce(range: &Range<u128>, id: u128, total: u128) -> Range<u128> {
+    let span = (range.end - range.start) / total;
+    let start = range.start + span * id;
     let end = match id {
         x if x < total - 1 => start + span,
-        x if x == total - 1 => u128::max_value(),
+        x if x == total - 1 => range.end,
         _ => unreachable!(),
     };
     Range { start, end }
 }
 
+// The number of nonce partitions a worker takes, one for each of its threads.
+fn worker_slots(config: &MinerWorkerConfig) -> u128 {
+    match config {
+        MinerWorkerConfig::Dummy(_) | MinerWorkerConfig::External(_) => 1,
+        MinerWorkerConfig::EaglesongSimple(config) => config.threads.max(1) as u128,
+    }
+}
+
+/// Splits the nonce space into disjoint ranges, one for each worker.
+///
+/// The workers never search the same nonce for the same work, even when they run on different
+/// devices.
+pub fn nonce_ranges(workers: &[MinerWorkerConfig]) -> Vec<Range<u128>> {
+    let full = Range {
+        start: 0,
+        end: u128::max_value(),
+    };
+    let total: u128 = workers.iter().map(worker_slots).sum();
+    let mut slot = 0;
+    workers
+        .iter()
+        .map(|config| {
+            let slots = worker_slots(config);
+            let first = partition_nonce(&full, slot, total);
+            let last = partition_nonce(&full, slot + slots - 1, total);
+            slot += slots;
+            Range {
+                start: first.start,
+                end: last.end,
+            }
+        })
+        .collect()
+}
+
 fn nonce_generator(range: Range<u128>) -> impl FnMut() -> u128 {
     let mut rng = rand::thread_rng();
     let Range { start, end } = range;
@@ -58,26 +97,33 @@ fn nonce_generator(range: Range<u128>) -> impl FnMut() -> u128 {
 
 const PROGRESS_BAR_TEMPLATE: &str = "{prefix:.bold.dim} {spinner:.green} [{elapsed_precise}] {msg}";
 
+/// Starts the worker of the config at `index` in the config list.
+///
+/// The worker names are prefixed with the index, because the configs may have the same type
+/// and the same name.
 pub fn start_worker(
     pow: Arc<dyn PowEngine>,
+    index: usize,
     config: &MinerWorkerConfig,
+    nonce_range: Range<u128>,
     nonce_tx: Sender<(Byte32, u128)>,
+    hash_rates: HashRates,
     mp: &MultiProgress,
 ) -> WorkerController {
     match config {
         MinerWorkerConfig::Dummy(config) => {
             if pow.as_any().downcast_ref::<DummyPowEngine>().is_some() {
-                let worker_name = "Dummy-Worker";
+                let worker_name = format!("{}-Dummy-Worker", index);
                 let pb = mp.add(ProgressBar::new(100));
                 pb.set_style(ProgressStyle::default_bar().template(PROGRESS_BAR_TEMPLATE));
-                pb.set_prefix(worker_name);
+                pb.set_prefix(worker_name.clone());
 
                 let (worker_tx, worker_rx) = unbounded();
                 let mut worker = Dummy::try_new(config, nonce_tx, worker_rx)
                     .expect("valid distribution parameters");
 
                 thread::Builder::new()
-                    .name(worker_name.to_string())
+                    .name(worker_name)
                     .spawn(move || {
                         let rng = || random();
                         worker.run(rng, pb);
@@ -98,8 +144,9 @@ pub fn start_worker(
             {
                 let worker_txs = (0..config.threads)
                     .map(|i| {
-                        let worker_name = format!("EaglesongSimple-Worker-{}", i);
-                        let nonce_range = partition_nonce(i as u128, config.threads as u128);
+                        let worker_name = format!("{}-EaglesongSimple-Worker-{}", index, i);
+                        let nonce_range =
+                            partition_nonce(&nonce_range, i as u128, config.threads as u128);
                         // `100` is the len of progress bar, we can use any dummy value here,
                         // since we only show the spinner in console.
                         let pb = mp.add(ProgressBar::new(100));
@@ -108,11 +155,17 @@ pub fn start_worker(
 
                         let (worker_tx, worker_rx) = unbounded();
                         let nonce_tx = nonce_tx.clone();
+                        let hash_rates = hash_rates.clone();
                         thread::Builder::new()
-                            .name(worker_name)
+                            .name(worker_name.clone())
                             .spawn(move || {
-                                let mut worker =
-                                    EaglesongSimple::new(nonce_tx, worker_rx, extra_hash_function);
+                                let mut worker = EaglesongSimple::new(
+                                    worker_name,
+                                    nonce_tx,
+                                    worker_rx,
+                                    extra_hash_function,
+                                    hash_rates,
+                                );
                                 let rng = nonce_generator(nonce_range);
                                 worker.run(rng, pb);
                             })
@@ -126,9 +179,67 @@ pub fn start_worker(
                 panic!("incompatible pow engine and worker type");
             }
         }
+        MinerWorkerConfig::External(config) => {
+            // The external process implements the pow engine itself.
+            let worker_name = format!("{}-External-Worker-{}", index, config.name);
+            let pb = mp.add(ProgressBar::new(100));
+            pb.set_style(ProgressStyle::default_bar().template(PROGRESS_BAR_TEMPLATE));
+            pb.set_prefix(worker_name.clone());
+
+            let (worker_tx, worker_rx) = unbounded();
+            External::new(worker_name, nonce_range, nonce_tx, worker_rx, hash_rates)
+                .spawn(config, pb)
+                .expect("Start `External` worker process failed");
+            WorkerController::new(vec![worker_tx])
+        }
     }
 }
 
 pub trait Worker {
     fn run<G: FnMut() -> u128>(&mut self, rng: G, progress_bar: ProgressBar);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_app_config::{DummyConfig, EaglesongSimpleConfig, ExternalWorkerConfig};
+
+    #[test]
+    fn test_nonce_ranges() {
+        let workers = vec![
+            MinerWorkerConfig::EaglesongSimple(EaglesongSimpleConfig {
+                threads: 3,
+                extra_hash_function: None,
+            }),
+            MinerWorkerConfig::Dummy(DummyConfig::Constant { value: 1 }),
+            MinerWorkerConfig::External(ExternalWorkerConfig {
+                name: "gpu".to_owned(),
+                command: "gpu-miner".to_owned(),
+                args: Vec::new(),
+            }),
+        ];
+        let ranges = nonce_ranges(&workers);
+        assert_eq!(ranges.len(), workers.len());
+
+        // the ranges are contiguous, disjoint and cover the whole nonce space
+        assert_eq!(ranges[0].start, 0);
+        assert_eq!(ranges[2].end, u128::max_value());
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+
+        // a worker with 3 threads takes 3 of the 5 slots
+        let span = u128::max_value() / 5;
+        assert_eq!(ranges[0].end - ranges[0].start, span * 3);
+        assert_eq!(ranges[1].end - ranges[1].start, span);
+
+        // the threads split the range of their worker the same way
+        let threads: Vec<_> = (0..3).map(|i| partition_nonce(&ranges[0], i, 3)).collect();
+        assert_eq!(threads[0].start, ranges[0].start);
+        assert_eq!(threads[2].end, ranges[0].end);
+        for pair in threads.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+            assert_eq!(pair[0].end - pair[0].start, span);
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! The audit stream of the peer connection lifecycle events.
+//!
+//! The events are logged to the target [`AUDIT_LOG_TARGET`] at the debug level, which is not
+//! written by the main logger with the default filter. Configure an extra logger to keep them in
+//! a separate file:
+//!
+//! ```toml
+//! [logger.extra.network-audit]
+//! filter = "ckb-network-audit=debug"
+//! ```
+//!
+//! The events are also sent to the subscribers of
+//! [`NetworkController::subscribe_audit_events`](../network/struct.NetworkController.html#method.subscribe_audit_events).
+use crate::{PeerIndex, ProtocolId};
+use ckb_channel::{bounded, Receiver, Sender, TrySendError};
+use ckb_logger::{debug, debug_target};
+use ckb_util::Mutex;
+use p2p::multiaddr::Multiaddr;
+use std::fmt;
+use std::time::Duration;
+
+/// The log target of the audit events.
+pub const AUDIT_LOG_TARGET: &str = "ckb-network-audit";
+/// The events are dropped for a subscriber when it has so many events not received yet.
+const AUDIT_CHANNEL_SIZE: usize = 1024;
+
+/// A peer connection lifecycle event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerAuditEvent {
+    /// The time when the event happened, in milliseconds.
+    pub timestamp: u64,
+    /// The event.
+    pub kind: PeerAuditEventKind,
+}
+
+/// The kinds of the peer connection lifecycle events.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PeerAuditEventKind {
+    /// Starts dialing the address.
+    Dial {
+        /// The dialed address
+        address: Multiaddr,
+    },
+    /// Dialing the address failed before the handshake.
+    DialFailed {
+        /// The dialed address
+        address: Multiaddr,
+        /// The error
+        reason: String,
+    },
+    /// The secure handshake or the protocol negotiation with the remote peer failed.
+    HandshakeFailed {
+        /// The remote address
+        address: Multiaddr,
+        /// The error
+        reason: String,
+    },
+    /// A session is opened.
+    SessionOpened {
+        /// The session id
+        peer: PeerIndex,
+        /// The remote address
+        address: Multiaddr,
+        /// Whether the session is initiated by the remote peer
+        inbound: bool,
+    },
+    /// The opened session is not accepted by the peer registry and is disconnected.
+    SessionRejected {
+        /// The session id
+        peer: PeerIndex,
+        /// The remote address
+        address: Multiaddr,
+        /// The error
+        reason: String,
+    },
+    /// A session is closed.
+    SessionClosed {
+        /// The session id
+        peer: PeerIndex,
+        /// The remote address
+        address: Multiaddr,
+    },
+    /// A CKB protocol is opened in the session.
+    ProtocolOpened {
+        /// The session id
+        peer: PeerIndex,
+        /// The protocol id
+        protocol: ProtocolId,
+        /// The negotiated protocol version
+        version: String,
+    },
+    /// A CKB protocol is closed in the session.
+    ProtocolClosed {
+        /// The session id
+        peer: PeerIndex,
+        /// The protocol id
+        protocol: ProtocolId,
+    },
+    /// The connected peer is evicted to make room for the others.
+    Evicted {
+        /// The session id
+        peer: PeerIndex,
+        /// The remote address
+        address: Multiaddr,
+        /// Why the peer is evicted
+        reason: String,
+    },
+    /// An address or an IP network is banned.
+    Banned {
+        /// The banned address or IP network
+        address: String,
+        /// How long the ban lasts
+        duration: Duration,
+        /// Why it is banned
+        reason: String,
+    },
+}
+
+impl PeerAuditEventKind {
+    /// The event name in the logs and the RPC.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PeerAuditEventKind::Dial { .. } => "dial",
+            PeerAuditEventKind::DialFailed { .. } => "dial_failed",
+            PeerAuditEventKind::HandshakeFailed { .. } => "handshake_failed",
+            PeerAuditEventKind::SessionOpened { .. } => "session_opened",
+            PeerAuditEventKind::SessionRejected { .. } => "session_rejected",
+            PeerAuditEventKind::SessionClosed { .. } => "session_closed",
+            PeerAuditEventKind::ProtocolOpened { .. } => "protocol_opened",
+            PeerAuditEventKind::ProtocolClosed { .. } => "protocol_closed",
+            PeerAuditEventKind::Evicted { .. } => "evicted",
+            PeerAuditEventKind::Banned { .. } => "banned",
+        }
+    }
+}
+
+// Formats the event as `key=value` pairs, the strings which may contain spaces are quoted.
+impl fmt::Display for PeerAuditEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "event={} ts={}", self.kind.name(), self.timestamp)?;
+        match &self.kind {
+            PeerAuditEventKind::Dial { address } => write!(f, " address={}", address),
+            PeerAuditEventKind::DialFailed { address, reason }
+            | PeerAuditEventKind::HandshakeFailed { address, reason } => {
+                write!(f, " address={} reason={:?}", address, reason)
+            }
+            PeerAuditEventKind::SessionOpened {
+                peer,
+                address,
+                inbound,
+            } => write!(f, " peer={} address={} inbound={}", peer, address, inbound),
+            PeerAuditEventKind::SessionRejected {
+                peer,
+                address,
+                reason,
+            }
+            | PeerAuditEventKind::Evicted {
+                peer,
+                address,
+                reason,
+            } => write!(f, " peer={} address={} reason={:?}", peer, address, reason),
+            PeerAuditEventKind::SessionClosed { peer, address } => {
+                write!(f, " peer={} address={}", peer, address)
+            }
+            PeerAuditEventKind::ProtocolOpened {
+                peer,
+                protocol,
+                version,
+            } => write!(
+                f,
+                " peer={} protocol={} version={}",
+                peer, protocol, version
+            ),
+            PeerAuditEventKind::ProtocolClosed { peer, protocol } => {
+                write!(f, " peer={} protocol={}", peer, protocol)
+            }
+            PeerAuditEventKind::Banned {
+                address,
+                duration,
+                reason,
+            } => write!(
+                f,
+                " address={} duration_secs={} reason={:?}",
+                address,
+                duration.as_secs(),
+                reason
+            ),
+        }
+    }
+}
+
+/// Records the audit events to the log and the subscribers.
+#[derive(Default)]
+pub(crate) struct AuditLog {
+    subscribers: Mutex<Vec<(String, Sender<PeerAuditEvent>)>>,
+}
+
+impl AuditLog {
+    pub(crate) fn new() -> Self {
+        AuditLog::default()
+    }
+
+    /// Subscribes to the events, the subscription is removed once the receiver is dropped.
+    pub(crate) fn subscribe(&self, name: &str) -> Receiver<PeerAuditEvent> {
+        let (sender, receiver) = bounded(AUDIT_CHANNEL_SIZE);
+        debug!("Register peer audit subscriber {:?}", name);
+        self.subscribers.lock().push((name.to_owned(), sender));
+        receiver
+    }
+
+    pub(crate) fn record(&self, kind: PeerAuditEventKind) {
+        let event = PeerAuditEvent {
+            timestamp: faketime::unix_time_as_millis(),
+            kind,
+        };
+        debug_target!(AUDIT_LOG_TARGET, "{}", event);
+
+        let mut subscribers = self.subscribers.lock();
+        if subscribers.is_empty() {
+            return;
+        }
+        // The network threads must not be blocked by a slow subscriber, the event is dropped for
+        // it instead.
+        subscribers.retain(|(name, sender)| match sender.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                debug!("Peer audit subscriber {:?} is full, drop the event", name);
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                debug!("Remove dropped peer audit subscriber {:?}", name);
+                false
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let audit = AuditLog::new();
+        let receiver = audit.subscribe("test");
+        let dropped = audit.subscribe("dropped");
+        drop(dropped);
+
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/8115".parse().unwrap();
+        audit.record(PeerAuditEventKind::Dial {
+            address: address.clone(),
+        });
+        assert_eq!(audit.subscribers.lock().len(), 1);
+
+        let event = receiver.try_recv().expect("recorded");
+        assert_eq!(event.kind, PeerAuditEventKind::Dial { address });
+        assert_eq!(
+            event.to_string(),
+            format!(
+                "event=dial ts={} address=/ip4/127.0.0.1/tcp/8115",
+                event.timestamp
+            )
+        );
+    }
+
+    #[test]
+    fn test_display_banned() {
+        let event = PeerAuditEvent {
+            timestamp: 42,
+            kind: PeerAuditEventKind::Banned {
+                address: "192.168.0.0/24".to_owned(),
+                duration: Duration::from_secs(300),
+                reason: "ProtocolError id=1".to_owned(),
+            },
+        };
+        assert_eq!(
+            event.to_string(),
+            "event=banned ts=42 address=192.168.0.0/24 duration_secs=300 reason=\"ProtocolError id=1\""
+        );
+    }
+}
@@ -4,9 +4,20 @@ use crate::Score;
 /// we maintain a score to each peer
 /// report peer bahaviour will affects peer's score
 ///
-/// Currently this feature is disabled, maybe someday we will add it back or totally remove it.
+/// The protocols report the misbehaviors which are not serious enough to ban the peer at once,
+/// the peer is banned when the accumulated score drops below the ban score.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Behaviour {
+    /// The peer sends a malformed message
+    MalformedMessage,
+    /// The peer sends a message which is not expected, such as a duplicated one
+    UnexpectedMessage,
+    /// The peer sends too many items in a message, such as addresses
+    TooManyItems,
+    /// The peer sends requests faster than the rate limit
+    TooManyRequests,
+    /// The peer does not respond in time
+    Timeout,
     #[cfg(test)]
     TestGood,
     #[cfg(test)]
@@ -16,12 +27,18 @@ pub enum Behaviour {
 impl Behaviour {
     /// Behaviour score
     pub fn score(self) -> Score {
-        #[cfg(test)]
         match self {
+            Behaviour::MalformedMessage => -40,
+            Behaviour::UnexpectedMessage => -20,
+            Behaviour::TooManyItems => -20,
+            // The peers behind the same NAT share the score and the rate limits are tripped
+            // occasionally by honest peers, so it only bans a peer flooding the requests.
+            Behaviour::TooManyRequests => -1,
+            Behaviour::Timeout => -10,
+            #[cfg(test)]
             Behaviour::TestGood => 10,
+            #[cfg(test)]
             Behaviour::TestBad => -10,
         }
-        #[cfg(not(test))]
-        0
     }
 }
@@ -0,0 +1,289 @@
+//! Guards the decoding of the P2P messages.
+//!
+//! The messages of the built-in protocols are molecule tables or unions. Before a message is
+//! verified and parsed by molecule, the guard checks the headers of the outer layout and the
+//! layout nested in it without reading the fields, and bounds the count of the extra fields and
+//! the union item ids, so a malformed message is rejected cheaply before it reaches the handler.
+use crate::protocols::support_protocols::SupportProtocols;
+use crate::ProtocolId;
+use ckb_types::{molecule::NUMBER_SIZE, packed};
+use std::convert::TryInto;
+use std::fmt;
+use std::time::Duration;
+
+/// The ban time of the peers sending the messages rejected by the guard.
+pub const MALFORMED_MESSAGE_BAN_TIME: Duration = Duration::from_secs(5 * 60);
+
+/// The max count of the extra fields appended by the newer versions to a table.
+///
+/// The tables are parsed in the compatible mode, which accepts the unknown extra fields.
+pub const MAX_EXTRA_FIELDS: usize = 8;
+
+/// The max count of fields of the tables nested in a message whose schema is not checked.
+const MAX_NESTED_FIELDS: usize = 32;
+
+/// The reason why a message is rejected by the guard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeGuardError {
+    /// The message is too short to contain the header
+    Truncated,
+    /// The total size in the header does not match the message length
+    SizeMismatch {
+        /// The total size in the header
+        header: usize,
+        /// The message length
+        actual: usize,
+    },
+    /// The field offsets are not ascending or out of the message
+    InvalidOffsets,
+    /// The table has too many fields
+    TooManyFields {
+        /// The field count in the header
+        count: usize,
+        /// The max allowed field count
+        limit: usize,
+    },
+    /// The table has fewer fields than the schema
+    TooFewFields {
+        /// The field count in the header
+        count: usize,
+        /// The field count in the schema
+        expected: usize,
+    },
+    /// The union item id is unknown
+    UnknownItem(u32),
+}
+
+impl fmt::Display for DecodeGuardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeGuardError::Truncated => write!(f, "truncated message"),
+            DecodeGuardError::SizeMismatch { header, actual } => write!(
+                f,
+                "total size {} in header mismatches the message length {}",
+                header, actual
+            ),
+            DecodeGuardError::InvalidOffsets => write!(f, "invalid field offsets"),
+            DecodeGuardError::TooManyFields { count, limit } => {
+                write!(f, "too many fields {}, limit {}", count, limit)
+            }
+            DecodeGuardError::TooFewFields { count, expected } => {
+                write!(f, "too few fields {}, expected {}", count, expected)
+            }
+            DecodeGuardError::UnknownItem(id) => write!(f, "unknown union item {}", id),
+        }
+    }
+}
+
+impl std::error::Error for DecodeGuardError {}
+
+// The outer layout of the message of a protocol.
+enum Layout {
+    // Not a molecule message.
+    Raw,
+    // A table with the field count in the schema.
+    Table(usize),
+    // A table with only one field, which is a union with the item count in the schema.
+    TableOfUnion(usize),
+    // A union with the item count in the schema, the items are tables.
+    Union(usize),
+}
+
+fn layout(protocol: &SupportProtocols) -> Layout {
+    match protocol {
+        SupportProtocols::Ping => Layout::TableOfUnion(packed::PingPayloadReader::ITEMS_COUNT),
+        SupportProtocols::Discovery => {
+            Layout::TableOfUnion(packed::DiscoveryPayloadReader::ITEMS_COUNT)
+        }
+        SupportProtocols::Identify => Layout::Table(packed::IdentifyMessageReader::FIELD_COUNT),
+        SupportProtocols::Feeler | SupportProtocols::DisconnectMessage => Layout::Raw,
+        SupportProtocols::Sync => Layout::Union(packed::SyncMessageReader::ITEMS_COUNT),
+        SupportProtocols::Relay | SupportProtocols::RelayV2 => {
+            Layout::Union(packed::RelayMessageReader::ITEMS_COUNT)
+        }
+        SupportProtocols::Time => Layout::Table(packed::TimeReader::FIELD_COUNT),
+        SupportProtocols::Alert => Layout::Table(packed::AlertReader::FIELD_COUNT),
+    }
+}
+
+/// Returns the built-in protocol of the id, or `None` for the protocols registered by the
+/// downstream builds.
+pub fn support_protocol(id: ProtocolId) -> Option<SupportProtocols> {
+    [
+        SupportProtocols::Ping,
+        SupportProtocols::Discovery,
+        SupportProtocols::Identify,
+        SupportProtocols::Feeler,
+        SupportProtocols::DisconnectMessage,
+        SupportProtocols::Sync,
+        SupportProtocols::Relay,
+        SupportProtocols::Time,
+        SupportProtocols::RelayV2,
+        SupportProtocols::Alert,
+    ]
+    .iter()
+    .find(|protocol| protocol.protocol_id() == id)
+    .cloned()
+}
+
+/// Checks the message of the protocol before it is decoded.
+///
+/// Passing the guard does not mean the message is valid, the message must still be verified by
+/// molecule.
+pub fn check_message(protocol: &SupportProtocols, data: &[u8]) -> Result<(), DecodeGuardError> {
+    match layout(protocol) {
+        Layout::Raw => Ok(()),
+        Layout::Table(fields) => check_table(data, fields, fields + MAX_EXTRA_FIELDS).map(|_| ()),
+        Layout::TableOfUnion(items) => {
+            let payload = check_table(data, 1, 1 + MAX_EXTRA_FIELDS)?;
+            check_union(payload, items)
+        }
+        Layout::Union(items) => check_union(data, items),
+    }
+}
+
+fn read_number(data: &[u8], index: usize) -> Result<usize, DecodeGuardError> {
+    let start = index * NUMBER_SIZE;
+    data.get(start..start + NUMBER_SIZE)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().expect("checked length")) as usize)
+        .ok_or(DecodeGuardError::Truncated)
+}
+
+// Checks the table header and returns the first field.
+fn check_table(data: &[u8], expected: usize, limit: usize) -> Result<&[u8], DecodeGuardError> {
+    let total_size = read_number(data, 0)?;
+    if total_size != data.len() {
+        return Err(DecodeGuardError::SizeMismatch {
+            header: total_size,
+            actual: data.len(),
+        });
+    }
+    let count = if total_size == NUMBER_SIZE {
+        0
+    } else {
+        let first_offset = read_number(data, 1)?;
+        if first_offset % NUMBER_SIZE != 0 || first_offset < NUMBER_SIZE * 2 {
+            return Err(DecodeGuardError::InvalidOffsets);
+        }
+        first_offset / NUMBER_SIZE - 1
+    };
+    if count > limit {
+        return Err(DecodeGuardError::TooManyFields { count, limit });
+    }
+    if count < expected {
+        return Err(DecodeGuardError::TooFewFields { count, expected });
+    }
+    if count == 0 {
+        return Ok(&[]);
+    }
+
+    let mut offsets = Vec::with_capacity(count + 1);
+    for index in 1..=count {
+        offsets.push(read_number(data, index)?);
+    }
+    offsets.push(total_size);
+    if offsets.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(DecodeGuardError::InvalidOffsets);
+    }
+    Ok(&data[offsets[0]..offsets[1]])
+}
+
+// Checks the union item id and the header of the item table.
+fn check_union(data: &[u8], items: usize) -> Result<(), DecodeGuardError> {
+    let item_id = read_number(data, 0)?;
+    if item_id >= items {
+        return Err(DecodeGuardError::UnknownItem(item_id as u32));
+    }
+    check_table(&data[NUMBER_SIZE..], 0, MAX_NESTED_FIELDS).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_types::prelude::*;
+
+    fn ping_message() -> Vec<u8> {
+        let ping = packed::Ping::new_builder().nonce(42u32.pack()).build();
+        let payload = packed::PingPayload::new_builder().set(ping).build();
+        packed::PingMessage::new_builder()
+            .payload(payload)
+            .build()
+            .as_slice()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_valid_messages() {
+        assert_eq!(
+            check_message(&SupportProtocols::Ping, &ping_message()),
+            Ok(())
+        );
+
+        let time = packed::Time::new_builder().timestamp(42u64.pack()).build();
+        assert_eq!(
+            check_message(&SupportProtocols::Time, time.as_slice()),
+            Ok(())
+        );
+
+        let message = packed::SyncMessage::new_builder()
+            .set(packed::InIBD::new_builder().build())
+            .build();
+        assert_eq!(
+            check_message(&SupportProtocols::Sync, message.as_slice()),
+            Ok(())
+        );
+
+        assert_eq!(check_message(&SupportProtocols::Feeler, &[0xff]), Ok(()));
+    }
+
+    #[test]
+    fn test_malformed_messages() {
+        assert_eq!(
+            check_message(&SupportProtocols::Ping, &[]),
+            Err(DecodeGuardError::Truncated)
+        );
+
+        let mut data = ping_message();
+        data.push(0);
+        assert!(matches!(
+            check_message(&SupportProtocols::Ping, &data),
+            Err(DecodeGuardError::SizeMismatch { .. })
+        ));
+
+        // the first field offset claims a huge number of fields
+        let mut data = ping_message();
+        data[4..8].copy_from_slice(&0x4000_0000u32.to_le_bytes());
+        assert!(matches!(
+            check_message(&SupportProtocols::Ping, &data),
+            Err(DecodeGuardError::TooManyFields { .. })
+        ));
+
+        // unknown payload item
+        let mut data = ping_message();
+        data[8..12].copy_from_slice(&2u32.to_le_bytes());
+        assert_eq!(
+            check_message(&SupportProtocols::Ping, &data),
+            Err(DecodeGuardError::UnknownItem(2))
+        );
+
+        let mut data = packed::SyncMessage::new_builder()
+            .set(packed::InIBD::new_builder().build())
+            .build()
+            .as_slice()
+            .to_vec();
+        data[0..4].copy_from_slice(&(packed::SyncMessageReader::ITEMS_COUNT as u32).to_le_bytes());
+        assert!(matches!(
+            check_message(&SupportProtocols::Sync, &data),
+            Err(DecodeGuardError::UnknownItem(_))
+        ));
+    }
+
+    #[test]
+    fn test_support_protocol() {
+        assert!(matches!(
+            support_protocol(SupportProtocols::RelayV2.protocol_id()),
+            Some(SupportProtocols::RelayV2)
+        ));
+        assert!(support_protocol(ProtocolId::new(200)).is_none());
+    }
+}
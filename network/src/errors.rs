@@ -62,6 +62,8 @@ pub enum PeerError {
     PeerIdExists(PeerId),
     /// Non-reserved peers
     NonReserved,
+    /// Peer is not in the allowlist
+    NotAllowlisted,
     /// Peer is banned
     Banned,
     /// Reach max inbound limit
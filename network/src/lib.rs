@@ -6,8 +6,10 @@
 //! And implemented several basic protocols: identify, discovery, ping, feeler, disconnect_message
 //!
 
+pub mod audit;
 mod behaviour;
 mod compress;
+pub mod decode_guard;
 pub mod errors;
 pub mod network;
 mod network_group;
@@ -21,10 +23,11 @@ mod services;
 mod tests;
 
 pub use crate::{
+    audit::{PeerAuditEvent, PeerAuditEventKind},
     behaviour::Behaviour,
     errors::Error,
     network::{DefaultExitHandler, ExitHandler, NetworkController, NetworkService, NetworkState},
-    peer::{Peer, PeerIdentifyInfo},
+    peer::{Peer, PeerIdentifyInfo, ProtocolMessageStats},
     peer_registry::PeerRegistry,
     peer_store::Score,
     protocols::{
@@ -1,9 +1,10 @@
 //! Global state struct and start function
+use crate::audit::{AuditLog, PeerAuditEvent, PeerAuditEventKind};
 use crate::errors::{Error, P2PError};
 use crate::peer_registry::{ConnectionStatus, PeerRegistry};
 use crate::peer_store::{
     types::{AddrInfo, BannedAddr},
-    PeerStore,
+    PeerScoreConfig, PeerStore, ReportResult,
 };
 use crate::protocols::{
     disconnect_message::DisconnectMessageProtocol,
@@ -19,6 +20,7 @@ use crate::services::{
 };
 use crate::{Behaviour, CKBProtocol, Peer, PeerIndex, ProtocolId, ServiceControl};
 use ckb_app_config::NetworkConfig;
+use ckb_channel::Receiver;
 use ckb_logger::{debug, error, info, trace, warn};
 use ckb_spawn::Spawn;
 use ckb_stop_handler::{SignalSender, StopHandler};
@@ -61,6 +63,15 @@ const P2P_SEND_TIMEOUT: Duration = Duration::from_secs(6);
 const P2P_TRY_SEND_INTERVAL: Duration = Duration::from_millis(100);
 // After 5 minutes we consider this dial hang
 const DIAL_HANG_TIMEOUT: Duration = Duration::from_secs(300);
+// The number of distinct peers which must report an observed address before dialing it to confirm
+const OBSERVED_ADDR_CONFIRMATIONS: usize = 2;
+// The max number of the observed addresses waiting for the confirmations
+pub(crate) const MAX_UNCONFIRMED_OBSERVED_ADDRS: usize = 64;
+// The max number of the unconfirmed observed addresses reported by one peer, a peer reports one
+// address for each of our listen addresses and the address it connects to
+pub(crate) const MAX_UNCONFIRMED_OBSERVED_ADDRS_PER_PEER: usize = 8;
+// The max number of the seed addresses waiting to be dialed
+const MAX_PENDING_SEED_ADDRS: usize = 64;
 
 /// The global shared state of the network module
 pub struct NetworkState {
@@ -72,7 +83,12 @@ pub struct NetworkState {
     /// Node public addresses,
     /// includes manually public addrs and remote peer observed addrs
     public_addrs: RwLock<HashSet<Multiaddr>>,
-    pending_observed_addrs: RwLock<HashSet<Multiaddr>>,
+    pub(crate) pending_observed_addrs: RwLock<HashSet<Multiaddr>>,
+    /// The observed addresses with the peers reporting them, which are not confirmed yet
+    pub(crate) unconfirmed_observed_addrs: RwLock<HashMap<Multiaddr, HashSet<PeerId>>>,
+    /// The seed addresses without peer ids, such as the DNS seeds resolved to IPs, which are
+    /// dialed directly like the bootnodes since they can't be saved in the peer store
+    pub(crate) pending_seed_addrs: RwLock<HashSet<Multiaddr>>,
     local_private_key: secio::SecioKeyPair,
     local_peer_id: PeerId,
     bootnodes: Vec<Multiaddr>,
@@ -83,6 +99,10 @@ pub struct NetworkState {
     pub(crate) protocols: RwLock<Vec<(ProtocolId, String, Vec<String>)>>,
 
     pub(crate) ckb2021: AtomicBool,
+    /// The allowed peers in the allowlist mode, including the whitelist peers
+    allowlist: Option<HashSet<PeerId>>,
+    /// The audit stream of the peer connection lifecycle events
+    pub(crate) audit: AuditLog,
 }
 
 impl NetworkState {
@@ -108,17 +128,26 @@ impl NetworkState {
                     })
             })
             .collect();
-        let peer_store = Mutex::new(PeerStore::load_from_dir_or_default(
-            config.peer_store_path(),
-        ));
+        let mut peer_store = PeerStore::load_from_dir_or_default(config.peer_store_path());
+        peer_store.set_score_config(PeerScoreConfig::from(&config.misbehavior));
+        let peer_store = Mutex::new(peer_store);
         let bootnodes = config.bootnodes();
 
+        let allowlist = if config.allowlist.enable {
+            let mut allowlist = config.allowlist.allowed_peer_ids()?;
+            allowlist.extend(config.whitelist_peers().iter().filter_map(extract_peer_id));
+            Some(allowlist)
+        } else {
+            None
+        };
         let peer_registry = PeerRegistry::new(
             config.max_inbound_peers(),
             config.max_outbound_peers(),
             config.whitelist_only,
             config.whitelist_peers(),
-        );
+        )
+        .with_allowlist(allowlist.clone())
+        .with_protected_peers(config.protected_peer_ids()?);
 
         Ok(NetworkState {
             peer_store,
@@ -129,11 +158,15 @@ impl NetworkState {
             public_addrs: RwLock::new(public_addrs),
             listened_addrs: RwLock::new(Vec::new()),
             pending_observed_addrs: RwLock::new(HashSet::default()),
+            unconfirmed_observed_addrs: RwLock::new(HashMap::default()),
+            pending_seed_addrs: RwLock::new(HashSet::default()),
             local_private_key,
             local_peer_id,
             active: AtomicBool::new(true),
             protocols: RwLock::new(Vec::new()),
             ckb2021: AtomicBool::new(false),
+            allowlist,
+            audit: AuditLog::new(),
         })
     }
 
@@ -149,32 +182,54 @@ impl NetworkState {
         session_id: SessionId,
         behaviour: Behaviour,
     ) {
+        if self.report_session_score(session_id, behaviour).is_banned() {
+            if let Err(err) = disconnect_with_message(p2p_control, session_id, "banned") {
+                debug!("Disconnect failed {:?}, error: {:?}", session_id, err);
+            }
+        }
+    }
+
+    /// Updates the misbehavior score of the session without disconnecting it
+    pub(crate) fn report_session_score(
+        &self,
+        session_id: SessionId,
+        behaviour: Behaviour,
+    ) -> ReportResult {
         if let Some(addr) = self.with_peer_registry(|reg| {
             reg.get_peer(session_id)
-                .filter(|peer| !peer.is_whitelist)
+                .filter(|peer| !peer.is_whitelist && !peer.is_protected)
                 .map(|peer| peer.connected_addr.clone())
         }) {
             trace!("report {:?} because {:?}", addr, behaviour);
-            let report_result = match self.peer_store.lock().report(&addr, behaviour) {
-                Ok(result) => result,
+            match self.peer_store.lock().report(&addr, behaviour) {
+                Ok(result) => {
+                    if result.is_banned() {
+                        info!(
+                            "Ban peer {:?}, reason: report behaviour {:?}",
+                            addr, behaviour
+                        );
+                        self.audit.record(PeerAuditEventKind::Banned {
+                            address: addr.to_string(),
+                            duration: Duration::from_secs(self.config.misbehavior.ban_timeout_secs),
+                            reason: format!("report behaviour {:?}", behaviour),
+                        });
+                    }
+                    result
+                }
                 Err(err) => {
                     error!(
                         "Report failed addr: {:?} behaviour: {:?} error: {:?}",
                         addr, behaviour, err
                     );
-                    return;
-                }
-            };
-            if report_result.is_banned() {
-                if let Err(err) = disconnect_with_message(p2p_control, session_id, "banned") {
-                    debug!("Disconnect failed {:?}, error: {:?}", session_id, err);
+                    ReportResult::Ok
                 }
             }
         } else {
             debug!(
-                "Report {} failed: not in peer registry or it is in the whitelist",
+                "Report {} failed: not in peer registry or it is whitelisted or protected",
                 session_id
             );
+            ReportResult::Ok
         }
     }
 
@@ -187,7 +242,7 @@ impl NetworkState {
     ) {
         if let Some(addr) = self.with_peer_registry(|reg| {
             reg.get_peer(session_id)
-                .filter(|peer| !peer.is_whitelist)
+                .filter(|peer| !peer.is_whitelist && !peer.is_protected)
                 .map(|peer| peer.connected_addr.clone())
         }) {
             info!(
@@ -197,6 +252,11 @@ impl NetworkState {
                 reason
             );
             if let Some(peer) = self.with_peer_registry_mut(|reg| reg.remove_peer(session_id)) {
+                self.audit.record(PeerAuditEventKind::Banned {
+                    address: peer.connected_addr.to_string(),
+                    duration,
+                    reason: reason.clone(),
+                });
                 self.peer_store.lock().ban_addr(
                     &peer.connected_addr,
                     duration.as_millis() as u64,
@@ -211,7 +271,7 @@ impl NetworkState {
             }
         } else {
             debug!(
-                "Ban session({}) failed: not in peer registry or it is in the whitelist",
+                "Ban session({}) failed: not in peer registry or it is whitelisted or protected",
                 session_id
             );
         }
@@ -330,6 +390,14 @@ impl NetworkState {
             trace!("Do not dial self: {:?}, {}", peer_id, addr);
             return false;
         }
+        if !self.is_allowed(peer_id) {
+            trace!(
+                "Do not dial peer not in the allowlist: {:?}, {}",
+                peer_id,
+                addr
+            );
+            return false;
+        }
         if self.public_addrs.read().contains(&addr) {
             trace!(
                 "Do not dial listened address(self): {:?}, {}",
@@ -377,6 +445,15 @@ impl NetworkState {
         true
     }
 
+    /// Whether the peer is allowed to connect, all peers are allowed unless the allowlist mode is
+    /// enabled.
+    pub fn is_allowed(&self, peer_id: &PeerId) -> bool {
+        self.allowlist
+            .as_ref()
+            .map(|allowlist| allowlist.contains(peer_id))
+            .unwrap_or(true)
+    }
+
     pub(crate) fn dial_success(&self, addr: &Multiaddr) {
         if let Some(peer_id) = extract_peer_id(addr) {
             self.dialing_addrs.write().remove(&peer_id);
@@ -407,6 +484,9 @@ impl NetworkState {
 
         debug!("dialing {}", addr);
         p2p_control.dial(addr.clone(), target)?;
+        self.audit.record(PeerAuditEventKind::Dial {
+            address: addr.clone(),
+        });
         self.dialing_addrs.write().insert(
             extract_peer_id(&addr).expect("verified addr"),
             Instant::now(),
@@ -470,10 +550,100 @@ impl NetworkState {
         }
     }
 
-    /// add observed address for identify protocol
-    pub(crate) fn add_observed_addrs(&self, iter: impl Iterator<Item = Multiaddr>) {
+    /// add observed address reported by the peer for identify protocol
+    ///
+    /// The address is dialed to confirm only after it is reported by enough distinct peers, so a
+    /// single peer cannot make us announce a wrong address.
+    ///
+    /// A peer can only report a few unconfirmed addresses. When the unconfirmed addresses are
+    /// full, one address of the peer reporting the most addresses is dropped, so the peers can't
+    /// flush the addresses reported by the others.
+    pub(crate) fn add_observed_addrs(
+        &self,
+        peer_id: &PeerId,
+        iter: impl Iterator<Item = Multiaddr>,
+    ) {
+        let mut unconfirmed_observed_addrs = self.unconfirmed_observed_addrs.write();
         let mut pending_observed_addrs = self.pending_observed_addrs.write();
-        pending_observed_addrs.extend(iter)
+        for addr in iter {
+            if pending_observed_addrs.contains(&addr) {
+                continue;
+            }
+            if !unconfirmed_observed_addrs.contains_key(&addr) {
+                // the number of the unconfirmed addresses reported by each peer
+                let mut reported: HashMap<&PeerId, usize> = HashMap::default();
+                for reporter in unconfirmed_observed_addrs.values().flatten() {
+                    *reported.entry(reporter).or_default() += 1;
+                }
+                if reported.get(peer_id).copied().unwrap_or(0)
+                    >= MAX_UNCONFIRMED_OBSERVED_ADDRS_PER_PEER
+                {
+                    trace!("too many unconfirmed observed addrs from {}", peer_id);
+                    continue;
+                }
+                if unconfirmed_observed_addrs.len() >= MAX_UNCONFIRMED_OBSERVED_ADDRS {
+                    let evicted = unconfirmed_observed_addrs
+                        .iter()
+                        .max_by_key(|(_, reporters)| {
+                            reporters.iter().map(|reporter| reported[reporter]).max()
+                        })
+                        .map(|(addr, _)| addr.clone());
+                    if let Some(evicted) = evicted {
+                        trace!("too many unconfirmed observed addrs, drop {}", evicted);
+                        unconfirmed_observed_addrs.remove(&evicted);
+                    }
+                }
+            }
+            let reporters = unconfirmed_observed_addrs.entry(addr.clone()).or_default();
+            reporters.insert(peer_id.clone());
+            if reporters.len() >= OBSERVED_ADDR_CONFIRMATIONS {
+                unconfirmed_observed_addrs.remove(&addr);
+                pending_observed_addrs.insert(addr);
+            }
+        }
+    }
+
+    /// add the seed addresses without peer ids, which are dialed by [`try_dial_seed_addrs`]
+    ///
+    /// [`try_dial_seed_addrs`]: #method.try_dial_seed_addrs
+    pub(crate) fn add_seed_addrs(&self, iter: impl Iterator<Item = Multiaddr>) {
+        let mut pending_seed_addrs = self.pending_seed_addrs.write();
+        for addr in iter {
+            if pending_seed_addrs.len() >= MAX_PENDING_SEED_ADDRS {
+                trace!("too many pending seed addrs, ignore {}", addr);
+                break;
+            }
+            pending_seed_addrs.insert(addr);
+        }
+    }
+
+    /// dial the pending seed addresses once, the peer ids are learned in the handshake and the
+    /// listened addresses of the peers are saved by the identify protocol
+    pub(crate) fn try_dial_seed_addrs(&self, p2p_control: &ServiceControl) {
+        let addrs: Vec<_> = self.pending_seed_addrs.write().drain().collect();
+        for addr in addrs {
+            let is_self = multiaddr_to_socketaddr(&addr).map_or(false, |socket_addr| {
+                self.public_addrs
+                    .read()
+                    .iter()
+                    .chain(self.listened_addrs.read().iter())
+                    .any(|self_addr| multiaddr_to_socketaddr(self_addr) == Some(socket_addr))
+            });
+            if is_self {
+                trace!("Do not dial seed addr of self: {}", addr);
+                continue;
+            }
+            debug!("dial seed addr {}", addr);
+            if let Err(err) = p2p_control.dial(
+                addr.clone(),
+                TargetProtocol::Single(SupportProtocols::Identify.protocol_id()),
+            ) {
+                debug!("try_dial_seed_addrs fail {} on {}", err, addr);
+            } else {
+                self.audit
+                    .record(PeerAuditEventKind::Dial { address: addr });
+            }
+        }
     }
 
     /// Network message processing controller, default is true, if false, discard any received messages
@@ -526,14 +696,17 @@ impl<T> EventHandler<T> {
                         registry
                             .peers()
                             .values()
-                            .filter(|peer| peer.is_inbound() && !peer.is_whitelist)
-                            .map(|peer| peer.session_id)
-                            .collect::<Vec<SessionId>>()
+                            .filter(|peer| {
+                                peer.is_inbound() && !peer.is_whitelist && !peer.is_protected
+                            })
+                            .map(|peer| (peer.session_id, peer.connected_addr.clone()))
+                            .collect::<Vec<(SessionId, Multiaddr)>>()
                     })
                     .into_iter()
                     .enumerate()
                 {
                     if index & 0x1 != 0 {
+                        let (peer, address) = peer;
                         if let Err(err) = disconnect_with_message(
                             context.control(),
                             peer,
@@ -542,6 +715,13 @@ impl<T> EventHandler<T> {
                             debug!("Inbound eviction failed {:?}, error: {:?}", peer, err);
                             return;
                         }
+                        self.network_state
+                            .audit
+                            .record(PeerAuditEventKind::Evicted {
+                                peer,
+                                address,
+                                reason: "bootnode random eviction".to_owned(),
+                            });
                     }
                 }
             }
@@ -571,6 +751,19 @@ impl<T: ExitHandler> ServiceHandle for EventHandler<T> {
                 } else {
                     public_addrs.remove(&address);
                 }
+                drop(public_addrs);
+                let kind = if let DialerErrorKind::HandshakeError(_) = error {
+                    PeerAuditEventKind::HandshakeFailed {
+                        address: address.clone(),
+                        reason: error.to_string(),
+                    }
+                } else {
+                    PeerAuditEventKind::DialFailed {
+                        address: address.clone(),
+                        reason: error.to_string(),
+                    }
+                };
+                self.network_state.audit.record(kind);
                 self.network_state.dial_failed(&address);
             }
             ServiceError::ProtocolError {
@@ -614,6 +807,12 @@ impl<T: ExitHandler> ServiceHandle for EventHandler<T> {
                     "ProtocolSelectError: proto_name={:?}, session_id={}",
                     proto_name, session_context.id,
                 );
+                self.network_state
+                    .audit
+                    .record(PeerAuditEventKind::HandshakeFailed {
+                        address: session_context.address.clone(),
+                        reason: format!("protocol {:?} selection failed", proto_name),
+                    });
             }
             ServiceError::SessionBlocked { session_context } => {
                 debug!("SessionBlocked: {}", session_context.id);
@@ -655,6 +854,13 @@ impl<T: ExitHandler> ServiceHandle for EventHandler<T> {
                     session_context.id, session_context.address,
                 );
                 self.network_state.dial_success(&session_context.address);
+                self.network_state
+                    .audit
+                    .record(PeerAuditEventKind::SessionOpened {
+                        peer: session_context.id,
+                        address: session_context.address.clone(),
+                        inbound: session_context.ty.is_inbound(),
+                    });
 
                 self.inbound_eviction(context);
 
@@ -683,6 +889,13 @@ impl<T: ExitHandler> ServiceHandle for EventHandler<T> {
                                     evicted_peer.session_id, err
                                 );
                             }
+                            self.network_state
+                                .audit
+                                .record(PeerAuditEventKind::Evicted {
+                                    peer: evicted_peer.session_id,
+                                    address: evicted_peer.connected_addr,
+                                    reason: "accepted a better peer".to_owned(),
+                                });
                         }
                         Ok(None) => debug!(
                             "{} open, registry {} success",
@@ -693,6 +906,13 @@ impl<T: ExitHandler> ServiceHandle for EventHandler<T> {
                                 "registry peer failed {:?} disconnect it, {} => {}",
                                 err, session_context.id, session_context.address,
                             );
+                            self.network_state
+                                .audit
+                                .record(PeerAuditEventKind::SessionRejected {
+                                    peer: session_context.id,
+                                    address: session_context.address.clone(),
+                                    reason: err.to_string(),
+                                });
                             if let Err(err) = disconnect_with_message(
                                 context.control(),
                                 session_context.id,
@@ -712,6 +932,12 @@ impl<T: ExitHandler> ServiceHandle for EventHandler<T> {
                     "SessionClose({}, {})",
                     session_context.id, session_context.address,
                 );
+                self.network_state
+                    .audit
+                    .record(PeerAuditEventKind::SessionClosed {
+                        peer: session_context.id,
+                        address: session_context.address.clone(),
+                    });
 
                 let peer_exists = self
                     .network_state
@@ -946,6 +1172,7 @@ impl<T: ExitHandler> NetworkService<T> {
             let dns_seeding_service = crate::services::dns_seeding::DnsSeedingService::new(
                 Arc::clone(&network_state),
                 config.dns_seeds.clone(),
+                config.dns_seed_pubkey.clone(),
             );
             bg_services.push(Box::pin(dns_seeding_service.start()) as Pin<Box<_>>);
         };
@@ -1166,6 +1393,13 @@ impl NetworkController {
         }
     }
 
+    /// Protects the peer from the eviction, the banning and the connection limits, or removes
+    /// its protection
+    pub fn set_peer_protection(&self, peer_id: PeerId, protected: bool) {
+        self.network_state
+            .with_peer_registry_mut(|reg| reg.set_peer_protection(peer_id, protected))
+    }
+
     /// Get banned peer list
     pub fn get_banned_addrs(&self) -> Vec<BannedAddr> {
         self.network_state
@@ -1190,12 +1424,36 @@ impl NetworkController {
             .cloned()
     }
 
-    /// Ban an ip
+    /// Ban an ip until the timestamp in milliseconds, disconnects the connected peers in the network
     pub fn ban(&self, address: IpNetwork, ban_until: u64, ban_reason: String) {
+        let timeout_ms = ban_until.saturating_sub(faketime::unix_time_as_millis());
+        self.network_state.audit.record(PeerAuditEventKind::Banned {
+            address: address.to_string(),
+            duration: Duration::from_millis(timeout_ms),
+            reason: ban_reason.clone(),
+        });
         self.network_state
             .peer_store
             .lock()
-            .ban_network(address, ban_until, ban_reason)
+            .ban_network(address, timeout_ms, ban_reason);
+        let sessions = self.network_state.with_peer_registry(|reg| {
+            reg.peers()
+                .iter()
+                .filter(|(_, peer)| {
+                    !peer.is_whitelist
+                        && !peer.is_protected
+                        && multiaddr_to_socketaddr(&peer.connected_addr)
+                            .map(|socket_addr| address.contains(socket_addr.ip()))
+                            .unwrap_or_default()
+                })
+                .map(|(session_id, _)| *session_id)
+                .collect::<Vec<_>>()
+        });
+        for session_id in sessions {
+            if let Err(err) = disconnect_with_message(&self.p2p_control, session_id, "banned") {
+                debug!("Disconnect failed {:?}, error: {:?}", session_id, err);
+            }
+        }
     }
 
     /// Unban an ip
@@ -1217,6 +1475,14 @@ impl NetworkController {
         })
     }
 
+    /// Subscribes to the peer connection lifecycle events, see [`audit`](../audit/index.html).
+    ///
+    /// The events are dropped for the subscriber when it falls behind, and the subscription is
+    /// removed once the receiver is dropped.
+    pub fn subscribe_audit_events(&self, name: &str) -> Receiver<PeerAuditEvent> {
+        self.network_state.audit.subscribe(name)
+    }
+
     /// Ban an peer through peer index
     pub fn ban_peer(&self, peer_index: PeerIndex, duration: Duration, reason: String) {
         self.network_state
@@ -1302,6 +1568,11 @@ impl NetworkController {
         let mut ping_controller = self.ping_controller.clone();
         let _ignore = ping_controller.try_send(());
     }
+
+    /// Stops the network service, even if the controller is still cloned elsewhere.
+    pub fn force_stop(&self) {
+        self.stop.force_send(());
+    }
 }
 
 impl Drop for NetworkController {
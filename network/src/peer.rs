@@ -1,7 +1,9 @@
 use crate::network_group::Group;
 use crate::{multiaddr::Multiaddr, ProtocolId, ProtocolVersion, SessionType};
+use ckb_util::Mutex;
 use p2p::SessionId;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Peer info from identify protocol message
@@ -11,6 +13,23 @@ pub struct PeerIdentifyInfo {
     pub client_version: String,
 }
 
+/// Message statistics of a protocol on a session
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProtocolMessageStats {
+    /// Count of the received messages
+    pub received_messages: u64,
+    /// Total size of the received messages in bytes
+    pub received_bytes: u64,
+    /// Count of the sent messages
+    pub sent_messages: u64,
+    /// Total size of the sent messages in bytes
+    pub sent_bytes: u64,
+    /// Unix timestamp in milliseconds of the last received message
+    pub last_received_at: Option<u64>,
+    /// Unix timestamp in milliseconds of the last sent message
+    pub last_sent_at: Option<u64>,
+}
+
 /// Peer info
 #[derive(Clone, Debug)]
 pub struct Peer {
@@ -36,6 +55,11 @@ pub struct Peer {
     pub protocols: HashMap<ProtocolId, ProtocolVersion>,
     /// Whether a whitelist
     pub is_whitelist: bool,
+    /// Whether a protected peer, which is never evicted or banned
+    pub is_protected: bool,
+    // Message statistics of the CKB protocols, shared by the clones to be updated under the
+    // read lock of the peer registry
+    message_stats: Arc<Mutex<HashMap<ProtocolId, ProtocolMessageStats>>>,
 }
 
 impl Peer {
@@ -58,6 +82,8 @@ impl Peer {
             session_type,
             protocols: HashMap::with_capacity_and_hasher(1, Default::default()),
             is_whitelist,
+            is_protected: false,
+            message_stats: Arc::new(Mutex::new(HashMap::default())),
         }
     }
 
@@ -80,4 +106,29 @@ impl Peer {
     pub fn protocol_version(&self, protocol_id: ProtocolId) -> Option<ProtocolVersion> {
         self.protocols.get(&protocol_id).cloned()
     }
+
+    /// Message statistics of the protocol, only the CKB protocols are counted
+    pub fn message_stats(&self, protocol_id: ProtocolId) -> ProtocolMessageStats {
+        self.message_stats
+            .lock()
+            .get(&protocol_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn record_received_message(&self, protocol_id: ProtocolId, size: usize) {
+        let mut message_stats = self.message_stats.lock();
+        let stats = message_stats.entry(protocol_id).or_default();
+        stats.received_messages += 1;
+        stats.received_bytes += size as u64;
+        stats.last_received_at = Some(faketime::unix_time_as_millis());
+    }
+
+    pub(crate) fn record_sent_message(&self, protocol_id: ProtocolId, size: usize) {
+        let mut message_stats = self.message_stats.lock();
+        let stats = message_stats.entry(protocol_id).or_default();
+        stats.sent_messages += 1;
+        stats.sent_bytes += size as u64;
+        stats.last_sent_at = Some(faketime::unix_time_as_millis());
+    }
 }
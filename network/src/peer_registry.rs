@@ -22,6 +22,10 @@ pub struct PeerRegistry {
     // Only whitelist peers or allow all peers.
     whitelist_only: bool,
     whitelist_peers: HashSet<PeerId>,
+    // Only the peers in the allowlist are accepted when it is set.
+    allowlist: Option<HashSet<PeerId>>,
+    // The peers exempt from the eviction, the banning and the connection limits.
+    protected_peers: HashSet<PeerId>,
     feeler_peers: HashSet<PeerId>,
 }
 
@@ -30,9 +34,9 @@ pub struct PeerRegistry {
 pub struct ConnectionStatus {
     /// Total session number
     pub total: u32,
-    /// Not whitelist inbound number
+    /// Neither whitelist nor protected inbound number
     pub non_whitelist_inbound: u32,
-    /// Not whitelist outbound number
+    /// Neither whitelist nor protected outbound number
     pub non_whitelist_outbound: u32,
     /// Maximum number of inbound session
     pub max_inbound: u32,
@@ -65,6 +69,35 @@ impl PeerRegistry {
             max_inbound,
             max_outbound,
             whitelist_only,
+            allowlist: None,
+            protected_peers: HashSet::default(),
+        }
+    }
+
+    /// Only accepts the peers in the allowlist and the whitelist.
+    pub fn with_allowlist(mut self, allowlist: Option<HashSet<PeerId>>) -> Self {
+        self.allowlist = allowlist;
+        self
+    }
+
+    /// Sets the protected peers, which are never evicted or banned, and not counted in the
+    /// connection limits.
+    pub fn with_protected_peers(mut self, protected_peers: HashSet<PeerId>) -> Self {
+        self.protected_peers = protected_peers;
+        self
+    }
+
+    /// Protects the peer or removes its protection, which also applies to the connected session.
+    pub fn set_peer_protection(&mut self, peer_id: PeerId, protected: bool) {
+        if let Some(session_id) = self.get_key_by_peer_id(&peer_id) {
+            if let Some(peer) = self.peers.get_mut(&session_id) {
+                peer.is_protected = protected;
+            }
+        }
+        if protected {
+            self.protected_peers.insert(peer_id);
+        } else {
+            self.protected_peers.remove(&peer_id);
         }
     }
 
@@ -84,12 +117,20 @@ impl PeerRegistry {
         }
 
         let is_whitelist = self.whitelist_peers.contains(&peer_id);
+        let is_protected = self.protected_peers.contains(&peer_id);
+        if let Some(allowlist) = &self.allowlist {
+            if !is_whitelist && !allowlist.contains(&peer_id) {
+                return Err(PeerError::NotAllowlisted.into());
+            }
+        }
         let mut evicted_peer: Option<Peer> = None;
 
         if !is_whitelist {
             if self.whitelist_only {
                 return Err(PeerError::NonReserved.into());
             }
+        }
+        if !is_whitelist && !is_protected {
             if peer_store.is_addr_banned(&remote_addr) {
                 return Err(PeerError::Banned.into());
             }
@@ -109,7 +150,8 @@ impl PeerRegistry {
             }
         }
         peer_store.add_connected_peer(remote_addr.clone(), session_type)?;
-        let peer = Peer::new(session_id, session_type, remote_addr, is_whitelist);
+        let mut peer = Peer::new(session_id, session_type, remote_addr, is_whitelist);
+        peer.is_protected = is_protected;
         self.peers.insert(session_id, peer);
         Ok(evicted_peer)
     }
@@ -119,7 +161,7 @@ impl PeerRegistry {
         let mut candidate_peers = {
             self.peers
                 .values()
-                .filter(|peer| peer.is_inbound() && !peer.is_whitelist)
+                .filter(|peer| peer.is_inbound() && !peer.is_whitelist && !peer.is_protected)
                 .collect::<Vec<_>>()
         };
         // Protect peers based on characteristics that an attacker hard to simulate or manipulate
@@ -247,7 +289,11 @@ impl PeerRegistry {
         let total = self.peers.len() as u32;
         let mut non_whitelist_inbound: u32 = 0;
         let mut non_whitelist_outbound: u32 = 0;
-        for peer in self.peers.values().filter(|peer| !peer.is_whitelist) {
+        for peer in self
+            .peers
+            .values()
+            .filter(|peer| !peer.is_whitelist && !peer.is_protected)
+        {
             if peer.is_outbound() {
                 non_whitelist_outbound += 1;
             } else {
@@ -71,9 +71,14 @@ impl BanList {
             .unwrap_or_default()
     }
 
-    /// Get banned address list
+    /// Get banned address list, the expired ones are excluded
     pub fn get_banned_addrs(&self) -> Vec<BannedAddr> {
-        self.inner.values().map(ToOwned::to_owned).collect()
+        let now_ms = unix_time_as_millis();
+        self.inner
+            .values()
+            .filter(|banned_addr| banned_addr.ban_until.gt(&now_ms))
+            .map(ToOwned::to_owned)
+            .collect()
     }
 
     fn clear_expires(&mut self) {
@@ -0,0 +1,116 @@
+//! Misbehavior scores
+use crate::peer_store::{PeerScoreConfig, Score};
+use ipnetwork::IpNetwork;
+use std::collections::HashMap;
+
+/// The maximum number of the tracked networks
+const SCORES_LIMIT: usize = 16384;
+const HOUR_MS: u64 = 3600 * 1000;
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    score: Score,
+    updated_at_ms: u64,
+}
+
+/// Misbehavior scores of the remote networks
+///
+/// The scores are tracked by the network the same as the ban list, so reconnecting from another
+/// port or peer id does not reset the score. A score recovers towards the default score over time.
+#[derive(Debug, Default)]
+pub struct MisbehaviorScores {
+    inner: HashMap<IpNetwork, Entry>,
+}
+
+impl MisbehaviorScores {
+    /// Adds `delta` to the score of the network and returns the new score
+    pub fn update(
+        &mut self,
+        network: IpNetwork,
+        delta: Score,
+        config: &PeerScoreConfig,
+        now_ms: u64,
+    ) -> Score {
+        if !self.inner.contains_key(&network) && self.inner.len() >= SCORES_LIMIT {
+            self.evict(config, now_ms);
+        }
+        let entry = self.inner.entry(network).or_insert(Entry {
+            score: config.default_score,
+            updated_at_ms: now_ms,
+        });
+        let recovered = recover(entry, config, now_ms);
+        let score = recovered.score.saturating_add(delta);
+        *entry = Entry {
+            score,
+            updated_at_ms: recovered.updated_at_ms,
+        };
+        score
+    }
+
+    /// Gets the current score of the network
+    pub fn score(&self, network: &IpNetwork, config: &PeerScoreConfig, now_ms: u64) -> Score {
+        self.inner
+            .get(network)
+            .map(|entry| recovered(entry, config, now_ms))
+            .unwrap_or(config.default_score)
+    }
+
+    /// Forgets the score of the network
+    pub fn remove(&mut self, network: &IpNetwork) {
+        self.inner.remove(network);
+    }
+
+    /// Get the numbers of the tracked networks
+    pub fn count(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn evict(&mut self, config: &PeerScoreConfig, now_ms: u64) {
+        self.inner
+            .retain(|_, entry| recovered(entry, config, now_ms) < config.default_score);
+        if self.inner.len() >= SCORES_LIMIT {
+            // Forgets the least misbehaved one
+            if let Some(network) = self
+                .inner
+                .iter()
+                .max_by_key(|(_, entry)| recovered(entry, config, now_ms))
+                .map(|(network, _)| *network)
+            {
+                self.inner.remove(&network);
+            }
+        }
+    }
+}
+
+fn recovered(entry: &Entry, config: &PeerScoreConfig, now_ms: u64) -> Score {
+    recover(entry, config, now_ms).score
+}
+
+// Adds the whole points recovered since the last update. The update time only advances by the
+// time those points account for, so the fraction of a point is carried over to the next update
+// instead of being lost.
+fn recover(entry: &Entry, config: &PeerScoreConfig, now_ms: u64) -> Entry {
+    let recovered = Entry {
+        score: entry.score,
+        updated_at_ms: now_ms,
+    };
+    if entry.score >= config.default_score || config.recovery_per_hour <= 0 {
+        return recovered;
+    }
+    let rate = config.recovery_per_hour as u64;
+    let elapsed_ms = now_ms.saturating_sub(entry.updated_at_ms);
+    let points = elapsed_ms.saturating_mul(rate) / HOUR_MS;
+    let score = i64::from(entry.score).saturating_add(points as i64);
+    if score >= i64::from(config.default_score) {
+        return Entry {
+            score: config.default_score,
+            ..recovered
+        };
+    }
+    // The time to recover `points`, rounded up, which never exceeds `elapsed_ms`
+    let consumed_ms = (points * HOUR_MS + rate - 1) / rate;
+    Entry {
+        score: score as Score,
+        updated_at_ms: entry.updated_at_ms + consumed_ms,
+    }
+}
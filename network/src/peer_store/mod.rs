@@ -1,12 +1,14 @@
 //! Peer store manager
 pub mod addr_manager;
 pub mod ban_list;
+pub mod misbehavior;
 mod peer_store_db;
 mod peer_store_impl;
 pub mod types;
 
 pub(crate) use crate::Behaviour;
 pub use crate::SessionType;
+use ckb_app_config::MisbehaviorConfig;
 use p2p::multiaddr::Multiaddr;
 pub use peer_store_impl::PeerStore;
 
@@ -29,6 +31,8 @@ pub struct PeerScoreConfig {
     pub ban_score: Score,
     /// Ban time
     pub ban_timeout_ms: u64,
+    /// The points a misbehavior score recovers per hour
+    pub recovery_per_hour: Score,
 }
 
 impl Default for PeerScoreConfig {
@@ -37,6 +41,18 @@ impl Default for PeerScoreConfig {
             default_score: 100,
             ban_score: 40,
             ban_timeout_ms: 24 * 3600 * 1000, // 1 day
+            recovery_per_hour: 10,
+        }
+    }
+}
+
+impl From<&MisbehaviorConfig> for PeerScoreConfig {
+    fn from(config: &MisbehaviorConfig) -> Self {
+        PeerScoreConfig {
+            ban_score: config.ban_score,
+            ban_timeout_ms: config.ban_timeout_secs.saturating_mul(1000),
+            recovery_per_hour: config.score_recovery_per_hour,
+            ..Default::default()
         }
     }
 }
@@ -5,8 +5,9 @@ use crate::{
     peer_store::{
         addr_manager::AddrManager,
         ban_list::BanList,
+        misbehavior::MisbehaviorScores,
         types::{ip_to_network, AddrInfo, BannedAddr, PeerInfo},
-        Behaviour, Multiaddr, PeerScoreConfig, ReportResult, Status, ADDR_COUNT_LIMIT,
+        Behaviour, Multiaddr, PeerScoreConfig, ReportResult, Score, Status, ADDR_COUNT_LIMIT,
         ADDR_TIMEOUT_MS,
     },
     PeerId, SessionType,
@@ -22,6 +23,7 @@ pub struct PeerStore {
     ban_list: RefCell<BanList>,
     peers: RefCell<HashMap<PeerId, PeerInfo>>,
     score_config: PeerScoreConfig,
+    misbehavior_scores: MisbehaviorScores,
 }
 
 impl PeerStore {
@@ -32,9 +34,20 @@ impl PeerStore {
             ban_list: RefCell::new(ban_list),
             peers: Default::default(),
             score_config: Default::default(),
+            misbehavior_scores: Default::default(),
         }
     }
 
+    /// Set the scoring configuration
+    pub fn set_score_config(&mut self, score_config: PeerScoreConfig) {
+        self.score_config = score_config;
+    }
+
+    /// Get the scoring configuration
+    pub fn score_config(&self) -> &PeerScoreConfig {
+        &self.score_config
+    }
+
     /// Add a peer and address into peer_store
     /// this method will assume peer is connected, which implies address is "verified".
     pub fn add_connected_peer(&mut self, addr: Multiaddr, session_type: SessionType) -> Result<()> {
@@ -82,22 +95,47 @@ impl PeerStore {
     }
 
     /// Report peer behaviours
+    ///
+    /// The behaviour score is added to the misbehavior score of the network which the address
+    /// belongs to, the network is banned when the score drops below the ban score.
     pub fn report(&mut self, addr: &Multiaddr, behaviour: Behaviour) -> Result<ReportResult> {
         if let Some(peer_addr) = self.addr_manager.get_mut(addr) {
-            let score = peer_addr.score.saturating_add(behaviour.score());
-            peer_addr.score = score;
-            if score < self.score_config.ban_score {
-                self.ban_addr(
-                    addr,
-                    self.score_config.ban_timeout_ms,
-                    format!("report behaviour {:?}", behaviour),
-                );
-                return Ok(ReportResult::Banned);
-            }
+            peer_addr.score = peer_addr.score.saturating_add(behaviour.score());
+        }
+        let network = match multiaddr_to_socketaddr(addr) {
+            Some(socket_addr) => ip_to_network(socket_addr.ip()),
+            None => return Ok(ReportResult::Ok),
+        };
+        let now_ms = faketime::unix_time_as_millis();
+        let score =
+            self.misbehavior_scores
+                .update(network, behaviour.score(), &self.score_config, now_ms);
+        if score < self.score_config.ban_score {
+            // Starts over when the ban expires
+            self.misbehavior_scores.remove(&network);
+            self.ban_network(
+                network,
+                self.score_config.ban_timeout_ms,
+                format!("report behaviour {:?}", behaviour),
+            );
+            return Ok(ReportResult::Banned);
         }
         Ok(ReportResult::Ok)
     }
 
+    /// Get the misbehavior score of the network which the address belongs to
+    pub fn misbehavior_score(&self, addr: &Multiaddr) -> Score {
+        multiaddr_to_socketaddr(addr)
+            .map(|socket_addr| {
+                self.misbehavior_scores.score(
+                    &ip_to_network(socket_addr.ip()),
+                    &self.score_config,
+                    faketime::unix_time_as_millis(),
+                )
+            })
+            .unwrap_or(self.score_config.default_score)
+    }
+
     /// Remove peer id
     pub fn remove_disconnected_peer(&mut self, addr: &Multiaddr) -> Option<PeerInfo> {
         extract_peer_id(addr).and_then(|peer_id| self.peers.borrow_mut().remove(&peer_id))
@@ -147,6 +185,20 @@ impl PeerStore {
             })
     }
 
+    /// Get peers for the discovery to probe, this method randomly returns non-connected peer
+    /// addrs, and removes the terrible addrs which are given up instead of returning them.
+    pub fn fetch_addrs_to_probe(&mut self, count: usize) -> Vec<AddrInfo> {
+        let now_ms = faketime::unix_time_as_millis();
+        let (terrible_addrs, addrs): (Vec<_>, Vec<_>) = self
+            .fetch_addrs_to_attempt(count)
+            .into_iter()
+            .partition(|peer_addr| peer_addr.is_terrible(now_ms));
+        for peer_addr in terrible_addrs {
+            self.addr_manager.remove(&peer_addr.addr);
+        }
+        addrs
+    }
+
     /// Return valid addrs that success connected, used for discovery.
     pub fn fetch_random_addrs(&mut self, count: usize) -> Vec<AddrInfo> {
         let now_ms = faketime::unix_time_as_millis();
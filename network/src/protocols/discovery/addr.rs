@@ -1,7 +1,7 @@
 use std::collections::hash_map::RandomState;
 
 use bloom_filters::{BloomFilter, DefaultBuildHashKernels, StableBloomFilter};
-use p2p::{multiaddr::Multiaddr, ProtocolId, SessionId};
+use p2p::{multiaddr::Multiaddr, service::ServiceControl, ProtocolId, SessionId};
 
 pub(crate) const DEFAULT_BUCKETS_NUM: usize = 5000;
 
@@ -42,6 +42,8 @@ pub trait AddressManager {
     fn add_new_addrs(&mut self, session_id: SessionId, addrs: Vec<Multiaddr>);
     fn misbehave(&mut self, session_id: SessionId, kind: Misbehavior) -> MisbehaveResult;
     fn get_random(&mut self, n: usize) -> Vec<Multiaddr>;
+    /// Dial a sample of n stored addresses to verify whether they are reachable
+    fn probe(&mut self, p2p_control: &ServiceControl, n: usize);
 }
 
 // bitcoin: bloom.h, bloom.cpp => CRollingBloomFilter
@@ -5,12 +5,14 @@ use std::{
 };
 
 use ckb_logger::{debug, trace, warn};
+use faketime::unix_time_as_millis;
 use p2p::{
     bytes,
     context::{ProtocolContext, ProtocolContextMutRef},
     multiaddr::Multiaddr,
+    service::ServiceControl,
     traits::ServiceProtocol,
-    utils::{is_reachable, multiaddr_to_socketaddr},
+    utils::{extract_peer_id, is_reachable, multiaddr_to_socketaddr},
     SessionId,
 };
 use rand::seq::SliceRandom;
@@ -24,7 +26,7 @@ use self::{
     protocol::{decode, encode},
     state::RemoteAddress,
 };
-use crate::{NetworkState, ProtocolId};
+use crate::{Behaviour, NetworkState, ProtocolId};
 
 mod addr;
 mod protocol;
@@ -38,6 +40,8 @@ const MAX_ADDR_TO_SEND: usize = 1000;
 const MAX_ADDRS: usize = 3;
 // Every 24 hours send announce nodes message
 const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(3600 * 24);
+// The number of stored addresses probed in every announce check
+const PROBE_ADDR_COUNT: usize = 3;
 
 pub struct DiscoveryProtocol<M> {
     sessions: HashMap<SessionId, SessionState>,
@@ -221,6 +225,9 @@ impl<M: AddressManager> ServiceProtocol for DiscoveryProtocol<M> {
     }
 
     fn notify(&mut self, context: &mut ProtocolContext, _token: u64) {
+        // verify the stored addresses, the unreachable ones are given up after the retries
+        self.addr_mgr.probe(context.control(), PROBE_ADDR_COUNT);
+
         let now = Instant::now();
         let addr_mgr = &self.addr_mgr;
         // get announce list
@@ -322,6 +329,13 @@ impl AddressManager for DiscoveryAddressManager {
     }
 
     fn is_valid_addr(&self, addr: &Multiaddr) -> bool {
+        // Neither stores nor announces the addresses of the other peers in the allowlist mode
+        let allowed = extract_peer_id(addr)
+            .map(|peer_id| self.network_state.is_allowed(&peer_id))
+            .unwrap_or(true);
+        if !allowed {
+            return false;
+        }
         if !self.discovery_local_address {
             let local_or_invalid = multiaddr_to_socketaddr(&addr)
                 .map(|socket_addr| !is_reachable(socket_addr.ip()))
@@ -354,8 +368,18 @@ impl AddressManager for DiscoveryAddressManager {
         }
     }
 
-    fn misbehave(&mut self, _session_id: SessionId, _kind: Misbehavior) -> MisbehaveResult {
-        // FIXME:
+    fn misbehave(&mut self, session_id: SessionId, kind: Misbehavior) -> MisbehaveResult {
+        let behaviour = match kind {
+            Misbehavior::DuplicateGetNodes | Misbehavior::DuplicateFirstNodes => {
+                Behaviour::UnexpectedMessage
+            }
+            Misbehavior::TooManyItems { .. } | Misbehavior::TooManyAddresses(_) => {
+                Behaviour::TooManyItems
+            }
+            Misbehavior::InvalidData => Behaviour::MalformedMessage,
+        };
+        self.network_state
+            .report_session_score(session_id, behaviour);
         MisbehaveResult::Disconnect
     }
 
@@ -375,4 +399,29 @@ impl AddressManager for DiscoveryAddressManager {
         trace!("discovery send random addrs: {:?}", addrs);
         addrs
     }
+
+    fn probe(&mut self, p2p_control: &ServiceControl, n: usize) {
+        if self.network_state.config.whitelist_only {
+            return;
+        }
+        let now_ms = unix_time_as_millis();
+        let addrs = self.network_state.with_peer_store_mut(|peer_store| {
+            let paddrs = peer_store.fetch_addrs_to_probe(n);
+            paddrs
+                .into_iter()
+                .filter(|paddr| self.is_valid_addr(&paddr.addr))
+                .map(|paddr| {
+                    // mark addr as tried, the attempts are reset once the probe connects
+                    if let Some(paddr) = peer_store.mut_addr_manager().get_mut(&paddr.addr) {
+                        paddr.mark_tried(now_ms);
+                    }
+                    paddr.addr
+                })
+                .collect::<Vec<_>>()
+        });
+        for addr in addrs {
+            trace!("discovery probe addr: {:?}", addr);
+            self.network_state.dial_feeler(p2p_control, addr);
+        }
+    }
 }
@@ -7,6 +7,8 @@ use p2p::{
 use tokio_util::codec::length_delimited::LengthDelimitedCodec;
 use tokio_util::codec::{Decoder, Encoder};
 
+use crate::decode_guard::check_message;
+use crate::SupportProtocols;
 use ckb_logger::debug;
 use ckb_types::{packed, prelude::*};
 
@@ -127,6 +129,7 @@ impl DiscoveryMessage {
     }
 
     pub fn decode(data: &[u8]) -> Option<Self> {
+        check_message(&SupportProtocols::Discovery, data).ok()?;
         let reader = packed::DiscoveryMessageReader::from_compatible_slice(data).ok()?;
         match reader.payload().to_enum() {
             packed::DiscoveryPayloadUnionReader::GetNodes(reader) => {
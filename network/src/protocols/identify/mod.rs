@@ -17,7 +17,7 @@ use p2p::{
 
 mod protocol;
 
-use crate::{NetworkState, PeerIdentifyInfo, SupportProtocols};
+use crate::{Behaviour, NetworkState, PeerIdentifyInfo, SupportProtocols};
 use ckb_types::{packed, prelude::*};
 
 use protocol::IdentifyMessage;
@@ -517,12 +517,26 @@ impl Callback for IdentifyCallback {
             })
             .chain(::std::iter::once(source_addr));
 
-        self.network_state.add_observed_addrs(observed_addrs_iter);
+        self.network_state
+            .add_observed_addrs(peer_id, observed_addrs_iter);
         // NOTE: for future usage
         MisbehaveResult::Continue
     }
 
-    fn misbehave(&mut self, _peer_id: &PeerId, _kind: Misbehavior) -> MisbehaveResult {
+    fn misbehave(&mut self, peer_id: &PeerId, kind: Misbehavior) -> MisbehaveResult {
+        let behaviour = match kind {
+            Misbehavior::DuplicateReceived => Behaviour::UnexpectedMessage,
+            Misbehavior::Timeout => Behaviour::Timeout,
+            Misbehavior::InvalidData => Behaviour::MalformedMessage,
+            Misbehavior::TooManyAddresses(_) => Behaviour::TooManyItems,
+        };
+        if let Some(session_id) = self
+            .network_state
+            .with_peer_registry(|reg| reg.get_key_by_peer_id(peer_id))
+        {
+            self.network_state
+                .report_session_score(session_id, behaviour);
+        }
         MisbehaveResult::Disconnect
     }
 }
@@ -1,5 +1,7 @@
 use p2p::{bytes::Bytes, multiaddr::Multiaddr};
 
+use crate::decode_guard::check_message;
+use crate::SupportProtocols;
 use ckb_types::{packed, prelude::*};
 use std::convert::TryFrom;
 
@@ -63,6 +65,7 @@ impl<'a> IdentifyMessage<'a> {
     }
 
     pub(crate) fn decode(data: &'a [u8]) -> Option<Self> {
+        check_message(&SupportProtocols::Identify, data).ok()?;
         let reader = packed::IdentifyMessageReader::from_compatible_slice(data).ok()?;
 
         let identify = reader.identify().raw_data();
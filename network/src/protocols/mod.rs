@@ -32,7 +32,9 @@ pub type PeerIndex = SessionId;
 pub type BoxedFutureTask = Pin<Box<dyn Future<Output = ()> + 'static + Send>>;
 
 use crate::{
+    audit::PeerAuditEventKind,
     compress::{compress, decompress},
+    decode_guard::{check_message, support_protocol, MALFORMED_MESSAGE_BAN_TIME},
     network::disconnect_with_message,
     Behaviour, Error, NetworkState, Peer, ProtocolVersion, SupportProtocols,
 };
@@ -268,6 +270,13 @@ impl ServiceProtocol for CKBHandler {
                 peer.protocols.insert(self.proto_id, version.to_owned());
             }
         });
+        self.network_state
+            .audit
+            .record(PeerAuditEventKind::ProtocolOpened {
+                peer: context.session.id,
+                protocol: self.proto_id,
+                version: version.to_owned(),
+            });
 
         if !self.network_state.is_active() {
             return;
@@ -288,6 +297,12 @@ impl ServiceProtocol for CKBHandler {
                 peer.protocols.remove(&self.proto_id);
             }
         });
+        self.network_state
+            .audit
+            .record(PeerAuditEventKind::ProtocolClosed {
+                peer: context.session.id,
+                protocol: self.proto_id,
+            });
 
         if !self.network_state.is_active() {
             return;
@@ -313,12 +328,31 @@ impl ServiceProtocol for CKBHandler {
             context.session.id,
             data.len()
         );
+        let peer_index = context.session.id;
+        self.network_state.with_peer_registry(|reg| {
+            if let Some(peer) = reg.get_peer(peer_index) {
+                peer.record_received_message(self.proto_id, data.len());
+            }
+        });
         let nc = DefaultCKBProtocolContext {
             proto_id: self.proto_id,
             network_state: Arc::clone(&self.network_state),
             p2p_control: context.control().to_owned(),
         };
-        let peer_index = context.session.id;
+        if let Some(protocol) = support_protocol(self.proto_id) {
+            if let Err(err) = check_message(&protocol, &data) {
+                debug!(
+                    "Peer {} sends us a malformed message in protocol {}: {}",
+                    peer_index, self.proto_id, err
+                );
+                nc.ban_peer(
+                    peer_index,
+                    MALFORMED_MESSAGE_BAN_TIME,
+                    format!("send us a malformed message: {}", err),
+                );
+                return;
+            }
+        }
         self.handler.received(Arc::new(nc), peer_index, data);
     }
 
@@ -359,6 +393,43 @@ struct DefaultCKBProtocolContext {
     p2p_control: ServiceControl,
 }
 
+impl DefaultCKBProtocolContext {
+    fn record_sent_message(&self, proto_id: ProtocolId, peer_index: PeerIndex, size: usize) {
+        self.network_state.with_peer_registry(|reg| {
+            if let Some(peer) = reg.get_peer(peer_index) {
+                peer.record_sent_message(proto_id, size);
+            }
+        });
+    }
+
+    // Counts the broadcast message for every target session
+    fn record_broadcast_message(&self, target: &TargetSession, size: usize) {
+        let peers = self.network_state.with_peer_registry(|reg| {
+            reg.peers()
+                .iter()
+                .filter(|(_, peer)| peer.protocols.contains_key(&self.proto_id))
+                .map(|(peer_index, _)| *peer_index)
+                .collect::<Vec<_>>()
+        });
+        // The filter runs without holding the lock of the peer registry
+        let peers: Vec<PeerIndex> = match target {
+            TargetSession::All => peers,
+            TargetSession::Single(peer_index) => vec![*peer_index],
+            TargetSession::Filter(filter) => peers
+                .into_iter()
+                .filter(|peer_index| filter(peer_index))
+                .collect(),
+        };
+        self.network_state.with_peer_registry(|reg| {
+            for peer_index in peers {
+                if let Some(peer) = reg.get_peer(peer_index) {
+                    peer.record_sent_message(self.proto_id, size);
+                }
+            }
+        });
+    }
+}
+
 impl CKBProtocolContext for DefaultCKBProtocolContext {
     fn ckb2021(&self) -> bool {
         self.network_state
@@ -387,8 +458,10 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
+        let size = data.len();
         self.p2p_control
             .quick_send_message_to(peer_index, proto_id, data)?;
+        self.record_sent_message(proto_id, peer_index, size);
         Ok(())
     }
     fn quick_send_message_to(&self, peer_index: PeerIndex, data: Bytes) -> Result<(), Error> {
@@ -398,11 +471,14 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
+        let size = data.len();
         self.p2p_control
             .quick_send_message_to(peer_index, self.proto_id, data)?;
+        self.record_sent_message(self.proto_id, peer_index, size);
         Ok(())
     }
     fn quick_filter_broadcast(&self, target: TargetSession, data: Bytes) -> Result<(), Error> {
+        self.record_broadcast_message(&target, data.len());
         self.p2p_control
             .quick_filter_broadcast(target, self.proto_id, data)?;
         Ok(())
@@ -428,8 +504,10 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
+        let size = data.len();
         self.p2p_control
             .send_message_to(peer_index, proto_id, data)?;
+        self.record_sent_message(proto_id, peer_index, size);
         Ok(())
     }
     fn send_message_to(&self, peer_index: PeerIndex, data: Bytes) -> Result<(), Error> {
@@ -439,11 +517,14 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
+        let size = data.len();
         self.p2p_control
             .send_message_to(peer_index, self.proto_id, data)?;
+        self.record_sent_message(self.proto_id, peer_index, size);
         Ok(())
     }
     fn filter_broadcast(&self, target: TargetSession, data: Bytes) -> Result<(), Error> {
+        self.record_broadcast_message(&target, data.len());
         self.p2p_control
             .filter_broadcast(target, self.proto_id, data)?;
         Ok(())
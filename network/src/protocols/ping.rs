@@ -1,5 +1,6 @@
+use crate::decode_guard::check_message;
 use crate::network::disconnect_with_message;
-use crate::NetworkState;
+use crate::{NetworkState, SupportProtocols};
 use ckb_logger::{debug, error, trace, warn};
 use ckb_types::{packed, prelude::*};
 use futures::{
@@ -326,6 +327,7 @@ impl PingMessage {
     }
 
     fn decode(data: &[u8]) -> Option<PingPayload> {
+        check_message(&SupportProtocols::Ping, data).ok()?;
         let reader = packed::PingMessageReader::from_compatible_slice(data).ok()?;
         match reader.payload().to_enum() {
             packed::PingPayloadUnionReader::Ping(reader) => {
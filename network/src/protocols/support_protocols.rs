@@ -42,7 +42,7 @@ pub enum SupportProtocols {
     ///
     /// [RFC](https://github.com/nervosnetwork/rfcs/blob/master/rfcs/0004-ckb-block-sync/0004-ckb-block-sync.md#new-block-announcement)
     Relay,
-    /// New Relay
+    /// New Relay, the version 3 supports the fee filter
     RelayV2,
     /// Time: A protocol used for node pairing that warns if there is a large gap between the local time and the remote node.
     Time,
@@ -101,7 +101,7 @@ impl SupportProtocols {
             SupportProtocols::Relay => vec!["1".to_owned()],
             SupportProtocols::Time => vec!["1".to_owned(), "2".to_owned()],
             SupportProtocols::Alert => vec!["1".to_owned(), "2".to_owned()],
-            SupportProtocols::RelayV2 => vec!["2".to_owned()],
+            SupportProtocols::RelayV2 => vec!["2".to_owned(), "3".to_owned()],
         }
     }
 
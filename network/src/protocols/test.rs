@@ -388,6 +388,25 @@ fn test_discovery_behavior() {
     wait_connect_state(&node3, 2);
 }
 
+#[test]
+fn test_dial_seed_addrs() {
+    let node1 = net_service_start("/test/1".to_string());
+    let node2 = net_service_start("/test/1".to_string());
+
+    // The DNS seeds resolved to IPs have no peer ids
+    let mut seed_addr = node2.listen_addr.clone();
+    assert!(matches!(seed_addr.pop(), Some(Protocol::P2P(_))));
+    node1
+        .network_state
+        .add_seed_addrs(vec![seed_addr].into_iter());
+    node1.network_state.try_dial_seed_addrs(&node1.control);
+
+    wait_connect_state(&node1, 1);
+    wait_connect_state(&node2, 1);
+    // The seeds are dialed only once
+    assert!(node1.network_state.pending_seed_addrs.read().is_empty());
+}
+
 #[test]
 fn test_dial_all() {
     let node1 = net_service_start("/test/1".to_string());
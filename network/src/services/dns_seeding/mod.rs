@@ -1,31 +1,46 @@
-use std::{error::Error, sync::Arc, time::Duration};
+use std::{error::Error, net::SocketAddr, sync::Arc, time::Duration};
 
 use ckb_logger::{debug, error, trace, warn};
 use faster_hex::hex_decode;
+use p2p::{
+    multiaddr::Multiaddr,
+    utils::{is_reachable, socketaddr_to_multiaddr},
+};
 use secp256k1::key::PublicKey;
 use tokio::time::Interval;
+use trust_dns_resolver::TokioAsyncResolver;
 
 mod seed_record;
 
 use crate::NetworkState;
 use seed_record::SeedRecord;
 
-// FIXME: should replace this later
-const TXT_VERIFY_PUBKEY: &str = "";
-
 pub(crate) struct DnsSeedingService {
     network_state: Arc<NetworkState>,
     check_interval: Interval,
     seeds: Vec<String>,
+    pubkey: Option<PublicKey>,
 }
 
 impl DnsSeedingService {
-    pub(crate) fn new(network_state: Arc<NetworkState>, seeds: Vec<String>) -> DnsSeedingService {
+    pub(crate) fn new(
+        network_state: Arc<NetworkState>,
+        seeds: Vec<String>,
+        pubkey: Option<String>,
+    ) -> DnsSeedingService {
         let check_interval = tokio::time::interval(Duration::from_secs(10));
+        let pubkey = pubkey.and_then(|pubkey| match parse_pubkey(&pubkey) {
+            Ok(pubkey) => Some(pubkey),
+            Err(err) => {
+                error!("invalid dns_seed_pubkey, TXT seeds are ignored: {}", err);
+                None
+            }
+        });
         DnsSeedingService {
             network_state,
             check_interval,
             seeds,
+            pubkey,
         }
     }
 
@@ -39,11 +54,6 @@ impl DnsSeedingService {
     }
 
     async fn seeding(&self) -> Result<(), Box<dyn Error>> {
-        // TODO: DNS seeding is disabled now, may enable in the future (need discussed)
-        if TXT_VERIFY_PUBKEY.is_empty() {
-            return Ok(());
-        }
-
         let enough_outbound = self.network_state.with_peer_registry(|reg| {
             reg.peers()
                 .values()
@@ -56,58 +66,165 @@ impl DnsSeedingService {
             return Ok(());
         }
 
-        let mut pubkey_bytes = [4u8; 65];
-        hex_decode(TXT_VERIFY_PUBKEY.as_bytes(), &mut pubkey_bytes[1..65])
-            .map_err(|err| format!("parse key({}) error: {:?}", TXT_VERIFY_PUBKEY, err))?;
-        let pubkey = PublicKey::from_slice(&pubkey_bytes)
-            .map_err(|err| format!("create PublicKey failed: {:?}", err))?;
-
         let resolver = trust_dns_resolver::AsyncResolver::tokio_from_system_conf()
             .await
             .map_err(|err| format!("Failed to create DNS resolver: {}", err))?;
 
         let mut addrs = Vec::new();
+        let mut seed_addrs = Vec::new();
         for seed in &self.seeds {
-            debug!("query txt records from: {}", seed);
-            match resolver.txt_lookup(seed.as_str()).await {
-                Ok(records) => {
-                    for record in records.iter() {
-                        for inner in record.iter() {
-                            match std::str::from_utf8(inner) {
-                                Ok(record) => {
-                                    match SeedRecord::decode_with_pubkey(&record, &pubkey) {
-                                        Ok(seed_record) => {
-                                            let address = seed_record.address();
-                                            trace!("got dns txt address: {}", address);
-                                            addrs.push(address);
-                                        }
-                                        Err(err) => {
-                                            debug!(
-                                                "decode dns txt record failed: {:?}, {:?}",
-                                                err, record
-                                            );
-                                        }
-                                    }
-                                }
-                                Err(err) => {
-                                    debug!("get dns txt record error: {:?}", err);
-                                }
-                            }
-                        }
-                    }
+            match split_host_port(seed) {
+                Some((host, port)) => {
+                    self.lookup_ip(&resolver, host, port, &mut seed_addrs).await;
+                }
+                // The domain names never contain colons
+                None if seed.contains(':') => {
+                    warn!(
+                        "Invalid DNS seed {}, the IPv6 address must be in the form [ip]:port",
+                        seed
+                    );
                 }
-                Err(_) => {
-                    warn!("Invalid domain name: {}", seed);
+                None => {
+                    if let Some(ref pubkey) = self.pubkey {
+                        self.lookup_txt(&resolver, seed, pubkey, &mut addrs).await;
+                    } else {
+                        debug!("skip TXT seed {} since dns_seed_pubkey is not set", seed);
+                    }
                 }
             }
         }
 
-        debug!("DNS seeding got {} address", addrs.len());
+        debug!(
+            "DNS seeding got {} address and {} address without peer id",
+            addrs.len(),
+            seed_addrs.len()
+        );
         self.network_state.with_peer_store_mut(|peer_store| {
             for addr in addrs {
                 let _ = peer_store.add_addr(addr);
             }
         });
+        // The peer store only accepts the addresses with peer ids, the IP seeds are dialed
+        // directly instead.
+        self.network_state.add_seed_addrs(seed_addrs.into_iter());
         Ok(())
     }
+
+    async fn lookup_txt(
+        &self,
+        resolver: &TokioAsyncResolver,
+        seed: &str,
+        pubkey: &PublicKey,
+        addrs: &mut Vec<Multiaddr>,
+    ) {
+        debug!("query txt records from: {}", seed);
+        match resolver.txt_lookup(seed).await {
+            Ok(records) => {
+                for record in records.iter() {
+                    for inner in record.iter() {
+                        match std::str::from_utf8(inner) {
+                            Ok(record) => match SeedRecord::decode_with_pubkey(&record, pubkey) {
+                                Ok(seed_record) => {
+                                    let address = seed_record.address();
+                                    trace!("got dns txt address: {}", address);
+                                    addrs.push(address);
+                                }
+                                Err(err) => {
+                                    debug!("decode dns txt record failed: {:?}, {:?}", err, record);
+                                }
+                            },
+                            Err(err) => {
+                                debug!("get dns txt record error: {:?}", err);
+                            }
+                        }
+                    }
+                }
+            }
+            Err(_) => {
+                warn!("Invalid domain name: {}", seed);
+            }
+        }
+    }
+
+    async fn lookup_ip(
+        &self,
+        resolver: &TokioAsyncResolver,
+        host: &str,
+        port: u16,
+        addrs: &mut Vec<Multiaddr>,
+    ) {
+        debug!("query ip records from: {}", host);
+        match resolver.lookup_ip(host).await {
+            Ok(ips) => {
+                for ip in ips.iter().filter(|ip| is_reachable(*ip)) {
+                    let address = socketaddr_to_multiaddr(SocketAddr::new(ip, port));
+                    trace!("got dns ip address: {}", address);
+                    addrs.push(address);
+                }
+            }
+            Err(_) => {
+                warn!("Invalid domain name: {}", host);
+            }
+        }
+    }
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<PublicKey, String> {
+    let pubkey = pubkey.trim_start_matches("0x");
+    let mut pubkey_bytes = [4u8; 65];
+    hex_decode(pubkey.as_bytes(), &mut pubkey_bytes[1..65])
+        .map_err(|err| format!("parse key({}) error: {:?}", pubkey, err))?;
+    PublicKey::from_slice(&pubkey_bytes)
+        .map_err(|err| format!("create PublicKey failed: {:?}", err))
+}
+
+// Splits `host:port` or `[ipv6]:port`, returns `None` when the seed is a plain domain name or
+// is not in these forms, such as a bare IPv6 address.
+fn split_host_port(seed: &str) -> Option<(&str, u16)> {
+    if let Ok(addr) = seed.parse::<SocketAddr>() {
+        let host = &seed[..seed.rfind(':')?];
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        return Some((host, addr.port()));
+    }
+    let index = seed.rfind(':')?;
+    let host = &seed[..index];
+    if host.is_empty() || host.contains(':') || host.contains('[') {
+        return None;
+    }
+    let port = seed[index + 1..].parse().ok()?;
+    Some((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_host_port;
+
+    #[test]
+    fn test_split_host_port() {
+        assert_eq!(split_host_port("seed.ckb.dev"), None);
+        assert_eq!(
+            split_host_port("seed.ckb.dev:8115"),
+            Some(("seed.ckb.dev", 8115))
+        );
+        assert_eq!(split_host_port("seed.ckb.dev:x"), None);
+        assert_eq!(split_host_port(":8115"), None);
+    }
+
+    #[test]
+    fn test_split_ip_port() {
+        assert_eq!(
+            split_host_port("47.110.15.57:8115"),
+            Some(("47.110.15.57", 8115))
+        );
+        assert_eq!(
+            split_host_port("[2001:db8::1]:8115"),
+            Some(("2001:db8::1", 8115))
+        );
+        assert_eq!(split_host_port("[::1]:8115"), Some(("::1", 8115)));
+        // The bare IPv6 addresses are ambiguous
+        assert_eq!(split_host_port("2001:db8::1"), None);
+        assert_eq!(split_host_port("2001:db8::1:8115"), None);
+        assert_eq!(split_host_port("::1"), None);
+        assert_eq!(split_host_port("[2001:db8::1]"), None);
+    }
 }
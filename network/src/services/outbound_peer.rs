@@ -81,6 +81,10 @@ impl OutboundPeerService {
         }
     }
 
+    fn try_dial_seeds(&self) {
+        self.network_state.try_dial_seed_addrs(&self.p2p_control);
+    }
+
     fn try_dial_observed(&self) {
         self.network_state
             .try_dial_observed_addrs(&self.p2p_control);
@@ -119,6 +123,10 @@ impl Future for OutboundPeerService {
                         }
                         // keep whitelist peer on connected
                         self.try_dial_whitelist();
+                        // try dial the seeds without peer ids
+                        if !self.network_state.config.whitelist_only {
+                            self.try_dial_seeds();
+                        }
                         // try dial observed addrs
                         self.try_dial_observed();
                         self.last_connect = Some(Instant::now());
@@ -1,4 +1,5 @@
 mod addr_manager;
+mod observed_addrs;
 mod peer_registry;
 mod peer_store;
 mod peer_store_db;
@@ -0,0 +1,92 @@
+use crate::{
+    multiaddr::Multiaddr,
+    network::{MAX_UNCONFIRMED_OBSERVED_ADDRS, MAX_UNCONFIRMED_OBSERVED_ADDRS_PER_PEER},
+    NetworkState, PeerId,
+};
+use ckb_app_config::NetworkConfig;
+
+fn network_state() -> NetworkState {
+    let config = NetworkConfig {
+        path: tempfile::tempdir()
+            .expect("create tempdir failed")
+            .path()
+            .to_path_buf(),
+        ..Default::default()
+    };
+    NetworkState::from_config(config).expect("Init network state failed")
+}
+
+fn observed_addr(port: usize) -> Multiaddr {
+    format!("/ip4/1.1.1.1/tcp/{}", port).parse().unwrap()
+}
+
+#[test]
+fn test_confirm_observed_addr() {
+    let network_state = network_state();
+    let addr = observed_addr(8115);
+
+    network_state.add_observed_addrs(&PeerId::random(), vec![addr.clone()].into_iter());
+    assert!(network_state
+        .unconfirmed_observed_addrs
+        .read()
+        .contains_key(&addr));
+    assert!(network_state.pending_observed_addrs.read().is_empty());
+
+    network_state.add_observed_addrs(&PeerId::random(), vec![addr.clone()].into_iter());
+    assert!(network_state.unconfirmed_observed_addrs.read().is_empty());
+    assert!(network_state.pending_observed_addrs.read().contains(&addr));
+}
+
+#[test]
+fn test_limit_unconfirmed_observed_addrs_per_peer() {
+    let network_state = network_state();
+    let peer = PeerId::random();
+
+    let addrs = (0..MAX_UNCONFIRMED_OBSERVED_ADDRS_PER_PEER + 2).map(observed_addr);
+    network_state.add_observed_addrs(&peer, addrs);
+    assert_eq!(
+        network_state.unconfirmed_observed_addrs.read().len(),
+        MAX_UNCONFIRMED_OBSERVED_ADDRS_PER_PEER
+    );
+
+    // the peer can still confirm the addresses reported by the others
+    let other_addr = observed_addr(8115);
+    network_state.add_observed_addrs(&PeerId::random(), vec![other_addr.clone()].into_iter());
+    network_state.add_observed_addrs(&peer, vec![other_addr.clone()].into_iter());
+    assert!(network_state
+        .pending_observed_addrs
+        .read()
+        .contains(&other_addr));
+}
+
+#[test]
+fn test_evict_observed_addr_of_busiest_peer() {
+    let network_state = network_state();
+    let busy_peer = PeerId::random();
+    let busy_addrs: Vec<_> = (0..MAX_UNCONFIRMED_OBSERVED_ADDRS_PER_PEER)
+        .map(observed_addr)
+        .collect();
+    network_state.add_observed_addrs(&busy_peer, busy_addrs.clone().into_iter());
+    let honest_addr = observed_addr(8115);
+    network_state.add_observed_addrs(&PeerId::random(), vec![honest_addr.clone()].into_iter());
+
+    // fill the unconfirmed addresses by the other peers
+    let start = busy_addrs.len();
+    let full = MAX_UNCONFIRMED_OBSERVED_ADDRS - start - 1;
+    for port in 10_000..10_000 + full + 2 {
+        network_state.add_observed_addrs(&PeerId::random(), vec![observed_addr(port)].into_iter());
+    }
+
+    // only the addresses of the busiest peer are dropped
+    let unconfirmed_observed_addrs = network_state.unconfirmed_observed_addrs.read();
+    assert_eq!(
+        unconfirmed_observed_addrs.len(),
+        MAX_UNCONFIRMED_OBSERVED_ADDRS
+    );
+    assert!(unconfirmed_observed_addrs.contains_key(&honest_addr));
+    let busy_left = busy_addrs
+        .iter()
+        .filter(|addr| unconfirmed_observed_addrs.contains_key(addr))
+        .count();
+    assert_eq!(busy_left, busy_addrs.len() - 2);
+}
@@ -5,7 +5,7 @@ use crate::{
     multiaddr::Multiaddr,
     peer_registry::{PeerRegistry, EVICTION_PROTECT_PEERS},
     peer_store::PeerStore,
-    PeerId, SessionType,
+    PeerId, ProtocolMessageStats, SessionType, SupportProtocols,
 };
 use std::time::{Duration, Instant};
 
@@ -42,6 +42,85 @@ fn test_accept_inbound_peer_in_reserve_only_mode() {
         .expect("accept");
 }
 
+#[test]
+fn test_accept_peer_in_allowlist_mode() {
+    let mut peer_store = PeerStore::default();
+    let whitelist_addr = format!("/ip4/127.0.0.1/tcp/43/p2p/{}", PeerId::random().to_base58())
+        .parse::<Multiaddr>()
+        .unwrap();
+    let allowed_addr = random_addr();
+    let allowlist = vec![extract_peer_id(&allowed_addr).unwrap()]
+        .into_iter()
+        .collect();
+
+    // allowlist mode: only accept the allowed peers and the whitelist peers
+    let mut peers = PeerRegistry::new(3, 3, false, vec![whitelist_addr.clone()])
+        .with_allowlist(Some(allowlist));
+    let err = peers
+        .accept_peer(
+            random_addr(),
+            1.into(),
+            SessionType::Inbound,
+            &mut peer_store,
+        )
+        .unwrap_err();
+    assert_eq!(
+        format!("{}", err),
+        format!("{}", Error::Peer(PeerError::NotAllowlisted))
+    );
+
+    peers
+        .accept_peer(
+            allowed_addr,
+            2.into(),
+            SessionType::Outbound,
+            &mut peer_store,
+        )
+        .expect("accept");
+    peers
+        .accept_peer(
+            whitelist_addr,
+            3.into(),
+            SessionType::Inbound,
+            &mut peer_store,
+        )
+        .expect("accept");
+}
+
+#[test]
+fn test_accept_protected_peer() {
+    let mut peer_store = PeerStore::default();
+    let protected_addr = random_addr();
+    let protected_peer_id = extract_peer_id(&protected_addr).unwrap();
+
+    let mut peers = PeerRegistry::new(1, 1, false, vec![])
+        .with_protected_peers(vec![protected_peer_id.clone()].into_iter().collect());
+    peers
+        .accept_peer(
+            random_addr(),
+            1.into(),
+            SessionType::Inbound,
+            &mut peer_store,
+        )
+        .expect("accept");
+    peer_store.ban_addr(&protected_addr, 10_000, "test".into());
+    // neither banned nor counted in the inbound limit
+    peers
+        .accept_peer(
+            protected_addr,
+            2.into(),
+            SessionType::Inbound,
+            &mut peer_store,
+        )
+        .expect("accept");
+    assert!(peers.get_peer(2.into()).unwrap().is_protected);
+    assert_eq!(peers.connection_status().non_whitelist_inbound, 1);
+
+    peers.set_peer_protection(protected_peer_id, false);
+    assert!(!peers.get_peer(2.into()).unwrap().is_protected);
+    assert_eq!(peers.connection_status().non_whitelist_inbound, 2);
+}
+
 #[test]
 fn test_accept_inbound_peer_until_full() {
     let mut peer_store = PeerStore::default();
@@ -219,3 +298,48 @@ fn test_accept_inbound_peer_eviction() {
     // should evict from one of evict_targets
     assert_eq!(len_after_eviction, evict_targets.len() - 1);
 }
+
+#[test]
+fn test_message_stats_reset_on_reconnect() {
+    let mut peer_store = PeerStore::default();
+    let mut peers = PeerRegistry::new(3, 3, false, vec![]);
+    let addr = random_addr();
+    let sync = SupportProtocols::Sync.protocol_id();
+    let relay = SupportProtocols::Relay.protocol_id();
+
+    peers
+        .accept_peer(
+            addr.clone(),
+            1.into(),
+            SessionType::Outbound,
+            &mut peer_store,
+        )
+        .expect("accept");
+    // The clones handed out to the protocols share the statistics
+    let peer = peers.get_peer(1.into()).cloned().expect("get peer");
+    peer.record_received_message(sync, 100);
+    peer.record_received_message(sync, 20);
+    peer.record_sent_message(sync, 5);
+
+    let stats = peers.get_peer(1.into()).unwrap().message_stats(sync);
+    assert_eq!(stats.received_messages, 2);
+    assert_eq!(stats.received_bytes, 120);
+    assert_eq!(stats.sent_messages, 1);
+    assert_eq!(stats.sent_bytes, 5);
+    assert!(stats.last_received_at.is_some());
+    assert!(stats.last_sent_at.is_some());
+    assert_eq!(
+        peers.get_peer(1.into()).unwrap().message_stats(relay),
+        ProtocolMessageStats::default()
+    );
+
+    // The same peer reconnects on a new session
+    peers.remove_peer(1.into());
+    peers
+        .accept_peer(addr, 2.into(), SessionType::Outbound, &mut peer_store)
+        .expect("accept");
+    assert_eq!(
+        peers.get_peer(2.into()).unwrap().message_stats(sync),
+        ProtocolMessageStats::default()
+    );
+}
@@ -2,7 +2,10 @@ use super::random_addr;
 use crate::{
     extract_peer_id,
     multiaddr::Multiaddr,
-    peer_store::{PeerStore, Status, ADDR_COUNT_LIMIT},
+    peer_store::{
+        misbehavior::MisbehaviorScores, PeerScoreConfig, PeerStore, ReportResult, Status,
+        ADDR_COUNT_LIMIT,
+    },
     Behaviour, PeerId, SessionType,
 };
 
@@ -35,6 +38,94 @@ fn test_report() {
     assert!(peer_store.report(&addr, Behaviour::TestGood).is_ok());
 }
 
+#[test]
+fn test_report_ban() {
+    let mut peer_store: PeerStore = Default::default();
+    let addr = random_addr();
+    // an inbound peer which is not in the address manager
+    peer_store
+        .add_connected_peer(addr.clone(), SessionType::Inbound)
+        .unwrap();
+    for _ in 0..6 {
+        assert_eq!(
+            peer_store.report(&addr, Behaviour::TestBad).unwrap(),
+            ReportResult::Ok
+        );
+    }
+    assert_eq!(peer_store.misbehavior_score(&addr), 40);
+    assert_eq!(
+        peer_store.report(&addr, Behaviour::TestBad).unwrap(),
+        ReportResult::Banned
+    );
+    assert!(peer_store.is_addr_banned(&addr));
+    assert_eq!(peer_store.misbehavior_score(&addr), 100);
+}
+
+#[test]
+fn test_misbehavior_score_recovery() {
+    let config = PeerScoreConfig::default();
+    let mut scores = MisbehaviorScores::default();
+    let network = "127.0.0.1/32".parse().unwrap();
+    let hour_ms = 3600 * 1000;
+
+    assert_eq!(scores.update(network, -30, &config, 0), 70);
+    assert_eq!(scores.score(&network, &config, hour_ms / 2), 75);
+    assert_eq!(scores.score(&network, &config, hour_ms), 80);
+    assert_eq!(scores.update(network, -30, &config, 2 * hour_ms), 60);
+    assert_eq!(scores.score(&network, &config, 100 * hour_ms), 100);
+}
+
+#[test]
+fn test_misbehavior_score_recovery_between_frequent_updates() {
+    let config = PeerScoreConfig::default();
+    let mut scores = MisbehaviorScores::default();
+    let network = "127.0.0.1/32".parse().unwrap();
+    let minute_ms = 60 * 1000;
+
+    assert_eq!(scores.update(network, -30, &config, 0), 70);
+    // A point recovers every 6 minutes, the updates every 5 minutes keep the fractions
+    for i in 1..=12 {
+        scores.update(network, 0, &config, i * 5 * minute_ms);
+    }
+    assert_eq!(scores.score(&network, &config, 60 * minute_ms), 80);
+    assert_eq!(scores.score(&network, &config, 66 * minute_ms), 81);
+}
+
+#[test]
+fn test_occasional_too_many_requests_never_ban() {
+    let config = PeerScoreConfig::default();
+    let mut scores = MisbehaviorScores::default();
+    let network = "127.0.0.1/32".parse().unwrap();
+    let minute_ms = 60 * 1000;
+
+    // Trips the rate limit every 10 minutes for two days
+    for i in 0..2 * 24 * 6 {
+        let score = scores.update(
+            network,
+            Behaviour::TooManyRequests.score(),
+            &config,
+            i * 10 * minute_ms,
+        );
+        assert!(score > config.ban_score, "score {} at {}", score, i);
+    }
+
+    // A burst of rate limited requests is tolerated as well
+    let mut peer_store: PeerStore = Default::default();
+    let addr = random_addr();
+    peer_store
+        .add_connected_peer(addr.clone(), SessionType::Inbound)
+        .unwrap();
+    for _ in 0..50 {
+        assert_eq!(
+            peer_store
+                .report(&addr, Behaviour::TooManyRequests)
+                .unwrap(),
+            ReportResult::Ok
+        );
+    }
+    assert!(!peer_store.is_addr_banned(&addr));
+}
+
 #[test]
 fn test_update_status() {
     let mut peer_store: PeerStore = Default::default();
@@ -121,6 +212,26 @@ fn test_fetch_addrs_to_feeler() {
     assert!(peer_store.fetch_addrs_to_feeler(1).is_empty());
 }
 
+#[test]
+fn test_fetch_addrs_to_probe() {
+    let mut peer_store: PeerStore = Default::default();
+    assert!(peer_store.fetch_addrs_to_probe(1).is_empty());
+    let addr = random_addr();
+    peer_store.add_addr(addr.clone()).unwrap();
+    assert_eq!(peer_store.fetch_addrs_to_probe(2).len(), 1);
+
+    // removes the addr which never connected after the retries
+    for _ in 0..3 {
+        peer_store
+            .mut_addr_manager()
+            .get_mut(&addr)
+            .unwrap()
+            .mark_tried(0);
+    }
+    assert!(peer_store.fetch_addrs_to_probe(1).is_empty());
+    assert_eq!(peer_store.addr_manager().count(), 0);
+}
+
 #[test]
 fn test_fetch_random_addrs() {
     let mut peer_store: PeerStore = Default::default();
@@ -1,17 +1,28 @@
 //! TODO(doc): @quake
 use ckb_app_config::NotifyConfig;
-use ckb_channel::{bounded, select, Receiver, RecvError, Sender};
-use ckb_logger::{debug, error, trace};
+use ckb_channel::{bounded, select, RecvError, Sender};
+use ckb_logger::{debug, trace};
 use ckb_stop_handler::{SignalSender, StopHandler};
 use ckb_types::{
-    core::{service::Request, tx_pool::Reject, BlockView},
+    core::{tx_pool::Reject, BlockView, HeaderView},
     packed::Alert,
+    prelude::*,
 };
-use std::collections::HashMap;
-use std::process::Command;
+use script::{ScriptKind, ScriptRunner};
 use std::thread;
+use subscription::Subscribers;
+use webhook::WebhookDispatcher;
+
+mod script;
+mod subscription;
+#[cfg(test)]
+mod tests;
+pub mod topic;
+mod webhook;
 
 pub use ckb_types::core::service::PoolTransactionEntry;
+pub use subscription::{RegisterCommand, SubscriberInfo, Subscription, SubscriptionId};
+pub use topic::Topic;
 
 /// TODO(doc): @quake
 pub const SIGNAL_CHANNEL_SIZE: usize = 1;
@@ -20,23 +31,68 @@ pub const REGISTER_CHANNEL_SIZE: usize = 2;
 /// TODO(doc): @quake
 pub const NOTIFY_CHANNEL_SIZE: usize = 128;
 
-/// TODO(doc): @quake
-pub type NotifyRegister<M> = Sender<Request<String, Receiver<M>>>;
+/// The reason why a noticed network alert is removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertRemovedReason {
+    /// The alert is cancelled by another alert.
+    Cancelled,
+    /// The alert passes its `notice_until` time.
+    Expired,
+}
+
+impl AlertRemovedReason {
+    /// The reason passed to the notify scripts and the webhooks.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AlertRemovedReason::Cancelled => "cancelled",
+            AlertRemovedReason::Expired => "expired",
+        }
+    }
+}
+
+/// An alert raised by the node itself, such as the local clock skew.
+///
+/// Unlike the network alerts, it is neither signed nor relayed, and it is not listed in the noticed
+/// network alerts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalAlert {
+    /// The alert message.
+    pub message: String,
+    /// The alert is noticed until this time, in milliseconds since UNIX epoch.
+    pub notice_until: u64,
+}
+
+// The chain events, which share one channel so they are handled in the order the chain emits them.
+enum ChainEvent {
+    NewBlock(BlockView),
+    Reorg(Vec<HeaderView>, Vec<HeaderView>),
+}
+
+/// The channel to register the subscribers of the topic with the message `M`.
+pub type NotifyRegister<M> = Sender<RegisterCommand<M>>;
 
 /// TODO(doc): @quake
 #[derive(Clone)]
 pub struct NotifyController {
     stop: StopHandler<()>,
     new_block_register: NotifyRegister<BlockView>,
-    new_block_notifier: Sender<BlockView>,
+    chain_event_notifier: Sender<ChainEvent>,
     new_transaction_register: NotifyRegister<PoolTransactionEntry>,
     new_transaction_notifier: Sender<PoolTransactionEntry>,
+    gap_transaction_register: NotifyRegister<PoolTransactionEntry>,
+    gap_transaction_notifier: Sender<PoolTransactionEntry>,
     proposed_transaction_register: NotifyRegister<PoolTransactionEntry>,
     proposed_transaction_notifier: Sender<PoolTransactionEntry>,
+    committed_transaction_register: NotifyRegister<(PoolTransactionEntry, HeaderView)>,
+    committed_transaction_notifier: Sender<(PoolTransactionEntry, HeaderView)>,
     reject_transaction_register: NotifyRegister<(PoolTransactionEntry, Reject)>,
     reject_transaction_notifier: Sender<(PoolTransactionEntry, Reject)>,
     network_alert_register: NotifyRegister<Alert>,
     network_alert_notifier: Sender<Alert>,
+    network_alert_removed_register: NotifyRegister<(Alert, AlertRemovedReason)>,
+    network_alert_removed_notifier: Sender<(Alert, AlertRemovedReason)>,
+    local_alert_register: NotifyRegister<LocalAlert>,
+    local_alert_notifier: Sender<LocalAlert>,
 }
 
 impl Drop for NotifyController {
@@ -48,11 +104,17 @@ impl Drop for NotifyController {
 /// TODO(doc): @quake
 pub struct NotifyService {
     config: NotifyConfig,
-    new_block_subscribers: HashMap<String, Sender<BlockView>>,
-    new_transaction_subscribers: HashMap<String, Sender<PoolTransactionEntry>>,
-    proposed_transaction_subscribers: HashMap<String, Sender<PoolTransactionEntry>>,
-    reject_transaction_subscribers: HashMap<String, Sender<(PoolTransactionEntry, Reject)>>,
-    network_alert_subscribers: HashMap<String, Sender<Alert>>,
+    // The subscription ids are unique across all the topics.
+    next_subscription_id: u64,
+    new_block_subscribers: Subscribers<BlockView>,
+    new_transaction_subscribers: Subscribers<PoolTransactionEntry>,
+    gap_transaction_subscribers: Subscribers<PoolTransactionEntry>,
+    proposed_transaction_subscribers: Subscribers<PoolTransactionEntry>,
+    committed_transaction_subscribers: Subscribers<(PoolTransactionEntry, HeaderView)>,
+    reject_transaction_subscribers: Subscribers<(PoolTransactionEntry, Reject)>,
+    network_alert_subscribers: Subscribers<Alert>,
+    network_alert_removed_subscribers: Subscribers<(Alert, AlertRemovedReason)>,
+    local_alert_subscribers: Subscribers<LocalAlert>,
 }
 
 impl NotifyService {
@@ -60,11 +122,16 @@ impl NotifyService {
     pub fn new(config: NotifyConfig) -> Self {
         Self {
             config,
-            new_block_subscribers: HashMap::default(),
-            new_transaction_subscribers: HashMap::default(),
-            proposed_transaction_subscribers: HashMap::default(),
-            reject_transaction_subscribers: HashMap::default(),
-            network_alert_subscribers: HashMap::default(),
+            next_subscription_id: 0,
+            new_block_subscribers: Subscribers::new(topic::NewBlock::NAME),
+            new_transaction_subscribers: Subscribers::new(topic::NewTransaction::NAME),
+            gap_transaction_subscribers: Subscribers::new(topic::GapTransaction::NAME),
+            proposed_transaction_subscribers: Subscribers::new(topic::ProposedTransaction::NAME),
+            committed_transaction_subscribers: Subscribers::new(topic::CommittedTransaction::NAME),
+            reject_transaction_subscribers: Subscribers::new(topic::RejectTransaction::NAME),
+            network_alert_subscribers: Subscribers::new(topic::NetworkAlert::NAME),
+            network_alert_removed_subscribers: Subscribers::new(topic::NetworkAlertRemoved::NAME),
+            local_alert_subscribers: Subscribers::new(topic::LocalAlert::NAME),
         }
     }
 
@@ -73,19 +140,33 @@ impl NotifyService {
     #[allow(clippy::zero_ptr, clippy::drop_copy)]
     pub fn start<S: ToString>(mut self, thread_name: Option<S>) -> NotifyController {
         let (signal_sender, signal_receiver) = bounded(SIGNAL_CHANNEL_SIZE);
+        // The workers of the scripts and the webhooks hold the clones, so the stop waits until
+        // they have drained the queued jobs.
+        let (done, done_receiver) = bounded(0);
+        let script_runner = ScriptRunner::new(&self.config, &done);
+        let webhook_dispatcher = WebhookDispatcher::new(&self.config.webhooks, &done);
 
         let (new_block_register, new_block_register_receiver) = bounded(REGISTER_CHANNEL_SIZE);
-        let (new_block_sender, new_block_receiver) = bounded(NOTIFY_CHANNEL_SIZE);
+        let (chain_event_sender, chain_event_receiver) = bounded(NOTIFY_CHANNEL_SIZE);
 
         let (new_transaction_register, new_transaction_register_receiver) =
             bounded(REGISTER_CHANNEL_SIZE);
         let (new_transaction_sender, new_transaction_receiver) = bounded(NOTIFY_CHANNEL_SIZE);
 
+        let (gap_transaction_register, gap_transaction_register_receiver) =
+            bounded(REGISTER_CHANNEL_SIZE);
+        let (gap_transaction_sender, gap_transaction_receiver) = bounded(NOTIFY_CHANNEL_SIZE);
+
         let (proposed_transaction_register, proposed_transaction_register_receiver) =
             bounded(REGISTER_CHANNEL_SIZE);
         let (proposed_transaction_sender, proposed_transaction_receiver) =
             bounded(NOTIFY_CHANNEL_SIZE);
 
+        let (committed_transaction_register, committed_transaction_register_receiver) =
+            bounded(REGISTER_CHANNEL_SIZE);
+        let (committed_transaction_sender, committed_transaction_receiver) =
+            bounded(NOTIFY_CHANNEL_SIZE);
+
         let (reject_transaction_register, reject_transaction_register_receiver) =
             bounded(REGISTER_CHANNEL_SIZE);
         let (reject_transaction_sender, reject_transaction_receiver) = bounded(NOTIFY_CHANNEL_SIZE);
@@ -94,101 +175,102 @@ impl NotifyService {
             bounded(REGISTER_CHANNEL_SIZE);
         let (network_alert_sender, network_alert_receiver) = bounded(NOTIFY_CHANNEL_SIZE);
 
+        let (network_alert_removed_register, network_alert_removed_register_receiver) =
+            bounded(REGISTER_CHANNEL_SIZE);
+        let (network_alert_removed_sender, network_alert_removed_receiver) =
+            bounded(NOTIFY_CHANNEL_SIZE);
+
+        let (local_alert_register, local_alert_register_receiver) = bounded(REGISTER_CHANNEL_SIZE);
+        let (local_alert_sender, local_alert_receiver) = bounded(NOTIFY_CHANNEL_SIZE);
+
         let mut thread_builder = thread::Builder::new();
         if let Some(name) = thread_name {
             thread_builder = thread_builder.name(name.to_string());
         }
-        let join_handle = thread_builder
-            .spawn(move || loop {
-                select! {
-                    recv(signal_receiver) -> _ => {
-                        break;
+        thread_builder
+            .spawn(move || {
+                let _done = done;
+                loop {
+                    select! {
+                        recv(signal_receiver) -> _ => {
+                            break;
+                        }
+                        recv(new_block_register_receiver) -> msg => self.new_block_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(chain_event_receiver) -> msg => self.handle_notify_chain_event(msg, &script_runner, webhook_dispatcher.as_ref()),
+                        recv(new_transaction_register_receiver) -> msg => self.new_transaction_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(new_transaction_receiver) -> msg => self.handle_notify_new_transaction(msg),
+                        recv(gap_transaction_register_receiver) -> msg => self.gap_transaction_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(gap_transaction_receiver) -> msg => self.handle_notify_gap_transaction(msg),
+                        recv(proposed_transaction_register_receiver) -> msg => self.proposed_transaction_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(proposed_transaction_receiver) -> msg => self.handle_notify_proposed_transaction(msg),
+                        recv(committed_transaction_register_receiver) -> msg => self.committed_transaction_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(committed_transaction_receiver) -> msg => self.handle_notify_committed_transaction(msg),
+                        recv(reject_transaction_register_receiver) -> msg => self.reject_transaction_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(reject_transaction_receiver) -> msg => self.handle_notify_reject_transaction(msg),
+                        recv(network_alert_register_receiver) -> msg => self.network_alert_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(network_alert_receiver) -> msg => self.handle_notify_network_alert(msg, &script_runner, webhook_dispatcher.as_ref()),
+                        recv(network_alert_removed_register_receiver) -> msg => self.network_alert_removed_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(network_alert_removed_receiver) -> msg => self.handle_notify_network_alert_removed(msg, &script_runner, webhook_dispatcher.as_ref()),
+                        recv(local_alert_register_receiver) -> msg => self.local_alert_subscribers.handle_register(msg, &mut self.next_subscription_id),
+                        recv(local_alert_receiver) -> msg => self.handle_notify_local_alert(msg, &script_runner),
                     }
-                    recv(new_block_register_receiver) -> msg => self.handle_register_new_block(msg),
-                    recv(new_block_receiver) -> msg => self.handle_notify_new_block(msg),
-                    recv(new_transaction_register_receiver) -> msg => self.handle_register_new_transaction(msg),
-                    recv(new_transaction_receiver) -> msg => self.handle_notify_new_transaction(msg),
-                    recv(proposed_transaction_register_receiver) -> msg => self.handle_register_proposed_transaction(msg),
-                    recv(proposed_transaction_receiver) -> msg => self.handle_notify_proposed_transaction(msg),
-                    recv(reject_transaction_register_receiver) -> msg => self.handle_register_reject_transaction(msg),
-                    recv(reject_transaction_receiver) -> msg => self.handle_notify_reject_transaction(msg),
-                    recv(network_alert_register_receiver) -> msg => self.handle_register_network_alert(msg),
-                    recv(network_alert_receiver) -> msg => self.handle_notify_network_alert(msg),
                 }
             })
             .expect("Start notify service failed");
 
         NotifyController {
             new_block_register,
-            new_block_notifier: new_block_sender,
+            chain_event_notifier: chain_event_sender,
             new_transaction_register,
             new_transaction_notifier: new_transaction_sender,
+            gap_transaction_register,
+            gap_transaction_notifier: gap_transaction_sender,
             proposed_transaction_register,
             proposed_transaction_notifier: proposed_transaction_sender,
+            committed_transaction_register,
+            committed_transaction_notifier: committed_transaction_sender,
             reject_transaction_register,
             reject_transaction_notifier: reject_transaction_sender,
             network_alert_register,
             network_alert_notifier: network_alert_sender,
-            stop: StopHandler::new(SignalSender::Crossbeam(signal_sender), Some(join_handle)),
+            network_alert_removed_register,
+            network_alert_removed_notifier: network_alert_removed_sender,
+            local_alert_register,
+            local_alert_notifier: local_alert_sender,
+            stop: StopHandler::with_done(SignalSender::Crossbeam(signal_sender), done_receiver),
         }
     }
 
-    fn handle_register_new_block(
+    fn handle_notify_chain_event(
         &mut self,
-        msg: Result<Request<String, Receiver<BlockView>>, RecvError>,
+        msg: Result<ChainEvent, RecvError>,
+        script_runner: &ScriptRunner,
+        webhook_dispatcher: Option<&WebhookDispatcher>,
     ) {
         match msg {
-            Ok(Request {
-                responder,
-                arguments: name,
-            }) => {
-                debug!("Register new_block {:?}", name);
-                let (sender, receiver) = bounded(NOTIFY_CHANNEL_SIZE);
-                self.new_block_subscribers.insert(name, sender);
-                let _ = responder.send(receiver);
-            }
-            _ => debug!("Register new_block channel is closed"),
-        }
-    }
-
-    fn handle_notify_new_block(&mut self, msg: Result<BlockView, RecvError>) {
-        match msg {
-            Ok(block) => {
+            Ok(ChainEvent::NewBlock(block)) => {
                 trace!("event new block {:?}", block);
                 // notify all subscribers
-                for subscriber in self.new_block_subscribers.values() {
-                    let _ = subscriber.send(block.clone());
-                }
+                self.new_block_subscribers.notify(&block);
                 // notify script
                 if let Some(script) = self.config.new_block_notify_script.as_ref() {
-                    let args = [format!("{:#x}", block.hash())];
-                    if let Err(err) = Command::new(script).args(&args).status() {
-                        error!(
-                            "failed to run new_block_notify_script: {} {}, error: {}",
-                            script, args[0], err
-                        );
-                    }
+                    script_runner.submit(
+                        ScriptKind::NewBlock,
+                        script,
+                        format!("{:#x}", block.hash()),
+                    );
+                }
+                if let Some(dispatcher) = webhook_dispatcher {
+                    dispatcher.block_accepted(&block);
                 }
             }
-            _ => debug!("new block channel is closed"),
-        }
-    }
-
-    fn handle_register_new_transaction(
-        &mut self,
-        msg: Result<Request<String, Receiver<PoolTransactionEntry>>, RecvError>,
-    ) {
-        match msg {
-            Ok(Request {
-                responder,
-                arguments: name,
-            }) => {
-                debug!("Register new_transaction {:?}", name);
-                let (sender, receiver) = bounded(NOTIFY_CHANNEL_SIZE);
-                self.new_transaction_subscribers.insert(name, sender);
-                let _ = responder.send(receiver);
+            Ok(ChainEvent::Reorg(detached, attached)) => {
+                trace!("event reorg {:?} {:?}", detached, attached);
+                if let Some(dispatcher) = webhook_dispatcher {
+                    dispatcher.reorg(&detached, &attached);
+                }
             }
-            _ => debug!("Register new_transaction channel is closed"),
+            _ => debug!("chain event channel is closed"),
         }
     }
 
@@ -197,29 +279,20 @@ impl NotifyService {
             Ok(tx_entry) => {
                 trace!("event new tx {:?}", tx_entry);
                 // notify all subscribers
-                for subscriber in self.new_transaction_subscribers.values() {
-                    let _ = subscriber.send(tx_entry.clone());
-                }
+                self.new_transaction_subscribers.notify(&tx_entry);
             }
             _ => debug!("new transaction channel is closed"),
         }
     }
 
-    fn handle_register_proposed_transaction(
-        &mut self,
-        msg: Result<Request<String, Receiver<PoolTransactionEntry>>, RecvError>,
-    ) {
+    fn handle_notify_gap_transaction(&mut self, msg: Result<PoolTransactionEntry, RecvError>) {
         match msg {
-            Ok(Request {
-                responder,
-                arguments: name,
-            }) => {
-                debug!("Register proposed_transaction {:?}", name);
-                let (sender, receiver) = bounded(NOTIFY_CHANNEL_SIZE);
-                self.proposed_transaction_subscribers.insert(name, sender);
-                let _ = responder.send(receiver);
+            Ok(tx_entry) => {
+                trace!("event gap tx {:?}", tx_entry);
+                // notify all subscribers
+                self.gap_transaction_subscribers.notify(&tx_entry);
             }
-            _ => debug!("Register proposed_transaction channel is closed"),
+            _ => debug!("gap transaction channel is closed"),
         }
     }
 
@@ -228,29 +301,23 @@ impl NotifyService {
             Ok(tx_entry) => {
                 trace!("event proposed tx {:?}", tx_entry);
                 // notify all subscribers
-                for subscriber in self.proposed_transaction_subscribers.values() {
-                    let _ = subscriber.send(tx_entry.clone());
-                }
+                self.proposed_transaction_subscribers.notify(&tx_entry);
             }
             _ => debug!("proposed transaction channel is closed"),
         }
     }
 
-    fn handle_register_reject_transaction(
+    fn handle_notify_committed_transaction(
         &mut self,
-        msg: Result<Request<String, Receiver<(PoolTransactionEntry, Reject)>>, RecvError>,
+        msg: Result<(PoolTransactionEntry, HeaderView), RecvError>,
     ) {
         match msg {
-            Ok(Request {
-                responder,
-                arguments: name,
-            }) => {
-                debug!("Register reject_transaction {:?}", name);
-                let (sender, receiver) = bounded(NOTIFY_CHANNEL_SIZE);
-                self.reject_transaction_subscribers.insert(name, sender);
-                let _ = responder.send(receiver);
+            Ok(tx_entry) => {
+                trace!("event committed tx {:?}", tx_entry);
+                // notify all subscribers
+                self.committed_transaction_subscribers.notify(&tx_entry);
             }
-            _ => debug!("Register reject_transaction channel is closed"),
+            _ => debug!("committed transaction channel is closed"),
         }
     }
 
@@ -262,81 +329,118 @@ impl NotifyService {
             Ok(tx_entry) => {
                 trace!("event reject tx {:?}", tx_entry);
                 // notify all subscribers
-                for subscriber in self.reject_transaction_subscribers.values() {
-                    let _ = subscriber.send(tx_entry.clone());
-                }
+                self.reject_transaction_subscribers.notify(&tx_entry);
             }
             _ => debug!("reject transaction channel is closed"),
         }
     }
 
-    fn handle_register_network_alert(
+    fn handle_notify_network_alert(
         &mut self,
-        msg: Result<Request<String, Receiver<Alert>>, RecvError>,
+        msg: Result<Alert, RecvError>,
+        script_runner: &ScriptRunner,
+        webhook_dispatcher: Option<&WebhookDispatcher>,
     ) {
-        match msg {
-            Ok(Request {
-                responder,
-                arguments: name,
-            }) => {
-                debug!("Register network_alert {:?}", name);
-                let (sender, receiver) = bounded(NOTIFY_CHANNEL_SIZE);
-                self.network_alert_subscribers.insert(name, sender);
-                let _ = responder.send(receiver);
-            }
-            _ => debug!("Register network_alert channel is closed"),
-        }
-    }
-
-    fn handle_notify_network_alert(&mut self, msg: Result<Alert, RecvError>) {
         match msg {
             Ok(alert) => {
                 trace!("event network alert {:?}", alert);
                 // notify all subscribers
-                for subscriber in self.network_alert_subscribers.values() {
-                    let _ = subscriber.send(alert.clone());
-                }
+                self.network_alert_subscribers.notify(&alert);
                 // notify script
                 if let Some(script) = self.config.network_alert_notify_script.as_ref() {
-                    let args = [alert
+                    let message = alert
                         .as_reader()
                         .raw()
                         .message()
                         .as_utf8()
                         .expect("alert message should be utf8")
-                        .to_owned()];
-                    if let Err(err) = Command::new(script).args(&args).status() {
-                        error!(
-                            "failed to run network_alert_notify_script: {} {}, error: {}",
-                            script, args[0], err
-                        );
-                    }
+                        .to_owned();
+                    script_runner.submit(ScriptKind::NetworkAlert, script, message);
+                }
+                if let Some(dispatcher) = webhook_dispatcher {
+                    dispatcher.alert(&alert);
                 }
             }
             _ => debug!("network alert channel is closed"),
         }
     }
+
+    fn handle_notify_network_alert_removed(
+        &mut self,
+        msg: Result<(Alert, AlertRemovedReason), RecvError>,
+        script_runner: &ScriptRunner,
+        webhook_dispatcher: Option<&WebhookDispatcher>,
+    ) {
+        match msg {
+            Ok((alert, reason)) => {
+                trace!("event network alert removed {:?} {:?}", alert, reason);
+                // notify all subscribers
+                self.network_alert_removed_subscribers
+                    .notify(&(alert.clone(), reason));
+                // notify script
+                if let Some(script) = self.config.network_alert_removed_notify_script.as_ref() {
+                    let id: u32 = alert.raw().id().unpack();
+                    script_runner.submit_args(
+                        ScriptKind::NetworkAlertRemoved,
+                        script,
+                        vec![id.to_string(), reason.as_str().to_owned()],
+                    );
+                }
+                if let Some(dispatcher) = webhook_dispatcher {
+                    dispatcher.alert_removed(&alert, reason);
+                }
+            }
+            _ => debug!("network alert removed channel is closed"),
+        }
+    }
+
+    fn handle_notify_local_alert(
+        &mut self,
+        msg: Result<LocalAlert, RecvError>,
+        script_runner: &ScriptRunner,
+    ) {
+        match msg {
+            Ok(alert) => {
+                trace!("event local alert {:?}", alert);
+                // notify all subscribers
+                self.local_alert_subscribers.notify(&alert);
+                // notify script, which only receives the message
+                if let Some(script) = self.config.network_alert_notify_script.as_ref() {
+                    script_runner.submit(ScriptKind::NetworkAlert, script, alert.message);
+                }
+            }
+            _ => debug!("local alert channel is closed"),
+        }
+    }
 }
 
 impl NotifyController {
-    /// TODO(doc): @quake
-    pub fn subscribe_new_block<S: ToString>(&self, name: S) -> Receiver<BlockView> {
-        Request::call(&self.new_block_register, name.to_string())
-            .expect("Subscribe new block should be OK")
+    /// Stops the notify service, even if the controller is still cloned elsewhere.
+    pub fn force_stop(&self) {
+        self.stop.force_send(());
     }
 
-    /// TODO(doc): @quake
-    pub fn notify_new_block(&self, block: BlockView) {
-        let _ = self.new_block_notifier.send(block);
+    /// Subscribes to the topic `T`.
+    ///
+    /// The `name` identifies the subscriber in the diagnostics, it is not required to be unique.
+    /// The subscription is removed when it is unsubscribed or dropped.
+    pub fn subscribe<T: Topic>(&self, name: &str) -> Subscription<T::Message> {
+        let register = T::register(self);
+        let (id, receiver) =
+            RegisterCommand::call(register, name.to_owned(), RegisterCommand::Subscribe)
+                .unwrap_or_else(|| panic!("Subscribe {} should be OK", T::NAME));
+        Subscription::new(id, receiver, register.clone())
+    }
+
+    /// Lists the subscribers of the topic `T`, in the order of the subscription.
+    pub fn subscribers<T: Topic>(&self) -> Vec<SubscriberInfo> {
+        RegisterCommand::call(T::register(self), (), RegisterCommand::ListSubscribers)
+            .unwrap_or_else(|| panic!("List {} subscribers should be OK", T::NAME))
     }
 
     /// TODO(doc): @quake
-    pub fn subscribe_new_transaction<S: ToString>(
-        &self,
-        name: S,
-    ) -> Receiver<PoolTransactionEntry> {
-        Request::call(&self.new_transaction_register, name.to_string())
-            .expect("Subscribe new transaction should be OK")
+    pub fn notify_new_block(&self, block: BlockView) {
+        let _ = self.chain_event_notifier.send(ChainEvent::NewBlock(block));
     }
 
     /// TODO(doc): @quake
@@ -344,13 +448,9 @@ impl NotifyController {
         let _ = self.new_transaction_notifier.send(tx_entry);
     }
 
-    /// TODO(doc): @quake
-    pub fn subscribe_proposed_transaction<S: ToString>(
-        &self,
-        name: S,
-    ) -> Receiver<PoolTransactionEntry> {
-        Request::call(&self.proposed_transaction_register, name.to_string())
-            .expect("Subscribe proposed transaction should be OK")
+    /// Notifies that an in-pool transaction enters the gap pool.
+    pub fn notify_gap_transaction(&self, tx_entry: PoolTransactionEntry) {
+        let _ = self.gap_transaction_notifier.send(tx_entry);
     }
 
     /// TODO(doc): @quake
@@ -358,13 +458,9 @@ impl NotifyController {
         let _ = self.proposed_transaction_notifier.send(tx_entry);
     }
 
-    /// TODO(doc): @quake
-    pub fn subscribe_reject_transaction<S: ToString>(
-        &self,
-        name: S,
-    ) -> Receiver<(PoolTransactionEntry, Reject)> {
-        Request::call(&self.reject_transaction_register, name.to_string())
-            .expect("Subscribe rejected transaction should be OK")
+    /// Notifies that an in-pool transaction is committed in the block with `header`.
+    pub fn notify_committed_transaction(&self, tx_entry: PoolTransactionEntry, header: HeaderView) {
+        let _ = self.committed_transaction_notifier.send((tx_entry, header));
     }
 
     /// TODO(doc): @quake
@@ -372,14 +468,26 @@ impl NotifyController {
         let _ = self.reject_transaction_notifier.send((tx_entry, reject));
     }
 
-    /// TODO(doc): @quake
-    pub fn subscribe_network_alert<S: ToString>(&self, name: S) -> Receiver<Alert> {
-        Request::call(&self.network_alert_register, name.to_string())
-            .expect("Subscribe network alert should be OK")
-    }
-
     /// TODO(doc): @quake
     pub fn notify_network_alert(&self, alert: Alert) {
         let _ = self.network_alert_notifier.send(alert);
     }
+
+    /// Notifies that a noticed network alert is cancelled or expired.
+    pub fn notify_network_alert_removed(&self, alert: Alert, reason: AlertRemovedReason) {
+        let _ = self.network_alert_removed_notifier.send((alert, reason));
+    }
+
+    /// Notifies an alert raised by the node itself.
+    pub fn notify_local_alert(&self, alert: LocalAlert) {
+        let _ = self.local_alert_notifier.send(alert);
+    }
+
+    /// Notifies that the main chain switches to another fork, with the headers of the detached
+    /// and the attached blocks in ascending order.
+    pub fn notify_reorg(&self, detached: Vec<HeaderView>, attached: Vec<HeaderView>) {
+        let _ = self
+            .chain_event_notifier
+            .send(ChainEvent::Reorg(detached, attached));
+    }
 }
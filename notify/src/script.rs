@@ -0,0 +1,205 @@
+//! Run notify scripts in a bounded pool of worker threads.
+use ckb_app_config::NotifyConfig;
+use ckb_channel::{bounded, Receiver, Sender, TrySendError};
+use ckb_logger::{debug, error, warn};
+use ckb_metrics::metrics;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::{env, thread};
+
+// The environment variables always passed to the scripts.
+const DEFAULT_KEEP_ENV: &[&str] = &["PATH"];
+
+/// The kind of the notify script, used in the logs and metrics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum ScriptKind {
+    NewBlock,
+    NetworkAlert,
+    NetworkAlertRemoved,
+}
+
+impl ScriptKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ScriptKind::NewBlock => "new_block_notify_script",
+            ScriptKind::NetworkAlert => "network_alert_notify_script",
+            ScriptKind::NetworkAlertRemoved => "network_alert_removed_notify_script",
+        }
+    }
+}
+
+struct ScriptJob {
+    kind: ScriptKind,
+    script: String,
+    args: Vec<String>,
+}
+
+/// Runs the notify scripts with a concurrency limit.
+///
+/// The scripts are queued when all the workers are busy, and dropped when the queue is full.
+pub(crate) struct ScriptRunner {
+    sender: Sender<ScriptJob>,
+    failures: Arc<HashMap<ScriptKind, AtomicU64>>,
+}
+
+impl ScriptRunner {
+    /// Starts the workers, each holds a clone of `done` until it exits.
+    pub(crate) fn new(config: &NotifyConfig, done: &Sender<()>) -> Self {
+        let (sender, receiver) = bounded(config.script_queue_size);
+        let failures: Arc<HashMap<_, _>> = Arc::new(
+            [
+                ScriptKind::NewBlock,
+                ScriptKind::NetworkAlert,
+                ScriptKind::NetworkAlertRemoved,
+            ]
+            .iter()
+            .map(|kind| (*kind, AtomicU64::new(0)))
+            .collect(),
+        );
+        let envs: Vec<(String, String)> = DEFAULT_KEEP_ENV
+            .iter()
+            .map(|name| name.to_string())
+            .chain(config.script_keep_env.iter().cloned())
+            .filter_map(|name| env::var(&name).ok().map(|value| (name, value)))
+            .collect();
+
+        for i in 0..config.script_concurrency.max(1) {
+            let receiver: Receiver<ScriptJob> = receiver.clone();
+            let failures = Arc::clone(&failures);
+            let envs = envs.clone();
+            let wrapper = config.script_wrapper.clone();
+            let done = done.clone();
+            thread::Builder::new()
+                .name(format!("NotifyScript-{}", i))
+                .spawn(move || {
+                    let _done = done;
+                    // exits when the runner is dropped
+                    for job in receiver {
+                        if !run_script(&job, &wrapper, &envs) {
+                            let count = failures[&job.kind].fetch_add(1, Ordering::Relaxed) + 1;
+                            metrics!(counter, "ckb-notify.script_failure", 1, "script" => job.kind.as_str());
+                            debug!("{} failed {} times", job.kind.as_str(), count);
+                        }
+                    }
+                })
+                .expect("Start notify script worker failed");
+        }
+
+        ScriptRunner { sender, failures }
+    }
+
+    /// Queues the script to run with the argument.
+    pub(crate) fn submit(&self, kind: ScriptKind, script: &str, arg: String) {
+        self.submit_args(kind, script, vec![arg]);
+    }
+
+    /// Queues the script to run with the arguments.
+    pub(crate) fn submit_args(&self, kind: ScriptKind, script: &str, args: Vec<String>) {
+        let job = ScriptJob {
+            kind,
+            script: script.to_owned(),
+            args,
+        };
+        if let Err(TrySendError::Full(job)) = self.sender.try_send(job) {
+            warn!(
+                "notify script queue is full, drop {}: {} {}",
+                job.kind.as_str(),
+                job.script,
+                job.args.join(" ")
+            );
+            metrics!(counter, "ckb-notify.script_dropped", 1, "script" => job.kind.as_str());
+        }
+    }
+
+    /// Returns the number of failed runs of the script.
+    #[cfg(test)]
+    pub(crate) fn failures(&self, kind: ScriptKind) -> u64 {
+        self.failures[&kind].load(Ordering::Relaxed)
+    }
+}
+
+// Returns whether the script exits successfully.
+fn run_script(job: &ScriptJob, wrapper: &[String], envs: &[(String, String)]) -> bool {
+    let mut command = match wrapper.split_first() {
+        Some((program, args)) => {
+            let mut command = Command::new(program);
+            command.args(args).arg(&job.script);
+            command
+        }
+        None => Command::new(&job.script),
+    };
+    command
+        .args(&job.args)
+        .env_clear()
+        .envs(envs.iter().cloned())
+        .stdin(Stdio::null());
+
+    match command.status() {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            error!(
+                "{} exits with {}: {} {}",
+                job.kind.as_str(),
+                status,
+                job.script,
+                job.args.join(" ")
+            );
+            false
+        }
+        Err(err) => {
+            error!(
+                "failed to run {}: {} {}, error: {}",
+                job.kind.as_str(),
+                job.script,
+                job.args.join(" "),
+                err
+            );
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn wait_failures(runner: &ScriptRunner, kind: ScriptKind, expected: u64) -> u64 {
+        let start = Instant::now();
+        while runner.failures(kind) < expected && start.elapsed() < Duration::from_secs(10) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        runner.failures(kind)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_count_script_failures() {
+        let runner = ScriptRunner::new(&NotifyConfig::default(), &bounded(0).0);
+        runner.submit(ScriptKind::NewBlock, "false", "0x0".to_owned());
+        runner.submit(
+            ScriptKind::NewBlock,
+            "not-exist-notify-script",
+            "0x0".to_owned(),
+        );
+        runner.submit(ScriptKind::NetworkAlert, "true", "alert".to_owned());
+
+        assert_eq!(wait_failures(&runner, ScriptKind::NewBlock, 2), 2);
+        assert_eq!(runner.failures(ScriptKind::NetworkAlert), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wrap_script() {
+        let config = NotifyConfig {
+            script_wrapper: vec!["sh".to_owned(), "-c".to_owned(), "exit 1".to_owned()],
+            ..Default::default()
+        };
+        let runner = ScriptRunner::new(&config, &bounded(0).0);
+        runner.submit(ScriptKind::NetworkAlert, "true", "alert".to_owned());
+
+        assert_eq!(wait_failures(&runner, ScriptKind::NetworkAlert, 1), 1);
+    }
+}
@@ -0,0 +1,180 @@
+//! The subscriptions of the notify topics.
+use crate::{NotifyRegister, NOTIFY_CHANNEL_SIZE};
+use ckb_channel::{bounded, Receiver, RecvError, Sender};
+use ckb_logger::debug;
+use ckb_types::core::service::Request;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::mpsc;
+
+/// The unique id of a subscription, which is never reused in the notify service.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubscriptionId(u64);
+
+impl fmt::Display for SubscriptionId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The subscriber of a topic, for the diagnostics.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubscriberInfo {
+    /// The subscription id.
+    pub id: SubscriptionId,
+    /// The name given by the subscriber.
+    pub name: String,
+    /// The number of the messages not received by the subscriber yet.
+    pub pending_messages: usize,
+}
+
+/// The commands sent to the notify service to manage the subscribers of a topic.
+pub enum RegisterCommand<M> {
+    /// Subscribes to the topic with the subscriber name.
+    Subscribe(Request<String, (SubscriptionId, Receiver<M>)>),
+    /// Removes the subscription.
+    Unsubscribe(SubscriptionId),
+    /// Lists the subscribers.
+    ListSubscribers(Request<(), Vec<SubscriberInfo>>),
+}
+
+impl<M> RegisterCommand<M> {
+    // Sends the command built from the request and waits for the response.
+    pub(crate) fn call<A, R>(
+        register: &NotifyRegister<M>,
+        arguments: A,
+        command: fn(Request<A, R>) -> Self,
+    ) -> Option<R> {
+        let (responder, response) = mpsc::channel();
+        let _ = register.send(command(Request {
+            responder,
+            arguments,
+        }));
+        response.recv().ok()
+    }
+}
+
+/// The handle of a subscription to a topic.
+///
+/// It dereferences to the receiver of the messages. The subscription is removed from the
+/// notify service when it is unsubscribed or dropped.
+pub struct Subscription<M> {
+    id: SubscriptionId,
+    receiver: Receiver<M>,
+    register: NotifyRegister<M>,
+}
+
+impl<M> Subscription<M> {
+    pub(crate) fn new(
+        id: SubscriptionId,
+        receiver: Receiver<M>,
+        register: NotifyRegister<M>,
+    ) -> Self {
+        Subscription {
+            id,
+            receiver,
+            register,
+        }
+    }
+
+    /// The subscription id.
+    pub fn id(&self) -> SubscriptionId {
+        self.id
+    }
+
+    /// The receiver of the messages.
+    pub fn receiver(&self) -> &Receiver<M> {
+        &self.receiver
+    }
+
+    /// Removes the subscription from the notify service.
+    pub fn unsubscribe(self) {
+        // the command is sent on drop
+    }
+}
+
+impl<M> Deref for Subscription<M> {
+    type Target = Receiver<M>;
+
+    fn deref(&self) -> &Receiver<M> {
+        &self.receiver
+    }
+}
+
+impl<M> Drop for Subscription<M> {
+    fn drop(&mut self) {
+        // Does not block when the register channel is full, the service removes the subscriber
+        // anyway once it finds the receiver dropped.
+        let _ = self
+            .register
+            .try_send(RegisterCommand::Unsubscribe(self.id));
+    }
+}
+
+// The subscribers of a topic in the notify service.
+pub(crate) struct Subscribers<M> {
+    topic: &'static str,
+    subscribers: HashMap<SubscriptionId, (String, Sender<M>)>,
+}
+
+impl<M: Clone> Subscribers<M> {
+    pub(crate) fn new(topic: &'static str) -> Self {
+        Subscribers {
+            topic,
+            subscribers: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn handle_register(
+        &mut self,
+        msg: Result<RegisterCommand<M>, RecvError>,
+        next_subscription_id: &mut u64,
+    ) {
+        match msg {
+            Ok(RegisterCommand::Subscribe(Request {
+                responder,
+                arguments: name,
+            })) => {
+                let id = SubscriptionId(*next_subscription_id);
+                *next_subscription_id += 1;
+                debug!("Register {} {:?} as subscription {}", self.topic, name, id);
+                let (sender, receiver) = bounded(NOTIFY_CHANNEL_SIZE);
+                self.subscribers.insert(id, (name, sender));
+                let _ = responder.send((id, receiver));
+            }
+            Ok(RegisterCommand::Unsubscribe(id)) => {
+                if let Some((name, _)) = self.subscribers.remove(&id) {
+                    debug!("Unregister {} {:?} subscription {}", self.topic, name, id);
+                }
+            }
+            Ok(RegisterCommand::ListSubscribers(Request { responder, .. })) => {
+                let mut subscribers: Vec<_> = self
+                    .subscribers
+                    .iter()
+                    .map(|(id, (name, sender))| SubscriberInfo {
+                        id: *id,
+                        name: name.clone(),
+                        pending_messages: sender.len(),
+                    })
+                    .collect();
+                subscribers.sort_by_key(|subscriber| subscriber.id);
+                let _ = responder.send(subscribers);
+            }
+            _ => debug!("Register {} channel is closed", self.topic),
+        }
+    }
+
+    // Sends the message to all the subscribers, and removes the ones whose receiver is dropped.
+    pub(crate) fn notify(&mut self, message: &M) {
+        let topic = self.topic;
+        self.subscribers.retain(|id, (name, sender)| {
+            if sender.send(message.clone()).is_ok() {
+                true
+            } else {
+                debug!("Remove dropped {} {:?} subscription {}", topic, name, id);
+                false
+            }
+        });
+    }
+}
@@ -0,0 +1,75 @@
+use crate::topic::{LocalAlert, NetworkAlert, NewBlock};
+use crate::{NotifyController, NotifyService};
+use ckb_types::{core::BlockBuilder, packed, prelude::*};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn start_service() -> NotifyController {
+    NotifyService::new(Default::default()).start(Some("test"))
+}
+
+#[test]
+fn test_subscribers_with_the_same_name() {
+    let notify_controller = start_service();
+    let subscription1 = notify_controller.subscribe::<NewBlock>("same");
+    let subscription2 = notify_controller.subscribe::<NewBlock>("same");
+    assert_ne!(subscription1.id(), subscription2.id());
+
+    let block = BlockBuilder::default().build();
+    notify_controller.notify_new_block(block.clone());
+    for subscription in &[&subscription1, &subscription2] {
+        assert_eq!(
+            subscription.recv_timeout(TIMEOUT).map(|block| block.hash()),
+            Ok(block.hash())
+        );
+    }
+
+    let subscribers = notify_controller.subscribers::<NewBlock>();
+    assert_eq!(
+        subscribers
+            .iter()
+            .map(|subscriber| (subscriber.id, subscriber.name.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(subscription1.id(), "same"), (subscription2.id(), "same")]
+    );
+    assert!(notify_controller.subscribers::<NetworkAlert>().is_empty());
+}
+
+#[test]
+fn test_unsubscribe() {
+    let notify_controller = start_service();
+    let subscription1 = notify_controller.subscribe::<NetworkAlert>("first");
+    let subscription2 = notify_controller.subscribe::<NetworkAlert>("second");
+    let id2 = subscription2.id();
+
+    subscription1.unsubscribe();
+    let subscribers = notify_controller.subscribers::<NetworkAlert>();
+    assert_eq!(subscribers.len(), 1);
+    assert_eq!(subscribers[0].id, id2);
+
+    let alert = packed::Alert::new_builder().build();
+    notify_controller.notify_network_alert(alert.clone());
+    assert_eq!(
+        subscription2
+            .recv_timeout(TIMEOUT)
+            .map(|alert| alert.as_bytes()),
+        Ok(alert.as_bytes())
+    );
+}
+
+#[test]
+fn test_local_alert() {
+    let notify_controller = start_service();
+    let network_alerts = notify_controller.subscribe::<NetworkAlert>("network");
+    let local_alerts = notify_controller.subscribe::<LocalAlert>("local");
+
+    let alert = crate::LocalAlert {
+        message: "local clock skew".to_owned(),
+        notice_until: 1,
+    };
+    notify_controller.notify_local_alert(alert.clone());
+    assert_eq!(local_alerts.recv_timeout(TIMEOUT), Ok(alert));
+    // The local alerts are not mixed with the network alerts
+    assert!(network_alerts.try_recv().is_err());
+}
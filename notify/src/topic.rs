@@ -0,0 +1,111 @@
+//! The typed topics of the notify service.
+//!
+//! Subscribe to a topic with [`NotifyController::subscribe`], the message type of the
+//! subscription is determined by the topic at compile time.
+//!
+//! [`NotifyController::subscribe`]: ../struct.NotifyController.html#method.subscribe
+use crate::{AlertRemovedReason, NotifyController, NotifyRegister, PoolTransactionEntry};
+use ckb_types::{
+    core::{tx_pool::Reject, BlockView, HeaderView},
+    packed::Alert,
+};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A topic of the notify service.
+///
+/// The trait is sealed, the topics are defined in this module.
+pub trait Topic: sealed::Sealed {
+    /// The message sent to the subscribers.
+    type Message: Clone + Send + 'static;
+    /// The topic name in the logs.
+    const NAME: &'static str;
+
+    #[doc(hidden)]
+    fn register(controller: &NotifyController) -> &NotifyRegister<Self::Message>;
+}
+
+macro_rules! define_topic {
+    ($(#[$doc:meta])* $topic:ident, $name:expr, $register:ident, $message:ty) => {
+        $(#[$doc])*
+        pub struct $topic;
+
+        impl sealed::Sealed for $topic {}
+
+        impl Topic for $topic {
+            type Message = $message;
+            const NAME: &'static str = $name;
+
+            fn register(controller: &NotifyController) -> &NotifyRegister<Self::Message> {
+                &controller.$register
+            }
+        }
+    };
+}
+
+define_topic!(
+    /// The new blocks appended to the main chain.
+    NewBlock,
+    "new_block",
+    new_block_register,
+    BlockView
+);
+define_topic!(
+    /// The transactions entering the tx-pool.
+    NewTransaction,
+    "new_transaction",
+    new_transaction_register,
+    PoolTransactionEntry
+);
+define_topic!(
+    /// The transactions which are proposed but not yet committable.
+    GapTransaction,
+    "gap_transaction",
+    gap_transaction_register,
+    PoolTransactionEntry
+);
+define_topic!(
+    /// The transactions which are proposed and committable.
+    ProposedTransaction,
+    "proposed_transaction",
+    proposed_transaction_register,
+    PoolTransactionEntry
+);
+define_topic!(
+    /// The in-pool transactions which are committed, along with the header of the block which
+    /// commits them.
+    CommittedTransaction,
+    "committed_transaction",
+    committed_transaction_register,
+    (PoolTransactionEntry, HeaderView)
+);
+define_topic!(
+    /// The transactions rejected by the tx-pool.
+    RejectTransaction,
+    "reject_transaction",
+    reject_transaction_register,
+    (PoolTransactionEntry, Reject)
+);
+define_topic!(
+    /// The network alerts.
+    NetworkAlert,
+    "network_alert",
+    network_alert_register,
+    Alert
+);
+define_topic!(
+    /// The noticed network alerts which are cancelled or expired.
+    NetworkAlertRemoved,
+    "network_alert_removed",
+    network_alert_removed_register,
+    (Alert, AlertRemovedReason)
+);
+define_topic!(
+    /// The alerts raised by the node itself.
+    LocalAlert,
+    "local_alert",
+    local_alert_register,
+    crate::LocalAlert
+);
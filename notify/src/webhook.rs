@@ -0,0 +1,430 @@
+//! Post the chain events to the webhooks.
+use crate::AlertRemovedReason;
+use ckb_app_config::{WebhookEvent, WebhooksConfig};
+use ckb_channel::{bounded, Receiver, Sender, TrySendError};
+use ckb_logger::{debug, error, warn};
+use ckb_metrics::metrics;
+use ckb_types::{
+    core::{BlockView, HeaderView},
+    packed::Alert,
+    prelude::*,
+};
+use hmac::{Hmac, Mac, NewMac};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// The delay before the first retry, which doubles after every retry.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+// The longest delay between two retries.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+fn event_name(event: WebhookEvent) -> &'static str {
+    match event {
+        WebhookEvent::BlockAccepted => "block_accepted",
+        WebhookEvent::Reorg => "reorg",
+        WebhookEvent::Alert => "alert",
+        WebhookEvent::AlertRemoved => "alert_removed",
+    }
+}
+
+struct WebhookJob {
+    event: WebhookEvent,
+    payload: String,
+    // the hex encoded HMAC-SHA256 of the payload
+    signature: Option<String>,
+}
+
+// The queue of the events to post to one webhook.
+struct WebhookQueue {
+    url: String,
+    sender: Sender<Arc<WebhookJob>>,
+}
+
+/// Posts the events to the webhooks, each webhook has its own queue and worker thread.
+///
+/// The events are posted to a webhook in order, a failed post is retried with an exponential
+/// backoff before the next event is posted to the same webhook, and it doesn't delay the other
+/// webhooks. The events are dropped when the queue of the webhook is full.
+pub(crate) struct WebhookDispatcher {
+    config: WebhooksConfig,
+    queues: Vec<WebhookQueue>,
+}
+
+impl WebhookDispatcher {
+    /// Starts the workers, returns `None` when there are no webhooks. The workers hold clones of
+    /// `done` until they exit.
+    pub(crate) fn new(config: &WebhooksConfig, done: &Sender<()>) -> Option<Self> {
+        if config.urls.is_empty() {
+            return None;
+        }
+        let client = Client::builder()
+            .timeout(Duration::from_millis(config.timeout))
+            .build()
+            .expect("Init webhook client failed");
+        let queues = config
+            .urls
+            .iter()
+            .enumerate()
+            .map(|(i, url)| {
+                let (sender, receiver) = bounded(config.queue_size);
+                let client = client.clone();
+                let worker_url = url.clone();
+                let max_retries = config.max_retries;
+                let done = done.clone();
+                thread::Builder::new()
+                    .name(format!("NotifyWebhook-{}", i))
+                    .spawn(move || {
+                        run_worker(&client, &worker_url, max_retries, receiver);
+                        drop(done);
+                    })
+                    .expect("Start notify webhook worker failed");
+                WebhookQueue {
+                    url: url.clone(),
+                    sender,
+                }
+            })
+            .collect();
+
+        Some(WebhookDispatcher {
+            config: config.clone(),
+            queues,
+        })
+    }
+
+    pub(crate) fn block_accepted(&self, block: &BlockView) {
+        if self.config.is_enabled(WebhookEvent::BlockAccepted) {
+            self.submit(WebhookEvent::BlockAccepted, block_accepted_payload(block));
+        }
+    }
+
+    pub(crate) fn reorg(&self, detached: &[HeaderView], attached: &[HeaderView]) {
+        if self.config.is_enabled(WebhookEvent::Reorg) {
+            self.submit(WebhookEvent::Reorg, reorg_payload(detached, attached));
+        }
+    }
+
+    pub(crate) fn alert(&self, alert: &Alert) {
+        if self.config.is_enabled(WebhookEvent::Alert) {
+            self.submit(WebhookEvent::Alert, alert_payload(alert));
+        }
+    }
+
+    pub(crate) fn alert_removed(&self, alert: &Alert, reason: AlertRemovedReason) {
+        if self.config.is_enabled(WebhookEvent::AlertRemoved) {
+            self.submit(
+                WebhookEvent::AlertRemoved,
+                alert_removed_payload(alert, reason),
+            );
+        }
+    }
+
+    fn submit(&self, event: WebhookEvent, payload: Value) {
+        let payload = payload.to_string();
+        let signature = self
+            .config
+            .secret
+            .as_ref()
+            .map(|secret| sign(secret.as_bytes(), payload.as_bytes()));
+        let job = Arc::new(WebhookJob {
+            event,
+            payload,
+            signature,
+        });
+        for queue in &self.queues {
+            if let Err(TrySendError::Full(job)) = queue.sender.try_send(Arc::clone(&job)) {
+                warn!(
+                    "notify webhook {} queue is full, drop {}: {}",
+                    queue.url,
+                    event_name(job.event),
+                    job.payload
+                );
+                metrics!(counter, "ckb-notify.webhook_dropped", 1, "event" => event_name(job.event));
+            }
+        }
+    }
+}
+
+fn run_worker(client: &Client, url: &str, max_retries: u32, receiver: Receiver<Arc<WebhookJob>>) {
+    // exits when the dispatcher is dropped
+    for job in receiver {
+        let mut delay = INITIAL_RETRY_DELAY;
+        let mut retries = 0;
+        while !post(client, url, &job) {
+            if retries >= max_retries {
+                error!(
+                    "give up posting {} to webhook {} after {} retries",
+                    event_name(job.event),
+                    url,
+                    retries
+                );
+                metrics!(counter, "ckb-notify.webhook_failure", 1, "event" => event_name(job.event));
+                break;
+            }
+            thread::sleep(delay);
+            delay = std::cmp::min(delay * 2, MAX_RETRY_DELAY);
+            retries += 1;
+        }
+    }
+}
+
+// Returns whether the webhook accepts the event.
+fn post(client: &Client, url: &str, job: &WebhookJob) -> bool {
+    let mut request = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("X-CKB-Event", event_name(job.event))
+        .body(job.payload.clone());
+    if let Some(ref signature) = job.signature {
+        request = request.header("X-CKB-Signature", format!("sha256={}", signature));
+    }
+    match request.send() {
+        Ok(response) if response.status().is_success() => true,
+        Ok(response) => {
+            debug!(
+                "webhook {} responds {} to {}",
+                url,
+                response.status(),
+                event_name(job.event)
+            );
+            false
+        }
+        Err(err) => {
+            debug!(
+                "failed to post {} to webhook {}, error: {}",
+                event_name(job.event),
+                url,
+                err
+            );
+            false
+        }
+    }
+}
+
+// Returns the hex encoded HMAC-SHA256 of the payload.
+fn sign(secret: &[u8], payload: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_varkey(secret).expect("HMAC accepts keys of any size");
+    mac.update(payload);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn header_json(header: &HeaderView) -> Value {
+    json!({
+        "number": header.number(),
+        "hash": format!("{:#x}", header.hash()),
+    })
+}
+
+fn block_accepted_payload(block: &BlockView) -> Value {
+    json!({
+        "event": event_name(WebhookEvent::BlockAccepted),
+        "number": block.number(),
+        "hash": format!("{:#x}", block.hash()),
+        "parent_hash": format!("{:#x}", block.parent_hash()),
+        "timestamp": block.timestamp(),
+        "transactions": block.transactions().len(),
+    })
+}
+
+fn reorg_payload(detached: &[HeaderView], attached: &[HeaderView]) -> Value {
+    json!({
+        "event": event_name(WebhookEvent::Reorg),
+        "detached": detached.iter().map(header_json).collect::<Vec<_>>(),
+        "attached": attached.iter().map(header_json).collect::<Vec<_>>(),
+    })
+}
+
+fn alert_payload(alert: &Alert) -> Value {
+    let raw = alert.raw();
+    let id: u32 = raw.id().unpack();
+    let priority: u32 = raw.priority().unpack();
+    let notice_until: u64 = raw.notice_until().unpack();
+    json!({
+        "event": event_name(WebhookEvent::Alert),
+        "id": id,
+        "priority": priority,
+        "notice_until": notice_until,
+        "message": String::from_utf8_lossy(&raw.message().raw_data()),
+    })
+}
+
+fn alert_removed_payload(alert: &Alert, reason: AlertRemovedReason) -> Value {
+    let id: u32 = alert.raw().id().unpack();
+    json!({
+        "event": event_name(WebhookEvent::AlertRemoved),
+        "id": id,
+        "reason": reason.as_str(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_channel::unbounded;
+    use ckb_types::core::{BlockBuilder, HeaderBuilder};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    // The event, the signature and the body of a received post.
+    type Received = (String, Option<String>, String);
+
+    // Serves the posts at a local address, sends the received posts to the returned receiver.
+    fn start_server() -> (String, Receiver<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ckb", listener.local_addr().unwrap());
+        let (sender, receiver) = unbounded();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let received = handle_post(stream.unwrap());
+                if sender.send(received).is_err() {
+                    break;
+                }
+            }
+        });
+        (url, receiver)
+    }
+
+    fn handle_post(mut stream: TcpStream) -> Received {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let (mut event, mut signature, mut content_length) = (String::new(), None, 0);
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(pos) = line.find(':') {
+                let (name, value) = (&line[..pos], line[pos + 1..].trim().to_owned());
+                match name.to_ascii_lowercase().as_str() {
+                    "x-ckb-event" => event = value,
+                    "x-ckb-signature" => signature = Some(value),
+                    "content-length" => content_length = value.parse().unwrap(),
+                    _ => {}
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        (event, signature, String::from_utf8(body).unwrap())
+    }
+
+    fn config(urls: Vec<String>) -> WebhooksConfig {
+        WebhooksConfig {
+            urls,
+            secret: Some("secret".to_owned()),
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_payloads() {
+        let block = BlockBuilder::default().number(10.pack()).build();
+        let payload = block_accepted_payload(&block);
+        assert_eq!(payload["event"], "block_accepted");
+        assert_eq!(payload["number"], 10);
+        assert_eq!(payload["hash"], format!("{:#x}", block.hash()));
+
+        let detached = HeaderBuilder::default().number(9.pack()).build();
+        let payload = reorg_payload(&[detached.clone()], &[block.header()]);
+        assert_eq!(payload["event"], "reorg");
+        assert_eq!(payload["detached"][0]["number"], 9);
+        assert_eq!(
+            payload["detached"][0]["hash"],
+            format!("{:#x}", detached.hash())
+        );
+        assert_eq!(payload["attached"][0]["number"], 10);
+    }
+
+    #[test]
+    fn test_enabled_events() {
+        let mut config = WebhooksConfig::default();
+        assert!(!config.is_enabled(WebhookEvent::Reorg));
+        config.urls.push("http://127.0.0.1:8000/ckb".to_owned());
+        assert!(config.is_enabled(WebhookEvent::Reorg));
+        config.events.push(WebhookEvent::Alert);
+        assert!(!config.is_enabled(WebhookEvent::Reorg));
+        assert!(config.is_enabled(WebhookEvent::Alert));
+    }
+
+    #[test]
+    fn test_post_in_order() {
+        let (url, received) = start_server();
+        let (done, _done_receiver) = bounded(1);
+        let dispatcher = WebhookDispatcher::new(&config(vec![url]), &done).unwrap();
+
+        let blocks: Vec<_> = (1..=3u64)
+            .map(|number| BlockBuilder::default().number(number.pack()).build())
+            .collect();
+        dispatcher.reorg(&[], &[blocks[0].header()]);
+        for block in &blocks {
+            dispatcher.block_accepted(block);
+        }
+
+        let (event, signature, body) = received.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(event, "reorg");
+        assert_eq!(
+            signature,
+            Some(format!("sha256={}", sign(b"secret", body.as_bytes())))
+        );
+        assert_eq!(body, reorg_payload(&[], &[blocks[0].header()]).to_string());
+        for block in &blocks {
+            let (event, signature, body) = received.recv_timeout(Duration::from_secs(10)).unwrap();
+            assert_eq!(event, "block_accepted");
+            assert_eq!(
+                signature,
+                Some(format!("sha256={}", sign(b"secret", body.as_bytes())))
+            );
+            assert_eq!(body, block_accepted_payload(block).to_string());
+        }
+    }
+
+    #[test]
+    fn test_stalled_webhook_does_not_block_others() {
+        // accepts the connections but never responds
+        let stalled = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stalled_url = format!("http://{}/ckb", stalled.local_addr().unwrap());
+        thread::spawn(move || {
+            let _streams: Vec<_> = stalled.incoming().collect();
+        });
+        // refuses the connections
+        let failing_url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/ckb", listener.local_addr().unwrap())
+        };
+        let (url, received) = start_server();
+        let (done, _done_receiver) = bounded(1);
+        let mut config = config(vec![stalled_url, failing_url, url]);
+        config.timeout = 60_000;
+        config.max_retries = 5;
+        let dispatcher = WebhookDispatcher::new(&config, &done).unwrap();
+
+        for number in 1..=3u64 {
+            let block = BlockBuilder::default().number(number.pack()).build();
+            dispatcher.block_accepted(&block);
+            let (event, _, body) = received.recv_timeout(Duration::from_secs(10)).unwrap();
+            assert_eq!(event, "block_accepted");
+            assert_eq!(body, block_accepted_payload(&block).to_string());
+        }
+    }
+}
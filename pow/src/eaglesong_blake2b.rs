@@ -1,4 +1,5 @@
 use super::PowEngine;
+use crate::eaglesong_simd::eaglesong_batch;
 use ckb_hash::blake2b_256;
 use ckb_types::{packed::Header, prelude::*, utilities::compact_to_target, U256};
 use eaglesong::eaglesong;
@@ -12,63 +13,77 @@ impl PowEngine for EaglesongBlake2bPowEngine {
     fn verify(&self, header: &Header) -> bool {
         let input =
             crate::pow_message(&header.as_reader().calc_pow_hash(), header.nonce().unpack());
-        let output = {
-            let mut output_tmp = [0u8; 32];
-            eaglesong(&input, &mut output_tmp);
-            blake2b_256(&output_tmp)
-        };
+        let mut output_tmp = [0u8; 32];
+        eaglesong(&input, &mut output_tmp);
+        verify_output(header, &input, output_tmp)
+    }
 
-        let (block_target, overflow) = compact_to_target(header.raw().compact_target().unpack());
+    fn verify_batch(&self, headers: &[Header]) -> Vec<bool> {
+        let inputs = headers
+            .iter()
+            .map(|header| {
+                crate::pow_message(&header.as_reader().calc_pow_hash(), header.nonce().unpack())
+            })
+            .collect::<Vec<_>>();
+        let outputs = eaglesong_batch(&inputs);
+        headers
+            .iter()
+            .zip(inputs.iter().zip(outputs))
+            .map(|(header, (input, output_tmp))| verify_output(header, input, output_tmp))
+            .collect()
+    }
+}
 
-        if block_target.is_zero() || overflow {
-            debug!(
-                "compact_target is invalid: {:#x}",
-                header.raw().compact_target()
-            );
-            return false;
-        }
+fn verify_output(header: &Header, input: &[u8; 48], output_tmp: [u8; 32]) -> bool {
+    let output = blake2b_256(&output_tmp);
 
-        if U256::from_big_endian(&output[..]).expect("bound checked") > block_target {
-            if log_enabled!(Debug) {
-                use ckb_types::bytes::Bytes;
+    let (block_target, overflow) = compact_to_target(header.raw().compact_target().unpack());
 
-                let mut output_tmp = [0u8; 32];
-                eaglesong(&input, &mut output_tmp);
+    if block_target.is_zero() || overflow {
+        debug!(
+            "compact_target is invalid: {:#x}",
+            header.raw().compact_target()
+        );
+        return false;
+    }
 
-                debug!(
-                    "PowEngine::verify error: expected target {:#x}, got {:#x}",
-                    block_target,
-                    U256::from_big_endian(&output[..]).unwrap()
-                );
+    if U256::from_big_endian(&output[..]).expect("bound checked") > block_target {
+        if log_enabled!(Debug) {
+            use ckb_types::bytes::Bytes;
 
-                debug!(
-                    "PowEngine::verify error: header raw 0x{:x}",
-                    &header.raw().as_bytes()
-                );
-                debug!(
-                    "PowEngine::verify error: pow hash {:#x}",
-                    &header.as_reader().calc_pow_hash()
-                );
-                debug!(
-                    "PowEngine::verify error: nonce {:#x}",
-                    header.nonce().unpack()
-                );
-                debug!(
-                    "PowEngine::verify error: pow input: 0x{:x}",
-                    Bytes::from(input.to_vec())
-                );
-                debug!(
-                    "PowEngine::verify error: eaglesong output: 0x{:#x}",
-                    Bytes::from(output_tmp.to_vec())
-                );
-                debug!(
-                    "PowEngine::verify error: pow output: 0x{:#x}",
-                    Bytes::from(output.to_vec())
-                );
-            }
-            return false;
-        }
+            debug!(
+                "PowEngine::verify error: expected target {:#x}, got {:#x}",
+                block_target,
+                U256::from_big_endian(&output[..]).unwrap()
+            );
 
-        true
+            debug!(
+                "PowEngine::verify error: header raw 0x{:x}",
+                &header.raw().as_bytes()
+            );
+            debug!(
+                "PowEngine::verify error: pow hash {:#x}",
+                &header.as_reader().calc_pow_hash()
+            );
+            debug!(
+                "PowEngine::verify error: nonce {:#x}",
+                header.nonce().unpack()
+            );
+            debug!(
+                "PowEngine::verify error: pow input: 0x{:x}",
+                Bytes::from(input.to_vec())
+            );
+            debug!(
+                "PowEngine::verify error: eaglesong output: 0x{:#x}",
+                Bytes::from(output_tmp.to_vec())
+            );
+            debug!(
+                "PowEngine::verify error: pow output: 0x{:#x}",
+                Bytes::from(output.to_vec())
+            );
+        }
+        return false;
     }
+
+    true
 }
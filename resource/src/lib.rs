@@ -49,6 +49,10 @@ include!(concat!(env!("OUT_DIR"), "/code_hashes.rs"));
 pub const CKB_CONFIG_FILE_NAME: &str = "ckb.toml";
 /// CKB miner config file name.
 pub const MINER_CONFIG_FILE_NAME: &str = "ckb-miner.toml";
+/// The optional CKB config file overlaid on `CKB_CONFIG_FILE_NAME`.
+pub const CKB_LOCAL_CONFIG_FILE_NAME: &str = "ckb.local.toml";
+/// The optional CKB miner config file overlaid on `MINER_CONFIG_FILE_NAME`.
+pub const MINER_LOCAL_CONFIG_FILE_NAME: &str = "ckb-miner.local.toml";
 /// The relative spec file path for the dev chain.
 pub const SPEC_DEV_FILE_NAME: &str = "specs/dev.toml";
 /// The file name of the generated RocksDB options file.
@@ -316,4 +320,46 @@ mod tests {
             .expect("export ckb.toml");
         assert!(Resource::exported_in(root_dir.path()));
     }
+
+    #[test]
+    fn test_export_with_optional_variables() {
+        let render = |kvs: Vec<(&'static str, &'static str)>| {
+            let root_dir = mkdir();
+            let mut context = TemplateContext::new(
+                "dev",
+                vec![
+                    ("rpc_port", "7000"),
+                    ("p2p_port", "8000"),
+                    ("log_to_file", "true"),
+                    ("log_to_stdout", "true"),
+                    ("block_assembler", ""),
+                    ("spec_source", "bundled"),
+                ],
+            );
+            for (key, value) in kvs {
+                context.insert(key, value);
+            }
+            Resource::bundled_ckb_config()
+                .export(&context, root_dir.path())
+                .expect("export ckb.toml");
+            fs::read_to_string(root_dir.path().join(CKB_CONFIG_FILE_NAME)).expect("read ckb.toml")
+        };
+
+        let default_config = render(Vec::new());
+        assert!(default_config.contains(
+            "\nmodules = [\"Net\", \"Pool\", \"Miner\", \"Chain\", \"Stats\", \"Subscription\", \"Experiment\", \"Debug\"]\n"
+        ));
+        assert!(default_config.contains("\n# [metrics.exporter.prometheus]\n"));
+
+        let config = render(vec![
+            ("rpc_modules", r#"["Net", "Chain"]"#),
+            ("metrics_exporter", "127.0.0.1:8100"),
+        ]);
+        assert!(config.contains("\nmodules = [\"Net\", \"Chain\"]\n"));
+        assert!(config.contains(
+            "\n[metrics.exporter.prometheus]\n\
+             target = { type = \"http\", listen_address = \"127.0.0.1:8100\" }\n\
+             format = { type = \"prometheus\" }\n"
+        ));
+    }
 }
@@ -10,6 +10,8 @@ pub const DEFAULT_P2P_PORT: &str = "8115";
 const START_MARKER: &str = " # {{";
 const END_MAKER: &str = "# }}";
 const WILDCARD_BRANCH: &str = "# _ => ";
+const VARIABLE_BRANCH: &str = "# ?";
+const BRANCH_SEPARATOR: &str = " => ";
 
 use std::collections::HashMap;
 use std::io;
@@ -27,11 +29,13 @@ use std::io;
 ///
 /// Between the start and end markers, every line is a branch starting with `# SPEC => CONTENT`, where
 /// `SPEC` is the branch spec name, and `CONTENT` is the text to be replaced for the spec.
-/// A special spec name `_` acts as a wildcard which matches any spec name.
+/// A special spec name `_` acts as a wildcard which matches any spec name, and a spec name `?VAR`
+/// matches when the variable `VAR` is defined in the context, see [Variable
+/// Branches](#variable-branches).
 ///
 /// The spec name is required to render the template, see [`Template::new`]. The block including
-/// the **whole** starting line which ends with ` # {{` will be replaced by the branch `CONTENT`
-/// which `SPEC` is `_` or equals to the given spec name.
+/// the **whole** starting line which ends with ` # {{` will be replaced by the first branch
+/// `CONTENT` which `SPEC` matches.
 ///
 /// In the `CONTENT`, variables are expanded and all the escape sequences `\n` are replaced by new
 /// lines.
@@ -75,6 +79,31 @@ use std::io;
 /// assert_eq!("listen_address = \"127.0.0.1:18114\"\n", text.unwrap());
 /// ```
 ///
+/// ### Variable Branches
+///
+/// A branch `# ?VAR => CONTENT` matches only when the variable `VAR` is defined in the context,
+/// so an optional variable overrides the default line, whatever the spec name is. The branches
+/// are tried in order, thus the variable branch should precede the spec branches it overrides.
+///
+/// ```
+/// use ckb_resource::{Template, TemplateContext};
+///
+/// let template = Template::new(
+///     r#"modules = ["Net"] # {{
+/// ## ?rpc_modules => modules = {rpc_modules}
+/// ## dev => modules = ["Net", "Debug"]
+/// ## }}"#
+///         .to_string(),
+/// );
+/// let text = template.render(&TemplateContext::new("dev", Vec::new()));
+/// assert_eq!("modules = [\"Net\", \"Debug\"]\n", text.unwrap());
+/// let text = template.render(&TemplateContext::new(
+///     "dev",
+///     vec![("rpc_modules", r#"["Chain"]"#)],
+/// ));
+/// assert_eq!("modules = [\"Chain\"]\n", text.unwrap());
+/// ```
+///
 /// [`TemplateContext`]: struct.TemplateContext.html
 /// [`TemplateContext::new`]: struct.TemplateContext.html#method_new
 /// [`TemplateContext::insert`]: struct.TemplateContext.html#method_insert
@@ -142,6 +171,17 @@ fn writeln<W: io::Write>(w: &mut W, s: &str, context: &TemplateContext) -> io::R
     )
 }
 
+// Returns the content of the variable branch `# ?VAR => CONTENT` if `VAR` is defined.
+fn variable_branch<'l>(line: &'l str, context: &TemplateContext) -> Option<&'l str> {
+    let branch = line.strip_prefix(VARIABLE_BRANCH)?;
+    let separator = branch.find(BRANCH_SEPARATOR)?;
+    if context.kvs.contains_key(&branch[..separator]) {
+        Some(&branch[(separator + BRANCH_SEPARATOR.len())..])
+    } else {
+        None
+    }
+}
+
 #[derive(Debug)]
 pub enum TemplateState<'a> {
     SearchStartMarker,
@@ -185,6 +225,9 @@ impl Template {
                     } else if line.starts_with(&spec_branch) {
                         writeln(w, &line[spec_branch.len()..], context)?;
                         state = TemplateState::SearchEndMarker;
+                    } else if let Some(c) = variable_branch(line, context) {
+                        writeln(w, c, context)?;
+                        state = TemplateState::SearchEndMarker;
                     } else if let Some(c) = line.strip_prefix(WILDCARD_BRANCH) {
                         writeln(w, c, context)?;
                         state = TemplateState::SearchEndMarker;
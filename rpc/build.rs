@@ -0,0 +1,324 @@
+//! Build script for crate `ckb-rpc` to generate the OpenRPC method objects from the RPC traits.
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const MODULE_DIR: &str = "src/module";
+const DEPRECATED_RPC_PREFIX: &str = "deprecated.";
+
+// The RPC module of each source file in `src/module`
+const MODULES: &[(&str, &str)] = &[
+    ("alert.rs", "Alert"),
+    ("chain.rs", "Chain"),
+    ("debug.rs", "Debug"),
+    ("experiment.rs", "Experiment"),
+    ("miner.rs", "Miner"),
+    ("net.rs", "Net"),
+    ("pool.rs", "Pool"),
+    ("stats.rs", "Stats"),
+    ("subscription.rs", "Subscription"),
+    ("test.rs", "IntegrationTest"),
+];
+
+// The types serialized as 0x-prefixed hex strings of integers
+const HEX_INTEGER_TYPES: &[&str] = &[
+    "AlertId",
+    "AlertPriority",
+    "BlockNumber",
+    "Capacity",
+    "Cycle",
+    "EpochNumber",
+    "EpochNumberWithFraction",
+    "Timestamp",
+    "Uint32",
+    "Uint64",
+    "Uint128",
+    "Version",
+];
+
+struct Method {
+    name: String,
+    docs: Vec<String>,
+    params: Vec<(String, String)>,
+    result: String,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", MODULE_DIR);
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("openrpc_methods.rs");
+    let mut out_file =
+        BufWriter::new(fs::File::create(&out_path).expect("create openrpc_methods.rs"));
+
+    writeln!(
+        &mut out_file,
+        "/// The OpenRPC method objects generated from the RPC traits, with their modules.\n\
+        pub(crate) const OPENRPC_METHODS: &[(RpcModule, &str)] = &["
+    )
+    .expect("write to openrpc_methods.rs");
+    for (file_name, module) in MODULES {
+        let path = Path::new(MODULE_DIR).join(file_name);
+        println!("cargo:rerun-if-changed={}", path.display());
+        let source = fs::read_to_string(&path).expect("read RPC module source");
+        for method in parse_methods(&source) {
+            writeln!(
+                &mut out_file,
+                "    (RpcModule::{}, {:?}),",
+                module,
+                method_json(&method, module)
+            )
+            .expect("write to openrpc_methods.rs");
+        }
+    }
+    writeln!(&mut out_file, "];").expect("write to openrpc_methods.rs");
+}
+
+// Collects the methods in the trait annotated with `#[rpc(server)]`.
+fn parse_methods(source: &str) -> Vec<Method> {
+    let mut methods = Vec::new();
+    let mut in_rpc_trait = false;
+    let mut docs = Vec::new();
+    let mut name: Option<String> = None;
+    let mut signature: Option<String> = None;
+
+    for line in source.lines() {
+        if !in_rpc_trait {
+            in_rpc_trait = line.starts_with("#[rpc(server)]");
+            continue;
+        }
+        if line == "}" {
+            break;
+        }
+
+        let trimmed = line.trim();
+        if let Some(sig) = signature.as_mut() {
+            sig.push(' ');
+            sig.push_str(trimmed);
+        } else if let Some(doc) = trimmed.strip_prefix("///") {
+            docs.push(doc.strip_prefix(' ').unwrap_or(doc).to_owned());
+            continue;
+        } else if trimmed.starts_with("#[rpc(") || trimmed.starts_with("#[pubsub(") {
+            name = attribute_name(trimmed);
+            continue;
+        } else if trimmed.is_empty()
+            || trimmed.starts_with("#[")
+            || trimmed.starts_with("pub trait")
+        {
+            // The doc comments still apply to the method after the blank lines and attributes
+            continue;
+        } else if trimmed.starts_with("fn ") {
+            signature = Some(trimmed.to_owned());
+        } else {
+            docs.clear();
+            continue;
+        }
+
+        let complete = signature
+            .as_ref()
+            .map(|sig| sig.ends_with(';'))
+            .unwrap_or(false);
+        if complete {
+            let sig = signature.take().unwrap();
+            if let Some(name) = name.take() {
+                let (params, result) = parse_signature(&sig);
+                methods.push(Method {
+                    name,
+                    docs: std::mem::take(&mut docs),
+                    params,
+                    result,
+                });
+            }
+            docs.clear();
+        }
+    }
+
+    methods
+}
+
+// Gets `name` in `#[rpc(name = "...")]` or `#[pubsub(..., name = "...")]`.
+fn attribute_name(attribute: &str) -> Option<String> {
+    let start = attribute.find("name = \"")? + "name = \"".len();
+    let len = attribute[start..].find('"')?;
+    Some(attribute[start..start + len].to_owned())
+}
+
+// Parses the params and the result type of the method signature.
+//
+// The receiver and the pubsub metadata and subscriber are skipped. The subscription methods
+// without the result type return the subscription id as a string.
+fn parse_signature(signature: &str) -> (Vec<(String, String)>, String) {
+    let open = signature.find('(').expect("method params");
+    let mut depth = 0;
+    let mut close = open;
+    for (i, c) in signature[open..].char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = open + i;
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let params = split_top_level(&signature[open + 1..close])
+        .into_iter()
+        .filter_map(|param| {
+            let mut parts = param.splitn(2, ':');
+            let name = parts.next()?.trim();
+            let ty = parts.next()?.trim();
+            if ty.contains("Self::Metadata") || ty.starts_with("Subscriber<") {
+                None
+            } else {
+                Some((name.to_owned(), ty.to_owned()))
+            }
+        })
+        .collect();
+
+    let rest = signature[close + 1..].trim().trim_end_matches(';').trim();
+    let result = rest
+        .strip_prefix("-> Result<")
+        .and_then(|ty| ty.strip_suffix('>'))
+        .unwrap_or("String")
+        .trim()
+        .to_owned();
+
+    (params, result)
+}
+
+// Splits the comma separated list without splitting the generic arguments.
+fn split_top_level(list: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut item = String::new();
+    for c in list.chars() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(item.trim().to_owned());
+                item.clear();
+                continue;
+            }
+            _ => {}
+        }
+        item.push(c);
+    }
+    if !item.trim().is_empty() {
+        items.push(item.trim().to_owned());
+    }
+    items
+}
+
+fn strip_generic<'a>(ty: &'a str, wrapper: &str) -> Option<&'a str> {
+    ty.strip_prefix(wrapper)
+        .and_then(|ty| ty.strip_prefix('<'))
+        .and_then(|ty| ty.strip_suffix('>'))
+        .map(str::trim)
+}
+
+fn schema_json(ty: &str) -> String {
+    if let Some(inner) = strip_generic(ty, "Option") {
+        return format!(
+            "{{\"oneOf\":[{},{{\"type\":\"null\"}}]}}",
+            schema_json(inner)
+        );
+    }
+    if let Some(inner) = strip_generic(ty, "Vec") {
+        return format!("{{\"type\":\"array\",\"items\":{}}}", schema_json(inner));
+    }
+    match ty {
+        "()" => "{\"type\":\"null\"}".to_owned(),
+        "bool" => "{\"type\":\"boolean\"}".to_owned(),
+        "String" | "SubscriptionId" => "{\"type\":\"string\"}".to_owned(),
+        "H256" => format!(
+            "{{\"title\":\"H256\",\"type\":\"string\",\"pattern\":{}}}",
+            json_string("^0x[0-9a-f]{64}$")
+        ),
+        "JsonBytes" => format!(
+            "{{\"title\":\"JsonBytes\",\"type\":\"string\",\"pattern\":{}}}",
+            json_string("^0x([0-9a-f]{2})*$")
+        ),
+        _ if HEX_INTEGER_TYPES.contains(&ty) => format!(
+            "{{\"title\":{},\"type\":\"string\",\"pattern\":{}}}",
+            json_string(ty),
+            json_string("^0x(0|[1-9a-f][0-9a-f]*)$")
+        ),
+        _ => format!("{{\"title\":{}}}", json_string(ty)),
+    }
+}
+
+fn method_json(method: &Method, module: &str) -> String {
+    let summary = method
+        .docs
+        .iter()
+        .take_while(|line| !line.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // The deprecated methods are mounted without the prefix
+    let (name, deprecated) = match method.name.strip_prefix(DEPRECATED_RPC_PREFIX) {
+        Some(name) => (name, true),
+        None => (method.name.as_str(), false),
+    };
+
+    let mut json = String::new();
+    write!(
+        json,
+        "{{\"name\":{},\"deprecated\":{},\"tags\":[{{\"name\":{}}}],\"summary\":{},\"description\":{},\"params\":[",
+        json_string(name),
+        deprecated,
+        json_string(module),
+        json_string(&summary),
+        json_string(&method.docs.join("\n")),
+    )
+    .unwrap();
+    for (i, (name, ty)) in method.params.iter().enumerate() {
+        let (required, ty) = match strip_generic(ty, "Option") {
+            Some(inner) => (false, inner),
+            None => (true, ty.as_str()),
+        };
+        if i > 0 {
+            json.push(',');
+        }
+        write!(
+            json,
+            "{{\"name\":{},\"required\":{},\"schema\":{}}}",
+            json_string(name),
+            required,
+            schema_json(ty)
+        )
+        .unwrap();
+    }
+    write!(
+        json,
+        "],\"result\":{{\"name\":\"result\",\"schema\":{}}}}}",
+        schema_json(&method.result)
+    )
+    .unwrap();
+    json
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
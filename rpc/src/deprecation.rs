@@ -0,0 +1,112 @@
+use serde_json::Value;
+
+/// A deprecated RPC method, or a field removed from the result of an RPC method.
+pub(crate) struct Deprecation {
+    pub method: &'static str,
+    // `None` when the whole method is deprecated
+    pub field: Option<&'static str>,
+    pub since: &'static str,
+    pub replacement: Option<&'static str>,
+}
+
+/// The deprecations in the current release.
+///
+/// * A deprecated method is mounted with the name prefix `deprecated.` and listed here.
+/// * A removed field is listed with the field replacing it in the same method result. The
+/// removed field is restored from the replacement when `rpc.enable_deprecated_rpc` is set.
+/// * A deprecated field without replacement is still served and only listed here, the field of
+/// a nested object is named by the dotted path.
+///
+/// The entries are deleted together with the methods and the fields after one release cycle.
+pub(crate) const DEPRECATIONS: &[Deprecation] = &[Deprecation {
+    method: "get_peers",
+    field: Some("sync_state.unknown_header_list_size"),
+    since: "0.100.0",
+    replacement: None,
+}];
+
+/// Finds the deprecation of the whole method.
+pub(crate) fn find_deprecated_method(method: &str) -> Option<&'static Deprecation> {
+    DEPRECATIONS
+        .iter()
+        .find(|deprecation| deprecation.method == method && deprecation.field.is_none())
+}
+
+/// Checks whether any field is removed from the result of the method and can be restored.
+pub(crate) fn has_removed_fields(method: &str) -> bool {
+    DEPRECATIONS.iter().any(|deprecation| {
+        deprecation.method == method
+            && deprecation.field.is_some()
+            && deprecation.replacement.is_some()
+    })
+}
+
+/// Restores the removed fields in the method result from their replacements.
+pub(crate) fn restore_removed_fields(
+    deprecations: &[Deprecation],
+    method: &str,
+    result: &mut Value,
+) {
+    if let Value::Object(object) = result {
+        for deprecation in deprecations.iter().filter(|d| d.method == method) {
+            if let (Some(field), Some(replacement)) = (deprecation.field, deprecation.replacement) {
+                if !object.contains_key(field) {
+                    if let Some(value) = object.get(replacement).cloned() {
+                        object.insert(field.to_owned(), value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl From<&Deprecation> for ckb_jsonrpc_types::Deprecation {
+    fn from(deprecation: &Deprecation) -> Self {
+        ckb_jsonrpc_types::Deprecation {
+            method: deprecation.method.to_owned(),
+            field: deprecation.field.map(ToOwned::to_owned),
+            since: deprecation.since.to_owned(),
+            replacement: deprecation.replacement.map(ToOwned::to_owned),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn restore_renamed_field() {
+        let deprecations = [Deprecation {
+            method: "get_fee_rate_statistics",
+            field: Some("mean_fee_rate"),
+            since: "0.100.0",
+            replacement: Some("mean"),
+        }];
+
+        let mut result = json!({ "mean": "0x3e8" });
+        restore_removed_fields(&deprecations, "get_fee_rate_statistics", &mut result);
+        assert_eq!(result, json!({ "mean": "0x3e8", "mean_fee_rate": "0x3e8" }));
+
+        let mut result = json!({ "mean": "0x3e8" });
+        restore_removed_fields(&deprecations, "get_blockchain_info", &mut result);
+        assert_eq!(result, json!({ "mean": "0x3e8" }));
+
+        let mut result = Value::Null;
+        restore_removed_fields(&deprecations, "get_fee_rate_statistics", &mut result);
+        assert_eq!(result, Value::Null);
+    }
+
+    #[test]
+    fn registered_deprecated_field() {
+        let deprecation = DEPRECATIONS
+            .iter()
+            .find(|d| d.field == Some("sync_state.unknown_header_list_size"))
+            .expect("unknown_header_list_size is deprecated");
+        assert_eq!(deprecation.method, "get_peers");
+        // it is still served as is, neither the method nor the result is wrapped
+        assert!(find_deprecated_method("get_peers").is_none());
+        assert!(!has_removed_fields("get_peers"));
+    }
+}
@@ -36,7 +36,9 @@ pub enum RPCError {
     /// (-2): The CKB method has been deprecated and disabled.
     ///
     /// Set `rpc.enable_deprecated_rpc` to `true` in the config file to enable all deprecated
-    /// methods.
+    /// methods. The error `data` is the [`Deprecation`] of the method when it is available.
+    ///
+    /// [`Deprecation`]: ../ckb_jsonrpc_types/struct.Deprecation.html
     Deprecated = -2,
     /// (-3): Error code -3 is no longer used.
     ///
@@ -56,6 +58,13 @@ pub enum RPCError {
     ///
     /// Users have to edit the config file to fix the error.
     ConfigError = -7,
+    /// (-8): The request is rejected because there are too many requests.
+    ///
+    /// The method exceeds the rate limit in the config option `rpc.rate_limits`, or the server is
+    /// handling `rpc.max_concurrent_requests` requests. Retry the request later.
+    RateLimited = -8,
+    /// (-9): The response exceeds the config option `rpc.max_response_size`.
+    ResponseTooLarge = -9,
     /// (-101): The CKB local node failed to broadcast a message to its peers.
     P2PFailedToBroadcast = -101,
     /// (-200): Internal database error.
@@ -88,7 +97,8 @@ pub enum RPCError {
     /// (-1103): Pool rejects some transactions which seem contain invalid VM instructions. See the issue
     /// link in the error message for details.
     PoolRejectedTransactionByIllTransactionChecker = -1103,
-    /// (-1104): The transaction fee rate must be greater than or equal to the config option `tx_pool.min_fee_rate`
+    /// (-1104): The transaction fee rate must be greater than or equal to the config option `tx_pool.min_fee_rate`,
+    /// which rises with the pool usage when `tx_pool.dynamic_min_fee_rate` is configured.
     ///
     /// The fee rate is calculated as:
     ///
@@ -1,6 +1,9 @@
 //! See [module](module/index.html) for the RPC methods documentation.
 
+pub(crate) mod deprecation;
 pub(crate) mod error;
+pub(crate) mod middleware;
+pub(crate) mod openrpc;
 pub(crate) mod server;
 pub(crate) mod service_builder;
 
@@ -14,4 +17,10 @@ pub use crate::server::RpcServer;
 pub use crate::service_builder::ServiceBuilder;
 
 #[doc(hidden)]
-pub type IoHandler = jsonrpc_pubsub::PubSubHandler<Option<crate::module::SubscriptionSession>>;
+pub type IoHandler = jsonrpc_pubsub::PubSubHandler<
+    Option<crate::module::SubscriptionSession>,
+    (
+        crate::middleware::MetricsMiddleware,
+        crate::middleware::QuotaMiddleware,
+    ),
+>;
@@ -0,0 +1,362 @@
+use crate::error::RPCError;
+use ckb_app_config::RpcConfig;
+use ckb_metrics::{metrics, Timer};
+use ckb_util::Mutex;
+use jsonrpc_core::{
+    futures::future::{self, Either, FutureExt},
+    BoxFuture, Call, ErrorCode, Id, Metadata, Middleware, Output, Response,
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use std::time::Instant;
+
+/// The listener label of the calls to the addresses in the `rpc` section, which are
+/// `listen_address`, `tcp_listen_address` and `ws_listen_address`.
+pub const DEFAULT_LISTENER: &str = "default";
+
+// The method label of the calls to the methods which do not exist, so the clients can not
+// create arbitrary label values.
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// Records the metrics of each RPC call, labeled by the method and the listener.
+///
+/// * `ckb-rpc.requests` - The count of the calls.
+/// * `ckb-rpc.latency` - The latency histogram of the calls, including the time waiting for the
+///   quotas.
+/// * `ckb-rpc.errors` - The count of the calls which return errors, also labeled by the error
+///   code.
+///
+/// The listener label is [`DEFAULT_LISTENER`] or the `listen_address` of an extra listener in
+/// `rpc.listeners`.
+///
+/// [`DEFAULT_LISTENER`]: constant.DEFAULT_LISTENER.html
+#[derive(Clone)]
+pub struct MetricsMiddleware {
+    listener: String,
+}
+
+impl MetricsMiddleware {
+    /// Creates the middleware for the listener.
+    pub fn new(listener: &str) -> Self {
+        MetricsMiddleware {
+            listener: listener.to_owned(),
+        }
+    }
+}
+
+// Returns the error code of the call output, None when the call succeeds.
+fn error_code(output: &Option<Output>) -> Option<i64> {
+    match output {
+        Some(Output::Failure(failure)) => Some(failure.error.code.code()),
+        _ => None,
+    }
+}
+
+impl<M: Metadata> Middleware<M> for MetricsMiddleware {
+    type Future = BoxFuture<Option<Response>>;
+    type CallFuture = BoxFuture<Option<Output>>;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let method = match &call {
+            Call::MethodCall(method_call) => method_call.method.clone(),
+            Call::Notification(notification) => notification.method.clone(),
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let listener = self.listener.clone();
+        let timer = Timer::start();
+        let future = next(call, meta);
+        Either::Left(
+            async move {
+                let output = future.await;
+                let elapsed = timer.stop();
+                let code = error_code(&output);
+                let method = if code == Some(ErrorCode::MethodNotFound.code()) {
+                    UNKNOWN_METHOD.to_owned()
+                } else {
+                    method
+                };
+                metrics!(counter, "ckb-rpc.requests", 1, "method" => method.clone(), "listener" => listener.clone());
+                metrics!(timing, "ckb-rpc.latency", elapsed, "method" => method.clone(), "listener" => listener.clone());
+                if let Some(code) = code {
+                    metrics!(counter, "ckb-rpc.errors", 1, "method" => method, "listener" => listener, "code" => code.to_string());
+                }
+                output
+            }
+            .boxed(),
+        )
+    }
+}
+
+/// Enforces the quotas configured in `RpcConfig` on each RPC call.
+///
+/// * The calls of a method exceeding `rpc.rate_limits` are rejected.
+/// * The calls are rejected when `rpc.max_concurrent_requests` calls are being handled.
+/// * The responses larger than `rpc.max_response_size` are replaced with an error.
+///
+/// The rejected calls get the error [`RateLimited`], and the oversized responses get the error
+/// [`ResponseTooLarge`].
+///
+/// The clones share the quota state, so the calls via all the listeners count against the same
+/// quotas.
+///
+/// [`RateLimited`]: ../enum.RPCError.html#variant.RateLimited
+/// [`ResponseTooLarge`]: ../enum.RPCError.html#variant.ResponseTooLarge
+#[derive(Clone, Default)]
+pub struct QuotaMiddleware {
+    rate_limits: Arc<HashMap<String, Mutex<TokenBucket>>>,
+    max_concurrent_requests: Option<usize>,
+    max_response_size: Option<usize>,
+    concurrent_requests: Arc<AtomicUsize>,
+}
+
+impl QuotaMiddleware {
+    /// Creates the middleware from the RPC config.
+    pub fn new(config: &RpcConfig) -> Self {
+        let rate_limits = config
+            .rate_limits
+            .iter()
+            .map(|(method, rate)| (method.to_owned(), Mutex::new(TokenBucket::new(*rate))))
+            .collect();
+        QuotaMiddleware {
+            rate_limits: Arc::new(rate_limits),
+            max_concurrent_requests: config.max_concurrent_requests,
+            max_response_size: config.max_response_size,
+            concurrent_requests: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn check_rate_limit(&self, method: &str) -> Result<(), String> {
+        if let Some(bucket) = self.rate_limits.get(method) {
+            let mut bucket = bucket.lock();
+            if !bucket.try_acquire() {
+                return Err(format!(
+                    "the method {} exceeds the rate limit {} requests per second",
+                    method, bucket.rate
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn acquire_concurrency(&self) -> Result<ConcurrencyGuard, String> {
+        let current = self.concurrent_requests.fetch_add(1, Ordering::SeqCst);
+        let guard = ConcurrencyGuard(Arc::clone(&self.concurrent_requests));
+        match self.max_concurrent_requests {
+            Some(max) if current >= max => Err(format!(
+                "the server is handling the max number of concurrent requests {}",
+                max
+            )),
+            _ => Ok(guard),
+        }
+    }
+}
+
+impl<M: Metadata> Middleware<M> for QuotaMiddleware {
+    type Future = BoxFuture<Option<Response>>;
+    type CallFuture = BoxFuture<Option<Output>>;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let (method, id, jsonrpc) = match &call {
+            Call::MethodCall(method_call) => (
+                method_call.method.clone(),
+                method_call.id.clone(),
+                method_call.jsonrpc,
+            ),
+            Call::Notification(notification) => {
+                (notification.method.clone(), Id::Null, notification.jsonrpc)
+            }
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let reject = |message: String| -> Either<Self::CallFuture, X> {
+            let error = RPCError::custom(RPCError::RateLimited, message);
+            let output = Output::from(Err(error), id.clone(), jsonrpc);
+            Either::Left(future::ready(Some(output)).boxed())
+        };
+
+        if let Err(message) = self.check_rate_limit(&method) {
+            return reject(message);
+        }
+        let guard = match self.acquire_concurrency() {
+            Ok(guard) => guard,
+            Err(message) => return reject(message),
+        };
+
+        let max_response_size = self.max_response_size;
+        let future = next(call, meta);
+        Either::Left(
+            async move {
+                let output = future.await;
+                drop(guard);
+                match (output, max_response_size) {
+                    (Some(output), Some(max)) => {
+                        let size = serde_json::to_vec(&output).map(|v| v.len()).unwrap_or(0);
+                        if size > max {
+                            let error = RPCError::custom(
+                                RPCError::ResponseTooLarge,
+                                format!(
+                                    "the response of {} is {} bytes, which exceeds the limit {} bytes",
+                                    method, size, max
+                                ),
+                            );
+                            Some(Output::from(Err(error), id, jsonrpc))
+                        } else {
+                            Some(output)
+                        }
+                    }
+                    (output, _) => output,
+                }
+            }
+            .boxed(),
+        )
+    }
+}
+
+// Decrements the number of concurrent requests when the call is finished.
+struct ConcurrencyGuard(Arc<AtomicUsize>);
+
+impl Drop for ConcurrencyGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Allows `rate` calls per second, with bursts up to `rate` calls.
+struct TokenBucket {
+    rate: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        TokenBucket {
+            rate,
+            tokens: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.rate as f64);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_code, QuotaMiddleware, TokenBucket};
+    use ckb_app_config::RpcConfig;
+    use jsonrpc_core::{Error, Id, MetaIoHandler, Output, Value, Version};
+
+    fn rpc_config(rate_limits: &[(&str, u64)], max_response_size: Option<usize>) -> RpcConfig {
+        RpcConfig {
+            listen_address: "127.0.0.1:0".to_owned(),
+            tcp_listen_address: None,
+            ws_listen_address: None,
+            max_request_body_size: 1024,
+            threads: None,
+            max_concurrent_requests: None,
+            max_response_size,
+            modules: Vec::new(),
+            reject_ill_transactions: true,
+            enable_deprecated_rpc: false,
+            extra_well_known_lock_scripts: Vec::new(),
+            extra_well_known_type_scripts: Vec::new(),
+            rate_limits: rate_limits
+                .iter()
+                .map(|(method, rate)| ((*method).to_owned(), *rate))
+                .collect(),
+            listeners: Vec::new(),
+        }
+    }
+
+    fn call(handler: &MetaIoHandler<(), QuotaMiddleware>, method: &str) -> Value {
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"{}","params":[]}}"#,
+            method
+        );
+        let response = handler.handle_request_sync(&request, ()).expect("response");
+        serde_json::from_str(&response).expect("parse response")
+    }
+
+    #[test]
+    fn token_bucket_allows_bursts_up_to_rate() {
+        let mut bucket = TokenBucket::new(3);
+        assert!((0..3).all(|_| bucket.try_acquire()));
+        assert!(!bucket.try_acquire());
+    }
+
+    #[test]
+    fn token_bucket_rejects_all_when_rate_is_zero() {
+        let mut bucket = TokenBucket::new(0);
+        assert!(!bucket.try_acquire());
+    }
+
+    #[test]
+    fn quota_errors_reach_the_client() {
+        let config = rpc_config(&[("ping", 1)], Some(64));
+        let mut handler = MetaIoHandler::with_middleware(QuotaMiddleware::new(&config));
+        handler.add_sync_method("ping", |_| Ok(Value::String("pong".to_owned())));
+        handler.add_sync_method("dump", |_| Ok(Value::String("x".repeat(128))));
+
+        assert_eq!(call(&handler, "ping")["result"], "pong");
+        let rate_limited = call(&handler, "ping");
+        assert_eq!(rate_limited["id"], 1);
+        assert_eq!(rate_limited["error"]["code"], -8);
+
+        // the method without a rate limit is not rejected, but its response is too large
+        let too_large = call(&handler, "dump");
+        assert_eq!(too_large["id"], 1);
+        assert_eq!(too_large["error"]["code"], -9);
+    }
+
+    #[test]
+    fn cloned_quotas_are_shared() {
+        let config = rpc_config(&[("ping", 1)], None);
+        let quota = QuotaMiddleware::new(&config);
+        let handlers = (0..2)
+            .map(|_| {
+                let mut handler = MetaIoHandler::with_middleware(quota.clone());
+                handler.add_sync_method("ping", |_| Ok(Value::String("pong".to_owned())));
+                handler
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(call(&handlers[0], "ping")["result"], "pong");
+        assert_eq!(call(&handlers[1], "ping")["error"]["code"], -8);
+    }
+
+    #[test]
+    fn error_code_of_outputs() {
+        let success = Output::from(Ok(Value::Null), Id::Num(1), Some(Version::V2));
+        assert_eq!(error_code(&Some(success)), None);
+        assert_eq!(error_code(&None), None);
+
+        let failure = Output::from(
+            Err(Error::method_not_found()),
+            Id::Num(1),
+            Some(Version::V2),
+        );
+        assert_eq!(error_code(&Some(failure)), Some(-32601));
+    }
+}
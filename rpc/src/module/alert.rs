@@ -68,6 +68,38 @@ pub trait AlertRpc {
     /// ```
     #[rpc(name = "send_alert")]
     fn send_alert(&self, alert: Alert) -> Result<()>;
+
+    /// Returns the active alerts which this node should notice.
+    ///
+    /// An alert is active when it is not expired nor cancelled, and the version of this node is in
+    /// the range specified by `min_version` and `max_version` of the alert.
+    ///
+    /// The alerts are sorted by priority, highest first.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_active_alerts",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": []
+    /// }
+    /// ```
+    #[rpc(name = "get_active_alerts")]
+    fn get_active_alerts(&self) -> Result<Vec<Alert>>;
 }
 
 pub(crate) struct AlertRpcImpl {
@@ -122,4 +154,15 @@ impl AlertRpc for AlertRpcImpl {
             )),
         }
     }
+
+    fn get_active_alerts(&self) -> Result<Vec<Alert>> {
+        let now_ms = faketime::unix_time_as_millis();
+        Ok(self
+            .notifier
+            .lock()
+            .active_alerts(now_ms)
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
 }
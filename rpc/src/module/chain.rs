@@ -1,12 +1,17 @@
 use crate::error::RPCError;
+use ckb_chain_spec::consensus::{
+    MAX_BLOCK_BYTES, MAX_BLOCK_CYCLES, MAX_BLOCK_PROPOSALS_LIMIT, MAX_UNCLE_NUM, TX_PROPOSAL_WINDOW,
+};
 use ckb_jsonrpc_types::{
-    BlockEconomicState, BlockNumber, BlockView, CellWithStatus, Consensus, EpochNumber, EpochView,
-    HeaderView, MerkleProof as JsonMerkleProof, OutPoint, ResponseFormat, Timestamp,
-    TransactionProof, TransactionWithStatus, Uint32,
+    BlockEconomicState, BlockNumber, BlockView, CellWithStatus, Consensus, ConsensusExtras,
+    EpochNumber, EpochPreview, EpochView, HeaderView, InputCell, LimitProvenance, LimitSource,
+    MerkleProof as JsonMerkleProof, MinerRewardComponent, OutPoint, PendingBlockReward,
+    ProposalWindow, ResponseFormat, Script, ScriptHashType, Timestamp, TransactionAndCells,
+    TransactionProof, TransactionWithStatus, TxStatus, Uint32, Uint64,
 };
 use ckb_logger::error;
 use ckb_reward_calculator::RewardCalculator;
-use ckb_shared::shared::Shared;
+use ckb_shared::{shared::Shared, Snapshot};
 use ckb_store::ChainStore;
 use ckb_traits::HeaderProvider;
 use ckb_types::{
@@ -16,9 +21,10 @@ use ckb_types::{
     utilities::{merkle_root, MerkleProof, CBMT},
     H256,
 };
-use jsonrpc_core::Result;
+use jsonrpc_core::{Error, Result};
 use jsonrpc_derive::rpc;
-use std::collections::HashSet;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 
 /// RPC Module Chain for methods related to the canonical chain.
 ///
@@ -523,6 +529,112 @@ pub trait ChainRpc {
     #[rpc(name = "get_transaction")]
     fn get_transaction(&self, tx_hash: H256) -> Result<Option<TransactionWithStatus>>;
 
+    /// Returns the information about a transaction requested by transaction hash, as well as the
+    /// cells consumed by its inputs.
+    ///
+    /// It saves the clients from querying the previous outputs one by one to display a
+    /// transaction.
+    ///
+    /// ## Params
+    ///
+    /// * `tx_hash` - Hash of a transaction
+    ///
+    /// ## Returns
+    ///
+    /// This RPC returns `null` if the transaction is not committed in the
+    /// [canonical chain](#canonical-chain) nor the transaction memory pool.
+    ///
+    /// The field `input_cells` has the same length as the transaction inputs. The previous outputs
+    /// are searched in the canonical chain and the transaction memory pool, so the consumed cells
+    /// are still returned after they are dead. An element is `null` for the cellbase input, or
+    /// when the previous output is not found.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_transaction_and_cells",
+    ///   "params": [
+    ///     "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "input_cells": [
+    ///       {
+    ///         "data_hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    ///         "output": {
+    ///           "capacity": "0x18e64efc04",
+    ///           "lock": {
+    ///             "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///             "hash_type": "data",
+    ///             "args": "0x"
+    ///           },
+    ///           "type": null
+    ///         }
+    ///       }
+    ///     ],
+    ///     "transaction": {
+    ///       "cell_deps": [
+    ///         {
+    ///           "dep_type": "code",
+    ///           "out_point": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3"
+    ///           }
+    ///         }
+    ///       ],
+    ///       "hash": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3",
+    ///       "header_deps": [
+    ///         "0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed"
+    ///       ],
+    ///       "inputs": [
+    ///         {
+    ///           "previous_output": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0x365698b50ca0da75dca2c87f9e7b563811d3b5813736b8cc62cc3b106faceb17"
+    ///           },
+    ///           "since": "0x0"
+    ///         }
+    ///       ],
+    ///       "outputs": [
+    ///         {
+    ///           "capacity": "0x2540be400",
+    ///           "lock": {
+    ///             "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///             "hash_type": "data",
+    ///             "args": "0x"
+    ///           },
+    ///           "type": null
+    ///         }
+    ///       ],
+    ///       "outputs_data": [
+    ///         "0x"
+    ///       ],
+    ///       "version": "0x0",
+    ///       "witnesses": []
+    ///     },
+    ///     "tx_status": {
+    ///       "block_hash": null,
+    ///       "status": "pending"
+    ///     }
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_transaction_and_cells")]
+    fn get_transaction_and_cells(&self, tx_hash: H256) -> Result<Option<TransactionAndCells>>;
+
     /// Returns the hash of a block in the [canonical chain](#canonical-chain) with the specified
     /// `block_number`.
     ///
@@ -878,8 +990,80 @@ pub trait ChainRpc {
     #[rpc(name = "get_block_economic_state")]
     fn get_block_economic_state(&self, block_hash: H256) -> Result<Option<BlockEconomicState>>;
 
+    /// Returns the projected rewards of the recent blocks mined by the miner, whose rewards are
+    /// not finalized yet.
+    ///
+    /// The rewards of a block are finalized when the block `N + ProposalWindow.farthest + 1` is
+    /// appended, see [`get_block_economic_state`](#tymethod.get_block_economic_state). Before
+    /// that, this RPC projects the rewards of the blocks in the
+    /// [canonical chain](#canonical-chain) using the current tip.
+    ///
+    /// The primary and secondary rewards and the fees of the committed transactions are final,
+    /// unless the block is rolled back. The proposal reward only counts the proposed transactions
+    /// committed up to the tip, so it is labeled as an estimate, which may increase until the
+    /// rewards are finalized.
+    ///
+    /// ## Params
+    ///
+    /// * `lock` - The miner lock script, which is the lock in the cellbase witness of the blocks.
+    ///
+    /// ## Returns
+    ///
+    /// The projected rewards of the blocks mined by `lock` in the last `ProposalWindow.farthest +
+    /// 1` blocks, in the ascending order of the block number.
+    ///
+    /// ## Errors
+    ///
+    /// * [`ChainIndexIsInconsistent (-201)`](../enum.RPCError.html#variant.ChainIndexIsInconsistent) - The index is inconsistent. It says a block hash is in the main chain, but cannot read it from the database.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_pending_block_rewards",
+    ///   "params": [
+    ///     {
+    ///       "args": "0x",
+    ///       "code_hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    ///       "hash_type": "data"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "block_hash": "0xa5f5c85987a15de25661e5a214f2c1449cd803f071acc7999820f25246471f40",
+    ///       "block_number": "0x5a",
+    ///       "estimated": [ "proposal" ],
+    ///       "finalized_at_number": "0x65",
+    ///       "miner_reward": {
+    ///         "committed": "0x0",
+    ///         "primary": "0x18ce922bca",
+    ///         "proposal": "0x0",
+    ///         "secondary": "0x17b93605"
+    ///       }
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_pending_block_rewards")]
+    fn get_pending_block_rewards(&self, lock: Script) -> Result<Vec<PendingBlockReward>>;
+
     /// Returns a Merkle proof that transactions are included in a block.
     ///
+    /// The blocks moved to the freezer are read from the freezer transparently.
+    ///
     /// ## Params
     ///
     /// * `tx_hashes` - Transaction hashes, all transactions must be in the same block
@@ -965,6 +1149,64 @@ pub trait ChainRpc {
     #[rpc(name = "verify_transaction_proof")]
     fn verify_transaction_proof(&self, tx_proof: TransactionProof) -> Result<Vec<H256>>;
 
+    /// Returns the Merkle proofs that transactions are included in the blocks, the transactions
+    /// can be in different blocks.
+    ///
+    /// It is the batch variant of [`get_transaction_proof`](#tymethod.get_transaction_proof).
+    /// The blocks are read in one batch, and the blocks moved to the freezer are read from the
+    /// freezer transparently.
+    ///
+    /// ## Params
+    ///
+    /// * `tx_hashes` - Transaction hashes, at most 1000.
+    ///
+    /// ## Returns
+    ///
+    /// One proof for each block containing the transactions, in the order in which the blocks
+    /// first appear in `tx_hashes`.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams)
+    ///     - `tx_hashes` is empty or has more than 1000 hashes.
+    ///     - A transaction is not in the chain, or it is duplicated.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_transaction_proofs",
+    ///   "params": [
+    ///     [ "0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3" ]
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "block_hash": "0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed",
+    ///       "proof": {
+    ///         "indices": [ "0x0" ],
+    ///         "lemmas": []
+    ///       },
+    ///       "witnesses_root": "0x2bb631f4a251ec39d943cc238fc1e39c7f0e99776e8a1e7be28a03c70c4f4853"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_transaction_proofs")]
+    fn get_transaction_proofs(&self, tx_hashes: Vec<H256>) -> Result<Vec<TransactionProof>>;
+
     /// Returns the information about a fork block by hash.
     ///
     /// ## Params
@@ -1139,6 +1381,156 @@ pub trait ChainRpc {
     #[rpc(name = "get_consensus")]
     fn get_consensus(&self) -> Result<Consensus>;
 
+    /// Returns the consensus limits active at the current epoch and their provenances.
+    ///
+    /// The SDKs can query the limits instead of hardcoding them. Each limit is either the CKB
+    /// default, overridden in the chain spec, or depends on a hardfork feature and the current
+    /// epoch.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_consensus_extras",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "epoch": "0x1",
+    ///     "max_block_bytes": "0x91c08",
+    ///     "max_block_cycles": "0xd09dc300",
+    ///     "max_block_proposals_limit": "0x5dc",
+    ///     "max_uncles_num": "0x2",
+    ///     "provenances": [
+    ///       {
+    ///         "activation_epoch": null,
+    ///         "hardfork_feature": null,
+    ///         "limit": "max_block_cycles",
+    ///         "source": "default"
+    ///       },
+    ///       {
+    ///         "activation_epoch": null,
+    ///         "hardfork_feature": null,
+    ///         "limit": "max_block_bytes",
+    ///         "source": "default"
+    ///       },
+    ///       {
+    ///         "activation_epoch": null,
+    ///         "hardfork_feature": null,
+    ///         "limit": "max_block_proposals_limit",
+    ///         "source": "default"
+    ///       },
+    ///       {
+    ///         "activation_epoch": null,
+    ///         "hardfork_feature": null,
+    ///         "limit": "max_uncles_num",
+    ///         "source": "default"
+    ///       },
+    ///       {
+    ///         "activation_epoch": null,
+    ///         "hardfork_feature": null,
+    ///         "limit": "tx_proposal_window",
+    ///         "source": "default"
+    ///       },
+    ///       {
+    ///         "activation_epoch": null,
+    ///         "hardfork_feature": "RFC PR 0232",
+    ///         "limit": "script_hash_types",
+    ///         "source": "hardfork"
+    ///       }
+    ///     ],
+    ///     "script_hash_types": ["data", "type"],
+    ///     "tx_proposal_window": {
+    ///       "closest": "0x2",
+    ///       "farthest": "0xa"
+    ///     }
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_consensus_extras")]
+    fn get_consensus_extras(&self) -> Result<ConsensusExtras>;
+
+    /// Predicts the upcoming epochs following the current epoch.
+    ///
+    /// The prediction assumes the hash rate stays the same and the orphan rate meets the target,
+    /// so the epoch length and difficulty keep unchanged. It helps to display the upcoming primary
+    /// reward halvings.
+    ///
+    /// ## Params
+    ///
+    /// * `n_epochs` - The number of epochs to predict, at most 10000.
+    ///
+    /// ## Returns
+    ///
+    /// The predicted epochs in ascending order of the epoch number. The difficulty bounds are the
+    /// range reachable if the hash rate moves to the limit of the difficulty adjustment dampening
+    /// in every epoch.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - `n_epochs` exceeds the limit.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_epoch_schedule_preview",
+    ///   "params": [
+    ///     "0x2"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "difficulty": "0x1f4003",
+    ///       "estimated_start_timestamp": "0x5dab7d17",
+    ///       "length": "0x708",
+    ///       "max_difficulty": "0x3e8006",
+    ///       "min_difficulty": "0xfa001",
+    ///       "number": "0x2",
+    ///       "primary_reward": "0x71afd498d000",
+    ///       "secondary_reward": "0x37d0c8e28542",
+    ///       "start_number": "0xaf0"
+    ///     },
+    ///     {
+    ///       "difficulty": "0x1f4003",
+    ///       "estimated_start_timestamp": "0x5e873717",
+    ///       "length": "0x708",
+    ///       "max_difficulty": "0x7d000c",
+    ///       "min_difficulty": "0x7d000",
+    ///       "number": "0x3",
+    ///       "primary_reward": "0x71afd498d000",
+    ///       "secondary_reward": "0x37d0c8e28542",
+    ///       "start_number": "0x11f8"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_epoch_schedule_preview")]
+    fn get_epoch_schedule_preview(&self, n_epochs: Uint64) -> Result<Vec<EpochPreview>>;
+
     /// Returns the past median time by block hash.
     ///
     /// ## Params
@@ -1186,6 +1578,51 @@ pub(crate) struct ChainRpcImpl {
 
 const DEFAULT_BLOCK_VERBOSITY_LEVEL: u32 = 2;
 const DEFAULT_HEADER_VERBOSITY_LEVEL: u32 = 1;
+const MAX_EPOCH_SCHEDULE_PREVIEW: u64 = 10_000;
+const MAX_TRANSACTION_PROOFS_HASHES: usize = 1_000;
+// The transaction hashes may hit as many blocks, so the blocks are read in small batches and
+// dropped once their proofs are built.
+const TRANSACTION_PROOFS_BLOCKS_BATCH_SIZE: usize = 16;
+
+impl ChainRpcImpl {
+    fn fetch_pool_transaction(
+        &self,
+        tx_hash: &packed::Byte32,
+    ) -> Result<Option<(bool, core::TransactionView)>> {
+        let id = packed::ProposalShortId::from_tx_hash(tx_hash);
+        self.shared
+            .tx_pool_controller()
+            .fetch_tx_for_rpc(id)
+            .map_err(|e| {
+                error!("send fetch_tx_for_rpc request error {}", e);
+                RPCError::ckb_internal_error(e)
+            })
+    }
+
+    fn fetch_input_cell(
+        &self,
+        snapshot: &Snapshot,
+        out_point: &packed::OutPoint,
+    ) -> Result<Option<InputCell>> {
+        if out_point.is_null() {
+            return Ok(None);
+        }
+
+        let tx_hash = out_point.tx_hash();
+        let tx = match snapshot.get_transaction(&tx_hash) {
+            Some((tx, _)) => Some(tx),
+            None => self.fetch_pool_transaction(&tx_hash)?.map(|(_, tx)| tx),
+        };
+        let index: u32 = out_point.index().unpack();
+
+        Ok(tx
+            .and_then(|tx| tx.output_with_data(index as usize))
+            .map(|(output, data)| InputCell {
+                data_hash: packed::CellOutput::calc_data_hash(&data).unpack(),
+                output: output.into(),
+            }))
+    }
+}
 
 impl ChainRpc for ChainRpcImpl {
     fn get_block(
@@ -1318,24 +1755,16 @@ impl ChainRpc for ChainRpcImpl {
 
     fn get_transaction(&self, tx_hash: H256) -> Result<Option<TransactionWithStatus>> {
         let tx_hash = tx_hash.pack();
-        let id = packed::ProposalShortId::from_tx_hash(&tx_hash);
 
-        let tx = {
-            let tx_pool = self.shared.tx_pool_controller();
-            let fetch_tx_for_rpc = tx_pool.fetch_tx_for_rpc(id);
-            if let Err(e) = fetch_tx_for_rpc {
-                error!("send fetch_tx_for_rpc request error {}", e);
-                return Err(RPCError::ckb_internal_error(e));
-            };
-
-            fetch_tx_for_rpc.unwrap().map(|(proposed, tx)| {
+        let tx = self
+            .fetch_pool_transaction(&tx_hash)?
+            .map(|(proposed, tx)| {
                 if proposed {
                     TransactionWithStatus::with_proposed(tx)
                 } else {
                     TransactionWithStatus::with_pending(tx)
                 }
-            })
-        };
+            });
 
         Ok(tx.or_else(|| {
             self.shared
@@ -1347,6 +1776,31 @@ impl ChainRpc for ChainRpcImpl {
         }))
     }
 
+    fn get_transaction_and_cells(&self, tx_hash: H256) -> Result<Option<TransactionAndCells>> {
+        let tx_hash = tx_hash.pack();
+        let snapshot = self.shared.snapshot();
+
+        let (tx, tx_status) = match self.fetch_pool_transaction(&tx_hash)? {
+            Some((true, tx)) => (tx, TxStatus::proposed()),
+            Some((false, tx)) => (tx, TxStatus::pending()),
+            None => match snapshot.get_transaction(&tx_hash) {
+                Some((tx, block_hash)) => (tx, TxStatus::committed(block_hash.unpack())),
+                None => return Ok(None),
+            },
+        };
+
+        let mut input_cells = Vec::with_capacity(tx.inputs().len());
+        for out_point in tx.input_pts_iter() {
+            input_cells.push(self.fetch_input_cell(&snapshot, &out_point)?);
+        }
+
+        Ok(Some(TransactionAndCells {
+            transaction: tx.into(),
+            tx_status,
+            input_cells,
+        }))
+    }
+
     fn get_block_hash(&self, block_number: BlockNumber) -> Result<Option<H256>> {
         Ok(self
             .shared
@@ -1469,6 +1923,49 @@ impl ChainRpc for ChainRpcImpl {
         }))
     }
 
+    fn get_pending_block_rewards(&self, lock: Script) -> Result<Vec<PendingBlockReward>> {
+        let lock: packed::Script = lock.into();
+        let snapshot = self.shared.snapshot();
+        let tip_header = snapshot.tip_header();
+        let delay_length = snapshot.consensus().finalization_delay_length();
+        // The rewards of the block N are finalized at the block N + delay_length
+        let start_number = cmp::max((tip_header.number() + 1).saturating_sub(delay_length), 1);
+        let calculator = RewardCalculator::new(snapshot.consensus(), snapshot.as_ref());
+
+        let mut rewards = Vec::new();
+        for number in start_number..=tip_header.number() {
+            let header = match snapshot
+                .get_block_hash(number)
+                .and_then(|hash| snapshot.get_block_header(&hash))
+            {
+                Some(header) => header,
+                None => {
+                    let message =
+                        format!("Chain index for block number {} is inconsistent", number);
+                    error!("{}", message);
+                    return Err(RPCError::custom(
+                        RPCError::ChainIndexIsInconsistent,
+                        message,
+                    ));
+                }
+            };
+            let (target_lock, block_reward) = calculator
+                .pending_block_reward(&header, tip_header)
+                .map_err(RPCError::from_ckb_error)?;
+            if target_lock != lock {
+                continue;
+            }
+            rewards.push(PendingBlockReward {
+                block_hash: header.hash().unpack(),
+                block_number: number.into(),
+                miner_reward: core::MinerReward::from(block_reward).into(),
+                estimated: vec![MinerRewardComponent::Proposal],
+                finalized_at_number: (number + delay_length).into(),
+            });
+        }
+        Ok(rewards)
+    }
+
     fn get_transaction_proof(
         &self,
         tx_hashes: Vec<H256>,
@@ -1519,28 +2016,56 @@ impl ChainRpc for ChainRpcImpl {
 
         snapshot
             .get_block(&retrieved_block_hash)
-            .ok_or_else(|| {
-                let message = format!(
-                    "Chain TransactionInfo says block {:#x} existing, but that block is not in the database",
-                    retrieved_block_hash
-                );
-                error!("{}", message);
-                RPCError::custom(RPCError::ChainIndexIsInconsistent, message)
-            })
-            .map(|block| {
-                let proof = CBMT::build_merkle_proof(
-                    &block.transactions().iter().map(|tx| tx.hash()).collect::<Vec<_>>(),
-                    &tx_indices.into_iter().collect::<Vec<_>>())
-                .expect("build proof with verified inputs should be OK");
-                TransactionProof {
-                    block_hash: block.hash().unpack(),
-                    witnesses_root: block.calc_witnesses_root().unpack(),
-                    proof: JsonMerkleProof {
-                        indices: proof.indices().iter().map(|index| (*index).into()).collect(),
-                        lemmas: proof.lemmas().iter().map(|lemma| Unpack::<H256>::unpack(lemma)).collect(),
-                    }
-                }
-            })
+            .ok_or_else(|| missing_proof_block_error(&retrieved_block_hash))
+            .map(|block| build_transaction_proof(&block, tx_indices))
+    }
+
+    fn get_transaction_proofs(&self, tx_hashes: Vec<H256>) -> Result<Vec<TransactionProof>> {
+        if tx_hashes.is_empty() {
+            return Err(RPCError::invalid_params("Empty transaction hashes"));
+        }
+        if tx_hashes.len() > MAX_TRANSACTION_PROOFS_HASHES {
+            return Err(RPCError::invalid_params(format!(
+                "Expect at most {} transaction hashes",
+                MAX_TRANSACTION_PROOFS_HASHES
+            )));
+        }
+        let snapshot = self.shared.snapshot();
+
+        // the blocks in the order of their first appearance
+        let mut block_hashes = Vec::new();
+        let mut block_tx_indices: HashMap<packed::Byte32, HashSet<u32>> = HashMap::new();
+        for tx_hash in tx_hashes {
+            let tx_info = snapshot
+                .get_transaction_info(&tx_hash.pack())
+                .ok_or_else(|| {
+                    RPCError::invalid_params(format!("Transaction {:#x} not yet in block", tx_hash))
+                })?;
+            let tx_indices = block_tx_indices
+                .entry(tx_info.block_hash.clone())
+                .or_insert_with(|| {
+                    block_hashes.push(tx_info.block_hash.clone());
+                    HashSet::new()
+                });
+            if !tx_indices.insert(tx_info.index as u32) {
+                return Err(RPCError::invalid_params(format!(
+                    "Duplicated tx_hash {:#x}",
+                    tx_hash
+                )));
+            }
+        }
+
+        let mut proofs = Vec::with_capacity(block_hashes.len());
+        for batch in block_hashes.chunks(TRANSACTION_PROOFS_BLOCKS_BATCH_SIZE) {
+            for (block, block_hash) in snapshot.get_blocks(batch).into_iter().zip(batch) {
+                let block = block.ok_or_else(|| missing_proof_block_error(block_hash))?;
+                let tx_indices = block_tx_indices
+                    .remove(block_hash)
+                    .expect("indices of the block are collected");
+                proofs.push(build_transaction_proof(&block, tx_indices));
+            }
+        }
+        Ok(proofs)
     }
 
     fn verify_transaction_proof(&self, tx_proof: TransactionProof) -> Result<Vec<H256>> {
@@ -1619,6 +2144,112 @@ impl ChainRpc for ChainRpcImpl {
         Ok(consensus.into())
     }
 
+    fn get_consensus_extras(&self) -> Result<ConsensusExtras> {
+        let snapshot = self.shared.snapshot();
+        let consensus = snapshot.consensus();
+        let epoch = snapshot.tip_header().epoch().number();
+        let hardfork_switch = consensus.hardfork_switch();
+
+        let mut script_hash_types = vec![ScriptHashType::Data, ScriptHashType::Type];
+        if hardfork_switch.is_vm_version_1_and_syscalls_2_enabled(epoch) {
+            script_hash_types.push(ScriptHashType::Data1);
+        }
+
+        let configured = |limit: &str, is_default: bool| LimitProvenance {
+            limit: limit.to_owned(),
+            source: if is_default {
+                LimitSource::Default
+            } else {
+                LimitSource::Overridden
+            },
+            hardfork_feature: None,
+            activation_epoch: None,
+        };
+        let vm_version_1_epoch = hardfork_switch.vm_version_1_and_syscalls_2();
+        let tx_proposal_window = consensus.tx_proposal_window();
+        let provenances = vec![
+            configured(
+                "max_block_cycles",
+                consensus.max_block_cycles() == MAX_BLOCK_CYCLES,
+            ),
+            configured(
+                "max_block_bytes",
+                consensus.max_block_bytes() == MAX_BLOCK_BYTES,
+            ),
+            configured(
+                "max_block_proposals_limit",
+                consensus.max_block_proposals_limit() == MAX_BLOCK_PROPOSALS_LIMIT,
+            ),
+            configured(
+                "max_uncles_num",
+                consensus.max_uncles_num() == MAX_UNCLE_NUM,
+            ),
+            configured(
+                "tx_proposal_window",
+                tx_proposal_window == TX_PROPOSAL_WINDOW,
+            ),
+            LimitProvenance {
+                limit: "script_hash_types".to_owned(),
+                source: LimitSource::Hardfork,
+                hardfork_feature: Some("RFC PR 0232".to_owned()),
+                activation_epoch: if vm_version_1_epoch == core::EpochNumber::MAX {
+                    None
+                } else {
+                    Some(vm_version_1_epoch.into())
+                },
+            },
+        ];
+
+        Ok(ConsensusExtras {
+            epoch: epoch.into(),
+            max_block_cycles: consensus.max_block_cycles().into(),
+            max_block_bytes: consensus.max_block_bytes().into(),
+            max_block_proposals_limit: consensus.max_block_proposals_limit().into(),
+            max_uncles_num: (consensus.max_uncles_num() as u64).into(),
+            tx_proposal_window: ProposalWindow {
+                closest: tx_proposal_window.closest().into(),
+                farthest: tx_proposal_window.farthest().into(),
+            },
+            script_hash_types,
+            provenances,
+        })
+    }
+
+    fn get_epoch_schedule_preview(&self, n_epochs: Uint64) -> Result<Vec<EpochPreview>> {
+        let n_epochs: u64 = n_epochs.into();
+        if n_epochs > MAX_EPOCH_SCHEDULE_PREVIEW {
+            return Err(RPCError::invalid_params(format!(
+                "n_epochs must not exceed {}",
+                MAX_EPOCH_SCHEDULE_PREVIEW
+            )));
+        }
+
+        let snapshot = self.shared.snapshot();
+        let consensus = snapshot.consensus();
+        let tip_header = snapshot.tip_header();
+        let epoch_duration_target = consensus.epoch_duration_target() * 1000;
+        Ok(consensus
+            .epoch_schedule_preview(snapshot.epoch_ext(), n_epochs)
+            .into_iter()
+            .map(|epoch| {
+                let blocks_left = epoch.start_number.saturating_sub(tip_header.number());
+                let estimated_start_timestamp = tip_header.timestamp()
+                    + blocks_left * epoch_duration_target / cmp::max(epoch.length, 1);
+                EpochPreview {
+                    number: epoch.number.into(),
+                    start_number: epoch.start_number.into(),
+                    length: epoch.length.into(),
+                    estimated_start_timestamp: estimated_start_timestamp.into(),
+                    primary_reward: epoch.primary_reward.into(),
+                    secondary_reward: epoch.secondary_reward.into(),
+                    difficulty: epoch.difficulty,
+                    min_difficulty: epoch.min_difficulty,
+                    max_difficulty: epoch.max_difficulty,
+                }
+            })
+            .collect())
+    }
+
     fn get_block_median_time(&self, block_hash: H256) -> Result<Option<Timestamp>> {
         let block_hash = block_hash.pack();
         let snapshot = self.shared.snapshot();
@@ -1633,3 +2264,40 @@ impl ChainRpc for ChainRpcImpl {
         Ok(Some(median_time.into()))
     }
 }
+
+fn missing_proof_block_error(block_hash: &packed::Byte32) -> Error {
+    let message = format!(
+        "Chain TransactionInfo says block {:#x} existing, but that block is not in the database",
+        block_hash
+    );
+    error!("{}", message);
+    RPCError::custom(RPCError::ChainIndexIsInconsistent, message)
+}
+
+fn build_transaction_proof(block: &core::BlockView, tx_indices: HashSet<u32>) -> TransactionProof {
+    let proof = CBMT::build_merkle_proof(
+        &block
+            .transactions()
+            .iter()
+            .map(|tx| tx.hash())
+            .collect::<Vec<_>>(),
+        &tx_indices.into_iter().collect::<Vec<_>>(),
+    )
+    .expect("build proof with verified inputs should be OK");
+    TransactionProof {
+        block_hash: block.hash().unpack(),
+        witnesses_root: block.calc_witnesses_root().unpack(),
+        proof: JsonMerkleProof {
+            indices: proof
+                .indices()
+                .iter()
+                .map(|index| (*index).into())
+                .collect(),
+            lemmas: proof
+                .lemmas()
+                .iter()
+                .map(|lemma| Unpack::<H256>::unpack(lemma))
+                .collect(),
+        },
+    }
+}
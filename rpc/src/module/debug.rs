@@ -1,7 +1,25 @@
-use ckb_jsonrpc_types::{ExtraLoggerConfig, MainLoggerConfig};
+use ckb_chain::chain::ChainController;
+use ckb_jsonrpc_types::{
+    ExtraLoggerConfig, MainLoggerConfig, PendingReorg, Transaction, Uint64, VerificationRuleTrace,
+};
+use ckb_logger_config::RateLimitConfig;
 use ckb_logger_service::Logger;
+use ckb_shared::{shared::Shared, Snapshot};
+use ckb_sync::SyncShared;
+use ckb_types::H256;
+use ckb_types::{
+    core::{
+        cell::{resolve_transaction_with_options, ResolveOptions},
+        Cycle,
+    },
+    packed,
+    prelude::*,
+};
+use ckb_verification::{explain_transaction, RuleTrace, TxVerifyEnv};
 use jsonrpc_core::{Error, ErrorCode::InternalError, Result};
 use jsonrpc_derive::rpc;
+use std::collections::HashSet;
+use std::sync::Arc;
 use std::time;
 
 /// RPC Module Debug for internal RPC methods.
@@ -33,9 +51,83 @@ pub trait DebugRpc {
     /// Removes the logger when this is null.
     #[rpc(name = "set_extra_logger")]
     fn set_extra_logger(&self, name: String, config_opt: Option<ExtraLoggerConfig>) -> Result<()>;
+    /// Returns the recent log records kept in memory, the oldest first.
+    ///
+    /// The number of records kept in memory is configured by `logger.ring_buffer_size`.
+    ///
+    /// ## Params
+    ///
+    /// * `filter` - Only returns the records matched by the filter, which uses the same syntax as
+    /// the logger filter, such as `ckb-sync=debug`. Returns all the records when this is null.
+    /// * `n` - Returns at most this number of the latest records, default is 100.
+    #[rpc(name = "tail_logs")]
+    fn tail_logs(&self, filter: Option<String>, n: Option<Uint64>) -> Result<Vec<String>>;
+    /// Explains the verification of a transaction.
+    ///
+    /// The transaction is verified as if it is submitted to the pool on top of the current tip,
+    /// but unlike `send_transaction`, it evaluates every rule instead of stopping at the first
+    /// violated one, and returns the trace of each rule in the order they are evaluated. The
+    /// scripts are limited by the config option `tx_pool.max_tx_verify_cycles` like the pool.
+    ///
+    /// When the transaction cannot be resolved, the trace only contains the rule `resolve`.
+    ///
+    /// ## Params
+    ///
+    /// * `tx` - The transaction.
+    #[rpc(name = "explain_transaction")]
+    fn explain_transaction(&self, tx: Transaction) -> Result<Vec<VerificationRuleTrace>>;
+    /// Returns the reorg paused because it rolls back more blocks than `chain.max_reorg_depth`.
+    ///
+    /// Returns null when there is no pending reorg.
+    #[rpc(name = "get_pending_reorg")]
+    fn get_pending_reorg(&self) -> Result<Option<PendingReorg>>;
+    /// Approves the pending reorg and switches the main chain to the fork.
+    ///
+    /// ## Params
+    ///
+    /// * `block_hash` - The hash of the best block in the pending reorg, which must match the
+    /// one returned by `get_pending_reorg`.
+    #[rpc(name = "approve_reorg")]
+    fn approve_reorg(&self, block_hash: H256) -> Result<()>;
+    /// Enables or disables collecting the metrics data while CKB is running.
+    ///
+    /// Returns an error when the metrics service is not configured in `ckb.toml`.
+    ///
+    /// ## Params
+    ///
+    /// * `enabled` - Whether to collect the metrics data.
+    #[rpc(name = "set_metrics_enabled")]
+    fn set_metrics_enabled(&self, enabled: bool) -> Result<()>;
+    /// Updates or clears the assume-valid target while CKB is running.
+    ///
+    /// The scripts in the blocks which are not verified yet are skipped until the block of the
+    /// target is verified. After the target is cleared, all the scripts in the following blocks
+    /// are verified.
+    ///
+    /// The change is not persisted. The target in the config file or the command line is used
+    /// again after restarting.
+    ///
+    /// ## Params
+    ///
+    /// * `block_hash` - The hash of the new target block. Clears the target when this is null.
+    ///
+    /// ## Errors
+    ///
+    /// * Invalid params when the header of the target is unknown, is not an ancestor of the best
+    /// known header, or is not old enough to be in the initial block download, or when the block
+    /// of the target is already stored.
+    #[rpc(name = "set_assume_valid_target")]
+    fn set_assume_valid_target(&self, block_hash: Option<H256>) -> Result<()>;
 }
 
-pub(crate) struct DebugRpcImpl {}
+const DEFAULT_TAIL_LOGS: u64 = 100;
+
+pub(crate) struct DebugRpcImpl {
+    pub shared: Shared,
+    pub chain: ChainController,
+    pub sync_shared: Arc<SyncShared>,
+    pub max_tx_verify_cycles: Cycle,
+}
 
 impl DebugRpc for DebugRpcImpl {
     fn jemalloc_profiling_dump(&self) -> Result<String> {
@@ -60,14 +152,34 @@ impl DebugRpc for DebugRpcImpl {
             to_stdout,
             to_file,
             color,
+            rate_limits,
         } = config;
-        if filter.is_none() && to_stdout.is_none() && to_file.is_none() && color.is_none() {
+        if filter.is_none()
+            && to_stdout.is_none()
+            && to_file.is_none()
+            && color.is_none()
+            && rate_limits.is_none()
+        {
             return Ok(());
         }
-        Logger::update_main_logger(filter, to_stdout, to_file, color).map_err(|err| Error {
-            code: InternalError,
-            message: err,
-            data: None,
+        let rate_limits = rate_limits.map(|rate_limits| {
+            rate_limits
+                .into_iter()
+                .map(|(target, config)| {
+                    let config = RateLimitConfig {
+                        max_per_second: config.max_per_second.map(Into::into),
+                        sample: config.sample.map(Into::into),
+                    };
+                    (target, config)
+                })
+                .collect()
+        });
+        Logger::update_main_logger(filter, to_stdout, to_file, color, rate_limits).map_err(|err| {
+            Error {
+                code: InternalError,
+                message: err,
+                data: None,
+            }
         })
     }
 
@@ -90,4 +202,96 @@ impl DebugRpc for DebugRpcImpl {
             data: None,
         })
     }
+
+    fn tail_logs(&self, filter: Option<String>, n: Option<Uint64>) -> Result<Vec<String>> {
+        let n: u64 = n.map(Into::into).unwrap_or(DEFAULT_TAIL_LOGS);
+        Logger::tail(filter.as_deref(), n as usize).map_err(|err| Error {
+            code: InternalError,
+            message: err,
+            data: None,
+        })
+    }
+
+    fn explain_transaction(&self, tx: Transaction) -> Result<Vec<VerificationRuleTrace>> {
+        let tx: packed::Transaction = tx.into();
+        let snapshot: &Snapshot = &self.shared.snapshot();
+        let consensus = snapshot.consensus();
+        let tip_header = snapshot.tip_header();
+        let tx_env = TxVerifyEnv::new_submit(tip_header);
+        let resolve_opts = {
+            let epoch_number = tx_env.epoch_number(consensus.tx_proposal_window());
+            let flag = consensus
+                .hardfork_switch()
+                .is_remove_header_deps_immature_rule_enabled(epoch_number);
+            ResolveOptions::empty().set_skip_immature_header_deps_check(flag)
+        };
+        let traces = match resolve_transaction_with_options(
+            tx.into_view(),
+            &mut HashSet::new(),
+            snapshot,
+            snapshot,
+            resolve_opts,
+        ) {
+            Ok(rtx) => explain_transaction(
+                &rtx,
+                consensus,
+                &snapshot.as_data_provider(),
+                &tx_env,
+                self.max_tx_verify_cycles,
+            ),
+            Err(err) => vec![RuleTrace {
+                rule: "resolve",
+                inputs: Vec::new(),
+                passed: false,
+                outcome: err.to_string(),
+            }],
+        };
+        Ok(traces
+            .into_iter()
+            .map(|trace| VerificationRuleTrace {
+                rule: trace.rule.to_owned(),
+                inputs: trace
+                    .inputs
+                    .into_iter()
+                    .map(|(name, value)| (name.to_owned(), value))
+                    .collect(),
+                passed: trace.passed,
+                outcome: trace.outcome,
+            })
+            .collect())
+    }
+
+    fn get_pending_reorg(&self) -> Result<Option<PendingReorg>> {
+        Ok(self.chain.pending_reorg().map(|pending| PendingReorg {
+            block_hash: pending.block_hash.unpack(),
+            block_number: pending.block_number.into(),
+            total_difficulty: pending.total_difficulty,
+            depth: pending.depth.into(),
+        }))
+    }
+
+    fn approve_reorg(&self, block_hash: H256) -> Result<()> {
+        self.chain
+            .approve_reorg(block_hash.pack())
+            .map(|_| ())
+            .map_err(|err| Error {
+                code: InternalError,
+                message: err.to_string(),
+                data: None,
+            })
+    }
+
+    fn set_metrics_enabled(&self, enabled: bool) -> Result<()> {
+        ckb_metrics_service::set_enabled(enabled).map_err(|err| Error {
+            code: InternalError,
+            message: err,
+            data: None,
+        })
+    }
+
+    fn set_assume_valid_target(&self, block_hash: Option<H256>) -> Result<()> {
+        self.sync_shared
+            .set_assume_valid_target(block_hash)
+            .map_err(Error::invalid_params)
+    }
 }
@@ -1,16 +1,20 @@
 use crate::error::RPCError;
 use ckb_dao::DaoCalculator;
-use ckb_jsonrpc_types::{Capacity, DryRunResult, OutPoint, Transaction};
+use ckb_jsonrpc_types::{
+    Capacity, CellOutput, CellOutputWithDataLen, DryRunResult, JsonBytes, OccupiedCapacity,
+    OutPoint, PartialTransaction, Script, ScriptGroupType, SigningGroup, Transaction, Uint64,
+};
 use ckb_shared::{shared::Shared, Snapshot};
 use ckb_store::ChainStore;
 use ckb_types::{
     core::{
         self,
         cell::{
-            resolve_transaction_with_options, CellProvider, CellStatus, HeaderChecker,
-            ResolveOptions,
+            resolve_transaction_with_options, CellMeta, CellMetaBuilder, CellProvider, CellStatus,
+            HeaderChecker, ResolveOptions,
         },
         error::OutPointError,
+        TransactionInfo,
     },
     packed,
     prelude::*,
@@ -19,7 +23,7 @@ use ckb_types::{
 use ckb_verification::{ScriptVerifier, TxVerifyEnv};
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// RPC Module Experiment for experimenting methods.
 ///
@@ -35,10 +39,23 @@ pub trait ExperimentRpc {
     ///
     /// It is used to debug transaction scripts and query how many cycles the scripts consume.
     ///
+    /// ## Params
+    ///
+    /// * `tx` - The transaction.
+    /// * `fork_tip` - Resolves the transaction as if the chain ends at this block, which can be a
+    /// block not in the [canonical chain](trait.ChainRpc.html#canonical-chain), default is the
+    /// tip of the canonical chain.
+    ///
+    /// When `fork_tip` is specified, the cells created and consumed by the fork blocks are
+    /// applied, and the cells created and consumed by the canonical blocks after the fork point
+    /// are reverted. The header deps must be in the fork chain. Both the fork blocks and the
+    /// reverted canonical blocks are limited to 256 blocks.
+    ///
     /// ## Errors
     ///
     /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - Failed to resolve the referenced cells and headers used in the transaction, as inputs or dependencies.
     /// * [`TransactionFailedToVerify (-302)`](../enum.RPCError.html#variant.TransactionFailedToVerify) - There is a script returns with an error.
+    /// * `InvalidParams (-32602)` - The block `fork_tip` is not found, or the fork is too deep.
     ///
     /// ## Examples
     ///
@@ -105,7 +122,7 @@ pub trait ExperimentRpc {
     /// }
     /// ```
     #[rpc(name = "dry_run_transaction")]
-    fn dry_run_transaction(&self, tx: Transaction) -> Result<DryRunResult>;
+    fn dry_run_transaction(&self, tx: Transaction, fork_tip: Option<H256>) -> Result<DryRunResult>;
 
     /// Calculates the maximum withdrawal one can get, given a referenced DAO cell, and
     /// a withdrawing block hash.
@@ -164,6 +181,318 @@ pub trait ExperimentRpc {
         out_point: OutPoint,
         block_hash: H256,
     ) -> Result<Capacity>;
+
+    /// Fills the input cells and the signing groups of a partially signed transaction (PSCT).
+    ///
+    /// The parties of a multi-party signing flow can sign their script groups offline with the
+    /// filled PSCT, because it carries everything needed to compute the signing messages.
+    ///
+    /// ## Params
+    ///
+    /// * `partial_transaction` - The PSCT. Its `input_cells`, `input_cells_data` and
+    /// `signing_groups` are ignored and replaced.
+    ///
+    /// ## Returns
+    ///
+    /// The PSCT with the cells consumed by the inputs, which must be live in the
+    /// [canonical chain](trait.ChainRpc.html#canonical-chain), and the script groups of the
+    /// transaction.
+    ///
+    /// The lock script groups come first, then the type script groups, and the groups are
+    /// ordered by the first input or output in the group. The `hint` of a group in the params is
+    /// kept when the returned group has the same script and script type.
+    ///
+    /// ## Errors
+    ///
+    /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - An input is not a live cell.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "fill_partial_transaction",
+    ///   "params": [
+    ///     {
+    ///       "input_cells": [],
+    ///       "input_cells_data": [],
+    ///       "signing_groups": [],
+    ///       "transaction": {
+    ///         "cell_deps": [
+    ///           {
+    ///             "dep_type": "code",
+    ///             "out_point": {
+    ///               "index": "0x0",
+    ///               "tx_hash": "0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3"
+    ///             }
+    ///           }
+    ///         ],
+    ///         "header_deps": [],
+    ///         "inputs": [
+    ///           {
+    ///             "previous_output": {
+    ///               "index": "0x0",
+    ///               "tx_hash": "0x365698b50ca0da75dca2c87f9e7b563811d3b5813736b8cc62cc3b106faceb17"
+    ///             },
+    ///             "since": "0x0"
+    ///           }
+    ///         ],
+    ///         "outputs": [
+    ///           {
+    ///             "capacity": "0x2540be400",
+    ///             "lock": {
+    ///               "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///               "hash_type": "data",
+    ///               "args": "0x"
+    ///             },
+    ///             "type": null
+    ///           }
+    ///         ],
+    ///         "outputs_data": [
+    ///           "0x"
+    ///         ],
+    ///         "version": "0x0",
+    ///         "witnesses": []
+    ///       }
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "input_cells": [
+    ///       {
+    ///         "capacity": "0x18e64efc04",
+    ///         "lock": {
+    ///           "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///           "hash_type": "data",
+    ///           "args": "0x"
+    ///         },
+    ///         "type": null
+    ///       }
+    ///     ],
+    ///     "input_cells_data": [
+    ///       "0x"
+    ///     ],
+    ///     "signing_groups": [
+    ///       {
+    ///         "hint": "0x",
+    ///         "input_indices": [
+    ///           "0x0"
+    ///         ],
+    ///         "output_indices": [],
+    ///         "script": {
+    ///           "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///           "hash_type": "data",
+    ///           "args": "0x"
+    ///         },
+    ///         "script_type": "lock"
+    ///       }
+    ///     ],
+    ///     "transaction": {
+    ///       "cell_deps": [
+    ///         {
+    ///           "dep_type": "code",
+    ///           "out_point": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3"
+    ///           }
+    ///         }
+    ///       ],
+    ///       "header_deps": [],
+    ///       "inputs": [
+    ///         {
+    ///           "previous_output": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0x365698b50ca0da75dca2c87f9e7b563811d3b5813736b8cc62cc3b106faceb17"
+    ///           },
+    ///           "since": "0x0"
+    ///         }
+    ///       ],
+    ///       "outputs": [
+    ///         {
+    ///           "capacity": "0x2540be400",
+    ///           "lock": {
+    ///             "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///             "hash_type": "data",
+    ///             "args": "0x"
+    ///           },
+    ///           "type": null
+    ///         }
+    ///       ],
+    ///       "outputs_data": [
+    ///         "0x"
+    ///       ],
+    ///       "version": "0x0",
+    ///       "witnesses": []
+    ///     }
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "fill_partial_transaction")]
+    fn fill_partial_transaction(
+        &self,
+        partial_transaction: PartialTransaction,
+    ) -> Result<PartialTransaction>;
+
+    /// Calculates the occupied capacity of a cell output.
+    ///
+    /// A cell must have enough capacity to store itself, the transactions creating the outputs
+    /// with less capacity are rejected by the tx-pool.
+    ///
+    /// ## Params
+    ///
+    /// * `output` - The cell output. Its `capacity` is only used to tell whether it is enough.
+    /// * `data_len` - The length of the cell data in bytes.
+    ///
+    /// ## Returns
+    ///
+    /// The occupied capacity of the output, which is the total size in bytes of the capacity
+    /// field (8), the lock script, the optional type script and the data, in CKBytes. A script
+    /// occupies 33 bytes for the `code_hash` and the `hash_type`, plus the length of the `args`.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - The occupied
+    /// capacity overflows.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "calculate_occupied_capacity",
+    ///   "params": [
+    ///     {
+    ///       "capacity": "0x2540be400",
+    ///       "lock": {
+    ///         "args": "0xc8328aabcd9b9e8e64fbc566c4385c3bdeb219d7",
+    ///         "code_hash": "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8",
+    ///         "hash_type": "type"
+    ///       },
+    ///       "type": null
+    ///     },
+    ///     "0x0"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "is_lack_of_capacity": false,
+    ///     "occupied_capacity": "0x16b969d00"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "calculate_occupied_capacity")]
+    fn calculate_occupied_capacity(
+        &self,
+        output: CellOutput,
+        data_len: Uint64,
+    ) -> Result<OccupiedCapacity>;
+
+    /// Calculates the occupied capacities of a batch of cell outputs.
+    ///
+    /// See [`calculate_occupied_capacity`](#tymethod.calculate_occupied_capacity) for how the
+    /// occupied capacity is calculated.
+    ///
+    /// ## Params
+    ///
+    /// * `outputs` - The cell outputs along with the lengths of their data.
+    ///
+    /// ## Returns
+    ///
+    /// The occupied capacities in the same order as `outputs`.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - The occupied
+    /// capacity of an output overflows.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "calculate_occupied_capacities",
+    ///   "params": [
+    ///     [
+    ///       {
+    ///         "data_len": "0x0",
+    ///         "output": {
+    ///           "capacity": "0x12a05f200",
+    ///           "lock": {
+    ///             "args": "0xc8328aabcd9b9e8e64fbc566c4385c3bdeb219d7",
+    ///             "code_hash": "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8",
+    ///             "hash_type": "type"
+    ///           },
+    ///           "type": null
+    ///         }
+    ///       },
+    ///       {
+    ///         "data_len": "0x10",
+    ///         "output": {
+    ///           "capacity": "0x174876e800",
+    ///           "lock": {
+    ///             "args": "0xc8328aabcd9b9e8e64fbc566c4385c3bdeb219d7",
+    ///             "code_hash": "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8",
+    ///             "hash_type": "type"
+    ///           },
+    ///           "type": {
+    ///             "args": "0x",
+    ///             "code_hash": "0x82d76d1b75fe2fd9a27dfbaa65a039221a380d76c926f378d3f81cf3e7e13f2e",
+    ///             "hash_type": "type"
+    ///           }
+    ///         }
+    ///       }
+    ///     ]
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "is_lack_of_capacity": true,
+    ///       "occupied_capacity": "0x16b969d00"
+    ///     },
+    ///     {
+    ///       "is_lack_of_capacity": false,
+    ///       "occupied_capacity": "0x28fa6ae00"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "calculate_occupied_capacities")]
+    fn calculate_occupied_capacities(
+        &self,
+        outputs: Vec<CellOutputWithDataLen>,
+    ) -> Result<Vec<OccupiedCapacity>>;
 }
 
 pub(crate) struct ExperimentRpcImpl {
@@ -171,9 +500,16 @@ pub(crate) struct ExperimentRpcImpl {
 }
 
 impl ExperimentRpc for ExperimentRpcImpl {
-    fn dry_run_transaction(&self, tx: Transaction) -> Result<DryRunResult> {
+    fn dry_run_transaction(&self, tx: Transaction, fork_tip: Option<H256>) -> Result<DryRunResult> {
         let tx: packed::Transaction = tx.into();
-        DryRunner::new(&self.shared).run(tx)
+        match fork_tip {
+            Some(fork_tip) => {
+                let snapshot: &Snapshot = &self.shared.snapshot();
+                let fork = ForkOverlay::new(snapshot, &fork_tip.pack())?;
+                dry_run(snapshot, tx, &fork, &fork, &fork.tip_header)
+            }
+            None => DryRunner::new(&self.shared).run(tx),
+        }
     }
 
     fn calculate_dao_maximum_withdraw(
@@ -209,6 +545,145 @@ impl ExperimentRpc for ExperimentRpcImpl {
             Err(err) => Err(RPCError::from_ckb_error(err)),
         }
     }
+
+    fn fill_partial_transaction(
+        &self,
+        partial_transaction: PartialTransaction,
+    ) -> Result<PartialTransaction> {
+        let snapshot: &Snapshot = &self.shared.snapshot();
+        let tx: packed::Transaction = partial_transaction.transaction.clone().into();
+
+        let mut input_cells = Vec::new();
+        let mut input_cells_data = Vec::new();
+        for input in tx.raw().inputs().into_iter() {
+            let out_point = input.previous_output();
+            match snapshot.cell(&out_point, true) {
+                CellStatus::Live(cell_meta) => {
+                    input_cells.push(cell_meta.cell_output);
+                    input_cells_data.push(cell_meta.mem_cell_data.unwrap_or_default());
+                }
+                CellStatus::Dead => {
+                    return Err(RPCError::custom_with_error(
+                        RPCError::TransactionFailedToResolve,
+                        OutPointError::Dead(out_point),
+                    ));
+                }
+                CellStatus::Unknown => {
+                    return Err(RPCError::custom_with_error(
+                        RPCError::TransactionFailedToResolve,
+                        OutPointError::Unknown(out_point),
+                    ));
+                }
+            }
+        }
+
+        let hints: HashMap<_, _> = partial_transaction
+            .signing_groups
+            .into_iter()
+            .map(|group| ((group.script, group.script_type), group.hint))
+            .collect();
+        let mut lock_groups = SigningGroups::default();
+        let mut type_groups = SigningGroups::default();
+        for (index, cell) in input_cells.iter().enumerate() {
+            lock_groups.add_input(cell.lock(), ScriptGroupType::Lock, index);
+            if let Some(type_) = cell.type_().to_opt() {
+                type_groups.add_input(type_, ScriptGroupType::Type, index);
+            }
+        }
+        for (index, output) in tx.raw().outputs().into_iter().enumerate() {
+            if let Some(type_) = output.type_().to_opt() {
+                type_groups.add_output(type_, index);
+            }
+        }
+        let signing_groups = lock_groups
+            .groups
+            .into_iter()
+            .chain(type_groups.groups)
+            .map(|mut group| {
+                if let Some(hint) = hints.get(&(group.script.clone(), group.script_type)) {
+                    group.hint = hint.clone();
+                }
+                group
+            })
+            .collect();
+
+        Ok(PartialTransaction {
+            transaction: partial_transaction.transaction,
+            input_cells: input_cells.into_iter().map(Into::into).collect(),
+            input_cells_data: input_cells_data
+                .into_iter()
+                .map(JsonBytes::from_bytes)
+                .collect(),
+            signing_groups,
+        })
+    }
+
+    fn calculate_occupied_capacity(
+        &self,
+        output: CellOutput,
+        data_len: Uint64,
+    ) -> Result<OccupiedCapacity> {
+        occupied_capacity(output, data_len)
+    }
+
+    fn calculate_occupied_capacities(
+        &self,
+        outputs: Vec<CellOutputWithDataLen>,
+    ) -> Result<Vec<OccupiedCapacity>> {
+        outputs
+            .into_iter()
+            .map(|item| occupied_capacity(item.output, item.data_len))
+            .collect()
+    }
+}
+
+fn occupied_capacity(output: CellOutput, data_len: Uint64) -> Result<OccupiedCapacity> {
+    let output: packed::CellOutput = output.into();
+    let occupied_capacity = core::Capacity::bytes(data_len.value() as usize)
+        .and_then(|data_capacity| output.occupied_capacity(data_capacity))
+        .map_err(|err| RPCError::invalid_params(format!("occupied capacity overflow: {}", err)))?;
+    Ok(OccupiedCapacity {
+        occupied_capacity: occupied_capacity.into(),
+        is_lack_of_capacity: occupied_capacity > output.capacity().unpack(),
+    })
+}
+
+// Collects the signing groups in the order of the first input or output in the group.
+#[derive(Default)]
+struct SigningGroups {
+    groups: Vec<SigningGroup>,
+    positions: HashMap<packed::Script, usize>,
+}
+
+impl SigningGroups {
+    fn group_mut(
+        &mut self,
+        script: packed::Script,
+        script_type: ScriptGroupType,
+    ) -> &mut SigningGroup {
+        let groups = &mut self.groups;
+        let position = *self.positions.entry(script.clone()).or_insert_with(|| {
+            groups.push(SigningGroup {
+                script: Script::from(script),
+                script_type,
+                ..Default::default()
+            });
+            groups.len() - 1
+        });
+        &mut groups[position]
+    }
+
+    fn add_input(&mut self, script: packed::Script, script_type: ScriptGroupType, index: usize) {
+        self.group_mut(script, script_type)
+            .input_indices
+            .push((index as u32).into());
+    }
+
+    fn add_output(&mut self, script: packed::Script, index: usize) {
+        self.group_mut(script, ScriptGroupType::Type)
+            .output_indices
+            .push((index as u32).into());
+    }
 }
 
 // DryRunner dry run given transaction, and return the result, including execution cycles.
@@ -247,46 +722,499 @@ impl<'a> DryRunner<'a> {
 
     pub(crate) fn run(&self, tx: packed::Transaction) -> Result<DryRunResult> {
         let snapshot: &Snapshot = &self.shared.snapshot();
-        let consensus = snapshot.consensus();
-        let tip_header = snapshot.tip_header();
-        let tx_env = TxVerifyEnv::new_submit(&tip_header);
-        let resolve_opts = {
-            let proposal_window = consensus.tx_proposal_window();
-            let epoch_number = tx_env.epoch_number(proposal_window);
-            let hardfork_switch = consensus.hardfork_switch();
-            let flag = hardfork_switch.is_remove_header_deps_immature_rule_enabled(epoch_number);
-            ResolveOptions::empty().set_skip_immature_header_deps_check(flag)
-        };
-        match resolve_transaction_with_options(
-            tx.into_view(),
-            &mut HashSet::new(),
-            self,
-            self,
-            resolve_opts,
-        ) {
-            Ok(resolved) => {
-                let max_cycles = consensus.max_block_cycles;
-                match ScriptVerifier::new(
-                    &resolved,
-                    consensus,
-                    &snapshot.as_data_provider(),
-                    &tx_env,
-                )
+        dry_run(snapshot, tx, self, self, &snapshot.tip_header())
+    }
+}
+
+// Resolves the transaction with the providers and runs the scripts as if it is submitted after
+// `tip_header`.
+fn dry_run<CP: CellProvider, HC: HeaderChecker>(
+    snapshot: &Snapshot,
+    tx: packed::Transaction,
+    cell_provider: &CP,
+    header_checker: &HC,
+    tip_header: &core::HeaderView,
+) -> Result<DryRunResult> {
+    let consensus = snapshot.consensus();
+    let tx_env = TxVerifyEnv::new_submit(tip_header);
+    let resolve_opts = {
+        let proposal_window = consensus.tx_proposal_window();
+        let epoch_number = tx_env.epoch_number(proposal_window);
+        let hardfork_switch = consensus.hardfork_switch();
+        let flag = hardfork_switch.is_remove_header_deps_immature_rule_enabled(epoch_number);
+        ResolveOptions::empty().set_skip_immature_header_deps_check(flag)
+    };
+    match resolve_transaction_with_options(
+        tx.into_view(),
+        &mut HashSet::new(),
+        cell_provider,
+        header_checker,
+        resolve_opts,
+    ) {
+        Ok(resolved) => {
+            let max_cycles = consensus.max_block_cycles;
+            match ScriptVerifier::new(&resolved, consensus, &snapshot.as_data_provider(), &tx_env)
                 .verify(max_cycles)
-                {
-                    Ok(cycles) => Ok(DryRunResult {
-                        cycles: cycles.into(),
-                    }),
-                    Err(err) => Err(RPCError::custom_with_error(
-                        RPCError::TransactionFailedToVerify,
-                        err,
-                    )),
+            {
+                Ok(cycles) => Ok(DryRunResult {
+                    cycles: cycles.into(),
+                }),
+                Err(err) => Err(RPCError::custom_with_error(
+                    RPCError::TransactionFailedToVerify,
+                    err,
+                )),
+            }
+        }
+        Err(err) => Err(RPCError::custom_with_error(
+            RPCError::TransactionFailedToResolve,
+            err,
+        )),
+    }
+}
+
+// Limits the fork blocks and the reverted canonical blocks.
+const MAX_FORK_DEPTH: usize = 256;
+
+// ForkOverlay resolves cells and headers as if the chain ends at the fork tip, which may be a
+// block not in the canonical chain.
+//
+// It applies the fork blocks and reverts the canonical blocks after the fork point on the
+// snapshot, without touching the store.
+pub(crate) struct ForkOverlay<'a> {
+    snapshot: &'a Snapshot,
+    tip_header: core::HeaderView,
+    // The number of the last common block of the fork and the canonical chain
+    fork_point: core::BlockNumber,
+    fork_blocks: HashSet<packed::Byte32>,
+    // The live cells created by the fork blocks
+    created_cells: HashMap<packed::OutPoint, CellMeta>,
+    // The cells consumed by the fork blocks
+    dead_cells: HashSet<packed::OutPoint>,
+    // The transactions in the reverted canonical blocks
+    reverted_txs: HashSet<packed::Byte32>,
+    // The cells consumed by the reverted canonical blocks
+    revived_cells: HashSet<packed::OutPoint>,
+}
+
+impl<'a> ForkOverlay<'a> {
+    pub(crate) fn new(snapshot: &'a Snapshot, fork_tip: &packed::Byte32) -> Result<Self> {
+        let tip_header = snapshot
+            .get_block_header(fork_tip)
+            .ok_or_else(|| RPCError::invalid_params(format!("Cannot find block {}", fork_tip)))?;
+
+        let mut fork_headers = Vec::new();
+        let mut header = tip_header.clone();
+        while !snapshot.is_main_chain(&header.hash()) {
+            if fork_headers.len() >= MAX_FORK_DEPTH {
+                return Err(RPCError::invalid_params(format!(
+                    "The fork is deeper than {} blocks",
+                    MAX_FORK_DEPTH
+                )));
+            }
+            let parent = snapshot
+                .get_block_header(&header.parent_hash())
+                .ok_or_else(|| {
+                    RPCError::invalid_params(format!(
+                        "Cannot find the parent of block {}",
+                        header.hash()
+                    ))
+                })?;
+            fork_headers.push(header);
+            header = parent;
+        }
+        let fork_point = header.number();
+        if snapshot.tip_number() - fork_point > MAX_FORK_DEPTH as u64 {
+            return Err(RPCError::invalid_params(format!(
+                "The fork point is more than {} blocks behind the tip",
+                MAX_FORK_DEPTH
+            )));
+        }
+
+        let mut overlay = ForkOverlay {
+            snapshot,
+            tip_header,
+            fork_point,
+            fork_blocks: HashSet::new(),
+            created_cells: HashMap::new(),
+            dead_cells: HashSet::new(),
+            reverted_txs: HashSet::new(),
+            revived_cells: HashSet::new(),
+        };
+
+        for number in (fork_point + 1)..=snapshot.tip_number() {
+            let hash = snapshot
+                .get_block_hash(number)
+                .expect("canonical block exists");
+            for tx in snapshot.get_block_body(&hash) {
+                if !tx.is_cellbase() {
+                    overlay.revived_cells.extend(tx.input_pts_iter());
                 }
+                overlay.reverted_txs.insert(tx.hash());
             }
-            Err(err) => Err(RPCError::custom_with_error(
-                RPCError::TransactionFailedToResolve,
-                err,
-            )),
         }
+
+        for header in fork_headers.into_iter().rev() {
+            let hash = header.hash();
+            for (index, tx) in snapshot.get_block_body(&hash).into_iter().enumerate() {
+                if !tx.is_cellbase() {
+                    for out_point in tx.input_pts_iter() {
+                        overlay.created_cells.remove(&out_point);
+                        overlay.dead_cells.insert(out_point);
+                    }
+                }
+                let tx_info =
+                    TransactionInfo::new(header.number(), header.epoch(), hash.clone(), index);
+                for (output_index, (output, data)) in tx.outputs_with_data_iter().enumerate() {
+                    let out_point = packed::OutPoint::new(tx.hash(), output_index as u32);
+                    let cell_meta = CellMetaBuilder::from_cell_output(output, data)
+                        .out_point(out_point.clone())
+                        .transaction_info(tx_info.clone())
+                        .build();
+                    overlay.created_cells.insert(out_point, cell_meta);
+                }
+            }
+            overlay.fork_blocks.insert(hash);
+        }
+
+        Ok(overlay)
+    }
+
+    // Rebuilds the cell consumed by the reverted canonical blocks from the transaction.
+    fn revived_cell(&self, out_point: &packed::OutPoint) -> CellStatus {
+        let tx_hash = out_point.tx_hash();
+        let index: usize = out_point.index().unpack();
+        self.snapshot
+            .get_transaction(&tx_hash)
+            .and_then(|(tx, _)| tx.output_with_data(index))
+            .and_then(|(output, data)| {
+                self.snapshot.get_transaction_info(&tx_hash).map(|tx_info| {
+                    CellMetaBuilder::from_cell_output(output, data)
+                        .out_point(out_point.clone())
+                        .transaction_info(tx_info)
+                        .build()
+                })
+            })
+            .map(CellStatus::live_cell)
+            .unwrap_or(CellStatus::Unknown)
+    }
+}
+
+impl<'a> CellProvider for ForkOverlay<'a> {
+    fn cell(&self, out_point: &packed::OutPoint, eager_load: bool) -> CellStatus {
+        if self.dead_cells.contains(out_point) {
+            CellStatus::Dead
+        } else if let Some(cell_meta) = self.created_cells.get(out_point) {
+            CellStatus::live_cell(cell_meta.clone())
+        } else if self.reverted_txs.contains(&out_point.tx_hash()) {
+            CellStatus::Unknown
+        } else if self.revived_cells.contains(out_point) {
+            self.revived_cell(out_point)
+        } else {
+            self.snapshot.cell(out_point, eager_load)
+        }
+    }
+}
+
+impl<'a> HeaderChecker for ForkOverlay<'a> {
+    fn check_valid(&self, block_hash: &packed::Byte32) -> std::result::Result<(), OutPointError> {
+        match self.snapshot.get_block_header(block_hash) {
+            Some(header)
+                if self.fork_blocks.contains(block_hash)
+                    || (header.number() <= self.fork_point
+                        && self.snapshot.is_main_chain(block_hash)) =>
+            {
+                let threshold = self.snapshot.consensus().cellbase_maturity().to_rational()
+                    + header.epoch().to_rational();
+                let current = self.tip_header.epoch().to_rational();
+                if current < threshold {
+                    Err(OutPointError::ImmatureHeader(block_hash.clone()))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(OutPointError::InvalidHeader(block_hash.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_chain::chain::{ChainController, ChainService};
+    use ckb_launcher::SharedBuilder;
+    use ckb_test_chain_utils::{always_success_cellbase, always_success_consensus};
+    use ckb_types::core::{
+        capacity_bytes, BlockBuilder, BlockView, Capacity, TransactionBuilder, TransactionView,
+    };
+    use ckb_verification_traits::Switch;
+    use std::sync::Arc;
+
+    fn start_chain() -> (Shared, ChainController) {
+        let (shared, mut pack) = SharedBuilder::with_temp_db()
+            .consensus(always_success_consensus())
+            .build()
+            .unwrap();
+        let chain_controller =
+            ChainService::new(shared.clone(), pack.take_proposal_table()).start::<&str>(None);
+        (shared, chain_controller)
+    }
+
+    fn new_block(
+        shared: &Shared,
+        parent: &core::HeaderView,
+        txs: Vec<TransactionView>,
+    ) -> BlockView {
+        let number = parent.number() + 1;
+        let epoch = shared
+            .consensus()
+            .next_epoch_ext(parent, &shared.snapshot().as_data_provider())
+            .unwrap()
+            .epoch();
+        BlockBuilder::default()
+            .transaction(always_success_cellbase(
+                number,
+                Capacity::zero(),
+                shared.consensus(),
+            ))
+            .transactions(txs)
+            .parent_hash(parent.hash())
+            .number(number.pack())
+            .epoch(epoch.number_with_fraction(number).pack())
+            .timestamp((parent.timestamp() + 1).pack())
+            .compact_target(parent.compact_target().pack())
+            .build()
+    }
+
+    fn spend(out_point: packed::OutPoint) -> TransactionView {
+        TransactionBuilder::default()
+            .input(packed::CellInput::new(out_point, 0))
+            .output(
+                packed::CellOutput::new_builder()
+                    .capacity(capacity_bytes!(100).pack())
+                    .build(),
+            )
+            .output_data(Default::default())
+            .build()
+    }
+
+    fn process(chain_controller: &ChainController, block: &BlockView) {
+        chain_controller
+            .internal_process_block(Arc::new(block.clone()), Switch::DISABLE_ALL)
+            .expect("process block ok");
+    }
+
+    // genesis -> main1 -> main2
+    //         \-> fork1
+    //
+    // main1 spends the genesis cell and fork1 spends an unrelated cell.
+    struct Fork {
+        genesis_cell: packed::OutPoint,
+        main_cell: packed::OutPoint,
+        fork_cell: packed::OutPoint,
+        fork_tip: packed::Byte32,
+    }
+
+    fn build_fork(shared: &Shared, chain_controller: &ChainController) -> Fork {
+        let genesis = shared.consensus().genesis_block().clone();
+        let genesis_tx = &genesis.transactions()[0];
+        let genesis_cell = packed::OutPoint::new(genesis_tx.hash(), 0);
+
+        let main_tx = spend(genesis_cell.clone());
+        let main1 = new_block(shared, &genesis.header(), vec![main_tx.clone()]);
+        process(chain_controller, &main1);
+        let main2 = new_block(shared, &main1.header(), vec![]);
+        process(chain_controller, &main2);
+
+        let fork_tx = spend(packed::OutPoint::new(Default::default(), 0));
+        let fork1 = new_block(shared, &genesis.header(), vec![fork_tx.clone()]);
+        process(chain_controller, &fork1);
+        assert_eq!(shared.snapshot().tip_hash(), main2.hash());
+
+        Fork {
+            genesis_cell,
+            main_cell: packed::OutPoint::new(main_tx.hash(), 0),
+            fork_cell: packed::OutPoint::new(fork_tx.hash(), 0),
+            fork_tip: fork1.hash(),
+        }
+    }
+
+    #[test]
+    fn test_fork_created_cells() {
+        let (shared, chain_controller) = start_chain();
+        let fork = build_fork(&shared, &chain_controller);
+        let snapshot: &Snapshot = &shared.snapshot();
+
+        assert!(matches!(
+            snapshot.cell(&fork.fork_cell, false),
+            CellStatus::Unknown
+        ));
+        let overlay = ForkOverlay::new(snapshot, &fork.fork_tip).expect("fork overlay");
+        match overlay.cell(&fork.fork_cell, false) {
+            CellStatus::Live(cell_meta) => {
+                let tx_info = cell_meta.transaction_info.expect("transaction info");
+                assert_eq!(tx_info.block_hash, fork.fork_tip);
+                assert_eq!(tx_info.block_number, 1);
+            }
+            status => panic!("unexpected cell status {:?}", status),
+        }
+        // The cells created by the reverted canonical blocks do not exist on the fork
+        assert!(matches!(
+            overlay.cell(&fork.main_cell, false),
+            CellStatus::Unknown
+        ));
+        assert!(overlay.check_valid(&fork.fork_tip).is_ok());
+    }
+
+    #[test]
+    fn test_fork_revived_cells() {
+        let (shared, chain_controller) = start_chain();
+        let fork = build_fork(&shared, &chain_controller);
+        let snapshot: &Snapshot = &shared.snapshot();
+
+        // The genesis cell is spent on the main chain, but alive on the fork
+        assert!(matches!(
+            snapshot.cell(&fork.genesis_cell, false),
+            CellStatus::Dead | CellStatus::Unknown
+        ));
+        let overlay = ForkOverlay::new(snapshot, &fork.fork_tip).expect("fork overlay");
+        match overlay.cell(&fork.genesis_cell, false) {
+            CellStatus::Live(cell_meta) => {
+                assert_eq!(cell_meta.out_point, fork.genesis_cell);
+                let tx_info = cell_meta.transaction_info.expect("transaction info");
+                assert_eq!(tx_info.block_number, 0);
+            }
+            status => panic!("unexpected cell status {:?}", status),
+        }
+        // The main chain blocks after the fork point are not valid header deps on the fork
+        let main_tip = snapshot.tip_hash();
+        assert!(overlay.check_valid(&main_tip).is_err());
+    }
+
+    #[test]
+    fn test_fork_parent_unknown() {
+        let (shared, chain_controller) = start_chain();
+        let fork = build_fork(&shared, &chain_controller);
+        let fork_tip = shared
+            .snapshot()
+            .get_block_header(&fork.fork_tip)
+            .expect("fork tip");
+
+        // Store a block whose parent is not stored
+        let orphan_parent = new_block(&shared, &fork_tip, vec![]);
+        let orphan = new_block(&shared, &orphan_parent.header(), vec![]);
+        let db_txn = shared.store().begin_transaction();
+        db_txn.insert_block(&orphan).expect("insert block");
+        db_txn.commit().expect("commit");
+        shared.refresh_snapshot();
+
+        let snapshot: &Snapshot = &shared.snapshot();
+        match ForkOverlay::new(snapshot, &orphan.hash()) {
+            Err(err) => assert!(err.message.contains("Cannot find the parent")),
+            Ok(_) => panic!("the fork with an unknown block should be rejected"),
+        }
+    }
+
+    fn cell_output(capacity: core::Capacity, has_type: bool) -> CellOutput {
+        let lock = packed::Script::new_builder()
+            .args(vec![0u8; 20].pack())
+            .hash_type(core::ScriptHashType::Type.into())
+            .build();
+        let type_ = packed::Script::new_builder()
+            .hash_type(core::ScriptHashType::Type.into())
+            .build();
+        packed::CellOutput::new_builder()
+            .capacity(capacity.pack())
+            .lock(lock)
+            .type_(Some(type_).filter(|_| has_type).pack())
+            .build()
+            .into()
+    }
+
+    fn occupied(occupied_capacity: core::Capacity, is_lack_of_capacity: bool) -> OccupiedCapacity {
+        OccupiedCapacity {
+            occupied_capacity: occupied_capacity.into(),
+            is_lack_of_capacity,
+        }
+    }
+
+    #[test]
+    fn test_calculate_occupied_capacity() {
+        let (shared, _chain_controller) = start_chain();
+        let rpc = ExperimentRpcImpl { shared };
+
+        // capacity (8) + lock code_hash (32) + hash_type (1) + args (20)
+        let output = cell_output(capacity_bytes!(61), false);
+        assert_eq!(
+            rpc.calculate_occupied_capacity(output, 0u64.into()),
+            Ok(occupied(capacity_bytes!(61), false))
+        );
+
+        // The type script occupies 33 bytes without args.
+        let output = cell_output(capacity_bytes!(100), true);
+        assert_eq!(
+            rpc.calculate_occupied_capacity(output.clone(), 0u64.into()),
+            Ok(occupied(capacity_bytes!(94), false))
+        );
+        assert_eq!(
+            rpc.calculate_occupied_capacity(output, 16u64.into()),
+            Ok(occupied(capacity_bytes!(110), true))
+        );
+
+        let output = cell_output(capacity_bytes!(100), false);
+        assert_eq!(
+            rpc.calculate_occupied_capacity(output, 40u64.into()),
+            Ok(occupied(capacity_bytes!(101), true))
+        );
+    }
+
+    #[test]
+    fn test_calculate_occupied_capacity_overflow() {
+        let (shared, _chain_controller) = start_chain();
+        let rpc = ExperimentRpcImpl { shared };
+        let max_data_len = u64::MAX / capacity_bytes!(1).as_u64();
+
+        // The data capacity overflows.
+        let output = cell_output(capacity_bytes!(100), false);
+        let err = rpc
+            .calculate_occupied_capacity(output.clone(), (max_data_len + 1).into())
+            .unwrap_err();
+        assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
+
+        // The data capacity fits, but the total overflows.
+        let err = rpc
+            .calculate_occupied_capacity(output, max_data_len.into())
+            .unwrap_err();
+        assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
+    }
+
+    #[test]
+    fn test_calculate_occupied_capacities() {
+        let (shared, _chain_controller) = start_chain();
+        let rpc = ExperimentRpcImpl { shared };
+        let item = |output: CellOutput, data_len: u64| CellOutputWithDataLen {
+            output,
+            data_len: data_len.into(),
+        };
+
+        assert_eq!(
+            rpc.calculate_occupied_capacities(vec![
+                item(cell_output(capacity_bytes!(50), false), 0),
+                item(cell_output(capacity_bytes!(200), true), 16),
+            ]),
+            Ok(vec![
+                occupied(capacity_bytes!(61), true),
+                occupied(capacity_bytes!(110), false),
+            ])
+        );
+        assert_eq!(rpc.calculate_occupied_capacities(vec![]), Ok(vec![]));
+
+        // One overflow fails the whole batch.
+        let err = rpc
+            .calculate_occupied_capacities(vec![
+                item(cell_output(capacity_bytes!(50), false), 0),
+                item(cell_output(capacity_bytes!(50), false), u64::MAX),
+            ])
+            .unwrap_err();
+        assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
     }
 }
@@ -58,6 +58,21 @@
 //! removed in 0.37.0. The minor versions are released monthly, so there's at least a two-month
 //! buffer for a deprecated RPC method.
 //!
+//! The fields removed from the RPC results follow the same process. A removed field is restored
+//! in the results when `rpc.enable_deprecated_rpc` is set, until it is deleted in the next
+//! release cycle.
+//!
+//! The RPC [`rpc_version`](trait.StatsRpc.html#tymethod.rpc_version) lists the deprecated
+//! methods and fields. When a deprecated method is disabled, the `data` of its error
+//! `Deprecated` describes the deprecation and the replacement.
+//!
+//! ## Schema Discovery
+//!
+//! The method `rpc.discover` returns an [OpenRPC](https://spec.open-rpc.org) document of the
+//! methods in the enabled modules. The document is generated from the RPC traits when building
+//! CKB, so it always matches the running node. Client SDK generators and documentation sites can
+//! consume it as a machine-readable schema.
+//!
 //! ## JSON Cheatsheet
 //!
 //! CKB uses a framework to serialize into and deserialize from JSON. Some Rust std-lib
@@ -1,7 +1,8 @@
 use crate::error::RPCError;
 use ckb_jsonrpc_types::{
-    BannedAddr, LocalNode, LocalNodeProtocol, NodeAddress, PeerSyncState, RemoteNode,
-    RemoteNodeProtocol, SyncState, Timestamp,
+    BannedAddr, BlockFetchPeer, LocalNode, LocalNodeProtocol, NetworkTime, NodeAddress,
+    PeerSyncState, PeerTimeOffset, RemoteNode, RemoteNodeProtocol, SyncProgress, SyncState,
+    Timestamp,
 };
 use ckb_network::{extract_peer_id, NetworkController};
 use ckb_sync::SyncShared;
@@ -78,6 +79,9 @@ pub trait NetRpc {
 
     /// Returns the connected peers' information.
     ///
+    /// The message statistics of each protocol and the chain synchronization state help to spot
+    /// the stuck peers.
+    ///
     /// ## Examples
     ///
     /// Request
@@ -116,34 +120,82 @@ pub trait NetRpc {
     ///       "protocols": [
     ///         {
     ///           "id": "0x4",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "0.0.1"
     ///         },
     ///         {
     ///           "id": "0x2",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "0.0.1"
     ///         },
     ///         {
     ///           "id": "0x1",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "0.0.1"
     ///         },
     ///         {
     ///           "id": "0x64",
+    ///           "last_received_at": "0x17b9f4a0f80",
+    ///           "last_sent_at": "0x17b9f4a0e12",
+    ///           "received_bytes": "0x1d3f2",
+    ///           "received_messages": "0x4b0",
+    ///           "sent_bytes": "0x2a8f",
+    ///           "sent_messages": "0x5dc",
     ///           "version": "1"
     ///         },
     ///         {
     ///           "id": "0x6e",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "1"
     ///         },
     ///         {
     ///           "id": "0x66",
+    ///           "last_received_at": "0x17b9f49fc2c",
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x10",
+    ///           "received_messages": "0x1",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "1"
     ///         },
     ///         {
     ///           "id": "0x65",
+    ///           "last_received_at": "0x17b9f4a0b5d",
+    ///           "last_sent_at": "0x17b9f4a0f01",
+    ///           "received_bytes": "0x3e81",
+    ///           "received_messages": "0x9c",
+    ///           "sent_bytes": "0xc5d2",
+    ///           "sent_messages": "0x1f4",
     ///           "version": "1"
     ///         },
     ///         {
     ///           "id": "0x0",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "0.0.1"
     ///         }
     ///       ],
@@ -151,6 +203,7 @@ pub trait NetRpc {
     ///         "best_known_header_hash": null,
     ///         "best_known_header_number": null,
     ///         "can_fetch_count": "0x80",
+    ///         "headers_behind": null,
     ///         "inflight_count": "0xa",
     ///         "last_common_header_hash": null,
     ///         "last_common_header_number": null,
@@ -172,34 +225,82 @@ pub trait NetRpc {
     ///       "protocols": [
     ///         {
     ///           "id": "0x0",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "0.0.1"
     ///         },
     ///         {
     ///           "id": "0x2",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "0.0.1"
     ///         },
     ///         {
     ///           "id": "0x6e",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "1"
     ///         },
     ///         {
     ///           "id": "0x66",
+    ///           "last_received_at": "0x17b9f49fc2c",
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x10",
+    ///           "received_messages": "0x1",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "1"
     ///         },
     ///         {
     ///           "id": "0x1",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "0.0.1"
     ///         },
     ///         {
     ///           "id": "0x65",
+    ///           "last_received_at": "0x17b9f4a0b5d",
+    ///           "last_sent_at": "0x17b9f4a0f01",
+    ///           "received_bytes": "0x3e81",
+    ///           "received_messages": "0x9c",
+    ///           "sent_bytes": "0xc5d2",
+    ///           "sent_messages": "0x1f4",
     ///           "version": "1"
     ///         },
     ///         {
     ///           "id": "0x64",
+    ///           "last_received_at": "0x17b9f4a0f80",
+    ///           "last_sent_at": "0x17b9f4a0e12",
+    ///           "received_bytes": "0x1d3f2",
+    ///           "received_messages": "0x4b0",
+    ///           "sent_bytes": "0x2a8f",
+    ///           "sent_messages": "0x5dc",
     ///           "version": "1"
     ///         },
     ///         {
     ///           "id": "0x4",
+    ///           "last_received_at": null,
+    ///           "last_sent_at": null,
+    ///           "received_bytes": "0x0",
+    ///           "received_messages": "0x0",
+    ///           "sent_bytes": "0x0",
+    ///           "sent_messages": "0x0",
     ///           "version": "0.0.1"
     ///         }
     ///       ],
@@ -207,6 +308,7 @@ pub trait NetRpc {
     ///         "best_known_header_hash": "0x2157c72b3eddd41a7a14c361173cd22ef27d7e0a29eda2e511ee0b3598c0b895",
     ///         "best_known_header_number": "0xdb835",
     ///         "can_fetch_count": "0x80",
+    ///         "headers_behind": "0x0",
     ///         "inflight_count": "0xa",
     ///         "last_common_header_hash": "0xc63026bd881d880bb142c855dc8153187543245f0a94391c831c75df31f263c4",
     ///         "last_common_header_number": "0x4dc08",
@@ -222,6 +324,9 @@ pub trait NetRpc {
 
     /// Returns all banned IPs/Subnets.
     ///
+    /// The IPs/Subnets are banned by [`set_ban`](#tymethod.set_ban) or automatically when the
+    /// misbehavior score of the peer drops below the threshold. The expired bans are excluded.
+    ///
     /// ## Examples
     ///
     /// Request
@@ -283,6 +388,8 @@ pub trait NetRpc {
 
     /// Inserts or deletes an IP/Subnet from the banned list
     ///
+    /// The connected peers in the inserted IP/Subnet are disconnected.
+    ///
     /// ## Params
     ///
     /// * `address` - The IP/Subnet with an optional netmask (default is /32 = single IP). Examples:
@@ -339,6 +446,10 @@ pub trait NetRpc {
 
     /// Returns chain synchronization state of this node.
     ///
+    /// The field `block_fetch_peers` lists the peers chosen to download blocks from, ranked by the
+    /// expected block throughput using the ping RTT and the block response time. The block
+    /// download also explores the peers ranked low periodically.
+    ///
     /// ## Examples
     ///
     /// Request
@@ -361,6 +472,7 @@ pub trait NetRpc {
     ///   "result": {
     ///     "best_known_block_number": "0x400",
     ///     "best_known_block_timestamp": "0x5cd2b117",
+    ///     "block_fetch_peers": [],
     ///     "fast_time": "0x3e8",
     ///     "ibd": true,
     ///     "inflight_blocks_count": "0x0",
@@ -373,6 +485,89 @@ pub trait NetRpc {
     #[rpc(name = "sync_state")]
     fn sync_state(&self) -> Result<SyncState>;
 
+    /// Returns the Initial Block Download progress of this node.
+    ///
+    /// The download and verify rates are measured in a sliding window of the last minute, and
+    /// the estimated time left is calculated from the verify rate.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "sync_progress",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "block_tip": "0x3e8",
+    ///     "download_rate": "0x258",
+    ///     "estimated_time_left": "0x5b8d80",
+    ///     "header_tip": "0x1770",
+    ///     "unverified_tip": "0x4b0",
+    ///     "verify_rate": "0x32"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "sync_progress")]
+    fn sync_progress(&self) -> Result<SyncProgress>;
+
+    /// Returns the network-adjusted time and the time offsets from the connected peers.
+    ///
+    /// The local clock skew makes the node reject valid block headers or produce blocks rejected
+    /// by the peers. The node raises an alert via `notify.network_alert_notify_script` when
+    /// the skew exceeds `network.sync.clock_skew_threshold` in the config.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_network_time",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "adjusted_time": "0x17b9f4a0af3",
+    ///     "clock_skew_threshold": "0x3a98",
+    ///     "local_time": "0x17b9f4a0f80",
+    ///     "offset": 1165,
+    ///     "peers": [
+    ///       {
+    ///         "node_id": "QmSrkzhdBMmfCGx8tQGwgXxzBg8kLtX8qMcqECMuKWsxDV",
+    ///         "offset": 1165
+    ///       },
+    ///       {
+    ///         "node_id": "QmXwUgF48ULy6hkgfqrEwEfuHW7WyWyWauueRDAYQHNDfN",
+    ///         "offset": -20
+    ///       }
+    ///     ]
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_network_time")]
+    fn get_network_time(&self) -> Result<NetworkTime>;
+
     /// Disable/enable all p2p network activity
     ///
     /// ## Params
@@ -503,6 +698,50 @@ pub trait NetRpc {
     #[rpc(name = "remove_node")]
     fn remove_node(&self, peer_id: String) -> Result<()>;
 
+    /// Protects a peer from the eviction, the misbehavior banning and the connection limits, or
+    /// removes its protection.
+    ///
+    /// It helps the operators to keep the connections among their own nodes. The protection
+    /// applies to the connected session immediately and lasts until the node restarts. Configure
+    /// `network.protected_peers` to protect the peers permanently.
+    ///
+    /// ## Params
+    ///
+    /// * `peer_id` - The peer id of the node.
+    /// * `protected` - true to protect the peer, false to remove the protection.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - Expected `peer_id` to be a valid peer id.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "set_peer_protection",
+    ///   "params": [
+    ///     "QmUsZHPbjjzU627UZFt4k8j6ycEcNvXRnVGxCPKqwbAfQS",
+    ///     true
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": null
+    /// }
+    /// ```
+    #[rpc(name = "set_peer_protection")]
+    fn set_peer_protection(&self, peer_id: String, protected: bool) -> Result<()>;
+
     /// Requests that a ping is sent to all connected peers, to measure ping time.
     ///
     /// ## Examples
@@ -566,6 +805,7 @@ impl NetRpc for NetRpcImpl {
     }
 
     fn get_peers(&self) -> Result<Vec<RemoteNode>> {
+        let best_header_number = self.sync_shared.state().shared_best_header().number();
         let peers: Vec<RemoteNode> = self
             .network_controller
             .connected_peers()
@@ -632,14 +872,26 @@ impl NetRpc for NetRpcImpl {
                             can_fetch_count: (inflight_blocks.peer_can_fetch_count(*peer_index)
                                 as u64)
                                 .into(),
+                            headers_behind: state.best_known_header.as_ref().map(|header| {
+                                best_header_number.saturating_sub(header.number()).into()
+                            }),
                         },
                     ),
                     protocols: peer
                         .protocols
                         .iter()
-                        .map(|(protocol_id, protocol_version)| RemoteNodeProtocol {
-                            id: (protocol_id.value() as u64).into(),
-                            version: protocol_version.clone(),
+                        .map(|(protocol_id, protocol_version)| {
+                            let stats = peer.message_stats(*protocol_id);
+                            RemoteNodeProtocol {
+                                id: (protocol_id.value() as u64).into(),
+                                version: protocol_version.clone(),
+                                received_messages: stats.received_messages.into(),
+                                received_bytes: stats.received_bytes.into(),
+                                sent_messages: stats.sent_messages.into(),
+                                sent_bytes: stats.sent_bytes.into(),
+                                last_received_at: stats.last_received_at.map(Into::into),
+                                last_sent_at: stats.last_sent_at.map(Into::into),
+                            }
                         })
                         .collect(),
                 }
@@ -713,6 +965,33 @@ impl NetRpc for NetRpcImpl {
         let state = chain.shared().state();
         let (fast_time, normal_time, low_time) = state.read_inflight_blocks().division_point();
         let best_known = state.shared_best_header();
+        let block_fetch_peers = {
+            let inflight = state.read_inflight_blocks();
+            let mut peers: Vec<_> = self
+                .network_controller
+                .connected_peers()
+                .into_iter()
+                .filter_map(|(peer_index, peer)| {
+                    let stats = inflight.peer_fetch_stats(peer_index)?;
+                    let score = inflight.peer_fetch_score(peer_index, peer.ping_rtt);
+                    let fetch_peer = BlockFetchPeer {
+                        node_id: extract_peer_id(&peer.connected_addr)
+                            .map(|peer_id| peer_id.to_base58())
+                            .unwrap_or_default(),
+                        ping_rtt: peer
+                            .ping_rtt
+                            .map(|duration| (duration.as_millis() as u64).into()),
+                        task_count: (stats.task_count as u64).into(),
+                        inflight_blocks_count: (stats.inflight_count as u64).into(),
+                        received_blocks_count: stats.received_count.into(),
+                        average_response_time: stats.average_response_time.map(Into::into),
+                    };
+                    Some((score, fetch_peer))
+                })
+                .collect();
+            peers.sort_by_key(|(score, _)| ::std::cmp::Reverse(*score));
+            peers.into_iter().map(|(_, peer)| peer).collect()
+        };
         let sync_state = SyncState {
             ibd: chain.is_initial_block_download(),
             best_known_block_number: best_known.number().into(),
@@ -723,11 +1002,53 @@ impl NetRpc for NetRpcImpl {
             fast_time: fast_time.into(),
             normal_time: normal_time.into(),
             low_time: low_time.into(),
+            block_fetch_peers,
         };
 
         Ok(sync_state)
     }
 
+    fn sync_progress(&self) -> Result<SyncProgress> {
+        let progress = self.sync_shared.sync_progress();
+        Ok(SyncProgress {
+            header_tip: progress.header_tip.into(),
+            block_tip: progress.block_tip.into(),
+            unverified_tip: progress.unverified_tip.into(),
+            download_rate: progress.download_rate.into(),
+            verify_rate: progress.verify_rate.into(),
+            estimated_time_left: progress.estimated_time_left.map(Into::into),
+        })
+    }
+
+    fn get_network_time(&self) -> Result<NetworkTime> {
+        let net_time = self.sync_shared.net_time();
+        let local_time = unix_time_as_millis();
+        let peer_offsets = net_time.peer_offsets();
+        let peers = self
+            .network_controller
+            .connected_peers()
+            .into_iter()
+            .filter_map(|(peer_index, peer)| {
+                peer_offsets
+                    .iter()
+                    .find(|(index, _)| *index == peer_index)
+                    .map(|(_, offset)| PeerTimeOffset {
+                        node_id: extract_peer_id(&peer.connected_addr)
+                            .map(|peer_id| peer_id.to_base58())
+                            .unwrap_or_default(),
+                        offset: *offset,
+                    })
+            })
+            .collect();
+        Ok(NetworkTime {
+            local_time: local_time.into(),
+            adjusted_time: net_time.adjusted_time(local_time).into(),
+            offset: net_time.network_offset(),
+            clock_skew_threshold: (net_time.tolerant_offset().as_millis() as u64).into(),
+            peers,
+        })
+    }
+
     fn set_network_active(&self, state: bool) -> Result<()> {
         self.network_controller.set_active(state);
         Ok(())
@@ -748,6 +1069,18 @@ impl NetRpc for NetRpcImpl {
         Ok(())
     }
 
+    fn set_peer_protection(&self, peer_id: String, protected: bool) -> Result<()> {
+        let peer_id = peer_id.parse().map_err(|_| {
+            RPCError::invalid_params(format!(
+                "Expected `params[0]` to be a valid peer id, got {}",
+                peer_id
+            ))
+        })?;
+        self.network_controller
+            .set_peer_protection(peer_id, protected);
+        Ok(())
+    }
+
     fn ping_peers(&self) -> Result<()> {
         self.network_controller.ping_peers();
         Ok(())
@@ -1,14 +1,19 @@
 use crate::error::RPCError;
 use ckb_chain_spec::consensus::Consensus;
 use ckb_constant::hardfork::{mainnet, testnet};
-use ckb_jsonrpc_types::{OutputsValidator, RawTxPool, Script, Transaction, TxPoolInfo};
+use ckb_jsonrpc_types::{
+    BlockProposals, OutputsValidator, PoolEntriesOrder, PoolTxConflicts, PoolTxDetails,
+    ProposalWindowInfo, RawTxPool, Script, StagedTransaction, Transaction, TxPoolInfo, Uint64,
+};
 use ckb_logger::error;
 use ckb_script::IllTransactionChecker;
 use ckb_shared::{shared::Shared, Snapshot};
+use ckb_store::ChainStore;
 use ckb_types::{core, packed, prelude::*, H256};
 use ckb_verification::{Since, SinceMetric, TxVerifyEnv};
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::sync::Arc;
 
@@ -22,11 +27,19 @@ pub trait PoolRpc {
     /// * `transaction` - The transaction.
     /// * `outputs_validator` - Validates the transaction outputs before entering the tx-pool. (**Optional**, default is "well_known_scripts_only").
     ///
+    /// ## Time Lock Staging
+    ///
+    /// The transaction is rejected when the `since` of an input is not satisfied yet. If
+    /// `tx_pool.time_lock_staging` is configured, such a transaction is staged instead and this
+    /// RPC returns its hash. The staged transaction is submitted again on every new tip until it
+    /// enters the pool, becomes invalid or expires. See
+    /// [`get_staged_transactions`](#tymethod.get_staged_transactions).
+    ///
     /// ## Errors
     ///
     /// * [`PoolRejectedTransactionByOutputsValidator (-1102)`](../enum.RPCError.html#variant.PoolRejectedTransactionByOutputsValidator) - The transaction is rejected by the validator specified by `outputs_validator`. If you really want to send transactions with advanced scripts, please set `outputs_validator` to "passthrough".
     /// * [`PoolRejectedTransactionByIllTransactionChecker (-1103)`](../enum.RPCError.html#variant.PoolRejectedTransactionByIllTransactionChecker) - Pool rejects some transactions which seem contain invalid VM instructions. See the issue link in the error message for details.
-    /// * [`PoolRejectedTransactionByMinFeeRate (-1104)`](../enum.RPCError.html#variant.PoolRejectedTransactionByMinFeeRate) - The transaction fee rate must be greater than or equal to the config option `tx_pool.min_fee_rate`.
+    /// * [`PoolRejectedTransactionByMinFeeRate (-1104)`](../enum.RPCError.html#variant.PoolRejectedTransactionByMinFeeRate) - The transaction fee rate must be greater than or equal to the `min_fee_rate` returned by [`tx_pool_info`](#tymethod.tx_pool_info).
     /// * [`PoolRejectedTransactionByMaxAncestorsCountLimit (-1105)`](../enum.RPCError.html#variant.PoolRejectedTransactionByMaxAncestorsCountLimit) - The ancestors count must be greater than or equal to the config option `tx_pool.max_ancestors_count`.
     /// * [`PoolIsFull (-1106)`](../enum.RPCError.html#variant.PoolIsFull) - Pool is full.
     /// * [`PoolRejectedDuplicatedTransaction (-1107)`](../enum.RPCError.html#variant.PoolRejectedDuplicatedTransaction) - The transaction is already in the pool.
@@ -210,11 +223,257 @@ pub trait PoolRpc {
 
     #[rpc(name = "get_raw_tx_pool")]
     fn get_raw_tx_pool(&self, verbose: Option<bool>) -> Result<RawTxPool>;
+
+    /// Returns the in-pool transactions which spend the same inputs as the specified in-pool
+    /// transaction, or spend its cell deps, or use its inputs as cell deps.
+    ///
+    /// The transaction pool only checks double spending against the proposed transactions, so
+    /// the pending transactions may conflict with each other or with a proposed transaction.
+    /// Only one transaction spending the same input can be committed, the others are rejected
+    /// then. The transactions using a cell as a cell dep are rejected once the cell is spent.
+    ///
+    /// The transaction pool does not replace a transaction with another one paying a higher
+    /// fee, the fee rates help to predict which transaction is likely to be committed first.
+    ///
+    /// ## Params
+    ///
+    /// * `tx_hash` - Hash of an in-pool transaction.
+    ///
+    /// ## Returns
+    ///
+    /// Returns null when the transaction is not in the pool.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_pool_conflicts",
+    ///   "params": [
+    ///     "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "conflicts": [
+    ///       {
+    ///         "deps": [],
+    ///         "fee": "0x174876e800",
+    ///         "fee_rate": "0x54f9851e21",
+    ///         "inputs": [
+    ///           {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0x365698b50ca0da75dca2c87f9e7b563811d3b5813736b8cc62cc3b106faceb17"
+    ///           }
+    ///         ],
+    ///         "status": "pending",
+    ///         "tx_hash": "0x7b9e3d27e89e21e3c0b2c1f8a6a0f8d0a6b8de54a04b3b4c2a9b6b0a3c1d2e4f"
+    ///       }
+    ///     ],
+    ///     "fee_rate": "0x52607f1408",
+    ///     "status": "pending",
+    ///     "tx_hash": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_pool_conflicts")]
+    fn get_pool_conflicts(&self, tx_hash: H256) -> Result<Option<PoolTxConflicts>>;
+
+    /// Returns a page of the in-pool transactions with their fee rates, ancestors, descendants
+    /// and eviction scores.
+    ///
+    /// It helps to analyze the pool composition without dumping the whole pool via
+    /// `get_raw_tx_pool`. Get the next page by passing `next_cursor` of the result as `cursor`.
+    ///
+    /// ## Params
+    ///
+    /// * `order_by` - The order of the transactions, "fee_rate" or "timestamp", default is
+    /// "fee_rate".
+    /// * `limit` - The max count of the transactions in the page, default is 100 and at most 1000.
+    /// * `cursor` - The hash of the last transaction in the previous page, starts from the first
+    /// transaction when it is omitted.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams)
+    ///     * `limit` is 0 or exceeds 1000.
+    ///     * The `cursor` transaction has left the pool, start over from the first page.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_pool_entries",
+    ///   "params": [
+    ///     "fee_rate",
+    ///     "0x1",
+    ///     null
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "entries": [
+    ///       {
+    ///         "ancestors_count": "0x0",
+    ///         "ancestors_cycles": "0x0",
+    ///         "ancestors_fee": "0x0",
+    ///         "ancestors_size": "0x0",
+    ///         "cycles": "0x219",
+    ///         "descendants_count": "0x1",
+    ///         "descendants_cycles": "0x219",
+    ///         "descendants_fee": "0x174876e800",
+    ///         "descendants_size": "0x112",
+    ///         "effective_fee_rate": "0x52607f1408",
+    ///         "eviction_score": "0x0",
+    ///         "fee": "0x174876e800",
+    ///         "fee_rate": "0x52607f1408",
+    ///         "size": "0x112",
+    ///         "status": "pending",
+    ///         "timestamp": "0x17c983e6e44",
+    ///         "tx_hash": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///       }
+    ///     ],
+    ///     "next_cursor": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3",
+    ///     "total": "0x2"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_pool_entries")]
+    fn get_pool_entries(
+        &self,
+        order_by: Option<PoolEntriesOrder>,
+        limit: Option<Uint64>,
+        cursor: Option<H256>,
+    ) -> Result<PoolTxDetails>;
+
+    /// Returns the transactions sent by `send_transaction` which are staged until the `since`
+    /// of their inputs are satisfied.
+    ///
+    /// The list is always empty unless `tx_pool.time_lock_staging` is configured.
+    ///
+    /// ## Returns
+    ///
+    /// The staged transactions, the earliest staged first. A transaction leaves the list once it
+    /// enters the pool, becomes invalid or expires.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_staged_transactions",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "expires_at": "0x17cf1b9e244",
+    ///       "immature_input": "0x0",
+    ///       "since": "0x2000000000000400",
+    ///       "staged_at": "0x17c983e6e44",
+    ///       "tx_hash": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_staged_transactions")]
+    fn get_staged_transactions(&self) -> Result<Vec<StagedTransaction>>;
+
+    /// Returns the proposals in the proposal window which are not committed yet, grouped by the
+    /// proposing blocks.
+    ///
+    /// A transaction proposed in block `p` can be committed in the blocks from `p + closest` to
+    /// `p + farthest`. The window covers the blocks whose proposals can still be committed by the
+    /// next block or the later ones. The proposals of a block include the proposals of its
+    /// uncles, and the ids proposed more than once are listed only once.
+    ///
+    /// The miner can only commit the proposed transactions which are in the tx-pool, the
+    /// `missing` ones are not committed by the blocks built by the node, which is a common cause
+    /// of the missing commit rewards.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_proposal_window",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "blocks": [
+    ///       {
+    ///         "committable": true,
+    ///         "hash": "0xa5f5c85987a15de25661e5a214f2c1449cd803f071acc7999820f25246471f40",
+    ///         "missing": [],
+    ///         "number": "0x3f7",
+    ///         "proposals": ["0xa0ef4eb5f4ceeb08a4c8"]
+    ///       },
+    ///       {
+    ///         "committable": false,
+    ///         "hash": "0x2b5ac5ea4f1ab94e8e1a2fe3b2d1e9b0a97d9a83e40bd7c1e7f6c7b3f32a4e5d",
+    ///         "missing": ["0x3f2de8e05c5e7b2c9d7a"],
+    ///         "number": "0x3ff",
+    ///         "proposals": ["0x3f2de8e05c5e7b2c9d7a"]
+    ///       }
+    ///     ],
+    ///     "closest": "0x2",
+    ///     "farthest": "0xa",
+    ///     "tip_hash": "0x2b5ac5ea4f1ab94e8e1a2fe3b2d1e9b0a97d9a83e40bd7c1e7f6c7b3f32a4e5d",
+    ///     "tip_number": "0x3ff"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_proposal_window")]
+    fn get_proposal_window(&self) -> Result<ProposalWindowInfo>;
 }
 
+const DEFAULT_POOL_ENTRIES_LIMIT: u64 = 100;
+const MAX_POOL_ENTRIES_LIMIT: u64 = 1000;
+
 pub(crate) struct PoolRpcImpl {
     shared: Shared,
-    min_fee_rate: core::FeeRate,
     reject_ill_transactions: bool,
     well_known_lock_scripts: Vec<packed::Script>,
     well_known_type_scripts: Vec<packed::Script>,
@@ -223,7 +482,6 @@ pub(crate) struct PoolRpcImpl {
 impl PoolRpcImpl {
     pub fn new(
         shared: Shared,
-        min_fee_rate: core::FeeRate,
         reject_ill_transactions: bool,
         mut extra_well_known_lock_scripts: Vec<packed::Script>,
         mut extra_well_known_type_scripts: Vec<packed::Script>,
@@ -238,7 +496,6 @@ impl PoolRpcImpl {
 
         PoolRpcImpl {
             shared,
-            min_fee_rate,
             reject_ill_transactions,
             well_known_lock_scripts,
             well_known_type_scripts,
@@ -380,7 +637,12 @@ impl PoolRpc for PoolRpcImpl {
         match submit_tx.unwrap() {
             Ok(_) => Ok(tx_hash.unpack()),
             Err(e) => match RPCError::downcast_submit_transaction_reject(&e) {
-                Some(reject) => Err(RPCError::from_submit_transaction_reject(reject)),
+                Some(reject) => match reject.immature_input() {
+                    Some(index) if matches!(tx_pool.stage_tx(tx, index), Ok(true)) => {
+                        Ok(tx_hash.unpack())
+                    }
+                    _ => Err(RPCError::from_submit_transaction_reject(reject)),
+                },
                 None => Err(RPCError::from_ckb_error(e)),
             },
         }
@@ -404,7 +666,7 @@ impl PoolRpc for PoolRpcImpl {
             orphan: (tx_pool_info.orphan_size as u64).into(),
             total_tx_size: (tx_pool_info.total_tx_size as u64).into(),
             total_tx_cycles: tx_pool_info.total_tx_cycles.into(),
-            min_fee_rate: self.min_fee_rate.as_u64().into(),
+            min_fee_rate: tx_pool_info.min_fee_rate.as_u64().into(),
             last_txs_updated_at: tx_pool_info.last_txs_updated_at.into(),
         })
     }
@@ -435,6 +697,137 @@ impl PoolRpc for PoolRpcImpl {
         };
         Ok(raw)
     }
+
+    fn get_pool_conflicts(&self, tx_hash: H256) -> Result<Option<PoolTxConflicts>> {
+        let tx_pool = self.shared.tx_pool_controller();
+        let conflicts = tx_pool
+            .get_pool_conflicts(tx_hash.pack())
+            .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
+        Ok(conflicts.map(Into::into))
+    }
+
+    fn get_pool_entries(
+        &self,
+        order_by: Option<PoolEntriesOrder>,
+        limit: Option<Uint64>,
+        cursor: Option<H256>,
+    ) -> Result<PoolTxDetails> {
+        let limit = limit
+            .map(|limit| limit.value())
+            .unwrap_or(DEFAULT_POOL_ENTRIES_LIMIT);
+        if limit == 0 || limit > MAX_POOL_ENTRIES_LIMIT {
+            return Err(RPCError::invalid_params(format!(
+                "Expected limit in range [1, {}], got {}",
+                MAX_POOL_ENTRIES_LIMIT, limit
+            )));
+        }
+        let order_by = order_by.unwrap_or_default();
+
+        let tx_pool = self.shared.tx_pool_controller();
+        let details = tx_pool
+            .get_entry_details(
+                order_by.into(),
+                limit as usize,
+                cursor.as_ref().map(|cursor| cursor.pack()),
+            )
+            .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
+        details.map(Into::into).ok_or_else(|| {
+            RPCError::invalid_params(format!(
+                "The cursor transaction {:#x} is not in the pool",
+                cursor.expect("only a cursor is missing")
+            ))
+        })
+    }
+
+    fn get_staged_transactions(&self) -> Result<Vec<StagedTransaction>> {
+        let tx_pool = self.shared.tx_pool_controller();
+        let staged = tx_pool
+            .get_staged_txs()
+            .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
+        Ok(staged.into_iter().map(Into::into).collect())
+    }
+
+    fn get_proposal_window(&self) -> Result<ProposalWindowInfo> {
+        let snapshot = self.shared.snapshot();
+        let tip_header = snapshot.tip_header();
+        let tip_number = tip_header.number();
+        let window = snapshot.consensus().tx_proposal_window();
+        let candidate_number = tip_number + 1;
+        let start = candidate_number.saturating_sub(window.farthest()).max(1);
+        let committable_end = candidate_number.saturating_sub(window.closest());
+
+        let blocks: Vec<core::BlockView> = (start..=tip_number)
+            .filter_map(|number| {
+                snapshot
+                    .get_block_hash(number)
+                    .and_then(|hash| snapshot.get_block(&hash))
+            })
+            .collect();
+        // The transactions committed in the window, with the numbers of the committing blocks.
+        let committed: HashMap<packed::ProposalShortId, core::BlockNumber> = blocks
+            .iter()
+            .flat_map(|block| {
+                let number = block.number();
+                block
+                    .transactions()
+                    .into_iter()
+                    .skip(1)
+                    .map(move |tx| (tx.proposal_short_id(), number))
+            })
+            .collect();
+
+        let uncommitted: Vec<(&core::BlockView, Vec<packed::ProposalShortId>)> = blocks
+            .iter()
+            .map(|block| {
+                let mut seen = HashSet::new();
+                let proposals = block
+                    .union_proposal_ids_iter()
+                    .filter(|id| seen.insert(id.clone()))
+                    .filter(|id| {
+                        committed
+                            .get(id)
+                            .map(|committed_number| *committed_number <= block.number())
+                            .unwrap_or(true)
+                    })
+                    .collect();
+                (block, proposals)
+            })
+            .collect();
+
+        let all_proposals: HashSet<packed::ProposalShortId> = uncommitted
+            .iter()
+            .flat_map(|(_, proposals)| proposals.iter().cloned())
+            .collect();
+        let missing: HashSet<packed::ProposalShortId> = self
+            .shared
+            .tx_pool_controller()
+            .fresh_proposals_filter(all_proposals.into_iter().collect())
+            .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?
+            .into_iter()
+            .collect();
+
+        Ok(ProposalWindowInfo {
+            tip_hash: tip_header.hash().unpack(),
+            tip_number: tip_number.into(),
+            closest: window.closest().into(),
+            farthest: window.farthest().into(),
+            blocks: uncommitted
+                .into_iter()
+                .map(|(block, proposals)| BlockProposals {
+                    number: block.number().into(),
+                    hash: block.hash().unpack(),
+                    committable: block.number() <= committable_end,
+                    missing: proposals
+                        .iter()
+                        .filter(|id| missing.contains(id))
+                        .cloned()
+                        .map(Into::into)
+                        .collect(),
+                    proposals: proposals.into_iter().map(Into::into).collect(),
+                })
+                .collect(),
+        })
+    }
 }
 
 struct WellKnownScriptsOnlyValidator<'a> {
@@ -634,8 +1027,22 @@ fn is_well_known_script(script: &packed::Script, well_known_script: &packed::Scr
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ckb_test_chain_utils::ckb_testnet_consensus;
-    use ckb_types::{core, packed};
+    use ckb_app_config::{NetworkConfig, TimeLockStagingConfig, TxPoolConfig};
+    use ckb_chain::chain::{ChainController, ChainService};
+    use ckb_chain_spec::consensus::ConsensusBuilder;
+    use ckb_dao_utils::genesis_dao_data;
+    use ckb_launcher::SharedBuilder;
+    use ckb_network::{DefaultExitHandler, NetworkService, NetworkState};
+    use ckb_test_chain_utils::{
+        always_success_cell, always_success_cellbase, ckb_testnet_consensus,
+    };
+    use ckb_types::{
+        core::{self, capacity_bytes, Capacity, EpochNumberWithFraction},
+        packed,
+        utilities::DIFF_TWO,
+    };
+    use ckb_verification_traits::Switch;
+    use std::{thread, time::Duration};
 
     #[test]
     fn test_default_outputs_validator() {
@@ -798,4 +1205,303 @@ mod tests {
             )
             .build()
     }
+
+    // The tx-pool is updated asynchronously after the chain processes a block.
+    const POOL_UPDATE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+    const POOL_UPDATE_MAX_POLLS: usize = 500;
+
+    struct Chain {
+        shared: Shared,
+        chain_controller: ChainController,
+        // The always success cell
+        cell_dep: packed::CellDep,
+        // The genesis cells locked by the always success script
+        cells: Vec<packed::OutPoint>,
+    }
+
+    fn start_chain(tx_pool_config: TxPoolConfig) -> Chain {
+        let (always_success_cell, always_success_cell_data, always_success_script) =
+            always_success_cell();
+        let always_success_tx = core::TransactionBuilder::default()
+            .input(packed::CellInput::new(packed::OutPoint::null(), 0))
+            .output(always_success_cell.clone())
+            .output_data(always_success_cell_data.pack())
+            .witness(always_success_script.clone().into_witness())
+            .build();
+        let cells_tx = core::TransactionBuilder::default()
+            .input(packed::CellInput::new(packed::OutPoint::null(), 0))
+            .outputs((0..3).map(|_| {
+                packed::CellOutput::new_builder()
+                    .capacity(capacity_bytes!(1000).pack())
+                    .lock(always_success_script.clone())
+                    .build()
+            }))
+            .outputs_data((0..3).map(|_| packed::Bytes::default()))
+            .build();
+        let dao = genesis_dao_data(vec![&always_success_tx, &cells_tx]).unwrap();
+        let genesis = core::BlockBuilder::default()
+            .dao(dao)
+            .compact_target(DIFF_TWO.pack())
+            .transaction(always_success_tx.clone())
+            .transaction(cells_tx.clone())
+            .build();
+        let consensus = ConsensusBuilder::default()
+            .genesis_block(genesis)
+            .cellbase_maturity(EpochNumberWithFraction::new(0, 0, 1))
+            .build();
+
+        let (shared, mut pack) = SharedBuilder::with_temp_db()
+            .consensus(consensus)
+            .tx_pool_config(tx_pool_config)
+            .build()
+            .unwrap();
+        let network_state = Arc::new(
+            NetworkState::from_config(NetworkConfig {
+                path: tempfile::tempdir().unwrap().path().to_path_buf(),
+                ..Default::default()
+            })
+            .expect("Init network state failed"),
+        );
+        let network_controller = NetworkService::new(
+            network_state,
+            Vec::new(),
+            Vec::new(),
+            shared.consensus().identify_name(),
+            "0.1.0".to_string(),
+            DefaultExitHandler::default(),
+        )
+        .start(shared.async_handle())
+        .expect("Start network service failed");
+        pack.take_tx_pool_builder().start(network_controller);
+        let chain_controller =
+            ChainService::new(shared.clone(), pack.take_proposal_table()).start::<&str>(None);
+
+        Chain {
+            shared,
+            chain_controller,
+            cell_dep: packed::CellDep::new_builder()
+                .out_point(packed::OutPoint::new(always_success_tx.hash(), 0))
+                .build(),
+            cells: (0..3)
+                .map(|index| packed::OutPoint::new(cells_tx.hash(), index))
+                .collect(),
+        }
+    }
+
+    impl Chain {
+        fn spend(&self, out_point: &packed::OutPoint, since: u64) -> core::TransactionView {
+            let (_, _, always_success_script) = always_success_cell();
+            core::TransactionBuilder::default()
+                .input(packed::CellInput::new(out_point.clone(), since))
+                .output(
+                    packed::CellOutput::new_builder()
+                        .capacity(capacity_bytes!(999).pack())
+                        .lock(always_success_script.clone())
+                        .build(),
+                )
+                .output_data(Default::default())
+                .cell_dep(self.cell_dep.clone())
+                .build()
+        }
+
+        // Returns the builder of the block next to the tip, with the cellbase.
+        fn next_block(&self) -> core::BlockBuilder {
+            let parent = self.shared.snapshot().tip_header().clone();
+            let number = parent.number() + 1;
+            let epoch = self
+                .shared
+                .consensus()
+                .next_epoch_ext(&parent, &self.shared.snapshot().as_data_provider())
+                .unwrap()
+                .epoch();
+            core::BlockBuilder::default()
+                .transaction(always_success_cellbase(
+                    number,
+                    Capacity::zero(),
+                    self.shared.consensus(),
+                ))
+                .parent_hash(parent.hash())
+                .number(number.pack())
+                .epoch(epoch.number_with_fraction(number).pack())
+                .timestamp((parent.timestamp() + 1).pack())
+                .compact_target(parent.compact_target().pack())
+        }
+
+        fn process_block(&self, block: &core::BlockView) {
+            self.chain_controller
+                .internal_process_block(Arc::new(block.clone()), Switch::DISABLE_ALL)
+                .expect("process block ok");
+        }
+    }
+
+    fn staged_tx_hashes(rpc: &PoolRpcImpl) -> Vec<H256> {
+        rpc.get_staged_transactions()
+            .unwrap()
+            .into_iter()
+            .map(|staged| staged.tx_hash)
+            .collect()
+    }
+
+    fn wait_staged_tx_hashes(rpc: &PoolRpcImpl, expected: Vec<H256>) {
+        for _ in 0..POOL_UPDATE_MAX_POLLS {
+            if staged_tx_hashes(rpc) == expected {
+                return;
+            }
+            thread::sleep(POOL_UPDATE_POLL_INTERVAL);
+        }
+        assert_eq!(staged_tx_hashes(rpc), expected);
+    }
+
+    fn is_in_pool(chain: &Chain, tx: &core::TransactionView) -> bool {
+        chain
+            .shared
+            .tx_pool_controller()
+            .fetch_tx_for_rpc(tx.proposal_short_id())
+            .unwrap()
+            .is_some()
+    }
+
+    #[test]
+    fn test_send_transaction_stages_immature_tx() {
+        let chain = start_chain(TxPoolConfig {
+            time_lock_staging: Some(TimeLockStagingConfig::default()),
+            ..Default::default()
+        });
+        let rpc = PoolRpcImpl::new(chain.shared.clone(), false, vec![], vec![]);
+        let send = |tx: &core::TransactionView| {
+            rpc.send_transaction(tx.data().into(), Some(OutputsValidator::Passthrough))
+        };
+
+        // The since values are the absolute block numbers. The pool verifies a transaction as if
+        // it were committed `1 + closest` blocks after the tip, which is 0 now.
+        let closest = chain.shared.consensus().tx_proposal_window().closest();
+        let mature_at_1 = chain.spend(&chain.cells[0], closest + 2);
+        let mature_at_2 = chain.spend(&chain.cells[1], closest + 3);
+        let conflicted = chain.spend(&chain.cells[2], closest + 3);
+        for &tx in &[&mature_at_1, &mature_at_2, &conflicted] {
+            assert_eq!(send(tx).unwrap(), tx.hash().unpack());
+            assert!(!is_in_pool(&chain, tx));
+        }
+        let staged = rpc.get_staged_transactions().unwrap();
+        assert_eq!(staged.len(), 3);
+        assert!(staged
+            .iter()
+            .all(|staged| staged.immature_input.value() == 0));
+
+        // Block 1 spends the cell of the conflicted transaction, which is dropped on the new tip.
+        chain.process_block(
+            &chain
+                .next_block()
+                .transaction(chain.spend(&chain.cells[2], 0))
+                .build(),
+        );
+        wait_staged_tx_hashes(&rpc, vec![mature_at_2.hash().unpack()]);
+        assert!(is_in_pool(&chain, &mature_at_1));
+        assert!(!is_in_pool(&chain, &conflicted));
+
+        chain.process_block(&chain.next_block().build());
+        wait_staged_tx_hashes(&rpc, vec![]);
+        assert!(is_in_pool(&chain, &mature_at_2));
+    }
+
+    #[test]
+    fn test_send_transaction_rejects_immature_tx_without_staging() {
+        let chain = start_chain(TxPoolConfig::default());
+        let rpc = PoolRpcImpl::new(chain.shared.clone(), false, vec![], vec![]);
+        let closest = chain.shared.consensus().tx_proposal_window().closest();
+        let tx = chain.spend(&chain.cells[0], closest + 2);
+
+        assert!(rpc
+            .send_transaction(tx.data().into(), Some(OutputsValidator::Passthrough))
+            .is_err());
+        assert!(rpc.get_staged_transactions().unwrap().is_empty());
+        assert!(!is_in_pool(&chain, &tx));
+    }
+
+    #[test]
+    fn test_get_proposal_window() {
+        let chain = start_chain(TxPoolConfig::default());
+        let rpc = PoolRpcImpl::new(chain.shared.clone(), false, vec![], vec![]);
+        let window = chain.shared.consensus().tx_proposal_window();
+        assert_eq!((window.closest(), window.farthest()), (2, 10));
+
+        let committed = chain.spend(&chain.cells[0], 0);
+        let uncommitted = chain.spend(&chain.cells[1], 0);
+        let uncle_proposed = chain.spend(&chain.cells[2], 0);
+        // The transactions not in the tx-pool
+        let missing = chain.spend(&chain.cells[1], 1);
+        let too_close = chain.spend(&chain.cells[2], 1);
+        let tx_pool = chain.shared.tx_pool_controller();
+        for &tx in &[&uncommitted, &uncle_proposed] {
+            assert!(tx_pool.submit_local_tx(tx.clone()).unwrap().is_ok());
+        }
+
+        // The uncle is a sibling of block 1, which is included by block 2.
+        let uncle = chain
+            .next_block()
+            .timestamp((chain.shared.snapshot().tip_header().timestamp() + 2).pack())
+            .proposal(uncle_proposed.proposal_short_id())
+            .build();
+        chain.process_block(
+            &chain
+                .next_block()
+                .proposal(committed.proposal_short_id())
+                .proposal(uncommitted.proposal_short_id())
+                .proposal(missing.proposal_short_id())
+                .build(),
+        );
+        chain.process_block(&chain.next_block().uncle(uncle.as_uncle()).build());
+        chain.process_block(&chain.next_block().transaction(committed.clone()).build());
+        // Proposes the committed transaction again
+        chain.process_block(
+            &chain
+                .next_block()
+                .proposal(committed.proposal_short_id())
+                .build(),
+        );
+        chain.process_block(&chain.next_block().build());
+        chain.process_block(
+            &chain
+                .next_block()
+                .proposal(too_close.proposal_short_id())
+                .build(),
+        );
+
+        let info = rpc.get_proposal_window().unwrap();
+        assert_eq!(info.tip_number.value(), 6);
+        assert_eq!(info.tip_hash, chain.shared.snapshot().tip_hash().unpack());
+        let id = |tx: &core::TransactionView| -> ckb_jsonrpc_types::ProposalShortId {
+            tx.proposal_short_id().into()
+        };
+        let blocks: Vec<_> = info
+            .blocks
+            .into_iter()
+            .map(|block| {
+                (
+                    block.number.value(),
+                    block.committable,
+                    block.proposals,
+                    block.missing,
+                )
+            })
+            .collect();
+        // The window starts at block 1, and the next block 7 can commit the proposals of the
+        // blocks up to 7 - closest.
+        assert_eq!(
+            blocks,
+            vec![
+                (
+                    1,
+                    true,
+                    vec![id(&uncommitted), id(&missing)],
+                    vec![id(&missing)]
+                ),
+                (2, true, vec![id(&uncle_proposed)], vec![]),
+                (3, true, vec![], vec![]),
+                (4, true, vec![id(&committed)], vec![id(&committed)]),
+                (5, true, vec![], vec![]),
+                (6, false, vec![id(&too_close)], vec![id(&too_close)]),
+            ]
+        );
+    }
 }
@@ -1,12 +1,23 @@
-use ckb_jsonrpc_types::{AlertMessage, ChainInfo};
+use crate::deprecation::DEPRECATIONS;
+use crate::error::RPCError;
+use ckb_jsonrpc_types::{AlertMessage, ChainInfo, EpochNumber, EpochStats, RpcVersion, Uint64};
 use ckb_network_alert::notifier::Notifier as AlertNotifier;
-use ckb_shared::shared::Shared;
+use ckb_shared::{shared::Shared, Snapshot};
+use ckb_store::ChainStore;
 use ckb_traits::HeaderProvider;
+use ckb_types::{
+    core::{BlockNumber, EpochExt, RationalU256},
+    utilities::compact_to_difficulty,
+    U256,
+};
 use ckb_util::Mutex;
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
+use std::cmp;
 use std::sync::Arc;
 
+const MAX_EPOCH_STATS_LIMIT: u64 = 100;
+
 /// RPC Module Stats for getting various statistic data.
 #[rpc(server)]
 pub trait StatsRpc {
@@ -50,11 +61,139 @@ pub trait StatsRpc {
     /// ```
     #[rpc(name = "get_blockchain_info")]
     fn get_blockchain_info(&self) -> Result<ChainInfo>;
+
+    /// Returns the version of the RPC API, and the deprecated methods and fields in it.
+    ///
+    /// Clients can check this RPC to adapt to the API changes before the deprecated methods and
+    /// fields are removed. See the [deprecation process](index.html#jsonrpc-deprecation-process).
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "rpc_version",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "deprecated_rpc_enabled": false,
+    ///     "deprecations": [
+    ///       {
+    ///         "field": "sync_state.unknown_header_list_size",
+    ///         "method": "get_peers",
+    ///         "replacement": null,
+    ///         "since": "0.100.0"
+    ///       }
+    ///     ],
+    ///     "version": "0.100.0"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "rpc_version")]
+    fn rpc_version(&self) -> Result<RpcVersion>;
+
+    /// Returns the statistics of the epochs in the [canonical chain](#canonical-chain).
+    ///
+    /// The statistics are computed from the block extras stored at the epoch boundaries, so the
+    /// clients can track the uncle rate and the difficulty trajectory without crawling every
+    /// header.
+    ///
+    /// ## Params
+    ///
+    /// * `start_epoch` - The number of the first epoch.
+    /// * `limit` - The max number of epochs to return, at most 100.
+    ///
+    /// ## Returns
+    ///
+    /// The statistics of the epochs from `start_epoch` in ascending order of the epoch number,
+    /// which stop at the current epoch. The statistics of the current epoch only cover the
+    /// blocks already in the chain.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - `limit` exceeds the limit.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_epoch_stats",
+    ///   "params": [
+    ///     "0x0",
+    ///     "0x2"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "average_block_interval": "0x1f40",
+    ///       "blocks_count": "0x3e8",
+    ///       "compact_target": "0x1e083126",
+    ///       "difficulty": "0x1f4003",
+    ///       "length": "0x3e8",
+    ///       "number": "0x0",
+    ///       "orphan_rate_target": {
+    ///         "denom": "0x28",
+    ///         "numer": "0x1"
+    ///       },
+    ///       "start_number": "0x0",
+    ///       "uncle_rate": {
+    ///         "denom": "0x3e8",
+    ///         "numer": "0x19"
+    ///       },
+    ///       "uncles_count": "0x19"
+    ///     },
+    ///     {
+    ///       "average_block_interval": "0x1f40",
+    ///       "blocks_count": "0x19",
+    ///       "compact_target": "0x1e083126",
+    ///       "difficulty": "0x1f4003",
+    ///       "length": "0x708",
+    ///       "number": "0x1",
+    ///       "orphan_rate_target": {
+    ///         "denom": "0x28",
+    ///         "numer": "0x1"
+    ///       },
+    ///       "start_number": "0x3e8",
+    ///       "uncle_rate": {
+    ///         "denom": "0x1",
+    ///         "numer": "0x0"
+    ///       },
+    ///       "uncles_count": "0x0"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_epoch_stats")]
+    fn get_epoch_stats(&self, start_epoch: EpochNumber, limit: Uint64) -> Result<Vec<EpochStats>>;
 }
 
 pub(crate) struct StatsRpcImpl {
     pub shared: Shared,
     pub alert_notifier: Arc<Mutex<AlertNotifier>>,
+    pub enable_deprecated_rpc: bool,
 }
 
 impl StatsRpc for StatsRpcImpl {
@@ -81,10 +220,9 @@ impl StatsRpc for StatsRpcImpl {
         let is_initial_block_download = self.shared.is_initial_block_download();
         let alerts: Vec<AlertMessage> = {
             let now = faketime::unix_time_as_millis();
-            let mut notifier = self.alert_notifier.lock();
-            notifier.clear_expired_alerts(now);
-            notifier
-                .noticed_alerts()
+            self.alert_notifier
+                .lock()
+                .active_alerts(now)
                 .into_iter()
                 .map(Into::into)
                 .collect()
@@ -99,4 +237,95 @@ impl StatsRpc for StatsRpcImpl {
             alerts,
         })
     }
+
+    fn rpc_version(&self) -> Result<RpcVersion> {
+        Ok(RpcVersion {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            deprecated_rpc_enabled: self.enable_deprecated_rpc,
+            deprecations: DEPRECATIONS.iter().map(Into::into).collect(),
+        })
+    }
+
+    fn get_epoch_stats(&self, start_epoch: EpochNumber, limit: Uint64) -> Result<Vec<EpochStats>> {
+        let limit: u64 = limit.into();
+        if limit > MAX_EPOCH_STATS_LIMIT {
+            return Err(RPCError::invalid_params(format!(
+                "limit must not exceed {}",
+                MAX_EPOCH_STATS_LIMIT
+            )));
+        }
+
+        let snapshot = self.shared.snapshot();
+        let start_epoch: u64 = start_epoch.into();
+        let current_epoch = snapshot.epoch_ext().number();
+        let tip_number = snapshot.tip_number();
+        let orphan_rate_target = snapshot.consensus().orphan_rate_target();
+        let stats = (start_epoch..start_epoch.saturating_add(limit))
+            .take_while(|number| *number <= current_epoch)
+            .map(|number| {
+                snapshot
+                    .get_epoch_index(number)
+                    .and_then(|hash| snapshot.get_epoch_ext(&hash))
+                    .and_then(|epoch| {
+                        epoch_stats(&snapshot, &epoch, tip_number, orphan_rate_target)
+                    })
+                    .ok_or_else(|| {
+                        RPCError::custom(
+                            RPCError::ChainIndexIsInconsistent,
+                            format!("failed to read the stats of epoch {}", number),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(stats)
+    }
+}
+
+// The uncles count and the timestamp are read from the last block of the epoch and the last
+// block of the previous epoch, the genesis block is the base of the genesis epoch.
+fn epoch_stats(
+    snapshot: &Snapshot,
+    epoch: &EpochExt,
+    tip_number: BlockNumber,
+    orphan_rate_target: &RationalU256,
+) -> Option<EpochStats> {
+    let start_number = epoch.start_number();
+    let end_number = cmp::min(start_number + epoch.length().saturating_sub(1), tip_number);
+    let blocks_count = end_number - start_number + 1;
+
+    let end_hash = snapshot.get_block_hash(end_number)?;
+    let end_header = snapshot.get_block_header(&end_hash)?;
+    let end_ext = snapshot.get_block_ext(&end_hash)?;
+    let (base_uncles_count, base_timestamp, intervals) = if start_number > 0 {
+        let base_hash = epoch.last_block_hash_in_previous_epoch();
+        let base_header = snapshot.get_block_header(&base_hash)?;
+        let base_ext = snapshot.get_block_ext(&base_hash)?;
+        (
+            base_ext.total_uncles_count,
+            base_header.timestamp(),
+            blocks_count,
+        )
+    } else {
+        let genesis = snapshot.get_block_header(&snapshot.get_block_hash(0)?)?;
+        (0, genesis.timestamp(), blocks_count - 1)
+    };
+
+    let uncles_count = end_ext.total_uncles_count.saturating_sub(base_uncles_count);
+    let average_block_interval = if intervals > 0 {
+        end_header.timestamp().saturating_sub(base_timestamp) / intervals
+    } else {
+        0
+    };
+    Some(EpochStats {
+        number: epoch.number().into(),
+        start_number: start_number.into(),
+        length: epoch.length().into(),
+        blocks_count: blocks_count.into(),
+        uncles_count: uncles_count.into(),
+        uncle_rate: RationalU256::new(U256::from(uncles_count), U256::from(blocks_count)),
+        orphan_rate_target: orphan_rate_target.clone(),
+        average_block_interval: average_block_interval.into(),
+        compact_target: epoch.compact_target().into(),
+        difficulty: compact_to_difficulty(epoch.compact_target()),
+    })
 }
@@ -1,7 +1,8 @@
 use ckb_channel::select;
 use ckb_jsonrpc_types::Topic;
 use ckb_logger::error;
-use ckb_notify::NotifyController;
+use ckb_network::{NetworkController, PeerAuditEvent, PeerAuditEventKind};
+use ckb_notify::{topic, NotifyController};
 use jsonrpc_core::{Metadata, Result};
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{
@@ -86,7 +87,7 @@ pub trait SubscriptionRpc {
     ///
     /// ## Params
     ///
-    /// * `topic` - Subscription topic (enum: new_tip_header | new_tip_block | new_transaction | proposed_transaction | rejected_transaction)
+    /// * `topic` - Subscription topic (enum: new_tip_header | new_tip_block | new_transaction | gap_transaction | proposed_transaction | committed_transaction | rejected_transaction | peer_event)
     ///
     /// ## Returns
     ///
@@ -129,12 +130,33 @@ pub trait SubscriptionRpc {
     ///
     /// The type of the `params.result` in the push message is [`PoolTransactionEntry`](../../ckb_jsonrpc_types/struct.PoolTransactionEntry.html).
     ///
+    /// ### `gap_transaction`
+    ///
+    /// Subscribers will get notified when an in-pool transaction is proposed by chain but it is
+    /// still too early to commit it, i.e., the proposal is not yet in the commit window.
+    ///
+    /// The type of the `params.result` in the push message is [`PoolTransactionEntry`](../../ckb_jsonrpc_types/struct.PoolTransactionEntry.html).
+    ///
     /// ### `proposed_transaction`
     ///
     /// Subscribers will get notified when an in-pool transaction is proposed by chain.
     ///
     /// The type of the `params.result` in the push message is [`PoolTransactionEntry`](../../ckb_jsonrpc_types/struct.PoolTransactionEntry.html).
     ///
+    /// ### `committed_transaction`
+    ///
+    /// Subscribers will get notified when an in-pool transaction is committed in a block which is
+    /// appended to the canonical chain.
+    ///
+    /// The type of the `params.result` in the push message is a two-elements array, where
+    ///
+    /// -   the first item type is [`PoolTransactionEntry`](../../ckb_jsonrpc_types/struct.PoolTransactionEntry.html), and
+    /// -   the second item type is [`HeaderView`](../../ckb_jsonrpc_types/struct.HeaderView.html), the header of the block which commits the transaction.
+    ///
+    /// A transaction usually goes through the topics `new_transaction`, `gap_transaction`,
+    /// `proposed_transaction` and `committed_transaction` in order. A transaction may skip some of
+    /// them, for example, when it is submitted after its proposal is already in the commit window.
+    ///
     /// ### `rejected_transaction`
     ///
     /// Subscribers will get notified when a pending transaction is rejected by tx-pool.
@@ -146,6 +168,16 @@ pub trait SubscriptionRpc {
     /// -   the first item type is [`PoolTransactionEntry`](../../ckb_jsonrpc_types/struct.PoolTransactionEntry.html), and
     /// -   the second item type is [`PoolTransactionReject`](../../ckb_jsonrpc_types/struct.PoolTransactionReject.html).
     ///
+    /// ### `peer_event`
+    ///
+    /// Subscribers will get notified of the peer connection lifecycle events, such as the dial
+    /// attempts, the handshake failures, the protocols opened and closed, the evictions and the
+    /// bans. The events are also logged to the target `ckb-network-audit` at the debug level.
+    ///
+    /// The events are dropped when the node pushes them faster than the subscriber consumes.
+    ///
+    /// The type of the `params.result` in the push message is [`PeerAuditEvent`](../../ckb_jsonrpc_types/struct.PeerAuditEvent.html).
+    ///
     /// ## Examples
     ///
     /// Request
@@ -276,23 +308,33 @@ impl SubscriptionRpc for SubscriptionRpcImpl {
 impl SubscriptionRpcImpl {
     // remove `allow` tag when https://github.com/crossbeam-rs/crossbeam/issues/404 is solved
     #[allow(clippy::zero_ptr, clippy::drop_copy)]
-    pub fn new<S: ToString>(notify_controller: NotifyController, name: S) -> Self {
-        let new_block_receiver = notify_controller.subscribe_new_block(name.to_string());
-        let new_transaction_receiver =
-            notify_controller.subscribe_new_transaction(name.to_string());
-        let proposed_transaction_receiver =
-            notify_controller.subscribe_proposed_transaction(name.to_string());
-        let reject_transaction_receiver =
-            notify_controller.subscribe_reject_transaction(name.to_string());
+    pub fn new<S: ToString>(
+        notify_controller: NotifyController,
+        network_controller: &NetworkController,
+        name: S,
+    ) -> Self {
+        let name = name.to_string();
+        let new_block_subscription = notify_controller.subscribe::<topic::NewBlock>(&name);
+        let new_transaction_subscription =
+            notify_controller.subscribe::<topic::NewTransaction>(&name);
+        let gap_transaction_subscription =
+            notify_controller.subscribe::<topic::GapTransaction>(&name);
+        let proposed_transaction_subscription =
+            notify_controller.subscribe::<topic::ProposedTransaction>(&name);
+        let committed_transaction_subscription =
+            notify_controller.subscribe::<topic::CommittedTransaction>(&name);
+        let reject_transaction_subscription =
+            notify_controller.subscribe::<topic::RejectTransaction>(&name);
+        let peer_audit_subscription = network_controller.subscribe_audit_events(&name);
 
         let subscription_rpc_impl = SubscriptionRpcImpl::default();
         let subscribers = Arc::clone(&subscription_rpc_impl.subscribers);
 
-        let thread_builder = thread::Builder::new().name(name.to_string());
+        let thread_builder = thread::Builder::new().name(name);
         thread_builder
             .spawn(move || loop {
                 select! {
-                    recv(new_block_receiver) -> msg => match msg {
+                    recv(new_block_subscription) -> msg => match msg {
                         Ok(block) => {
                             let subscribers = subscribers.read().expect("acquiring subscribers read lock");
                             if let Some(new_tip_header_subscribers) = subscribers.get(&Topic::NewTipHeader) {
@@ -311,11 +353,11 @@ impl SubscriptionRpcImpl {
                             }
                         },
                         _ => {
-                            error!("new_block_receiver closed");
+                            error!("new_block_subscription closed");
                             break;
                         },
                     },
-                    recv(new_transaction_receiver) -> msg => match msg {
+                    recv(new_transaction_subscription) -> msg => match msg {
                         Ok(tx_entry) => {
                             let subscribers = subscribers.read().expect("acquiring subscribers read lock");
                             if let Some(new_transaction_subscribers) = subscribers.get(&Topic::NewTransaction) {
@@ -327,11 +369,27 @@ impl SubscriptionRpcImpl {
                             }
                         },
                         _ => {
-                            error!("new_transaction_receiver closed");
+                            error!("new_transaction_subscription closed");
                             break;
                         },
                     },
-                    recv(proposed_transaction_receiver) -> msg => match msg {
+                    recv(gap_transaction_subscription) -> msg => match msg {
+                        Ok(tx_entry) => {
+                            let subscribers = subscribers.read().expect("acquiring subscribers read lock");
+                            if let Some(gap_transaction_subscribers) = subscribers.get(&Topic::GapTransaction) {
+                                let entry: ckb_jsonrpc_types::PoolTransactionEntry = tx_entry.into();
+                                let json_string = Ok(serde_json::to_string(&entry).expect("serialization should be ok"));
+                                for sink in gap_transaction_subscribers.values() {
+                                    let _ = sink.notify(json_string.clone());
+                                }
+                            }
+                        },
+                        _ => {
+                            error!("gap_transaction_subscription closed");
+                            break;
+                        },
+                    },
+                    recv(proposed_transaction_subscription) -> msg => match msg {
                         Ok(tx_entry) => {
                             let subscribers = subscribers.read().expect("acquiring subscribers read lock");
                             if let Some(new_transaction_subscribers) = subscribers.get(&Topic::ProposedTransaction) {
@@ -343,12 +401,29 @@ impl SubscriptionRpcImpl {
                             }
                         },
                         _ => {
-                            error!("proposed_transaction_receiver closed");
+                            error!("proposed_transaction_subscription closed");
+                            break;
+                        },
+                    },
+                    recv(committed_transaction_subscription) -> msg => match msg {
+                        Ok((tx_entry, header)) => {
+                            let subscribers = subscribers.read().expect("acquiring subscribers read lock");
+                            if let Some(committed_transaction_subscribers) = subscribers.get(&Topic::CommittedTransaction) {
+                                let entry: ckb_jsonrpc_types::PoolTransactionEntry = tx_entry.into();
+                                let header: ckb_jsonrpc_types::HeaderView = header.into();
+                                let json_string = Ok(serde_json::to_string(&(entry, header)).expect("serialization should be ok"));
+                                for sink in committed_transaction_subscribers.values() {
+                                    let _ = sink.notify(json_string.clone());
+                                }
+                            }
+                        },
+                        _ => {
+                            error!("committed_transaction_subscription closed");
                             break;
                         },
                     },
 
-                    recv(reject_transaction_receiver) -> msg => match msg {
+                    recv(reject_transaction_subscription) -> msg => match msg {
                         Ok((tx_entry, reject)) => {
                             let subscribers = subscribers.read().expect("acquiring subscribers read lock");
                             if let Some(new_transaction_subscribers) = subscribers.get(&Topic::RejectedTransaction) {
@@ -361,7 +436,23 @@ impl SubscriptionRpcImpl {
                             }
                         },
                         _ => {
-                            error!("reject_transaction_receiver closed");
+                            error!("reject_transaction_subscription closed");
+                            break;
+                        },
+                    },
+                    recv(peer_audit_subscription) -> msg => match msg {
+                        Ok(event) => {
+                            let subscribers = subscribers.read().expect("acquiring subscribers read lock");
+                            if let Some(peer_event_subscribers) = subscribers.get(&Topic::PeerEvent) {
+                                let event = peer_audit_event_json(event);
+                                let json_string = Ok(serde_json::to_string(&event).expect("serialization should be ok"));
+                                for sink in peer_event_subscribers.values() {
+                                    let _ = sink.notify(json_string.clone());
+                                }
+                            }
+                        },
+                        _ => {
+                            error!("peer_audit_subscription closed");
                             break;
                         },
                     },
@@ -372,3 +463,95 @@ impl SubscriptionRpcImpl {
         subscription_rpc_impl
     }
 }
+
+fn peer_audit_event_json(event: PeerAuditEvent) -> ckb_jsonrpc_types::PeerAuditEvent {
+    use ckb_jsonrpc_types::PeerAuditEventType as Type;
+
+    let mut json = ckb_jsonrpc_types::PeerAuditEvent {
+        timestamp: event.timestamp.into(),
+        event: Type::Dial,
+        session_id: None,
+        address: None,
+        inbound: None,
+        protocol_id: None,
+        version: None,
+        reason: None,
+        ban_duration: None,
+    };
+    match event.kind {
+        PeerAuditEventKind::Dial { address } => {
+            json.address = Some(address.to_string());
+        }
+        PeerAuditEventKind::DialFailed { address, reason } => {
+            json.event = Type::DialFailed;
+            json.address = Some(address.to_string());
+            json.reason = Some(reason);
+        }
+        PeerAuditEventKind::HandshakeFailed { address, reason } => {
+            json.event = Type::HandshakeFailed;
+            json.address = Some(address.to_string());
+            json.reason = Some(reason);
+        }
+        PeerAuditEventKind::SessionOpened {
+            peer,
+            address,
+            inbound,
+        } => {
+            json.event = Type::SessionOpened;
+            json.session_id = Some((peer.value() as u64).into());
+            json.address = Some(address.to_string());
+            json.inbound = Some(inbound);
+        }
+        PeerAuditEventKind::SessionRejected {
+            peer,
+            address,
+            reason,
+        } => {
+            json.event = Type::SessionRejected;
+            json.session_id = Some((peer.value() as u64).into());
+            json.address = Some(address.to_string());
+            json.reason = Some(reason);
+        }
+        PeerAuditEventKind::SessionClosed { peer, address } => {
+            json.event = Type::SessionClosed;
+            json.session_id = Some((peer.value() as u64).into());
+            json.address = Some(address.to_string());
+        }
+        PeerAuditEventKind::ProtocolOpened {
+            peer,
+            protocol,
+            version,
+        } => {
+            json.event = Type::ProtocolOpened;
+            json.session_id = Some((peer.value() as u64).into());
+            json.protocol_id = Some((protocol.value() as u64).into());
+            json.version = Some(version);
+        }
+        PeerAuditEventKind::ProtocolClosed { peer, protocol } => {
+            json.event = Type::ProtocolClosed;
+            json.session_id = Some((peer.value() as u64).into());
+            json.protocol_id = Some((protocol.value() as u64).into());
+        }
+        PeerAuditEventKind::Evicted {
+            peer,
+            address,
+            reason,
+        } => {
+            json.event = Type::Evicted;
+            json.session_id = Some((peer.value() as u64).into());
+            json.address = Some(address.to_string());
+            json.reason = Some(reason);
+        }
+        PeerAuditEventKind::Banned {
+            address,
+            duration,
+            reason,
+        } => {
+            json.event = Type::Banned;
+            json.address = Some(address);
+            json.reason = Some(reason);
+            json.ban_duration = Some((duration.as_millis() as u64).into());
+        }
+    }
+    json
+}
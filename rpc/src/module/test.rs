@@ -3,7 +3,8 @@ use ckb_app_config::BlockAssemblerConfig;
 use ckb_chain::chain::ChainController;
 use ckb_dao::DaoCalculator;
 use ckb_jsonrpc_types::{
-    AsEpochNumberWithFraction, Block, BlockTemplate, Cycle, JsonBytes, Script, Transaction,
+    AsEpochNumberWithFraction, Block, BlockTemplate, Cycle, JsonBytes, Script, Timestamp,
+    Transaction, Uint64,
 };
 use ckb_logger::error;
 use ckb_network::{NetworkController, SupportProtocols};
@@ -22,6 +23,7 @@ use ckb_types::{
     prelude::*,
     H256,
 };
+use ckb_util::mock_clock;
 use ckb_verification_traits::Switch;
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
@@ -52,6 +54,9 @@ pub trait IntegrationTestRpc {
 
     #[rpc(name = "generate_block_with_template")]
     fn generate_block_with_template(&self, block_template: BlockTemplate) -> Result<H256>;
+
+    #[rpc(name = "advance_mock_time")]
+    fn advance_mock_time(&self, millis: Uint64) -> Result<Timestamp>;
 }
 
 pub(crate) struct IntegrationTestRpcImpl {
@@ -145,6 +150,16 @@ impl IntegrationTestRpc for IntegrationTestRpcImpl {
         self.process_and_announce_block(block_template.into())
     }
 
+    fn advance_mock_time(&self, millis: Uint64) -> Result<Timestamp> {
+        mock_clock::advance(millis.into())
+            .map(Into::into)
+            .ok_or_else(|| {
+                RPCError::invalid_params(
+                    "The mock clock is only enabled in the sandbox mode, see `ckb run --sandbox`",
+                )
+            })
+    }
+
     fn broadcast_transaction(&self, transaction: Transaction, cycles: Cycle) -> Result<H256> {
         let tx: packed::Transaction = transaction.into();
         let hash = tx.calc_tx_hash();
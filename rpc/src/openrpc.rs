@@ -0,0 +1,82 @@
+use ckb_app_config::RpcModule;
+use serde_json::{json, Value};
+
+include!(concat!(env!("OUT_DIR"), "/openrpc_methods.rs"));
+
+/// The version of the OpenRPC specification which the document follows.
+const OPENRPC_VERSION: &str = "1.2.6";
+
+/// The method serving the OpenRPC document.
+pub(crate) const DISCOVER_METHOD: &str = "rpc.discover";
+
+/// Builds the OpenRPC document of the methods in the enabled modules.
+///
+/// The method objects are generated from the RPC traits at build time, see `build.rs`. The
+/// params and the results of the primitive types have JSON schemas, and the other types are
+/// referred by their names in the schema titles.
+pub(crate) fn openrpc_document<F>(module_enable: F) -> Value
+where
+    F: Fn(RpcModule) -> bool,
+{
+    let methods: Vec<Value> = OPENRPC_METHODS
+        .iter()
+        .filter(|(module, _)| module_enable(*module))
+        .map(|(_, method)| serde_json::from_str(method).expect("valid generated OpenRPC method"))
+        .collect();
+
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "CKB JSON-RPC",
+            "version": env!("CARGO_PKG_VERSION"),
+            "license": { "name": "MIT" },
+        },
+        "methods": methods,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_generated_methods() {
+        let document = openrpc_document(|_| true);
+        let methods = document["methods"].as_array().unwrap();
+
+        let names: HashSet<_> = methods
+            .iter()
+            .map(|method| method["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names.len(), methods.len(), "method names are unique");
+        assert!(names.contains("subscribe"));
+        assert!(names.contains("send_alert"));
+
+        let get_block = methods
+            .iter()
+            .find(|method| method["name"] == "get_block")
+            .unwrap();
+        assert_eq!(get_block["tags"], json!([{ "name": "Chain" }]));
+        assert!(get_block["summary"]
+            .as_str()
+            .unwrap()
+            .starts_with("Returns the information about a block by hash."));
+        assert_eq!(get_block["params"][0]["name"], "block_hash");
+        assert_eq!(get_block["params"][0]["required"], true);
+        assert_eq!(get_block["params"][0]["schema"]["title"], "H256");
+        assert_eq!(get_block["params"][1]["name"], "verbosity");
+        assert_eq!(get_block["params"][1]["required"], false);
+        assert_eq!(get_block["params"][1]["schema"]["title"], "Uint32");
+    }
+
+    #[test]
+    fn test_filter_modules() {
+        let document = openrpc_document(|module| module == RpcModule::Net);
+        let methods = document["methods"].as_array().unwrap();
+        assert!(!methods.is_empty());
+        assert!(methods
+            .iter()
+            .all(|method| method["tags"][0]["name"] == "Net"));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::module::{SubscriptionRpc, SubscriptionRpcImpl, SubscriptionSession};
 use crate::IoHandler;
-use ckb_app_config::RpcConfig;
+use ckb_app_config::{RpcConfig, RpcListenerConfig};
 use ckb_logger::info;
+use ckb_network::NetworkController;
 use ckb_notify::NotifyController;
+use jsonrpc_http_server::{hyper, RequestMiddleware, RequestMiddlewareAction};
 use jsonrpc_pubsub::Session;
 use jsonrpc_server_utils::cors::AccessControlAllowOrigin;
 use jsonrpc_server_utils::hosts::DomainsValidation;
@@ -13,6 +15,7 @@ pub struct RpcServer {
     pub(crate) http: jsonrpc_http_server::Server,
     pub(crate) _tcp: Option<jsonrpc_tcp_server::Server>,
     pub(crate) _ws: Option<jsonrpc_ws_server::Server>,
+    pub(crate) _listeners: Vec<jsonrpc_http_server::Server>,
 }
 
 impl RpcServer {
@@ -22,11 +25,16 @@ impl RpcServer {
     ///
     /// * `config` - RPC config options.
     /// * `io_handler` - RPC methods handler. See [ServiceBuilder](../service_builder/struct.ServiceBuilder.html).
+    /// * `listener_handlers` - RPC methods handlers of the extra listeners in `rpc.listeners`.
+    /// See [ServiceBuilder::build_listeners](../service_builder/struct.ServiceBuilder.html#method.build_listeners).
     /// * `notify_controller` - Controler emitting notifications.
+    /// * `network_controller` - Controller emitting the peer connection events.
     pub fn new(
         config: RpcConfig,
         io_handler: IoHandler,
+        listener_handlers: Vec<IoHandler>,
         notify_controller: &NotifyController,
+        network_controller: &NetworkController,
     ) -> RpcServer {
         let http = jsonrpc_http_server::ServerBuilder::new(io_handler.clone())
             .cors(DomainsValidation::AllowOnly(vec![
@@ -51,8 +59,11 @@ impl RpcServer {
             .tcp_listen_address
             .as_ref()
             .map(|tcp_listen_address| {
-                let subscription_rpc_impl =
-                    SubscriptionRpcImpl::new(notify_controller.clone(), "TcpSubscription");
+                let subscription_rpc_impl = SubscriptionRpcImpl::new(
+                    notify_controller.clone(),
+                    network_controller,
+                    "TcpSubscription",
+                );
                 let mut handler = io_handler.clone();
                 if config.subscription_enable() {
                     handler.extend_with(subscription_rpc_impl.to_delegate());
@@ -79,8 +90,11 @@ impl RpcServer {
             });
 
         let _ws = config.ws_listen_address.as_ref().map(|ws_listen_address| {
-            let subscription_rpc_impl =
-                SubscriptionRpcImpl::new(notify_controller.clone(), "WsSubscription");
+            let subscription_rpc_impl = SubscriptionRpcImpl::new(
+                notify_controller.clone(),
+                network_controller,
+                "WsSubscription",
+            );
             let mut handler = io_handler.clone();
             if config.subscription_enable() {
                 handler.extend_with(subscription_rpc_impl.to_delegate());
@@ -104,7 +118,43 @@ impl RpcServer {
             ws_server
         });
 
-        RpcServer { http, _tcp, _ws }
+        let _listeners = config
+            .listeners
+            .iter()
+            .zip(listener_handlers)
+            .map(|(listener, io_handler)| {
+                let http = jsonrpc_http_server::ServerBuilder::new(io_handler)
+                    .cors(DomainsValidation::AllowOnly(vec![
+                        AccessControlAllowOrigin::Null,
+                        AccessControlAllowOrigin::Any,
+                    ]))
+                    .threads(config.threads.unwrap_or_else(num_cpus::get))
+                    .max_request_body_size(config.max_request_body_size)
+                    .health_api(("/ping", "ping"))
+                    .request_middleware(ListenerAuth::new(listener))
+                    .start_http(
+                        &listener
+                            .listen_address
+                            .to_socket_addrs()
+                            .expect("config listeners.listen_address parsed")
+                            .next()
+                            .expect("config listeners.listen_address parsed"),
+                    )
+                    .expect("Start Jsonrpc HTTP service");
+                info!(
+                    "Listen HTTP RPCServer on address {} with modules {:?}",
+                    listener.listen_address, listener.modules
+                );
+                http
+            })
+            .collect();
+
+        RpcServer {
+            http,
+            _tcp,
+            _ws,
+            _listeners,
+        }
     }
 
     /// Gets the HTTP RPC endpoint.
@@ -112,3 +162,102 @@ impl RpcServer {
         self.http.address()
     }
 }
+
+// Checks the `Authorization` header of the requests to an extra listener.
+struct ListenerAuth {
+    // The accepted header values, all requests are accepted when it is empty
+    accepted: Vec<String>,
+}
+
+impl ListenerAuth {
+    fn new(listener: &RpcListenerConfig) -> Self {
+        let mut accepted = Vec::new();
+        if let Some(token) = &listener.bearer_token {
+            accepted.push(format!("Bearer {}", token));
+        }
+        if let Some(user_password) = &listener.basic_auth {
+            let mut encoded = "Basic ".to_string();
+            base64::encode_config_buf(user_password, base64::STANDARD, &mut encoded);
+            accepted.push(encoded);
+        }
+        ListenerAuth { accepted }
+    }
+}
+
+impl RequestMiddleware for ListenerAuth {
+    fn on_request(&self, request: hyper::Request<hyper::Body>) -> RequestMiddlewareAction {
+        if self.accepted.is_empty() {
+            return request.into();
+        }
+        let authorized = request
+            .headers()
+            .get(hyper::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                // checks all the accepted values in constant time, so the timing does not leak
+                // how many bytes of a guess match
+                self.accepted.iter().fold(false, |authorized, accepted| {
+                    constant_time_eq(accepted.as_bytes(), value.as_bytes()) | authorized
+                })
+            })
+            .unwrap_or(false);
+        if authorized {
+            request.into()
+        } else {
+            hyper::Response::builder()
+                .status(hyper::StatusCode::UNAUTHORIZED)
+                .header(hyper::header::WWW_AUTHENTICATE, "Basic realm=\"ckb\"")
+                .body(hyper::Body::from("Unauthorized"))
+                .expect("build unauthorized response")
+                .into()
+        }
+    }
+}
+
+// Compares the bytes without returning early at the first difference. Only the length, which is
+// fixed by the auth scheme and the configured secret, is compared directly.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_app_config::RpcModule;
+
+    fn is_authorized(auth: &ListenerAuth, authorization: Option<&str>) -> bool {
+        let mut request = hyper::Request::builder();
+        if let Some(value) = authorization {
+            request = request.header(hyper::header::AUTHORIZATION, value);
+        }
+        let request = request.body(hyper::Body::empty()).unwrap();
+        matches!(
+            auth.on_request(request),
+            RequestMiddlewareAction::Proceed { .. }
+        )
+    }
+
+    #[test]
+    fn listener_auth() {
+        let mut listener = RpcListenerConfig {
+            listen_address: "127.0.0.1:0".to_owned(),
+            modules: vec![RpcModule::Chain],
+            bearer_token: None,
+            basic_auth: None,
+        };
+        assert!(is_authorized(&ListenerAuth::new(&listener), None));
+
+        listener.bearer_token = Some("secret".to_owned());
+        listener.basic_auth = Some("user:password".to_owned());
+        let auth = ListenerAuth::new(&listener);
+        assert!(is_authorized(&auth, Some("Bearer secret")));
+        assert!(is_authorized(&auth, Some("Basic dXNlcjpwYXNzd29yZA==")));
+        assert!(!is_authorized(&auth, Some("Bearer wrong")));
+        assert!(!is_authorized(&auth, Some("Bearer secreu")));
+        assert!(!is_authorized(&auth, Some("Bearer secret2")));
+        assert!(!is_authorized(&auth, None));
+    }
+}
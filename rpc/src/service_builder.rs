@@ -1,22 +1,32 @@
 #![allow(deprecated)]
+use crate::deprecation::{
+    find_deprecated_method, has_removed_fields, restore_removed_fields, DEPRECATIONS,
+};
 use crate::error::RPCError;
+use crate::middleware::{MetricsMiddleware, QuotaMiddleware, DEFAULT_LISTENER};
 use crate::module::SubscriptionSession;
 use crate::module::{
     AlertRpc, AlertRpcImpl, ChainRpc, ChainRpcImpl, DebugRpc, DebugRpcImpl, ExperimentRpc,
     ExperimentRpcImpl, IntegrationTestRpc, IntegrationTestRpcImpl, MinerRpc, MinerRpcImpl, NetRpc,
     NetRpcImpl, PoolRpc, PoolRpcImpl, StatsRpc, StatsRpcImpl,
 };
+use crate::openrpc::{openrpc_document, DISCOVER_METHOD};
 use crate::IoHandler;
-use ckb_app_config::RpcConfig;
+use ckb_app_config::{RpcConfig, RpcModule};
 use ckb_chain::chain::ChainController;
+use ckb_logger::warn;
 use ckb_network::NetworkController;
 use ckb_network_alert::{notifier::Notifier as AlertNotifier, verifier::Verifier as AlertVerifier};
 use ckb_shared::shared::Shared;
 use ckb_sync::SyncShared;
-use ckb_types::{core::FeeRate, packed::Script};
+use ckb_types::{core::Cycle, packed::Script};
 use ckb_util::Mutex;
-use jsonrpc_core::RemoteProcedure;
-use std::sync::Arc;
+use jsonrpc_core::{MetaIoHandler, RemoteProcedure, RpcMethod};
+use std::collections::HashMap;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 const DEPRECATED_RPC_PREFIX: &str = "deprecated.";
 
@@ -24,14 +34,25 @@ const DEPRECATED_RPC_PREFIX: &str = "deprecated.";
 pub struct ServiceBuilder<'a> {
     config: &'a RpcConfig,
     io_handler: IoHandler,
+    // The module of each method, used to filter the methods exposed on the extra listeners
+    method_modules: HashMap<String, RpcModule>,
+    // The modules whose methods are mounted, listed in the OpenRPC document
+    enabled_modules: Vec<RpcModule>,
+    // The quotas shared by the default and the extra listeners
+    quota: QuotaMiddleware,
 }
 
 impl<'a> ServiceBuilder<'a> {
     /// Creates the RPC service builder from config.
     pub fn new(config: &'a RpcConfig) -> Self {
+        let quota = QuotaMiddleware::new(config);
+        let middleware = (MetricsMiddleware::new(DEFAULT_LISTENER), quota.clone());
         Self {
             config,
-            io_handler: IoHandler::default(),
+            io_handler: IoHandler::new(MetaIoHandler::with_middleware(middleware)),
+            method_modules: HashMap::new(),
+            enabled_modules: Vec::new(),
+            quota,
         }
     }
 
@@ -39,9 +60,9 @@ impl<'a> ServiceBuilder<'a> {
     pub fn enable_chain(mut self, shared: Shared) -> Self {
         let rpc_methods = ChainRpcImpl { shared }.to_delegate();
         if self.config.chain_enable() {
-            self.add_methods(rpc_methods);
+            self.add_methods(RpcModule::Chain, rpc_methods);
         } else {
-            self.update_disabled_methods("Chain", rpc_methods);
+            self.update_disabled_methods(RpcModule::Chain, rpc_methods);
         }
         self
     }
@@ -50,23 +71,21 @@ impl<'a> ServiceBuilder<'a> {
     pub fn enable_pool(
         mut self,
         shared: Shared,
-        min_fee_rate: FeeRate,
         reject_ill_transactions: bool,
         extra_well_known_lock_scripts: Vec<Script>,
         extra_well_known_type_scripts: Vec<Script>,
     ) -> Self {
         let rpc_methods = PoolRpcImpl::new(
             shared,
-            min_fee_rate,
             reject_ill_transactions,
             extra_well_known_lock_scripts,
             extra_well_known_type_scripts,
         )
         .to_delegate();
         if self.config.pool_enable() {
-            self.add_methods(rpc_methods);
+            self.add_methods(RpcModule::Pool, rpc_methods);
         } else {
-            self.update_disabled_methods("Pool", rpc_methods);
+            self.update_disabled_methods(RpcModule::Pool, rpc_methods);
         }
         self
     }
@@ -86,9 +105,9 @@ impl<'a> ServiceBuilder<'a> {
         }
         .to_delegate();
         if enable && self.config.miner_enable() {
-            self.add_methods(rpc_methods);
+            self.add_methods(RpcModule::Miner, rpc_methods);
         } else {
-            self.update_disabled_methods("Miner", rpc_methods);
+            self.update_disabled_methods(RpcModule::Miner, rpc_methods);
         }
         self
     }
@@ -105,9 +124,9 @@ impl<'a> ServiceBuilder<'a> {
         }
         .to_delegate();
         if self.config.net_enable() {
-            self.add_methods(rpc_methods);
+            self.add_methods(RpcModule::Net, rpc_methods);
         } else {
-            self.update_disabled_methods("Net", rpc_methods);
+            self.update_disabled_methods(RpcModule::Net, rpc_methods);
         }
         self
     }
@@ -121,12 +140,13 @@ impl<'a> ServiceBuilder<'a> {
         let rpc_methods = StatsRpcImpl {
             shared,
             alert_notifier,
+            enable_deprecated_rpc: self.config.enable_deprecated_rpc,
         }
         .to_delegate();
         if self.config.stats_enable() {
-            self.add_methods(rpc_methods);
+            self.add_methods(RpcModule::Stats, rpc_methods);
         } else {
-            self.update_disabled_methods("Stats", rpc_methods);
+            self.update_disabled_methods(RpcModule::Stats, rpc_methods);
         }
         self
     }
@@ -135,9 +155,9 @@ impl<'a> ServiceBuilder<'a> {
     pub fn enable_experiment(mut self, shared: Shared) -> Self {
         let rpc_methods = ExperimentRpcImpl { shared }.to_delegate();
         if self.config.experiment_enable() {
-            self.add_methods(rpc_methods);
+            self.add_methods(RpcModule::Experiment, rpc_methods);
         } else {
-            self.update_disabled_methods("Experiment", rpc_methods);
+            self.update_disabled_methods(RpcModule::Experiment, rpc_methods);
         }
         self
     }
@@ -156,9 +176,9 @@ impl<'a> ServiceBuilder<'a> {
         }
         .to_delegate();
         if self.config.integration_test_enable() {
-            self.add_methods(rpc_methods);
+            self.add_methods(RpcModule::IntegrationTest, rpc_methods);
         } else {
-            self.update_disabled_methods("IntegrationTest", rpc_methods);
+            self.update_disabled_methods(RpcModule::IntegrationTest, rpc_methods);
         }
         self
     }
@@ -173,66 +193,190 @@ impl<'a> ServiceBuilder<'a> {
         let rpc_methods =
             AlertRpcImpl::new(alert_verifier, alert_notifier, network_controller).to_delegate();
         if self.config.alert_enable() {
-            self.add_methods(rpc_methods);
+            self.add_methods(RpcModule::Alert, rpc_methods);
         } else {
-            self.update_disabled_methods("Alert", rpc_methods);
+            self.update_disabled_methods(RpcModule::Alert, rpc_methods);
         }
         self
     }
 
     /// Mounts methods from module Debug if it is enabled in the config.
-    pub fn enable_debug(mut self) -> Self {
+    pub fn enable_debug(
+        mut self,
+        shared: Shared,
+        chain: ChainController,
+        sync_shared: Arc<SyncShared>,
+        max_tx_verify_cycles: Cycle,
+    ) -> Self {
         if self.config.debug_enable() {
-            self.io_handler.extend_with(DebugRpcImpl {}.to_delegate());
+            self.add_methods(
+                RpcModule::Debug,
+                DebugRpcImpl {
+                    shared,
+                    chain,
+                    sync_shared,
+                    max_tx_verify_cycles,
+                }
+                .to_delegate(),
+            );
         }
         self
     }
 
-    fn update_disabled_methods<I, M>(&mut self, module: &str, rpc_methods: I)
+    fn update_disabled_methods<I, M>(&mut self, module: RpcModule, rpc_methods: I)
     where
         I: IntoIterator<Item = (String, M)>,
     {
+        let module_name = format!("{:?}", module);
         rpc_methods.into_iter().for_each(|(name, _method)| {
-            let error = Err(RPCError::rpc_module_is_disabled(module));
-            self.io_handler.add_sync_method(
-                name.split("deprecated.")
-                    .collect::<Vec<&str>>()
-                    .last()
-                    .unwrap(),
-                move |_param| error.clone(),
-            )
+            let error = Err(RPCError::rpc_module_is_disabled(&module_name));
+            let name = name.split("deprecated.").last().unwrap().to_owned();
+            self.method_modules.insert(name.clone(), module);
+            self.io_handler
+                .add_sync_method(&name, move |_param| error.clone())
         });
     }
 
-    fn add_methods<I>(&mut self, rpc_methods: I)
+    fn add_methods<I>(&mut self, module: RpcModule, rpc_methods: I)
     where
         I: IntoIterator<Item = (String, RemoteProcedure<Option<SubscriptionSession>>)>,
     {
+        if !self.enabled_modules.contains(&module) {
+            self.enabled_modules.push(module);
+        }
         let enable_deprecated_rpc = self.config.enable_deprecated_rpc;
+        let method_modules = &mut self.method_modules;
         self.io_handler
             .extend_with(rpc_methods.into_iter().map(|(name, method)| {
-                if let Some(deprecated_method_name) = name.strip_prefix(DEPRECATED_RPC_PREFIX) {
-                    (
-                        deprecated_method_name.to_owned(),
-                        if enable_deprecated_rpc {
-                            method
-                        } else {
-                            RemoteProcedure::Method(Arc::new(|_param, _meta| async {
-                                Err(RPCError::rpc_method_is_deprecated())
-                            }))
-                        },
-                    )
+                let (name, method) = if let Some(deprecated_method_name) =
+                    name.strip_prefix(DEPRECATED_RPC_PREFIX)
+                {
+                    let name = deprecated_method_name.to_owned();
+                    let method = if enable_deprecated_rpc {
+                        warn_deprecated_method(name.clone(), method)
+                    } else {
+                        let mut error = RPCError::rpc_method_is_deprecated();
+                        error.data = find_deprecated_method(&name).map(|deprecation| {
+                            serde_json::to_value(ckb_jsonrpc_types::Deprecation::from(deprecation))
+                                .expect("serialize deprecation")
+                        });
+                        RemoteProcedure::Method(Arc::new(move |_param, _meta| {
+                            let error = error.clone();
+                            async move { Err(error) }
+                        }))
+                    };
+                    (name, method)
+                } else if enable_deprecated_rpc && has_removed_fields(&name) {
+                    let method = restore_fields(name.clone(), method);
+                    (name, method)
                 } else {
                     (name, method)
-                }
+                };
+                method_modules.insert(name.clone(), module);
+                (name, method)
             }));
     }
 
+    /// Builds the RPC methods handlers of the extra listeners in `rpc.listeners`, in the same
+    /// order.
+    ///
+    /// The methods of the modules not exposed on a listener return the error
+    /// `RPCModuleIsDisabled`. The listeners share the quotas with the default listener.
+    pub fn build_listeners(&self) -> Vec<IoHandler> {
+        self.config
+            .listeners
+            .iter()
+            .map(|listener| {
+                let all_methods: MetaIoHandler<_, _> = self.io_handler.clone().into();
+                let mut io_handler = IoHandler::new(MetaIoHandler::with_middleware((
+                    MetricsMiddleware::new(&listener.listen_address),
+                    self.quota.clone(),
+                )));
+                io_handler.extend_with(all_methods.into_iter().map(|(name, method)| {
+                    match self.method_modules.get(&name) {
+                        Some(module) if !listener.module_enable(*module) => {
+                            let error = RPCError::custom(
+                                RPCError::RPCModuleIsDisabled,
+                                format!(
+                                    "This RPC method is in the module `{:?}`, which is not exposed on this listener.",
+                                    module
+                                ),
+                            );
+                            let method = RemoteProcedure::Method(Arc::new(move |_param, _meta| {
+                                let error = error.clone();
+                                async move { Err(error) }
+                            }));
+                            (name, method)
+                        }
+                        _ => (name, method),
+                    }
+                }));
+                io_handler.add_sync_method("ping", |_| Ok("pong".into()));
+                let document = openrpc_document(|module| {
+                    self.enabled_modules.contains(&module) && listener.module_enable(module)
+                });
+                io_handler.add_sync_method(DISCOVER_METHOD, move |_| Ok(document.clone()));
+                io_handler
+            })
+            .collect()
+    }
+
     /// Builds the RPC methods handler used in the RPC server.
     pub fn build(self) -> IoHandler {
+        let enabled_modules = self.enabled_modules;
         let mut io_handler = self.io_handler;
         io_handler.add_sync_method("ping", |_| Ok("pong".into()));
+        let document = openrpc_document(|module| enabled_modules.contains(&module));
+        io_handler.add_sync_method(DISCOVER_METHOD, move |_| Ok(document.clone()));
 
         io_handler
     }
 }
+
+// Logs a warning on the first call of the deprecated method.
+fn warn_deprecated_method(
+    name: String,
+    method: RemoteProcedure<Option<SubscriptionSession>>,
+) -> RemoteProcedure<Option<SubscriptionSession>> {
+    match method {
+        RemoteProcedure::Method(method) => {
+            let warned = AtomicBool::new(false);
+            RemoteProcedure::Method(Arc::new(move |params, meta| {
+                if !warned.swap(true, Ordering::Relaxed) {
+                    match find_deprecated_method(&name).and_then(|d| d.replacement) {
+                        Some(replacement) => warn!(
+                            "the deprecated RPC method {} is called, use {} instead",
+                            name, replacement
+                        ),
+                        None => warn!("the deprecated RPC method {} is called", name),
+                    }
+                }
+                method.call(params, meta)
+            }))
+        }
+        method => method,
+    }
+}
+
+// Restores the removed fields in the method result, see `DEPRECATIONS`.
+fn restore_fields(
+    name: String,
+    method: RemoteProcedure<Option<SubscriptionSession>>,
+) -> RemoteProcedure<Option<SubscriptionSession>> {
+    match method {
+        RemoteProcedure::Method(method) => {
+            let name = Arc::new(name);
+            RemoteProcedure::Method(Arc::new(move |params, meta| {
+                let name = Arc::clone(&name);
+                let result = method.call(params, meta);
+                async move {
+                    result.await.map(|mut value| {
+                        restore_removed_fields(DEPRECATIONS, &name, &mut value);
+                        value
+                    })
+                }
+            }))
+        }
+        method => method,
+    }
+}
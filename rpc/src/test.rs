@@ -17,7 +17,7 @@ use ckb_test_chain_utils::{always_success_cell, always_success_cellbase};
 use ckb_types::{
     core::{
         capacity_bytes, cell::resolve_transaction, BlockBuilder, BlockView, Capacity,
-        EpochNumberWithFraction, FeeRate, HeaderView, TransactionBuilder, TransactionView,
+        EpochNumberWithFraction, HeaderView, TransactionBuilder, TransactionView,
     },
     h256,
     packed::{AlertBuilder, CellDep, CellInput, CellOutputBuilder, OutPoint, RawAlertBuilder},
@@ -218,6 +218,7 @@ fn setup_rpc_test_suite(height: u64) -> RpcTestSuite {
         shared.clone(),
         Default::default(),
         pack.take_relay_tx_receiver(),
+        pack.take_ask_for_txs_receiver(),
     ));
 
     let notify_controller = NotifyService::new(Default::default()).start(Some("test"));
@@ -254,6 +255,8 @@ fn setup_rpc_test_suite(height: u64) -> RpcTestSuite {
         ws_listen_address: None,
         max_request_body_size: 20_000_000,
         threads: None,
+        max_concurrent_requests: None,
+        max_response_size: None,
         // enable all rpc modules in unit test
         modules: vec![
             RpcModule::Net,
@@ -272,18 +275,20 @@ fn setup_rpc_test_suite(height: u64) -> RpcTestSuite {
         enable_deprecated_rpc: true,
         extra_well_known_lock_scripts: vec![],
         extra_well_known_type_scripts: vec![],
+        rate_limits: Default::default(),
+        listeners: vec![],
     };
 
     let builder = ServiceBuilder::new(&rpc_config)
         .enable_chain(shared.clone())
-        .enable_pool(shared.clone(), FeeRate::zero(), true, vec![], vec![])
+        .enable_pool(shared.clone(), true, vec![], vec![])
         .enable_miner(
             shared.clone(),
             network_controller.clone(),
             chain_controller.clone(),
             true,
         )
-        .enable_net(network_controller.clone(), sync_shared)
+        .enable_net(network_controller.clone(), Arc::clone(&sync_shared))
         .enable_stats(shared.clone(), Arc::clone(&alert_notifier))
         .enable_experiment(shared.clone())
         .enable_integration_test(
@@ -291,11 +296,22 @@ fn setup_rpc_test_suite(height: u64) -> RpcTestSuite {
             network_controller.clone(),
             chain_controller.clone(),
         )
-        .enable_debug()
-        .enable_alert(alert_verifier, alert_notifier, network_controller);
+        .enable_debug(
+            shared.clone(),
+            chain_controller.clone(),
+            sync_shared,
+            shared.consensus().max_block_cycles(),
+        )
+        .enable_alert(alert_verifier, alert_notifier, network_controller.clone());
     let io_handler = builder.build();
 
-    let rpc_server = RpcServer::new(rpc_config, io_handler, shared.notify_controller());
+    let rpc_server = RpcServer::new(
+        rpc_config,
+        io_handler,
+        vec![],
+        shared.notify_controller(),
+        &network_controller,
+    );
     let rpc_uri = format!(
         "http://{}:{}/",
         rpc_server.http_address().ip(),
@@ -663,22 +679,51 @@ where
 // * Use replace_rpc_response to skip the response matching assertions.
 // * Fix timestamp related fields.
 fn mock_rpc_response(example: &RpcTestExample, response: &mut RpcTestResponse) {
-    use ckb_jsonrpc_types::{BannedAddr, Capacity, LocalNode, RemoteNode, Uint64};
+    use ckb_jsonrpc_types::{
+        BannedAddr, Capacity, EpochStats, LocalNode, NetworkTime, PendingBlockReward,
+        PoolTxConflicts, PoolTxDetails, ProposalWindowInfo, RemoteNode, StagedTransaction,
+        SyncProgress, Uint64,
+    };
 
     match example.request.method.as_str() {
         "local_node_info" => replace_rpc_response::<LocalNode>(example, response),
         "get_peers" => replace_rpc_response::<Vec<RemoteNode>>(example, response),
         "get_banned_addresses" => replace_rpc_response::<Vec<BannedAddr>>(example, response),
+        "sync_progress" => replace_rpc_response::<SyncProgress>(example, response),
+        "get_network_time" => replace_rpc_response::<NetworkTime>(example, response),
         "calculate_dao_maximum_withdraw" => replace_rpc_response::<Capacity>(example, response),
         "subscribe" => replace_rpc_response::<Uint64>(example, response),
         "unsubscribe" => replace_rpc_response::<bool>(example, response),
         "send_transaction" => replace_rpc_response::<H256>(example, response),
+        "get_pool_conflicts" => replace_rpc_response::<PoolTxConflicts>(example, response),
+        "get_pool_entries" => replace_rpc_response::<PoolTxDetails>(example, response),
+        "get_staged_transactions" => {
+            replace_rpc_response::<Vec<StagedTransaction>>(example, response)
+        }
+        "get_proposal_window" => replace_rpc_response::<ProposalWindowInfo>(example, response),
+        "rpc_version" => {
+            response.result["version"] = example.response.result["version"].clone();
+            response.result["deprecated_rpc_enabled"] =
+                example.response.result["deprecated_rpc_enabled"].clone();
+        }
+        "get_epoch_stats" => replace_rpc_response::<Vec<EpochStats>>(example, response),
+        "get_pending_block_rewards" => {
+            replace_rpc_response::<Vec<PendingBlockReward>>(example, response)
+        }
         "get_block_template" => {
             response.result["current_time"] = example.response.result["current_time"].clone()
         }
         "get_blockchain_info" => {
             response.result["chain"] = example.response.result["chain"].clone()
         }
+        "get_transaction_and_cells" => {
+            response.result["input_cells"][0]["output"]["capacity"] =
+                example.response.result["input_cells"][0]["output"]["capacity"].clone()
+        }
+        "fill_partial_transaction" => {
+            response.result["input_cells"][0]["capacity"] =
+                example.response.result["input_cells"][0]["capacity"].clone()
+        }
         "send_alert" => response.error["data"] = example.response.error["data"].clone(),
         _ => {}
     }
@@ -0,0 +1,42 @@
+//! The phases of the initial block download, which the IBD wall-time is attributed to.
+use std::fmt;
+
+/// A phase of the initial block download.
+///
+/// The time spent in each phase is reported via [`Shared::record_ibd_phase`] to the counter
+/// `ckb.ibd.phase_time_us` in microseconds, and the number of the recorded operations to the
+/// counter `ckb.ibd.phase_ops`, both labeled by the phase name.
+///
+/// [`Shared::record_ibd_phase`]: crate::Shared::record_ibd_phase
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IbdPhase {
+    /// Processing the received headers, including the PoW verification.
+    HeaderDownload,
+    /// Waiting for a requested block, from the request is sent until the block is received.
+    BlockDownload,
+    /// Verifying a block, both non-contextual and contextual.
+    Verify,
+    /// Committing a processed block to the database.
+    Commit,
+    /// Moving the ancient blocks to the freezer.
+    Freezer,
+}
+
+impl IbdPhase {
+    /// Returns the phase name used as the metrics label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IbdPhase::HeaderDownload => "header_download",
+            IbdPhase::BlockDownload => "block_download",
+            IbdPhase::Verify => "verify",
+            IbdPhase::Commit => "commit",
+            IbdPhase::Freezer => "freezer",
+        }
+    }
+}
+
+impl fmt::Display for IbdPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -1,9 +1,13 @@
 //! TODO(doc): @quake
 
 // num_cpus is used in proc_macro
+pub mod ibd_phase;
+pub mod memory_budget;
 pub mod shared;
 
 pub use ckb_snapshot::{Snapshot, SnapshotMgr};
+pub use ibd_phase::IbdPhase;
+pub use memory_budget::MemoryBudget;
 use p2p::SessionId;
 pub use shared::Shared;
 
@@ -0,0 +1,218 @@
+//! The memory budget coordinating the sizes of the caches.
+use ckb_app_config::StoreConfig;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const MB: u64 = 1024 * 1024;
+
+// The part of the budget in percent left for the memory which is not tracked by the shares, such as
+// RocksDB, the network buffers and the chain state
+const HEADROOM_PERCENT: u64 = 20;
+
+// The shares of the rest of the budget in percent
+const HEADER_MAP_PERCENT: u64 = 30;
+const TX_VERIFY_CACHE_PERCENT: u64 = 10;
+const STORE_CACHE_PERCENT: u64 = 20;
+const TX_POOL_PERCENT: u64 = 40;
+
+// The estimated memory sizes of the cache entries in bytes
+const HEADER_ENTRY_SIZE: usize = 512;
+const TX_VERIFY_CACHE_ENTRY_SIZE: usize = 256;
+const CELL_DATA_ENTRY_SIZE: usize = 4096;
+const BLOCK_SECTION_ENTRY_SIZE: usize = 4096;
+
+// The resizable caches shrink to this percent of their shares under pressure
+const SHRINK_PERCENT: usize = 50;
+// The pressure is relieved when the allocated memory drops below this percent of the budget
+const RELIEF_PERCENT: u64 = 80;
+
+/// The memory budget shared by the header map, the transaction verification cache, the store
+/// caches and the transaction pool.
+///
+/// The budget is the limit of the memory allocated by the whole process. 20% of it is left as the
+/// headroom for the memory which is not tracked, the rest is split into fixed shares, and each
+/// share is converted into the capacity of the component with the estimated entry sizes.
+///
+/// The memory is under pressure when the allocated memory exceeds the budget. The resizable
+/// caches, which are the transaction verification cache and the store caches, shrink to half of
+/// their shares until the allocated memory drops below 80% of the budget.
+#[derive(Debug)]
+pub struct MemoryBudget {
+    budget: u64,
+    under_pressure: AtomicBool,
+}
+
+impl MemoryBudget {
+    /// Creates a budget of `budget_mb` MB.
+    pub fn new(budget_mb: u64) -> Self {
+        MemoryBudget {
+            budget: budget_mb.saturating_mul(MB),
+            under_pressure: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the budget in bytes.
+    pub fn budget(&self) -> u64 {
+        self.budget
+    }
+
+    /// Returns whether the allocated memory exceeds the budget.
+    pub fn is_under_pressure(&self) -> bool {
+        self.under_pressure.load(Ordering::Relaxed)
+    }
+
+    /// Updates the pressure with the memory allocated by the process in bytes.
+    ///
+    /// Returns true when the pressure changes, and the resizable caches should be resized.
+    pub fn update_pressure(&self, allocated: u64) -> bool {
+        let under_pressure = self.is_under_pressure();
+        if !under_pressure && allocated > self.budget {
+            self.under_pressure.store(true, Ordering::Relaxed);
+            true
+        } else if under_pressure && allocated < self.budget / 100 * RELIEF_PERCENT {
+            self.under_pressure.store(false, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the max number of headers kept in memory by the header map.
+    pub fn header_map_limit(&self) -> usize {
+        (self.share(HEADER_MAP_PERCENT) / HEADER_ENTRY_SIZE).max(1)
+    }
+
+    /// Returns the max size of the transactions in the pool in bytes.
+    pub fn tx_pool_max_mem_size(&self) -> usize {
+        self.share(TX_POOL_PERCENT)
+    }
+
+    /// Returns the capacity of the transaction verification cache under the current pressure.
+    pub fn tx_verify_cache_size(&self) -> usize {
+        self.scale(self.share(TX_VERIFY_CACHE_PERCENT) / TX_VERIFY_CACHE_ENTRY_SIZE)
+    }
+
+    /// Sets the capacities of the store caches under the current pressure.
+    ///
+    /// The headers take 40% of the share, the cell data take 30%, and the block sections take the
+    /// rest.
+    pub fn apply_store_config(&self, config: &mut StoreConfig) {
+        let share = self.share(STORE_CACHE_PERCENT);
+        let block_sections = self.scale(share * 30 / 100 / 4 / BLOCK_SECTION_ENTRY_SIZE);
+        config.header_cache_size = self.scale(share * 40 / 100 / HEADER_ENTRY_SIZE);
+        config.cell_data_cache_size = self.scale(share * 30 / 100 / CELL_DATA_ENTRY_SIZE);
+        config.block_proposals_cache_size = block_sections;
+        config.block_tx_hashes_cache_size = block_sections;
+        config.block_uncles_cache_size = block_sections;
+        config.block_extensions_cache_size = block_sections;
+    }
+
+    fn share(&self, percent: u64) -> usize {
+        (self.budget / 100 * (100 - HEADROOM_PERCENT) / 100 * percent) as usize
+    }
+
+    fn scale(&self, capacity: usize) -> usize {
+        let capacity = if self.is_under_pressure() {
+            capacity * SHRINK_PERCENT / 100
+        } else {
+            capacity
+        };
+        capacity.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The estimated memory in bytes taken by the components when they are full.
+    fn estimated_usage(budget: &MemoryBudget) -> usize {
+        let mut config = StoreConfig::default();
+        budget.apply_store_config(&mut config);
+        budget.header_map_limit() * HEADER_ENTRY_SIZE
+            + budget.tx_pool_max_mem_size()
+            + budget.tx_verify_cache_size() * TX_VERIFY_CACHE_ENTRY_SIZE
+            + config.header_cache_size * HEADER_ENTRY_SIZE
+            + config.cell_data_cache_size * CELL_DATA_ENTRY_SIZE
+            + (config.block_proposals_cache_size
+                + config.block_tx_hashes_cache_size
+                + config.block_uncles_cache_size
+                + config.block_extensions_cache_size)
+                * BLOCK_SECTION_ENTRY_SIZE
+    }
+
+    #[test]
+    fn test_shares_leave_headroom() {
+        for budget_mb in &[100, 4096] {
+            let budget = MemoryBudget::new(*budget_mb);
+            let usage = estimated_usage(&budget) as u64;
+            let headroom = budget.budget() / 100 * HEADROOM_PERCENT;
+            // the components never fill the whole budget, so they can not push the process
+            // into the pressure by themselves
+            assert!(usage <= budget.budget() - headroom);
+            assert!(usage > (budget.budget() - headroom) / 100 * 99);
+        }
+
+        // the capacities never drop to zero
+        let budget = MemoryBudget::new(0);
+        assert_eq!(budget.header_map_limit(), 1);
+        assert_eq!(budget.tx_verify_cache_size(), 1);
+    }
+
+    #[test]
+    fn test_pressure() {
+        let budget = MemoryBudget::new(100);
+        let limit = budget.budget();
+        let relief = limit / 100 * RELIEF_PERCENT;
+        assert!(!budget.is_under_pressure());
+
+        assert!(!budget.update_pressure(limit));
+        assert!(!budget.is_under_pressure());
+
+        // enters the pressure above the budget
+        assert!(budget.update_pressure(limit + 1));
+        assert!(budget.is_under_pressure());
+        assert!(!budget.update_pressure(limit + 1));
+
+        // stays under pressure until the allocated memory drops below the relief level
+        assert!(!budget.update_pressure(limit));
+        assert!(!budget.update_pressure(relief));
+        assert!(budget.is_under_pressure());
+
+        assert!(budget.update_pressure(relief - 1));
+        assert!(!budget.is_under_pressure());
+        assert!(!budget.update_pressure(relief - 1));
+    }
+
+    #[test]
+    fn test_shrink_under_pressure() {
+        let budget = MemoryBudget::new(100);
+        let tx_verify_cache_size = budget.tx_verify_cache_size();
+        let header_map_limit = budget.header_map_limit();
+        let tx_pool_max_mem_size = budget.tx_pool_max_mem_size();
+        let mut config = StoreConfig::default();
+        budget.apply_store_config(&mut config);
+
+        assert!(budget.update_pressure(budget.budget() + 1));
+        let mut shrunk = StoreConfig::default();
+        budget.apply_store_config(&mut shrunk);
+        let half = |capacity: usize| capacity * SHRINK_PERCENT / 100;
+        assert_eq!(budget.tx_verify_cache_size(), half(tx_verify_cache_size));
+        assert_eq!(shrunk.header_cache_size, half(config.header_cache_size));
+        assert_eq!(
+            shrunk.cell_data_cache_size,
+            half(config.cell_data_cache_size)
+        );
+        assert_eq!(
+            shrunk.block_tx_hashes_cache_size,
+            half(config.block_tx_hashes_cache_size)
+        );
+        assert!(estimated_usage(&budget) < (budget.budget() / 100 * RELIEF_PERCENT) as usize);
+
+        // the header map and the tx-pool are not resizable
+        assert_eq!(budget.header_map_limit(), header_map_limit);
+        assert_eq!(budget.tx_pool_max_mem_size(), tx_pool_max_mem_size);
+
+        assert!(budget.update_pressure(0));
+        assert_eq!(budget.tx_verify_cache_size(), tx_verify_cache_size);
+    }
+}
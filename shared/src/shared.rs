@@ -1,7 +1,10 @@
 //! TODO(doc): @quake
+use crate::IbdPhase;
+use crate::MemoryBudget;
 use crate::PeerIndex;
 use crate::{Snapshot, SnapshotMgr};
 use arc_swap::Guard;
+use ckb_app_config::StoreConfig;
 use ckb_async_runtime::Handle;
 use ckb_chain_spec::consensus::Consensus;
 use ckb_channel::Sender;
@@ -10,19 +13,20 @@ use ckb_constant::sync::MAX_TIP_AGE;
 use ckb_db::{Direction, IteratorMode};
 use ckb_db_schema::{COLUMN_BLOCK_BODY, COLUMN_NUMBER_HASH};
 use ckb_error::Error;
+use ckb_metrics::{metrics, Timer};
 use ckb_notify::NotifyController;
 use ckb_proposal_table::ProposalView;
 use ckb_stop_handler::{SignalSender, StopHandler};
 use ckb_store::{ChainDB, ChainStore};
 use ckb_tx_pool::{TokioRwLock, TxPoolController};
 use ckb_types::{
-    core::{service, BlockNumber, EpochExt, EpochNumber, HeaderView},
+    core::{service, BlockNumber, EpochExt, EpochNumber, FeeRate, HeaderView},
     packed::{self, Byte32},
     prelude::*,
     U256,
 };
+use ckb_util::mock_clock::unix_time_as_millis;
 use ckb_verification::cache::TxVerificationCache;
-use faketime::unix_time_as_millis;
 use std::cmp;
 use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -58,7 +62,8 @@ pub struct Shared {
     pub(crate) snapshot_mgr: Arc<SnapshotMgr>,
     pub(crate) async_handle: Handle,
     pub(crate) ibd_finished: Arc<AtomicBool>,
-    pub(crate) relay_tx_sender: Sender<(Option<PeerIndex>, bool, Byte32)>,
+    pub(crate) relay_tx_sender: Sender<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+    pub(crate) memory_budget: Option<Arc<MemoryBudget>>,
 }
 
 impl Shared {
@@ -73,7 +78,8 @@ impl Shared {
         snapshot_mgr: Arc<SnapshotMgr>,
         async_handle: Handle,
         ibd_finished: Arc<AtomicBool>,
-        relay_tx_sender: Sender<(Option<PeerIndex>, bool, Byte32)>,
+        relay_tx_sender: Sender<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+        memory_budget: Option<Arc<MemoryBudget>>,
     ) -> Shared {
         Shared {
             store,
@@ -85,6 +91,7 @@ impl Shared {
             async_handle,
             ibd_finished,
             relay_tx_sender,
+            memory_budget,
         }
     }
     /// Spawn freeze background thread that periodically checks and moves ancient data from the kv database into the freezer.
@@ -98,10 +105,12 @@ impl Shared {
                 .spawn(move || loop {
                     match signal_receiver.recv_timeout(FREEZER_INTERVAL) {
                         Err(_) => {
+                            let timer = Timer::start();
                             if let Err(e) = shared.freeze() {
                                 ckb_logger::error!("Freezer error {}", e);
                                 break;
                             }
+                            shared.record_ibd_phase(IbdPhase::Freezer, timer.stop());
                         }
                         Ok(_) => {
                             ckb_logger::info!("Freezer closing");
@@ -354,4 +363,62 @@ impl Shared {
             false
         }
     }
+
+    /// Returns the memory budget, which is `None` when it is disabled.
+    pub fn memory_budget(&self) -> Option<&Arc<MemoryBudget>> {
+        self.memory_budget.as_ref()
+    }
+
+    /// Resizes the store caches and the transaction verification cache when the memory pressure
+    /// changes.
+    ///
+    /// `allocated` is the memory allocated by the process in bytes.
+    pub fn rebalance_memory(&self, allocated: u64) {
+        let budget = match self.memory_budget.as_ref() {
+            Some(budget) => budget,
+            None => return,
+        };
+        if !budget.update_pressure(allocated) {
+            return;
+        }
+        let under_pressure = budget.is_under_pressure();
+        if under_pressure {
+            ckb_logger::warn!(
+                "allocated memory {} bytes exceeds the budget {} bytes, shrink the caches",
+                allocated,
+                budget.budget()
+            );
+        } else {
+            ckb_logger::info!(
+                "allocated memory {} bytes drops below the budget, restore the caches",
+                allocated
+            );
+        }
+        metrics!(
+            gauge,
+            "ckb.memory_budget.under_pressure",
+            under_pressure as i64
+        );
+
+        if let Some(cache) = self.store.cache() {
+            let mut store_config = StoreConfig::default();
+            budget.apply_store_config(&mut store_config);
+            cache.resize(&store_config);
+        }
+        let txs_verify_cache = Arc::clone(&self.txs_verify_cache);
+        let capacity = budget.tx_verify_cache_size();
+        self.async_handle.spawn(async move {
+            txs_verify_cache.write().await.resize(capacity);
+        });
+    }
+
+    /// Attributes the elapsed time to the phase when the chain is in initial block download.
+    ///
+    /// Nothing is recorded once the initial block download has finished.
+    pub fn record_ibd_phase(&self, phase: IbdPhase, elapsed: Duration) {
+        if self.is_initial_block_download() {
+            metrics!(counter, "ckb.ibd.phase_time_us", elapsed.as_micros() as u64, "phase" => phase.as_str());
+            metrics!(counter, "ckb.ibd.phase_ops", 1, "phase" => phase.as_str());
+        }
+    }
 }
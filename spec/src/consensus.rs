@@ -33,8 +33,10 @@ use std::sync::Arc;
 pub(crate) const DEFAULT_SECONDARY_EPOCH_REWARD: Capacity = Capacity::shannons(613_698_63013698);
 // 4.2 billion per year
 pub(crate) const INITIAL_PRIMARY_EPOCH_REWARD: Capacity = Capacity::shannons(1_917_808_21917808);
-const MAX_UNCLE_NUM: usize = 2;
-pub(crate) const TX_PROPOSAL_WINDOW: ProposalWindow = ProposalWindow(2, 10);
+/// The default maximum amount of uncles allowed for a block
+pub const MAX_UNCLE_NUM: usize = 2;
+/// The default two-step-transaction-confirmation proposal window
+pub const TX_PROPOSAL_WINDOW: ProposalWindow = ProposalWindow(2, 10);
 // Cellbase outputs are "locked" and require 4 epoch confirmations (approximately 16 hours) before
 // they mature sufficiently to be spendable,
 // This is to reduce the risk of later txs being reversed if a chain reorganization occurs.
@@ -71,7 +73,8 @@ pub(crate) const DEFAULT_PRIMARY_EPOCH_REWARD_HALVING_INTERVAL: EpochNumber =
 
 /// The default maximum allowed size in bytes for a block
 pub const MAX_BLOCK_BYTES: u64 = TWO_IN_TWO_OUT_BYTES * TWO_IN_TWO_OUT_COUNT;
-pub(crate) const MAX_BLOCK_CYCLES: u64 = TWO_IN_TWO_OUT_CYCLES * TWO_IN_TWO_OUT_COUNT;
+/// The default maximum cycles that all the scripts in all the commit transactions can take
+pub const MAX_BLOCK_CYCLES: u64 = TWO_IN_TWO_OUT_CYCLES * TWO_IN_TWO_OUT_COUNT;
 
 /// The default maximum allowed amount of proposals for a block
 ///
@@ -931,6 +934,66 @@ impl Consensus {
     pub fn hardfork_switch(&self) -> &HardForkSwitch {
         &self.hardfork_switch
     }
+
+    /// Predicts the `n_epochs` epochs following `epoch`.
+    ///
+    /// The prediction assumes the hash rate stays the same and the orphan rate meets the target,
+    /// in which case the difficulty adjustment keeps the epoch length and the difficulty
+    /// unchanged. The difficulty bounds are the range reachable if the hash rate moves to the
+    /// dampening limit in every epoch.
+    pub fn epoch_schedule_preview(&self, epoch: &EpochExt, n_epochs: u64) -> Vec<EpochPreview> {
+        let difficulty = compact_to_difficulty(epoch.compact_target());
+        let mut min_difficulty = difficulty.clone();
+        let mut max_difficulty = difficulty.clone();
+        let mut start_number = epoch.start_number() + epoch.length();
+
+        (epoch.number() + 1..)
+            .take(n_epochs as usize)
+            .map(|number| {
+                if !self.permanent_difficulty() {
+                    min_difficulty = cmp::max(&min_difficulty / TAU, U256::one());
+                    max_difficulty = if max_difficulty > &U256::max_value() / TAU {
+                        U256::max_value()
+                    } else {
+                        &max_difficulty * TAU
+                    };
+                }
+                let preview = EpochPreview {
+                    number,
+                    start_number,
+                    length: epoch.length(),
+                    primary_reward: self.primary_epoch_reward(number),
+                    secondary_reward: self.secondary_epoch_reward(),
+                    difficulty: difficulty.clone(),
+                    min_difficulty: min_difficulty.clone(),
+                    max_difficulty: max_difficulty.clone(),
+                };
+                start_number += epoch.length();
+                preview
+            })
+            .collect()
+    }
+}
+
+/// The predicted parameters of an upcoming epoch, see `Consensus::epoch_schedule_preview`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpochPreview {
+    /// The epoch number
+    pub number: EpochNumber,
+    /// The predicted number of the first block in the epoch
+    pub start_number: BlockNumber,
+    /// The predicted number of blocks in the epoch
+    pub length: BlockNumber,
+    /// The primary reward of the epoch, which is halved every halving interval
+    pub primary_reward: Capacity,
+    /// The secondary reward of the epoch
+    pub secondary_reward: Capacity,
+    /// The predicted difficulty assuming the current hash rate
+    pub difficulty: U256,
+    /// The lowest difficulty the epoch can reach
+    pub min_difficulty: U256,
+    /// The highest difficulty the epoch can reach
+    pub max_difficulty: U256,
 }
 
 /// Trait for consensus provider.
@@ -1177,4 +1240,40 @@ pub mod test {
             epoch.primary_reward().as_u64()
         );
     }
+
+    #[test]
+    fn test_epoch_schedule_preview() {
+        let cellbase = TransactionBuilder::default()
+            .witness(Bytes::default())
+            .build();
+        let epoch_ext = build_genesis_epoch_ext(
+            capacity_bytes!(100),
+            DIFF_TWO,
+            GENESIS_EPOCH_LENGTH,
+            DEFAULT_EPOCH_DURATION_TARGET,
+            DEFAULT_ORPHAN_RATE_TARGET,
+        );
+        let genesis = BlockBuilder::default().transaction(cellbase).build();
+        let consensus = ConsensusBuilder::new(genesis, epoch_ext)
+            .initial_primary_epoch_reward(capacity_bytes!(100))
+            .primary_epoch_reward_halving_interval(2)
+            .build();
+        let genesis_epoch = consensus.genesis_epoch_ext();
+
+        let preview = consensus.epoch_schedule_preview(genesis_epoch, 4);
+        assert_eq!(preview.len(), 4);
+        for (i, epoch) in preview.iter().enumerate() {
+            let i = i as u64;
+            assert_eq!(epoch.number, i + 1);
+            assert_eq!(epoch.start_number, (i + 1) * GENESIS_EPOCH_LENGTH);
+            assert_eq!(epoch.length, GENESIS_EPOCH_LENGTH);
+            assert_eq!(epoch.difficulty, U256::from(2u64));
+            assert_eq!(epoch.min_difficulty, U256::one());
+            assert_eq!(epoch.max_difficulty, U256::from(2u64 << (i + 1)));
+        }
+        assert_eq!(preview[0].primary_reward, capacity_bytes!(100));
+        assert_eq!(preview[1].primary_reward, capacity_bytes!(50));
+        assert_eq!(preview[2].primary_reward, capacity_bytes!(50));
+        assert_eq!(preview[3].primary_reward, capacity_bytes!(25));
+    }
 }
@@ -11,13 +11,13 @@
 // details https://docs.rs/toml/0.5.0/toml/ser/index.html
 
 use crate::consensus::{
-    build_genesis_dao_data, build_genesis_epoch_ext, Consensus, ConsensusBuilder,
-    SATOSHI_CELL_OCCUPIED_RATIO, SATOSHI_PUBKEY_HASH, TYPE_ID_CODE_HASH,
+    build_genesis_dao_data, build_genesis_epoch_ext, Consensus, ConsensusBuilder, ProposalWindow,
+    SATOSHI_CELL_OCCUPIED_RATIO, SATOSHI_PUBKEY_HASH, TX_PROPOSAL_WINDOW, TYPE_ID_CODE_HASH,
 };
 use ckb_constant::hardfork::{mainnet, testnet};
 use ckb_crypto::secp::Privkey;
 use ckb_hash::{blake2b_256, new_blake2b};
-use ckb_jsonrpc_types::Script;
+use ckb_jsonrpc_types::{JsonBytes, Script};
 use ckb_pow::{Pow, PowEngine};
 use ckb_resource::{
     Resource, CODE_HASH_DAO, CODE_HASH_SECP256K1_BLAKE160_MULTISIG_ALL,
@@ -35,18 +35,22 @@ use ckb_types::{
     H160, H256, U128,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 
 pub use error::SpecError;
 pub use hardfork::HardForkConfig;
+pub use preset::ParamsPreset;
 
 pub mod consensus;
 mod error;
 mod hardfork;
+mod preset;
 
 // Just a random secp256k1 secret key for dep group input cell's lock
 const SPECIAL_CELL_PRIVKEY: H256 =
@@ -171,6 +175,11 @@ pub mod default_params {
 #[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Params {
+    /// The named preset of the parameters
+    ///
+    /// The parameters not set explicitly are taken from the preset, see [`ParamsPreset`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<ParamsPreset>,
     /// The initial_primary_epoch_reward
     ///
     /// See [`initial_primary_epoch_reward`](consensus/struct.Consensus.html#structfield.initial_primary_epoch_reward)
@@ -246,21 +255,24 @@ impl Params {
             .unwrap_or_else(default_params::secondary_epoch_reward)
     }
 
-    /// Return the `max_block_cycles`, otherwise if None, returns the default value
+    /// Return the `max_block_cycles`, otherwise if None, returns the value of the preset or the default value
     pub fn max_block_cycles(&self) -> Cycle {
         self.max_block_cycles
+            .or_else(|| self.preset.map(ParamsPreset::max_block_cycles))
             .unwrap_or_else(default_params::max_block_cycles)
     }
 
-    /// Return the `max_block_bytes`, otherwise if None, returns the default value
+    /// Return the `max_block_bytes`, otherwise if None, returns the value of the preset or the default value
     pub fn max_block_bytes(&self) -> u64 {
         self.max_block_bytes
+            .or_else(|| self.preset.map(ParamsPreset::max_block_bytes))
             .unwrap_or_else(default_params::max_block_bytes)
     }
 
-    /// Return the `cellbase_maturity`, otherwise if None, returns the default value
+    /// Return the `cellbase_maturity`, otherwise if None, returns the value of the preset or the default value
     pub fn cellbase_maturity(&self) -> u64 {
         self.cellbase_maturity
+            .or_else(|| self.preset.map(ParamsPreset::cellbase_maturity))
             .unwrap_or_else(default_params::cellbase_maturity)
     }
 
@@ -270,27 +282,39 @@ impl Params {
             .unwrap_or_else(default_params::primary_epoch_reward_halving_interval)
     }
 
-    /// Return the `permanent_difficulty_in_dummy`, otherwise if None, returns the default value
+    /// Return the `permanent_difficulty_in_dummy`, otherwise if None, returns the value of the preset or the default value
     pub fn permanent_difficulty_in_dummy(&self) -> bool {
         self.permanent_difficulty_in_dummy
+            .or_else(|| self.preset.map(ParamsPreset::permanent_difficulty_in_dummy))
             .unwrap_or_else(default_params::permanent_difficulty_in_dummy)
     }
 
     /// Return the `epoch_duration_target`, otherwise if None, returns the default value
+    ///
+    /// With a preset, the default value is scaled with the `genesis_epoch_length`, which may
+    /// be set explicitly, to keep the target block interval.
     pub fn epoch_duration_target(&self) -> u64 {
-        self.epoch_duration_target
-            .unwrap_or_else(default_params::epoch_duration_target)
+        self.epoch_duration_target.unwrap_or_else(|| {
+            if self.preset.is_some() {
+                default_params::epoch_duration_target() * self.genesis_epoch_length()
+                    / default_params::genesis_epoch_length()
+            } else {
+                default_params::epoch_duration_target()
+            }
+        })
     }
 
-    /// Return the `genesis_epoch_length`, otherwise if None, returns the default value
+    /// Return the `genesis_epoch_length`, otherwise if None, returns the value of the preset or the default value
     pub fn genesis_epoch_length(&self) -> BlockNumber {
         self.genesis_epoch_length
+            .or_else(|| self.preset.map(ParamsPreset::genesis_epoch_length))
             .unwrap_or_else(default_params::genesis_epoch_length)
     }
 
-    /// Return the `max_block_proposals_limit`, otherwise if None, returns the default value
+    /// Return the `max_block_proposals_limit`, otherwise if None, returns the value of the preset or the default value
     pub fn max_block_proposals_limit(&self) -> BlockNumber {
         self.max_block_proposals_limit
+            .or_else(|| self.preset.map(ParamsPreset::max_block_proposals_limit))
             .unwrap_or_else(default_params::max_block_proposals_limit)
     }
 
@@ -299,6 +323,13 @@ impl Params {
         self.orphan_rate_target
             .unwrap_or_else(default_params::orphan_rate_target)
     }
+
+    /// Return the `tx_proposal_window` of the preset, otherwise if None, returns the default value
+    pub fn tx_proposal_window(&self) -> ProposalWindow {
+        self.preset
+            .map(ParamsPreset::tx_proposal_window)
+            .unwrap_or(TX_PROPOSAL_WINDOW)
+    }
 }
 
 /// The genesis information
@@ -322,9 +353,24 @@ pub struct Genesis {
     pub hash: Option<H256>,
     /// The genesis block nonce
     pub nonce: U128,
+    /// The genesis block extension
+    ///
+    /// It requires the hard fork feature `rfc_0224` activated at epoch 0, and it must not be
+    /// empty or longer than 96 bytes, the same as the extensions of the other blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension: Option<JsonBytes>,
+    /// The CSV file of the additional issued cells, which is absolute or relative to the
+    /// directory containing the spec file
+    ///
+    /// Each row has the lock args of the SECP256K1/blake160 sighash-all lock and the capacity in
+    /// shannons. The cells are sorted by the lock args and appended to `issued_cells` when the
+    /// spec is loaded, and this field is cleared then.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_cells_file: Option<PathBuf>,
     /// The genesis block issued_cells
     ///
     /// Initial token supply
+    #[serde(default)]
     pub issued_cells: Vec<IssuedCell>,
     /// The genesis cell
     ///
@@ -343,6 +389,12 @@ pub struct Genesis {
     ///
     /// see detail [dep-group](https://github.com/nervosnetwork/rfcs/blob/f639fa8b30b5568b895449b7ab3ef4ad40ca077a/rfcs/0022-transaction-structure/0022-transaction-structure.md#dep-group)
     pub dep_groups: Vec<DepGroupResource>,
+    /// The additional genesis transactions after the dep group transaction
+    ///
+    /// Each transaction spends a special issued cell in the genesis cellbase, whose capacity is
+    /// the total capacity of the transaction outputs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_transactions: Vec<GenesisTransaction>,
     /// The burned 25% of Nervos CKBytes in genesis block
     #[serde(default)]
     pub satoshi_gift: SatoshiGift,
@@ -379,6 +431,54 @@ pub struct IssuedCell {
     pub capacity: Capacity,
     /// The cell lock
     pub lock: Script,
+    /// Locks the cell until the absolute since, such as the cliff of a vesting schedule
+    ///
+    /// The lock must be the SECP256K1/multisig-all lock with the 20 bytes multisig script hash
+    /// in the args. The since is appended to the args when building the genesis block, so the
+    /// lock rejects spending the cell before it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<IssuedCellSince>,
+}
+
+/// The absolute since which locks an issued cell
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum IssuedCellSince {
+    /// Locked until the block number
+    BlockNumber(BlockNumber),
+    /// Locked until the start of the epoch
+    Epoch(EpochNumber),
+    /// Locked until the median time of the previous blocks reaches the timestamp, unit second
+    Timestamp(u64),
+}
+
+impl IssuedCellSince {
+    // The metric flags in the highest byte of the since value
+    const BLOCK_NUMBER_FLAG: u64 = 0x0000_0000_0000_0000;
+    const EPOCH_FLAG: u64 = 0x2000_0000_0000_0000;
+    const TIMESTAMP_FLAG: u64 = 0x4000_0000_0000_0000;
+    const VALUE_MASK: u64 = 0x00ff_ffff_ffff_ffff;
+
+    /// The since value in the format of the transaction input since
+    pub fn to_since(self) -> Result<u64, Box<dyn Error>> {
+        let (flag, value) = match self {
+            IssuedCellSince::BlockNumber(number) => (Self::BLOCK_NUMBER_FLAG, number),
+            IssuedCellSince::Epoch(number) => {
+                if number >= EpochNumberWithFraction::NUMBER_MAXIMUM_VALUE {
+                    return Err(format!("Epoch number {} in since is too large", number).into());
+                }
+                (
+                    Self::EPOCH_FLAG,
+                    EpochNumberWithFraction::new(number, 0, 1).full_value(),
+                )
+            }
+            IssuedCellSince::Timestamp(timestamp) => (Self::TIMESTAMP_FLAG, timestamp),
+        };
+        if value & !Self::VALUE_MASK != 0 {
+            return Err(format!("Value {} in since is too large", value).into());
+        }
+        Ok(flag | value)
+    }
 }
 
 /// The genesis dep_group file resources
@@ -391,6 +491,31 @@ pub struct DepGroupResource {
     pub files: Vec<Resource>,
 }
 
+/// An additional genesis transaction
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisTransaction {
+    /// The transaction outputs
+    pub outputs: Vec<GenesisOutput>,
+}
+
+/// An output cell of an additional genesis transaction
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisOutput {
+    /// Overwrite the cell capacity. Set to None to use the minimal capacity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<u64>,
+    /// The cell data
+    #[serde(default)]
+    pub data: JsonBytes,
+    /// The cell lock
+    pub lock: Script,
+    /// The cell type script
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<Script>,
+}
+
 /// The burned 25% of Nervos CKBytes in genesis block
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -414,6 +539,7 @@ impl Default for SatoshiGift {
 pub(crate) enum SpecLoadError {
     FileNotFound,
     GenesisMismatch { expect: H256, actual: H256 },
+    InvalidIssuedCellsFile(String),
 }
 
 impl SpecLoadError {
@@ -424,6 +550,10 @@ impl SpecLoadError {
     fn genesis_mismatch(expect: H256, actual: H256) -> Box<Self> {
         Box::new(SpecLoadError::GenesisMismatch { expect, actual })
     }
+
+    fn invalid_issued_cells_file(reason: String) -> Box<Self> {
+        Box::new(SpecLoadError::InvalidIssuedCellsFile(reason))
+    }
 }
 
 impl Error for SpecLoadError {}
@@ -437,6 +567,9 @@ impl fmt::Display for SpecLoadError {
                 "ChainSpec: genesis hash mismatch, expect {:#x}, actual {:#x}",
                 expect, actual
             ),
+            SpecLoadError::InvalidIssuedCellsFile(reason) => {
+                write!(f, "ChainSpec: invalid issued_cells_file, {}", reason)
+            }
         }
     }
 }
@@ -456,7 +589,42 @@ impl ChainSpec {
             }
         }
         // leverage serialize for sanitizing
-        spec.hash = packed::Byte32::new(blake2b_256(&toml::to_vec(&spec)?));
+        let spec_bytes = toml::to_vec(&spec)?;
+        // The file is cleared once its cells are merged, so serializing the loaded spec does not
+        // append them again when it is loaded back.
+        spec.hash = match spec.genesis.issued_cells_file.take() {
+            Some(file) => {
+                let path = match resource.parent() {
+                    Some(parent) => parent.join(file),
+                    None if file.is_absolute() => file,
+                    None => {
+                        return Err(SpecLoadError::invalid_issued_cells_file(format!(
+                            "the relative path {} requires a spec file on the file system",
+                            file.display()
+                        )));
+                    }
+                };
+                let issued_cells = load_issued_cells_file(&path)?;
+                // The checksum of the sorted cells, so the spec hash does not depend on the
+                // formatting and the rows order of the file.
+                let mut checksum = [0u8; 32];
+                let mut blake2b = new_blake2b();
+                for cell in &issued_cells {
+                    blake2b.update(cell.build_output()?.as_slice());
+                }
+                blake2b.finalize(&mut checksum);
+
+                let mut hash = [0u8; 32];
+                let mut blake2b = new_blake2b();
+                blake2b.update(&spec_bytes);
+                blake2b.update(&checksum);
+                blake2b.finalize(&mut hash);
+
+                spec.genesis.issued_cells.extend(issued_cells);
+                packed::Byte32::new(hash)
+            }
+            None => packed::Byte32::new(blake2b_256(&spec_bytes)),
+        };
 
         Ok(spec)
     }
@@ -524,6 +692,7 @@ impl ChainSpec {
             .permanent_difficulty_in_dummy(self.params.permanent_difficulty_in_dummy())
             .max_block_proposals_limit(self.params.max_block_proposals_limit())
             .orphan_rate_target(self.params.orphan_rate_target())
+            .tx_proposal_window(self.params.tx_proposal_window())
             .hardfork_switch(hardfork_switch)
             .build();
 
@@ -532,9 +701,25 @@ impl ChainSpec {
 
     /// Build genesis block from chain spec
     pub fn build_genesis(&self) -> Result<BlockView, Box<dyn Error>> {
+        let extra_transactions_outputs = self
+            .genesis
+            .extra_transactions
+            .iter()
+            .map(GenesisTransaction::build_outputs)
+            .collect::<Result<Vec<_>, _>>()?;
+        let extra_special_cell_capacities = extra_transactions_outputs
+            .iter()
+            .map(|(outputs, _)| {
+                outputs
+                    .iter()
+                    .map(|output| Unpack::<Capacity>::unpack(&output.capacity()))
+                    .try_fold(Capacity::zero(), Capacity::safe_add)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
         let special_cell_capacity = {
-            let cellbase_transaction_for_special_cell_capacity =
-                self.build_cellbase_transaction(capacity_bytes!(500))?;
+            let cellbase_transaction_for_special_cell_capacity = self
+                .build_cellbase_transaction(capacity_bytes!(500), &extra_special_cell_capacities)?;
             // build transaction other than cellbase should return inputs for dao statistics
             let dep_group_transaction_for_special_cell_capacity =
                 self.build_dep_group_transaction(&cellbase_transaction_for_special_cell_capacity)?;
@@ -548,9 +733,23 @@ impl ChainSpec {
                 .try_fold(Capacity::zero(), Capacity::safe_add)
         }?;
 
-        let cellbase_transaction = self.build_cellbase_transaction(special_cell_capacity)?;
+        let cellbase_transaction =
+            self.build_cellbase_transaction(special_cell_capacity, &extra_special_cell_capacities)?;
         // build transaction other than cellbase should return inputs for dao statistics
         let dep_group_transaction = self.build_dep_group_transaction(&cellbase_transaction)?;
+        let extra_transactions = extra_transactions_outputs
+            .into_iter()
+            .enumerate()
+            .map(|(index, (outputs, outputs_data))| {
+                // The first special issued cell is spent by the dep group transaction
+                build_special_cell_spending_transaction(
+                    &cellbase_transaction,
+                    index + 1,
+                    outputs,
+                    outputs_data,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         let genesis_epoch_length = self.params.genesis_epoch_length();
         let genesis_primary_issuance = calculate_block_reward(
@@ -559,8 +758,10 @@ impl ChainSpec {
         );
         let genesis_secondary_issuance =
             calculate_block_reward(self.params.secondary_epoch_reward(), genesis_epoch_length);
+        let mut genesis_transactions = vec![&cellbase_transaction, &dep_group_transaction];
+        genesis_transactions.extend(extra_transactions.iter());
         let dao = build_genesis_dao_data(
-            vec![&cellbase_transaction, &dep_group_transaction],
+            genesis_transactions,
             &self.genesis.satoshi_gift.satoshi_pubkey_hash,
             self.genesis.satoshi_gift.satoshi_cell_occupied_ratio,
             genesis_primary_issuance,
@@ -578,6 +779,13 @@ impl ChainSpec {
             .nonce(u128::from_le_bytes(self.genesis.nonce.to_le_bytes()).pack())
             .transaction(cellbase_transaction)
             .transaction(dep_group_transaction)
+            .transactions(extra_transactions)
+            .extension(
+                self.genesis
+                    .extension
+                    .clone()
+                    .map(|extension| extension.into_bytes().pack()),
+            )
             .build();
 
         self.check_block(&block)?;
@@ -686,12 +894,33 @@ impl ChainSpec {
             &CODE_HASH_SECP256K1_BLAKE160_MULTISIG_ALL,
         )?;
 
+        // Check the block extension, with the same rules as the other blocks
+        if let Some(extension) = block.extension() {
+            if !self
+                .build_hardfork_switch()?
+                .is_reuse_uncles_hash_as_extra_hash_enabled(0)
+            {
+                return Err(
+                    "The genesis extension requires the hard fork feature rfc_0224 at epoch 0"
+                        .into(),
+                );
+            }
+            if extension.is_empty() || extension.len() > 96 {
+                return Err(format!(
+                    "Invalid genesis extension length {}, expected 1 to 96 bytes",
+                    extension.len()
+                )
+                .into());
+            }
+        }
+
         Ok(())
     }
 
     fn build_cellbase_transaction(
         &self,
         special_cell_capacity: Capacity,
+        extra_special_cell_capacities: &[Capacity],
     ) -> Result<TransactionView, Box<dyn Error>> {
         let input = packed::CellInput::new_cellbase_input(0);
         let mut outputs = Vec::<packed::CellOutput>::with_capacity(
@@ -703,6 +932,7 @@ impl ChainSpec {
         // - genesis cell, which contains a message and can never be spent.
         // - system cells, which stores the built-in code blocks.
         // - special issued cell, for dep group cell in next transaction
+        // - special issued cells, for the extra transactions
         // - issued cells
         let (output, data) = self.genesis.genesis_cell.build_output()?;
         outputs.push(output);
@@ -735,17 +965,36 @@ impl ChainSpec {
             .build();
         let special_issued_cell = packed::CellOutput::new_builder()
             .capacity(special_cell_capacity.pack())
-            .lock(special_issued_lock)
+            .lock(special_issued_lock.clone())
             .build();
         outputs.push(special_issued_cell);
         outputs_data.push(Bytes::new());
 
-        outputs.extend(
-            self.genesis
-                .issued_cells
-                .iter()
-                .map(IssuedCell::build_output),
-        );
+        for (index, capacity) in extra_special_cell_capacities.iter().enumerate() {
+            let special_issued_cell = packed::CellOutput::new_builder()
+                .capacity(capacity.pack())
+                .lock(special_issued_lock.clone())
+                .build();
+            let occupied_capacity = special_issued_cell.occupied_capacity(Capacity::zero())?;
+            if occupied_capacity > *capacity {
+                return Err(format!(
+                    "Insufficient capacity to create special issued cell for extra transaction \
+                     at index {}, occupied / capacity = {} / {}",
+                    index, occupied_capacity, capacity
+                )
+                .into());
+            }
+            outputs.push(special_issued_cell);
+            outputs_data.push(Bytes::new());
+        }
+
+        let issued_cells_outputs = self
+            .genesis
+            .issued_cells
+            .iter()
+            .map(IssuedCell::build_output)
+            .collect::<Result<Vec<_>, _>>()?;
+        outputs.extend(issued_cells_outputs);
         outputs_data.extend(self.genesis.issued_cells.iter().map(|_| Bytes::new()));
 
         let script: packed::Script = self.genesis.bootstrap_lock.clone().into();
@@ -810,52 +1059,78 @@ impl ChainSpec {
             .into_iter()
             .unzip();
 
-        let privkey = Privkey::from(SPECIAL_CELL_PRIVKEY.clone());
-        let lock_arg = secp_lock_arg(&privkey);
-        let input_out_point = cellbase_tx
-            .outputs()
-            .into_iter()
-            .position(|output| Unpack::<Bytes>::unpack(&output.lock().args()) == lock_arg)
-            .map(|index| packed::OutPoint::new(cellbase_tx.hash(), index as u32))
-            .expect("Get special issued input failed");
-        let input = packed::CellInput::new(input_out_point, 0);
-
-        let secp_data_out_point =
-            find_out_point_by_data_hash(cellbase_tx, &CODE_HASH_SECP256K1_DATA.pack())
-                .ok_or_else(|| String::from("Get secp data out point failed"))?;
-        let secp_blake160_out_point = find_out_point_by_data_hash(
-            cellbase_tx,
-            &CODE_HASH_SECP256K1_BLAKE160_SIGHASH_ALL.pack(),
-        )
-        .ok_or_else(|| String::from("Get secp blake160 out point failed"))?;
-        let cell_deps = vec![
-            packed::CellDep::new_builder()
-                .out_point(secp_data_out_point)
-                .build(),
-            packed::CellDep::new_builder()
-                .out_point(secp_blake160_out_point)
-                .build(),
-        ];
-        let tx = TransactionBuilder::default()
-            .cell_deps(cell_deps.clone())
-            .input(input.clone())
-            .outputs(outputs.clone())
-            .outputs_data(outputs_data.clone())
-            .build();
-
-        let tx_hash: H256 = tx.hash().unpack();
-        let message = H256::from(blake2b_256(&tx_hash));
-        let sig = privkey.sign_recoverable(&message).expect("sign");
-        let witness = Bytes::from(sig.serialize()).pack();
+        build_special_cell_spending_transaction(cellbase_tx, 0, outputs, outputs_data)
+    }
+}
 
-        Ok(TransactionBuilder::default()
-            .cell_deps(cell_deps)
-            .input(input)
-            .outputs(outputs)
-            .outputs_data(outputs_data)
-            .witness(witness)
-            .build())
+// Builds the genesis transaction spending the special issued cell at `special_cell_index` among
+// the special issued cells in the cellbase, signed by the special cell private key.
+fn build_special_cell_spending_transaction(
+    cellbase_tx: &TransactionView,
+    special_cell_index: usize,
+    outputs: Vec<packed::CellOutput>,
+    outputs_data: Vec<packed::Bytes>,
+) -> Result<TransactionView, Box<dyn Error>> {
+    fn find_out_point_by_data_hash(
+        tx: &TransactionView,
+        data_hash: &packed::Byte32,
+    ) -> Option<packed::OutPoint> {
+        tx.outputs_data()
+            .into_iter()
+            .position(|data| {
+                let hash = packed::CellOutput::calc_data_hash(&data.raw_data());
+                &hash == data_hash
+            })
+            .map(|index| packed::OutPoint::new(tx.hash(), index as u32))
     }
+
+    let privkey = Privkey::from(SPECIAL_CELL_PRIVKEY.clone());
+    let lock_arg = secp_lock_arg(&privkey);
+    let input_out_point = cellbase_tx
+        .outputs()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, output)| Unpack::<Bytes>::unpack(&output.lock().args()) == lock_arg)
+        .nth(special_cell_index)
+        .map(|(index, _)| packed::OutPoint::new(cellbase_tx.hash(), index as u32))
+        .expect("Get special issued input failed");
+    let input = packed::CellInput::new(input_out_point, 0);
+
+    let secp_data_out_point =
+        find_out_point_by_data_hash(cellbase_tx, &CODE_HASH_SECP256K1_DATA.pack())
+            .ok_or_else(|| String::from("Get secp data out point failed"))?;
+    let secp_blake160_out_point = find_out_point_by_data_hash(
+        cellbase_tx,
+        &CODE_HASH_SECP256K1_BLAKE160_SIGHASH_ALL.pack(),
+    )
+    .ok_or_else(|| String::from("Get secp blake160 out point failed"))?;
+    let cell_deps = vec![
+        packed::CellDep::new_builder()
+            .out_point(secp_data_out_point)
+            .build(),
+        packed::CellDep::new_builder()
+            .out_point(secp_blake160_out_point)
+            .build(),
+    ];
+    let tx = TransactionBuilder::default()
+        .cell_deps(cell_deps.clone())
+        .input(input.clone())
+        .outputs(outputs.clone())
+        .outputs_data(outputs_data.clone())
+        .build();
+
+    let tx_hash: H256 = tx.hash().unpack();
+    let message = H256::from(blake2b_256(&tx_hash));
+    let sig = privkey.sign_recoverable(&message).expect("sign");
+    let witness = Bytes::from(sig.serialize()).pack();
+
+    Ok(TransactionBuilder::default()
+        .cell_deps(cell_deps)
+        .input(input)
+        .outputs(outputs)
+        .outputs_data(outputs_data)
+        .witness(witness)
+        .build())
 }
 
 impl GenesisCell {
@@ -869,11 +1144,39 @@ impl GenesisCell {
 }
 
 impl IssuedCell {
-    fn build_output(&self) -> packed::CellOutput {
-        packed::CellOutput::new_builder()
-            .lock(self.lock.clone().into())
+    fn build_output(&self) -> Result<packed::CellOutput, Box<dyn Error>> {
+        let lock: packed::Script = self.lock.clone().into();
+        let lock = match self.since {
+            Some(since) => {
+                let multisig_type_hash =
+                    build_genesis_type_id_script(OUTPUT_INDEX_SECP256K1_BLAKE160_MULTISIG_ALL)
+                        .calc_script_hash();
+                let hash_type = ScriptHashType::try_from(lock.hash_type())?;
+                let is_multisig_lock = match hash_type {
+                    ScriptHashType::Type => lock.code_hash() == multisig_type_hash,
+                    ScriptHashType::Data | ScriptHashType::Data1 => {
+                        lock.code_hash() == CODE_HASH_SECP256K1_BLAKE160_MULTISIG_ALL.pack()
+                    }
+                };
+                let args = lock.args().raw_data();
+                if !is_multisig_lock || args.len() != 20 {
+                    return Err(format!(
+                        "Issued cell with since requires the SECP256K1/multisig-all lock \
+                         with 20 bytes args, lock: {}",
+                        lock
+                    )
+                    .into());
+                }
+                let mut args = args.to_vec();
+                args.extend_from_slice(&since.to_since()?.to_le_bytes());
+                lock.as_builder().args(Bytes::from(args).pack()).build()
+            }
+            None => lock,
+        };
+        Ok(packed::CellOutput::new_builder()
+            .lock(lock)
             .capacity(self.capacity.pack())
-            .build()
+            .build())
     }
 }
 
@@ -915,6 +1218,132 @@ impl SystemCell {
     }
 }
 
+impl GenesisTransaction {
+    fn build_outputs(
+        &self,
+    ) -> Result<(Vec<packed::CellOutput>, Vec<packed::Bytes>), Box<dyn Error>> {
+        self.outputs
+            .iter()
+            .enumerate()
+            .map(|(index, output)| {
+                output
+                    .build_output(index)
+                    .map(|(cell, data)| (cell, data.pack()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|outputs| outputs.into_iter().unzip())
+    }
+}
+
+impl GenesisOutput {
+    fn build_output(
+        &self,
+        output_index: usize,
+    ) -> Result<(packed::CellOutput, Bytes), Box<dyn Error>> {
+        let data = self.data.clone().into_bytes();
+        let type_script: Option<packed::Script> = self.type_.clone().map(Into::into);
+        let builder = packed::CellOutput::new_builder()
+            .type_(type_script.pack())
+            .lock(self.lock.clone().into());
+
+        let data_len = Capacity::bytes(data.len())?;
+        let cell = if let Some(capacity) = self.capacity {
+            let cell = builder.capacity(capacity.pack()).build();
+            let occupied_capacity = cell.occupied_capacity(data_len)?.as_u64();
+            if occupied_capacity > capacity {
+                return Err(format!(
+                    "Insufficient capacity to create genesis extra transaction output at index {}, \
+                     occupied / capacity = {} / {}",
+                    output_index, occupied_capacity, capacity
+                )
+                .into());
+            }
+            cell
+        } else {
+            builder.build_exact_capacity(data_len)?
+        };
+
+        Ok((cell, data))
+    }
+}
+
+// Loads the issued cells from the CSV file, sorted by the lock args.
+//
+// Each row is `lock_args,capacity`, where the lock args is the 20 bytes hex string of the
+// SECP256K1/blake160 sighash-all lock and the capacity is in shannons, which must cover the
+// occupied capacity of the cell. The header row and the lines starting with `#` are skipped.
+fn load_issued_cells_file(path: &Path) -> Result<Vec<IssuedCell>, Box<dyn Error>> {
+    let invalid = |line: u64, reason: String| {
+        SpecLoadError::invalid_issued_cells_file(format!("{}:{}: {}", path.display(), line, reason))
+    };
+    let code_hash: H256 = build_genesis_type_id_script(OUTPUT_INDEX_SECP256K1_BLAKE160_SIGHASH_ALL)
+        .calc_script_hash()
+        .unpack();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|err| SpecLoadError::invalid_issued_cells_file(err.to_string()))?;
+    let mut cells = BTreeMap::new();
+    for record in reader.records() {
+        let record = record.map_err(|err| {
+            let line = err.position().map(|pos| pos.line()).unwrap_or_default();
+            invalid(line, err.to_string())
+        })?;
+        let line = record.position().map(|pos| pos.line()).unwrap_or_default();
+        if record.len() != 2 {
+            return Err(invalid(
+                line,
+                format!("expect 2 columns, got {}", record.len()),
+            ));
+        }
+        let lock_args = H160::from_str(record[0].trim_start_matches("0x"))
+            .map_err(|err| invalid(line, format!("invalid lock args: {}", err)))?;
+        let capacity = record[1]
+            .parse()
+            .map(Capacity::shannons)
+            .map_err(|err| invalid(line, format!("invalid capacity: {}", err)))?;
+        let lock = packed::Script::new_builder()
+            .code_hash(code_hash.pack())
+            .hash_type(ScriptHashType::Type.into())
+            .args(Bytes::from(lock_args.as_bytes().to_owned()).pack())
+            .build();
+        let occupied_capacity = packed::CellOutput::new_builder()
+            .capacity(capacity.pack())
+            .lock(lock.clone())
+            .build()
+            .occupied_capacity(Capacity::zero())
+            .map_err(|err| invalid(line, format!("invalid capacity: {}", err)))?;
+        if occupied_capacity > capacity {
+            return Err(invalid(
+                line,
+                format!(
+                    "insufficient capacity, occupied / capacity = {} / {}",
+                    occupied_capacity, capacity
+                ),
+            ));
+        }
+        if cells.contains_key(&lock_args) {
+            return Err(invalid(
+                line,
+                format!("duplicate lock args {:#x}", lock_args),
+            ));
+        }
+        cells.insert(
+            lock_args,
+            IssuedCell {
+                capacity,
+                lock: lock.into(),
+                since: None,
+            },
+        );
+    }
+
+    Ok(cells.into_iter().map(|(_, cell)| cell).collect())
+}
+
 fn secp_lock_arg(privkey: &Privkey) -> Bytes {
     let pubkey_data = privkey.pubkey().expect("Get pubkey failed").serialize();
     Bytes::from((&blake2b_256(&pubkey_data)[0..20]).to_owned())
@@ -1168,6 +1597,73 @@ pub mod test {
         assert_eq!(params, expected);
     }
 
+    #[test]
+    fn test_params_preset() {
+        let test_params: &str = r#"
+            preset = "fast-dev"
+            cellbase_maturity = 4
+        "#;
+
+        let params: Params = toml::from_str(&test_params).unwrap();
+        let expected = Params {
+            preset: Some(ParamsPreset::FastDev),
+            cellbase_maturity: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(params, expected);
+
+        // the explicit parameters override the preset
+        assert_eq!(params.cellbase_maturity(), 4);
+        assert_eq!(params.genesis_epoch_length(), 100);
+        assert_eq!(
+            params.epoch_duration_target(),
+            default_params::epoch_duration_target() / 10
+        );
+        assert_eq!(params.tx_proposal_window(), ProposalWindow(1, 3));
+        assert!(params.permanent_difficulty_in_dummy());
+
+        // the epoch duration target follows the explicit genesis epoch length
+        let params: Params = toml::from_str(
+            r#"
+            preset = "fast-dev"
+            genesis_epoch_length = 50
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            params.epoch_duration_target(),
+            default_params::epoch_duration_target() / 20
+        );
+        let params: Params = toml::from_str(
+            r#"
+            preset = "fast-dev"
+            genesis_epoch_length = 50
+            epoch_duration_target = 100
+        "#,
+        )
+        .unwrap();
+        assert_eq!(params.epoch_duration_target(), 100);
+
+        let params: Params = toml::from_str(r#"preset = "stress""#).unwrap();
+        assert_eq!(
+            params.max_block_bytes(),
+            default_params::max_block_bytes() * 10
+        );
+        assert_eq!(
+            params.genesis_epoch_length(),
+            default_params::genesis_epoch_length()
+        );
+
+        let params: Params = toml::from_str(r#"preset = "mainnet-like""#).unwrap();
+        assert_eq!(
+            params.cellbase_maturity(),
+            default_params::cellbase_maturity()
+        );
+        assert_eq!(params.tx_proposal_window(), TX_PROPOSAL_WINDOW);
+
+        assert!(toml::from_str::<Params>(r#"preset = "unknown""#).is_err());
+    }
+
     #[test]
     fn test_params_skip_serializing_if_option_is_none() {
         let default = Params::default();
@@ -1209,4 +1705,286 @@ pub mod test {
 
         assert_eq!(genesis_epoch_ext, expected);
     }
+
+    fn write_dev_spec_with_issued_cells_file(dir: &Path, rows: &str) -> Resource {
+        let spec = Resource::bundled("specs/dev.toml".to_string())
+            .get()
+            .unwrap();
+        let spec = String::from_utf8(spec.into_owned()).unwrap().replacen(
+            "[genesis]\n",
+            "[genesis]\nissued_cells_file = \"allocations.csv\"\n",
+            1,
+        );
+        let spec_path = dir.join("dev.toml");
+        std::fs::write(&spec_path, spec).unwrap();
+        std::fs::write(
+            dir.join("allocations.csv"),
+            format!("lock_args,capacity\n{}", rows),
+        )
+        .unwrap();
+        Resource::file_system(spec_path)
+    }
+
+    #[test]
+    fn test_load_issued_cells_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundled = load_spec_by_name("ckb_dev");
+        let resource = write_dev_spec_with_issued_cells_file(
+            dir.path(),
+            "# comment\n\
+             0x2222222222222222222222222222222222222222,20000000000\n\
+             0x1111111111111111111111111111111111111111, 10000000000\n",
+        );
+        let spec = ChainSpec::load_from(&resource).expect("load spec");
+        assert_ne!(spec.hash, bundled.hash);
+
+        let issued_cells = &spec.genesis.issued_cells;
+        assert_eq!(issued_cells.len(), bundled.genesis.issued_cells.len() + 2);
+        let loaded = &issued_cells[bundled.genesis.issued_cells.len()..];
+        assert_eq!(loaded[0].capacity, capacity_bytes!(100));
+        assert_eq!(loaded[0].lock.args.as_bytes(), &[0x11; 20]);
+        assert_eq!(loaded[1].capacity, capacity_bytes!(200));
+        assert_eq!(loaded[1].lock.args.as_bytes(), &[0x22; 20]);
+        assert!(spec.build_genesis().is_ok());
+
+        // the merged cells are serialized inline, and the file is not merged again on reload
+        assert!(spec.genesis.issued_cells_file.is_none());
+        let reserialized_path = dir.path().join("reserialized.toml");
+        std::fs::write(&reserialized_path, toml::to_string(&spec).unwrap()).unwrap();
+        let reserialized =
+            ChainSpec::load_from(&Resource::file_system(reserialized_path)).expect("load spec");
+        assert_eq!(reserialized.genesis.issued_cells, spec.genesis.issued_cells);
+        assert_eq!(
+            reserialized.build_genesis().unwrap().hash(),
+            spec.build_genesis().unwrap().hash()
+        );
+
+        // the rows order does not change the spec hash
+        let reordered = write_dev_spec_with_issued_cells_file(
+            dir.path(),
+            "0x1111111111111111111111111111111111111111,10000000000\n\
+             0x2222222222222222222222222222222222222222,20000000000\n",
+        );
+        let reordered_spec = ChainSpec::load_from(&reordered).expect("load spec");
+        assert_eq!(reordered_spec.hash, spec.hash);
+    }
+
+    #[test]
+    fn test_load_invalid_issued_cells_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for (rows, reason) in &[
+            (
+                "0x1111111111111111111111111111111111111111,10000000000\n\
+                 0x1111111111111111111111111111111111111111,20000000000\n",
+                "allocations.csv:3: duplicate lock args",
+            ),
+            (
+                "0x1111111111111111111111111111111111111111,0\n",
+                "allocations.csv:2: insufficient capacity",
+            ),
+            (
+                "0x1111111111111111111111111111111111111111,6100000000\n\
+                 0x2222222222222222222222222222222222222222,6099999999\n",
+                "allocations.csv:3: insufficient capacity",
+            ),
+            ("0x11,10000000000\n", "allocations.csv:2: invalid lock args"),
+            (
+                "0x1111111111111111111111111111111111111111,1.5\n",
+                "allocations.csv:2: invalid capacity",
+            ),
+            (
+                "0x1111111111111111111111111111111111111111,10000000000\n\
+                 0x2222222222222222222222222222222222222222\n",
+                "allocations.csv:3:",
+            ),
+        ] {
+            let resource = write_dev_spec_with_issued_cells_file(dir.path(), rows);
+            let err = ChainSpec::load_from(&resource).unwrap_err().to_string();
+            assert!(err.contains("invalid issued_cells_file"), "{}", err);
+            assert!(err.contains(reason), "{}", err);
+        }
+    }
+
+    fn multisig_issued_cell(since: Option<IssuedCellSince>) -> IssuedCell {
+        IssuedCell {
+            capacity: capacity_bytes!(1_000_000),
+            lock: packed::Script::new_builder()
+                .code_hash(
+                    build_genesis_type_id_script(OUTPUT_INDEX_SECP256K1_BLAKE160_MULTISIG_ALL)
+                        .calc_script_hash(),
+                )
+                .hash_type(ScriptHashType::Type.into())
+                .args(Bytes::from(vec![0x11; 20]).pack())
+                .build()
+                .into(),
+            since,
+        }
+    }
+
+    #[test]
+    fn test_issued_cell_since() {
+        for (since, expected) in &[
+            (IssuedCellSince::BlockNumber(100), 0x0000_0000_0000_0064u64),
+            (IssuedCellSince::Epoch(180), 0x2000_0100_0000_00b4),
+            (
+                IssuedCellSince::Timestamp(1_600_000_000),
+                0x4000_0000_5f5e_1000,
+            ),
+        ] {
+            let mut spec = load_spec_by_name("ckb_dev");
+            spec.genesis
+                .issued_cells
+                .push(multisig_issued_cell(Some(*since)));
+            let genesis = spec.build_genesis().expect("build genesis");
+            let output = genesis.transactions()[0]
+                .outputs()
+                .into_iter()
+                .last()
+                .unwrap();
+            let args = output.lock().args().raw_data();
+            assert_eq!(args.len(), 28);
+            assert_eq!(&args[..20], &[0x11; 20][..]);
+            assert_eq!(&args[20..], &expected.to_le_bytes()[..]);
+        }
+
+        // the args are unchanged without since
+        let output = multisig_issued_cell(None).build_output().unwrap();
+        assert_eq!(output.lock().args().raw_data().len(), 20);
+    }
+
+    #[test]
+    fn test_invalid_issued_cell_since() {
+        // the since requires the multisig lock
+        let mut cell = multisig_issued_cell(Some(IssuedCellSince::Epoch(180)));
+        let sighash_lock: packed::Script = cell.lock.clone().into();
+        cell.lock = sighash_lock
+            .as_builder()
+            .code_hash(
+                build_genesis_type_id_script(OUTPUT_INDEX_SECP256K1_BLAKE160_SIGHASH_ALL)
+                    .calc_script_hash(),
+            )
+            .build()
+            .into();
+        assert!(cell.build_output().is_err());
+
+        // the since requires the 20 bytes args
+        let mut cell = multisig_issued_cell(Some(IssuedCellSince::Epoch(180)));
+        let lock: packed::Script = cell.lock.clone().into();
+        cell.lock = lock
+            .as_builder()
+            .args(Bytes::from(vec![0x11; 28]).pack())
+            .build()
+            .into();
+        assert!(cell.build_output().is_err());
+
+        // the value overflows the since
+        let cell = multisig_issued_cell(Some(IssuedCellSince::Timestamp(1 << 56)));
+        assert!(cell.build_output().is_err());
+        let cell = multisig_issued_cell(Some(IssuedCellSince::Epoch(1 << 24)));
+        assert!(cell.build_output().is_err());
+    }
+
+    fn sighash_genesis_output(data: &[u8]) -> GenesisOutput {
+        GenesisOutput {
+            capacity: None,
+            data: JsonBytes::from_vec(data.to_vec()),
+            lock: packed::Script::new_builder()
+                .code_hash(
+                    build_genesis_type_id_script(OUTPUT_INDEX_SECP256K1_BLAKE160_SIGHASH_ALL)
+                        .calc_script_hash(),
+                )
+                .hash_type(ScriptHashType::Type.into())
+                .args(Bytes::from(vec![0x22; 20]).pack())
+                .build()
+                .into(),
+            type_: None,
+        }
+    }
+
+    #[test]
+    fn test_genesis_extra_transactions_and_extension() {
+        let mut spec = load_spec_by_name("ckb_dev");
+        spec.genesis.extra_transactions = vec![
+            GenesisTransaction {
+                outputs: vec![sighash_genesis_output(&[1, 2, 3])],
+            },
+            GenesisTransaction {
+                outputs: vec![sighash_genesis_output(&[]), sighash_genesis_output(&[4])],
+            },
+        ];
+        spec.genesis.extension = Some(JsonBytes::from_vec(vec![0xab; 32]));
+        let genesis = spec.build_genesis().expect("build genesis");
+
+        let txs = genesis.transactions();
+        assert_eq!(txs.len(), 4);
+        assert_eq!(
+            genesis.extension().map(|extension| extension.raw_data()),
+            Some(Bytes::from(vec![0xab; 32]))
+        );
+        assert_eq!(genesis.calc_extra_hash().extra_hash(), genesis.extra_hash());
+
+        // each extra transaction spends its own special issued cell, which has the exact capacity
+        let dep_group_input = txs[1].inputs().get(0).unwrap().previous_output();
+        for (tx, outputs_count) in txs[2..].iter().zip(&[1, 2]) {
+            assert_eq!(tx.outputs().len(), *outputs_count);
+            let input = tx.inputs().get(0).unwrap().previous_output();
+            assert_eq!(input.tx_hash(), txs[0].hash());
+            assert_ne!(input, dep_group_input);
+            let special_cell = txs[0]
+                .outputs()
+                .get(Unpack::<u32>::unpack(&input.index()) as usize)
+                .unwrap();
+            assert_eq!(
+                Unpack::<Capacity>::unpack(&special_cell.capacity()),
+                tx.outputs_capacity().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_genesis_extension() {
+        let mut spec = load_spec_by_name("ckb_dev");
+        spec.genesis.extension = Some(JsonBytes::from_vec(vec![0xab; 97]));
+        assert!(spec.build_genesis().is_err());
+        spec.genesis.extension = Some(JsonBytes::default());
+        assert!(spec.build_genesis().is_err());
+
+        // the extension requires rfc_0224 at epoch 0
+        spec.genesis.extension = Some(JsonBytes::from_vec(vec![0xab; 32]));
+        spec.params.hardfork = None;
+        assert!(spec.build_genesis().is_err());
+    }
+
+    #[test]
+    fn test_invalid_genesis_extra_transaction_lock() {
+        let mut spec = load_spec_by_name("ckb_dev");
+        let mut output = sighash_genesis_output(&[]);
+        let lock: packed::Script = output.lock.clone().into();
+        output.lock = lock
+            .as_builder()
+            .code_hash(h256!("0x1").pack())
+            .hash_type(ScriptHashType::Data.into())
+            .build()
+            .into();
+        spec.genesis.extra_transactions = vec![GenesisTransaction {
+            outputs: vec![output],
+        }];
+        assert!(spec.build_genesis().is_err());
+    }
+
+    #[test]
+    fn test_issued_cell_since_serde() {
+        let cell: IssuedCell = toml::from_str(
+            r#"
+            capacity = 100
+            since = { epoch = 180 }
+            [lock]
+            code_hash = "0x0000000000000000000000000000000000000000000000000000000000000000"
+            args = "0x"
+            hash_type = "type"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cell.since, Some(IssuedCellSince::Epoch(180)));
+    }
 }
@@ -0,0 +1,88 @@
+//! Named parameter presets for the dev chains.
+
+use crate::consensus::{
+    ProposalWindow, CELLBASE_MATURITY, GENESIS_EPOCH_LENGTH, MAX_BLOCK_BYTES, MAX_BLOCK_CYCLES,
+    MAX_BLOCK_PROPOSALS_LIMIT, TX_PROPOSAL_WINDOW,
+};
+use ckb_types::core::{BlockNumber, Cycle};
+use serde::{Deserialize, Serialize};
+
+// The scale of the block limits in the stress preset.
+const STRESS_BLOCK_LIMITS_SCALE: u64 = 10;
+
+/// A named set of coherent parameters.
+///
+/// The preset fills the parameters which are not set explicitly in `[params]`, so a spec can
+/// still override any single parameter. The epoch duration target is scaled with the genesis
+/// epoch length to keep the target block interval, and the proposal window always fits in the
+/// first epoch.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ParamsPreset {
+    /// Short epochs, a narrow proposal window and immediately mature cellbases, for the local
+    /// development and the integration tests.
+    FastDev,
+    /// The same parameters as the mainnet, for rehearsing the behaviors depending on the epoch
+    /// and the cellbase maturity.
+    MainnetLike,
+    /// The mainnet epochs with 10 times of the block limits, for the load tests.
+    Stress,
+}
+
+impl ParamsPreset {
+    /// The genesis_epoch_length of the preset.
+    pub fn genesis_epoch_length(self) -> BlockNumber {
+        match self {
+            ParamsPreset::FastDev => GENESIS_EPOCH_LENGTH / 10,
+            ParamsPreset::MainnetLike | ParamsPreset::Stress => GENESIS_EPOCH_LENGTH,
+        }
+    }
+
+    /// The tx_proposal_window of the preset.
+    pub fn tx_proposal_window(self) -> ProposalWindow {
+        match self {
+            ParamsPreset::FastDev => ProposalWindow(1, 3),
+            ParamsPreset::MainnetLike | ParamsPreset::Stress => TX_PROPOSAL_WINDOW,
+        }
+    }
+
+    /// The cellbase_maturity of the preset, in the full value of `EpochNumberWithFraction`.
+    pub fn cellbase_maturity(self) -> u64 {
+        match self {
+            ParamsPreset::FastDev | ParamsPreset::Stress => 0,
+            ParamsPreset::MainnetLike => CELLBASE_MATURITY.full_value(),
+        }
+    }
+
+    /// The max_block_cycles of the preset.
+    pub fn max_block_cycles(self) -> Cycle {
+        match self {
+            ParamsPreset::FastDev | ParamsPreset::MainnetLike => MAX_BLOCK_CYCLES,
+            ParamsPreset::Stress => MAX_BLOCK_CYCLES * STRESS_BLOCK_LIMITS_SCALE,
+        }
+    }
+
+    /// The max_block_bytes of the preset.
+    pub fn max_block_bytes(self) -> u64 {
+        match self {
+            ParamsPreset::FastDev | ParamsPreset::MainnetLike => MAX_BLOCK_BYTES,
+            ParamsPreset::Stress => MAX_BLOCK_BYTES * STRESS_BLOCK_LIMITS_SCALE,
+        }
+    }
+
+    /// The max_block_proposals_limit of the preset.
+    pub fn max_block_proposals_limit(self) -> u64 {
+        match self {
+            ParamsPreset::FastDev | ParamsPreset::MainnetLike => MAX_BLOCK_PROPOSALS_LIMIT,
+            ParamsPreset::Stress => MAX_BLOCK_PROPOSALS_LIMIT * STRESS_BLOCK_LIMITS_SCALE,
+        }
+    }
+
+    /// The permanent_difficulty_in_dummy of the preset.
+    pub fn permanent_difficulty_in_dummy(self) -> bool {
+        match self {
+            ParamsPreset::FastDev | ParamsPreset::Stress => true,
+            ParamsPreset::MainnetLike => false,
+        }
+    }
+}
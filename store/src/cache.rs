@@ -1,4 +1,6 @@
 use ckb_app_config::StoreConfig;
+use ckb_db_schema::Col;
+use ckb_metrics::metrics;
 use ckb_types::{
     bytes::Bytes,
     core::{HeaderView, UncleBlockVecView},
@@ -23,6 +25,8 @@ pub struct StoreCache {
     pub block_uncles: Mutex<LruCache<Byte32, UncleBlockVecView>>,
     /// The cache of block extension sections.
     pub block_extensions: Mutex<LruCache<Byte32, Option<packed::Bytes>>>,
+    /// Whether to report the cache lookups and the snapshot reads to the metrics.
+    pub(crate) read_stats: bool,
 }
 
 impl Default for StoreCache {
@@ -42,6 +46,60 @@ impl StoreCache {
             block_tx_hashes: Mutex::new(LruCache::new(config.block_tx_hashes_cache_size)),
             block_uncles: Mutex::new(LruCache::new(config.block_uncles_cache_size)),
             block_extensions: Mutex::new(LruCache::new(config.block_extensions_cache_size)),
+            read_stats: config.read_stats,
+        }
+    }
+
+    /// Changes the capacities of the caches, the least recently used entries are dropped when a
+    /// cache shrinks.
+    pub fn resize(&self, config: &StoreConfig) {
+        self.headers.lock().resize(config.header_cache_size);
+        self.cell_data.lock().resize(config.cell_data_cache_size);
+        self.cell_data_hash
+            .lock()
+            .resize(config.cell_data_cache_size);
+        self.block_proposals
+            .lock()
+            .resize(config.block_proposals_cache_size);
+        self.block_tx_hashes
+            .lock()
+            .resize(config.block_tx_hashes_cache_size);
+        self.block_uncles
+            .lock()
+            .resize(config.block_uncles_cache_size);
+        self.block_extensions
+            .lock()
+            .resize(config.block_extensions_cache_size);
+    }
+
+    // Reports a lookup of the named cache when the read statistics are enabled.
+    pub(crate) fn record_lookup(&self, cache: &'static str, hit: bool) {
+        if self.read_stats {
+            let result = if hit { "hit" } else { "miss" };
+            metrics!(counter, "ckb.store.cache_lookups", 1, "cache" => cache, "result" => result);
+        }
+    }
+
+    // Reports a point read of the column via a snapshot, `value_len` is `None` when the key is
+    // not found.
+    pub(crate) fn record_read(&self, col: Col, value_len: Option<usize>) {
+        if self.read_stats {
+            let result = if value_len.is_some() {
+                "found"
+            } else {
+                "not_found"
+            };
+            metrics!(counter, "ckb.store.snapshot_reads", 1, "column" => col, "result" => result);
+            if let Some(len) = value_len {
+                metrics!(counter, "ckb.store.snapshot_read_bytes", len as u64, "column" => col);
+            }
+        }
+    }
+
+    // Reports an iteration of the column via a snapshot.
+    pub(crate) fn record_iter(&self, col: Col) {
+        if self.read_stats {
+            metrics!(counter, "ckb.store.snapshot_iters", 1, "column" => col);
         }
     }
 }
@@ -0,0 +1,91 @@
+use ckb_db_schema::Col;
+use ckb_logger::warn;
+use ckb_metrics::metrics;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// The writes of a column in a transaction.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnWriteStats {
+    /// The number of the inserted keys
+    pub puts: usize,
+    /// The number of the deleted keys
+    pub deletes: usize,
+    /// The total size of the inserted keys and values, and the deleted keys in bytes
+    pub bytes: usize,
+}
+
+/// The writes of a transaction, grouped by the column.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct CommitStats {
+    columns: BTreeMap<Col, ColumnWriteStats>,
+}
+
+impl CommitStats {
+    pub(crate) fn record_put(&mut self, col: Col, key: &[u8], value: &[u8]) {
+        let stats = self.columns.entry(col).or_default();
+        stats.puts += 1;
+        stats.bytes += key.len() + value.len();
+    }
+
+    pub(crate) fn record_delete(&mut self, col: Col, key: &[u8]) {
+        let stats = self.columns.entry(col).or_default();
+        stats.deletes += 1;
+        stats.bytes += key.len();
+    }
+
+    /// Returns the writes of the column.
+    pub fn column(&self, col: Col) -> ColumnWriteStats {
+        self.columns.get(col).copied().unwrap_or_default()
+    }
+
+    /// Returns the total number of the written keys.
+    pub fn keys(&self) -> usize {
+        self.columns
+            .values()
+            .map(|stats| stats.puts + stats.deletes)
+            .sum()
+    }
+
+    /// Returns the total size of the writes in bytes.
+    pub fn bytes(&self) -> usize {
+        self.columns.values().map(|stats| stats.bytes).sum()
+    }
+
+    // Reports the metrics of the commit, and logs the breakdown when it is slower than the
+    // threshold. The elapsed time includes syncing the WAL to the disk.
+    pub(crate) fn report(&self, elapsed: Duration, slow_threshold: Option<Duration>) {
+        metrics!(value, "ckb.store.commit_keys", self.keys() as u64);
+        metrics!(value, "ckb.store.commit_bytes", self.bytes() as u64);
+        metrics!(timing, "ckb.store.commit", elapsed);
+        if let Some(threshold) = slow_threshold {
+            if elapsed >= threshold {
+                warn!(
+                    "slow store commit: {}ms, keys: {}, bytes: {}, columns: {}",
+                    elapsed.as_millis(),
+                    self.keys(),
+                    self.bytes(),
+                    self
+                );
+            }
+        }
+    }
+}
+
+impl fmt::Display for CommitStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, (col, stats)) in self.columns.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{}: {{puts: {}, deletes: {}, bytes: {}}}",
+                col, stats.puts, stats.deletes, stats.bytes
+            )?;
+        }
+        write!(f, "]")
+    }
+}
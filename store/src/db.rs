@@ -1,5 +1,7 @@
 use crate::cache::StoreCache;
 use crate::cell::attach_block_cell;
+use crate::commit_stats::CommitStats;
+use crate::header_checkpoint::HeaderCheckpoint;
 use crate::store::ChainStore;
 use crate::transaction::StoreTransaction;
 use crate::write_batch::StoreWriteBatch;
@@ -7,14 +9,24 @@ use crate::StoreSnapshot;
 use ckb_app_config::StoreConfig;
 use ckb_chain_spec::consensus::Consensus;
 use ckb_db::{
-    iter::{DBIter, DBIterator, IteratorMode},
+    iter::{DBIter, DBIterator, Direction, IteratorMode},
     DBPinnableSlice, RocksDB,
 };
-use ckb_db_schema::{Col, CHAIN_SPEC_HASH_KEY, MIGRATION_VERSION_KEY};
+use ckb_db_schema::{
+    Col, CHAIN_SPEC_HASH_KEY, COLUMNS, COLUMN_META, META_HEADER_CHECKPOINT_KEY,
+    META_INVALID_HEADERS_VERSION_KEY, META_INVALID_HEADER_PREFIX, META_VERIFIED_HEADER_PREFIX,
+    MIGRATION_VERSION_KEY,
+};
 use ckb_error::Error;
 use ckb_freezer::Freezer;
-use ckb_types::{core::BlockExt, packed, prelude::*};
+use ckb_types::{
+    core::{BlockExt, BlockNumber},
+    packed,
+    prelude::*,
+};
+use ckb_util::Mutex;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// TODO(doc): @quake
 #[derive(Clone)]
@@ -22,6 +34,7 @@ pub struct ChainDB {
     db: RocksDB,
     freezer: Option<Freezer>,
     cache: Arc<StoreCache>,
+    slow_commit_threshold: Option<Duration>,
 }
 
 impl<'a> ChainStore<'a> for ChainDB {
@@ -54,6 +67,7 @@ impl ChainDB {
             db,
             freezer: None,
             cache: Arc::new(cache),
+            slow_commit_threshold: slow_commit_threshold(&config),
         }
     }
 
@@ -64,6 +78,7 @@ impl ChainDB {
             db,
             freezer: Some(freezer),
             cache: Arc::new(cache),
+            slow_commit_threshold: slow_commit_threshold(&config),
         }
     }
 
@@ -72,6 +87,15 @@ impl ChainDB {
         &self.db
     }
 
+    /// Flushes all the columns to the SST files, see [`RocksDB::flush`]
+    ///
+    /// [`RocksDB::flush`]: ../ckb_db/struct.RocksDB.html#method.flush
+    pub fn flush(&self) -> Result<(), Error> {
+        let cols: Vec<String> = (0..COLUMNS).map(|col| col.to_string()).collect();
+        let cols: Vec<&str> = cols.iter().map(String::as_str).collect();
+        self.db.flush(&cols)
+    }
+
     /// TODO(doc): @quake
     pub fn into_inner(self) -> RocksDB {
         self.db
@@ -97,12 +121,139 @@ impl ChainDB {
             .expect("db operation should be ok")
     }
 
+    /// Return the header checkpoint recorded by the header sync
+    pub fn get_header_checkpoint(&self) -> Option<HeaderCheckpoint> {
+        self.get(COLUMN_META, META_HEADER_CHECKPOINT_KEY)
+            .and_then(|raw| HeaderCheckpoint::from_slice(raw.as_ref()))
+    }
+
+    /// Store the header checkpoint and the verified headers, which are keyed by the numbers
+    ///
+    /// A verified header replaces the one recorded at the same number. When they differ, the
+    /// recorded headers above it are on another branch and they are removed as well.
+    pub fn insert_header_checkpoint(
+        &self,
+        checkpoint: &HeaderCheckpoint,
+        verified_headers: &[(BlockNumber, packed::Byte32)],
+    ) -> Result<(), Error> {
+        let mut batch = self.new_write_batch();
+        let fork_point = verified_headers.iter().find(|(number, hash)| {
+            self.get(COLUMN_META, &verified_header_key(*number))
+                .map_or(false, |raw| raw.as_ref() != hash.as_slice())
+        });
+        if let Some((number, _)) = fork_point {
+            for (key, _) in self
+                .get_iter(
+                    COLUMN_META,
+                    IteratorMode::From(&verified_header_key(*number), Direction::Forward),
+                )
+                .take_while(|(key, _)| key.starts_with(META_VERIFIED_HEADER_PREFIX))
+            {
+                batch.delete(COLUMN_META, &key)?;
+            }
+        }
+        for (number, hash) in verified_headers {
+            batch.put(COLUMN_META, &verified_header_key(*number), hash.as_slice())?;
+        }
+        batch.put(
+            COLUMN_META,
+            META_HEADER_CHECKPOINT_KEY,
+            &checkpoint.to_vec(),
+        )?;
+        self.write(&batch)
+    }
+
+    /// Return whether the header has been verified by the header sync
+    pub fn is_header_verified(&self, number: BlockNumber, hash: &packed::Byte32) -> bool {
+        self.get(COLUMN_META, &verified_header_key(number))
+            .map_or(false, |raw| raw.as_ref() == hash.as_slice())
+    }
+
+    /// Remove the header checkpoint and the hashes of the verified headers
+    pub fn clear_header_checkpoint(&self) -> Result<(), Error> {
+        let mut batch = self.new_write_batch();
+        for (key, _) in self
+            .get_iter(
+                COLUMN_META,
+                IteratorMode::From(META_VERIFIED_HEADER_PREFIX, Direction::Forward),
+            )
+            .take_while(|(key, _)| key.starts_with(META_VERIFIED_HEADER_PREFIX))
+        {
+            batch.delete(COLUMN_META, &key)?;
+        }
+        batch.delete(COLUMN_META, META_HEADER_CHECKPOINT_KEY)?;
+        self.write(&batch)
+    }
+
+    /// Mark the header as the root of an invalid header branch
+    ///
+    /// The markers survive the restart, the caller bounds their count and clears them when the
+    /// client version changes.
+    pub fn insert_invalid_header(&self, hash: &packed::Byte32) -> Result<(), Error> {
+        let mut batch = self.new_write_batch();
+        batch.put(COLUMN_META, &invalid_header_key(hash), &[])?;
+        self.write(&batch)
+    }
+
+    /// Remove the invalid header marker
+    pub fn delete_invalid_header(&self, hash: &packed::Byte32) -> Result<(), Error> {
+        let mut batch = self.new_write_batch();
+        batch.delete(COLUMN_META, &invalid_header_key(hash))?;
+        self.write(&batch)
+    }
+
+    /// Return whether the header is the root of an invalid header branch
+    pub fn is_header_invalid(&self, hash: &packed::Byte32) -> bool {
+        self.get(COLUMN_META, &invalid_header_key(hash)).is_some()
+    }
+
+    /// Return the hashes of all the invalid header markers
+    pub fn get_invalid_headers(&self) -> Vec<packed::Byte32> {
+        self.get_iter(
+            COLUMN_META,
+            IteratorMode::From(META_INVALID_HEADER_PREFIX, Direction::Forward),
+        )
+        .take_while(|(key, _)| key.starts_with(META_INVALID_HEADER_PREFIX))
+        .filter_map(|(key, _)| {
+            packed::Byte32::from_slice(&key[META_INVALID_HEADER_PREFIX.len()..]).ok()
+        })
+        .collect()
+    }
+
+    /// Return the client version which recorded the invalid header markers
+    pub fn get_invalid_headers_version(&self) -> Option<String> {
+        self.get(COLUMN_META, META_INVALID_HEADERS_VERSION_KEY)
+            .map(|raw| String::from_utf8_lossy(raw.as_ref()).into_owned())
+    }
+
+    /// Remove all the invalid header markers, the new markers are recorded by the client `version`
+    pub fn clear_invalid_headers(&self, version: &str) -> Result<(), Error> {
+        let mut batch = self.new_write_batch();
+        for (key, _) in self
+            .get_iter(
+                COLUMN_META,
+                IteratorMode::From(META_INVALID_HEADER_PREFIX, Direction::Forward),
+            )
+            .take_while(|(key, _)| key.starts_with(META_INVALID_HEADER_PREFIX))
+        {
+            batch.delete(COLUMN_META, &key)?;
+        }
+        batch.put(
+            COLUMN_META,
+            META_INVALID_HEADERS_VERSION_KEY,
+            version.as_bytes(),
+        )?;
+        self.write(&batch)
+    }
+
     /// TODO(doc): @quake
     pub fn begin_transaction(&self) -> StoreTransaction {
         StoreTransaction {
             inner: self.db.transaction(),
             freezer: self.freezer.clone(),
             cache: Arc::clone(&self.cache),
+            stats: Mutex::new(CommitStats::default()),
+            slow_commit_threshold: self.slow_commit_threshold,
         }
     }
 
@@ -175,12 +326,40 @@ impl ChainDB {
     }
 }
 
+fn slow_commit_threshold(config: &StoreConfig) -> Option<Duration> {
+    if config.slow_commit_threshold_ms > 0 {
+        Some(Duration::from_millis(config.slow_commit_threshold_ms))
+    } else {
+        None
+    }
+}
+
+// The number is big endian so the keys are sorted by the number
+fn verified_header_key(number: BlockNumber) -> Vec<u8> {
+    let mut key = Vec::with_capacity(META_VERIFIED_HEADER_PREFIX.len() + 8);
+    key.extend_from_slice(META_VERIFIED_HEADER_PREFIX);
+    key.extend_from_slice(&number.to_be_bytes());
+    key
+}
+
+fn invalid_header_key(hash: &packed::Byte32) -> Vec<u8> {
+    let mut key = Vec::with_capacity(META_INVALID_HEADER_PREFIX.len() + 32);
+    key.extend_from_slice(META_INVALID_HEADER_PREFIX);
+    key.extend_from_slice(hash.as_slice());
+    key
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ColumnWriteStats;
     use ckb_chain_spec::consensus::ConsensusBuilder;
     use ckb_db::RocksDB;
     use ckb_db_schema::COLUMNS;
+    use ckb_types::{
+        bytes::Bytes,
+        core::{BlockBuilder, TransactionBuilder, TransactionView},
+    };
 
     fn setup_db(columns: u32) -> RocksDB {
         RocksDB::open_tmp(columns)
@@ -221,6 +400,55 @@ mod tests {
         assert_eq!(block, store.get_block(&hash).unwrap());
     }
 
+    #[test]
+    fn save_and_get_blocks() {
+        let db = setup_db(COLUMNS);
+        let store = ChainDB::new(db, Default::default());
+        // the blocks with the adjacent bodies are read by the same iterator
+        let blocks: Vec<_> = (0..3u64)
+            .map(|i| {
+                packed::Block::new_builder()
+                    .header(
+                        packed::Header::new_builder()
+                            .raw(packed::RawHeader::new_builder().number(i.pack()).build())
+                            .build(),
+                    )
+                    .transactions(
+                        (0..=i)
+                            .map(|j| {
+                                packed::Transaction::new_builder()
+                                    .raw(
+                                        packed::RawTransaction::new_builder()
+                                            .version((j as u32).pack())
+                                            .build(),
+                                    )
+                                    .build()
+                            })
+                            .collect::<Vec<_>>()
+                            .pack(),
+                    )
+                    .build()
+                    .into_view()
+            })
+            .collect();
+
+        let txn = store.begin_transaction();
+        for block in &blocks {
+            txn.insert_block(block).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let mut hashes: Vec<_> = blocks.iter().rev().map(|block| block.hash()).collect();
+        hashes.insert(1, packed::Byte32::zero());
+        let expected = vec![
+            Some(blocks[2].clone()),
+            None,
+            Some(blocks[1].clone()),
+            Some(blocks[0].clone()),
+        ];
+        assert_eq!(store.get_blocks(&hashes), expected);
+    }
+
     #[test]
     fn save_and_get_block_ext() {
         let db = setup_db(COLUMNS);
@@ -243,6 +471,63 @@ mod tests {
         assert_eq!(ext, store.get_block_ext(&hash).unwrap());
     }
 
+    #[test]
+    fn live_cells_iter() {
+        let db = setup_db(COLUMNS);
+        let store = ChainDB::new(db, Default::default());
+        let build_tx = |version: u32, locks: Vec<(u8, Vec<u8>)>| {
+            let outputs: Vec<_> = locks
+                .into_iter()
+                .map(|(code_hash, args)| {
+                    let lock = packed::Script::new_builder()
+                        .code_hash(packed::Byte32::new([code_hash; 32]))
+                        .args(Bytes::from(args).pack())
+                        .build();
+                    packed::CellOutput::new_builder().lock(lock).build()
+                })
+                .collect();
+            TransactionBuilder::default()
+                .version(version.pack())
+                .outputs_data(vec![Bytes::new().pack(); outputs.len()])
+                .outputs(outputs)
+                .build()
+        };
+        let attach = |tx: &TransactionView| {
+            let block = BlockBuilder::default().transaction(tx.clone()).build();
+            let txn = store.begin_transaction();
+            attach_block_cell(&txn, &block).unwrap();
+            txn.commit().unwrap();
+        };
+
+        let tx = build_tx(0, vec![(1, vec![1, 2]), (1, vec![3]), (2, vec![1, 2])]);
+        attach(&tx);
+        let snapshot = store.get_snapshot();
+        // The cells attached after the snapshot is taken are invisible.
+        attach(&build_tx(1, vec![(1, vec![1, 2])]));
+
+        let out_points = |lock_prefix: &[u8], cursor: Option<&packed::OutPoint>| {
+            snapshot
+                .live_cells_iter(lock_prefix, cursor)
+                .map(|cell| cell.out_point)
+                .collect::<Vec<_>>()
+        };
+        let out_point = |index: u32| packed::OutPoint::new(tx.hash(), index);
+
+        assert_eq!(
+            out_points(&[], None),
+            vec![out_point(0), out_point(1), out_point(2)]
+        );
+        // code_hash, hash_type "data" and the args prefix
+        let mut prefix = vec![1u8; 32];
+        prefix.extend_from_slice(&[0, 1]);
+        assert_eq!(out_points(&prefix, None), vec![out_point(0)]);
+        assert_eq!(
+            out_points(&[1u8; 32], Some(&out_point(0))),
+            vec![out_point(1)]
+        );
+        assert!(out_points(&[], Some(&out_point(2))).is_empty());
+    }
+
     #[test]
     fn index_store() {
         let db = RocksDB::open_tmp(COLUMNS);
@@ -262,4 +547,110 @@ mod tests {
 
         assert_eq!(block.header(), store.get_tip_header().unwrap());
     }
+
+    #[test]
+    fn header_checkpoint() {
+        let db = RocksDB::open_tmp(COLUMNS);
+        let store = ChainDB::new(db, Default::default());
+        assert!(store.get_header_checkpoint().is_none());
+
+        let verified = vec![
+            (100, packed::Byte32::new([1; 32])),
+            (200, packed::Byte32::new([2; 32])),
+            (300, packed::Byte32::new([3; 32])),
+        ];
+        let checkpoint = HeaderCheckpoint {
+            spec_hash: packed::Byte32::new([3; 32]),
+            assume_valid_target: None,
+            number: 300,
+            hash: verified[2].1.clone(),
+            total_difficulty: 1000u64.into(),
+        };
+        store
+            .insert_header_checkpoint(&checkpoint, &verified)
+            .unwrap();
+        assert_eq!(store.get_header_checkpoint(), Some(checkpoint.clone()));
+        assert!(checkpoint.matches(&packed::Byte32::new([3; 32]), None));
+        assert!(!checkpoint.matches(
+            &packed::Byte32::new([3; 32]),
+            Some(&packed::Byte32::new([4; 32]))
+        ));
+        assert!(verified
+            .iter()
+            .all(|(number, hash)| store.is_header_verified(*number, hash)));
+        assert!(!store.is_header_verified(100, &packed::Byte32::new([4; 32])));
+        assert!(!store.is_header_verified(400, &packed::Byte32::new([4; 32])));
+
+        // Another branch forks before the block 200
+        let branch = vec![(200, packed::Byte32::new([5; 32]))];
+        store
+            .insert_header_checkpoint(&checkpoint, &branch)
+            .unwrap();
+        assert!(store.is_header_verified(100, &verified[0].1));
+        assert!(store.is_header_verified(200, &branch[0].1));
+        assert!(!store.is_header_verified(200, &verified[1].1));
+        assert!(!store.is_header_verified(300, &verified[2].1));
+
+        store.clear_header_checkpoint().unwrap();
+        assert!(store.get_header_checkpoint().is_none());
+        assert!(!store.is_header_verified(100, &verified[0].1));
+        assert!(!store.is_header_verified(200, &branch[0].1));
+    }
+
+    #[test]
+    fn invalid_header() {
+        let db = RocksDB::open_tmp(COLUMNS);
+        let store = ChainDB::new(db, Default::default());
+        let hash = packed::Byte32::new([1; 32]);
+        assert!(!store.is_header_invalid(&hash));
+
+        store.insert_invalid_header(&hash).unwrap();
+        assert!(store.is_header_invalid(&hash));
+        assert!(!store.is_header_verified(100, &hash));
+
+        // the markers are kept when the header checkpoint is cleared
+        store.clear_header_checkpoint().unwrap();
+        assert!(store.is_header_invalid(&hash));
+
+        let other = packed::Byte32::new([2; 32]);
+        store.insert_invalid_header(&other).unwrap();
+        assert_eq!(
+            store.get_invalid_headers(),
+            vec![hash.clone(), other.clone()]
+        );
+        store.delete_invalid_header(&other).unwrap();
+        assert_eq!(store.get_invalid_headers(), vec![hash.clone()]);
+
+        assert_eq!(store.get_invalid_headers_version(), None);
+        store.clear_invalid_headers("0.100.0").unwrap();
+        assert!(!store.is_header_invalid(&hash));
+        assert!(store.get_invalid_headers().is_empty());
+        assert_eq!(
+            store.get_invalid_headers_version(),
+            Some("0.100.0".to_owned())
+        );
+    }
+
+    #[test]
+    fn commit_stats() {
+        let db = RocksDB::open_tmp(COLUMNS);
+        let store = ChainDB::new(db, Default::default());
+        let txn = store.begin_transaction();
+        txn.insert_raw(COLUMN_META, b"key1", b"value").unwrap();
+        txn.insert_raw(COLUMN_META, b"key2", b"value").unwrap();
+        txn.delete(COLUMN_META, b"key3").unwrap();
+        txn.commit().unwrap();
+
+        let stats = txn.commit_stats();
+        assert_eq!(
+            stats.column(COLUMN_META),
+            ColumnWriteStats {
+                puts: 2,
+                deletes: 1,
+                bytes: 22,
+            }
+        );
+        assert_eq!(stats.keys(), 3);
+        assert_eq!(stats.bytes(), 22);
+    }
 }
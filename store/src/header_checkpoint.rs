@@ -0,0 +1,65 @@
+use ckb_types::{core::BlockNumber, packed, prelude::*, U256};
+
+const CHECKPOINT_SIZE: usize = 32 + 32 + 8 + 32 + 32;
+
+/// The best header verified by the header sync.
+///
+/// The checkpoint is only valid for the chain spec and the assume-valid target it is recorded
+/// with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeaderCheckpoint {
+    /// The hash of the chain spec
+    pub spec_hash: packed::Byte32,
+    /// The assume-valid target
+    pub assume_valid_target: Option<packed::Byte32>,
+    /// The number of the best verified header
+    pub number: BlockNumber,
+    /// The hash of the best verified header
+    pub hash: packed::Byte32,
+    /// The total difficulty of the best verified header
+    pub total_difficulty: U256,
+}
+
+impl HeaderCheckpoint {
+    /// Returns whether the checkpoint is recorded with the chain spec and the assume-valid target.
+    pub fn matches(
+        &self,
+        spec_hash: &packed::Byte32,
+        assume_valid_target: Option<&packed::Byte32>,
+    ) -> bool {
+        &self.spec_hash == spec_hash && self.assume_valid_target.as_ref() == assume_valid_target
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(CHECKPOINT_SIZE);
+        bytes.extend_from_slice(self.spec_hash.as_slice());
+        bytes.extend_from_slice(
+            self.assume_valid_target
+                .clone()
+                .unwrap_or_else(packed::Byte32::zero)
+                .as_slice(),
+        );
+        bytes.extend_from_slice(self.number.pack().as_slice());
+        bytes.extend_from_slice(self.hash.as_slice());
+        bytes.extend_from_slice(self.total_difficulty.pack().as_slice());
+        bytes
+    }
+
+    pub(crate) fn from_slice(slice: &[u8]) -> Option<Self> {
+        if slice.len() != CHECKPOINT_SIZE {
+            return None;
+        }
+        let assume_valid_target = packed::Byte32::from_slice(&slice[32..64]).ok()?;
+        Some(HeaderCheckpoint {
+            spec_hash: packed::Byte32::from_slice(&slice[0..32]).ok()?,
+            assume_valid_target: if assume_valid_target.is_zero() {
+                None
+            } else {
+                Some(assume_valid_target)
+            },
+            number: packed::Uint64::from_slice(&slice[64..72]).ok()?.unpack(),
+            hash: packed::Byte32::from_slice(&slice[72..104]).ok()?,
+            total_difficulty: packed::Uint256::from_slice(&slice[104..136]).ok()?.unpack(),
+        })
+    }
+}
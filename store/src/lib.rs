@@ -1,8 +1,10 @@
 //! TODO(doc): @quake
 mod cache;
 mod cell;
+mod commit_stats;
 pub mod data_loader_wrapper;
 mod db;
+mod header_checkpoint;
 mod snapshot;
 mod store;
 mod transaction;
@@ -10,7 +12,9 @@ mod write_batch;
 
 pub use cache::StoreCache;
 pub use cell::{attach_block_cell, detach_block_cell};
+pub use commit_stats::{ColumnWriteStats, CommitStats};
 pub use db::ChainDB;
+pub use header_checkpoint::HeaderCheckpoint;
 pub use snapshot::StoreSnapshot;
 pub use store::ChainStore;
 pub use transaction::StoreTransaction;
@@ -1,11 +1,16 @@
 use crate::cache::StoreCache;
-use crate::store::ChainStore;
+use crate::store::{build_cell_meta_from_reader, ChainStore};
 use ckb_db::{
-    iter::{DBIter, DBIterator, IteratorMode},
+    iter::{DBIter, DBIterator, Direction, IteratorMode},
     DBPinnableSlice, RocksDBSnapshot,
 };
-use ckb_db_schema::Col;
+use ckb_db_schema::{Col, COLUMN_CELL};
 use ckb_freezer::Freezer;
+use ckb_types::{
+    core::cell::CellMeta,
+    packed::{self, OutPoint},
+    prelude::*,
+};
 use std::sync::Arc;
 
 /// TODO(doc): @quake
@@ -15,6 +20,67 @@ pub struct StoreSnapshot {
     pub(crate) cache: Arc<StoreCache>,
 }
 
+impl StoreSnapshot {
+    /// Iterates the live cells in the snapshot, in the order of the cell keys, which are the
+    /// transaction hash and the big-endian output index of the out points.
+    ///
+    /// * `lock_prefix` - only the cells whose lock script starts with it are returned. The lock
+    ///   script is matched as the bytes of `code_hash`, `hash_type` and `args` concatenated, an
+    ///   empty prefix matches all the cells.
+    /// * `cursor` - starts from the cell after it, which is usually the last cell returned in the
+    ///   previous scan. The cursor cell is not required to be live.
+    ///
+    /// The cell data are not loaded, use [`get_cell_data`](trait.ChainStore.html#method.get_cell_data)
+    /// when required.
+    pub fn live_cells_iter<'a>(
+        &'a self,
+        lock_prefix: &'a [u8],
+        cursor: Option<&OutPoint>,
+    ) -> impl Iterator<Item = CellMeta> + 'a {
+        let cursor_key = cursor.map(|out_point| out_point.to_cell_key());
+        let mode = match cursor_key {
+            Some(ref key) => IteratorMode::From(key, Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        let iter = self.get_iter(COLUMN_CELL, mode);
+        iter.skip_while(move |(key, _)| Some(&key[..]) == cursor_key.as_deref())
+            .filter_map(move |(key, value)| {
+                let reader = packed::CellEntryReader::from_slice_should_be_ok(&value);
+                if !lock_starts_with(reader.output().lock(), lock_prefix) {
+                    return None;
+                }
+                let out_point = out_point_from_cell_key(&key);
+                Some(build_cell_meta_from_reader(out_point, reader))
+            })
+    }
+}
+
+// The cell key is the transaction hash followed by the big-endian output index.
+fn out_point_from_cell_key(key: &[u8]) -> OutPoint {
+    let tx_hash = packed::Byte32Reader::from_slice_should_be_ok(&key[..32]).to_entity();
+    let mut index = [0u8; 4];
+    index.copy_from_slice(&key[32..36]);
+    OutPoint::new(tx_hash, u32::from_be_bytes(index))
+}
+
+fn lock_starts_with(lock: packed::ScriptReader, prefix: &[u8]) -> bool {
+    let mut rest = prefix;
+    for part in [
+        lock.code_hash().raw_data(),
+        lock.hash_type().as_slice(),
+        lock.args().raw_data(),
+    ]
+    .iter()
+    {
+        let len = rest.len().min(part.len());
+        if rest[..len] != part[..len] {
+            return false;
+        }
+        rest = &rest[len..];
+    }
+    rest.is_empty()
+}
+
 impl<'a> ChainStore<'a> for StoreSnapshot {
     type Vector = DBPinnableSlice<'a>;
 
@@ -27,12 +93,17 @@ impl<'a> ChainStore<'a> for StoreSnapshot {
     }
 
     fn get(&'a self, col: Col, key: &[u8]) -> Option<Self::Vector> {
-        self.inner
+        let ret = self
+            .inner
             .get_pinned(col, key)
-            .expect("db operation should be ok")
+            .expect("db operation should be ok");
+        self.cache
+            .record_read(col, ret.as_ref().map(|value| value.len()));
+        ret
     }
 
     fn get_iter(&self, col: Col, mode: IteratorMode) -> DBIter {
+        self.cache.record_iter(col);
         self.inner
             .iter(col, mode)
             .expect("db operation should be ok")
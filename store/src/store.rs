@@ -45,38 +45,46 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
     /// Get block by block header hash
     fn get_block(&'a self, h: &packed::Byte32) -> Option<BlockView> {
         let header = self.get_block_header(h)?;
-        if let Some(freezer) = self.freezer() {
-            if header.number() > 0 && header.number() < freezer.number() {
-                let raw_block = freezer.retrieve(header.number()).expect("block frozen")?;
-                let raw_block = packed::BlockReader::from_compatible_slice(&raw_block)
-                    .expect("checked data")
-                    .to_entity();
-                return Some(raw_block.into_view());
-            }
+        if is_frozen(self, &header) {
+            return get_frozen_block(self, &header);
         }
         let body = self.get_block_body(h);
-        let uncles = self
-            .get_block_uncles(h)
-            .expect("block uncles must be stored");
-        let proposals = self
-            .get_block_proposal_txs_ids(h)
-            .expect("block proposal_ids must be stored");
-        let extension_opt = self.get_block_extension(h);
+        Some(build_block(self, header, body))
+    }
 
-        let block = if let Some(extension) = extension_opt {
-            BlockView::new_unchecked_with_extension(header, uncles, body, proposals, extension)
-        } else {
-            BlockView::new_unchecked(header, uncles, body, proposals)
-        };
-        Some(block)
+    /// Get blocks by block header hashes in one batch
+    ///
+    /// The bodies of the unfrozen blocks are read by one iterator over the block body column,
+    /// which seeks to the transactions of each block instead of opening an iterator per block.
+    fn get_blocks(&'a self, hashes: &[packed::Byte32]) -> Vec<Option<BlockView>> {
+        let mut body_iter: Option<DBIter> = None;
+        hashes
+            .iter()
+            .map(|hash| {
+                let header = self.get_block_header(hash)?;
+                if is_frozen(self, &header) {
+                    return get_frozen_block(self, &header);
+                }
+                let mode = IteratorMode::From(hash.as_slice(), Direction::Forward);
+                match body_iter {
+                    Some(ref mut iter) => iter.set_mode(mode),
+                    None => body_iter = Some(self.get_iter(COLUMN_BLOCK_BODY, mode)),
+                }
+                let iter = body_iter.as_mut().expect("body iterator is opened");
+                let body = collect_block_body(iter, hash);
+                Some(build_block(self, header, body))
+            })
+            .collect()
     }
 
     /// Get header by block header hash
     fn get_block_header(&'a self, hash: &packed::Byte32) -> Option<HeaderView> {
         if let Some(cache) = self.cache() {
             if let Some(header) = cache.headers.lock().get(hash) {
+                cache.record_lookup("headers", true);
                 return Some(header.clone());
             }
+            cache.record_lookup("headers", false);
         };
         let ret = self.get(COLUMN_BLOCK_HEADER, hash.as_slice()).map(|slice| {
             let reader = packed::HeaderViewReader::from_slice_should_be_ok(&slice.as_ref());
@@ -95,17 +103,11 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
 
     /// Get block body by block header hash
     fn get_block_body(&'a self, hash: &packed::Byte32) -> Vec<TransactionView> {
-        let prefix = hash.as_slice();
-        self.get_iter(
+        let mut iter = self.get_iter(
             COLUMN_BLOCK_BODY,
-            IteratorMode::From(prefix, Direction::Forward),
-        )
-        .take_while(|(key, _)| key.starts_with(prefix))
-        .map(|(_key, value)| {
-            let reader = packed::TransactionViewReader::from_slice_should_be_ok(&value.as_ref());
-            Unpack::<TransactionView>::unpack(&reader)
-        })
-        .collect()
+            IteratorMode::From(hash.as_slice(), Direction::Forward),
+        );
+        collect_block_body(&mut iter, hash)
     }
 
     /// Get unfrozen block from ky-store with given hash
@@ -153,8 +155,10 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
     fn get_block_txs_hashes(&'a self, hash: &packed::Byte32) -> Vec<packed::Byte32> {
         if let Some(cache) = self.cache() {
             if let Some(hashes) = cache.block_tx_hashes.lock().get(hash) {
+                cache.record_lookup("block_tx_hashes", true);
                 return hashes.clone();
             }
+            cache.record_lookup("block_tx_hashes", false);
         };
 
         let prefix = hash.as_slice();
@@ -185,8 +189,10 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
     ) -> Option<packed::ProposalShortIdVec> {
         if let Some(cache) = self.cache() {
             if let Some(data) = cache.block_proposals.lock().get(hash) {
+                cache.record_lookup("block_proposals", true);
                 return Some(data.clone());
             }
+            cache.record_lookup("block_proposals", false);
         };
 
         let ret = self
@@ -210,8 +216,10 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
     fn get_block_uncles(&'a self, hash: &packed::Byte32) -> Option<UncleBlockVecView> {
         if let Some(cache) = self.cache() {
             if let Some(data) = cache.block_uncles.lock().get(hash) {
+                cache.record_lookup("block_uncles", true);
                 return Some(data.clone());
             }
+            cache.record_lookup("block_uncles", false);
         };
 
         let ret = self.get(COLUMN_BLOCK_UNCLE, hash.as_slice()).map(|slice| {
@@ -233,8 +241,10 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
     fn get_block_extension(&'a self, hash: &packed::Byte32) -> Option<packed::Bytes> {
         if let Some(cache) = self.cache() {
             if let Some(data) = cache.block_extensions.lock().get(hash) {
+                cache.record_lookup("block_extensions", true);
                 return data.clone();
             }
+            cache.record_lookup("block_extensions", false);
         };
 
         let ret = self
@@ -354,8 +364,10 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
         let key = out_point.to_cell_key();
         if let Some(cache) = self.cache() {
             if let Some(cached) = cache.cell_data.lock().get(&key) {
+                cache.record_lookup("cell_data", true);
                 return Some(cached.clone());
             }
+            cache.record_lookup("cell_data", false);
         };
 
         let ret = self.get(COLUMN_CELL_DATA, &key).map(|slice| {
@@ -393,8 +405,10 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
         let key = out_point.to_cell_key();
         if let Some(cache) = self.cache() {
             if let Some(cached) = cache.cell_data_hash.lock().get(&key) {
+                cache.record_lookup("cell_data_hash", true);
                 return Some(cached.clone());
             }
+            cache.record_lookup("cell_data_hash", false);
         };
 
         let ret = self.get(COLUMN_CELL_DATA_HASH, &key).map(|raw| {
@@ -533,7 +547,10 @@ pub trait ChainStore<'a>: Send + Sync + Sized {
     }
 }
 
-fn build_cell_meta_from_reader(out_point: OutPoint, reader: packed::CellEntryReader) -> CellMeta {
+pub(crate) fn build_cell_meta_from_reader(
+    out_point: OutPoint,
+    reader: packed::CellEntryReader,
+) -> CellMeta {
     CellMeta {
         out_point,
         cell_output: reader.output().to_entity(),
@@ -581,3 +598,54 @@ where
         }
     }
 }
+
+// The blocks below the freezer number are moved to the freezer, except the genesis block.
+fn is_frozen<'a, S: ChainStore<'a>>(store: &'a S, header: &HeaderView) -> bool {
+    store
+        .freezer()
+        .map(|freezer| header.number() > 0 && header.number() < freezer.number())
+        .unwrap_or(false)
+}
+
+fn get_frozen_block<'a, S: ChainStore<'a>>(store: &'a S, header: &HeaderView) -> Option<BlockView> {
+    let freezer = store.freezer().expect("checked frozen");
+    let raw_block = freezer.retrieve(header.number()).expect("block frozen")?;
+    let raw_block = packed::BlockReader::from_compatible_slice(&raw_block)
+        .expect("checked data")
+        .to_entity();
+    Some(raw_block.into_view())
+}
+
+// Collects the transactions of the block from the iterator positioned at the block hash, the
+// keys of the block body column are the block hash followed by the transaction index.
+fn collect_block_body(iter: &mut DBIter, hash: &packed::Byte32) -> Vec<TransactionView> {
+    let prefix = hash.as_slice();
+    iter.by_ref()
+        .take_while(|(key, _)| key.starts_with(prefix))
+        .map(|(_key, value)| {
+            let reader = packed::TransactionViewReader::from_slice_should_be_ok(&value.as_ref());
+            Unpack::<TransactionView>::unpack(&reader)
+        })
+        .collect()
+}
+
+fn build_block<'a, S: ChainStore<'a>>(
+    store: &'a S,
+    header: HeaderView,
+    body: Vec<TransactionView>,
+) -> BlockView {
+    let hash = header.hash();
+    let uncles = store
+        .get_block_uncles(&hash)
+        .expect("block uncles must be stored");
+    let proposals = store
+        .get_block_proposal_txs_ids(&hash)
+        .expect("block proposal_ids must be stored");
+    let extension_opt = store.get_block_extension(&hash);
+
+    if let Some(extension) = extension_opt {
+        BlockView::new_unchecked_with_extension(header, uncles, body, proposals, extension)
+    } else {
+        BlockView::new_unchecked(header, uncles, body, proposals)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::cache::StoreCache;
+use crate::commit_stats::CommitStats;
 use crate::store::ChainStore;
 use ckb_db::{
     iter::{DBIter, DBIterator, IteratorMode},
@@ -13,18 +14,23 @@ use ckb_db_schema::{
 };
 use ckb_error::Error;
 use ckb_freezer::Freezer;
+use ckb_metrics::Timer;
 use ckb_types::{
     core::{BlockExt, BlockView, EpochExt, HeaderView},
     packed,
     prelude::*,
 };
+use ckb_util::Mutex;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// TODO(doc): @quake
 pub struct StoreTransaction {
     pub(crate) inner: RocksDBTransaction,
     pub(crate) freezer: Option<Freezer>,
     pub(crate) cache: Arc<StoreCache>,
+    pub(crate) stats: Mutex<CommitStats>,
+    pub(crate) slow_commit_threshold: Option<Duration>,
 }
 
 impl<'a> ChainStore<'a> for StoreTransaction {
@@ -80,17 +86,34 @@ impl<'a> ChainStore<'a> for StoreTransactionSnapshot<'a> {
 impl StoreTransaction {
     /// TODO(doc): @quake
     pub fn insert_raw(&self, col: Col, key: &[u8], value: &[u8]) -> Result<(), Error> {
-        self.inner.put(col, key, value)
+        self.inner.put(col, key, value)?;
+        self.stats.lock().record_put(col, key, value);
+        Ok(())
     }
 
     /// TODO(doc): @quake
     pub fn delete(&self, col: Col, key: &[u8]) -> Result<(), Error> {
-        self.inner.delete(col, key)
+        self.inner.delete(col, key)?;
+        self.stats.lock().record_delete(col, key);
+        Ok(())
     }
 
-    /// TODO(doc): @quake
+    /// Commits the transaction.
+    ///
+    /// It reports the size and the latency of the commit as metrics, and logs the writes of each
+    /// column when the commit is slower than `store.slow_commit_threshold_ms`.
     pub fn commit(&self) -> Result<(), Error> {
-        self.inner.commit()
+        let timer = Timer::start();
+        self.inner.commit()?;
+        self.stats
+            .lock()
+            .report(timer.stop(), self.slow_commit_threshold);
+        Ok(())
+    }
+
+    /// Returns the writes in the transaction so far.
+    pub fn commit_stats(&self) -> CommitStats {
+        self.stats.lock().clone()
     }
 
     /// TODO(doc): @quake
@@ -15,11 +15,11 @@ mod utils;
 #[cfg(test)]
 mod tests;
 
-pub use crate::net_time_checker::NetTimeProtocol;
+pub use crate::net_time_checker::{ClockSkew, NetTime, NetTimeProtocol};
 pub use crate::relayer::Relayer;
 pub use crate::status::{Status, StatusCode};
 pub use crate::synchronizer::Synchronizer;
-pub use crate::types::SyncShared;
+pub use crate::types::{PeerFetchStats, SyncProgress, SyncShared};
 use ckb_constant::sync::MAX_BLOCKS_IN_TRANSIT_PER_PEER;
 
 // Time recording window size, ibd period scheduler dynamically adjusts frequency
@@ -31,5 +31,13 @@ pub(crate) const FAST_INDEX: usize = TIME_TRACE_SIZE / 3;
 pub(crate) const NORMAL_INDEX: usize = TIME_TRACE_SIZE * 4 / 5;
 // Low Zone Boundaries for the Time Window
 pub(crate) const LOW_INDEX: usize = TIME_TRACE_SIZE * 9 / 10;
+// The weight of the history in the moving average of a peer's block response time, the latest
+// sample weighs 1 / RESPONSE_TIME_AVERAGE_WEIGHT
+pub(crate) const RESPONSE_TIME_AVERAGE_WEIGHT: u64 = 8;
+// The ping RTT assumed for the peers which have not answered a ping yet, unit ms
+pub(crate) const UNKNOWN_PING_RTT: u64 = 200;
+// Every so many block fetch rounds, a peer in turn is moved to the front of the fetch order, so
+// the slow or unmeasured peers are explored
+pub(crate) const FETCH_EXPLORE_INTERVAL: u64 = 10;
 
 pub(crate) const LOG_TARGET_RELAY: &str = "ckb_relay";
@@ -2,14 +2,19 @@ use crate::utils::send_message_to;
 use ckb_constant::sync::BAD_MESSAGE_BAN_TIME;
 use ckb_logger::{debug, info, warn};
 use ckb_network::{bytes::Bytes, CKBProtocolContext, CKBProtocolHandler, PeerIndex};
+use ckb_notify::{LocalAlert, NotifyController};
 use ckb_types::{packed, prelude::*};
 use ckb_util::RwLock;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 
 const TOLERANT_OFFSET: u64 = 7_200_000;
 const MIN_SAMPLES: usize = 5;
 const MAX_SAMPLES: usize = 11;
+// The clock skew alert is noticed for a day, unless the skew is fixed and detected again
+const CLOCK_SKEW_ALERT_NOTICE_DURATION: u64 = 24 * 60 * 60 * 1000;
 
 /// Collect and check time offset samples
 #[derive(Clone)]
@@ -38,7 +43,8 @@ impl NetTimeChecker {
         }
     }
 
-    fn median_offset(&self) -> Option<i64> {
+    /// Returns the median of the samples, or `None` when there are not enough samples.
+    pub fn median_offset(&self) -> Option<i64> {
         if self.samples.is_empty() || self.samples.len() < self.min_samples {
             return None;
         }
@@ -64,6 +70,11 @@ impl NetTimeChecker {
         }
         Ok(())
     }
+
+    /// Returns the max offset the local clock is allowed to have.
+    pub fn tolerant_offset(&self) -> u64 {
+        self.tolerant_offset
+    }
 }
 
 impl Default for NetTimeChecker {
@@ -72,35 +83,163 @@ impl Default for NetTimeChecker {
     }
 }
 
-/// Collect time offset samples from network peers and send notify to user if offset is too large
-pub struct NetTimeProtocol {
-    checker: RwLock<NetTimeChecker>,
+/// The skew of the local clock against the network peers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockSkew {
+    /// The local clock is ahead of the network peers.
+    Ahead(Duration),
+    /// The local clock is behind the network peers.
+    Behind(Duration),
 }
 
-impl Clone for NetTimeProtocol {
-    fn clone(&self) -> Self {
-        NetTimeProtocol {
-            checker: RwLock::new(self.checker.read().to_owned()),
+impl ClockSkew {
+    /// Creates the skew from the network offset, which is the local time minus the peer time in
+    /// milliseconds.
+    pub fn from_offset(offset: i64) -> Self {
+        let skew = Duration::from_millis(offset.unsigned_abs());
+        if offset > 0 {
+            ClockSkew::Ahead(skew)
+        } else {
+            ClockSkew::Behind(skew)
+        }
+    }
+
+    /// Returns the skew regardless of its direction.
+    pub fn duration(&self) -> Duration {
+        match self {
+            ClockSkew::Ahead(skew) | ClockSkew::Behind(skew) => *skew,
+        }
+    }
+}
+
+impl fmt::Display for ClockSkew {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClockSkew::Ahead(skew) => write!(f, "{}ms ahead of", skew.as_millis()),
+            ClockSkew::Behind(skew) => write!(f, "{}ms behind", skew.as_millis()),
+        }
+    }
+}
+
+/// The time offsets from the network peers, shared by the time protocol and the RPC.
+///
+/// The offset is the local time minus the peer time in milliseconds, a positive offset means the
+/// local clock is ahead of the peer.
+#[derive(Clone, Default)]
+pub struct NetTime {
+    inner: Arc<RwLock<NetTimeState>>,
+}
+
+#[derive(Default)]
+struct NetTimeState {
+    checker: NetTimeChecker,
+    peer_offsets: HashMap<PeerIndex, i64>,
+}
+
+impl NetTime {
+    /// Creates the net time which tolerates the local clock offset up to `tolerant_offset`.
+    pub fn new(tolerant_offset: Duration) -> Self {
+        Self::with_samples(MIN_SAMPLES, MAX_SAMPLES, tolerant_offset.as_millis() as u64)
+    }
+
+    fn with_samples(min_samples: usize, max_samples: usize, tolerant_offset: u64) -> Self {
+        let state = NetTimeState {
+            checker: NetTimeChecker::new(min_samples, max_samples, tolerant_offset),
+            peer_offsets: HashMap::default(),
+        };
+        NetTime {
+            inner: Arc::new(RwLock::new(state)),
         }
     }
+
+    fn add_sample(&self, peer_index: PeerIndex, offset: i64) -> Result<(), i64> {
+        let mut state = self.inner.write();
+        state.peer_offsets.insert(peer_index, offset);
+        state.checker.add_sample(offset);
+        state.checker.check()
+    }
+
+    fn remove_peer(&self, peer_index: PeerIndex) {
+        self.inner.write().peer_offsets.remove(&peer_index);
+    }
+
+    /// Returns the median offset of the recent samples, or `None` when there are not enough
+    /// samples.
+    pub fn network_offset(&self) -> Option<i64> {
+        self.inner.read().checker.median_offset()
+    }
+
+    /// Returns the local time `now` corrected by the network offset, both in milliseconds since
+    /// UNIX epoch.
+    pub fn adjusted_time(&self, now: u64) -> u64 {
+        match self.network_offset() {
+            Some(offset) => (i128::from(now) - i128::from(offset)).max(0) as u64,
+            None => now,
+        }
+    }
+
+    /// Returns the max offset the local clock is allowed to have.
+    pub fn tolerant_offset(&self) -> Duration {
+        Duration::from_millis(self.inner.read().checker.tolerant_offset())
+    }
+
+    /// Returns the last offset sample of each connected outbound peer.
+    pub fn peer_offsets(&self) -> Vec<(PeerIndex, i64)> {
+        self.inner
+            .read()
+            .peer_offsets
+            .iter()
+            .map(|(peer_index, offset)| (*peer_index, *offset))
+            .collect()
+    }
+}
+
+/// Collect time offset samples from network peers and send notify to user if offset is too large
+#[derive(Clone, Default)]
+pub struct NetTimeProtocol {
+    net_time: NetTime,
+    notify_controller: Option<NotifyController>,
+    // Whether the clock skew has been alerted, it is reset when the skew is fixed
+    skew_alerted: bool,
 }
 
 impl NetTimeProtocol {
     /// Init time protocol
     pub fn new(min_samples: usize, max_samples: usize, tolerant_offset: u64) -> Self {
-        let checker = RwLock::new(NetTimeChecker::new(
-            min_samples,
-            max_samples,
-            tolerant_offset,
-        ));
-        NetTimeProtocol { checker }
+        NetTimeProtocol {
+            net_time: NetTime::with_samples(min_samples, max_samples, tolerant_offset),
+            notify_controller: None,
+            skew_alerted: false,
+        }
     }
-}
 
-impl Default for NetTimeProtocol {
-    fn default() -> Self {
-        let checker = RwLock::new(NetTimeChecker::default());
-        NetTimeProtocol { checker }
+    /// Init time protocol which collects samples into `net_time`, and raises a local alert via
+    /// `notify_controller` when the local clock skew exceeds the tolerant offset.
+    pub fn with_net_time(net_time: NetTime, notify_controller: NotifyController) -> Self {
+        NetTimeProtocol {
+            net_time,
+            notify_controller: Some(notify_controller),
+            skew_alerted: false,
+        }
+    }
+
+    fn alert_clock_skew(&mut self, skew: ClockSkew) {
+        if self.skew_alerted {
+            return;
+        }
+        self.skew_alerted = true;
+        if let Some(notify_controller) = self.notify_controller.as_ref() {
+            let message = format!(
+                "The local clock is {} the network peers, which exceeds the tolerant offset {}ms, please check the system clock",
+                skew,
+                self.net_time.tolerant_offset().as_millis(),
+            );
+            let notice_until = faketime::unix_time_as_millis() + CLOCK_SKEW_ALERT_NOTICE_DURATION;
+            notify_controller.notify_local_alert(LocalAlert {
+                message,
+                notice_until,
+            });
+        }
     }
 }
 
@@ -152,13 +291,19 @@ impl CKBProtocolHandler for NetTimeProtocol {
 
         let now: u64 = faketime::unix_time_as_millis();
         let offset: i64 = (i128::from(now) - i128::from(timestamp)) as i64;
-        let mut net_time_checker = self.checker.write();
         debug!("new net time offset sample {}ms", offset);
-        net_time_checker.add_sample(offset);
-        if let Err(offset) = net_time_checker.check() {
-            warn!("Please check your computer's local clock({}ms offset from network peers), If your clock is wrong, it may cause unexpected errors.", offset);
+        match self.net_time.add_sample(peer_index, offset) {
+            Ok(()) => self.skew_alerted = false,
+            Err(offset) => {
+                warn!("Please check your computer's local clock({}ms offset from network peers), If your clock is wrong, it may cause unexpected errors.", offset);
+                self.alert_clock_skew(ClockSkew::from_offset(offset));
+            }
         }
     }
+
+    fn disconnected(&mut self, _nc: Arc<dyn CKBProtocolContext + Sync>, peer_index: PeerIndex) {
+        self.net_time.remove_peer(peer_index);
+    }
 }
 
 #[cfg(test)]
@@ -195,4 +340,41 @@ mod tests {
         ntc.add_sample(-(TOLERANT_OFFSET as i64) - 3);
         assert_eq!(ntc.check().unwrap_err(), -(TOLERANT_OFFSET as i64) - 1);
     }
+
+    #[test]
+    fn test_net_time_peer_offsets() {
+        let net_time = NetTime::with_samples(3, 5, 1_000);
+        assert_eq!(net_time.network_offset(), None);
+        assert_eq!(net_time.adjusted_time(10_000), 10_000);
+
+        assert!(net_time.add_sample(1.into(), 2_000).is_ok());
+        assert!(net_time.add_sample(2.into(), 3_000).is_ok());
+        assert_eq!(net_time.add_sample(3.into(), -100), Err(2_000));
+        assert_eq!(net_time.network_offset(), Some(2_000));
+        assert_eq!(net_time.adjusted_time(10_000), 8_000);
+
+        net_time.remove_peer(2.into());
+        let mut peer_offsets = net_time.peer_offsets();
+        peer_offsets.sort_unstable_by_key(|(_, offset)| *offset);
+        assert_eq!(peer_offsets, vec![(3.into(), -100), (1.into(), 2_000)]);
+        // The samples of the disconnected peers are kept
+        assert_eq!(net_time.network_offset(), Some(2_000));
+    }
+
+    #[test]
+    fn test_clock_skew_from_offset() {
+        let ahead = ClockSkew::from_offset(2_000);
+        assert_eq!(ahead, ClockSkew::Ahead(Duration::from_secs(2)));
+        assert_eq!(ahead.to_string(), "2000ms ahead of");
+
+        let behind = ClockSkew::from_offset(-1_500);
+        assert_eq!(behind, ClockSkew::Behind(Duration::from_millis(1_500)));
+        assert_eq!(behind.duration(), Duration::from_millis(1_500));
+        assert_eq!(behind.to_string(), "1500ms behind");
+
+        assert_eq!(
+            ClockSkew::from_offset(i64::MIN).duration(),
+            Duration::from_millis(i64::MIN.unsigned_abs())
+        );
+    }
 }
@@ -1,11 +1,11 @@
 use crate::relayer::block_transactions_verifier::BlockTransactionsVerifier;
 use crate::relayer::block_uncles_verifier::BlockUnclesVerifier;
 use crate::relayer::{ReconstructionResult, Relayer};
-use crate::utils::send_message_to;
+use crate::utils::quick_send_message_to;
 use crate::{attempt, Status, StatusCode};
 use ckb_network::{CKBProtocolContext, PeerIndex};
 use ckb_types::{core, packed, prelude::*};
-use std::collections::hash_map::Entry;
+use std::collections::{hash_map::Entry, HashSet};
 use std::mem;
 use std::sync::Arc;
 
@@ -105,9 +105,14 @@ impl<'a> BlockTransactionsProcess<'a> {
 
                 // Request proposal
                 {
+                    // The proposals of the block itself are requested on receiving the compact
+                    // block.
+                    let block_proposals: HashSet<_> =
+                        compact_block.proposals().into_iter().collect();
                     let proposals: Vec<_> = received_uncles
                         .into_iter()
                         .flat_map(|u| u.data().proposals().into_iter())
+                        .filter(|id| !block_proposals.contains(id))
                         .collect();
                     self.relayer.request_proposal_txs(
                         self.nc.as_ref(),
@@ -120,6 +125,7 @@ impl<'a> BlockTransactionsProcess<'a> {
                 match ret {
                     ReconstructionResult::Block(block) => {
                         pending.remove();
+                        self.relayer.observe_block_reconstructed(&block_hash);
                         self.relayer
                             .accept_block(self.nc.as_ref(), self.peer, block);
                         return Status::ok();
@@ -156,7 +162,7 @@ impl<'a> BlockTransactionsProcess<'a> {
                         .build();
                     let message = packed::RelayMessage::new_builder().set(content).build();
 
-                    attempt!(send_message_to(self.nc.as_ref(), self.peer, &message));
+                    attempt!(quick_send_message_to(self.nc.as_ref(), self.peer, &message));
                 }
 
                 let _ignore_prev_value =
@@ -1,7 +1,7 @@
 use crate::block_status::BlockStatus;
 use crate::relayer::compact_block_verifier::CompactBlockVerifier;
 use crate::relayer::{ReconstructionResult, Relayer};
-use crate::utils::send_message_to;
+use crate::utils::quick_send_message_to;
 use crate::{attempt, Status, StatusCode};
 use ckb_logger::{self, debug_target};
 use ckb_network::{CKBProtocolContext, PeerIndex};
@@ -181,6 +181,7 @@ impl<'a> CompactBlockProcess<'a> {
 
                 // Header has been verified ok, update state
                 shared.insert_valid_header(self.peer, &header);
+                self.relayer.mark_block_first_seen(&block_hash);
             }
 
             // Request proposal
@@ -205,6 +206,7 @@ impl<'a> CompactBlockProcess<'a> {
             match ret {
                 ReconstructionResult::Block(block) => {
                     pending_compact_blocks.remove(&block_hash);
+                    self.relayer.observe_block_reconstructed(&block_hash);
                     self.relayer
                         .accept_block(self.nc.as_ref(), self.peer, block);
                     return Status::ok();
@@ -262,7 +264,7 @@ impl<'a> CompactBlockProcess<'a> {
             .uncle_indexes(missing_uncles.pack())
             .build();
         let message = packed::RelayMessage::new_builder().set(content).build();
-        let sending = send_message_to(self.nc.as_ref(), self.peer, &message);
+        let sending = quick_send_message_to(self.nc.as_ref(), self.peer, &message);
         if !sending.is_ok() {
             ckb_logger::warn_target!(
                 crate::LOG_TARGET_RELAY,
@@ -0,0 +1,41 @@
+use crate::relayer::Relayer;
+use crate::Status;
+use ckb_logger::debug_target;
+use ckb_network::PeerIndex;
+use ckb_types::{core::FeeRate, packed, prelude::*};
+
+pub struct FeeFilterProcess<'a> {
+    message: packed::RelayFeeFilterReader<'a>,
+    relayer: &'a Relayer,
+    peer: PeerIndex,
+}
+
+impl<'a> FeeFilterProcess<'a> {
+    pub fn new(
+        message: packed::RelayFeeFilterReader<'a>,
+        relayer: &'a Relayer,
+        peer: PeerIndex,
+    ) -> Self {
+        FeeFilterProcess {
+            message,
+            relayer,
+            peer,
+        }
+    }
+
+    pub fn execute(self) -> Status {
+        let fee_rate = FeeRate::from_u64(self.message.fee_rate().unpack());
+        debug_target!(
+            crate::LOG_TARGET_RELAY,
+            "peer {} sets the fee filter {}",
+            self.peer,
+            fee_rate
+        );
+        self.relayer
+            .shared()
+            .state()
+            .peers()
+            .set_fee_filter(self.peer, fee_rate);
+        Status::ok()
+    }
+}
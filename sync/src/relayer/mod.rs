@@ -4,6 +4,7 @@ mod block_transactions_verifier;
 mod block_uncles_verifier;
 mod compact_block_process;
 mod compact_block_verifier;
+mod fee_filter_process;
 mod get_block_proposal_process;
 mod get_block_transactions_process;
 mod get_transactions_process;
@@ -15,13 +16,14 @@ mod transactions_process;
 use self::block_proposal_process::BlockProposalProcess;
 use self::block_transactions_process::BlockTransactionsProcess;
 use self::compact_block_process::CompactBlockProcess;
+use self::fee_filter_process::FeeFilterProcess;
 use self::get_block_proposal_process::GetBlockProposalProcess;
 use self::get_block_transactions_process::GetBlockTransactionsProcess;
 use self::get_transactions_process::GetTransactionsProcess;
 use self::transaction_hashes_process::TransactionHashesProcess;
 use self::transactions_process::TransactionsProcess;
 use crate::block_status::BlockStatus;
-use crate::types::{ActiveChain, SyncShared};
+use crate::types::{ActiveChain, SyncShared, MIN_ANNOUNCE_INTERVAL};
 use crate::utils::send_message_to;
 use crate::{Status, StatusCode};
 use ckb_chain::chain::ChainController;
@@ -48,10 +50,22 @@ pub const TX_PROPOSAL_TOKEN: u64 = 0;
 pub const ASK_FOR_TXS_TOKEN: u64 = 1;
 pub const TX_HASHES_TOKEN: u64 = 2;
 pub const SEARCH_ORPHAN_POOL_TOKEN: u64 = 3;
+pub const FEE_FILTER_TOKEN: u64 = 4;
+
+/// The version of the relay v2 protocol which supports the fee filter.
+///
+/// The peers which open the relay v2 protocol in this version send the `RelayFeeFilter` message
+/// to advertise the min fee rate of the transactions they accept, and again when the rate changes.
+/// The transactions below the fee filter of a peer are not announced to it.
+pub const RELAY_FEE_FILTER_VERSION: &str = "3";
 
 pub const MAX_RELAY_PEERS: usize = 128;
 pub const MAX_RELAY_TXS_NUM_PER_BATCH: usize = 32767;
 pub const MAX_RELAY_TXS_BYTES_PER_BATCH: usize = 1024 * 1024;
+const FIRST_SEEN_BLOCKS_CACHE_SIZE: usize = 64;
+// The min fee rate of the tx-pool rises and falls with the pool usage, so it is checked at this
+// interval and advertised again when it changes.
+const FEE_FILTER_INTERVAL: Duration = Duration::from_secs(10);
 
 type RateLimiter<T> = governor::RateLimiter<
     T,
@@ -75,6 +89,10 @@ pub struct Relayer {
     pub(crate) min_fee_rate: FeeRate,
     pub(crate) max_tx_verify_cycles: Cycle,
     rate_limiter: Arc<Mutex<RateLimiter<(PeerIndex, u32)>>>,
+    // The time when the compact blocks are first seen, used to export the propagation latency
+    first_seen_blocks: Arc<Mutex<lru::LruCache<Byte32, Instant>>>,
+    // The fee filters advertised to the peers
+    pub(crate) sent_fee_filters: Arc<Mutex<HashMap<PeerIndex, FeeRate>>>,
     v2: bool,
 }
 
@@ -83,7 +101,8 @@ impl Relayer {
     ///
     /// This is a runtime relay protocol shared state, and any relay messages will be processed and forwarded by it
     ///
-    /// min_fee_rate: Default transaction fee unit, can be modified by configuration file
+    /// min_fee_rate: Default transaction fee unit, can be modified by configuration file, which is
+    /// advertised to the peers when the tx-pool min fee rate is not available
     /// max_tx_verify_cycles: Maximum transaction consumption allowed by default, can be modified by configuration file
     pub fn new(
         chain: ChainController,
@@ -101,6 +120,10 @@ impl Relayer {
             min_fee_rate,
             max_tx_verify_cycles,
             rate_limiter,
+            first_seen_blocks: Arc::new(Mutex::new(lru::LruCache::new(
+                FIRST_SEEN_BLOCKS_CACHE_SIZE,
+            ))),
+            sent_fee_filters: Arc::new(Mutex::new(HashMap::new())),
             v2: false,
         }
     }
@@ -195,6 +218,9 @@ impl Relayer {
             packed::RelayMessageUnionReader::BlockProposal(reader) => {
                 BlockProposalProcess::new(reader, self).execute()
             }
+            packed::RelayMessageUnionReader::RelayFeeFilter(reader) => {
+                FeeFilterProcess::new(reader, self, peer).execute()
+            }
         }
     }
 
@@ -245,9 +271,34 @@ impl Relayer {
                 status
             );
         }
+        if let Some(behaviour) = status.should_report() {
+            nc.report_peer(peer, behaviour);
+        }
+    }
+
+    /// Records the time when the compact block is first seen
+    pub(crate) fn mark_block_first_seen(&self, block_hash: &Byte32) {
+        let mut first_seen_blocks = self.first_seen_blocks.lock();
+        if !first_seen_blocks.contains(block_hash) {
+            first_seen_blocks.put(block_hash.clone(), Instant::now());
+        }
+    }
+
+    /// Exports the latency from the compact block is first seen to it is fully reconstructed
+    pub(crate) fn observe_block_reconstructed(&self, block_hash: &Byte32) {
+        if let Some(first_seen) = self.first_seen_blocks.lock().pop(block_hash) {
+            metrics!(
+                timing,
+                "ckb.relay.block_propagation_latency",
+                first_seen.elapsed()
+            );
+        }
     }
 
     /// Request the transaction corresponding to the proposal id from the specified node
+    ///
+    /// The duplicated ids, such as the ones proposed by both the block and its uncles, are
+    /// requested only once.
     pub fn request_proposal_txs(
         &self,
         nc: &dyn CKBProtocolContext,
@@ -255,7 +306,8 @@ impl Relayer {
         block_hash: Byte32,
         mut proposals: Vec<packed::ProposalShortId>,
     ) {
-        proposals.dedup();
+        let mut seen = HashSet::with_capacity(proposals.len());
+        proposals.retain(|id| seen.insert(id.clone()));
         let tx_pool = self.shared.shared().tx_pool_controller();
         let fresh_proposals = match tx_pool.fresh_proposals_filter(proposals) {
             Err(err) => {
@@ -421,6 +473,39 @@ impl Relayer {
         let mut missing_uncles = Vec::with_capacity(compact_block.uncles().len());
         let mut uncles = Vec::with_capacity(compact_block.uncles().len());
 
+        // The uncles not received are looked up by their status, and the stored ones are read
+        // in one batch.
+        let uncle_statuses: Vec<BlockStatus> = compact_block
+            .uncles()
+            .into_iter()
+            .enumerate()
+            .map(|(i, uncle_hash)| {
+                if uncles_index.contains(&(i as u32)) {
+                    BlockStatus::UNKNOWN
+                } else {
+                    active_chain.get_block_status(&uncle_hash)
+                }
+            })
+            .collect();
+        let stored_uncle_hashes: Vec<Byte32> = compact_block
+            .uncles()
+            .into_iter()
+            .zip(&uncle_statuses)
+            .filter(|(_, status)| {
+                matches!(
+                    **status,
+                    BlockStatus::BLOCK_STORED | BlockStatus::BLOCK_VALID
+                )
+            })
+            .map(|(uncle_hash, _)| uncle_hash)
+            .collect();
+        let mut stored_uncles: HashMap<Byte32, core::BlockView> = stored_uncle_hashes
+            .iter()
+            .cloned()
+            .zip(active_chain.get_blocks(&stored_uncle_hashes))
+            .filter_map(|(uncle_hash, uncle)| uncle.map(|uncle| (uncle_hash, uncle)))
+            .collect();
+
         let mut position = 0;
         for (i, uncle_hash) in compact_block.uncles().into_iter().enumerate() {
             if uncles_index.contains(&(i as u32)) {
@@ -434,11 +519,11 @@ impl Relayer {
                 position += 1;
                 continue;
             };
-            let status = active_chain.get_block_status(&uncle_hash);
+            let status = uncle_statuses[i];
             match status {
                 BlockStatus::UNKNOWN | BlockStatus::HEADER_VALID => missing_uncles.push(i),
                 BlockStatus::BLOCK_STORED | BlockStatus::BLOCK_VALID => {
-                    if let Some(uncle) = active_chain.get_block(&uncle_hash) {
+                    if let Some(uncle) = stored_uncles.remove(&uncle_hash) {
                         uncles.push(uncle.as_uncle().data());
                     } else {
                         debug_target!(
@@ -573,6 +658,65 @@ impl Relayer {
         }
     }
 
+    // The min fee rate of the transactions accepted by the tx-pool now
+    fn pool_min_fee_rate(&self) -> FeeRate {
+        match self.shared.shared().tx_pool_controller().get_tx_pool_info() {
+            Ok(info) => info.min_fee_rate,
+            Err(err) => {
+                debug_target!(
+                    crate::LOG_TARGET_RELAY,
+                    "relayer get_tx_pool_info error: {:?}",
+                    err
+                );
+                self.min_fee_rate
+            }
+        }
+    }
+
+    /// Advertise the min fee rate of the transactions accepted by the tx-pool to the peer
+    pub(crate) fn send_fee_filter(&self, nc: &dyn CKBProtocolContext, peer: PeerIndex) {
+        let fee_rate = self.pool_min_fee_rate();
+        self.send_fee_filter_rate(nc, peer, fee_rate);
+    }
+
+    /// Advertise the min fee rate of the tx-pool again to the peers which have received another
+    /// one
+    pub(crate) fn resend_fee_filters(&self, nc: &dyn CKBProtocolContext) {
+        let fee_rate = self.pool_min_fee_rate();
+        let peers: Vec<PeerIndex> = self
+            .sent_fee_filters
+            .lock()
+            .iter()
+            .filter(|(_, sent)| **sent != fee_rate)
+            .map(|(peer, _)| *peer)
+            .collect();
+        for peer in peers {
+            self.send_fee_filter_rate(nc, peer, fee_rate);
+        }
+    }
+
+    fn send_fee_filter_rate(
+        &self,
+        nc: &dyn CKBProtocolContext,
+        peer: PeerIndex,
+        fee_rate: FeeRate,
+    ) {
+        let content = packed::RelayFeeFilter::new_builder()
+            .fee_rate(fee_rate.as_u64().pack())
+            .build();
+        let message = packed::RelayMessage::new_builder().set(content).build();
+        let status = send_message_to(nc, peer, &message);
+        if !status.is_ok() {
+            debug_target!(
+                crate::LOG_TARGET_RELAY,
+                "relayer send RelayFeeFilter to {} error: {}",
+                peer,
+                status
+            );
+        }
+        self.sent_fee_filters.lock().insert(peer, fee_rate);
+    }
+
     /// Ask for relay transaction by hash from all peers
     pub fn ask_for_txs(&self, nc: &dyn CKBProtocolContext) {
         for (peer, mut tx_hashes) in self.shared().state().pop_ask_for_txs() {
@@ -596,7 +740,9 @@ impl Relayer {
         }
     }
 
-    /// Send bulk of tx hashes to selected peers
+    /// Queue the new tx hashes for the selected peers, and send the batches which are due
+    ///
+    /// The batches sent to each peer are limited by `sync.tx_announce_bytes_per_sec`.
     pub fn send_bulk_of_tx_hashes(&self, nc: &dyn CKBProtocolContext) {
         const BUFFER_SIZE: usize = 42;
 
@@ -610,16 +756,27 @@ impl Relayer {
             .shared
             .state()
             .take_relay_tx_hashes(MAX_RELAY_TXS_NUM_PER_BATCH);
+        let fee_filters = self.shared.state().peers().fee_filters();
         let mut selected: HashMap<PeerIndex, Vec<Byte32>> = HashMap::default();
+        let mut filtered = 0u64;
         {
             let mut known_txs = self.shared.state().known_txs();
-            for (origin_peer, is_ckb2021, hash) in &tx_hashes {
+            for (origin_peer, is_ckb2021, hash, fee_rate) in &tx_hashes {
                 // must all fork or all no-fork
                 if ckb2021 != *is_ckb2021 {
                     continue;
                 }
 
                 for target in &connected_peers {
+                    // the peer rejects the transactions below its fee filter
+                    if fee_filters
+                        .get(target)
+                        .map(|fee_filter| fee_rate < fee_filter)
+                        .unwrap_or(false)
+                    {
+                        filtered += 1;
+                        continue;
+                    }
                     match origin_peer {
                         Some(origin) => {
                             // broadcast tx hash to all connected peers except origin peer
@@ -642,7 +799,36 @@ impl Relayer {
                 }
             }
         }
-        for (peer, hashes) in selected {
+
+        if filtered > 0 {
+            metrics!(counter, "ckb.relay.tx_hashes_filtered", filtered);
+        }
+
+        let now = Instant::now();
+        let (batches, dropped, pending) = {
+            let mut tx_announcer = self.shared.state().tx_announcer();
+            let mut dropped = 0u64;
+            for (peer, hashes) in selected {
+                for hash in hashes {
+                    if !tx_announcer.push(peer, hash, now) {
+                        dropped += 1;
+                    }
+                }
+            }
+            let batches = tx_announcer.pop_due(now, MAX_RELAY_TXS_NUM_PER_BATCH);
+            (batches, dropped, tx_announcer.pending_len())
+        };
+        if dropped > 0 {
+            debug_target!(
+                crate::LOG_TARGET_RELAY,
+                "drop {} tx hashes since the announcement queues are full",
+                dropped
+            );
+            metrics!(counter, "ckb.relay.tx_hashes_dropped", dropped);
+        }
+        metrics!(gauge, "ckb.relay.tx_hashes_pending", pending as i64);
+
+        for (peer, hashes) in batches {
             let content = packed::RelayTransactionHashes::new_builder()
                 .tx_hashes(hashes.pack())
                 .build();
@@ -665,11 +851,15 @@ impl CKBProtocolHandler for Relayer {
             .expect("set_notify at init is ok");
         nc.set_notify(Duration::from_millis(100), ASK_FOR_TXS_TOKEN)
             .expect("set_notify at init is ok");
-        nc.set_notify(Duration::from_millis(300), TX_HASHES_TOKEN)
+        nc.set_notify(MIN_ANNOUNCE_INTERVAL, TX_HASHES_TOKEN)
             .expect("set_notify at init is ok");
         // todo: remove when the asynchronous verification is completed
         nc.set_notify(Duration::from_secs(5), SEARCH_ORPHAN_POOL_TOKEN)
             .expect("set_notify at init is ok");
+        if self.v2 {
+            nc.set_notify(FEE_FILTER_INTERVAL, FEE_FILTER_TOKEN)
+                .expect("set_notify at init is ok");
+        }
     }
 
     fn received(
@@ -773,7 +963,7 @@ impl CKBProtocolHandler for Relayer {
 
     fn connected(
         &mut self,
-        _nc: Arc<dyn CKBProtocolContext + Sync>,
+        nc: Arc<dyn CKBProtocolContext + Sync>,
         peer_index: PeerIndex,
         version: &str,
     ) {
@@ -784,6 +974,9 @@ impl CKBProtocolHandler for Relayer {
             version,
             peer_index
         );
+        if self.v2 && version == RELAY_FEE_FILTER_VERSION {
+            self.send_fee_filter(nc.as_ref(), peer_index);
+        }
     }
 
     fn disconnected(&mut self, _nc: Arc<dyn CKBProtocolContext + Sync>, peer_index: PeerIndex) {
@@ -792,6 +985,7 @@ impl CKBProtocolHandler for Relayer {
             "RelayProtocol.disconnected peer={}",
             peer_index
         );
+        self.sent_fee_filters.lock().remove(&peer_index);
         // Retains all keys in the rate limiter that were used recently enough.
         self.rate_limiter.lock().retain_recent();
     }
@@ -842,6 +1036,7 @@ impl CKBProtocolHandler for Relayer {
                     &self.shared.active_chain().tip_header().hash(),
                 )
             }),
+            FEE_FILTER_TOKEN => self.resend_fee_filters(nc.as_ref()),
             _ => unreachable!(),
         }
         trace_target!(
@@ -316,8 +316,8 @@ fn test_collision_and_send_missing_indexes() {
     let message = packed::RelayMessage::new_builder().set(content).build();
     let data = message.as_bytes();
 
-    // send missing indexes messages
-    assert!(nc.has_sent(SupportProtocols::Relay.protocol_id(), peer_index, data));
+    // send missing indexes messages through the quick queue
+    assert!(nc.has_quick_sent(SupportProtocols::Relay.protocol_id(), peer_index, data));
 
     // update cached missing_index
     {
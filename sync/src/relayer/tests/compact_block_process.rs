@@ -428,8 +428,8 @@ fn test_send_missing_indexes() {
     let message = packed::RelayMessage::new_builder().set(content).build();
     let data = message.as_bytes();
 
-    // send missing indexes messages
-    assert!(nc.has_sent(SupportProtocols::Relay.protocol_id(), peer_index, data));
+    // send missing indexes messages through the quick queue
+    assert!(nc.has_quick_sent(SupportProtocols::Relay.protocol_id(), peer_index, data));
 
     // insert inflight proposal
     assert!(relayer
@@ -449,6 +449,35 @@ fn test_send_missing_indexes() {
     assert!(nc.has_sent(SupportProtocols::Relay.protocol_id(), peer_index, data));
 }
 
+#[test]
+fn test_request_proposal_txs_dedup() {
+    let (relayer, _) = build_chain(5);
+    let nc = Arc::new(MockProtocolContext::new(SupportProtocols::Relay));
+    let peer_index: PeerIndex = 100.into();
+    let block_hash = relayer.shared.active_chain().tip_header().hash();
+
+    let id1 = ProposalShortId::new([1u8; 10]);
+    let id2 = ProposalShortId::new([2u8; 10]);
+    // The uncles propose the same ids as the block.
+    relayer.request_proposal_txs(
+        nc.as_ref(),
+        peer_index,
+        block_hash.clone(),
+        vec![id1.clone(), id2.clone(), id1.clone(), id2.clone()],
+    );
+
+    let content = packed::GetBlockProposal::new_builder()
+        .block_hash(block_hash)
+        .proposals(vec![id1, id2].into_iter().pack())
+        .build();
+    let message = packed::RelayMessage::new_builder().set(content).build();
+    assert!(nc.has_sent(
+        SupportProtocols::Relay.protocol_id(),
+        peer_index,
+        message.as_bytes()
+    ));
+}
+
 #[test]
 fn test_accept_block() {
     let (relayer, _) = build_chain(5);
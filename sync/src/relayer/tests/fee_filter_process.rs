@@ -0,0 +1,87 @@
+use crate::relayer::fee_filter_process::FeeFilterProcess;
+use crate::relayer::tests::helper::{build_chain, MockProtocolContext};
+use crate::Status;
+use ckb_network::{PeerIndex, SupportProtocols};
+use ckb_types::core::FeeRate;
+use ckb_types::packed;
+use ckb_types::prelude::*;
+
+#[test]
+fn test_set_fee_filter() {
+    let (relayer, _) = build_chain(5);
+    let peers = relayer.shared.state().peers();
+    let peer: PeerIndex = 1.into();
+    peers.relay_connected(peer);
+    assert!(peers.fee_filters().is_empty());
+
+    let content = packed::RelayFeeFilter::new_builder()
+        .fee_rate(2000u64.pack())
+        .build();
+    let process = FeeFilterProcess::new(content.as_reader(), &relayer, peer);
+    assert_eq!(process.execute(), Status::ok());
+    assert_eq!(
+        peers.fee_filters().get(&peer),
+        Some(&FeeRate::from_u64(2000))
+    );
+
+    // the disconnected peer is ignored
+    let content = packed::RelayFeeFilter::new_builder()
+        .fee_rate(1000u64.pack())
+        .build();
+    let process = FeeFilterProcess::new(content.as_reader(), &relayer, 2.into());
+    assert_eq!(process.execute(), Status::ok());
+    assert_eq!(peers.fee_filters().len(), 1);
+}
+
+#[test]
+fn test_advertise_pool_min_fee_rate() {
+    let (relayer, _) = build_chain(5);
+    let nc = MockProtocolContext::new(SupportProtocols::RelayV2);
+    let pool_min_fee_rate = relayer
+        .shared
+        .shared()
+        .tx_pool_controller()
+        .get_tx_pool_info()
+        .unwrap()
+        .min_fee_rate;
+    // the relayer is configured with another rate
+    assert_ne!(pool_min_fee_rate, relayer.min_fee_rate);
+    let fee_filter = |fee_rate: FeeRate| {
+        let content = packed::RelayFeeFilter::new_builder()
+            .fee_rate(fee_rate.as_u64().pack())
+            .build();
+        packed::RelayMessage::new_builder()
+            .set(content)
+            .build()
+            .as_bytes()
+    };
+
+    let peer1: PeerIndex = 1.into();
+    relayer.send_fee_filter(&nc, peer1);
+    assert!(nc.has_sent(
+        SupportProtocols::RelayV2.protocol_id(),
+        peer1,
+        fee_filter(pool_min_fee_rate)
+    ));
+    assert_eq!(
+        relayer.sent_fee_filters.lock().get(&peer1),
+        Some(&pool_min_fee_rate)
+    );
+
+    // the peer which has received an outdated rate gets the current one
+    let peer2: PeerIndex = 2.into();
+    relayer
+        .sent_fee_filters
+        .lock()
+        .insert(peer2, FeeRate::from_u64(pool_min_fee_rate.as_u64() + 1));
+    relayer.resend_fee_filters(&nc);
+    assert!(nc.has_sent(
+        SupportProtocols::RelayV2.protocol_id(),
+        peer2,
+        fee_filter(pool_min_fee_rate)
+    ));
+    assert_eq!(
+        relayer.sent_fee_filters.lock().get(&peer2),
+        Some(&pool_min_fee_rate)
+    );
+}
@@ -192,6 +192,7 @@ pub(crate) fn build_chain(tip: BlockNumber) -> (Relayer, OutPoint) {
         shared,
         Default::default(),
         pack.take_relay_tx_receiver(),
+        pack.take_ask_for_txs_receiver(),
     ));
     (
         Relayer::new(
@@ -207,6 +208,7 @@ pub(crate) fn build_chain(tip: BlockNumber) -> (Relayer, OutPoint) {
 pub(crate) struct MockProtocolContext {
     protocol: SupportProtocols,
     sent_messages: RefCell<Vec<(ProtocolId, PeerIndex, P2pBytes)>>,
+    quick_sent_messages: RefCell<Vec<(ProtocolId, PeerIndex, P2pBytes)>>,
 }
 
 impl MockProtocolContext {
@@ -214,6 +216,7 @@ impl MockProtocolContext {
         Self {
             protocol,
             sent_messages: Default::default(),
+            quick_sent_messages: Default::default(),
         }
     }
 
@@ -223,7 +226,18 @@ impl MockProtocolContext {
         peer_index: PeerIndex,
         data: P2pBytes,
     ) -> bool {
-        self.sent_messages
+        let message = (protocol_id, peer_index, data);
+        self.sent_messages.borrow().contains(&message)
+            || self.quick_sent_messages.borrow().contains(&message)
+    }
+
+    pub(crate) fn has_quick_sent(
+        &self,
+        protocol_id: ProtocolId,
+        peer_index: PeerIndex,
+        data: P2pBytes,
+    ) -> bool {
+        self.quick_sent_messages
             .borrow()
             .contains(&(protocol_id, peer_index, data))
     }
@@ -241,14 +255,18 @@ impl CKBProtocolContext for MockProtocolContext {
     }
     fn quick_send_message(
         &self,
-        _proto_id: ProtocolId,
-        _peer_index: PeerIndex,
-        _data: P2pBytes,
+        proto_id: ProtocolId,
+        peer_index: PeerIndex,
+        data: P2pBytes,
     ) -> Result<(), Error> {
-        unimplemented!();
+        self.quick_sent_messages
+            .borrow_mut()
+            .push((proto_id, peer_index, data));
+        Ok(())
     }
-    fn quick_send_message_to(&self, _peer_index: PeerIndex, _data: P2pBytes) -> Result<(), Error> {
-        unimplemented!();
+    fn quick_send_message_to(&self, peer_index: PeerIndex, data: P2pBytes) -> Result<(), Error> {
+        let protocol_id = self.protocol_id();
+        self.quick_send_message(protocol_id, peer_index, data)
     }
     fn quick_filter_broadcast(&self, _target: TargetSession, _data: P2pBytes) -> Result<(), Error> {
         unimplemented!();
@@ -4,5 +4,7 @@ mod block_transactions_verifier;
 mod compact_block;
 mod compact_block_process;
 mod compact_block_verifier;
+mod fee_filter_process;
 mod helper;
+mod orphan_tx;
 mod reconstruct_block;
@@ -0,0 +1,53 @@
+use crate::relayer::tests::helper::dummy_network;
+use crate::SyncShared;
+use ckb_launcher::SharedBuilder;
+use ckb_network::PeerIndex;
+use ckb_types::prelude::*;
+use ckb_types::{
+    bytes::Bytes,
+    core::{Capacity, TransactionBuilder},
+    packed::{Byte32, CellInput, CellOutputBuilder, OutPoint},
+};
+
+#[test]
+fn test_orphan_requests_missing_parent_once() {
+    let (shared, mut pack) = SharedBuilder::with_temp_db().build().unwrap();
+    let network = dummy_network(&shared);
+    pack.take_tx_pool_builder().start(network);
+    let ask_for_txs_receiver = pack.take_ask_for_txs_receiver();
+
+    let parent_hash = Byte32::new([1u8; 32]);
+    let orphan = TransactionBuilder::default()
+        .input(CellInput::new(OutPoint::new(parent_hash.clone(), 0), 0))
+        .output(
+            CellOutputBuilder::default()
+                .capacity(Capacity::bytes(1).unwrap().pack())
+                .build(),
+        )
+        .output_data(Bytes::new().pack())
+        .build();
+
+    // The same orphan is announced by two peers, only the first one is asked for the parent
+    let peer_index: PeerIndex = 100.into();
+    let other_peer_index: PeerIndex = 101.into();
+    let tx_pool = shared.tx_pool_controller();
+    for peer in &[peer_index, other_peer_index] {
+        shared
+            .async_handle()
+            .block_on(tx_pool.submit_remote_tx(orphan.clone(), 1000, *peer))
+            .expect("submit orphan tx");
+    }
+
+    assert_eq!(tx_pool.get_tx_pool_info().unwrap().orphan_size, 1);
+    assert_eq!(ask_for_txs_receiver.len(), 1);
+
+    let sync_shared = SyncShared::new(
+        shared,
+        Default::default(),
+        pack.take_relay_tx_receiver(),
+        ask_for_txs_receiver,
+    );
+    let ask_for_txs = sync_shared.state().pop_ask_for_txs();
+    assert_eq!(ask_for_txs.len(), 1);
+    assert_eq!(ask_for_txs.get(&peer_index), Some(&vec![parent_hash]));
+}
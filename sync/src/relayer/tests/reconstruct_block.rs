@@ -128,6 +128,35 @@ fn test_reconstruct_transactions_and_uncles() {
     assert_eq!(ret, ReconstructionResult::Block(block), "{:?}", ret,);
 }
 
+#[test]
+fn test_reconstruct_stored_and_missing_uncles() {
+    let (relayer, _) = build_chain(5);
+
+    let stored_uncle = BlockBuilder::default().build();
+    let missing_uncle = BlockBuilder::default().number(1.pack()).build();
+    let block = BlockBuilder::default()
+        .uncles(vec![stored_uncle.as_uncle(), missing_uncle.as_uncle()])
+        .build();
+    // BLOCK_VALID
+    let ext = packed::BlockExtBuilder::default()
+        .verified(Some(true).pack())
+        .build();
+    {
+        let db_txn = relayer.shared().shared().store().begin_transaction();
+        db_txn.insert_block(&stored_uncle).unwrap();
+        db_txn
+            .insert_block_ext(&stored_uncle.hash(), &ext.unpack())
+            .unwrap();
+        db_txn.commit().unwrap();
+    }
+    relayer.shared().shared().refresh_snapshot();
+
+    let compact = packed::CompactBlock::build_from_block(&block, &HashSet::new());
+    let ret =
+        relayer.reconstruct_block(&relayer.shared().active_chain(), &compact, vec![], &[], &[]);
+    assert_eq!(ret, ReconstructionResult::Missing(vec![], vec![1]));
+}
+
 #[test]
 fn test_reconstruct_invalid_uncles() {
     let (relayer, _) = build_chain(5);
@@ -1,4 +1,5 @@
 use ckb_constant::sync::{BAD_MESSAGE_BAN_TIME, SYNC_USELESS_BAN_TIME};
+use ckb_network::Behaviour;
 use std::fmt::{self, Display, Formatter};
 use std::time::Duration;
 
@@ -160,6 +161,14 @@ impl Status {
         }
     }
 
+    /// The misbehavior to report, which lowers the score of the peer rather than banning it at once
+    pub fn should_report(&self) -> Option<Behaviour> {
+        match self.code {
+            StatusCode::TooManyRequests => Some(Behaviour::TooManyRequests),
+            _ => None,
+        }
+    }
+
     /// Whether should output a warning log
     pub fn should_warn(&self) -> bool {
         self.code as u16 >= 500
@@ -1,7 +1,10 @@
 use crate::{synchronizer::Synchronizer, utils::is_internal_db_error, Status, StatusCode};
 use ckb_logger::debug;
 use ckb_network::PeerIndex;
+use ckb_shared::IbdPhase;
 use ckb_types::{packed, prelude::*};
+use faketime::unix_time_as_millis;
+use std::time::Duration;
 
 pub struct BlockProcess<'a> {
     message: packed::SendBlockReader<'a>,
@@ -32,7 +35,17 @@ impl<'a> BlockProcess<'a> {
         let shared = self.synchronizer.shared();
         let state = shared.state();
 
+        let requested_at = state
+            .read_inflight_blocks()
+            .inflight_state_by_block(&(block.number(), block.hash()).into())
+            .map(|inflight| inflight.timestamp);
         if state.new_block_received(&block) {
+            if let Some(requested_at) = requested_at {
+                let elapsed = unix_time_as_millis().saturating_sub(requested_at);
+                shared
+                    .shared()
+                    .record_ibd_phase(IbdPhase::BlockDownload, Duration::from_millis(elapsed));
+            }
             if let Err(err) = self.synchronizer.process_new_block(block.clone()) {
                 if !is_internal_db_error(&err) {
                     return StatusCode::BlockIsInvalid.with_context(format!(
@@ -55,19 +55,28 @@ impl<'a> GetBlocksProcess<'a> {
                 .iter()
                 .take(NEW_INIT_BLOCKS_IN_TRANSIT_PER_PEER),
         };
-        for block_hash in iter {
-            debug!("get_blocks {} from peer {:?}", block_hash, self.peer);
-            let block_hash = block_hash.to_entity();
+        let block_hashes: Vec<_> = iter
+            .map(|block_hash| block_hash.to_entity())
+            .filter(|block_hash| {
+                debug!("get_blocks {} from peer {:?}", block_hash, self.peer);
+                if active_chain.contains_block_status(block_hash, BlockStatus::BLOCK_VALID) {
+                    true
+                } else {
+                    debug!(
+                        "ignoring get_block {} request from peer={} for unverified",
+                        block_hash, self.peer
+                    );
+                    false
+                }
+            })
+            .collect();
 
-            if !active_chain.contains_block_status(&block_hash, BlockStatus::BLOCK_VALID) {
-                debug!(
-                    "ignoring get_block {} request from peer={} for unverified",
-                    block_hash, self.peer
-                );
-                continue;
-            }
-
-            if let Some(block) = active_chain.get_block(&block_hash) {
+        // the blocks are read in one batch
+        for (block_hash, block) in block_hashes
+            .iter()
+            .zip(active_chain.get_blocks(&block_hashes))
+        {
+            if let Some(block) = block {
                 debug!(
                     "respond_block {} {} to peer {:?}",
                     block.number(),
@@ -8,8 +8,10 @@ use ckb_logger::{debug, log_enabled, warn, Level};
 use ckb_network::{CKBProtocolContext, PeerIndex};
 use ckb_traits::HeaderProvider;
 use ckb_types::{core, packed, prelude::*};
-use ckb_verification::{HeaderError, HeaderVerifier};
+use ckb_verification::{HeaderError, HeaderVerifier, PowError};
 use ckb_verification_traits::Verifier;
+use std::cmp;
+use std::collections::HashSet;
 
 pub struct HeadersProcess<'a> {
     message: packed::SendHeadersReader<'a>,
@@ -147,8 +149,46 @@ impl<'a> HeadersProcess<'a> {
             }
         };
 
+        // The headers up to the last one verified before restart commit to the verified
+        // ancestors, skip their PoW. Verify the PoW of the rest headers in a batch, the failed
+        // ones are checked again by the acceptor to report the error.
+        let checkpoint_len = headers
+            .iter()
+            .rposition(|header| shared.is_header_verified(header))
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let pow_verified = {
+            let pending = headers
+                .iter()
+                .skip(cmp::max(checkpoint_len, 1))
+                .filter(|header| {
+                    !self
+                        .active_chain
+                        .contains_block_status(&header.hash(), BlockStatus::HEADER_VALID)
+                })
+                .collect::<Vec<_>>();
+            let results = consensus.pow_engine().verify_batch(
+                &pending
+                    .iter()
+                    .map(|header| header.data())
+                    .collect::<Vec<_>>(),
+            );
+            pending
+                .into_iter()
+                .zip(results)
+                .filter_map(|(header, verified)| if verified { Some(header.hash()) } else { None })
+                .chain(
+                    headers
+                        .iter()
+                        .take(checkpoint_len)
+                        .map(|header| header.hash()),
+                )
+                .collect::<HashSet<_>>()
+        };
+
         for header in headers.iter().skip(1) {
-            let verifier = HeaderVerifier::new(shared, consensus);
+            let verifier = HeaderVerifier::new(shared, consensus)
+                .pow_verified(pow_verified.contains(&header.hash()));
             let acceptor =
                 HeaderAcceptor::new(&header, self.peer, verifier, self.active_chain.clone());
             let result = acceptor.accept();
@@ -174,6 +214,7 @@ impl<'a> HeadersProcess<'a> {
             };
         }
 
+        shared.record_verified_headers(&headers);
         self.debug();
 
         if headers.len() == MAX_HEADERS_LEN {
@@ -241,7 +282,9 @@ impl<'a, DL: HeaderProvider> HeaderAcceptor<'a, DL> {
         Ok(())
     }
 
-    pub fn non_contextual_check(&self, state: &mut ValidationResult) -> Result<(), bool> {
+    /// Returns `Err(None)` when the header may become valid later, otherwise returns whether the
+    /// header fails a context-free rule, which does not depend on the parent or the local time.
+    pub fn non_contextual_check(&self, state: &mut ValidationResult) -> Result<(), Option<bool>> {
         self.verifier.verify(self.header).map_err(|error| {
             debug!(
                 "HeadersProcess accept {:?} error {:?}",
@@ -252,14 +295,15 @@ impl<'a, DL: HeaderProvider> HeaderAcceptor<'a, DL> {
             if let Some(header_error) = error.downcast_ref::<HeaderError>() {
                 if header_error.is_too_new() {
                     state.temporary_invalid(Some(ValidationError::Verify(error)));
-                    false
+                    None
                 } else {
+                    let context_free = header_error.downcast_ref::<PowError>().is_some();
                     state.invalid(Some(ValidationError::Verify(error)));
-                    true
+                    Some(context_free)
                 }
             } else {
                 state.invalid(Some(ValidationError::Verify(error)));
-                true
+                Some(false)
             }
         })
     }
@@ -278,9 +322,17 @@ impl<'a, DL: HeaderProvider> HeaderAcceptor<'a, DL> {
         let shared = self.active_chain.shared();
         let state = shared.state();
 
-        // FIXME If status == BLOCK_INVALID then return early. But which error
-        // type should we return?
+        // The header has been rejected before, including the runs before restart.
         let status = self.active_chain.get_block_status(&self.header.hash());
+        if status.contains(BlockStatus::BLOCK_INVALID) {
+            debug!(
+                "HeadersProcess reject known invalid header: {} {}",
+                self.header.number(),
+                self.header.hash(),
+            );
+            result.invalid(Some(ValidationError::KnownInvalid));
+            return result;
+        }
         if status.contains(BlockStatus::HEADER_VALID) {
             let header_view = shared
                 .get_header_view(
@@ -304,14 +356,19 @@ impl<'a, DL: HeaderProvider> HeaderAcceptor<'a, DL> {
             return result;
         }
 
-        if let Some(is_invalid) = self.non_contextual_check(&mut result).err() {
+        if let Err(invalid) = self.non_contextual_check(&mut result) {
             debug!(
                 "HeadersProcess reject non-contextual header: {} {}",
                 self.header.number(),
                 self.header.hash(),
             );
-            if is_invalid {
-                state.insert_block_status(self.header.hash(), BlockStatus::BLOCK_INVALID);
+            match invalid {
+                // Only the context-free failures are recorded in the store
+                Some(true) => shared.mark_header_invalid(self.header.hash()),
+                Some(false) => {
+                    state.insert_block_status(self.header.hash(), BlockStatus::BLOCK_INVALID)
+                }
+                None => {}
             }
             return result;
         }
@@ -322,6 +379,7 @@ impl<'a, DL: HeaderProvider> HeaderAcceptor<'a, DL> {
                 self.header.number(),
                 self.header.hash(),
             );
+            // The allowed versions may change in a hard fork
             state.insert_block_status(self.header.hash(), BlockStatus::BLOCK_INVALID);
             return result;
         }
@@ -349,6 +407,7 @@ pub enum ValidationError {
     Verify(Error),
     Version,
     InvalidParent,
+    KnownInvalid,
 }
 
 #[derive(Debug, Default)]
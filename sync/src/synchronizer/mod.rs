@@ -14,20 +14,22 @@ use self::in_ibd_process::InIBDProcess;
 use crate::block_status::BlockStatus;
 use crate::types::{HeaderView, HeadersSyncController, IBDState, PeerFlags, Peers, SyncShared};
 use crate::utils::send_message_to;
-use crate::{Status, StatusCode};
+use crate::{Status, StatusCode, FETCH_EXPLORE_INTERVAL};
 use ckb_chain::chain::ChainController;
 use ckb_channel as channel;
 use ckb_constant::sync::{
     BAD_MESSAGE_BAN_TIME, CHAIN_SYNC_TIMEOUT, EVICTION_HEADERS_RESPONSE_TIME,
-    INIT_BLOCKS_IN_TRANSIT_PER_PEER, MAX_OUTBOUND_PEERS_TO_PROTECT_FROM_DISCONNECT, MAX_TIP_AGE,
+    MAX_OUTBOUND_PEERS_TO_PROTECT_FROM_DISCONNECT, MAX_TIP_AGE, STALE_TIP_CHECK_INTERVAL,
+    STALE_TIP_PEERS_TO_ROTATE,
 };
 use ckb_error::Error as CKBError;
 use ckb_logger::{debug, error, info, trace, warn};
-use ckb_metrics::metrics;
+use ckb_metrics::{metrics, Timer};
 use ckb_network::{
     bytes::Bytes, CKBProtocolContext, CKBProtocolHandler, PeerIndex, ServiceControl,
     SupportProtocols,
 };
+use ckb_shared::IbdPhase;
 use ckb_types::{
     core::{self, BlockNumber},
     packed::{self, Byte32},
@@ -44,6 +46,7 @@ pub const SEND_GET_HEADERS_TOKEN: u64 = 0;
 pub const IBD_BLOCK_FETCH_TOKEN: u64 = 1;
 pub const NOT_IBD_BLOCK_FETCH_TOKEN: u64 = 2;
 pub const TIMEOUT_EVICTION_TOKEN: u64 = 3;
+pub const STALE_TIP_CHECK_TOKEN: u64 = 4;
 pub const NO_PEER_CHECK_TOKEN: u64 = 255;
 
 const SYNC_NOTIFY_INTERVAL: Duration = Duration::from_millis(200);
@@ -103,11 +106,12 @@ impl BlockFetchCMD {
                         let state = self.sync.shared.state();
                         let best_known = state.shared_best_header_ref();
                         let number = best_known.number();
-                        let assume_valid_target: Byte32 = state
-                            .assume_valid_target()
-                            .as_ref()
-                            .map(Pack::pack)
-                            .expect("assume valid target must exist");
+                        // The target may be cleared via RPC after `can_start` returns
+                        let assume_valid_target: Byte32 =
+                            match state.assume_valid_target().as_ref().map(Pack::pack) {
+                                Some(target) => target,
+                                None => continue,
+                            };
 
                         if number != self.number && (number - self.number) % 10000 == 0 {
                             self.number = number;
@@ -206,6 +210,7 @@ pub struct Synchronizer {
     /// Sync shared state
     pub shared: Arc<SyncShared>,
     fetch_channel: Option<channel::Sender<FetchCMD>>,
+    fetch_rounds: u64,
 }
 
 impl Synchronizer {
@@ -217,6 +222,7 @@ impl Synchronizer {
             chain,
             shared,
             fetch_channel: None,
+            fetch_rounds: 0,
         }
     }
 
@@ -236,7 +242,12 @@ impl Synchronizer {
                 GetHeadersProcess::new(reader, self, peer, nc).execute()
             }
             packed::SyncMessageUnionReader::SendHeaders(reader) => {
-                HeadersProcess::new(reader, self, peer, nc).execute()
+                let timer = Timer::start();
+                let status = HeadersProcess::new(reader, self, peer, nc).execute();
+                self.shared
+                    .shared()
+                    .record_ibd_phase(IbdPhase::HeaderDownload, timer.stop());
+                status
             }
             packed::SyncMessageUnionReader::GetBlocks(reader) => {
                 GetBlocksProcess::new(reader, self, peer, nc).execute()
@@ -284,6 +295,9 @@ impl Synchronizer {
         } else if !status.is_ok() {
             debug!("receive {} from {}, {}", item_name, peer, status);
         }
+        if let Some(behaviour) = status.should_report() {
+            nc.report_peer(peer, behaviour);
+        }
     }
 
     /// Get peers info
@@ -471,6 +485,50 @@ impl Synchronizer {
         }
     }
 
+    // When the tip has not advanced for `sync.stale_tip_timeout`, the connected peers may all be
+    // stuck or on a stale fork. Disconnect the outbound peers knowing the least work, the
+    // outbound peer service then fills the slots with the peers from the fresh addresses.
+    // The protected and whitelisted peers are kept.
+    fn rotate_on_stale_tip(&self, nc: &dyn CKBProtocolContext) {
+        let stale = match self.shared.check_stale_tip() {
+            Some(stale) => stale,
+            None => return,
+        };
+        metrics!(counter, "ckb.sync.stale_tip", 1);
+
+        let mut candidates: Vec<_> = self
+            .peers()
+            .state
+            .iter()
+            .filter(|kv_pair| {
+                let flags = &kv_pair.value().peer_flags;
+                flags.is_outbound && !flags.is_protect && !flags.is_whitelist
+            })
+            .map(|kv_pair| {
+                let total_difficulty = kv_pair
+                    .value()
+                    .best_known_header
+                    .as_ref()
+                    .map(|header| header.total_difficulty().to_owned());
+                (*kv_pair.key(), total_difficulty)
+            })
+            .collect();
+        candidates.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+        warn!(
+            "tip has not advanced for {}s, rotating {} of {} outbound peers",
+            stale / 1000,
+            candidates.len().min(STALE_TIP_PEERS_TO_ROTATE),
+            candidates.len()
+        );
+        for (peer, _) in candidates.into_iter().take(STALE_TIP_PEERS_TO_ROTATE) {
+            info!("stale tip rotation peer={}", peer);
+            if let Err(err) = nc.disconnect(peer, "sync stale tip rotation") {
+                debug!("synchronizer disconnect error: {:?}", err);
+            }
+        }
+    }
+
     fn start_sync_headers(&self, nc: &dyn CKBProtocolContext) {
         let now = unix_time_as_millis();
         let active_chain = self.shared.active_chain();
@@ -518,17 +576,18 @@ impl Synchronizer {
         }
     }
 
+    // The peers are ordered by the expected block throughput, which prefers the peers with more
+    // download tasks and lower latency, the earlier peers are assigned the more urgent blocks.
+    // Every `FETCH_EXPLORE_INTERVAL` rounds, a peer in turn is moved to the front, so the peers
+    // ranked low still get blocks and their statistics are refreshed.
     fn get_peers_to_fetch(
         &self,
+        nc: &dyn CKBProtocolContext,
         ibd: IBDState,
         disconnect_list: &HashSet<PeerIndex>,
     ) -> Vec<PeerIndex> {
         trace!("poll find_blocks_to_fetch select peers");
-        let state = &self
-            .shared
-            .state()
-            .read_inflight_blocks()
-            .download_schedulers;
+        let inflight = self.shared.state().read_inflight_blocks();
         let mut peers: Vec<PeerIndex> = self
             .peers()
             .state
@@ -549,18 +608,20 @@ impl Synchronizer {
             })
             .map(|kv_pair| *kv_pair.key())
             .collect();
-        peers.sort_by_key(|id| {
-            ::std::cmp::Reverse(
-                state
-                    .get(id)
-                    .map_or(INIT_BLOCKS_IN_TRANSIT_PER_PEER, |d| d.task_count()),
-            )
+        peers.sort_by_cached_key(|id| {
+            let ping_rtt = nc.get_peer(*id).and_then(|peer| peer.ping_rtt);
+            ::std::cmp::Reverse(inflight.peer_fetch_score(*id, ping_rtt))
         });
+        if self.fetch_rounds % FETCH_EXPLORE_INTERVAL == 0 && !peers.is_empty() {
+            let explored = (self.fetch_rounds / FETCH_EXPLORE_INTERVAL) as usize % peers.len();
+            peers[..=explored].rotate_right(1);
+        }
         peers
     }
 
     fn find_blocks_to_fetch(&mut self, nc: &dyn CKBProtocolContext, ibd: IBDState) {
         let tip = self.shared.active_chain().tip_number();
+        self.fetch_rounds = self.fetch_rounds.wrapping_add(1);
 
         let disconnect_list = {
             let mut list = self.shared().state().write_inflight_blocks().prune(tip);
@@ -602,7 +663,7 @@ impl Synchronizer {
             Some(raw) => match self.fetch_channel {
                 Some(ref sender) => {
                     if !sender.is_full() {
-                        let peers = self.get_peers_to_fetch(ibd, &disconnect_list);
+                        let peers = self.get_peers_to_fetch(nc, ibd, &disconnect_list);
                         let _ignore = sender.try_send(FetchCMD::Fetch((peers, ibd)));
                     }
                 }
@@ -610,7 +671,7 @@ impl Synchronizer {
                     let p2p_control = raw.clone();
                     let sync = self.clone();
                     let (sender, recv) = channel::bounded(2);
-                    let peers = self.get_peers_to_fetch(ibd, &disconnect_list);
+                    let peers = self.get_peers_to_fetch(nc, ibd, &disconnect_list);
                     sender.send(FetchCMD::Fetch((peers, ibd))).unwrap();
                     self.fetch_channel = Some(sender);
                     let thread = ::std::thread::Builder::new();
@@ -631,7 +692,7 @@ impl Synchronizer {
                 }
             },
             None => {
-                for peer in self.get_peers_to_fetch(ibd, &disconnect_list) {
+                for peer in self.get_peers_to_fetch(nc, ibd, &disconnect_list) {
                     if let Some(fetch) = self.get_blocks_to_fetch(peer, ibd) {
                         for item in fetch {
                             self.send_getblocks(item, nc, peer);
@@ -669,6 +730,8 @@ impl CKBProtocolHandler for Synchronizer {
             .expect("set_notify at init is ok");
         nc.set_notify(NOT_IBD_BLOCK_FETCH_INTERVAL, NOT_IBD_BLOCK_FETCH_TOKEN)
             .expect("set_notify at init is ok");
+        nc.set_notify(STALE_TIP_CHECK_INTERVAL, STALE_TIP_CHECK_TOKEN)
+            .expect("set_notify at init is ok");
         nc.set_notify(Duration::from_secs(2), NO_PEER_CHECK_TOKEN)
             .expect("set_notify at init is ok");
     }
@@ -823,6 +886,9 @@ impl CKBProtocolHandler for Synchronizer {
                 TIMEOUT_EVICTION_TOKEN => {
                     self.eviction(nc.as_ref());
                 }
+                STALE_TIP_CHECK_TOKEN => {
+                    self.rotate_on_stale_tip(nc.as_ref());
+                }
                 // Here is just for NO_PEER_CHECK_TOKEN token, only handle it when there is no peer.
                 _ => {}
             }
@@ -841,7 +907,7 @@ impl CKBProtocolHandler for Synchronizer {
 #[cfg(test)]
 mod tests {
     use self::block_process::BlockProcess;
-    use self::headers_process::HeadersProcess;
+    use self::headers_process::{HeaderAcceptor, HeadersProcess, ValidationState};
     use super::*;
     use crate::{
         types::{HeaderView, HeadersSyncController, PeerState},
@@ -871,6 +937,7 @@ mod tests {
         U256,
     };
     use ckb_util::Mutex;
+    use ckb_verification::HeaderVerifier;
     use ckb_verification_traits::Switch;
     use futures::future::Future;
     use std::{
@@ -895,6 +962,7 @@ mod tests {
             shared.clone(),
             Default::default(),
             pack.take_relay_tx_receiver(),
+            pack.take_ask_for_txs_receiver(),
         ));
         let synchronizer = Synchronizer::new(chain_controller.clone(), sync_shared);
 
@@ -983,6 +1051,34 @@ mod tests {
             .expect("process block ok");
     }
 
+    #[test]
+    fn test_invalid_version_header_not_persisted() {
+        let (_chain_controller, shared, synchronizer) = start_chain(None);
+        let snapshot = shared.snapshot();
+        let parent = snapshot.tip_header().to_owned();
+        let epoch = snapshot
+            .consensus()
+            .next_epoch_ext(&parent, &snapshot.as_data_provider())
+            .unwrap()
+            .epoch();
+        let header = gen_block(&shared, &parent, &epoch, 1)
+            .header()
+            .as_advanced_builder()
+            .version(1u32.pack())
+            .build();
+
+        let sync_shared: &SyncShared = synchronizer.shared();
+        let verifier = HeaderVerifier::new(sync_shared, sync_shared.consensus());
+        let result =
+            HeaderAcceptor::new(&header, 1.into(), verifier, sync_shared.active_chain()).accept();
+        assert_eq!(result.state, ValidationState::Invalid);
+        assert!(sync_shared
+            .active_chain()
+            .contains_block_status(&header.hash(), BlockStatus::BLOCK_INVALID));
+        // The allowed versions may change, so the header is not recorded in the store
+        assert!(!shared.store().is_header_invalid(&header.hash()));
+    }
+
     #[test]
     fn test_locator() {
         let (chain_controller, shared, synchronizer) = start_chain(None);
@@ -1883,6 +1979,7 @@ mod tests {
             shared,
             Default::default(),
             pack.take_relay_tx_receiver(),
+            pack.take_ask_for_txs_receiver(),
         ));
 
         let mut chain_controller = ChainController::faux();
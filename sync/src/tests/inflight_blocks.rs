@@ -4,6 +4,7 @@ use ckb_types::prelude::*;
 use ckb_types::{h256, H256};
 use std::collections::HashSet;
 use std::iter::FromIterator;
+use std::time::Duration;
 
 #[test]
 fn inflight_blocks_count() {
@@ -198,3 +199,31 @@ fn inflight_trace_number_state() {
     assert_eq!(inflight_blocks.peer_can_fetch_count(3.into()), 8);
     assert_eq!(inflight_blocks.peer_can_fetch_count(4.into()), 8);
 }
+
+#[test]
+fn inflight_blocks_fetch_score() {
+    let mut inflight_blocks = InflightBlocks::default();
+
+    // the nearby peer is preferred before any block is received
+    let near = inflight_blocks.peer_fetch_score(1.into(), Some(Duration::from_millis(20)));
+    let far = inflight_blocks.peer_fetch_score(2.into(), Some(Duration::from_millis(300)));
+    assert!(near > far);
+
+    assert!(inflight_blocks.insert(1.into(), (1, h256!("0x1").pack()).into()));
+    assert_eq!(
+        inflight_blocks.peer_fetch_stats(1.into()).map(|stats| (
+            stats.inflight_count,
+            stats.received_count,
+            stats.average_response_time
+        )),
+        Some((1, 0, None))
+    );
+
+    // receive block 0x1
+    inflight_blocks.remove_by_block((1, h256!("0x1").pack()).into());
+    let stats = inflight_blocks.peer_fetch_stats(1.into()).unwrap();
+    assert_eq!(stats.inflight_count, 0);
+    assert_eq!(stats.received_count, 1);
+    assert!(stats.average_response_time.is_some());
+    assert_eq!(inflight_blocks.peer_fetch_stats(2.into()), None);
+}
@@ -2,11 +2,15 @@ use crate::block_status::BlockStatus;
 use crate::tests::util::{build_chain, inherit_block};
 use crate::SyncShared;
 use ckb_chain::chain::ChainService;
+use ckb_constant::sync::MAX_INVALID_HEADERS;
 use ckb_launcher::SharedBuilder;
 use ckb_store::{self, ChainStore};
 use ckb_test_chain_utils::always_success_cellbase;
 use ckb_types::core::{BlockBuilder, BlockView, Capacity};
+use ckb_types::packed::Byte32;
 use ckb_types::prelude::*;
+use ckb_types::H256;
+use faketime::unix_time_as_millis;
 use std::sync::Arc;
 
 #[test]
@@ -65,7 +69,12 @@ fn test_insert_parent_unknown_block() {
             chain_service.start::<&str>(None)
         };
         (
-            SyncShared::new(shared, Default::default(), pack.take_relay_tx_receiver()),
+            SyncShared::new(
+                shared,
+                Default::default(),
+                pack.take_relay_tx_receiver(),
+                pack.take_ask_for_txs_receiver(),
+            ),
             chain_controller,
         )
     };
@@ -204,3 +213,163 @@ fn test_switch_valid_fork() {
         );
     }
 }
+
+#[test]
+fn test_set_assume_valid_target() {
+    let (shared, _chain) = build_chain(2);
+    let tip_hash = shared.active_chain().tip_header().hash();
+    let next_block = inherit_block(shared.shared(), &tip_hash).build();
+    let stored: H256 = tip_hash.unpack();
+    let target: H256 = next_block.hash().unpack();
+
+    // The block of the target is already stored
+    assert!(shared.set_assume_valid_target(Some(stored)).is_err());
+    // The header of the target is unknown
+    assert!(shared
+        .set_assume_valid_target(Some(target.clone()))
+        .is_err());
+
+    shared.insert_valid_header(1.into(), &next_block.header());
+    shared
+        .set_assume_valid_target(Some(target.clone()))
+        .expect("set the target");
+    assert_eq!(*shared.state().assume_valid_target(), Some(target));
+
+    shared
+        .set_assume_valid_target(None)
+        .expect("clear the target");
+    assert!(shared.state().assume_valid_target().is_none());
+}
+
+#[test]
+fn test_set_assume_valid_target_on_side_fork() {
+    let (shared, _chain) = build_chain(2);
+    let tip_hash = shared.active_chain().tip_header().hash();
+    let main_block = inherit_block(shared.shared(), &tip_hash).build();
+    let main_child = main_block
+        .as_advanced_builder()
+        .parent_hash(main_block.hash())
+        .number((main_block.number() + 1).pack())
+        .timestamp((main_block.timestamp() + 1).pack())
+        .build();
+    let fork_block = inherit_block(shared.shared(), &tip_hash)
+        .timestamp((main_block.timestamp() + 1).pack())
+        .build();
+    shared.insert_valid_header(1.into(), &main_block.header());
+    shared.insert_valid_header(1.into(), &main_child.header());
+    shared.insert_valid_header(2.into(), &fork_block.header());
+    assert_eq!(
+        shared.state().shared_best_header().hash(),
+        main_child.hash()
+    );
+
+    // The target on the side fork is rejected
+    assert!(shared
+        .set_assume_valid_target(Some(fork_block.hash().unpack()))
+        .is_err());
+    assert!(shared.state().assume_valid_target().is_none());
+    let target: H256 = main_block.hash().unpack();
+    shared
+        .set_assume_valid_target(Some(target.clone()))
+        .expect("set the ancestor of the best header");
+
+    // The target younger than MAX_TIP_AGE is rejected
+    let recent_block = main_child
+        .as_advanced_builder()
+        .parent_hash(main_child.hash())
+        .number((main_child.number() + 1).pack())
+        .timestamp(unix_time_as_millis().pack())
+        .build();
+    shared.insert_valid_header(1.into(), &recent_block.header());
+    assert_eq!(
+        shared.state().shared_best_header().hash(),
+        recent_block.hash()
+    );
+    assert!(shared
+        .set_assume_valid_target(Some(recent_block.hash().unpack()))
+        .is_err());
+    assert_eq!(*shared.state().assume_valid_target(), Some(target));
+}
+
+#[test]
+fn test_invalid_header_persisted() {
+    let (shared, _chain) = build_chain(2);
+    let invalid_header = inherit_block(shared.shared(), &shared.active_chain().tip_hash())
+        .build()
+        .header();
+    let hash = invalid_header.hash();
+    assert_eq!(
+        shared.active_chain().get_block_status(&hash),
+        BlockStatus::UNKNOWN
+    );
+
+    shared.mark_header_invalid(hash.clone());
+    assert!(shared
+        .active_chain()
+        .contains_block_status(&hash, BlockStatus::BLOCK_INVALID));
+
+    // the marker is still there after the in-memory status is dropped, e.g. on restart
+    shared.state().remove_block_status(&hash);
+    assert!(shared.store().is_header_invalid(&hash));
+    assert!(shared
+        .active_chain()
+        .contains_block_status(&hash, BlockStatus::BLOCK_INVALID));
+}
+
+#[test]
+fn test_invalid_headers_bounded() {
+    let (shared, _chain) = build_chain(2);
+    let hashes: Vec<Byte32> = (0..=MAX_INVALID_HEADERS as u64)
+        .map(|i| {
+            let mut bytes = [0u8; 32];
+            bytes[..8].copy_from_slice(&i.to_le_bytes());
+            Byte32::new(bytes)
+        })
+        .collect();
+    for hash in &hashes {
+        shared.mark_header_invalid(hash.clone());
+    }
+
+    // The least recently marked one is dropped from the store
+    assert!(!shared.store().is_header_invalid(&hashes[0]));
+    assert!(hashes[1..]
+        .iter()
+        .all(|hash| shared.store().is_header_invalid(hash)));
+    assert_eq!(
+        shared.store().get_invalid_headers().len(),
+        MAX_INVALID_HEADERS
+    );
+}
+
+#[test]
+fn test_invalid_headers_cleared_on_version_change() {
+    let (shared, _chain) = build_chain(2);
+    let hash = Byte32::new([1; 32]);
+    shared.mark_header_invalid(hash.clone());
+
+    // The markers recorded by the same version are loaded on restart
+    let restarted = restart(&shared);
+    assert!(restarted.store().is_header_invalid(&hash));
+    assert!(restarted
+        .active_chain()
+        .contains_block_status(&hash, BlockStatus::BLOCK_INVALID));
+
+    // The markers recorded by another version are cleared on restart
+    shared.store().clear_invalid_headers("0.0.0").unwrap();
+    shared.store().insert_invalid_header(&hash).unwrap();
+    let restarted = restart(&shared);
+    assert!(!restarted.store().is_header_invalid(&hash));
+    assert_eq!(
+        restarted.active_chain().get_block_status(&hash),
+        BlockStatus::UNKNOWN
+    );
+}
+
+fn restart(shared: &SyncShared) -> SyncShared {
+    SyncShared::new(
+        shared.shared().clone(),
+        Default::default(),
+        ckb_channel::unbounded().1,
+        ckb_channel::unbounded().1,
+    )
+}
@@ -174,6 +174,7 @@ fn setup_node(height: u64) -> (TestNode, Shared) {
         shared.clone(),
         Default::default(),
         pack.take_relay_tx_receiver(),
+        pack.take_ask_for_txs_receiver(),
     ));
     let synchronizer = Synchronizer::new(chain_controller, sync_shared);
     let mut node = TestNode::default();
@@ -24,7 +24,12 @@ pub fn build_chain(tip: BlockNumber) -> (SyncShared, ChainController) {
         chain_service.start::<&str>(None)
     };
     generate_blocks(&shared, &chain_controller, tip);
-    let sync_shared = SyncShared::new(shared, Default::default(), pack.take_relay_tx_receiver());
+    let sync_shared = SyncShared::new(
+        shared,
+        Default::default(),
+        pack.take_relay_tx_receiver(),
+        pack.take_ask_for_txs_receiver(),
+    );
     (sync_shared, chain_controller)
 }
 
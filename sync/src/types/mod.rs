@@ -1,26 +1,31 @@
 use crate::block_status::BlockStatus;
+use crate::net_time_checker::NetTime;
 use crate::orphan_block_pool::OrphanBlockPool;
 use crate::utils::is_internal_db_error;
-use crate::{FAST_INDEX, LOW_INDEX, NORMAL_INDEX, TIME_TRACE_SIZE};
+use crate::{
+    FAST_INDEX, LOW_INDEX, NORMAL_INDEX, RESPONSE_TIME_AVERAGE_WEIGHT, TIME_TRACE_SIZE,
+    UNKNOWN_PING_RTT,
+};
 use ckb_app_config::SyncConfig;
 use ckb_chain::chain::ChainController;
 use ckb_chain_spec::consensus::Consensus;
 use ckb_channel::Receiver;
 use ckb_constant::sync::{
     BLOCK_DOWNLOAD_TIMEOUT, HEADERS_DOWNLOAD_HEADERS_PER_SECOND, HEADERS_DOWNLOAD_INSPECT_WINDOW,
-    HEADERS_DOWNLOAD_TOLERABLE_BIAS_FOR_SINGLE_SAMPLE, INIT_BLOCKS_IN_TRANSIT_PER_PEER,
-    MAX_BLOCKS_IN_TRANSIT_PER_PEER, MAX_HEADERS_LEN, MAX_OUTBOUND_PEERS_TO_PROTECT_FROM_DISCONNECT,
-    POW_INTERVAL, RETRY_ASK_TX_TIMEOUT_INCREASE, SUSPEND_SYNC_TIME,
+    HEADERS_DOWNLOAD_TOLERABLE_BIAS_FOR_SINGLE_SAMPLE, HEADER_CHECKPOINT_INTERVAL,
+    INIT_BLOCKS_IN_TRANSIT_PER_PEER, MAX_BLOCKS_IN_TRANSIT_PER_PEER, MAX_HEADERS_LEN,
+    MAX_INVALID_HEADERS, MAX_OUTBOUND_PEERS_TO_PROTECT_FROM_DISCONNECT, MAX_TIP_AGE, POW_INTERVAL,
+    RETRY_ASK_TX_TIMEOUT_INCREASE, SUSPEND_SYNC_TIME,
 };
 use ckb_error::Error as CKBError;
-use ckb_logger::{debug, error, trace};
+use ckb_logger::{debug, error, info, trace};
 use ckb_metrics::metrics;
 use ckb_network::{CKBProtocolContext, PeerIndex, SupportProtocols};
 use ckb_shared::{shared::Shared, Snapshot};
-use ckb_store::{ChainDB, ChainStore};
+use ckb_store::{ChainDB, ChainStore, HeaderCheckpoint};
 use ckb_traits::HeaderProvider;
 use ckb_types::{
-    core::{self, BlockNumber, EpochExt},
+    core::{self, BlockNumber, EpochExt, FeeRate},
     packed::{self, Byte32},
     prelude::*,
     H256, U256,
@@ -40,9 +45,17 @@ use std::time::{Duration, Instant};
 use std::{cmp, fmt, iter};
 
 mod header_map;
+mod progress;
+mod tip_watchdog;
+mod tx_announcer;
 
 use crate::utils::send_message;
 pub use header_map::HeaderMapLru as HeaderMap;
+use progress::ProgressTracker;
+pub use progress::SyncProgress;
+use tip_watchdog::TipWatchdog;
+use tx_announcer::TxAnnouncer;
+pub(crate) use tx_announcer::MIN_ANNOUNCE_INTERVAL;
 
 const FILTER_SIZE: usize = 20000;
 const MAX_UNKNOWN_TX_HASHES_SIZE: usize = 50000;
@@ -54,6 +67,8 @@ const ORPHAN_BLOCK_SIZE: usize = 1024;
 // 2 ** 13 < 6 * 1800 < 2 ** 14
 const ONE_DAY_BLOCK_NUMBER: u64 = 8192;
 const SHRINK_THRESHOLD: usize = 300;
+// The invalid headers recorded by the other versions are cleared on startup
+const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // State used to enforce CHAIN_SYNC_TIMEOUT
 // Only in effect for connections that are outbound, non-manual,
@@ -262,6 +277,9 @@ pub struct PeerState {
     // use on ibd concurrent block download
     // save `get_headers` locator hashes here
     pub unknown_header_list: Vec<Byte32>,
+    // The min fee rate of the transactions the peer wants to be announced, set by its
+    // RelayFeeFilter message
+    pub fee_filter: Option<FeeRate>,
 }
 
 impl PeerState {
@@ -273,6 +291,7 @@ impl PeerState {
             best_known_header: None,
             last_common_header: None,
             unknown_header_list: Vec::new(),
+            fee_filter: None,
         }
     }
 
@@ -469,6 +488,9 @@ pub struct DownloadScheduler {
     task_count: usize,
     timeout_count: usize,
     hashes: HashSet<BlockNumberAndHash>,
+    received_count: u64,
+    // The moving average of the block response time, unit ms
+    average_response_time: Option<u64>,
 }
 
 impl Default for DownloadScheduler {
@@ -477,10 +499,25 @@ impl Default for DownloadScheduler {
             hashes: HashSet::default(),
             task_count: INIT_BLOCKS_IN_TRANSIT_PER_PEER,
             timeout_count: 0,
+            received_count: 0,
+            average_response_time: None,
         }
     }
 }
 
+/// The block download statistics of a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerFetchStats {
+    /// The number of the blocks the peer can download at the same time.
+    pub task_count: usize,
+    /// The number of the blocks the peer is downloading.
+    pub inflight_count: usize,
+    /// The number of the blocks received from the peer.
+    pub received_count: u64,
+    /// The moving average of the block response time, unit ms.
+    pub average_response_time: Option<u64>,
+}
+
 impl DownloadScheduler {
     fn inflight_count(&self) -> usize {
         self.hashes.len()
@@ -490,10 +527,6 @@ impl DownloadScheduler {
         self.task_count.saturating_sub(self.hashes.len())
     }
 
-    pub(crate) const fn task_count(&self) -> usize {
-        self.task_count
-    }
-
     fn increase(&mut self, num: usize) {
         if self.task_count < MAX_BLOCKS_IN_TRANSIT_PER_PEER {
             self.task_count = ::std::cmp::min(
@@ -514,6 +547,17 @@ impl DownloadScheduler {
     fn punish(&mut self, exp: usize) {
         self.task_count >>= exp
     }
+
+    fn record_response(&mut self, elapsed: u64) {
+        self.received_count += 1;
+        self.average_response_time = Some(match self.average_response_time {
+            Some(average) => {
+                (average * (RESPONSE_TIME_AVERAGE_WEIGHT - 1) + elapsed)
+                    / RESPONSE_TIME_AVERAGE_WEIGHT
+            }
+            None => elapsed,
+        });
+    }
 }
 
 #[derive(Clone)]
@@ -604,6 +648,35 @@ impl InflightBlocks {
         )
     }
 
+    pub fn peer_fetch_stats(&self, peer: PeerIndex) -> Option<PeerFetchStats> {
+        self.download_schedulers.get(&peer).map(|d| PeerFetchStats {
+            task_count: d.task_count,
+            inflight_count: d.inflight_count(),
+            received_count: d.received_count,
+            average_response_time: d.average_response_time,
+        })
+    }
+
+    /// Returns the expected block throughput of the peer, in blocks per 1000 seconds, which ranks
+    /// the peers to fetch blocks from.
+    ///
+    /// The expected latency is the ping RTT plus the average block response time, so the nearby
+    /// peers are preferred even before they have responded any block. The peers without a
+    /// response yet are assumed to have the normal response time of all the peers.
+    pub fn peer_fetch_score(&self, peer: PeerIndex, ping_rtt: Option<Duration>) -> u64 {
+        let (task_count, average_response_time) = self
+            .download_schedulers
+            .get(&peer)
+            .map_or((INIT_BLOCKS_IN_TRANSIT_PER_PEER, None), |d| {
+                (d.task_count, d.average_response_time)
+            });
+        let latency = ping_rtt
+            .map_or(UNKNOWN_PING_RTT, |rtt| rtt.as_millis() as u64)
+            .saturating_add(average_response_time.unwrap_or(self.time_analyzer.normal_time))
+            .max(1);
+        (task_count as u64).saturating_mul(1_000_000) / latency
+    }
+
     pub fn inflight_block_by_peer(&self, peer: PeerIndex) -> Option<&HashSet<BlockNumberAndHash>> {
         self.download_schedulers.get(&peer).map(|d| &d.hashes)
     }
@@ -807,6 +880,7 @@ impl InflightBlocks {
                 let elapsed = unix_time_as_millis().saturating_sub(state.timestamp);
                 if let Some(set) = download_schedulers.get_mut(&state.peer) {
                     set.hashes.remove(&block);
+                    set.record_response(elapsed);
                     if !compact.is_empty() {
                         compact.remove(&block.hash);
                     }
@@ -856,6 +930,23 @@ impl Peers {
             .or_insert_with(|| PeerState::new(PeerFlags::default()));
     }
 
+    pub fn set_fee_filter(&self, peer: PeerIndex, fee_rate: FeeRate) {
+        if let Some(mut peer_state) = self.state.get_mut(&peer) {
+            peer_state.fee_filter = Some(fee_rate);
+        }
+    }
+
+    /// Returns the fee filters of the peers which have sent one
+    pub fn fee_filters(&self) -> HashMap<PeerIndex, FeeRate> {
+        self.state
+            .iter()
+            .filter_map(|kv_pair| {
+                let (peer, state) = kv_pair.pair();
+                state.fee_filter.map(|fee_rate| (*peer, fee_rate))
+            })
+            .collect()
+    }
+
     pub fn get_best_known_header(&self, pi: PeerIndex) -> Option<HeaderView> {
         self.state
             .get(&pi)
@@ -1164,9 +1255,16 @@ impl SyncShared {
     pub fn new(
         shared: Shared,
         sync_config: SyncConfig,
-        tx_relay_receiver: Receiver<(Option<PeerIndex>, bool, Byte32)>,
+        tx_relay_receiver: Receiver<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+        ask_for_txs_receiver: Receiver<(PeerIndex, Vec<Byte32>)>,
     ) -> SyncShared {
-        Self::with_tmpdir::<PathBuf>(shared, sync_config, None, tx_relay_receiver)
+        Self::with_tmpdir::<PathBuf>(
+            shared,
+            sync_config,
+            None,
+            tx_relay_receiver,
+            ask_for_txs_receiver,
+        )
     }
 
     /// Generate a global sync state through configuration
@@ -1174,7 +1272,8 @@ impl SyncShared {
         shared: Shared,
         sync_config: SyncConfig,
         tmpdir: Option<P>,
-        tx_relay_receiver: Receiver<(Option<PeerIndex>, bool, Byte32)>,
+        tx_relay_receiver: Receiver<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+        ask_for_txs_receiver: Receiver<(PeerIndex, Vec<Byte32>)>,
     ) -> SyncShared
     where
         P: AsRef<Path>,
@@ -1186,6 +1285,56 @@ impl SyncShared {
                 snapshot.tip_header().to_owned(),
             )
         };
+        let header_checkpoint = {
+            let store = shared.store();
+            let spec_hash = store.get_chain_spec_hash().unwrap_or_default();
+            let assume_valid_target = sync_config.assume_valid_target.as_ref().map(Pack::pack);
+            match store.get_header_checkpoint() {
+                Some(checkpoint)
+                    if checkpoint.matches(&spec_hash, assume_valid_target.as_ref()) =>
+                {
+                    info!(
+                        "headers verified up to {} with total difficulty {:#x}",
+                        checkpoint.number, checkpoint.total_difficulty
+                    );
+                    checkpoint
+                }
+                stale => {
+                    if stale.is_some() {
+                        info!("the chain spec or the assume-valid target is changed, clear the header checkpoint");
+                        if let Err(err) = store.clear_header_checkpoint() {
+                            error!("clear the header checkpoint error {:?}", err);
+                        }
+                        // The headers are judged by the other rules now
+                        if let Err(err) = store.clear_invalid_headers(CLIENT_VERSION) {
+                            error!("clear the invalid headers error {:?}", err);
+                        }
+                    }
+                    HeaderCheckpoint {
+                        spec_hash,
+                        assume_valid_target,
+                        number: header.number(),
+                        hash: header.hash(),
+                        total_difficulty: total_difficulty.clone(),
+                    }
+                }
+            }
+        };
+        let invalid_headers = {
+            let store = shared.store();
+            // A new client may fix the header verification, let it judge the headers again
+            if store.get_invalid_headers_version().as_deref() != Some(CLIENT_VERSION) {
+                info!("the client version is changed, clear the invalid headers");
+                if let Err(err) = store.clear_invalid_headers(CLIENT_VERSION) {
+                    error!("clear the invalid headers error {:?}", err);
+                }
+            }
+            let mut invalid_headers = LruCache::new(MAX_INVALID_HEADERS);
+            for hash in store.get_invalid_headers() {
+                invalid_headers.put(hash, ());
+            }
+            invalid_headers
+        };
         let shared_best_header = RwLock::new(HeaderView::new(header, total_difficulty));
         let header_map = HeaderMap::new(
             tmpdir,
@@ -1210,8 +1359,15 @@ impl SyncShared {
             inflight_blocks: RwLock::new(InflightBlocks::default()),
             pending_get_headers: RwLock::new(LruCache::new(GET_HEADERS_CACHE_SIZE)),
             tx_relay_receiver,
+            ask_for_txs_receiver,
             assume_valid_target: Mutex::new(sync_config.assume_valid_target),
             min_chain_work: sync_config.min_chain_work,
+            progress_tracker: Mutex::new(ProgressTracker::default()),
+            net_time: NetTime::new(Duration::from_millis(sync_config.clock_skew_threshold)),
+            tip_watchdog: Mutex::new(TipWatchdog::new(sync_config.stale_tip_timeout)),
+            tx_announcer: Mutex::new(TxAnnouncer::new(sync_config.tx_announce_bytes_per_sec)),
+            header_checkpoint: Mutex::new(header_checkpoint),
+            invalid_headers: Mutex::new(invalid_headers),
         };
 
         SyncShared {
@@ -1249,12 +1405,41 @@ impl SyncShared {
         self.shared.consensus()
     }
 
+    /// Get the IBD progress, including the tips, download and verify rates and the ETA
+    pub fn sync_progress(&self) -> SyncProgress {
+        let header_tip = self.state.shared_best_header_ref().number();
+        let block_tip = self.shared.snapshot().tip_number();
+        self.state
+            .progress_tracker
+            .lock()
+            .progress(header_tip, block_tip, unix_time_as_millis())
+    }
+
+    /// Get the time offsets from the network peers
+    pub fn net_time(&self) -> &NetTime {
+        &self.state.net_time
+    }
+
+    /// Get how long the tip has been stale when it exceeds `sync.stale_tip_timeout`, unit ms
+    pub fn check_stale_tip(&self) -> Option<u64> {
+        let tip_hash = self.shared.snapshot().tip_hash();
+        self.state
+            .tip_watchdog
+            .lock()
+            .check(&tip_hash, unix_time_as_millis())
+    }
+
     /// Insert new block to chain store
     pub fn insert_new_block(
         &self,
         chain: &ChainController,
         block: Arc<core::BlockView>,
     ) -> Result<bool, CKBError> {
+        self.state
+            .progress_tracker
+            .lock()
+            .record_downloaded(block.number(), unix_time_as_millis());
+
         // Insert the given block into orphan_block_pool if its parent is not found
         if !self.is_parent_stored(&block) {
             debug!(
@@ -1347,11 +1532,56 @@ impl SyncShared {
             // status via fetching block_ext from the database.
             self.state.remove_block_status(&block.as_ref().hash());
             self.state.remove_header_view(&block.as_ref().hash());
+            self.state
+                .progress_tracker
+                .lock()
+                .record_verified(unix_time_as_millis());
         }
 
         ret
     }
 
+    /// Updates or clears the assume-valid target while the node is running.
+    ///
+    /// It takes effect for the blocks which are not verified yet, and it is not persisted. Returns
+    /// an error when the header of the target is unknown, is not an ancestor of the best known
+    /// header, or is younger than `MAX_TIP_AGE`, or when the block of the target is already
+    /// stored.
+    pub fn set_assume_valid_target(&self, target: Option<H256>) -> Result<(), String> {
+        if let Some(ref hash) = target {
+            let block_hash = hash.pack();
+            if self.store().get_block_header(&block_hash).is_some() {
+                return Err(format!("block {:#x} is already stored", hash));
+            }
+            let header = self
+                .state
+                .header_map
+                .get(&block_hash)
+                .ok_or_else(|| format!("header {:#x} is unknown", hash))?;
+            // Blocks that are no longer in the scope of ibd must be forced to verify
+            if unix_time_as_millis().saturating_sub(header.timestamp()) < MAX_TIP_AGE {
+                return Err(format!(
+                    "header {:#x} is too recent to skip the script verification",
+                    hash
+                ));
+            }
+            // The scripts on a side fork must not be skipped
+            let best_hash = self.state.shared_best_header_ref().hash();
+            let ancestor = self
+                .active_chain()
+                .get_ancestor(&best_hash, header.number());
+            if ancestor.map(|ancestor| ancestor.hash()) != Some(block_hash) {
+                return Err(format!(
+                    "header {:#x} is not an ancestor of the best known header {}",
+                    hash, best_hash
+                ));
+            }
+        }
+        info!("update the assume-valid target to {:?}", target);
+        *self.state.assume_valid_target() = target;
+        Ok(())
+    }
+
     /// Sync a new valid header, try insert to sync state
     // Update the header_map
     // Update the block_status_map
@@ -1392,6 +1622,67 @@ impl SyncShared {
         self.state.may_set_shared_best_header(header_view);
     }
 
+    /// Whether the header has been verified by the header sync, including the runs before restart.
+    ///
+    /// The PoW of the header and its ancestors need not be verified again. Only the headers at
+    /// the checkpoint interval are recorded, the others are never verified by this check.
+    pub fn is_header_verified(&self, header: &core::HeaderView) -> bool {
+        header.number() % HEADER_CHECKPOINT_INTERVAL == 0
+            && self
+                .store()
+                .is_header_verified(header.number(), &header.hash())
+    }
+
+    /// Marks the header as the root of an invalid header branch, both in memory and in the store.
+    ///
+    /// Only the headers failing the context-free verification themselves, such as the PoW, should
+    /// be marked. Their descendants are rejected by the parent check, so they are kept in memory
+    /// only and a peer cannot fill the store with the headers on an invalid branch. At most
+    /// `MAX_INVALID_HEADERS` markers are kept, the least recently marked one is dropped first.
+    pub fn mark_header_invalid(&self, hash: Byte32) {
+        let mut invalid_headers = self.state.invalid_headers.lock();
+        if !invalid_headers.contains(&hash) && invalid_headers.len() >= MAX_INVALID_HEADERS {
+            if let Some((evicted, _)) = invalid_headers.pop_lru() {
+                if let Err(err) = self.store().delete_invalid_header(&evicted) {
+                    error!("remove the invalid header {} error {:?}", evicted, err);
+                }
+            }
+        }
+        if let Err(err) = self.store().insert_invalid_header(&hash) {
+            error!("record the invalid header {} error {:?}", hash, err);
+        }
+        invalid_headers.put(hash.clone(), ());
+        self.state
+            .insert_block_status(hash, BlockStatus::BLOCK_INVALID);
+    }
+
+    /// Records the continuous headers accepted by the header sync into the header checkpoint.
+    ///
+    /// Only the headers at the checkpoint interval are recorded, because they commit to their
+    /// ancestors. It bounds both the recorded keys and the lookups per headers message.
+    pub fn record_verified_headers(&self, headers: &[core::HeaderView]) {
+        let verified = headers
+            .iter()
+            .filter(|header| header.number() % HEADER_CHECKPOINT_INTERVAL == 0)
+            .map(|header| (header.number(), header.hash()))
+            .collect::<Vec<_>>();
+        let mut checkpoint = self.state.header_checkpoint.lock();
+        {
+            let best = self.state.shared_best_header_ref();
+            if best.is_better_than(&checkpoint.total_difficulty) {
+                checkpoint.number = best.number();
+                checkpoint.hash = best.hash();
+                checkpoint.total_difficulty = best.total_difficulty().to_owned();
+            }
+        }
+        if let Err(err) = self
+            .store()
+            .insert_header_checkpoint(&checkpoint, &verified)
+        {
+            error!("record the header checkpoint error {:?}", err);
+        }
+    }
+
     /// Get header view with hash
     pub fn get_header_view(
         &self,
@@ -1524,9 +1815,25 @@ pub struct SyncState {
     inflight_blocks: RwLock<InflightBlocks>,
 
     /* cached for sending bulk */
-    tx_relay_receiver: Receiver<(Option<PeerIndex>, bool, Byte32)>,
+    tx_relay_receiver: Receiver<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+    /* tx hashes waiting to be announced to the peers */
+    tx_announcer: Mutex<TxAnnouncer>,
+    /* missing parents of the orphan transactions, requested from the announcing peers */
+    ask_for_txs_receiver: Receiver<(PeerIndex, Vec<Byte32>)>,
     assume_valid_target: Mutex<Option<H256>>,
     min_chain_work: U256,
+
+    /* IBD progress statistics */
+    progress_tracker: Mutex<ProgressTracker>,
+    tip_watchdog: Mutex<TipWatchdog>,
+
+    /* time offsets from the network peers */
+    net_time: NetTime,
+
+    /* the best header verified by the header sync, persisted across restarts */
+    header_checkpoint: Mutex<HeaderCheckpoint>,
+    /// The invalid headers recorded in the store, in the order of the rejection
+    invalid_headers: Mutex<LruCache<Byte32, ()>>,
 }
 
 impl SyncState {
@@ -1576,10 +1883,17 @@ impl SyncState {
         &self.inflight_proposals
     }
 
-    pub fn take_relay_tx_hashes(&self, limit: usize) -> Vec<(Option<PeerIndex>, bool, Byte32)> {
+    pub fn take_relay_tx_hashes(
+        &self,
+        limit: usize,
+    ) -> Vec<(Option<PeerIndex>, bool, Byte32, FeeRate)> {
         self.tx_relay_receiver.try_iter().take(limit).collect()
     }
 
+    pub(crate) fn tx_announcer(&self) -> MutexGuard<TxAnnouncer> {
+        self.tx_announcer.lock()
+    }
+
     pub fn shared_best_header(&self) -> HeaderView {
         self.shared_best_header.read().to_owned()
     }
@@ -1636,6 +1950,17 @@ impl SyncState {
     }
 
     pub fn pop_ask_for_txs(&self) -> HashMap<PeerIndex, Vec<Byte32>> {
+        for (peer_index, tx_hashes) in self.ask_for_txs_receiver.try_iter() {
+            let tx_hashes = {
+                let tx_filter = self.tx_filter.lock();
+                tx_hashes
+                    .into_iter()
+                    .filter(|hash| !tx_filter.contains(hash))
+                    .collect()
+            };
+            self.add_ask_for_txs(peer_index, tx_hashes);
+        }
+
         let mut unknown_tx_hashes = self.unknown_tx_hashes.lock();
         let mut result: HashMap<PeerIndex, Vec<Byte32>> = HashMap::new();
         let now = Instant::now();
@@ -1771,6 +2096,7 @@ impl SyncState {
 
     pub fn disconnected(&self, pi: PeerIndex) -> Option<PeerState> {
         self.known_txs().inner.remove(&pi);
+        self.tx_announcer().remove_peer(pi);
         self.write_inflight_blocks().remove_by_peer(pi);
         self.peers().disconnected(pi)
     }
@@ -1834,6 +2160,10 @@ impl ActiveChain {
         self.store().get_block(h)
     }
 
+    pub fn get_blocks(&self, hashes: &[packed::Byte32]) -> Vec<Option<core::BlockView>> {
+        self.store().get_blocks(hashes)
+    }
+
     pub fn get_block_header(&self, h: &packed::Byte32) -> Option<core::HeaderView> {
         self.store().get_block_header(h)
     }
@@ -2081,6 +2411,10 @@ impl ActiveChain {
                         .get_block_ext(block_hash)
                         .map(|block_ext| block_ext.verified);
                     match verified {
+                        // the invalid header branches recorded before restart
+                        None if self.store().is_header_invalid(block_hash) => {
+                            BlockStatus::BLOCK_INVALID
+                        }
                         None => BlockStatus::UNKNOWN,
                         Some(None) => BlockStatus::BLOCK_STORED,
                         Some(Some(true)) => BlockStatus::BLOCK_VALID,
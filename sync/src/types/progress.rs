@@ -0,0 +1,132 @@
+use ckb_types::core::BlockNumber;
+use std::collections::VecDeque;
+
+// The sliding window used to measure the download and verify rates, unit ms
+const RATE_WINDOW: u64 = 60 * 1000;
+// Limit the samples kept in a window, blocks are hardly processed faster than this during IBD
+const MAX_RATE_SAMPLES: usize = 1 << 16;
+
+/// The IBD progress of the local node, see `SyncShared::sync_progress`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncProgress {
+    /// The number of the best known header
+    pub header_tip: BlockNumber,
+    /// The number of the tip block in the local chain, which has been verified
+    pub block_tip: BlockNumber,
+    /// The number of the highest downloaded block, which may still wait for verification
+    pub unverified_tip: BlockNumber,
+    /// Blocks downloaded in the last minute
+    pub download_rate: u64,
+    /// Blocks verified in the last minute
+    pub verify_rate: u64,
+    /// The estimated time to reach the header tip at the current verify rate, unit ms
+    ///
+    /// It is `None` when no block has been verified in the last minute.
+    pub estimated_time_left: Option<u64>,
+}
+
+#[derive(Default)]
+struct RateMeter {
+    samples: VecDeque<u64>,
+}
+
+impl RateMeter {
+    fn record(&mut self, now: u64) {
+        if self.samples.len() >= MAX_RATE_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(now);
+        self.evict(now);
+    }
+
+    fn evict(&mut self, now: u64) {
+        while let Some(ts) = self.samples.front() {
+            if now.saturating_sub(*ts) >= RATE_WINDOW {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    // Count of samples in the last `RATE_WINDOW`
+    fn rate(&mut self, now: u64) -> u64 {
+        self.evict(now);
+        self.samples.len() as u64
+    }
+}
+
+/// Tracks downloaded and verified blocks to estimate the IBD progress.
+#[derive(Default)]
+pub(crate) struct ProgressTracker {
+    downloaded: RateMeter,
+    verified: RateMeter,
+    unverified_tip: BlockNumber,
+}
+
+impl ProgressTracker {
+    pub(crate) fn record_downloaded(&mut self, number: BlockNumber, now: u64) {
+        self.downloaded.record(now);
+        self.unverified_tip = self.unverified_tip.max(number);
+    }
+
+    pub(crate) fn record_verified(&mut self, now: u64) {
+        self.verified.record(now);
+    }
+
+    pub(crate) fn progress(
+        &mut self,
+        header_tip: BlockNumber,
+        block_tip: BlockNumber,
+        now: u64,
+    ) -> SyncProgress {
+        let download_rate = self.downloaded.rate(now);
+        let verify_rate = self.verified.rate(now);
+        let estimated_time_left = if verify_rate == 0 {
+            None
+        } else {
+            Some(header_tip.saturating_sub(block_tip) * RATE_WINDOW / verify_rate)
+        };
+        SyncProgress {
+            header_tip,
+            block_tip,
+            unverified_tip: self.unverified_tip.max(block_tip),
+            download_rate,
+            verify_rate,
+            estimated_time_left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_rates_and_eta() {
+        let mut tracker = ProgressTracker::default();
+        let progress = tracker.progress(100, 10, 0);
+        assert_eq!(progress.unverified_tip, 10);
+        assert_eq!(progress.estimated_time_left, None);
+
+        for number in 11..=40 {
+            tracker.record_downloaded(number, number * 1000);
+        }
+        for number in 11..=30 {
+            tracker.record_verified(number * 1000);
+        }
+
+        let progress = tracker.progress(100, 30, 40_000);
+        assert_eq!(progress.unverified_tip, 40);
+        assert_eq!(progress.download_rate, 30);
+        assert_eq!(progress.verify_rate, 20);
+        // 70 blocks left at 20 blocks per minute
+        assert_eq!(progress.estimated_time_left, Some(70 * RATE_WINDOW / 20));
+
+        // samples out of the window are evicted
+        let progress = tracker.progress(100, 30, 40_000 + RATE_WINDOW);
+        assert_eq!(progress.download_rate, 0);
+        assert_eq!(progress.verify_rate, 0);
+        assert_eq!(progress.estimated_time_left, None);
+    }
+}
@@ -0,0 +1,73 @@
+use ckb_types::packed::Byte32;
+
+/// Detects that the tip of the local chain has not advanced for a while.
+pub(crate) struct TipWatchdog {
+    // Unit ms, 0 disables the watchdog
+    timeout: u64,
+    tip_hash: Byte32,
+    last_advanced_at: u64,
+}
+
+impl TipWatchdog {
+    pub(crate) fn new(timeout: u64) -> Self {
+        TipWatchdog {
+            timeout,
+            tip_hash: Byte32::zero(),
+            last_advanced_at: 0,
+        }
+    }
+
+    /// Returns how long the tip has been stale when it exceeds the timeout, unit ms.
+    ///
+    /// The timer restarts after reporting, so the stale tip is reported once per timeout.
+    pub(crate) fn check(&mut self, tip_hash: &Byte32, now: u64) -> Option<u64> {
+        if self.timeout == 0 {
+            return None;
+        }
+        if &self.tip_hash != tip_hash {
+            self.tip_hash = tip_hash.clone();
+            self.last_advanced_at = now;
+            return None;
+        }
+        let stale = now.saturating_sub(self.last_advanced_at);
+        if stale >= self.timeout {
+            self.last_advanced_at = now;
+            Some(stale)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_types::{h256, prelude::*};
+
+    #[test]
+    fn test_tip_watchdog() {
+        let tip_a = h256!("0x1").pack();
+        let tip_b = h256!("0x2").pack();
+        let mut watchdog = TipWatchdog::new(1000);
+
+        assert_eq!(watchdog.check(&tip_a, 0), None);
+        assert_eq!(watchdog.check(&tip_a, 999), None);
+        assert_eq!(watchdog.check(&tip_a, 1000), Some(1000));
+        // reported once per timeout
+        assert_eq!(watchdog.check(&tip_a, 1500), None);
+        assert_eq!(watchdog.check(&tip_a, 2000), Some(1000));
+
+        // the timer restarts when the tip advances
+        assert_eq!(watchdog.check(&tip_b, 2500), None);
+        assert_eq!(watchdog.check(&tip_b, 3400), None);
+        assert_eq!(watchdog.check(&tip_b, 3500), Some(1000));
+    }
+
+    #[test]
+    fn test_tip_watchdog_disabled() {
+        let tip = h256!("0x1").pack();
+        let mut watchdog = TipWatchdog::new(0);
+        assert_eq!(watchdog.check(&tip, 0), None);
+        assert_eq!(watchdog.check(&tip, u64::MAX), None);
+    }
+}
@@ -0,0 +1,235 @@
+use ckb_network::PeerIndex;
+use ckb_types::packed::Byte32;
+use std::cmp;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// The size of an announced transaction hash in bytes
+const HASH_SIZE: u64 = 32;
+/// The shortest interval between two announcements to a peer
+pub(crate) const MIN_ANNOUNCE_INTERVAL: Duration = Duration::from_millis(100);
+/// The longest interval between two announcements to a peer, also bounds the burst of the budget
+const MAX_ANNOUNCE_INTERVAL: Duration = Duration::from_secs(2);
+/// The max number of hashes waiting to be announced to a peer
+const MAX_PENDING_HASHES: usize = 16_384;
+
+/// Batches the transaction hashes announced to the peers within a per-peer bandwidth budget.
+///
+/// The interval between two announcements to a peer doubles when the budget cannot drain the
+/// queued hashes, so the surges are sent in fewer and larger batches, and it halves when the queue
+/// is drained.
+pub(crate) struct TxAnnouncer {
+    // Unit bytes per second, 0 disables the budget
+    bytes_per_sec: u64,
+    peers: HashMap<PeerIndex, PeerAnnouncements>,
+}
+
+struct PeerAnnouncements {
+    pending: VecDeque<Byte32>,
+    // The bytes which can be sent now
+    allowance: u64,
+    refilled_at: Instant,
+    interval: Duration,
+    next_announce_at: Instant,
+}
+
+impl PeerAnnouncements {
+    fn new(allowance: u64, now: Instant) -> Self {
+        PeerAnnouncements {
+            pending: VecDeque::new(),
+            allowance,
+            refilled_at: now,
+            interval: MIN_ANNOUNCE_INTERVAL,
+            next_announce_at: now,
+        }
+    }
+}
+
+impl TxAnnouncer {
+    pub(crate) fn new(bytes_per_sec: u64) -> Self {
+        TxAnnouncer {
+            bytes_per_sec,
+            peers: HashMap::default(),
+        }
+    }
+
+    fn max_allowance(&self) -> u64 {
+        self.bytes_per_sec
+            .saturating_mul(MAX_ANNOUNCE_INTERVAL.as_millis() as u64)
+            / 1000
+    }
+
+    /// Queues the hash to be announced to the peer.
+    ///
+    /// Returns false when the hash is dropped since the queue of the peer is full.
+    pub(crate) fn push(&mut self, peer: PeerIndex, hash: Byte32, now: Instant) -> bool {
+        let max_allowance = self.max_allowance();
+        let announcements = self
+            .peers
+            .entry(peer)
+            .or_insert_with(|| PeerAnnouncements::new(max_allowance, now));
+        if announcements.pending.len() >= MAX_PENDING_HASHES {
+            return false;
+        }
+        announcements.pending.push_back(hash);
+        true
+    }
+
+    /// Takes the batches which are due, each batch has at most `max_batch_size` hashes.
+    pub(crate) fn pop_due(
+        &mut self,
+        now: Instant,
+        max_batch_size: usize,
+    ) -> Vec<(PeerIndex, Vec<Byte32>)> {
+        let bytes_per_sec = self.bytes_per_sec;
+        let max_allowance = self.max_allowance();
+        let mut batches = Vec::new();
+        for (peer, announcements) in self.peers.iter_mut() {
+            if announcements.pending.is_empty() || now < announcements.next_announce_at {
+                continue;
+            }
+
+            let size = if bytes_per_sec == 0 {
+                announcements.pending.len()
+            } else {
+                let elapsed = now.saturating_duration_since(announcements.refilled_at);
+                let refill = bytes_per_sec.saturating_mul(elapsed.as_millis() as u64) / 1000;
+                announcements.allowance = cmp::min(
+                    announcements.allowance.saturating_add(refill),
+                    max_allowance,
+                );
+                announcements.refilled_at = now;
+                cmp::min(
+                    announcements.pending.len(),
+                    (announcements.allowance / HASH_SIZE) as usize,
+                )
+            };
+            let size = cmp::min(size, max_batch_size);
+            announcements.allowance = announcements
+                .allowance
+                .saturating_sub(size as u64 * HASH_SIZE);
+
+            announcements.interval = if announcements.pending.len() > size {
+                cmp::min(announcements.interval * 2, MAX_ANNOUNCE_INTERVAL)
+            } else {
+                cmp::max(announcements.interval / 2, MIN_ANNOUNCE_INTERVAL)
+            };
+            announcements.next_announce_at = now + announcements.interval;
+
+            if size > 0 {
+                batches.push((*peer, announcements.pending.drain(..size).collect()));
+            }
+        }
+        batches
+    }
+
+    /// Returns the number of the hashes waiting to be announced.
+    pub(crate) fn pending_len(&self) -> usize {
+        self.peers
+            .values()
+            .map(|announcements| announcements.pending.len())
+            .sum()
+    }
+
+    pub(crate) fn remove_peer(&mut self, peer: PeerIndex) {
+        self.peers.remove(&peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_types::{h256, prelude::*};
+
+    fn hash(n: u64) -> Byte32 {
+        let mut hash = h256!("0x0");
+        hash.0[..8].copy_from_slice(&n.to_le_bytes());
+        hash.pack()
+    }
+
+    #[test]
+    fn test_unlimited_announcements() {
+        let now = Instant::now();
+        let peer: PeerIndex = 1.into();
+        let mut announcer = TxAnnouncer::new(0);
+        for n in 0..100 {
+            assert!(announcer.push(peer, hash(n), now));
+        }
+
+        let batches = announcer.pop_due(now, 1000);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].1.len(), 100);
+        assert_eq!(announcer.pending_len(), 0);
+
+        // respects the max batch size
+        for n in 0..100 {
+            announcer.push(peer, hash(n), now);
+        }
+        let later = now + MIN_ANNOUNCE_INTERVAL;
+        assert_eq!(announcer.pop_due(later, 60)[0].1.len(), 60);
+        assert_eq!(announcer.pending_len(), 40);
+    }
+
+    #[test]
+    fn test_budget_limits_announcements() {
+        let now = Instant::now();
+        let peer: PeerIndex = 1.into();
+        // 10 hashes per second, the burst is 20 hashes
+        let mut announcer = TxAnnouncer::new(10 * HASH_SIZE);
+        for n in 0..100 {
+            announcer.push(peer, hash(n), now);
+        }
+
+        assert_eq!(announcer.pop_due(now, 1000)[0].1.len(), 20);
+        // not due until the interval elapses, which doubles when the queue is not drained
+        assert!(announcer
+            .pop_due(now + MIN_ANNOUNCE_INTERVAL, 1000)
+            .is_empty());
+
+        let later = now + MIN_ANNOUNCE_INTERVAL * 2;
+        assert_eq!(announcer.pop_due(later, 1000)[0].1.len(), 2);
+        assert!(announcer
+            .pop_due(later + MIN_ANNOUNCE_INTERVAL * 3, 1000)
+            .is_empty());
+        assert_eq!(
+            announcer.pop_due(later + MIN_ANNOUNCE_INTERVAL * 4, 1000)[0]
+                .1
+                .len(),
+            4
+        );
+        assert_eq!(announcer.pending_len(), 74);
+    }
+
+    #[test]
+    fn test_interval_recovers_when_drained() {
+        let now = Instant::now();
+        let peer: PeerIndex = 1.into();
+        let mut announcer = TxAnnouncer::new(10 * HASH_SIZE);
+        for n in 0..22 {
+            announcer.push(peer, hash(n), now);
+        }
+
+        assert_eq!(announcer.pop_due(now, 1000)[0].1.len(), 20);
+        let later = now + MIN_ANNOUNCE_INTERVAL * 2;
+        assert_eq!(announcer.pop_due(later, 1000)[0].1.len(), 2);
+
+        // the interval halves once the queue is drained
+        announcer.push(peer, hash(22), later);
+        let later = later + MIN_ANNOUNCE_INTERVAL;
+        assert_eq!(announcer.pop_due(later, 1000)[0].1.len(), 1);
+    }
+
+    #[test]
+    fn test_pending_hashes_are_bounded() {
+        let now = Instant::now();
+        let peer: PeerIndex = 1.into();
+        let mut announcer = TxAnnouncer::new(0);
+        for n in 0..MAX_PENDING_HASHES {
+            assert!(announcer.push(peer, hash(n as u64), now));
+        }
+        assert!(!announcer.push(peer, hash(MAX_PENDING_HASHES as u64), now));
+
+        announcer.remove_peer(peer);
+        assert_eq!(announcer.pending_len(), 0);
+    }
+}
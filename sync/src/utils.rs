@@ -15,7 +15,36 @@ pub(crate) fn send_message<Message: Entity>(
     peer_index: PeerIndex,
     message: &Message,
 ) -> Status {
-    if let Err(err) = nc.send_message(protocol_id, peer_index, message.as_bytes()) {
+    send_message_via(protocol_id, nc, peer_index, message, false)
+}
+
+/// Send network message into `nc.protocol_id()` protocol connection through the quick queue,
+/// so it does not wait behind the messages queued in the same session.
+///
+/// Equal to `nc.quick_send_message_to`.
+#[must_use]
+pub(crate) fn quick_send_message_to<Message: Entity>(
+    nc: &dyn CKBProtocolContext,
+    peer_index: PeerIndex,
+    message: &Message,
+) -> Status {
+    let protocol_id = nc.protocol_id();
+    send_message_via(protocol_id, nc, peer_index, message, true)
+}
+
+fn send_message_via<Message: Entity>(
+    protocol_id: ProtocolId,
+    nc: &dyn CKBProtocolContext,
+    peer_index: PeerIndex,
+    message: &Message,
+    quick: bool,
+) -> Status {
+    let result = if quick {
+        nc.quick_send_message(protocol_id, peer_index, message.as_bytes())
+    } else {
+        nc.send_message(protocol_id, peer_index, message.as_bytes())
+    };
+    if let Err(err) = result {
         let name = message_name(protocol_id, message);
         let error_message = format!("nc.send_message {}, error: {:?}", name, err);
         ckb_logger::error!("{}", error_message);
@@ -416,6 +416,7 @@ fn all_specs() -> Vec<Box<dyn Spec>> {
         Box::new(InvalidLocatorSize),
         Box::new(SizeLimit),
         Box::new(CyclesLimit),
+        Box::new(DynamicMinFeeRate),
         Box::new(SendDefectedBinary::new(
             "send_defected_binary_reject_known_bugs",
             true,
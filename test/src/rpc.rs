@@ -185,7 +185,7 @@ impl RpcClient {
 
     pub fn dry_run_transaction(&self, tx: Transaction) -> DryRunResult {
         self.inner
-            .dry_run_transaction(tx)
+            .dry_run_transaction(tx, None)
             .expect("rpc call dry_run_transaction")
     }
 
@@ -300,7 +300,7 @@ jsonrpc!(pub struct Inner {
     pub fn submit_block(&self, _work_id: String, _data: Block) -> H256;
     pub fn get_blockchain_info(&self) -> ChainInfo;
     pub fn get_block_median_time(&self, block_hash: H256) -> Option<Timestamp>;
-    pub fn dry_run_transaction(&self, _tx: Transaction) -> DryRunResult;
+    pub fn dry_run_transaction(&self, _tx: Transaction, _fork_tip: Option<H256>) -> DryRunResult;
     pub fn send_transaction(&self, tx: Transaction, outputs_validator: Option<String>) -> H256;
     pub fn tx_pool_info(&self) -> TxPoolInfo;
 
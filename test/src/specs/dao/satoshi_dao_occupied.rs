@@ -158,5 +158,6 @@ fn issue_satoshi_cell() -> IssuedCell {
     IssuedCell {
         capacity: SATOSHI_CELL_CAPACITY,
         lock: lock.into(),
+        since: None,
     }
 }
@@ -2,8 +2,9 @@ use crate::util::mining::{mine, mine_until_out_bootstrap_period};
 use crate::utils::assert_send_transaction_fail;
 use crate::{Node, Spec, DEFAULT_TX_PROPOSAL_WINDOW};
 
+use ckb_app_config::DynamicMinFeeRateConfig;
 use ckb_logger::info;
-use ckb_types::core::FeeRate;
+use ckb_types::core::{capacity_bytes, Capacity, FeeRate};
 
 pub struct SizeLimit;
 
@@ -118,3 +119,57 @@ impl Spec for CyclesLimit {
         config.tx_pool.min_fee_rate = FeeRate::zero();
     }
 }
+
+pub struct DynamicMinFeeRate;
+
+const MAX_MEM_SIZE_FOR_DYNAMIC_MIN_FEE_RATE: usize = 2000;
+const BASE_MIN_FEE_RATE: u64 = 1000;
+
+impl Spec for DynamicMinFeeRate {
+    fn run(&self, nodes: &mut Vec<Node>) {
+        let node = &nodes[0];
+
+        info!("Generate DEFAULT_TX_PROPOSAL_WINDOW block on node");
+        mine_until_out_bootstrap_period(node);
+
+        info!("Fill the pool until the min fee rate rises");
+        let tx = node.new_transaction_spend_tip_cellbase();
+        let mut hash = node.submit_transaction(&tx);
+        let mut capacity = capacity_bytes!(100);
+        let mut min_fee_rate = node.get_tip_tx_pool_info().min_fee_rate.value();
+        while min_fee_rate == BASE_MIN_FEE_RATE {
+            capacity = capacity.safe_sub(capacity_bytes!(1)).unwrap();
+            let tx = node.new_transaction_with_since_capacity(hash, 0, capacity);
+            hash = node.submit_transaction(&tx);
+            min_fee_rate = node.get_tip_tx_pool_info().min_fee_rate.value();
+        }
+        assert!(min_fee_rate > BASE_MIN_FEE_RATE);
+
+        info!("The tx paying 1.5 times the base fee rate is rejected under the raised floor");
+        let tx_size = node
+            .new_transaction_with_since_capacity(hash.clone(), 0, capacity)
+            .data()
+            .serialized_size_in_block() as u64;
+        let fee = Capacity::shannons(BASE_MIN_FEE_RATE * tx_size * 3 / 2 / 1000);
+        let low_fee_tx =
+            node.new_transaction_with_since_capacity(hash, 0, capacity.safe_sub(fee).unwrap());
+        assert_send_transaction_fail(node, &low_fee_tx, "The min fee rate is");
+
+        info!("The floor drops back once the pool is drained by the new blocks");
+        mine(&node, DEFAULT_TX_PROPOSAL_WINDOW.0);
+        mine(&node, 1);
+        node.assert_tx_pool_serialized_size(0);
+        assert_eq!(
+            node.get_tip_tx_pool_info().min_fee_rate.value(),
+            BASE_MIN_FEE_RATE
+        );
+        node.submit_transaction(&low_fee_tx);
+    }
+
+    fn modify_app_config(&self, config: &mut ckb_app_config::CKBAppConfig) {
+        config.tx_pool.max_mem_size = MAX_MEM_SIZE_FOR_DYNAMIC_MIN_FEE_RATE;
+        config.tx_pool.max_cycles = MAX_CYCLES_FOR_SIZE_LIMIT;
+        config.tx_pool.min_fee_rate = FeeRate::from_u64(BASE_MIN_FEE_RATE);
+        config.tx_pool.dynamic_min_fee_rate = Some(DynamicMinFeeRateConfig::default());
+    }
+}
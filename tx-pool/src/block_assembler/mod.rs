@@ -5,14 +5,18 @@ use crate::error::BlockAssemblerError;
 pub use candidate_uncles::CandidateUncles;
 use ckb_app_config::BlockAssemblerConfig;
 use ckb_chain_spec::consensus::Consensus;
+use ckb_dao::simulate_dao_field;
 use ckb_error::AnyError;
 use ckb_jsonrpc_types::{BlockTemplate, CellbaseTemplate, TransactionTemplate, UncleTemplate};
+use ckb_logger::error;
+use ckb_metrics::metrics;
 use ckb_reward_calculator::RewardCalculator;
 use ckb_snapshot::Snapshot;
 use ckb_store::ChainStore;
 use ckb_types::{
     bytes::Bytes,
     core::{
+        cell::{OverlayCellChecker, ResolveOptions, ResolvedTransaction, TransactionsChecker},
         BlockNumber, Capacity, Cycle, EpochExt, HeaderView, TransactionBuilder, TransactionView,
         UncleBlockView, Version,
     },
@@ -21,6 +25,7 @@ use ckb_types::{
 };
 use lru::LruCache;
 use std::collections::HashSet;
+use std::iter;
 use std::sync::{atomic::AtomicU64, Arc};
 use tokio::sync::Mutex;
 use tokio::task::block_in_place;
@@ -193,6 +198,72 @@ impl BlockAssembler {
         Ok(tx)
     }
 
+    /// Sanity check of the assembled template before handing it to the miners.
+    ///
+    /// `rtxs` are the resolved pool entries the template is assembled from, including the
+    /// cellbase, `fees` are the fees recorded in the entries and `dao` is the dao field computed
+    /// from them. The resolved cells held by the entries are checked to be still live in the
+    /// snapshot or created by an earlier transaction of the template, then the dao field and fees
+    /// are recomputed from them. Any divergence means the pool entries are stale and the template
+    /// would be rejected by the chain.
+    pub(crate) fn check_template_dao(
+        snapshot: &Snapshot,
+        rtxs: &[ResolvedTransaction],
+        fees: &[Capacity],
+        dao: &Byte32,
+        resolve_opts: ResolveOptions,
+    ) -> Result<(), AnyError> {
+        let tip_header = snapshot.tip_header();
+        let inconsistent = |reason: String| -> AnyError {
+            error!(
+                "inconsistent block template, tip_number: {}, tip_hash: {}, {}",
+                tip_header.number(),
+                tip_header.hash(),
+                reason
+            );
+            metrics!(counter, "ckb.block_assembler.inconsistent_template", 1);
+            BlockAssemblerError::InconsistentTemplate(reason).into()
+        };
+
+        let mut transactions_checker = TransactionsChecker::new(iter::empty());
+        let mut seen_inputs = HashSet::new();
+        for rtx in rtxs {
+            let cell_checker = OverlayCellChecker::new(&transactions_checker, snapshot);
+            rtx.check(&mut seen_inputs, &cell_checker, snapshot, resolve_opts)
+                .map_err(|err| {
+                    inconsistent(format!("stale entry {}: {}", rtx.transaction.hash(), err))
+                })?;
+            transactions_checker.insert(&rtx.transaction);
+        }
+
+        let simulation = simulate_dao_field(
+            snapshot.consensus(),
+            &snapshot.as_data_provider(),
+            tip_header,
+            rtxs,
+        )
+        .map_err(|err| inconsistent(format!("failed to recompute dao: {}", err)))?;
+        if let Some((rtx, (expected, actual))) = rtxs
+            .iter()
+            .zip(simulation.fees.iter().zip(fees.iter()))
+            .find(|(_, (expected, actual))| expected != actual)
+        {
+            return Err(inconsistent(format!(
+                "fee of {} is {}, recomputed {}",
+                rtx.transaction.hash(),
+                actual,
+                expected
+            )));
+        }
+        if &simulation.dao != dao {
+            return Err(inconsistent(format!(
+                "dao is {}, recomputed {}",
+                dao, simulation.dao
+            )));
+        }
+        Ok(())
+    }
+
     // A block B1 is considered to be the uncle of another block B2 if all of the following conditions are met:
     // (1) they are in the same epoch, sharing the same difficulty;
     // (2) height(B2) > height(B1);
@@ -235,3 +306,101 @@ impl BlockAssembler {
         uncles
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_app_config::StoreConfig;
+    use ckb_dao::DaoCalculator;
+    use ckb_db::RocksDB;
+    use ckb_db_schema::COLUMNS;
+    use ckb_proposal_table::ProposalView;
+    use ckb_store::{attach_block_cell, ChainDB};
+    use ckb_test_chain_utils::always_success_consensus;
+    use ckb_types::core::{cell::resolve_transaction, BlockBuilder, BlockView};
+
+    fn build_snapshot(store: &ChainDB, tip: &BlockView, consensus: &Arc<Consensus>) -> Snapshot {
+        Snapshot::new(
+            tip.header(),
+            tip.header().difficulty(),
+            consensus.genesis_epoch_ext().clone(),
+            store.get_snapshot(),
+            ProposalView::default(),
+            Arc::clone(consensus),
+        )
+    }
+
+    fn build_cellbase(number: BlockNumber) -> TransactionView {
+        TransactionBuilder::default()
+            .input(CellInput::new_cellbase_input(number))
+            .output(CellOutput::new_builder().build())
+            .output_data(Bytes::default().pack())
+            .build()
+    }
+
+    fn spend(out_point: packed::OutPoint, capacity: Capacity) -> TransactionView {
+        TransactionBuilder::default()
+            .input(CellInput::new(out_point, 0))
+            .output(CellOutput::new_builder().capacity(capacity.pack()).build())
+            .output_data(Bytes::default().pack())
+            .build()
+    }
+
+    #[test]
+    fn test_check_template_dao_stale_entry() {
+        let consensus = Arc::new(always_success_consensus());
+        let store = ChainDB::new(RocksDB::open_tmp(COLUMNS), StoreConfig::default());
+        store.init(&consensus).unwrap();
+        let genesis = consensus.genesis_block().clone();
+        let snapshot = build_snapshot(&store, &genesis, &consensus);
+
+        let genesis_tx = &genesis.transactions()[0];
+        let out_point = packed::OutPoint::new(genesis_tx.hash(), 0);
+        let input_capacity: Capacity = genesis_tx.outputs().get(0).unwrap().capacity().unpack();
+        let fee = Capacity::shannons(1000);
+
+        // the template spends the genesis cell
+        let tx = spend(out_point.clone(), input_capacity.safe_sub(fee).unwrap());
+        let rtxs = vec![
+            ResolvedTransaction::dummy_resolve(build_cellbase(1)),
+            resolve_transaction(tx, &mut HashSet::new(), &snapshot, &snapshot).unwrap(),
+        ];
+        let fees = vec![Capacity::zero(), fee];
+        let dao = DaoCalculator::new(&consensus, &snapshot.as_data_provider())
+            .dao_field(&rtxs, &genesis.header())
+            .unwrap();
+        let resolve_opts = ResolveOptions::empty();
+        assert!(
+            BlockAssembler::check_template_dao(&snapshot, &rtxs, &fees, &dao, resolve_opts).is_ok()
+        );
+
+        let wrong_fees = vec![Capacity::zero(), Capacity::shannons(999)];
+        assert!(BlockAssembler::check_template_dao(
+            &snapshot,
+            &rtxs,
+            &wrong_fees,
+            &dao,
+            resolve_opts
+        )
+        .is_err());
+
+        // the new tip spends the same genesis cell, the template entry becomes stale
+        let block = BlockBuilder::default()
+            .parent_hash(genesis.hash())
+            .number(1.pack())
+            .epoch(consensus.genesis_epoch_ext().number_with_fraction(1).pack())
+            .transaction(build_cellbase(1))
+            .transaction(spend(out_point, input_capacity))
+            .build();
+        let db_txn = store.begin_transaction();
+        db_txn.insert_block(&block).unwrap();
+        db_txn.attach_block(&block).unwrap();
+        attach_block_cell(&db_txn, &block).unwrap();
+        db_txn.commit().unwrap();
+        let snapshot = build_snapshot(&store, &block, &consensus);
+
+        let err = BlockAssembler::check_template_dao(&snapshot, &rtxs, &fees, &dao, resolve_opts)
+            .unwrap_err();
+        assert!(err.to_string().contains("stale entry"), "{}", err);
+    }
+}
@@ -1,19 +1,23 @@
 use super::component::TxEntry;
 use crate::error::Reject;
 use crate::pool::TxPool;
+use ckb_types::core::HeaderView;
 
 /// Callback boxed fn pointer wrapper
 pub type Callback = Box<dyn Fn(&mut TxPool, &TxEntry) + Sync + Send>;
 /// Proposed Callback boxed fn pointer wrapper
 pub type ProposedCallback = Box<dyn Fn(&mut TxPool, &TxEntry, bool) + Sync + Send>;
+/// Committed Callback boxed fn pointer wrapper
+pub type CommittedCallback = Box<dyn Fn(&mut TxPool, &TxEntry, &HeaderView) + Sync + Send>;
 /// Reject Callback boxed fn pointer wrapper
 pub type RejectCallback = Box<dyn Fn(&mut TxPool, &TxEntry, Reject) + Sync + Send>;
 
 /// Struct hold callbacks
 pub struct Callbacks {
     pub(crate) pending: Option<Callback>,
+    pub(crate) gap: Option<Callback>,
     pub(crate) proposed: Option<ProposedCallback>,
-    pub(crate) committed: Option<Callback>,
+    pub(crate) committed: Option<CommittedCallback>,
     pub(crate) reject: Option<RejectCallback>,
 }
 
@@ -28,6 +32,7 @@ impl Callbacks {
     pub fn new() -> Self {
         Callbacks {
             pending: None,
+            gap: None,
             proposed: None,
             committed: None,
             reject: None,
@@ -39,13 +44,18 @@ impl Callbacks {
         self.pending = Some(callback);
     }
 
+    /// Register a new gap callback
+    pub fn register_gap(&mut self, callback: Callback) {
+        self.gap = Some(callback);
+    }
+
     /// Register a new proposed callback
     pub fn register_proposed(&mut self, callback: ProposedCallback) {
         self.proposed = Some(callback);
     }
 
     /// Register a new committed callback
-    pub fn register_committed(&mut self, callback: Callback) {
+    pub fn register_committed(&mut self, callback: CommittedCallback) {
         self.committed = Some(callback);
     }
 
@@ -61,6 +71,13 @@ impl Callbacks {
         }
     }
 
+    /// Call on after gap
+    pub fn call_gap(&self, tx_pool: &mut TxPool, entry: &TxEntry) {
+        if let Some(call) = &self.gap {
+            call(tx_pool, entry)
+        }
+    }
+
     /// Call on after proposed
     pub fn call_proposed(&self, tx_pool: &mut TxPool, entry: &TxEntry, new: bool) {
         if let Some(call) = &self.proposed {
@@ -68,10 +85,10 @@ impl Callbacks {
         }
     }
 
-    /// Call on after proposed
-    pub fn call_committed(&self, tx_pool: &mut TxPool, entry: &TxEntry) {
+    /// Call on after committed
+    pub fn call_committed(&self, tx_pool: &mut TxPool, entry: &TxEntry, header: &HeaderView) {
         if let Some(call) = &self.committed {
-            call(tx_pool, entry)
+            call(tx_pool, entry, header)
         }
     }
 
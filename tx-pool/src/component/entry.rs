@@ -1,9 +1,12 @@
 use crate::component::container::AncestorsScoreSortKey;
 use crate::component::get_transaction_virtual_bytes;
 use ckb_types::{
-    core::{cell::ResolvedTransaction, tx_pool::TxEntryInfo, Capacity, Cycle, TransactionView},
+    core::{
+        cell::ResolvedTransaction, tx_pool::TxEntryInfo, Capacity, Cycle, FeeRate, TransactionView,
+    },
     packed::{OutPoint, ProposalShortId},
 };
+use faketime::unix_time_as_millis;
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 
@@ -26,6 +29,8 @@ pub struct TxEntry {
     pub ancestors_cycles: Cycle,
     /// ancestors txs count
     pub ancestors_count: usize,
+    /// The unix timestamp in milliseconds when the entry is created
+    pub timestamp: u64,
 }
 
 impl TxEntry {
@@ -40,6 +45,7 @@ impl TxEntry {
             ancestors_fee: fee,
             ancestors_cycles: cycles,
             ancestors_count: 1,
+            timestamp: unix_time_as_millis(),
         }
     }
 
@@ -64,6 +70,22 @@ impl TxEntry {
         self.transaction().proposal_short_id()
     }
 
+    /// Returns the fee rate computed from the fee and the virtual bytes
+    pub fn fee_rate(&self) -> FeeRate {
+        let vbytes = get_transaction_virtual_bytes(self.size, self.cycles);
+        FeeRate::calculate(self.fee, vbytes as usize)
+    }
+
+    /// Returns the lower one of the fee rate and the fee rate of the ancestors package tracked
+    /// by the entry, which the block assembler ranks the proposed entries by
+    pub fn effective_fee_rate(&self) -> FeeRate {
+        let vbytes = get_transaction_virtual_bytes(self.ancestors_size, self.ancestors_cycles);
+        std::cmp::min(
+            self.fee_rate(),
+            FeeRate::calculate(self.ancestors_fee, vbytes as usize),
+        )
+    }
+
     /// Returns a sorted_key
     pub fn as_sorted_key(&self) -> AncestorsScoreSortKey {
         AncestorsScoreSortKey::from(self)
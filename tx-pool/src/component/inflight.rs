@@ -0,0 +1,118 @@
+//! The transactions in the submit path.
+use ckb_types::{packed::Byte32, prelude::*};
+use ckb_util::Mutex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+// The first byte of the witness hash spreads the transactions evenly.
+const SHARDS: usize = 16;
+
+/// The witness hashes of the transactions which are being verified and submitted.
+///
+/// The submissions run concurrently, and the same transaction usually arrives from several
+/// peers at the same time. Only the first submission verifies the transaction, the others are
+/// rejected as duplicated before the expensive script verification. The transactions are keyed
+/// by the witness hash, so a copy with different witnesses, which may be invalid, does not keep
+/// the other copies out. The hashes are split into shards so the concurrent submissions do not
+/// contend on one lock.
+pub(crate) struct InflightTxs {
+    shards: Vec<Mutex<HashSet<Byte32>>>,
+}
+
+impl InflightTxs {
+    pub(crate) fn new() -> Self {
+        InflightTxs {
+            shards: (0..SHARDS).map(|_| Mutex::new(HashSet::new())).collect(),
+        }
+    }
+
+    fn shard(&self, witness_hash: &Byte32) -> &Mutex<HashSet<Byte32>> {
+        &self.shards[witness_hash.as_slice()[0] as usize % SHARDS]
+    }
+
+    /// Marks the transaction in the submit path until the returned guard is dropped.
+    ///
+    /// Returns `None` when the transaction is already in the submit path.
+    pub(crate) fn enter(self: &Arc<Self>, witness_hash: Byte32) -> Option<InflightGuard> {
+        if self
+            .shard(&witness_hash)
+            .lock()
+            .insert(witness_hash.clone())
+        {
+            Some(InflightGuard {
+                inflight: Arc::clone(self),
+                witness_hash,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Removes the transaction from the submit path when dropped.
+pub(crate) struct InflightGuard {
+    inflight: Arc<InflightTxs>,
+    witness_hash: Byte32,
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.inflight
+            .shard(&self.witness_hash)
+            .lock()
+            .remove(&self.witness_hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_types::{
+        bytes::Bytes,
+        core::{TransactionBuilder, TransactionView},
+        prelude::*,
+    };
+
+    fn hash(n: u8) -> Byte32 {
+        [n; 32].pack()
+    }
+
+    fn tx_with_witness(witness: &'static [u8]) -> TransactionView {
+        TransactionBuilder::default()
+            .witness(Bytes::from_static(witness).pack())
+            .build()
+    }
+
+    #[test]
+    fn test_enter_and_leave() {
+        let inflight = Arc::new(InflightTxs::new());
+
+        let guard1 = inflight.enter(hash(1)).expect("first submission");
+        assert!(inflight.enter(hash(1)).is_none());
+        let guard2 = inflight
+            .enter(hash(17))
+            .expect("another transaction in the same shard");
+        assert!(inflight.enter(hash(17)).is_none());
+
+        drop(guard1);
+        assert!(inflight.enter(hash(1)).is_some());
+        assert!(inflight.enter(hash(17)).is_none());
+
+        drop(guard2);
+        assert!(inflight.enter(hash(17)).is_some());
+    }
+
+    #[test]
+    fn test_copy_with_other_witnesses() {
+        let inflight = Arc::new(InflightTxs::new());
+        let garbage = tx_with_witness(b"garbage");
+        let valid = tx_with_witness(b"signature");
+        assert_eq!(garbage.proposal_short_id(), valid.proposal_short_id());
+
+        let _guard = inflight
+            .enter(garbage.witness_hash())
+            .expect("garbage copy");
+        assert!(inflight.enter(valid.witness_hash()).is_some());
+        assert!(inflight.enter(garbage.witness_hash()).is_none());
+    }
+}
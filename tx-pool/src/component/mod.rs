@@ -3,9 +3,11 @@ pub mod entry;
 
 pub(crate) mod chunk;
 pub(crate) mod container;
+pub(crate) mod inflight;
 pub(crate) mod orphan;
 pub(crate) mod pending;
 pub(crate) mod proposed;
+pub(crate) mod staging;
 
 pub use self::entry::TxEntry;
 
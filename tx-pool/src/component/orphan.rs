@@ -1,4 +1,5 @@
 use ckb_logger::trace;
+use ckb_metrics::metrics;
 use ckb_network::PeerIndex;
 use ckb_types::{
     core::{Cycle, TransactionView},
@@ -99,19 +100,28 @@ impl OrphanPool {
             .cloned()
             .collect();
 
-        let mut evicted = expires.len();
+        let expired = expires.len();
 
         for id in expires {
             self.remove_orphan_tx(&id);
         }
 
+        let mut overflowed = 0;
         while self.len() > DEFAULT_MAX_ORPHAN_TRANSACTIONS {
-            evicted += 1;
+            overflowed += 1;
             // Evict a random orphan:
             let id = self.entries.keys().next().cloned().expect("bound checked");
             self.remove_orphan_tx(&id);
         }
 
+        if expired > 0 {
+            metrics!(counter, "ckb-tx-pool.orphan_evicted", expired as u64, "reason" => "expired");
+        }
+        if overflowed > 0 {
+            metrics!(counter, "ckb-tx-pool.orphan_evicted", overflowed as u64, "reason" => "full");
+        }
+
+        let evicted = expired + overflowed;
         if evicted > 0 {
             trace!("OrphanTxPool full, evicted {} tx", evicted);
             self.shrink_to_fit();
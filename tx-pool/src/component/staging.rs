@@ -0,0 +1,243 @@
+use ckb_app_config::TimeLockStagingConfig;
+use ckb_logger::trace;
+use ckb_metrics::metrics;
+use ckb_types::{
+    core::{tx_pool::StagedTxInfo, TransactionView},
+    packed::ProposalShortId,
+    prelude::*,
+};
+use ckb_util::{mock_clock::unix_time_as_millis, shrink_to_fit};
+use std::collections::HashMap;
+
+const SHRINK_THRESHOLD: usize = 100;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Entry {
+    /// Transaction
+    pub(crate) tx: TransactionView,
+    /// The index of the input whose since is not satisfied in the last submission
+    pub(crate) immature_input: usize,
+    /// Staged timestamp in milliseconds
+    pub(crate) staged_at: u64,
+    /// Expire timestamp in milliseconds
+    pub(crate) expires_at: u64,
+}
+
+impl Entry {
+    fn info(&self) -> StagedTxInfo {
+        let since: u64 = self
+            .tx
+            .inputs()
+            .get(self.immature_input)
+            .map(|input| input.since().unpack())
+            .unwrap_or(0);
+        StagedTxInfo {
+            tx_hash: self.tx.hash(),
+            immature_input: self.immature_input,
+            since,
+            staged_at: self.staged_at,
+            expires_at: self.expires_at,
+        }
+    }
+}
+
+/// The local transactions which are rejected because their inputs `since` are not satisfied yet.
+///
+/// They are submitted to the pool again on every new tip.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct StagingArea {
+    pub(crate) entries: HashMap<ProposalShortId, Entry>,
+}
+
+impl StagingArea {
+    pub fn new() -> Self {
+        StagingArea::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        shrink_to_fit!(self.entries, SHRINK_THRESHOLD);
+    }
+
+    /// Stages the transaction, returns false when the staging area is full.
+    ///
+    /// The staged transaction with the same proposal short id is replaced when the witnesses
+    /// differ, such as a resubmission fixing a bad witness.
+    pub fn add_staged_tx(
+        &mut self,
+        tx: TransactionView,
+        immature_input: usize,
+        config: &TimeLockStagingConfig,
+    ) -> bool {
+        let id = tx.proposal_short_id();
+        if let Some(entry) = self.entries.get_mut(&id) {
+            if entry.tx.witness_hash() != tx.witness_hash() {
+                entry.tx = tx;
+            }
+            entry.immature_input = immature_input;
+            return true;
+        }
+        self.remove_expired();
+        if self.len() >= config.max_transactions {
+            metrics!(counter, "ckb-tx-pool.staging_rejected", 1, "reason" => "full");
+            return false;
+        }
+
+        let now = unix_time_as_millis();
+        self.entries.insert(
+            id,
+            Entry {
+                tx,
+                immature_input,
+                staged_at: now,
+                expires_at: now.saturating_add(config.expiry_secs.saturating_mul(1000)),
+            },
+        );
+        true
+    }
+
+    /// Updates the immature input of the staged transaction which is still immature.
+    pub fn update_immature_input(&mut self, id: &ProposalShortId, immature_input: usize) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.immature_input = immature_input;
+        }
+    }
+
+    pub fn remove_staged_tx(&mut self, id: &ProposalShortId) -> Option<Entry> {
+        self.entries.remove(id)
+    }
+
+    pub fn remove_staged_txs(&mut self, ids: impl Iterator<Item = ProposalShortId>) {
+        for id in ids {
+            self.remove_staged_tx(&id);
+        }
+        self.shrink_to_fit();
+    }
+
+    /// Drops the expired transactions, returns the count of them.
+    pub fn remove_expired(&mut self) -> usize {
+        let now = unix_time_as_millis();
+        let expires: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.expires_at <= now)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let expired = expires.len();
+        if expired > 0 {
+            for id in expires {
+                self.remove_staged_tx(&id);
+            }
+            metrics!(counter, "ckb-tx-pool.staging_evicted", expired as u64, "reason" => "expired");
+            trace!("TimeLockStaging evicted {} expired tx", expired);
+            self.shrink_to_fit();
+        }
+        expired
+    }
+
+    /// Returns the staged transactions, the earliest staged first.
+    pub fn txs(&self) -> Vec<TransactionView> {
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_by_key(|entry| entry.staged_at);
+        entries.into_iter().map(|entry| entry.tx.clone()).collect()
+    }
+
+    /// Returns the info of the staged transactions, the earliest staged first.
+    pub fn infos(&self) -> Vec<StagedTxInfo> {
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_by_key(|entry| entry.staged_at);
+        entries.into_iter().map(Entry::info).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_types::{
+        bytes::Bytes,
+        core::TransactionBuilder,
+        packed::{CellInput, OutPoint},
+    };
+
+    fn build_tx(index: u32, since: u64) -> TransactionView {
+        TransactionBuilder::default()
+            .input(CellInput::new(
+                OutPoint::new(Default::default(), index),
+                since,
+            ))
+            .build()
+    }
+
+    #[test]
+    fn test_add_staged_tx() {
+        let config = TimeLockStagingConfig {
+            max_transactions: 2,
+            expiry_secs: 60,
+        };
+        let mut staging = StagingArea::new();
+        let tx1 = build_tx(1, 100);
+        let tx2 = build_tx(2, 200);
+        let tx3 = build_tx(3, 300);
+
+        assert!(staging.add_staged_tx(tx1.clone(), 0, &config));
+        assert!(staging.add_staged_tx(tx2.clone(), 0, &config));
+        // Staging the same transaction again does not take more room.
+        assert!(staging.add_staged_tx(tx1.clone(), 0, &config));
+        assert!(!staging.add_staged_tx(tx3.clone(), 0, &config));
+        assert_eq!(staging.len(), 2);
+
+        let info = staging
+            .infos()
+            .into_iter()
+            .find(|info| info.tx_hash == tx2.hash())
+            .expect("staged");
+        assert_eq!(info.since, 200);
+        assert_eq!(info.expires_at, info.staged_at + 60_000);
+
+        staging.remove_staged_txs(vec![tx1.proposal_short_id()].into_iter());
+        assert!(!staging.entries.contains_key(&tx1.proposal_short_id()));
+        assert!(staging.add_staged_tx(tx3, 0, &config));
+    }
+
+    #[test]
+    fn test_replace_staged_tx_with_other_witnesses() {
+        let config = TimeLockStagingConfig {
+            max_transactions: 1,
+            expiry_secs: 60,
+        };
+        let mut staging = StagingArea::new();
+        let tx = build_tx(1, 100);
+        let fixed_tx = tx
+            .as_advanced_builder()
+            .witness(Bytes::from(vec![1]).pack())
+            .build();
+        assert_eq!(tx.proposal_short_id(), fixed_tx.proposal_short_id());
+
+        assert!(staging.add_staged_tx(tx.clone(), 0, &config));
+        let staged_at = staging.infos()[0].staged_at;
+        assert!(staging.add_staged_tx(fixed_tx.clone(), 0, &config));
+        assert_eq!(staging.len(), 1);
+        assert_eq!(
+            staging.txs()[0].witness_hash(),
+            fixed_tx.witness_hash(),
+            "the resubmitted witnesses replace the staged ones"
+        );
+        assert_eq!(staging.infos()[0].staged_at, staged_at);
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let config = TimeLockStagingConfig {
+            max_transactions: 10,
+            expiry_secs: 0,
+        };
+        let mut staging = StagingArea::new();
+        assert!(staging.add_staged_tx(build_tx(1, 100), 0, &config));
+        assert_eq!(staging.remove_expired(), 1);
+        assert_eq!(staging.len(), 0);
+    }
+}
@@ -19,6 +19,9 @@ pub enum BlockAssemblerError {
     /// BlockAssembler is disabled
     #[error("Disabled")]
     Disabled,
+    /// The assembled template does not agree with the recomputation from the chain state
+    #[error("InconsistentTemplate {0}")]
+    InconsistentTemplate(String),
 }
 
 impl_error_conversion_with_kind!(
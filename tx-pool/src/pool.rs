@@ -5,7 +5,7 @@ use crate::component::pending::PendingQueue;
 use crate::component::proposed::ProposedPool;
 use crate::error::Reject;
 use crate::util::verify_rtx;
-use ckb_app_config::TxPoolConfig;
+use ckb_app_config::{DynamicMinFeeRateConfig, TxPoolConfig};
 use ckb_logger::{error, trace};
 use ckb_snapshot::Snapshot;
 use ckb_store::ChainStore;
@@ -16,20 +16,49 @@ use ckb_types::{
             resolve_transaction_with_options, OverlayCellChecker, OverlayCellProvider,
             ResolveOptions, ResolvedTransaction,
         },
-        tx_pool::{TxPoolEntryInfo, TxPoolIds},
-        Cycle, TransactionView,
+        tx_pool::{
+            PoolEntriesOrder, TxConflict, TxPoolConflicts, TxPoolEntryDetail, TxPoolEntryDetails,
+            TxPoolEntryInfo, TxPoolIds, TxStatus,
+        },
+        Capacity, Cycle, FeeRate, HeaderView, TransactionView,
     },
     packed::{Byte32, OutPoint, ProposalShortId},
 };
 use ckb_verification::{cache::CacheEntry, TxVerifyEnv};
 use faketime::unix_time_as_millis;
 use lru::LruCache;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::{
     atomic::{AtomicU64, Ordering},
     Arc,
 };
 
+// The floor curve of `DynamicMinFeeRateConfig`
+fn dynamic_min_fee_rate(
+    config: &DynamicMinFeeRateConfig,
+    base: FeeRate,
+    total_tx_size: usize,
+    max_mem_size: usize,
+) -> FeeRate {
+    if max_mem_size == 0 {
+        return base;
+    }
+    let usage_percent = (total_tx_size as u128 * 100 / max_mem_size as u128) as u64;
+    if usage_percent < config.start_percent {
+        return base;
+    }
+    let steps = (usage_percent - config.start_percent) / config.step_percent.max(1) + 1;
+    let factor = config
+        .multiplier
+        .checked_pow(steps.min(u64::from(u32::MAX)) as u32)
+        .unwrap_or(u64::MAX);
+    let floor = base
+        .as_u64()
+        .saturating_mul(factor)
+        .min(config.max_fee_rate.as_u64());
+    FeeRate::from_u64(floor.max(base.as_u64()))
+}
+
 /// Tx-pool implementation
 pub struct TxPool {
     pub(crate) config: TxPoolConfig,
@@ -81,6 +110,145 @@ pub struct TxPoolInfo {
     pub total_tx_cycles: Cycle,
     /// Last updated time. This is the Unix timestamp in milliseconds.
     pub last_txs_updated_at: u64,
+    /// The current min fee rate to admit transactions.
+    pub min_fee_rate: FeeRate,
+}
+
+// The sum of the in-pool ancestors or descendants of a transaction
+#[derive(Default)]
+struct RelativesStats {
+    count: u64,
+    size: u64,
+    cycles: Cycle,
+    fee: Capacity,
+}
+
+// The spending relations between the in-pool transactions, indexed by their positions
+struct EntryGraph {
+    hashes: Vec<Byte32>,
+    parents: Vec<Vec<usize>>,
+    children: Vec<Vec<usize>>,
+}
+
+impl EntryGraph {
+    fn new(entries: &[(&TxEntry, TxStatus)]) -> Self {
+        let hashes: Vec<Byte32> = entries
+            .iter()
+            .map(|(entry, _)| entry.transaction().hash())
+            .collect();
+        let positions: HashMap<&Byte32, usize> = hashes
+            .iter()
+            .enumerate()
+            .map(|(i, hash)| (hash, i))
+            .collect();
+        let mut parents = vec![Vec::new(); entries.len()];
+        let mut children = vec![Vec::new(); entries.len()];
+        for (i, (entry, _)) in entries.iter().enumerate() {
+            for out_point in entry.transaction().input_pts_iter() {
+                if let Some(&parent) = positions.get(&out_point.tx_hash()) {
+                    if !parents[i].contains(&parent) {
+                        parents[i].push(parent);
+                        children[parent].push(i);
+                    }
+                }
+            }
+        }
+        EntryGraph {
+            hashes,
+            parents,
+            children,
+        }
+    }
+
+    // Sums the transactions reachable from `start` through `links`, excluding `start`.
+    fn relatives_stats(
+        &self,
+        entries: &[(&TxEntry, TxStatus)],
+        start: usize,
+        links: &[Vec<usize>],
+    ) -> RelativesStats {
+        let mut stats = RelativesStats::default();
+        let mut visited = HashSet::new();
+        visited.insert(start);
+        let mut queue = links[start].clone();
+        while let Some(i) = queue.pop() {
+            if !visited.insert(i) {
+                continue;
+            }
+            let entry = entries[i].0;
+            stats.count += 1;
+            stats.size += entry.size as u64;
+            stats.cycles = stats.cycles.saturating_add(entry.cycles);
+            stats.fee = stats.fee.safe_add(entry.fee).unwrap_or(stats.fee);
+            queue.extend(links[i].iter().copied());
+        }
+        stats
+    }
+}
+
+// The cells used as the cell deps, including the cells of the dep groups.
+fn dep_out_points(entry: &TxEntry) -> HashSet<OutPoint> {
+    entry
+        .transaction()
+        .cell_deps_iter()
+        .map(|cell_dep| cell_dep.out_point())
+        .chain(entry.related_dep_out_points().cloned())
+        .collect()
+}
+
+// Finds the entries which spend the same inputs as the transaction, spend its cell deps, or use
+// its inputs as cell deps.
+fn find_conflicts(entries: &[(&TxEntry, TxStatus)], tx_hash: &Byte32) -> Option<TxPoolConflicts> {
+    let id = ProposalShortId::from_tx_hash(tx_hash);
+    let (entry, status) = entries
+        .iter()
+        .find(|(entry, _)| entry.proposal_short_id() == id)?;
+    let inputs: Vec<OutPoint> = entry.transaction().input_pts_iter().collect();
+    let input_set: HashSet<&OutPoint> = inputs.iter().collect();
+    let deps = dep_out_points(entry);
+
+    let conflicts = entries
+        .iter()
+        .filter(|(other, _)| other.proposal_short_id() != id)
+        .filter_map(|(other, status)| {
+            let other_inputs: Vec<OutPoint> = other.transaction().input_pts_iter().collect();
+            let other_deps = dep_out_points(other);
+            let shared_inputs: Vec<OutPoint> = other_inputs
+                .iter()
+                .filter(|out_point| input_set.contains(out_point))
+                .cloned()
+                .collect();
+            let shared_deps: Vec<OutPoint> = other_inputs
+                .iter()
+                .filter(|out_point| deps.contains(*out_point))
+                .chain(
+                    inputs
+                        .iter()
+                        .filter(|out_point| other_deps.contains(*out_point)),
+                )
+                .cloned()
+                .collect();
+            if shared_inputs.is_empty() && shared_deps.is_empty() {
+                None
+            } else {
+                Some(TxConflict {
+                    tx_hash: other.transaction().hash(),
+                    status: *status,
+                    fee: other.fee,
+                    fee_rate: other.fee_rate(),
+                    inputs: shared_inputs,
+                    deps: shared_deps,
+                })
+            }
+        })
+        .collect();
+
+    Some(TxPoolConflicts {
+        tx_hash: tx_hash.to_owned(),
+        status: *status,
+        fee_rate: entry.fee_rate(),
+        conflicts,
+    })
 }
 
 impl TxPool {
@@ -127,6 +295,23 @@ impl TxPool {
             total_tx_size: self.total_tx_size,
             total_tx_cycles: self.total_tx_cycles,
             last_txs_updated_at: self.get_last_txs_updated_at(),
+            min_fee_rate: self.min_fee_rate(),
+        }
+    }
+
+    /// The min fee rate to admit transactions.
+    ///
+    /// It is `tx_pool.min_fee_rate` in the config, and rises with the pool usage when
+    /// `tx_pool.dynamic_min_fee_rate` is configured.
+    pub fn min_fee_rate(&self) -> FeeRate {
+        match self.config.dynamic_min_fee_rate {
+            Some(ref dynamic) => dynamic_min_fee_rate(
+                dynamic,
+                self.config.min_fee_rate,
+                self.total_tx_size,
+                self.config.max_mem_size,
+            ),
+            None => self.config.min_fee_rate,
         }
     }
 
@@ -222,6 +407,15 @@ impl TxPool {
             })
     }
 
+    /// Returns the fee rate of the tx corresponding to the id.
+    pub(crate) fn get_fee_rate(&self, id: &ProposalShortId) -> Option<FeeRate> {
+        self.pending
+            .get(id)
+            .or_else(|| self.gap.get(id))
+            .or_else(|| self.proposed.get(id))
+            .map(TxEntry::fee_rate)
+    }
+
     /// Returns tx corresponding to the id.
     pub fn get_tx(&self, id: &ProposalShortId) -> Option<&TransactionView> {
         self.pending
@@ -252,20 +446,20 @@ impl TxPool {
             .or_else(|| self.pending.get_tx(id))
     }
 
-    pub(crate) fn remove_committed_txs<'a>(
+    pub(crate) fn remove_committed_txs(
         &mut self,
-        txs: impl Iterator<Item = (&'a TransactionView, Vec<OutPoint>)>,
+        txs: impl Iterator<Item = (TransactionView, HeaderView, Vec<OutPoint>)>,
         callbacks: &Callbacks,
     ) {
-        for (tx, related_out_points) in txs {
+        for (tx, header, related_out_points) in txs {
             let hash = tx.hash();
             trace!("committed {}", hash);
             // try remove committed tx from proposed
-            if let Some(entry) = self.proposed.remove_committed_tx(tx, &related_out_points) {
-                callbacks.call_committed(self, &entry)
+            if let Some(entry) = self.proposed.remove_committed_tx(&tx, &related_out_points) {
+                callbacks.call_committed(self, &entry, &header)
             } else {
                 // if committed tx is not in proposed, it may conflict
-                let (input_conflict, deps_consumed) = self.proposed.resolve_conflict(tx);
+                let (input_conflict, deps_consumed) = self.proposed.resolve_conflict(&tx);
 
                 for (entry, reject) in input_conflict {
                     callbacks.call_reject(self, &entry, reject);
@@ -493,6 +687,135 @@ impl TxPool {
         TxPoolEntryInfo { pending, proposed }
     }
 
+    // The pending and gap queues do not track the spent inputs, so they may hold transactions
+    // spending the same inputs, with each other or with a proposed transaction.
+    pub(crate) fn get_conflicts(&self, tx_hash: &Byte32) -> Option<TxPoolConflicts> {
+        let entries: Vec<(&TxEntry, TxStatus)> = self
+            .pending
+            .iter()
+            .chain(self.gap.iter())
+            .map(|(_, entry)| (entry, TxStatus::Pending))
+            .chain(
+                self.proposed
+                    .iter()
+                    .map(|(_, entry)| (entry, TxStatus::Proposed)),
+            )
+            .collect();
+        find_conflicts(&entries, tx_hash)
+    }
+
+    /// Returns a page of the in-pool transaction details in the order, which starts after the
+    /// transaction `cursor`. Returns `None` when the cursor is not in the pool.
+    ///
+    /// The entries are ranked by the ancestors packages tracked by the pool. The pending queue
+    /// does not track the relations between the transactions, so the ancestors and the
+    /// descendants of the entries in the page are computed from the inputs of the in-pool
+    /// transactions.
+    pub(crate) fn get_entry_details(
+        &self,
+        order_by: PoolEntriesOrder,
+        limit: usize,
+        cursor: Option<&Byte32>,
+    ) -> Option<TxPoolEntryDetails> {
+        let entries: Vec<(&TxEntry, TxStatus)> = self
+            .pending
+            .iter()
+            .chain(self.gap.iter())
+            .map(|(_, entry)| (entry, TxStatus::Pending))
+            .chain(
+                self.proposed
+                    .iter()
+                    .map(|(_, entry)| (entry, TxStatus::Proposed)),
+            )
+            .collect();
+        let graph = EntryGraph::new(&entries);
+        let effective_fee_rates: Vec<FeeRate> = entries
+            .iter()
+            .map(|(entry, _)| entry.effective_fee_rate())
+            .collect();
+
+        // The eviction score is the share of the pool size ranking ahead by the effective fee
+        // rate, the ties are broken by the hashes to keep the pages stable.
+        let mut by_fee_rate: Vec<usize> = (0..entries.len()).collect();
+        by_fee_rate.sort_by(|&a, &b| {
+            effective_fee_rates[b]
+                .cmp(&effective_fee_rates[a])
+                .then_with(|| graph.hashes[a].raw_data().cmp(&graph.hashes[b].raw_data()))
+        });
+        let total_size: u64 = entries.iter().map(|(entry, _)| entry.size as u64).sum();
+        let min_fee_rate = self.min_fee_rate();
+        let mut eviction_scores = vec![0u8; entries.len()];
+        let mut size_ahead = 0u64;
+        for &i in &by_fee_rate {
+            let entry = entries[i].0;
+            eviction_scores[i] = if entry.fee_rate() < min_fee_rate {
+                100
+            } else {
+                (u128::from(size_ahead) * 100 / u128::from(total_size.max(1))) as u8
+            };
+            size_ahead += entry.size as u64;
+        }
+
+        let order = match order_by {
+            PoolEntriesOrder::FeeRate => by_fee_rate,
+            PoolEntriesOrder::Timestamp => {
+                let mut order: Vec<usize> = (0..entries.len()).collect();
+                order.sort_by(|&a, &b| {
+                    entries[a]
+                        .0
+                        .timestamp
+                        .cmp(&entries[b].0.timestamp)
+                        .then_with(|| graph.hashes[a].raw_data().cmp(&graph.hashes[b].raw_data()))
+                });
+                order
+            }
+        };
+        let start = match cursor {
+            Some(cursor) => order.iter().position(|&i| &graph.hashes[i] == cursor)? + 1,
+            None => 0,
+        };
+        let end = std::cmp::min(start.saturating_add(limit), order.len());
+        let next_cursor = if end < order.len() && end > start {
+            Some(graph.hashes[order[end - 1]].clone())
+        } else {
+            None
+        };
+
+        let details = order[start..end]
+            .iter()
+            .map(|&i| {
+                let (entry, status) = entries[i];
+                let ancestors = graph.relatives_stats(&entries, i, &graph.parents);
+                let descendants = graph.relatives_stats(&entries, i, &graph.children);
+                TxPoolEntryDetail {
+                    tx_hash: graph.hashes[i].clone(),
+                    status,
+                    cycles: entry.cycles,
+                    size: entry.size as u64,
+                    fee: entry.fee,
+                    fee_rate: entry.fee_rate(),
+                    effective_fee_rate: effective_fee_rates[i],
+                    ancestors_count: ancestors.count,
+                    ancestors_size: ancestors.size,
+                    ancestors_cycles: ancestors.cycles,
+                    ancestors_fee: ancestors.fee,
+                    descendants_count: descendants.count,
+                    descendants_size: descendants.size,
+                    descendants_cycles: descendants.cycles,
+                    descendants_fee: descendants.fee,
+                    timestamp: entry.timestamp,
+                    eviction_score: eviction_scores[i],
+                }
+            })
+            .collect();
+
+        Some(TxPoolEntryDetails {
+            entries: details,
+            total: entries.len() as u64,
+            next_cursor,
+        })
+    }
+
     pub(crate) fn drain_all_transactions(&mut self) -> Vec<TransactionView> {
         let mut txs = CommitTxsScanner::new(&self.proposed)
             .txs_to_commit(self.total_tx_size, self.total_tx_cycles)
@@ -506,3 +829,193 @@ impl TxPool {
         txs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_min_fee_rate() {
+        let config = DynamicMinFeeRateConfig::default();
+        let base = FeeRate::from_u64(1_000);
+        let floor = |size| dynamic_min_fee_rate(&config, base, size, 1_000).as_u64();
+
+        assert_eq!(floor(0), 1_000);
+        assert_eq!(floor(499), 1_000);
+        assert_eq!(floor(500), 2_000);
+        assert_eq!(floor(599), 2_000);
+        assert_eq!(floor(600), 4_000);
+        assert_eq!(floor(900), 32_000);
+        assert_eq!(floor(1_000), 64_000);
+        // capped by max_fee_rate
+        assert_eq!(floor(2_000), 100_000);
+        // never lower than the base
+        let base = FeeRate::from_u64(200_000);
+        assert_eq!(
+            dynamic_min_fee_rate(&config, base, 1_000, 1_000).as_u64(),
+            200_000
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        use ckb_types::{
+            bytes::Bytes,
+            core::TransactionBuilder,
+            h256,
+            packed::{CellDep, CellInput},
+            prelude::*,
+            H256,
+        };
+
+        let out_point = |index: u32| OutPoint::new(h256!("0x1").pack(), index);
+        let entry = |inputs: &[u32], deps: &[u32], fee: u64| {
+            let tx = TransactionBuilder::default()
+                .inputs(
+                    inputs
+                        .iter()
+                        .map(|index| CellInput::new(out_point(*index), 0)),
+                )
+                .cell_deps(
+                    deps.iter()
+                        .map(|index| CellDep::new_builder().out_point(out_point(*index)).build()),
+                )
+                .witness(Bytes::new().pack())
+                .build();
+            TxEntry::dummy_resolve(tx, 0, Capacity::shannons(fee), 100)
+        };
+        let tx = entry(&[0, 1], &[2], 100);
+        // spends the same input
+        let double_spend = entry(&[1], &[], 200);
+        // spends the cell dep
+        let dep_spend = entry(&[2], &[], 300);
+        // uses the input as a cell dep
+        let input_dep = entry(&[3], &[0], 400);
+        let unrelated = entry(&[4], &[5], 500);
+        let entries = vec![
+            (&double_spend, TxStatus::Pending),
+            (&tx, TxStatus::Pending),
+            (&dep_spend, TxStatus::Proposed),
+            (&input_dep, TxStatus::Pending),
+            (&unrelated, TxStatus::Proposed),
+        ];
+
+        let conflict =
+            |entry: &TxEntry, status, inputs: Vec<OutPoint>, deps: Vec<OutPoint>| TxConflict {
+                tx_hash: entry.transaction().hash(),
+                status,
+                fee: entry.fee,
+                fee_rate: entry.fee_rate(),
+                inputs,
+                deps,
+            };
+        let tx_hash = tx.transaction().hash();
+        assert_eq!(
+            find_conflicts(&entries, &tx_hash),
+            Some(TxPoolConflicts {
+                tx_hash: tx_hash.clone(),
+                status: TxStatus::Pending,
+                fee_rate: tx.fee_rate(),
+                conflicts: vec![
+                    conflict(&double_spend, TxStatus::Pending, vec![out_point(1)], vec![]),
+                    conflict(&dep_spend, TxStatus::Proposed, vec![], vec![out_point(2)]),
+                    conflict(&input_dep, TxStatus::Pending, vec![], vec![out_point(0)]),
+                ],
+            })
+        );
+
+        // the conflicts are found from the other side too
+        let conflicts = find_conflicts(&entries, &dep_spend.transaction().hash()).unwrap();
+        assert_eq!(conflicts.status, TxStatus::Proposed);
+        assert_eq!(
+            conflicts.conflicts,
+            vec![conflict(&tx, TxStatus::Pending, vec![], vec![out_point(2)])]
+        );
+        assert!(find_conflicts(&entries, &unrelated.transaction().hash())
+            .unwrap()
+            .conflicts
+            .is_empty());
+        assert!(find_conflicts(&entries, &h256!("0x2").pack()).is_none());
+    }
+
+    #[test]
+    fn test_entry_graph_relatives() {
+        use ckb_types::{
+            bytes::Bytes,
+            core::TransactionBuilder,
+            packed::{CellInput, OutPoint},
+            prelude::*,
+        };
+
+        let spend = |parent: &TxEntry, fee: u64| {
+            TxEntry::dummy_resolve(
+                TransactionBuilder::default()
+                    .input(CellInput::new(
+                        OutPoint::new(parent.transaction().hash(), 0),
+                        0,
+                    ))
+                    .witness(Bytes::new().pack())
+                    .build(),
+                fee,
+                Capacity::shannons(fee),
+                fee as usize,
+            )
+        };
+        let tx1 = TxEntry::dummy_resolve(
+            TransactionBuilder::default().build(),
+            100,
+            Capacity::shannons(100),
+            100,
+        );
+        let tx2 = spend(&tx1, 200);
+        let tx3 = spend(&tx2, 300);
+        let entries = vec![
+            (&tx3, TxStatus::Pending),
+            (&tx1, TxStatus::Proposed),
+            (&tx2, TxStatus::Pending),
+        ];
+        let graph = EntryGraph::new(&entries);
+
+        let ancestors = graph.relatives_stats(&entries, 0, &graph.parents);
+        assert_eq!(ancestors.count, 2);
+        assert_eq!(ancestors.size, 300);
+        assert_eq!(ancestors.cycles, 300);
+        assert_eq!(ancestors.fee, Capacity::shannons(300));
+
+        let descendants = graph.relatives_stats(&entries, 1, &graph.children);
+        assert_eq!(descendants.count, 2);
+        assert_eq!(descendants.size, 500);
+
+        let descendants = graph.relatives_stats(&entries, 0, &graph.children);
+        assert_eq!(descendants.count, 0);
+    }
+
+    #[test]
+    fn test_effective_fee_rate() {
+        use ckb_types::{bytes::Bytes, core::TransactionBuilder, prelude::*};
+
+        let parent = TxEntry::dummy_resolve(
+            TransactionBuilder::default().build(),
+            0,
+            Capacity::shannons(100),
+            100,
+        );
+        let mut child = TxEntry::dummy_resolve(
+            TransactionBuilder::default()
+                .witness(Bytes::new().pack())
+                .build(),
+            0,
+            Capacity::shannons(1000),
+            100,
+        );
+        assert_eq!(child.effective_fee_rate(), FeeRate::from_u64(10_000));
+
+        // the cheap parent lowers the package fee rate
+        child.add_entry_weight(&parent);
+        assert_eq!(child.fee_rate(), FeeRate::from_u64(10_000));
+        assert_eq!(child.effective_fee_rate(), FeeRate::from_u64(5_500));
+
+        // without ancestors it is the own fee rate
+        assert_eq!(parent.effective_fee_rate(), FeeRate::from_u64(1_000));
+    }
+}
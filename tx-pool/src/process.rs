@@ -3,6 +3,7 @@ use crate::callback::Callbacks;
 use crate::component::chunk::DEFAULT_MAX_CHUNK_TRANSACTIONS;
 use crate::component::commit_txs_scanner::CommitTxsScanner;
 use crate::component::entry::TxEntry;
+use crate::component::get_transaction_virtual_bytes;
 use crate::component::orphan::Entry as OrphanEntry;
 use crate::error::Reject;
 use crate::pool::TxPool;
@@ -16,6 +17,7 @@ use ckb_dao::DaoCalculator;
 use ckb_error::{AnyError, InternalErrorKind};
 use ckb_jsonrpc_types::BlockTemplate;
 use ckb_logger::{debug, error, info, warn};
+use ckb_metrics::metrics;
 use ckb_network::PeerIndex;
 use ckb_snapshot::Snapshot;
 use ckb_store::ChainStore;
@@ -26,19 +28,19 @@ use ckb_types::{
             TransactionsChecker,
         },
         hardfork::HardForkSwitch,
-        BlockView, Capacity, Cycle, EpochExt, HeaderView, ScriptHashType, TransactionView,
+        BlockView, Capacity, Cycle, EpochExt, FeeRate, HeaderView, ScriptHashType, TransactionView,
         UncleBlockView, Version,
     },
     packed::{Byte32, CellbaseWitness, OutPoint, ProposalShortId, Script},
     prelude::*,
 };
+use ckb_util::mock_clock::unix_time_as_millis;
 use ckb_util::LinkedHashSet;
 use ckb_verification::{
     cache::{CacheEntry, Completed},
     ContextualTransactionVerifier, ScriptVerifyResult, TimeRelativeTransactionVerifier,
     TxVerifyEnv,
 };
-use faketime::unix_time_as_millis;
 use std::collections::HashSet;
 use std::collections::{HashMap, VecDeque};
 use std::convert::TryInto;
@@ -244,7 +246,7 @@ impl TxPoolService {
             ResolveOptions::empty().set_skip_immature_header_deps_check(flag)
         };
 
-        let rtxs: Vec<_> = block_in_place(|| {
+        let (rtxs, fees): (Vec<_>, Vec<_>) = block_in_place(|| {
             entries_iter
                 .enumerate()
                 .filter_map(|(index, entry)| {
@@ -269,16 +271,21 @@ impl TxPoolService {
                             transactions_checker.insert(entry.transaction());
                             template_txs.push(BlockAssembler::transform_tx(&entry, false, None))
                         }
-                        Some(entry.rtx)
+                        Some((entry.rtx, entry.fee))
                     }
                 })
-                .collect()
+                .unzip()
         });
 
         // Generate DAO fields here
         let dao = DaoCalculator::new(consensus, &snapshot.as_data_provider())
             .dao_field(&rtxs, tip_header)?;
 
+        // Reject the template rather than let miners waste hashes on an invalid block
+        block_in_place(|| {
+            BlockAssembler::check_template_dao(snapshot, &rtxs, &fees, &dao, resolve_opts)
+        })?;
+
         let candidate_number = tip_header.number() + 1;
         let cycles_limit = consensus.max_block_cycles();
         let uncles_count_limit = consensus.max_uncles_num() as u32;
@@ -543,7 +550,12 @@ impl TxPoolService {
             Some((declared_cycle, peer)) => match ret {
                 Ok(verified) => {
                     if declared_cycle == verified.cycles {
-                        self.broadcast_tx(Some(peer), tx_hash, with_vm_2021);
+                        self.broadcast_tx(
+                            Some(peer),
+                            tx_hash,
+                            with_vm_2021,
+                            completed_fee_rate(&tx, verified),
+                        );
                         self.process_orphan_tx(&tx).await;
                     } else {
                         warn!(
@@ -569,13 +581,26 @@ impl TxPoolService {
             },
             None => {
                 match ret {
-                    Ok(_) => {
-                        self.broadcast_tx(None, tx_hash, with_vm_2021);
+                    Ok(verified) => {
+                        self.broadcast_tx(
+                            None,
+                            tx_hash,
+                            with_vm_2021,
+                            completed_fee_rate(&tx, verified),
+                        );
                         self.process_orphan_tx(&tx).await;
                     }
                     Err(Reject::Duplicated(_)) => {
-                        // re-broadcast tx when it's duplicated and submitted through local rpc
-                        self.broadcast_tx(None, tx_hash, with_vm_2021);
+                        // re-broadcast tx when it's duplicated and submitted through local rpc,
+                        // the tx which is still being verified is broadcast once it is accepted
+                        let fee_rate = self
+                            .tx_pool
+                            .read()
+                            .await
+                            .get_fee_rate(&tx.proposal_short_id());
+                        if let Some(fee_rate) = fee_rate {
+                            self.broadcast_tx(None, tx_hash, with_vm_2021, fee_rate);
+                        }
                     }
                     Err(_err) => {
                         // ignore
@@ -591,10 +616,29 @@ impl TxPoolService {
         peer: PeerIndex,
         declared_cycle: Cycle,
     ) {
-        self.orphan
-            .write()
-            .await
-            .add_orphan_tx(tx, peer, declared_cycle)
+        let missing_parents: Vec<Byte32> = {
+            let mut orphan = self.orphan.write().await;
+            if orphan.contains_key(&tx.proposal_short_id()) {
+                return;
+            }
+            // The parents in the orphan pool are waiting for their own parents
+            let missing_parents = tx
+                .input_pts_iter()
+                .map(|pt| pt.tx_hash())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .filter(|hash| !orphan.contains_key(&ProposalShortId::from_tx_hash(hash)))
+                .collect();
+            orphan.add_orphan_tx(tx, peer, declared_cycle);
+            missing_parents
+        };
+
+        // Asks the announcing peer for the missing parents through the relayer
+        if !missing_parents.is_empty() {
+            if let Err(e) = self.ask_for_txs_sender.send((peer, missing_parents)) {
+                error!("tx-pool ask_for_txs internal error {}", e);
+            }
+        }
     }
 
     pub(crate) async fn find_orphan_by_previous(
@@ -620,6 +664,7 @@ impl TxPoolService {
             if let Some(orphan) = self.find_orphan_by_previous(&previous).await {
                 if orphan.cycle > self.tx_pool_config.max_tx_verify_cycles {
                     self.remove_orphan_tx(&orphan.tx.proposal_short_id()).await;
+                    metrics!(counter, "ckb-tx-pool.orphan_promoted", 1, "to" => "chunk");
                     orphan_queue.push_back(orphan.tx.clone());
                     self.chunk
                         .write()
@@ -627,7 +672,7 @@ impl TxPoolService {
                         .add_remote_tx(orphan.tx, (orphan.cycle, orphan.peer));
                 } else {
                     match self._process_tx(orphan.tx.clone(), None).await {
-                        Ok(_) => {
+                        Ok(verified) => {
                             let with_vm_2021 = {
                                 let epoch = self.snapshot().tip_header().epoch().number();
                                 self.consensus
@@ -635,12 +680,19 @@ impl TxPoolService {
                                     .is_vm_version_1_and_syscalls_2_enabled(epoch)
                             };
                             self.remove_orphan_tx(&orphan.tx.proposal_short_id()).await;
-                            self.broadcast_tx(Some(orphan.peer), orphan.tx.hash(), with_vm_2021);
+                            metrics!(counter, "ckb-tx-pool.orphan_promoted", 1, "to" => "pool");
+                            self.broadcast_tx(
+                                Some(orphan.peer),
+                                orphan.tx.hash(),
+                                with_vm_2021,
+                                completed_fee_rate(&orphan.tx, &verified),
+                            );
                             orphan_queue.push_back(orphan.tx);
                         }
                         Err(reject) => {
                             if !is_missing_input(&reject) {
                                 self.remove_orphan_tx(&orphan.tx.proposal_short_id()).await;
+                                metrics!(counter, "ckb-tx-pool.orphan_evicted", 1, "reason" => "rejected");
                             }
                             if reject.is_malformed_tx() {
                                 self.ban_malformed(orphan.peer, format!("reject {}", reject));
@@ -653,6 +705,61 @@ impl TxPoolService {
         }
     }
 
+    /// Stages the local transaction which is rejected because the `since` of the input
+    /// `immature_input` is not satisfied yet.
+    ///
+    /// Returns false when the staging is disabled or full.
+    pub(crate) async fn stage_tx(&self, tx: TransactionView, immature_input: usize) -> bool {
+        match self.tx_pool_config.time_lock_staging {
+            Some(ref config) => {
+                let tx_hash = tx.hash();
+                let staged = self
+                    .staging
+                    .write()
+                    .await
+                    .add_staged_tx(tx, immature_input, config);
+                if staged {
+                    debug!(
+                        "stage tx {} until the since of input {} is satisfied",
+                        tx_hash, immature_input
+                    );
+                }
+                staged
+            }
+            None => false,
+        }
+    }
+
+    // Submits the staged transactions again on the new tip, the ones still immature stay staged.
+    pub(crate) async fn process_staged_txs(&self) {
+        let txs = {
+            let mut staging = self.staging.write().await;
+            staging.remove_expired();
+            staging.txs()
+        };
+
+        for tx in txs {
+            let id = tx.proposal_short_id();
+            let ret = self.process_tx(tx.clone(), None).await;
+            let immature_input = ret.as_ref().err().and_then(Reject::immature_input);
+            let mut staging = self.staging.write().await;
+            if let Some(index) = immature_input {
+                staging.update_immature_input(&id, index);
+                continue;
+            }
+            staging.remove_staged_tx(&id);
+            match ret {
+                Ok(_) => {
+                    metrics!(counter, "ckb-tx-pool.staging_promoted", 1);
+                }
+                Err(reject) => {
+                    debug!("drop staged tx {}, reason: {}", tx.hash(), reject);
+                    metrics!(counter, "ckb-tx-pool.staging_evicted", 1, "reason" => "rejected");
+                }
+            }
+        }
+    }
+
     pub(crate) fn all_inputs_is_unknown(&self, tx: &TransactionView) -> bool {
         let snapshot = self.snapshot();
         !tx.input_pts_iter()
@@ -664,8 +771,12 @@ impl TxPoolService {
         origin: Option<PeerIndex>,
         tx_hash: Byte32,
         with_vm_2021: bool,
+        fee_rate: FeeRate,
     ) {
-        if let Err(e) = self.tx_relay_sender.send((origin, with_vm_2021, tx_hash)) {
+        if let Err(e) = self
+            .tx_relay_sender
+            .send((origin, with_vm_2021, tx_hash, fee_rate))
+        {
             error!("tx-pool broadcast_tx internal error {}", e);
         }
     }
@@ -707,6 +818,11 @@ impl TxPoolService {
         if self.chunk_contains(&tx).await || self.orphan_contains(&tx).await {
             return Err(Reject::Duplicated(tx.hash()));
         }
+        // Only the first of the concurrent submissions verifies the transaction
+        let _inflight = self
+            .inflight
+            .enter(tx.witness_hash())
+            .ok_or_else(|| Reject::Duplicated(tx.hash()))?;
 
         let (tip_hash, snapshot, rtx, status, fee, tx_size) = self.pre_check(tx).await?;
 
@@ -818,6 +934,11 @@ impl TxPoolService {
         if self.chunk_contains(&tx).await || self.orphan_contains(&tx).await {
             return Err(Reject::Duplicated(tx.hash()));
         }
+        // Only the first of the concurrent submissions verifies the transaction
+        let _inflight = self
+            .inflight
+            .enter(tx.witness_hash())
+            .ok_or_else(|| Reject::Duplicated(tx.hash()))?;
 
         let (tip_hash, snapshot, rtx, status, fee, tx_size) = self.pre_check(tx).await?;
 
@@ -862,7 +983,7 @@ impl TxPoolService {
             detached.extend(blk.transactions().into_iter().skip(1))
         }
 
-        for blk in attached_blocks {
+        for blk in &attached_blocks {
             attached.extend(blk.transactions().into_iter().skip(1));
         }
         let retain: Vec<TransactionView> = detached.difference(&attached).cloned().collect();
@@ -890,7 +1011,7 @@ impl TxPoolService {
 
                 _update_tx_pool_for_reorg(
                     &mut tx_pool,
-                    &attached,
+                    &attached_blocks,
                     detached_proposal_id,
                     snapshot,
                     &self.callbacks,
@@ -915,6 +1036,12 @@ impl TxPoolService {
             chunk.remove_chunk_txs(attached.iter().map(|tx| tx.proposal_short_id()));
         }
 
+        {
+            let mut staging = self.staging.write().await;
+            staging.remove_staged_txs(attached.iter().map(|tx| tx.proposal_short_id()));
+        }
+        self.process_staged_txs().await;
+
         // update network fork switch each block
         {
             if !self.network.load_ckb2021() {
@@ -995,6 +1122,13 @@ type PreCheckedTx = (
 
 type ResolveResult = Result<(ResolvedTransaction, TxStatus), Reject>;
 
+// The fee rate of the verified tx, computed from the virtual bytes like the pool entry.
+fn completed_fee_rate(tx: &TransactionView, completed: &Completed) -> FeeRate {
+    let vbytes =
+        get_transaction_virtual_bytes(tx.data().serialized_size_in_block(), completed.cycles);
+    FeeRate::calculate(completed.fee, vbytes as usize)
+}
+
 fn check_rtx(
     tx_pool: &TxPool,
     snapshot: &Snapshot,
@@ -1079,6 +1213,7 @@ fn _submit_entry(
         TxStatus::Gap => {
             tx_pool.add_gap(entry.clone());
             callbacks.call_pending(tx_pool, &entry);
+            callbacks.call_gap(tx_pool, &entry);
         }
         TxStatus::Proposed => {
             tx_pool.add_proposed(entry.clone())?;
@@ -1090,23 +1225,32 @@ fn _submit_entry(
 
 fn _update_tx_pool_for_reorg(
     tx_pool: &mut TxPool,
-    attached: &LinkedHashSet<TransactionView>,
+    attached_blocks: &VecDeque<BlockView>,
     detached_proposal_id: HashSet<ProposalShortId>,
     snapshot: Arc<Snapshot>,
     callbacks: &Callbacks,
 ) {
     tx_pool.snapshot = Arc::clone(&snapshot);
 
-    let txs_iter = attached.iter().map(|tx| {
-        let get_cell_data = |out_point: &OutPoint| {
-            snapshot
-                .get_cell_data(out_point)
-                .map(|(data, _data_hash)| data)
-        };
-        let related_out_points =
-            get_related_dep_out_points(tx, get_cell_data).expect("Get dep out points failed");
-        (tx, related_out_points)
-    });
+    let txs_iter = attached_blocks
+        .iter()
+        .flat_map(|blk| {
+            let header = blk.header();
+            blk.transactions()
+                .into_iter()
+                .skip(1)
+                .map(move |tx| (tx, header.clone()))
+        })
+        .map(|(tx, header)| {
+            let get_cell_data = |out_point: &OutPoint| {
+                snapshot
+                    .get_cell_data(out_point)
+                    .map(|(data, _data_hash)| data)
+            };
+            let related_out_points =
+                get_related_dep_out_points(&tx, get_cell_data).expect("Get dep out points failed");
+            (tx, header, related_out_points)
+        });
     // NOTE: `remove_expired` will try to re-put the given expired/detached proposals into
     // pending-pool if they can be found within txpool. As for a transaction
     // which is both expired and committed at the one time(commit at its end of commit-window),
@@ -1166,6 +1310,8 @@ fn _update_tx_pool_for_reorg(
         if let Err(e) = tx_pool.gap_rtx(cycles, entry.size, entry.rtx.clone()) {
             debug!("Failed to add tx to gap {}, reason: {}", tx_hash, e);
             callbacks.call_reject(tx_pool, &entry, e.clone());
+        } else {
+            callbacks.call_gap(tx_pool, &entry);
         }
     }
 }
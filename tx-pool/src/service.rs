@@ -1,9 +1,12 @@
 //! Tx-pool background service
 
 use crate::block_assembler::BlockAssembler;
-use crate::callback::{Callback, Callbacks, ProposedCallback, RejectCallback};
+use crate::callback::{Callback, Callbacks, CommittedCallback, ProposedCallback, RejectCallback};
 use crate::chunk_process::Command;
-use crate::component::{chunk::ChunkQueue, entry::TxEntry, orphan::OrphanPool};
+use crate::component::{
+    chunk::ChunkQueue, entry::TxEntry, inflight::InflightTxs, orphan::OrphanPool,
+    staging::StagingArea,
+};
 use crate::error::{handle_recv_error, handle_send_cmd_error, handle_try_send_error};
 use crate::pool::{TxPool, TxPoolInfo};
 use crate::process::PlugTarget;
@@ -18,8 +21,11 @@ use ckb_snapshot::{Snapshot, SnapshotMgr};
 use ckb_stop_handler::{SignalSender, StopHandler, WATCH_INIT};
 use ckb_types::{
     core::{
-        tx_pool::{TxPoolEntryInfo, TxPoolIds},
-        BlockView, Cycle, TransactionView, UncleBlockView, Version,
+        tx_pool::{
+            PoolEntriesOrder, StagedTxInfo, TxPoolConflicts, TxPoolEntryDetails, TxPoolEntryInfo,
+            TxPoolIds,
+        },
+        BlockView, Cycle, FeeRate, TransactionView, UncleBlockView, Version,
     },
     packed::{Byte32, ProposalShortId},
 };
@@ -95,6 +101,10 @@ pub(crate) enum Message {
     ClearPool(Request<Arc<Snapshot>, ()>),
     GetAllEntryInfo(Request<(), TxPoolEntryInfo>),
     GetAllIds(Request<(), TxPoolIds>),
+    GetPoolConflicts(Request<Byte32, Option<TxPoolConflicts>>),
+    GetEntryDetails(Request<(PoolEntriesOrder, usize, Option<Byte32>), Option<TxPoolEntryDetails>>),
+    StageTx(Request<(TransactionView, usize), bool>),
+    GetStagedTxs(Request<(), Vec<StagedTxInfo>>),
 }
 
 /// Controller to the tx-pool service.
@@ -126,6 +136,14 @@ impl TxPoolController {
         self.started.load(Ordering::Relaxed)
     }
 
+    /// Stops the tx-pool service, even if the controller is still cloned elsewhere.
+    pub fn force_stop(&self) {
+        if self.service_started() {
+            self.chunk_stop.force_send(Command::Stop);
+            self.stop.force_send(());
+        }
+    }
+
     /// Return reference of tokio runtime handle
     pub fn handle(&self) -> &Handle {
         &self.handle
@@ -412,6 +430,80 @@ impl TxPoolController {
             .map_err(Into::into)
     }
 
+    /// Returns the in-pool transactions spending the same inputs as the in-pool transaction,
+    /// or `None` when the transaction is not in the pool
+    pub fn get_pool_conflicts(&self, tx_hash: Byte32) -> Result<Option<TxPoolConflicts>, AnyError> {
+        let (responder, response) = oneshot::channel();
+        let request = Request::call(tx_hash, responder);
+        self.sender
+            .try_send(Message::GetPoolConflicts(request))
+            .map_err(|e| {
+                let (_m, e) = handle_try_send_error(e);
+                e
+            })?;
+        self.handle
+            .block_on(response)
+            .map_err(handle_recv_error)
+            .map_err(Into::into)
+    }
+
+    /// Returns a page of the in-pool transaction details, which starts after the transaction
+    /// `cursor`, or `None` when the cursor is not in the pool
+    pub fn get_entry_details(
+        &self,
+        order_by: PoolEntriesOrder,
+        limit: usize,
+        cursor: Option<Byte32>,
+    ) -> Result<Option<TxPoolEntryDetails>, AnyError> {
+        let (responder, response) = oneshot::channel();
+        let request = Request::call((order_by, limit, cursor), responder);
+        self.sender
+            .try_send(Message::GetEntryDetails(request))
+            .map_err(|e| {
+                let (_m, e) = handle_try_send_error(e);
+                e
+            })?;
+        self.handle
+            .block_on(response)
+            .map_err(handle_recv_error)
+            .map_err(Into::into)
+    }
+
+    /// Stages the local transaction rejected because the `since` of the input `immature_input`
+    /// is not satisfied yet, it is submitted again on every new tip.
+    ///
+    /// Returns false when `tx_pool.time_lock_staging` is not configured or the staging is full.
+    pub fn stage_tx(&self, tx: TransactionView, immature_input: usize) -> Result<bool, AnyError> {
+        let (responder, response) = oneshot::channel();
+        let request = Request::call((tx, immature_input), responder);
+        self.sender
+            .try_send(Message::StageTx(request))
+            .map_err(|e| {
+                let (_m, e) = handle_try_send_error(e);
+                e
+            })?;
+        self.handle
+            .block_on(response)
+            .map_err(handle_recv_error)
+            .map_err(Into::into)
+    }
+
+    /// Returns the staged transactions, the earliest staged first
+    pub fn get_staged_txs(&self) -> Result<Vec<StagedTxInfo>, AnyError> {
+        let (responder, response) = oneshot::channel();
+        let request = Request::call((), responder);
+        self.sender
+            .try_send(Message::GetStagedTxs(request))
+            .map_err(|e| {
+                let (_m, e) = handle_try_send_error(e);
+                e
+            })?;
+        self.handle
+            .block_on(response)
+            .map_err(handle_recv_error)
+            .map_err(Into::into)
+    }
+
     /// send suspend chunk process cmd
     pub fn suspend_chunk_process(&self) -> Result<(), AnyError> {
         self.chunk_tx
@@ -441,14 +533,22 @@ pub struct TxPoolServiceBuilder {
     pub(crate) reorg_receiver: mpsc::Receiver<Notify<ChainReorgArgs>>,
     pub(crate) signal_receiver: watch::Receiver<u8>,
     pub(crate) handle: Handle,
-    pub(crate) tx_relay_sender: ckb_channel::Sender<(Option<PeerIndex>, bool, Byte32)>,
+    pub(crate) tx_relay_sender: ckb_channel::Sender<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+    pub(crate) ask_for_txs_sender: ckb_channel::Sender<(PeerIndex, Vec<Byte32>)>,
     pub(crate) chunk_rx: ckb_channel::Receiver<Command>,
     pub(crate) chunk: Arc<RwLock<ChunkQueue>>,
     pub(crate) started: Arc<AtomicBool>,
+    // Held by each task of the service, the stop waits until all of them are dropped
+    pub(crate) done: ckb_channel::Sender<()>,
 }
 
 impl TxPoolServiceBuilder {
     /// Creates a new TxPoolServiceBuilder.
+    ///
+    /// The hashes of the transactions to be broadcast are sent to `tx_relay_sender`, and the
+    /// missing parents of the orphan transactions to be requested from the peers are sent to
+    /// `ask_for_txs_sender`.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         tx_pool_config: TxPoolConfig,
         snapshot: Arc<Snapshot>,
@@ -456,12 +556,14 @@ impl TxPoolServiceBuilder {
         txs_verify_cache: Arc<RwLock<TxVerificationCache>>,
         snapshot_mgr: Arc<SnapshotMgr>,
         handle: &Handle,
-        tx_relay_sender: ckb_channel::Sender<(Option<PeerIndex>, bool, Byte32)>,
+        tx_relay_sender: ckb_channel::Sender<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+        ask_for_txs_sender: ckb_channel::Sender<(PeerIndex, Vec<Byte32>)>,
     ) -> (TxPoolServiceBuilder, TxPoolController) {
         let (sender, receiver) = mpsc::channel(DEFAULT_CHANNEL_SIZE);
         let (reorg_sender, reorg_receiver) = mpsc::channel(DEFAULT_CHANNEL_SIZE);
         let (signal_sender, signal_receiver) = watch::channel(WATCH_INIT);
         let (chunk_tx, chunk_rx) = ckb_channel::bounded(12);
+        let (done, done_receiver) = ckb_channel::bounded(0);
         let chunk = Arc::new(RwLock::new(ChunkQueue::new()));
         let started = Arc::new(AtomicBool::new(false));
 
@@ -477,12 +579,14 @@ impl TxPoolServiceBuilder {
             signal_receiver,
             handle: handle.clone(),
             tx_relay_sender,
+            ask_for_txs_sender,
             chunk_rx,
             chunk,
             started: Arc::clone(&started),
+            done,
         };
 
-        let stop = StopHandler::new(SignalSender::Watch(signal_sender), None);
+        let stop = StopHandler::with_done(SignalSender::Watch(signal_sender), done_receiver);
         let chunk_stop = StopHandler::new(SignalSender::Crossbeam(chunk_tx.clone()), None);
         let controller = TxPoolController {
             sender,
@@ -501,13 +605,18 @@ impl TxPoolServiceBuilder {
         self.callbacks.register_pending(callback);
     }
 
+    /// Register new gap callback
+    pub fn register_gap(&mut self, callback: Callback) {
+        self.callbacks.register_gap(callback);
+    }
+
     /// Register new proposed callback
     pub fn register_proposed(&mut self, callback: ProposedCallback) {
         self.callbacks.register_proposed(callback);
     }
 
     /// Register new committed callback
-    pub fn register_committed(&mut self, callback: Callback) {
+    pub fn register_committed(&mut self, callback: CommittedCallback) {
         self.callbacks.register_committed(callback);
     }
 
@@ -530,12 +639,15 @@ impl TxPoolServiceBuilder {
             tx_pool_config: Arc::new(tx_pool.config),
             tx_pool: Arc::new(RwLock::new(tx_pool)),
             orphan: Arc::new(RwLock::new(OrphanPool::new())),
+            staging: Arc::new(RwLock::new(StagingArea::new())),
             block_assembler: self.block_assembler,
             txs_verify_cache: self.txs_verify_cache,
             snapshot_mgr: self.snapshot_mgr,
             callbacks: Arc::new(self.callbacks),
             tx_relay_sender: self.tx_relay_sender,
+            ask_for_txs_sender: self.ask_for_txs_sender,
             chunk: self.chunk,
+            inflight: Arc::new(InflightTxs::new()),
             network,
             consensus,
             last_txs_updated_at,
@@ -547,7 +659,11 @@ impl TxPoolServiceBuilder {
             self.chunk_rx,
         );
 
-        self.handle.spawn_blocking(move || chunk_process.run());
+        let done = self.done.clone();
+        self.handle.spawn_blocking(move || {
+            chunk_process.run();
+            drop(done);
+        });
 
         let mut receiver = self.receiver;
         let mut reorg_receiver = self.reorg_receiver;
@@ -555,20 +671,29 @@ impl TxPoolServiceBuilder {
 
         let process_service = service.clone();
         let mut signal_receiver = self.signal_receiver.clone();
+        let done = self.done.clone();
         self.handle.spawn(async move {
             loop {
                 tokio::select! {
                     Some(message) = receiver.recv() => {
                         let service_clone = process_service.clone();
-                        handle_clone.spawn(process(service_clone, message));
+                        let done = done.clone();
+                        handle_clone.spawn(async move {
+                            process(service_clone, message).await;
+                            drop(done);
+                        });
                     },
                     _ = signal_receiver.changed() => break,
                     else => break,
                 }
             }
+            // The requests sent after the stop fail instead of waiting for the response
+            drop(receiver);
+            drop(done);
         });
 
         let mut signal_receiver = self.signal_receiver;
+        let done = self.done.clone();
         self.handle.spawn(async move {
             loop {
                 tokio::select! {
@@ -590,6 +715,7 @@ impl TxPoolServiceBuilder {
                     else => break,
                 }
             }
+            drop(done);
         });
 
         self.started.store(true, Ordering::Relaxed);
@@ -600,6 +726,7 @@ impl TxPoolServiceBuilder {
 pub(crate) struct TxPoolService {
     pub(crate) tx_pool: Arc<RwLock<TxPool>>,
     pub(crate) orphan: Arc<RwLock<OrphanPool>>,
+    pub(crate) staging: Arc<RwLock<StagingArea>>,
     pub(crate) consensus: Arc<Consensus>,
     pub(crate) tx_pool_config: Arc<TxPoolConfig>,
     pub(crate) block_assembler: Option<BlockAssembler>,
@@ -608,8 +735,10 @@ pub(crate) struct TxPoolService {
     pub(crate) callbacks: Arc<Callbacks>,
     pub(crate) snapshot_mgr: Arc<SnapshotMgr>,
     pub(crate) network: NetworkController,
-    pub(crate) tx_relay_sender: ckb_channel::Sender<(Option<PeerIndex>, bool, Byte32)>,
+    pub(crate) tx_relay_sender: ckb_channel::Sender<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+    pub(crate) ask_for_txs_sender: ckb_channel::Sender<(PeerIndex, Vec<Byte32>)>,
     pub(crate) chunk: Arc<RwLock<ChunkQueue>>,
+    pub(crate) inflight: Arc<InflightTxs>,
 }
 
 impl TxPoolService {
@@ -622,7 +751,8 @@ impl TxPoolService {
 async fn process(mut service: TxPoolService, message: Message) {
     match message {
         Message::GetTxPoolInfo(Request { responder, .. }) => {
-            let info = service.tx_pool.read().await.info();
+            let mut info = service.tx_pool.read().await.info();
+            info.orphan_size = service.orphan.read().await.len();
             if let Err(e) = responder.send(info) {
                 error!("responder send get_tx_pool_info failed {:?}", e);
             };
@@ -791,5 +921,40 @@ async fn process(mut service: TxPoolService, message: Message) {
                 error!("responder send get_ids failed {:?}", e)
             };
         }
+        Message::GetPoolConflicts(Request {
+            responder,
+            arguments: tx_hash,
+        }) => {
+            let tx_pool = service.tx_pool.read().await;
+            let conflicts = tx_pool.get_conflicts(&tx_hash);
+            if let Err(e) = responder.send(conflicts) {
+                error!("responder send get_pool_conflicts failed {:?}", e)
+            };
+        }
+        Message::GetEntryDetails(Request {
+            responder,
+            arguments: (order_by, limit, cursor),
+        }) => {
+            let tx_pool = service.tx_pool.read().await;
+            let details = tx_pool.get_entry_details(order_by, limit, cursor.as_ref());
+            if let Err(e) = responder.send(details) {
+                error!("responder send get_entry_details failed {:?}", e)
+            };
+        }
+        Message::StageTx(Request {
+            responder,
+            arguments: (tx, immature_input),
+        }) => {
+            let staged = service.stage_tx(tx, immature_input).await;
+            if let Err(e) = responder.send(staged) {
+                error!("responder send stage_tx failed {:?}", e)
+            };
+        }
+        Message::GetStagedTxs(Request { responder, .. }) => {
+            let staged = service.staging.read().await.infos();
+            if let Err(e) = responder.send(staged) {
+                error!("responder send get_staged_txs failed {:?}", e)
+            };
+        }
     }
 }
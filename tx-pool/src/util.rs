@@ -46,11 +46,11 @@ pub(crate) fn check_tx_fee(
     let fee = DaoCalculator::new(snapshot.consensus(), &snapshot.as_data_provider())
         .transaction_fee(&rtx)
         .map_err(|err| Reject::Malformed(format!("Transcation fee calculate overflow: {}", err)))?;
-    let min_fee = tx_pool.config.min_fee_rate.fee(tx_size);
+    let min_fee_rate = tx_pool.min_fee_rate();
+    let min_fee = min_fee_rate.fee(tx_size);
     // reject txs which fee lower than min fee rate
     if fee < min_fee {
-        let reject =
-            Reject::LowFeeRate(tx_pool.config.min_fee_rate, min_fee.as_u64(), fee.as_u64());
+        let reject = Reject::LowFeeRate(min_fee_rate, min_fee.as_u64(), fee.as_u64());
         ckb_logger::debug!("reject tx {}", reject);
         return Err(reject);
     }
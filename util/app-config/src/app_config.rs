@@ -13,12 +13,12 @@ use serde::{Deserialize, Serialize};
 use ckb_chain_spec::ChainSpec;
 pub use ckb_logger_config::Config as LogConfig;
 pub use ckb_metrics_config::Config as MetricsConfig;
-use ckb_resource::Resource;
+use ckb_resource::{Resource, CKB_LOCAL_CONFIG_FILE_NAME, MINER_LOCAL_CONFIG_FILE_NAME};
 
 use super::configs::*;
 #[cfg(feature = "with_sentry")]
 use super::sentry_config::SentryConfig;
-use super::{cli, legacy, ExitCode};
+use super::{cli, layered, legacy, ExitCode};
 
 /// The parsed config file.
 ///
@@ -82,6 +82,12 @@ pub struct CKBAppConfig {
     /// Notify config options.
     #[serde(default)]
     pub notify: NotifyConfig,
+    /// Health check endpoints config options.
+    #[serde(default)]
+    pub health: HealthConfig,
+    /// Memory budget config options.
+    #[serde(default)]
+    pub memory: MemoryConfig,
 }
 
 /// The miner config file for `ckb miner`. Usually it is the `ckb-miner.toml` in the CKB root
@@ -119,6 +125,23 @@ pub struct MinerAppConfig {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct ChainConfig {
+    /// Pauses the reorgs which roll back more blocks than this depth, until they are approved
+    /// via the RPC `approve_reorg`.
+    ///
+    /// Default is `None`, which does not limit the reorg depth.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_reorg_depth: Option<u64>,
+    /// Mines a block as soon as the tx-pool has transactions, until the pool is drained.
+    ///
+    /// Only available on the chains using the dummy PoW, and requires the block assembler.
+    #[serde(default)]
+    pub instant_seal: bool,
+    /// When instant seal is enabled, also mines a block if no block has been mined for this
+    /// number of seconds.
+    ///
+    /// Default is `None`, which mines only when the tx-pool has transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instant_seal_interval: Option<u64>,
     /// Specifies the chain spec.
     pub spec: Resource,
 }
@@ -128,22 +151,25 @@ impl AppConfig {
     ///
     /// This will reads the `ckb-miner.toml` in the CKB directory for `ckb miner`, and `ckb.toml`
     /// for all other subcommands.
+    ///
+    /// The optional local config file and the environment variables are overlaid on the config
+    /// file, see `CKBAppConfig::load_layered`.
     pub fn load_for_subcommand<P: AsRef<Path>>(
         root_dir: P,
         subcommand_name: &str,
     ) -> Result<AppConfig, ExitCode> {
         match subcommand_name {
             cli::CMD_MINER => {
-                let resource = ensure_ckb_dir(Resource::miner_config(root_dir.as_ref()))?;
-                let config = MinerAppConfig::load_from_slice(&resource.get()?)?;
+                let value = MinerAppConfig::load_layered(root_dir.as_ref())?;
+                let config = MinerAppConfig::load_from_value(value)?;
 
                 Ok(AppConfig::with_miner(
                     config.derive_options(root_dir.as_ref())?,
                 ))
             }
             _ => {
-                let resource = ensure_ckb_dir(Resource::ckb_config(root_dir.as_ref()))?;
-                let config = CKBAppConfig::load_from_slice(&resource.get()?)?;
+                let value = CKBAppConfig::load_layered(root_dir.as_ref())?;
+                let config = CKBAppConfig::load_from_value(value)?;
                 Ok(AppConfig::with_ckb(
                     config.derive_options(root_dir.as_ref(), subcommand_name)?,
                 ))
@@ -235,7 +261,12 @@ impl AppConfig {
 impl CKBAppConfig {
     /// Load a new instance from a file
     pub fn load_from_slice(slice: &[u8]) -> Result<Self, ExitCode> {
-        let legacy_config: legacy::CKBAppConfig = toml::from_slice(&slice)?;
+        Self::load_from_value(toml::from_slice(&slice)?)
+    }
+
+    /// Load a new instance from a parsed config value.
+    pub fn load_from_value(value: toml::Value) -> Result<Self, ExitCode> {
+        let legacy_config: legacy::CKBAppConfig = value.try_into()?;
         for field in legacy_config.deprecated_fields() {
             eprintln!(
                 "WARN: the option \"{}\" in configuration files is deprecated since v{}.",
@@ -245,13 +276,29 @@ impl CKBAppConfig {
         Ok(legacy_config.into())
     }
 
+    /// Reads `ckb.toml` in the CKB directory, and overlays `ckb.local.toml` and the environment
+    /// variables starting with `CKB__` on it.
+    ///
+    /// For example, `CKB__NETWORK__MAX_PEERS=20` sets `max_peers` in the section `network`.
+    pub fn load_layered(root_dir: &Path) -> Result<toml::Value, ExitCode> {
+        let resource = ensure_ckb_dir(Resource::ckb_config(root_dir))?;
+        layered::load(
+            &resource,
+            &root_dir.join(CKB_LOCAL_CONFIG_FILE_NAME),
+            layered::CKB_ENV_PREFIX,
+        )
+    }
+
     fn derive_options(mut self, root_dir: &Path, subcommand_name: &str) -> Result<Self, ExitCode> {
         self.data_dir = canonicalize_data_dir(self.data_dir, root_dir);
 
         self.db.adjust(root_dir, &self.data_dir, "db");
-        self.ancient = mkdir(path_specified_or_else(&self.ancient, || {
-            self.data_dir.join("ancient")
-        }))?;
+        let ancient = canonicalize_data_dir(
+            path_specified_or_else(&self.ancient, || self.data_dir.join("ancient")),
+            root_dir,
+        );
+        check_ancient_path(&ancient, &self.db.path)?;
+        self.ancient = mkdir(ancient)?;
 
         self.network.path = self.data_dir.join("network");
         if self.tmp_dir.is_none() {
@@ -286,7 +333,12 @@ impl CKBAppConfig {
 impl MinerAppConfig {
     /// Load a new instance from a file.
     pub fn load_from_slice(slice: &[u8]) -> Result<Self, ExitCode> {
-        let legacy_config: legacy::MinerAppConfig = toml::from_slice(&slice)?;
+        Self::load_from_value(toml::from_slice(&slice)?)
+    }
+
+    /// Load a new instance from a parsed config value.
+    pub fn load_from_value(value: toml::Value) -> Result<Self, ExitCode> {
+        let legacy_config: legacy::MinerAppConfig = value.try_into()?;
         for field in legacy_config.deprecated_fields() {
             eprintln!(
                 "WARN: the option \"{}\" in configuration files is deprecated since v{}.",
@@ -296,6 +348,17 @@ impl MinerAppConfig {
         Ok(legacy_config.into())
     }
 
+    /// Reads `ckb-miner.toml` in the CKB directory, and overlays `ckb-miner.local.toml` and the
+    /// environment variables starting with `CKB_MINER__` on it.
+    pub fn load_layered(root_dir: &Path) -> Result<toml::Value, ExitCode> {
+        let resource = ensure_ckb_dir(Resource::miner_config(root_dir))?;
+        layered::load(
+            &resource,
+            &root_dir.join(MINER_LOCAL_CONFIG_FILE_NAME),
+            layered::MINER_ENV_PREFIX,
+        )
+    }
+
     fn derive_options(mut self, root_dir: &Path) -> Result<Self, ExitCode> {
         self.data_dir = mkdir(canonicalize_data_dir(self.data_dir, root_dir))?;
         self.logger.log_dir = self.data_dir.join("logs");
@@ -318,6 +381,22 @@ fn canonicalize_data_dir(data_dir: PathBuf, root_dir: &Path) -> PathBuf {
     }
 }
 
+// The freezer and the database must not share the directory, otherwise maintaining one of them,
+// such as `ckb move-ancient`, destroys the other one.
+fn check_ancient_path(ancient: &Path, db: &Path) -> Result<(), ExitCode> {
+    let ancient = ancient.clean();
+    let db = db.clean();
+    if ancient.starts_with(&db) || db.starts_with(&ancient) {
+        eprintln!(
+            "Config Error: ancient path {} overlaps with the db path {}",
+            ancient.display(),
+            db.display()
+        );
+        return Err(ExitCode::Config);
+    }
+    Ok(())
+}
+
 fn mkdir(dir: PathBuf) -> Result<PathBuf, ExitCode> {
     fs::create_dir_all(&dir.clean())?;
     // std::fs::canonicalize will bring windows compatibility problems
@@ -626,4 +705,14 @@ mod tests {
             assert_eq!(miner_config.miner.client.rpc_url, "http://127.0.0.1:7000/");
         }
     }
+
+    #[test]
+    fn test_check_ancient_path() {
+        let data_dir = Path::new("/data");
+        assert!(check_ancient_path(&data_dir.join("ancient"), &data_dir.join("db")).is_ok());
+        assert!(check_ancient_path(Path::new("/mnt/ancient"), &data_dir.join("db")).is_ok());
+        assert!(check_ancient_path(&data_dir.join("db/ancient"), &data_dir.join("db")).is_err());
+        assert!(check_ancient_path(&data_dir.join("db"), &data_dir.join("db/")).is_err());
+        assert!(check_ancient_path(data_dir, &data_dir.join("db")).is_err());
+    }
 }
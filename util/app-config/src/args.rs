@@ -43,6 +43,10 @@ pub struct RunArgs {
     pub overwrite_chain_spec: bool,
     /// Hash of serialized configured chain spec
     pub chain_spec_hash: Byte32,
+    /// Whether only performs the startup validations and prints the report
+    pub check: bool,
+    /// Whether runs in the sandbox mode with the mock clock and without the network
+    pub sandbox: bool,
 }
 
 /// Enable profile on blocks in the range `[from, to]`.
@@ -62,6 +66,27 @@ pub struct ReplayArgs {
     pub sanity_check: bool,
     /// Enable full verification.
     pub full_verification: bool,
+    /// Check the replayed blocks against the stored ones in the range `[from, to]`.
+    pub divergence_check: DivergenceCheckArgs,
+}
+
+/// Checks of the replayed blocks against the stored ones, see `ckb replay --verify-dao`.
+pub struct DivergenceCheckArgs {
+    /// The first block to check, default is 1.
+    pub from: Option<u64>,
+    /// The last block to check, default is the tip.
+    pub to: Option<u64>,
+    /// Recomputes the DAO fields and compares them with the stored headers.
+    pub verify_dao: bool,
+    /// Compares the cell set changes and the block extras with the stored ones.
+    pub verify_cells: bool,
+}
+
+impl DivergenceCheckArgs {
+    /// Whether any check is enabled.
+    pub fn enabled(&self) -> bool {
+        self.verify_dao || self.verify_cells
+    }
 }
 
 /// Parsed command line arguments for `ckb miner`.
@@ -101,6 +126,14 @@ pub struct InitArgs {
     pub rpc_port: String,
     /// P2P port.
     pub p2p_port: String,
+    /// RPC modules, the modules of the chain are used when it is empty.
+    pub rpc_modules: Vec<String>,
+    /// The HTTP listen address of the Prometheus metrics exporter.
+    pub metrics_exporter: Option<String>,
+    /// The directory of the config fragments merged into `ckb.local.toml`.
+    ///
+    /// The fragments are rendered as templates with the same context as `ckb.toml`.
+    pub config_dropin: Option<PathBuf>,
     /// Whether to save the logs into the log file.
     pub log_to_file: bool,
     /// Whether to print the logs on the process stdout.
@@ -172,6 +205,28 @@ pub struct PeerIDArgs {
     pub peer_id: secio::PeerId,
 }
 
+/// Parsed command line arguments for `ckb schema dump`.
+pub struct SchemaDumpArgs {
+    /// The target directory to write the schemas.
+    pub target: PathBuf,
+}
+
+/// Parsed command line arguments for `ckb alert sign`.
+pub struct AlertSignArgs {
+    /// The alert to sign, which may have been signed by the other keys.
+    pub alert: ckb_jsonrpc_types::Alert,
+    /// The path to the secp256k1 private key file.
+    pub privkey_path: PathBuf,
+}
+
+/// Parsed command line arguments for `ckb config dump`.
+pub struct ConfigDumpArgs {
+    /// The root directory of the CKB node.
+    pub root_dir: PathBuf,
+    /// Whether to print the effective config instead of `ckb.toml`.
+    pub effective: bool,
+}
+
 /// Parsed command line arguments for `ckb migrate`.
 pub struct MigrateArgs {
     /// The parsed `ckb.toml.`
@@ -184,12 +239,44 @@ pub struct MigrateArgs {
     pub force: bool,
 }
 
+/// Parsed command line arguments for `ckb move-ancient`.
+pub struct MoveAncientArgs {
+    /// Parsed `ckb.toml`.
+    pub config: Box<CKBAppConfig>,
+    /// The directory to move the freezer data to.
+    pub target: PathBuf,
+}
+
 /// Parsed command line arguments for `ckb db-repair`.
 pub struct RepairArgs {
     /// Parsed `ckb.toml`.
     pub config: Box<CKBAppConfig>,
 }
 
+/// Parsed command line arguments for `ckb recover`.
+pub struct RecoverArgs {
+    /// Parsed `ckb.toml`.
+    pub config: Box<CKBAppConfig>,
+    /// Loaded consensus.
+    pub consensus: Consensus,
+    /// Repairs and truncates without interactive prompt.
+    pub force: bool,
+}
+
+/// Parsed command line arguments for `ckb db compact`.
+pub struct DBCompactArgs {
+    /// Parsed `ckb.toml`.
+    pub config: Box<CKBAppConfig>,
+    /// The column family to compact, compacts all the column families when it is `None`.
+    pub column: Option<String>,
+}
+
+/// Parsed command line arguments for `ckb db stats`.
+pub struct DBStatsArgs {
+    /// Parsed `ckb.toml`.
+    pub config: Box<CKBAppConfig>,
+}
+
 impl CustomizeSpec {
     /// No specified parameters for chain spec.
     pub fn is_unset(&self) -> bool {
@@ -31,6 +31,24 @@ pub const CMD_FROM_SECRET: &str = "from-secret";
 pub const CMD_MIGRATE: &str = "migrate";
 /// Subcommand `db-repair`.
 pub const CMD_DB_REPAIR: &str = "db-repair";
+/// Subcommand `recover`.
+pub const CMD_RECOVER: &str = "recover";
+/// Subcommand `db`.
+pub const CMD_DB: &str = "db";
+/// Subcommand `compact`.
+pub const CMD_COMPACT: &str = "compact";
+/// Subcommand `move-ancient`.
+pub const CMD_MOVE_ANCIENT: &str = "move-ancient";
+/// Subcommand `config`.
+pub const CMD_CONFIG: &str = "config";
+/// Subcommand `dump`.
+pub const CMD_DUMP: &str = "dump";
+/// Subcommand `alert`.
+pub const CMD_ALERT: &str = "alert";
+/// Subcommand `sign`.
+pub const CMD_SIGN: &str = "sign";
+/// Subcommand `schema`.
+pub const CMD_SCHEMA: &str = "schema";
 
 /// Command line argument `--config-dir`.
 pub const ARG_CONFIG_DIR: &str = "config-dir";
@@ -56,6 +74,12 @@ pub const ARG_GENESIS_MESSAGE: &str = "genesis-message";
 pub const ARG_P2P_PORT: &str = "p2p-port";
 /// Command line argument `--rpc-port`.
 pub const ARG_RPC_PORT: &str = "rpc-port";
+/// Command line argument `--rpc-modules`.
+pub const ARG_RPC_MODULES: &str = "rpc-modules";
+/// Command line argument `--metrics-exporter`.
+pub const ARG_METRICS_EXPORTER: &str = "metrics-exporter";
+/// Command line argument `--config-dropin`.
+pub const ARG_CONFIG_DROPIN: &str = "config-dropin";
 /// Command line argument `--force`.
 pub const ARG_FORCE: &str = "force";
 /// Command line argument `--log-to`.
@@ -100,6 +124,10 @@ pub const ARG_PROFILE: &str = "profile";
 pub const ARG_SANITY_CHECK: &str = "sanity-check";
 /// Command line argument `--full-verification`.
 pub const ARG_FULL_VERIFICATION: &str = "full-verification";
+/// Command line argument `--verify-dao`.
+pub const ARG_VERIFY_DAO: &str = "verify-dao";
+/// Command line argument `--verify-cells`.
+pub const ARG_VERIFY_CELLS: &str = "verify-cells";
 /// Command line argument `--skip-spec-check`.
 pub const ARG_SKIP_CHAIN_SPEC_CHECK: &str = "skip-spec-check";
 /// Present `overwrite-spec` arg to force overriding the chain spec in the database with the present configured chain spec
@@ -108,6 +136,32 @@ pub const ARG_OVERWRITE_CHAIN_SPEC: &str = "overwrite-spec";
 pub const ARG_ASSUME_VALID_TARGET: &str = "assume-valid-target";
 /// Command line argument `--check`.
 pub const ARG_MIGRATE_CHECK: &str = "check";
+/// Command line argument `--check` of `ckb run`.
+pub const ARG_RUN_CHECK: &str = "check";
+/// Command line argument `--sandbox`.
+pub const ARG_SANDBOX: &str = "sandbox";
+/// Command line argument `--effective`.
+pub const ARG_EFFECTIVE: &str = "effective";
+/// Command line argument `--cf`.
+pub const ARG_CF: &str = "cf";
+/// Command line argument `--privkey-path`.
+pub const ARG_PRIVKEY_PATH: &str = "privkey-path";
+/// Command line argument `--alert`.
+pub const ARG_ALERT: &str = "alert";
+/// Command line argument `--id`.
+pub const ARG_ID: &str = "id";
+/// Command line argument `--cancel`.
+pub const ARG_CANCEL: &str = "cancel";
+/// Command line argument `--priority`.
+pub const ARG_PRIORITY: &str = "priority";
+/// Command line argument `--notice-until`.
+pub const ARG_NOTICE_UNTIL: &str = "notice-until";
+/// Command line argument `--message`.
+pub const ARG_MESSAGE: &str = "message";
+/// Command line argument `--min-version`.
+pub const ARG_MIN_VERSION: &str = "min-version";
+/// Command line argument `--max-version`.
+pub const ARG_MAX_VERSION: &str = "max-version";
 
 /// Command line arguments group `ba` for block assembler.
 const GROUP_BA: &str = "ba";
@@ -139,6 +193,12 @@ fn basic_app<'b>() -> App<'static, 'b> {
         .subcommand(peer_id())
         .subcommand(migrate())
         .subcommand(db_repair())
+        .subcommand(recover())
+        .subcommand(db())
+        .subcommand(move_ancient())
+        .subcommand(config())
+        .subcommand(alert())
+        .subcommand(schema())
 }
 
 /// Parse the command line arguments by supplying the version information.
@@ -181,6 +241,17 @@ fn run() -> App<'static, 'static> {
             the download of the block starts; If the assume valid target is not found or it's \
             timestamp within 24 hours of the current time, the target will automatically become invalid, \
             and the download of the block will be started with verify")
+    ).arg(
+        Arg::with_name(ARG_RUN_CHECK)
+            .long(ARG_RUN_CHECK)
+            .help("Performs the startup validations, prints the report and exits without \
+            starting the node. Exits with code 0 when no validation fails")
+    ).arg(
+        Arg::with_name(ARG_SANDBOX)
+            .long(ARG_SANDBOX)
+            .help("Runs in the deterministic simulation mode. The node reads the time from a mock \
+            clock which is only advanced via the IntegrationTest RPC `advance_mock_time`, and the \
+            network is disabled")
     )
 }
 
@@ -271,6 +342,7 @@ fn replay() -> App<'static, 'static> {
         .help("
             --tmp-target <tmp> --profile 1 10,\n
             --tmp-target <tmp> --sanity-check,\n
+            --tmp-target <tmp> --verify-dao --verify-cells 1 10,\n
         ")
         .arg(Arg::with_name(ARG_TMP_TARGET).long(ARG_TMP_TARGET).takes_value(true).required(true).help(
             "Specifies a target path, prof command make a temporary directory inside of target and the directory will be automatically deleted when finished",
@@ -280,11 +352,11 @@ fn replay() -> App<'static, 'static> {
         ))
         .arg(
             Arg::with_name(ARG_FROM)
-              .help("Specifies profile from block number."),
+              .help("Specifies profile or verification from block number."),
         )
         .arg(
             Arg::with_name(ARG_TO)
-              .help("Specifies profile to block number."),
+              .help("Specifies profile or verification to block number."),
         )
         .arg(
             Arg::with_name(ARG_SANITY_CHECK).long(ARG_SANITY_CHECK).help("Enable sanity check")
@@ -292,9 +364,22 @@ fn replay() -> App<'static, 'static> {
         .arg(
             Arg::with_name(ARG_FULL_VERIFICATION).long(ARG_FULL_VERIFICATION).help("Enable sanity check")
         )
+        .arg(
+            Arg::with_name(ARG_VERIFY_DAO)
+                .long(ARG_VERIFY_DAO)
+                .conflicts_with_all(&[ARG_PROFILE, ARG_SANITY_CHECK])
+                .help("Recomputes the DAO fields of the replayed blocks and reports the first one differing from the stored block"),
+        )
+        .arg(
+            Arg::with_name(ARG_VERIFY_CELLS)
+                .long(ARG_VERIFY_CELLS)
+                .conflicts_with_all(&[ARG_PROFILE, ARG_SANITY_CHECK])
+                .help("Compares the cell set changes and the block extras of the replayed blocks with the stored ones and reports the first divergence"),
+        )
         .group(
             ArgGroup::with_name("mode")
-                .args(&[ARG_PROFILE, ARG_SANITY_CHECK])
+                .args(&[ARG_PROFILE, ARG_SANITY_CHECK, ARG_VERIFY_DAO, ARG_VERIFY_CELLS])
+                .multiple(true)
                 .required(true)
         )
 }
@@ -351,6 +436,66 @@ fn db_repair() -> App<'static, 'static> {
     SubCommand::with_name(CMD_DB_REPAIR).about("Try repair ckb database")
 }
 
+fn recover() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_RECOVER)
+        .about(
+            "Recovers the database of the stopped ckb node\n\
+             It repairs the database if it fails to open, checks the chain against the freezer, \
+             and truncates the chain to the last consistent block, \
+             then `ckb run` resumes syncing from there.\n\
+             Example:\n\
+             ckb -C <dir> recover",
+        )
+        .arg(
+            Arg::with_name(ARG_FORCE)
+                .short("f")
+                .long(ARG_FORCE)
+                .help("Repairs and truncates without interactive prompt"),
+        )
+}
+
+fn db() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_DB)
+        .about("Maintains the database of the stopped ckb node")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name(CMD_COMPACT)
+                .about("Compacts the database manually")
+                .arg(
+                    Arg::with_name(ARG_CF)
+                        .long(ARG_CF)
+                        .value_name("name")
+                        .takes_value(true)
+                        .help(
+                            "Compacts only the column family, which is the column number or \
+                             the name printed by `ckb db stats`. Compacts all the column \
+                             families by default.",
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(CMD_STATS)
+                .about("Prints the estimated size of each column family"),
+        )
+}
+
+fn move_ancient() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_MOVE_ANCIENT)
+        .about(
+            "Moves the freezer data of the stopped ckb node to another directory\n\
+             Example:\n\
+             ckb -C <dir> move-ancient --to /mnt/hdd/ckb-ancient",
+        )
+        .arg(
+            Arg::with_name(ARG_TO)
+                .long(ARG_TO)
+                .value_name("path")
+                .takes_value(true)
+                .required(true)
+                .help("Specifies the target directory, which must not exist or be empty"),
+        )
+}
+
 fn list_hashes() -> App<'static, 'static> {
     SubCommand::with_name(CMD_LIST_HASHES)
         .about("Lists well known hashes")
@@ -420,6 +565,50 @@ fn init() -> App<'static, 'static> {
                 .default_value(DEFAULT_P2P_PORT)
                 .help("Replaces CKB P2P port in the created config file"),
         )
+        .arg(
+            Arg::with_name(ARG_RPC_MODULES)
+                .long(ARG_RPC_MODULES)
+                .value_name("modules")
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&[
+                    "Net",
+                    "Chain",
+                    "Miner",
+                    "Pool",
+                    "Experiment",
+                    "Stats",
+                    "IntegrationTest",
+                    "Alert",
+                    "Subscription",
+                    "Debug",
+                ])
+                .help(
+                    "Replaces the comma separated RPC modules in the created config file \
+                     [default: the modules of the chain]",
+                ),
+        )
+        .arg(
+            Arg::with_name(ARG_METRICS_EXPORTER)
+                .long(ARG_METRICS_EXPORTER)
+                .value_name("listen_address")
+                .takes_value(true)
+                .help(
+                    "Enables the Prometheus metrics exporter listening on the HTTP address \
+                     in the created config file",
+                ),
+        )
+        .arg(
+            Arg::with_name(ARG_CONFIG_DROPIN)
+                .long(ARG_CONFIG_DROPIN)
+                .value_name("dir")
+                .takes_value(true)
+                .help(
+                    "Renders the *.toml fragments in the directory with the same template \
+                     variables and merges them in the file name order into ckb.local.toml, \
+                     which overlays ckb.toml",
+                ),
+        )
         .arg(
             Arg::with_name(ARG_BA_CODE_HASH)
                 .long(ARG_BA_CODE_HASH)
@@ -515,6 +704,134 @@ fn peer_id() -> App<'static, 'static> {
         )
 }
 
+fn config() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_CONFIG)
+        .about("About the config files")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name(CMD_DUMP)
+                .about(
+                    "Print ckb.toml\n\
+                     Example:\n\
+                     ckb -C <dir> config dump --effective",
+                )
+                .arg(Arg::with_name(ARG_EFFECTIVE).long(ARG_EFFECTIVE).help(
+                    "Print the effective config, which overlays ckb.local.toml and \
+                     the CKB__ environment variables on ckb.toml",
+                )),
+        )
+}
+
+fn alert() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_ALERT)
+        .about("About the network alert")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name(CMD_SIGN)
+                .about(
+                    "Sign a network alert offline and print the alert in JSON, \
+                     which is the parameter of the RPC send_alert\n\
+                     Example:\n\
+                     ckb alert sign --privkey-path key1 --id 1 --notice-until 1600000000000 \
+                     --message 'Upgrade now' > alert.json\n\
+                     ckb alert sign --privkey-path key2 --alert alert.json",
+                )
+                .arg(
+                    Arg::with_name(ARG_PRIVKEY_PATH)
+                        .long(ARG_PRIVKEY_PATH)
+                        .required(true)
+                        .takes_value(true)
+                        .help("The file containing the hex encoded secp256k1 private key"),
+                )
+                .arg(
+                    Arg::with_name(ARG_ALERT)
+                        .long(ARG_ALERT)
+                        .takes_value(true)
+                        .conflicts_with_all(&[
+                            ARG_ID,
+                            ARG_CANCEL,
+                            ARG_PRIORITY,
+                            ARG_NOTICE_UNTIL,
+                            ARG_MESSAGE,
+                            ARG_MIN_VERSION,
+                            ARG_MAX_VERSION,
+                        ])
+                        .help(
+                            "Adds the signature to the alert in the JSON file, \
+                             which is printed by another signer",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(ARG_ID)
+                        .long(ARG_ID)
+                        .takes_value(true)
+                        .required_unless(ARG_ALERT)
+                        .help("The alert id"),
+                )
+                .arg(
+                    Arg::with_name(ARG_CANCEL)
+                        .long(ARG_CANCEL)
+                        .takes_value(true)
+                        .help("The id of the alert to cancel, default is 0 which cancels nothing"),
+                )
+                .arg(
+                    Arg::with_name(ARG_PRIORITY)
+                        .long(ARG_PRIORITY)
+                        .takes_value(true)
+                        .help("The alert priority, higher is more important, default is 0"),
+                )
+                .arg(
+                    Arg::with_name(ARG_NOTICE_UNTIL)
+                        .long(ARG_NOTICE_UNTIL)
+                        .takes_value(true)
+                        .required_unless(ARG_ALERT)
+                        .help("The timestamp in milliseconds when the alert expires"),
+                )
+                .arg(
+                    Arg::with_name(ARG_MESSAGE)
+                        .long(ARG_MESSAGE)
+                        .takes_value(true)
+                        .required_unless(ARG_ALERT)
+                        .help("The alert message"),
+                )
+                .arg(
+                    Arg::with_name(ARG_MIN_VERSION)
+                        .long(ARG_MIN_VERSION)
+                        .takes_value(true)
+                        .help("The minimal version of the nodes to notice the alert"),
+                )
+                .arg(
+                    Arg::with_name(ARG_MAX_VERSION)
+                        .long(ARG_MAX_VERSION)
+                        .takes_value(true)
+                        .help("The maximal version of the nodes to notice the alert"),
+                ),
+        )
+}
+
+fn schema() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_SCHEMA)
+        .about("About the molecule schemas and the JSON mappings")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name(CMD_DUMP)
+                .about(
+                    "Writes the molecule schemas and the JSON mappings of the entities \
+                     with examples to the target directory\n\
+                     Example:\n\
+                     ckb schema dump --target schemas",
+                )
+                .arg(
+                    Arg::with_name(ARG_TARGET)
+                        .short("t")
+                        .long(ARG_TARGET)
+                        .value_name("path")
+                        .required(true)
+                        .help("Specifies the target directory."),
+                ),
+        )
+}
+
 fn is_hex(hex: String) -> Result<(), String> {
     let tmp = hex.as_bytes();
     if tmp.len() < 2 {
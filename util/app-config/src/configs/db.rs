@@ -23,6 +23,11 @@ pub struct Config {
     ///
     /// More details can be found in [the official tuning guide](https://github.com/facebook/rocksdb/wiki/RocksDB-Tuning-Guide).
     pub options_file: Option<PathBuf>,
+    /// Enables the RocksDB statistics, such as the block cache hits and misses, default is false.
+    ///
+    /// The memory tracker reports them to the metrics. The statistics slow down each read a little.
+    #[serde(default)]
+    pub statistics: bool,
 }
 
 impl Config {
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Health check endpoints config options.
+///
+/// The endpoints are served on a separate port, independent of the JSON-RPC server:
+///
+/// * `GET /live` returns 200 as long as the process is serving requests.
+/// * `GET /ready` returns 200 when the node is ready to serve the clients, otherwise 503.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The address to serve the health check endpoints, such as `127.0.0.1:8116`.
+    ///
+    /// The endpoints are disabled when it is not set.
+    pub listen_address: Option<String>,
+    /// The minimum number of connected peers for readiness.
+    #[serde(default = "default_min_peers")]
+    pub min_peers: usize,
+    /// The node is ready only when the tip is at most this number of blocks behind the best
+    /// known header.
+    #[serde(default = "default_max_tip_lag")]
+    pub max_tip_lag: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            listen_address: None,
+            min_peers: default_min_peers(),
+            max_tip_lag: default_max_tip_lag(),
+        }
+    }
+}
+
+const fn default_min_peers() -> usize {
+    1
+}
+
+const fn default_max_tip_lag() -> u64 {
+    10
+}
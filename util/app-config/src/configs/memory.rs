@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// Memory budget config options.
+///
+/// The budget is shared by the header map, the transaction verification cache, the store caches
+/// and the transaction pool, which override their own size options.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The budget of the memory allocated by the process in MB, 0 disables it.
+    ///
+    /// 20% of it is left as the headroom for the memory which is not tracked, such as RocksDB.
+    #[serde(default)]
+    pub budget_mb: u64,
+}
@@ -6,10 +6,17 @@ use serde::{Deserialize, Serialize};
 pub struct Config {
     /// Tracking interval in seconds.
     pub interval: u64,
+    /// Whether to gather the estimates of each RocksDB column family, such as the live data size,
+    /// the number of keys, the pending compaction bytes and the read amplification.
+    #[serde(default)]
+    pub rocksdb_cf_stats: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Self { interval: 0 }
+        Self {
+            interval: 0,
+            rocksdb_cf_stats: false,
+        }
     }
 }
@@ -34,6 +34,34 @@ pub enum WorkerConfig {
     Dummy(DummyConfig),
     /// Eaglesong worker which solves Eaglesong PoW.
     EaglesongSimple(EaglesongSimpleConfig),
+    /// Worker running in an external process, such as a GPU miner.
+    External(ExternalWorkerConfig),
+}
+
+/// External worker config options.
+///
+/// The miner starts the command and talks with it in JSON lines via the stdin and the stdout.
+///
+/// The miner writes the requests to the stdin:
+///
+/// * `{"method": "new_work", "pow_hash": "0x...", "target": "0x...", "nonce_start": "0x...",
+///   "nonce_end": "0x..."}` - Solves the new work with the nonces in `[nonce_start, nonce_end)`.
+/// * `{"method": "stop"}` and `{"method": "start"}` - Pauses and resumes the work.
+///
+/// The process writes the responses to the stdout:
+///
+/// * `{"method": "nonce", "pow_hash": "0x...", "nonce": "0x..."}` - A nonce is found.
+/// * `{"method": "hashrate", "value": 1000.0}` - The current hashes per second.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExternalWorkerConfig {
+    /// The worker name shown in the progress bar and the metrics.
+    pub name: String,
+    /// The command to start the worker process.
+    pub command: String,
+    /// The arguments of the command.
+    #[serde(default)]
+    pub args: Vec<String>,
 }
 
 /// Dummy worker config options.
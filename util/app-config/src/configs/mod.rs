@@ -1,4 +1,6 @@
 mod db;
+mod health;
+mod memory;
 mod memory_tracker;
 mod miner;
 mod network;
@@ -9,16 +11,20 @@ mod store;
 mod tx_pool;
 
 pub use db::Config as DBConfig;
+pub use health::Config as HealthConfig;
+pub use memory::Config as MemoryConfig;
 pub use memory_tracker::Config as MemoryTrackerConfig;
 pub use miner::{
     ClientConfig as MinerClientConfig, Config as MinerConfig, DummyConfig, EaglesongSimpleConfig,
-    ExtraHashFunction, WorkerConfig as MinerWorkerConfig,
+    ExternalWorkerConfig, ExtraHashFunction, WorkerConfig as MinerWorkerConfig,
 };
-pub use network::{Config as NetworkConfig, HeaderMapConfig, SyncConfig};
+pub use network::{Config as NetworkConfig, HeaderMapConfig, MisbehaviorConfig, SyncConfig};
 pub use network_alert::Config as NetworkAlertConfig;
-pub use notify::Config as NotifyConfig;
-pub use rpc::{Config as RpcConfig, Module as RpcModule};
+pub use notify::{Config as NotifyConfig, WebhookEvent, WebhooksConfig};
+pub use rpc::{Config as RpcConfig, ListenerConfig as RpcListenerConfig, Module as RpcModule};
 pub use store::Config as StoreConfig;
-pub use tx_pool::{BlockAssemblerConfig, TxPoolConfig};
+pub use tx_pool::{
+    BlockAssemblerConfig, DynamicMinFeeRateConfig, TimeLockStagingConfig, TxPoolConfig,
+};
 
 pub(crate) use network::{generate_random_key, read_secret_key, write_secret_to_file};
@@ -1,7 +1,9 @@
+use ckb_jsonrpc_types::JsonBytes;
 use ckb_types::{H256, U256};
-use multiaddr::Multiaddr;
+use multiaddr::{Multiaddr, Protocol};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
 use std::io::{Error, ErrorKind, Read, Write};
 use std::path::PathBuf;
@@ -28,8 +30,16 @@ pub struct Config {
     #[serde(default)]
     pub path: PathBuf,
     /// A list of DNS servers to discover peers.
+    ///
+    /// A seed in the form `host` is queried for TXT records signed by `dns_seed_pubkey`, and a
+    /// seed in the form `host:port` is resolved via A/AAAA records using the given port. The IPv6
+    /// addresses must be in the form `[ip]:port`. The resolved addresses have no peer ids, so they
+    /// are dialed directly instead of being saved in the peer store.
     #[serde(default)]
     pub dns_seeds: Vec<String>,
+    /// The public key which signs the TXT records of DNS seeds, in hex without the `0x04` prefix.
+    #[serde(default)]
+    pub dns_seed_pubkey: Option<String>,
     /// Whether to probe and store local addresses.
     #[serde(default)]
     pub discovery_local_address: bool,
@@ -62,6 +72,12 @@ pub struct Config {
     /// When `whitelist_only` is enabled, the node will only connect to peers in this list.
     #[serde(default)]
     pub whitelist_peers: Vec<Multiaddr>,
+    /// A list of peers protected from the eviction, the misbehavior banning and the connection
+    /// limits, such as the other nodes of the same operator.
+    ///
+    /// Each item is either a multiaddr ending with `/p2p/<peer id>` or a base58 peer id.
+    #[serde(default)]
+    pub protected_peers: Vec<String>,
     /// Enable UPNP when the router supports it.
     #[serde(default)]
     pub upnp: bool,
@@ -79,15 +95,40 @@ pub struct Config {
     /// Chain synchronization config options.
     #[serde(default)]
     pub sync: SyncConfig,
+    /// Peer misbehavior scoring config options.
+    #[serde(default)]
+    pub misbehavior: MisbehaviorConfig,
+    /// Peer identity allowlist config options.
+    #[serde(default)]
+    pub allowlist: AllowlistConfig,
 }
 
 /// Chain synchronization config options.
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct SyncConfig {
     /// Header map config options.
     #[serde(default)]
     pub header_map: HeaderMapConfig,
+    /// Raises a local alert when the local clock offsets from the network peers more than this
+    /// value, unit ms.
+    ///
+    /// Default is 15 seconds, which is the allowed future block time of the header verification,
+    /// a larger skew makes the node reject valid headers or produce blocks rejected by the peers.
+    #[serde(default = "default_clock_skew_threshold")]
+    pub clock_skew_threshold: u64,
+    /// Rotates the outbound peers when the tip of the local chain has not advanced for this
+    /// duration, unit ms.
+    ///
+    /// Default is 30 minutes, 0 disables the rotation.
+    #[serde(default = "default_stale_tip_timeout")]
+    pub stale_tip_timeout: u64,
+    /// The max bytes per second of the transaction hashes announced to each peer.
+    ///
+    /// Default is 64 KiB, about 2000 transactions per second, 0 disables the limit. The hashes
+    /// beyond the budget are queued and announced in larger batches later.
+    #[serde(default = "default_tx_announce_bytes_per_sec")]
+    pub tx_announce_bytes_per_sec: u64,
     /// Block hash of assume valid target
     #[serde(skip, default)]
     pub assume_valid_target: Option<H256>,
@@ -96,6 +137,31 @@ pub struct SyncConfig {
     pub min_chain_work: U256,
 }
 
+impl Default for SyncConfig {
+    fn default() -> Self {
+        SyncConfig {
+            header_map: HeaderMapConfig::default(),
+            clock_skew_threshold: default_clock_skew_threshold(),
+            stale_tip_timeout: default_stale_tip_timeout(),
+            tx_announce_bytes_per_sec: default_tx_announce_bytes_per_sec(),
+            assume_valid_target: None,
+            min_chain_work: U256::default(),
+        }
+    }
+}
+
+const fn default_clock_skew_threshold() -> u64 {
+    15_000
+}
+
+const fn default_stale_tip_timeout() -> u64 {
+    30 * 60 * 1000
+}
+
+const fn default_tx_announce_bytes_per_sec() -> u64 {
+    64 * 1024
+}
+
 /// Header map config options.
 ///
 /// Header map stores the block headers before fully verifying the block.
@@ -117,6 +183,98 @@ impl Default for HeaderMapConfig {
     }
 }
 
+/// Peer identity allowlist config options.
+///
+/// It is designed for the private consortium chains and the closed devnets. When it is enabled,
+/// the node only accepts and dials the allowlisted peers and the `whitelist_peers`, and it does
+/// not discover the peers from the DNS seeds or store the addresses of the other peers.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AllowlistConfig {
+    /// Enables the allowlist mode.
+    #[serde(default)]
+    pub enable: bool,
+    /// The allowed peer ids, in base58.
+    #[serde(default)]
+    pub peer_ids: Vec<String>,
+    /// The allowed secp256k1 public keys in the compressed format, such as "0x02...".
+    ///
+    /// The peer id is derived from the public key, which is the same as the peer id of the node
+    /// using the corresponding network secret key.
+    #[serde(default)]
+    pub public_keys: Vec<JsonBytes>,
+}
+
+impl AllowlistConfig {
+    /// Gets the allowed peer ids, including the ones derived from the public keys.
+    pub fn allowed_peer_ids(&self) -> Result<HashSet<secio::PeerId>, Error> {
+        let mut peer_ids = HashSet::with_capacity(self.peer_ids.len() + self.public_keys.len());
+        for peer_id in &self.peer_ids {
+            let peer_id = peer_id.parse::<secio::PeerId>().map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid peer id in network.allowlist: {}", peer_id),
+                )
+            })?;
+            peer_ids.insert(peer_id);
+        }
+        for public_key in &self.public_keys {
+            let bytes = public_key.as_bytes();
+            if bytes.len() != 33 || (bytes[0] != 0x02 && bytes[0] != 0x03) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "invalid compressed secp256k1 public key in network.allowlist: {}",
+                        serde_json::to_string(public_key).expect("serialize json bytes")
+                    ),
+                ));
+            }
+            peer_ids.insert(secio::PublicKey::Secp256k1(bytes.to_vec()).peer_id());
+        }
+        Ok(peer_ids)
+    }
+}
+
+/// Peer misbehavior scoring config options.
+///
+/// Every remote network starts with the score 100, the misbehaviors reported by the protocols
+/// decrease the score and the score recovers over time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MisbehaviorConfig {
+    /// The remote network is banned when its score drops below this threshold.
+    #[serde(default = "default_ban_score")]
+    pub ban_score: i32,
+    /// How long the remote network is banned for, in seconds.
+    #[serde(default = "default_ban_timeout_secs")]
+    pub ban_timeout_secs: u64,
+    /// How many points the score recovers per hour.
+    #[serde(default = "default_score_recovery_per_hour")]
+    pub score_recovery_per_hour: i32,
+}
+
+impl Default for MisbehaviorConfig {
+    fn default() -> Self {
+        Self {
+            ban_score: default_ban_score(),
+            ban_timeout_secs: default_ban_timeout_secs(),
+            score_recovery_per_hour: default_score_recovery_per_hour(),
+        }
+    }
+}
+
+const fn default_ban_score() -> i32 {
+    40
+}
+
+const fn default_ban_timeout_secs() -> u64 {
+    24 * 60 * 60
+}
+
+const fn default_score_recovery_per_hour() -> i32 {
+    10
+}
+
 pub(crate) fn generate_random_key() -> [u8; 32] {
     loop {
         let mut key: [u8; 32] = [0; 32];
@@ -253,6 +411,35 @@ impl Config {
         self.whitelist_peers.clone()
     }
 
+    /// Gets the peer ids of the protected peers.
+    pub fn protected_peer_ids(&self) -> Result<HashSet<secio::PeerId>, Error> {
+        self.protected_peers
+            .iter()
+            .map(|peer| {
+                let peer_id = if peer.starts_with('/') {
+                    peer.parse::<Multiaddr>().ok().and_then(|addr| {
+                        addr.iter().find_map(|proto| match proto {
+                            Protocol::P2P(bytes) => secio::PeerId::from_bytes(bytes.to_vec()).ok(),
+                            _ => None,
+                        })
+                    })
+                } else {
+                    peer.parse::<secio::PeerId>().ok()
+                };
+                peer_id.ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "invalid network.protected_peers item {}, \
+                             expect a multiaddr with the peer id or a peer id",
+                            peer
+                        ),
+                    )
+                })
+            })
+            .collect()
+    }
+
     /// Gets a list of bootnodes.
     pub fn bootnodes(&self) -> Vec<Multiaddr> {
         self.bootnodes.clone()
@@ -264,8 +451,10 @@ impl Config {
     }
 
     /// Checks whether the DNS seeding service should be enabled.
+    ///
+    /// It is disabled in the allowlist mode.
     pub fn dns_seeding_service_enabled(&self) -> bool {
-        !self.dns_seeds.is_empty()
+        !self.dns_seeds.is_empty() && !self.allowlist.enable
     }
 }
 
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 /// Notify config options.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// An executable script to be called whenever there's a new block in the canonical chain.
@@ -9,6 +9,133 @@ pub struct Config {
     pub new_block_notify_script: Option<String>,
     /// An executable script to be called whenever there's a new network alert received.
     ///
-    /// The script is called with the alert message as the argument.
+    /// The script is called with the alert message as the argument. It is also called with the
+    /// message of the alerts raised by the node itself, such as the local clock skew.
     pub network_alert_notify_script: Option<String>,
+    /// An executable script to be called whenever a noticed network alert is cancelled or
+    /// expired.
+    ///
+    /// The script is called with the alert id and the reason, `cancelled` or `expired`, as the
+    /// arguments.
+    pub network_alert_removed_notify_script: Option<String>,
+    /// The maximum number of notify scripts running at the same time.
+    #[serde(default = "default_script_concurrency")]
+    pub script_concurrency: usize,
+    /// The maximum number of notify scripts waiting to run.
+    ///
+    /// New notifications are dropped when the queue is full.
+    #[serde(default = "default_script_queue_size")]
+    pub script_queue_size: usize,
+    /// The names of the environment variables passed to the notify scripts.
+    ///
+    /// The scripts are executed with a cleared environment except `PATH` and the variables listed
+    /// here.
+    #[serde(default)]
+    pub script_keep_env: Vec<String>,
+    /// A command used to wrap the notify scripts, such as `["prlimit", "--nproc=32", "--"]` or
+    /// `["systemd-run", "--user", "--scope", "-p", "MemoryMax=64M"]`.
+    ///
+    /// The script and its argument are appended to this command.
+    #[serde(default)]
+    pub script_wrapper: Vec<String>,
+    /// The webhooks receiving the chain events.
+    #[serde(default)]
+    pub webhooks: WebhooksConfig,
+}
+
+/// The events posted to the webhooks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A block becomes the new tip.
+    BlockAccepted,
+    /// The main chain switches to another fork.
+    Reorg,
+    /// A network alert is received.
+    Alert,
+    /// A noticed network alert is cancelled or expired.
+    AlertRemoved,
+}
+
+/// Webhooks config options.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhooksConfig {
+    /// The URLs which the events are posted to as JSON, the webhooks are disabled when it is empty.
+    #[serde(default)]
+    pub urls: Vec<String>,
+    /// The events to post, all the events are posted when it is empty.
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    /// The key to sign the payloads.
+    ///
+    /// The HMAC-SHA256 of the payload is sent in the header `X-CKB-Signature` as
+    /// `sha256=<hex>`.
+    pub secret: Option<String>,
+    /// The maximum number of retries of a failed post, the delay doubles after every retry.
+    #[serde(default = "default_webhook_max_retries")]
+    pub max_retries: u32,
+    /// The timeout of a post in milliseconds.
+    #[serde(default = "default_webhook_timeout")]
+    pub timeout: u64,
+    /// The maximum number of events waiting to be posted to each webhook.
+    ///
+    /// New events for a webhook are dropped when its queue is full.
+    #[serde(default = "default_webhook_queue_size")]
+    pub queue_size: usize,
+}
+
+impl WebhooksConfig {
+    /// Returns whether the event is posted to the webhooks.
+    pub fn is_enabled(&self, event: WebhookEvent) -> bool {
+        !self.urls.is_empty() && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        WebhooksConfig {
+            urls: Vec::new(),
+            events: Vec::new(),
+            secret: None,
+            max_retries: default_webhook_max_retries(),
+            timeout: default_webhook_timeout(),
+            queue_size: default_webhook_queue_size(),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            new_block_notify_script: None,
+            network_alert_notify_script: None,
+            network_alert_removed_notify_script: None,
+            script_concurrency: default_script_concurrency(),
+            script_queue_size: default_script_queue_size(),
+            script_keep_env: Vec::new(),
+            script_wrapper: Vec::new(),
+            webhooks: WebhooksConfig::default(),
+        }
+    }
+}
+
+const fn default_script_concurrency() -> usize {
+    4
+}
+
+const fn default_script_queue_size() -> usize {
+    64
+}
+
+const fn default_webhook_max_retries() -> u32 {
+    5
+}
+
+const fn default_webhook_timeout() -> u64 {
+    5_000
+}
+
+const fn default_webhook_queue_size() -> usize {
+    256
 }
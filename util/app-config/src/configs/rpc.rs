@@ -1,5 +1,6 @@
 use ckb_jsonrpc_types::Script;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// RPC modules.
 #[derive(Clone, Debug, Copy, Eq, PartialEq, Serialize, Deserialize)]
@@ -37,14 +38,24 @@ pub struct Config {
     pub max_request_body_size: usize,
     /// Number of RPC worker threads.
     pub threads: Option<usize>,
+    /// Max number of requests handled at the same time, the extra requests are rejected.
+    ///
+    /// Default is `None`, which does not limit the concurrent requests.
+    #[serde(default)]
+    pub max_concurrent_requests: Option<usize>,
+    /// Max response size in bytes, the larger responses are replaced with an error.
+    ///
+    /// Default is `None`, which does not limit the response size.
+    #[serde(default)]
+    pub max_response_size: Option<usize>,
     /// Enabled RPC modules.
     pub modules: Vec<Module>,
     /// Rejects txs with scripts that might trigger known bugs
     #[serde(default)]
     pub reject_ill_transactions: bool,
-    /// Whether enable deprecated RPC methods.
+    /// Whether enable deprecated RPC methods, and the fields removed from the RPC results.
     ///
-    /// Deprecated RPC methods are disabled by default.
+    /// Deprecated RPC methods and removed fields are disabled by default.
     #[serde(default)]
     pub enable_deprecated_rpc: bool,
     /// Customized extra well known lock scripts.
@@ -53,6 +64,43 @@ pub struct Config {
     /// Customized extra well known type scripts.
     #[serde(default)]
     pub extra_well_known_type_scripts: Vec<Script>,
+    /// Max number of requests per second for each method, such as `{ send_transaction = 10 }`.
+    ///
+    /// The methods not listed are not limited.
+    #[serde(default)]
+    pub rate_limits: HashMap<String, u64>,
+    /// Extra HTTP listeners, each exposes a subset of the enabled modules and may require the
+    /// authentication.
+    #[serde(default)]
+    pub listeners: Vec<ListenerConfig>,
+}
+
+/// An extra RPC HTTP listener.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListenerConfig {
+    /// The listen address.
+    pub listen_address: String,
+    /// The modules exposed on this listener.
+    ///
+    /// A module must be enabled in `rpc.modules` as well, the methods of the other modules return
+    /// the error `RPCModuleIsDisabled`.
+    pub modules: Vec<Module>,
+    /// Requires the header `Authorization: Bearer <bearer_token>`.
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    /// Requires the HTTP basic authentication, in the format `user:password`.
+    ///
+    /// When both `bearer_token` and `basic_auth` are set, either of them is accepted.
+    #[serde(default)]
+    pub basic_auth: Option<String>,
+}
+
+impl ListenerConfig {
+    /// Checks whether the module is exposed on this listener.
+    pub fn module_enable(&self, module: Module) -> bool {
+        self.modules.contains(&module)
+    }
 }
 
 impl Config {
@@ -18,4 +18,14 @@ pub struct Config {
     pub block_extensions_cache_size: usize,
     /// whether enable freezer
     pub freezer_enable: bool,
+    /// The maximum number of threads used to load and compress blocks while freezing.
+    pub freezer_threads: usize,
+    /// Whether to store a checksum for every frozen block and verify it on read.
+    pub freezer_checksum: bool,
+    /// Logs the writes of each column when committing a transaction takes longer than this
+    /// number of milliseconds, 0 disables it.
+    pub slow_commit_threshold_ms: u64,
+    /// Whether to report the cache hits and misses and the reads of each column via snapshots to
+    /// the metrics.
+    pub read_stats: bool,
 }
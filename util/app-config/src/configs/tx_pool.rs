@@ -18,6 +18,99 @@ pub struct TxPoolConfig {
     pub max_tx_verify_cycles: Cycle,
     /// max ancestors size limit for a single tx
     pub max_ancestors_count: usize,
+    /// Raises the min fee rate when the pool is under pressure, disabled when it is `None`
+    pub dynamic_min_fee_rate: Option<DynamicMinFeeRateConfig>,
+    /// Stages the local transactions whose `since` is not satisfied yet, disabled when it is `None`
+    pub time_lock_staging: Option<TimeLockStagingConfig>,
+}
+
+/// Dynamic min fee rate config options.
+///
+/// When the total size of the transactions in the pool reaches `start_percent` of
+/// `max_mem_size`, the pool rejects the transactions which fee rate is below
+///
+/// ```text
+/// min(min_fee_rate * multiplier ^ n, max_fee_rate)
+/// n = (usage_percent - start_percent) / step_percent + 1
+/// ```
+///
+/// With the default options, the floor doubles at 50%, 60%, 70%, 80% and 90% pool usage.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DynamicMinFeeRateConfig {
+    /// The pool usage in percent at which the floor starts to rise.
+    #[serde(default = "default_start_percent")]
+    pub start_percent: u64,
+    /// The floor is multiplied by `multiplier` every time the pool usage grows by this percent.
+    #[serde(default = "default_step_percent")]
+    pub step_percent: u64,
+    /// The factor to multiply the floor at every step.
+    #[serde(default = "default_multiplier")]
+    pub multiplier: u64,
+    /// The floor never exceeds this fee rate.
+    #[serde(default = "default_max_dynamic_fee_rate", with = "FeeRateDef")]
+    pub max_fee_rate: FeeRate,
+}
+
+impl Default for DynamicMinFeeRateConfig {
+    fn default() -> Self {
+        DynamicMinFeeRateConfig {
+            start_percent: default_start_percent(),
+            step_percent: default_step_percent(),
+            multiplier: default_multiplier(),
+            max_fee_rate: default_max_dynamic_fee_rate(),
+        }
+    }
+}
+
+const fn default_start_percent() -> u64 {
+    50
+}
+
+const fn default_step_percent() -> u64 {
+    10
+}
+
+const fn default_multiplier() -> u64 {
+    2
+}
+
+const fn default_max_dynamic_fee_rate() -> FeeRate {
+    FeeRate::from_u64(100_000)
+}
+
+/// Time lock staging config options.
+///
+/// The transactions submitted through the RPC `send_transaction` are rejected when their inputs
+/// `since` are not satisfied yet. With this section configured, such transactions are staged
+/// instead, and they are submitted to the pool again on every new tip until the `since` are
+/// satisfied, the transactions become invalid, or they expire.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeLockStagingConfig {
+    /// The max count of the staged transactions, the new ones are not staged when it is full.
+    #[serde(default = "default_max_staged_transactions")]
+    pub max_transactions: usize,
+    /// The staged transactions are dropped after this many seconds.
+    #[serde(default = "default_staged_expiry_secs")]
+    pub expiry_secs: u64,
+}
+
+impl Default for TimeLockStagingConfig {
+    fn default() -> Self {
+        TimeLockStagingConfig {
+            max_transactions: default_max_staged_transactions(),
+            expiry_secs: default_staged_expiry_secs(),
+        }
+    }
+}
+
+const fn default_max_staged_transactions() -> usize {
+    1_000
+}
+
+const fn default_staged_expiry_secs() -> u64 {
+    7 * 24 * 60 * 60
 }
 
 /// Block assembler config options.
@@ -0,0 +1,251 @@
+//! Layered config files.
+//!
+//! The config file is loaded in the following layers, the latter overrides the former:
+//!
+//! 1. The config file, such as `ckb.toml`.
+//! 2. The optional local config file in the same directory, such as `ckb.local.toml`.
+//! 3. The environment variables starting with the prefix, such as `CKB__`.
+//!
+//! The environment variable name is split by `__` into the path of the option. For example,
+//! `CKB__NETWORK__MAX_PEERS=20` sets the option `max_peers` in the section `network`.
+use ckb_resource::Resource;
+use std::{env, fs, path::Path};
+use toml::Value;
+
+use super::ExitCode;
+
+/// The prefix of the environment variables overriding `ckb.toml`.
+pub const CKB_ENV_PREFIX: &str = "CKB__";
+/// The prefix of the environment variables overriding `ckb-miner.toml`.
+pub const MINER_ENV_PREFIX: &str = "CKB_MINER__";
+
+const ENV_PATH_SEPARATOR: &str = "__";
+
+/// Loads the config file and overlays the local config file and the environment variables.
+pub(crate) fn load(
+    resource: &Resource,
+    local_config_path: &Path,
+    env_prefix: &str,
+) -> Result<Value, ExitCode> {
+    let mut value: Value = toml::from_slice(&resource.get()?)?;
+    if local_config_path.exists() {
+        let local_value: Value = toml::from_slice(&fs::read(local_config_path)?)?;
+        merge(&mut value, local_value);
+    }
+    apply_env_vars(&mut value, env_prefix, env::vars())?;
+    Ok(value)
+}
+
+/// Merges the config fragments in order into one table, the latter overrides the former.
+///
+/// `ckb init --config-dropin` uses it to merge the drop-in fragments into `ckb.local.toml`.
+pub fn merge_fragments<I, S>(fragments: I) -> Result<Value, ExitCode>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut value = Value::Table(Default::default());
+    for fragment in fragments {
+        let fragment_value: Value = toml::from_str(fragment.as_ref())?;
+        merge(&mut value, fragment_value);
+    }
+    Ok(value)
+}
+
+/// Merges the tables recursively, other values in `overlay` replace the ones in `base`.
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Table(base_table), Value::Table(overlay_table)) => {
+            for (key, overlay_value) in overlay_table {
+                match base_table.get_mut(&key) {
+                    Some(base_value) => merge(base_value, overlay_value),
+                    None => {
+                        base_table.insert(key, overlay_value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn apply_env_vars<I>(value: &mut Value, prefix: &str, vars: I) -> Result<(), ExitCode>
+where
+    I: Iterator<Item = (String, String)>,
+{
+    let mut vars: Vec<_> = vars
+        .filter_map(|(name, raw)| {
+            name.strip_prefix(prefix)
+                .map(|path| (name.clone(), path.to_lowercase(), raw))
+        })
+        .collect();
+    // Applies in a deterministic order.
+    vars.sort();
+
+    for (name, path, raw) in vars {
+        let keys: Vec<&str> = path.split(ENV_PATH_SEPARATOR).collect();
+        if keys.iter().any(|key| key.is_empty()) {
+            eprintln!("Config Error: invalid environment variable name {}", name);
+            return Err(ExitCode::Config);
+        }
+        set_option(value, &keys, &raw).map_err(|err| {
+            eprintln!("Config Error: environment variable {}: {}", name, err);
+            ExitCode::Config
+        })?;
+    }
+
+    Ok(())
+}
+
+fn set_option(value: &mut Value, keys: &[&str], raw: &str) -> Result<(), String> {
+    let (last, parents) = keys.split_last().expect("keys are not empty");
+    let mut table = value
+        .as_table_mut()
+        .ok_or_else(|| "the config is not a table".to_owned())?;
+    for key in parents {
+        table = table
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Table(Default::default()))
+            .as_table_mut()
+            .ok_or_else(|| format!("option {} is not a section", key))?;
+    }
+
+    let new_value = match table.get(*last) {
+        // Keeps the string type, so hex strings such as "0x1" are not parsed as integers.
+        Some(Value::String(_)) => Value::String(raw.to_owned()),
+        _ => parse_env_value(raw),
+    };
+    table.insert(last.to_string(), new_value);
+    Ok(())
+}
+
+// Parses the value as a TOML value, such as integer, boolean and array, falls back to string.
+fn parse_env_value(raw: &str) -> Value {
+    format!("value = {}", raw)
+        .parse::<Value>()
+        .ok()
+        .and_then(|mut doc| doc.as_table_mut().and_then(|t| t.remove("value")))
+        .unwrap_or_else(|| Value::String(raw.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn base() -> Value {
+        r#"
+        data_dir = "data"
+        [network]
+        listen_addresses = ["/ip4/0.0.0.0/tcp/8115"]
+        max_peers = 125
+        [block_assembler]
+        args = "0x"
+        "#
+        .parse()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_merge_local_config() {
+        let mut value = base();
+        let local: Value = r#"
+        [network]
+        max_peers = 20
+        [rpc]
+        listen_address = "0.0.0.0:8114"
+        "#
+        .parse()
+        .unwrap();
+        merge(&mut value, local);
+
+        assert_eq!(value["data_dir"].as_str(), Some("data"));
+        assert_eq!(value["network"]["max_peers"].as_integer(), Some(20));
+        assert_eq!(
+            value["network"]["listen_addresses"]
+                .as_array()
+                .map(Vec::len),
+            Some(1)
+        );
+        assert_eq!(
+            value["rpc"]["listen_address"].as_str(),
+            Some("0.0.0.0:8114")
+        );
+    }
+
+    #[test]
+    fn test_merge_fragments() {
+        let value = merge_fragments(&[
+            "[rpc]\nmodules = [\"Net\"]\n[network]\nmax_peers = 20",
+            "[rpc]\nmodules = [\"Chain\"]",
+        ])
+        .unwrap();
+        assert_eq!(value["network"]["max_peers"].as_integer(), Some(20));
+        assert_eq!(
+            value["rpc"]["modules"]
+                .as_array()
+                .map(|modules| modules.iter().filter_map(Value::as_str).collect::<Vec<_>>()),
+            Some(vec!["Chain"])
+        );
+
+        assert!(merge_fragments(&["[rpc"]).is_err());
+    }
+
+    #[test]
+    fn test_apply_env_vars() {
+        let mut value = base();
+        apply_env_vars(
+            &mut value,
+            CKB_ENV_PREFIX,
+            vars(&[
+                ("CKB__NETWORK__MAX_PEERS", "20"),
+                (
+                    "CKB__NETWORK__LISTEN_ADDRESSES",
+                    r#"["/ip4/0.0.0.0/tcp/8000"]"#,
+                ),
+                ("CKB__BLOCK_ASSEMBLER__ARGS", "0x1"),
+                ("CKB__RPC__LISTEN_ADDRESS", "0.0.0.0:8114"),
+                ("CKB_MINER__MINER__CLIENT__POLL_INTERVAL", "1000"),
+                ("PATH", "/bin"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(value["network"]["max_peers"].as_integer(), Some(20));
+        assert_eq!(
+            value["network"]["listen_addresses"][0].as_str(),
+            Some("/ip4/0.0.0.0/tcp/8000")
+        );
+        assert_eq!(value["block_assembler"]["args"].as_str(), Some("0x1"));
+        assert_eq!(
+            value["rpc"]["listen_address"].as_str(),
+            Some("0.0.0.0:8114")
+        );
+        assert!(value.get("miner").is_none());
+        assert!(value.get("path").is_none());
+    }
+
+    #[test]
+    fn test_invalid_env_vars() {
+        let mut value = base();
+        assert_eq!(
+            apply_env_vars(&mut value, CKB_ENV_PREFIX, vars(&[("CKB__NETWORK__", "1")])),
+            Err(ExitCode::Config)
+        );
+        assert_eq!(
+            apply_env_vars(
+                &mut value,
+                CKB_ENV_PREFIX,
+                vars(&[("CKB__DATA_DIR__PATH", "data")])
+            ),
+            Err(ExitCode::Config)
+        );
+    }
+}
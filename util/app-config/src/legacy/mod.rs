@@ -55,6 +55,10 @@ pub(crate) struct CKBAppConfig {
     alert_signature: Option<crate::NetworkAlertConfig>,
     #[serde(default)]
     notify: crate::NotifyConfig,
+    #[serde(default)]
+    health: crate::HealthConfig,
+    #[serde(default)]
+    memory: crate::MemoryConfig,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -101,6 +105,8 @@ impl From<CKBAppConfig> for crate::CKBAppConfig {
             store,
             alert_signature,
             notify,
+            health,
+            memory,
         } = input;
         #[cfg(not(feature = "with_sentry"))]
         let _ = sentry;
@@ -122,6 +128,8 @@ impl From<CKBAppConfig> for crate::CKBAppConfig {
             store: store.into(),
             alert_signature,
             notify,
+            health,
+            memory,
         }
     }
 }
@@ -13,6 +13,14 @@ pub(crate) struct StoreConfig {
     block_extensions_cache_size: usize,
     #[serde(default = "default_freezer_enable")]
     freezer_enable: bool,
+    #[serde(default = "default_freezer_threads")]
+    freezer_threads: usize,
+    #[serde(default)]
+    freezer_checksum: bool,
+    #[serde(default = "default_slow_commit_threshold_ms")]
+    slow_commit_threshold_ms: u64,
+    #[serde(default)]
+    read_stats: bool,
 }
 
 const fn default_block_extensions_cache_size() -> usize {
@@ -23,6 +31,14 @@ const fn default_freezer_enable() -> bool {
     false
 }
 
+const fn default_freezer_threads() -> usize {
+    1
+}
+
+const fn default_slow_commit_threshold_ms() -> u64 {
+    1000
+}
+
 impl Default for crate::StoreConfig {
     fn default() -> Self {
         StoreConfig::default().into()
@@ -40,6 +56,10 @@ impl Default for StoreConfig {
             cellbase_cache_size: None,
             block_extensions_cache_size: default_block_extensions_cache_size(),
             freezer_enable: default_freezer_enable(),
+            freezer_threads: default_freezer_threads(),
+            freezer_checksum: false,
+            slow_commit_threshold_ms: default_slow_commit_threshold_ms(),
+            read_stats: false,
         }
     }
 }
@@ -55,6 +75,10 @@ impl From<StoreConfig> for crate::StoreConfig {
             cellbase_cache_size: _,
             block_extensions_cache_size,
             freezer_enable,
+            freezer_threads,
+            freezer_checksum,
+            slow_commit_threshold_ms,
+            read_stats,
         } = input;
         Self {
             header_cache_size,
@@ -64,6 +88,10 @@ impl From<StoreConfig> for crate::StoreConfig {
             block_uncles_cache_size,
             block_extensions_cache_size,
             freezer_enable,
+            freezer_threads,
+            freezer_checksum,
+            slow_commit_threshold_ms,
+            read_stats,
         }
     }
 }
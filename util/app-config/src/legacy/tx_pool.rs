@@ -22,6 +22,10 @@ pub(crate) struct TxPoolConfig {
     min_fee_rate: FeeRate,
     max_tx_verify_cycles: Cycle,
     max_ancestors_count: usize,
+    #[serde(default)]
+    dynamic_min_fee_rate: Option<crate::DynamicMinFeeRateConfig>,
+    #[serde(default)]
+    time_lock_staging: Option<crate::TimeLockStagingConfig>,
 }
 
 impl Default for crate::TxPoolConfig {
@@ -41,6 +45,8 @@ impl Default for TxPoolConfig {
             min_fee_rate: DEFAULT_MIN_FEE_RATE,
             max_tx_verify_cycles: DEFAULT_MAX_TX_VERIFY_CYCLES,
             max_ancestors_count: DEFAULT_MAX_ANCESTORS_COUNT,
+            dynamic_min_fee_rate: None,
+            time_lock_staging: None,
         }
     }
 }
@@ -56,6 +62,8 @@ impl From<TxPoolConfig> for crate::TxPoolConfig {
             min_fee_rate,
             max_tx_verify_cycles,
             max_ancestors_count,
+            dynamic_min_fee_rate,
+            time_lock_staging,
         } = input;
         Self {
             max_mem_size,
@@ -63,6 +71,8 @@ impl From<TxPoolConfig> for crate::TxPoolConfig {
             min_fee_rate,
             max_tx_verify_cycles,
             max_ancestors_count,
+            dynamic_min_fee_rate,
+            time_lock_staging,
         }
     }
 }
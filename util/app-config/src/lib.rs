@@ -4,6 +4,7 @@ mod args;
 pub mod cli;
 mod configs;
 mod exit_code;
+mod layered;
 pub(crate) mod legacy;
 #[cfg(feature = "with_sentry")]
 mod sentry_config;
@@ -12,11 +13,13 @@ pub use app_config::{
     AppConfig, CKBAppConfig, ChainConfig, LogConfig, MetricsConfig, MinerAppConfig,
 };
 pub use args::{
-    ExportArgs, ImportArgs, InitArgs, MigrateArgs, MinerArgs, PeerIDArgs, RepairArgs, ReplayArgs,
-    ResetDataArgs, RunArgs, StatsArgs,
+    AlertSignArgs, ConfigDumpArgs, DBCompactArgs, DBStatsArgs, DivergenceCheckArgs, ExportArgs,
+    ImportArgs, InitArgs, MigrateArgs, MinerArgs, MoveAncientArgs, PeerIDArgs, RecoverArgs,
+    RepairArgs, ReplayArgs, ResetDataArgs, RunArgs, SchemaDumpArgs, StatsArgs,
 };
 pub use configs::*;
 pub use exit_code::ExitCode;
+pub use layered::{merge_fragments, CKB_ENV_PREFIX, MINER_ENV_PREFIX};
 #[cfg(feature = "with_sentry")]
 pub use sentry_config::SentryConfig;
 
@@ -89,6 +92,20 @@ impl Setup {
             .value_of(cli::ARG_ASSUME_VALID_TARGET)
             .and_then(|s| H256::from_str(&s[2..]).ok());
 
+        let sandbox = matches.is_present(cli::ARG_SANDBOX);
+        if sandbox {
+            // neither listens nor connects to any peer
+            let network = &mut config.network;
+            network.listen_addresses.clear();
+            network.public_addresses.clear();
+            network.bootnodes.clear();
+            network.dns_seeds.clear();
+            network.whitelist_peers.clear();
+            network.whitelist_only = true;
+            network.max_outbound_peers = 0;
+            network.upnp = false;
+        }
+
         Ok(RunArgs {
             config,
             consensus,
@@ -96,6 +113,8 @@ impl Setup {
             skip_chain_spec_check: matches.is_present(cli::ARG_SKIP_CHAIN_SPEC_CHECK),
             overwrite_chain_spec: matches.is_present(cli::ARG_OVERWRITE_CHAIN_SPEC),
             chain_spec_hash,
+            check: matches.is_present(cli::ARG_RUN_CHECK),
+            sandbox,
         })
     }
 
@@ -121,6 +140,42 @@ impl Setup {
         Ok(RepairArgs { config })
     }
 
+    /// `recover` subcommand
+    pub fn recover(self, matches: &ArgMatches<'_>) -> Result<RecoverArgs, ExitCode> {
+        let consensus = self.consensus()?;
+        let config = self.config.into_ckb()?;
+        let force = matches.is_present(cli::ARG_FORCE);
+
+        Ok(RecoverArgs {
+            config,
+            consensus,
+            force,
+        })
+    }
+
+    /// `db compact` subcommand
+    pub fn db_compact(self, matches: &ArgMatches<'_>) -> Result<DBCompactArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+        let column = matches.value_of(cli::ARG_CF).map(ToOwned::to_owned);
+
+        Ok(DBCompactArgs { config, column })
+    }
+
+    /// `db stats` subcommand
+    pub fn db_stats(self, _matches: &ArgMatches<'_>) -> Result<DBStatsArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+
+        Ok(DBStatsArgs { config })
+    }
+
+    /// `move-ancient` subcommand
+    pub fn move_ancient(self, matches: &ArgMatches<'_>) -> Result<MoveAncientArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+        let target = value_t!(matches.value_of(cli::ARG_TO), PathBuf)?;
+
+        Ok(MoveAncientArgs { config, target })
+    }
+
     /// Executes `ckb miner`.
     pub fn miner(self, matches: &ArgMatches<'_>) -> Result<MinerArgs, ExitCode> {
         let spec = self.chain_spec()?;
@@ -157,6 +212,12 @@ impl Setup {
         };
         let sanity_check = matches.is_present(cli::ARG_SANITY_CHECK);
         let full_verification = matches.is_present(cli::ARG_FULL_VERIFICATION);
+        let divergence_check = DivergenceCheckArgs {
+            from: option_value_t!(matches, cli::ARG_FROM, u64)?,
+            to: option_value_t!(matches, cli::ARG_TO, u64)?,
+            verify_dao: matches.is_present(cli::ARG_VERIFY_DAO),
+            verify_cells: matches.is_present(cli::ARG_VERIFY_CELLS),
+        };
         Ok(ReplayArgs {
             config,
             consensus,
@@ -164,6 +225,7 @@ impl Setup {
             profile,
             sanity_check,
             full_verification,
+            divergence_check,
         })
     }
 
@@ -235,6 +297,15 @@ impl Setup {
         };
         let rpc_port = matches.value_of(cli::ARG_RPC_PORT).unwrap().to_string();
         let p2p_port = matches.value_of(cli::ARG_P2P_PORT).unwrap().to_string();
+        let rpc_modules = matches
+            .values_of(cli::ARG_RPC_MODULES)
+            .unwrap_or_default()
+            .map(str::to_string)
+            .collect();
+        let metrics_exporter = matches
+            .value_of(cli::ARG_METRICS_EXPORTER)
+            .map(str::to_string);
+        let config_dropin = matches.value_of(cli::ARG_CONFIG_DROPIN).map(PathBuf::from);
         let (log_to_file, log_to_stdout) = match matches.value_of(cli::ARG_LOG_TO) {
             Some("file") => (true, false),
             Some("stdout") => (false, true),
@@ -269,6 +340,9 @@ impl Setup {
             chain,
             rpc_port,
             p2p_port,
+            rpc_modules,
+            metrics_exporter,
+            config_dropin,
             list_chains,
             force,
             log_to_file,
@@ -382,6 +456,60 @@ impl Setup {
         }
     }
 
+    /// Executes `ckb config dump`.
+    pub fn config_dump(matches: &ArgMatches<'_>) -> Result<ConfigDumpArgs, ExitCode> {
+        Ok(ConfigDumpArgs {
+            root_dir: Self::root_dir_from_matches(matches)?,
+            effective: matches.is_present(cli::ARG_EFFECTIVE),
+        })
+    }
+
+    /// Executes `ckb alert sign`.
+    pub fn alert_sign(matches: &ArgMatches<'_>) -> Result<AlertSignArgs, ExitCode> {
+        let privkey_path = value_t!(matches, cli::ARG_PRIVKEY_PATH, PathBuf)?;
+        let alert = match matches.value_of(cli::ARG_ALERT) {
+            Some(path) => {
+                let content = std::fs::read_to_string(path)?;
+                serde_json::from_str(&content).map_err(|err| {
+                    eprintln!("Invalid alert file {}: {}", path, err);
+                    ExitCode::Cli
+                })?
+            }
+            None => ckb_jsonrpc_types::Alert {
+                id: value_t!(matches, cli::ARG_ID, u32)?.into(),
+                cancel: option_value_t!(matches, cli::ARG_CANCEL, u32)?
+                    .unwrap_or(0)
+                    .into(),
+                min_version: matches
+                    .value_of(cli::ARG_MIN_VERSION)
+                    .map(ToOwned::to_owned),
+                max_version: matches
+                    .value_of(cli::ARG_MAX_VERSION)
+                    .map(ToOwned::to_owned),
+                priority: option_value_t!(matches, cli::ARG_PRIORITY, u32)?
+                    .unwrap_or(0)
+                    .into(),
+                notice_until: value_t!(matches, cli::ARG_NOTICE_UNTIL, u64)?.into(),
+                message: matches
+                    .value_of(cli::ARG_MESSAGE)
+                    .expect("required arg")
+                    .to_owned(),
+                signatures: Vec::new(),
+            },
+        };
+        Ok(AlertSignArgs {
+            alert,
+            privkey_path,
+        })
+    }
+
+    /// Executes `ckb schema dump`.
+    pub fn schema_dump(matches: &ArgMatches<'_>) -> Result<SchemaDumpArgs, ExitCode> {
+        Ok(SchemaDumpArgs {
+            target: value_t!(matches, cli::ARG_TARGET, PathBuf)?,
+        })
+    }
+
     /// Generates the network secret key.
     pub fn gen(matches: &ArgMatches<'_>) -> Result<(), ExitCode> {
         let path = matches.value_of(cli::ARG_SECRET_PATH).unwrap();
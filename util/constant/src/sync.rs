@@ -34,6 +34,10 @@ pub const POW_INTERVAL: u64 = 10;
 /// Protect at least this many outbound peers from disconnection due to slow
 /// behind headers chain.
 pub const MAX_OUTBOUND_PEERS_TO_PROTECT_FROM_DISCONNECT: usize = 4;
+/// The number of outbound peers disconnected each time the tip is found stale
+pub const STALE_TIP_PEERS_TO_ROTATE: usize = 2;
+/// The interval to check whether the tip is stale
+pub const STALE_TIP_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 /// Chain sync timout
 pub const CHAIN_SYNC_TIMEOUT: u64 = 12 * 60 * 1000; // 12 minutes
 /// Suspend sync time
@@ -63,3 +67,11 @@ pub const BAD_MESSAGE_BAN_TIME: Duration = Duration::from_secs(5 * 60);
 /// Default ban time for sync useless
 // 10 minutes, peer have no common ancestor block
 pub const SYNC_USELESS_BAN_TIME: Duration = Duration::from_secs(10 * 60);
+
+/// The interval of the block numbers of the headers recorded in the header checkpoint, the PoW of
+/// these headers and their ancestors is not verified again after restart
+pub const HEADER_CHECKPOINT_INTERVAL: u64 = 100;
+
+/// The max number of the invalid headers recorded in the store, the least recently rejected ones
+/// are dropped first
+pub const MAX_INVALID_HEADERS: usize = 1024;
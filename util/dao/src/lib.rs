@@ -302,6 +302,53 @@ impl<'a, DL: CellDataProvider + EpochProvider + HeaderProvider> DaoCalculator<'a
     }
 }
 
+/// The expected dao field and fees of a block, see [`simulate_dao_field`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaoFieldSimulation {
+    /// The expected dao field of the block
+    pub dao: Byte32,
+    /// The fee contributed by each transaction, in the same order as the transactions.
+    ///
+    /// The fee of the cellbase is always zero.
+    pub fees: Vec<Capacity>,
+}
+
+impl DaoFieldSimulation {
+    /// Returns the total fees of the transactions.
+    pub fn total_fee(&self) -> CapacityResult<Capacity> {
+        self.fees
+            .iter()
+            .try_fold(Capacity::zero(), |total, fee| total.safe_add(*fee))
+    }
+}
+
+/// Simulates packaging the resolved transactions into a block on top of `parent`.
+///
+/// Returns the dao field the block must carry and the fee of every transaction, without touching
+/// any state. Miners and test harnesses can use it to cross-check a block template before
+/// submitting it. `data_loader` must be able to provide the epoch of `parent` and the headers
+/// referenced by the NervosDAO withdrawing transactions.
+pub fn simulate_dao_field<DL: CellDataProvider + EpochProvider + HeaderProvider>(
+    consensus: &Consensus,
+    data_loader: &DL,
+    parent: &HeaderView,
+    rtxs: &[ResolvedTransaction],
+) -> Result<DaoFieldSimulation, Error> {
+    let calculator = DaoCalculator::new(consensus, data_loader);
+    let dao = calculator.dao_field(rtxs, parent)?;
+    let fees = rtxs
+        .iter()
+        .map(|rtx| {
+            if rtx.transaction.is_cellbase() {
+                Ok(Capacity::zero())
+            } else {
+                calculator.transaction_fee(rtx)
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(DaoFieldSimulation { dao, fees })
+}
+
 /// return special occupied capacity if cell is satoshi's gift
 /// otherwise return cell occupied capacity
 pub fn modified_occupied_capacity(
@@ -333,6 +380,7 @@ mod tests {
             HeaderBuilder, TransactionBuilder,
         },
         h256,
+        packed::CellInput,
         utilities::DIFF_TWO,
         H256, U256,
     };
@@ -544,6 +592,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check_dao_field_simulation() {
+        let consensus = Consensus::default();
+
+        let parent_number = 12345;
+        let parent_header = HeaderBuilder::default()
+            .number(parent_number.pack())
+            .dao(pack_dao_data(
+                10_000_000_000_123_456,
+                Capacity::shannons(500_000_000_123_000),
+                Capacity::shannons(400_000_000_123),
+                Capacity::shannons(600_000_000_000),
+            ))
+            .build();
+
+        let (store, parent_header) = prepare_store(&parent_header, None);
+        let cellbase = TransactionBuilder::default()
+            .input(CellInput::new_cellbase_input(parent_number + 1))
+            .output(
+                CellOutput::new_builder()
+                    .capacity(capacity_bytes!(1000).pack())
+                    .build(),
+            )
+            .output_data(Bytes::new().pack())
+            .build();
+        let input_cell = CellOutput::new_builder()
+            .capacity(capacity_bytes!(20000).pack())
+            .build();
+        let tx = TransactionBuilder::default()
+            .output(
+                CellOutput::new_builder()
+                    .capacity(capacity_bytes!(19000).pack())
+                    .build(),
+            )
+            .output_data(Bytes::from("abcde").pack())
+            .build();
+        let rtxs = vec![
+            ResolvedTransaction {
+                transaction: cellbase,
+                resolved_cell_deps: vec![],
+                resolved_inputs: vec![],
+                resolved_dep_groups: vec![],
+            },
+            ResolvedTransaction {
+                transaction: tx,
+                resolved_cell_deps: vec![],
+                resolved_inputs: vec![
+                    CellMetaBuilder::from_cell_output(input_cell, Bytes::new()).build()
+                ],
+                resolved_dep_groups: vec![],
+            },
+        ];
+
+        let data_loader = store.as_data_provider();
+        let simulation =
+            simulate_dao_field(&consensus, &data_loader, &parent_header, &rtxs).unwrap();
+        let dao = DaoCalculator::new(&consensus, &data_loader)
+            .dao_field(&rtxs, &parent_header)
+            .unwrap();
+        assert_eq!(simulation.dao, dao);
+        assert_eq!(
+            simulation.fees,
+            vec![Capacity::zero(), capacity_bytes!(1000)]
+        );
+        assert_eq!(simulation.total_fee().unwrap(), capacity_bytes!(1000));
+    }
+
     #[test]
     fn check_withdraw_calculation() {
         let data = Bytes::from(vec![1; 10]);
@@ -11,6 +11,8 @@ pub use blake2b_ref::{Blake2b, Blake2bBuilder};
 #[cfg(not(target_arch = "wasm32"))]
 pub use blake2b_rs::{Blake2b, Blake2bBuilder};
 
+use std::io;
+
 #[doc(hidden)]
 pub const BLAKE2B_KEY: &[u8] = &[];
 /// Output digest size.
@@ -70,6 +72,101 @@ pub fn blake2b_256<T: AsRef<[u8]>>(s: T) -> [u8; 32] {
     inner_blake2b_256(s)
 }
 
+/// Hashes the concatenation of the chunks without copying them into one buffer.
+///
+/// ## Examples
+///
+/// ```
+/// use ckb_hash::{blake2b_256, blake2b_256_chunks};
+///
+/// let digest = blake2b_256_chunks(&[&b"left|"[..], &b"right"[..]]);
+/// assert_eq!(digest, blake2b_256(&b"left|right"));
+/// ```
+pub fn blake2b_256_chunks<I, T>(chunks: I) -> [u8; 32]
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut writer = Blake2bWriter::new();
+    for chunk in chunks {
+        writer.update(chunk.as_ref());
+    }
+    writer.finalize()
+}
+
+/// Hashes the data read from the reader until EOF.
+///
+/// The data is hashed in fixed size chunks, so large files and network streams can be hashed
+/// without loading them into memory.
+///
+/// ## Examples
+///
+/// ```
+/// use ckb_hash::{blake2b_256, blake2b_256_reader};
+///
+/// let digest = blake2b_256_reader(&b"ckb"[..]).unwrap();
+/// assert_eq!(digest, blake2b_256(&b"ckb"));
+/// ```
+pub fn blake2b_256_reader<R: io::Read>(mut reader: R) -> io::Result<[u8; 32]> {
+    let mut writer = Blake2bWriter::new();
+    io::copy(&mut reader, &mut writer)?;
+    Ok(writer.finalize())
+}
+
+/// A hasher with the CKB default hash configurations which implements [`std::io::Write`].
+///
+/// ## Examples
+///
+/// ```
+/// use ckb_hash::{blake2b_256, Blake2bWriter};
+/// use std::io::Write;
+///
+/// let mut writer = Blake2bWriter::new();
+/// write!(writer, "{}|{}", "left", "right").unwrap();
+/// assert_eq!(writer.finalize(), blake2b_256(&b"left|right"));
+/// ```
+pub struct Blake2bWriter {
+    blake2b: Blake2b,
+}
+
+impl Blake2bWriter {
+    /// Creates a new writer.
+    pub fn new() -> Self {
+        Blake2bWriter {
+            blake2b: new_blake2b(),
+        }
+    }
+
+    /// Feeds the data into the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        self.blake2b.update(data);
+    }
+
+    /// Returns the digest of the data written so far.
+    pub fn finalize(self) -> [u8; 32] {
+        let mut result = [0u8; 32];
+        self.blake2b.finalize(&mut result);
+        result
+    }
+}
+
+impl Default for Blake2bWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl io::Write for Blake2bWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 fn inner_blake2b_256<T: AsRef<[u8]>>(s: T) -> [u8; 32] {
     let mut result = [0u8; 32];
     let mut blake2b = new_blake2b();
@@ -83,3 +180,12 @@ fn empty_blake2b() {
     let actual = inner_blake2b_256([]);
     assert_eq!(actual, BLANK_HASH);
 }
+
+#[test]
+fn streaming_blake2b() {
+    let data = (0..10_000u32).map(|i| i as u8).collect::<Vec<_>>();
+    let expected = blake2b_256(&data);
+    assert_eq!(blake2b_256_chunks(data.chunks(7)), expected);
+    assert_eq!(blake2b_256_reader(&data[..]).unwrap(), expected);
+    assert_eq!(blake2b_256_chunks(Vec::<&[u8]>::new()), BLANK_HASH);
+}
@@ -1,75 +1,404 @@
+use crate::progress::{Bar, Progress, BYTES_TEMPLATE, COUNT_TEMPLATE};
 use ckb_chain::chain::ChainController;
-use ckb_jsonrpc_types::BlockView as JsonBlock;
-use ckb_types::core;
-#[cfg(feature = "progress_bar")]
-use indicatif::{ProgressBar, ProgressStyle};
+use ckb_chain_spec::consensus::Consensus;
+use ckb_channel::{bounded, Receiver};
+use ckb_jsonrpc_types::{BlockView as JsonBlock, HeaderView as JsonHeader};
+use ckb_shared::shared::Shared;
+use ckb_types::{
+    core::{self, BlockNumber},
+    packed::Byte32,
+    prelude::*,
+};
+use ckb_verification::{BlockVerifier, NonContextualBlockTxsVerifier};
+use ckb_verification_traits::{Switch, Verifier};
+use rayon::prelude::*;
+use serde::Deserialize;
 use std::error::Error;
 use std::fs;
-use std::io;
-use std::io::BufRead;
-use std::path::PathBuf;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::thread;
+
+/// The max count of the blocks parsed and verified in parallel as a batch.
+const BATCH_SIZE: usize = 1024;
+/// The max total size of the exported blocks in a batch, so the large blocks are read in smaller
+/// batches.
+const BATCH_BYTES: usize = 16 * 1024 * 1024;
+/// The count of the verified batches waiting to be committed.
+///
+/// Besides them, one batch is being verified and one is being committed, so the blocks in the
+/// pipeline are bounded by `(PIPELINE_DEPTH + 2) * BATCH_BYTES` of the exported JSON, which is
+/// larger than the parsed blocks.
+const PIPELINE_DEPTH: usize = 4;
+
+type ImportError = Box<dyn Error + Send + Sync>;
+type Lines = io::Lines<io::BufReader<fs::File>>;
+
+// Only the header is deserialized in the headers pass, the other fields are skipped.
+#[derive(Deserialize)]
+struct JsonBlockHeader {
+    header: JsonHeader,
+}
 
 /// Export block date from file to database.
+///
+/// The import runs in two passes over the file:
+///
+/// 1. The header chain is verified first, including the proof of work, so a broken or forged
+///    file is rejected before any block is committed.
+/// 2. The blocks are parsed and verified non-contextually in parallel batches, while the
+///    verified batches are committed to the chain in order.
+///
+/// Only the parsing and the non-contextual verification run in parallel. The contextual
+/// verification, including the scripts, and the commit still run block by block in the chain
+/// service, so the speedup depends on how much of the import time the former take.
 pub struct Import {
     /// source file contains block data
     source: PathBuf,
     chain: ChainController,
+    shared: Shared,
 }
 
 impl Import {
     /// Creates a new import job.
-    pub fn new(chain: ChainController, source: PathBuf) -> Self {
-        Import { chain, source }
+    pub fn new(chain: ChainController, shared: Shared, source: PathBuf) -> Self {
+        Import {
+            chain,
+            shared,
+            source,
+        }
     }
 
     /// Executes the import job.
     pub fn execute(self) -> Result<(), Box<dyn Error>> {
-        self.read_from_json()
+        let progress = Progress::new(3);
+        let headers_bar = progress.add(
+            1,
+            fs::metadata(&self.source)?.len(),
+            BYTES_TEMPLATE,
+            "verifying headers",
+        );
+        let verified_bar = progress.add(2, 0, COUNT_TEMPLATE, "verifying blocks");
+        let committed_bar = progress.add(3, 0, COUNT_TEMPLATE, "committing blocks");
+
+        let job = thread::Builder::new()
+            .name("Import".to_owned())
+            .spawn(move || {
+                let ret = self.import(&headers_bar, &verified_bar, &committed_bar);
+                // The unfinished bars block the drawing.
+                if ret.is_err() {
+                    headers_bar.abandon();
+                    verified_bar.abandon();
+                    committed_bar.abandon();
+                }
+                ret
+            })?;
+        progress.join();
+        job.join()
+            .map_err(|_| "the import thread panicked")?
+            .map_err(|err| err as Box<dyn Error>)
     }
 
-    #[cfg(not(feature = "progress_bar"))]
-    pub fn read_from_json(&self) -> Result<(), Box<dyn Error>> {
-        let f = fs::File::open(&self.source)?;
-        let reader = io::BufReader::new(f);
+    fn import(
+        &self,
+        headers_bar: &Bar,
+        verified_bar: &Bar,
+        committed_bar: &Bar,
+    ) -> Result<(), ImportError> {
+        let count = self.verify_headers(headers_bar)?;
+        headers_bar.finish_with_message("headers verified");
+        verified_bar.set_length(count);
+        committed_bar.set_length(count);
 
-        for line in reader.lines() {
-            let s = line?;
-            let block: JsonBlock = serde_json::from_str(&s)?;
-            let block: Arc<core::BlockView> = Arc::new(block.into());
-            if !block.is_genesis() {
-                self.chain
-                    .process_block(block)
-                    .expect("import occur malformation data");
+        let (sender, receiver) = bounded(PIPELINE_DEPTH);
+        let shared = self.shared.clone();
+        let source = self.source.clone();
+        let bar = verified_bar.clone();
+        let verifier = thread::Builder::new()
+            .name("ImportVerifier".to_owned())
+            .spawn(move || -> Result<(), ImportError> {
+                let consensus = shared.consensus();
+                let mut lines = open_lines(&source)?;
+                loop {
+                    let batch = read_batch(&mut lines)?;
+                    if batch.is_empty() {
+                        return Ok(());
+                    }
+                    let mut blocks = batch
+                        .par_iter()
+                        .map(|line| parse_block(consensus, line))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    blocks.retain(|block| !block.is_genesis());
+                    bar.inc(blocks.len() as u64);
+                    // The committer has stopped on an error.
+                    if sender.send(blocks).is_err() {
+                        return Ok(());
+                    }
+                }
+            })?;
+
+        let committed = self.commit_blocks(&receiver, committed_bar);
+        drop(receiver);
+        let verified = verifier
+            .join()
+            .map_err(|_| "the import verifier panicked")?;
+        committed.and(verified)?;
+
+        verified_bar.finish_with_message("blocks verified");
+        committed_bar.finish_with_message("done!");
+        Ok(())
+    }
+
+    // Verifies that the headers form a chain and satisfy the proof of work, returns the count of
+    // the blocks to import.
+    fn verify_headers(&self, bar: &Bar) -> Result<u64, ImportError> {
+        let consensus = self.shared.consensus();
+        let pow_engine = consensus.pow_engine();
+        let mut lines = open_lines(&self.source)?;
+        let mut parent: Option<(BlockNumber, Byte32)> = None;
+        let mut count = 0;
+        loop {
+            let batch = read_batch(&mut lines)?;
+            if batch.is_empty() {
+                return Ok(count);
+            }
+            let headers = batch
+                .par_iter()
+                .map(|line| -> Result<core::HeaderView, ImportError> {
+                    let json: JsonBlockHeader = serde_json::from_str(line)?;
+                    let expected_hash = json.header.hash.pack();
+                    let header: core::HeaderView = json.header.into();
+                    if header.hash() != expected_hash {
+                        return Err(format!(
+                            "the hash of header {} does not match the exported {}",
+                            header.number(),
+                            expected_hash
+                        )
+                        .into());
+                    }
+                    if header.is_genesis() {
+                        if header.hash() != consensus.genesis_hash() {
+                            return Err("the genesis block does not match the chain spec".into());
+                        }
+                    } else if !pow_engine.verify(&header.data()) {
+                        return Err(format!(
+                            "header {} has invalid proof of work",
+                            header.number()
+                        )
+                        .into());
+                    }
+                    Ok(header)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for header in headers {
+                if let Some((number, hash)) = &parent {
+                    if header.number() != number + 1 || &header.parent_hash() != hash {
+                        return Err(format!(
+                            "header {}-{} does not follow header {}-{}",
+                            header.number(),
+                            header.hash(),
+                            number,
+                            hash
+                        )
+                        .into());
+                    }
+                }
+                if !header.is_genesis() {
+                    count += 1;
+                }
+                parent = Some((header.number(), header.hash()));
             }
+            bar.inc(batch.iter().map(|line| line.len() as u64 + 1).sum());
         }
-        Ok(())
     }
 
-    /// Imports the chain from the JSON file.
-    #[cfg(feature = "progress_bar")]
-    pub fn read_from_json(&self) -> Result<(), Box<dyn Error>> {
-        let metadata = fs::metadata(&self.source)?;
-        let f = fs::File::open(&self.source)?;
-        let reader = io::BufReader::new(f);
-        let progress_bar = ProgressBar::new(metadata.len() as u64);
-        progress_bar.set_style(
-            ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] {bar:50.cyan/blue} {bytes:>6}/{total_bytes:6} {msg}")
-                .progress_chars("##-"),
-        );
-        for line in reader.lines() {
-            let s = line?;
-            let block: JsonBlock = serde_json::from_str(&s)?;
-            let block: Arc<core::BlockView> = Arc::new(block.into());
-            if !block.is_genesis() {
+    // The non-contextual verification is done in the verifier thread.
+    fn commit_blocks(
+        &self,
+        receiver: &Receiver<Vec<core::BlockView>>,
+        bar: &Bar,
+    ) -> Result<(), ImportError> {
+        for blocks in receiver.iter() {
+            for block in blocks {
+                let number = block.number();
                 self.chain
-                    .process_block(block)
-                    .expect("import occur malformation data");
+                    .internal_process_block(Arc::new(block), Switch::DISABLE_NON_CONTEXTUAL)
+                    .map_err(|err| format!("failed to import block {}: {}", number, err))?;
+                bar.inc(1);
             }
-            progress_bar.inc(s.as_bytes().len() as u64);
         }
-        progress_bar.finish_with_message("done!");
         Ok(())
     }
 }
+
+fn open_lines(path: &Path) -> io::Result<Lines> {
+    fs::File::open(path).map(|f| io::BufReader::new(f).lines())
+}
+
+// Reads up to `BATCH_SIZE` lines, and stops earlier once the lines reach `BATCH_BYTES`.
+fn read_batch(lines: &mut Lines) -> io::Result<Vec<String>> {
+    let mut batch = Vec::new();
+    let mut bytes = 0;
+    while batch.len() < BATCH_SIZE && bytes < BATCH_BYTES {
+        match lines.next() {
+            Some(line) => {
+                let line = line?;
+                bytes += line.len();
+                batch.push(line);
+            }
+            None => break,
+        }
+    }
+    Ok(batch)
+}
+
+fn parse_block(consensus: &Consensus, line: &str) -> Result<core::BlockView, ImportError> {
+    let block: JsonBlock = serde_json::from_str(line)?;
+    let block: core::BlockView = block.into();
+    if !block.is_genesis() {
+        BlockVerifier::new(consensus)
+            .verify(&block)
+            .and_then(|_| NonContextualBlockTxsVerifier::new(consensus).verify(&block))
+            .map_err(|err| format!("block {} is invalid: {}", block.number(), err))?;
+    }
+    Ok(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_chain::chain::ChainService;
+    use ckb_chain_spec::consensus::ConsensusBuilder;
+    use ckb_launcher::SharedBuilder;
+    use ckb_pow::Pow;
+    use ckb_types::{
+        core::{HeaderBuilder, HeaderView},
+        packed,
+        utilities::difficulty_to_compact,
+        U256,
+    };
+    use std::io::Write;
+
+    // The default genesis block has the difficulty 2, so half of the nonces pass the proof of
+    // work of its children.
+    fn consensus() -> Consensus {
+        ConsensusBuilder::default().pow(Pow::Eaglesong).build()
+    }
+
+    // Finds the nonce with which the header passes or fails the proof of work.
+    fn seal(consensus: &Consensus, header: HeaderView, valid: bool) -> HeaderView {
+        let pow_engine = consensus.pow_engine();
+        (0u128..)
+            .map(|nonce| header.as_advanced_builder().nonce(nonce.pack()).build())
+            .find(|header| pow_engine.verify(&header.data()) == valid)
+            .expect("nonce found")
+    }
+
+    fn child(consensus: &Consensus, parent: &HeaderView) -> HeaderView {
+        let header = HeaderBuilder::default()
+            .parent_hash(parent.hash())
+            .number((parent.number() + 1).pack())
+            .timestamp((parent.timestamp() + 1).pack())
+            .compact_target(parent.compact_target().pack())
+            .build();
+        seal(consensus, header, true)
+    }
+
+    fn headers(consensus: &Consensus, count: usize) -> Vec<HeaderView> {
+        let mut headers = vec![consensus.genesis_block().header()];
+        for _ in 0..count {
+            let header = child(consensus, headers.last().unwrap());
+            headers.push(header);
+        }
+        headers
+    }
+
+    fn verify_headers(headers: &[HeaderView]) -> Result<u64, ImportError> {
+        let (shared, mut pack) = SharedBuilder::with_temp_db()
+            .consensus(consensus())
+            .build()
+            .unwrap();
+        let chain =
+            ChainService::new(shared.clone(), pack.take_proposal_table()).start::<&str>(None);
+
+        let mut source = tempfile::NamedTempFile::new().unwrap();
+        for header in headers {
+            let block = packed::Block::new_builder()
+                .header(header.data())
+                .build()
+                .into_view();
+            let json = serde_json::to_string(&JsonBlock::from(block)).unwrap();
+            writeln!(source, "{}", json).unwrap();
+        }
+        source.flush().unwrap();
+
+        let import = Import::new(chain, shared, source.path().to_path_buf());
+        let progress = Progress::new(1);
+        let bar = progress.add(1, 0, BYTES_TEMPLATE, "verifying headers");
+        import.verify_headers(&bar)
+    }
+
+    #[test]
+    fn test_verify_headers() {
+        let consensus = consensus();
+        assert_eq!(verify_headers(&headers(&consensus, 3)).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_verify_headers_broken_link() {
+        let consensus = consensus();
+        let mut headers = headers(&consensus, 3);
+        // Header 2 is built on the genesis block.
+        headers[2] = seal(
+            &consensus,
+            headers[2]
+                .as_advanced_builder()
+                .parent_hash(headers[0].hash())
+                .build(),
+            true,
+        );
+
+        let err = verify_headers(&headers).unwrap_err();
+        assert!(
+            err.to_string().contains("does not follow header 1"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_verify_headers_invalid_pow() {
+        let consensus = consensus();
+        let mut headers = headers(&consensus, 2);
+        headers[2] = seal(
+            &consensus,
+            headers[2]
+                .as_advanced_builder()
+                .compact_target(difficulty_to_compact(U256::from(u64::MAX)).pack())
+                .build(),
+            false,
+        );
+
+        let err = verify_headers(&headers).unwrap_err();
+        assert_eq!(err.to_string(), "header 2 has invalid proof of work");
+    }
+
+    #[test]
+    fn test_verify_headers_wrong_genesis() {
+        let consensus = consensus();
+        let genesis = consensus.genesis_block().header();
+        let other_genesis = genesis
+            .as_advanced_builder()
+            .timestamp((genesis.timestamp() + 1).pack())
+            .build();
+        let headers = vec![other_genesis.clone(), child(&consensus, &other_genesis)];
+
+        let err = verify_headers(&headers).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the genesis block does not match the chain spec"
+        );
+    }
+}
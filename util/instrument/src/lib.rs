@@ -7,6 +7,7 @@
 
 mod export;
 mod import;
+mod progress;
 
 pub use crate::export::Export;
 pub use crate::import::Import;
@@ -0,0 +1,86 @@
+//! The progress bars of the long running jobs, which are hidden without the feature
+//! `progress_bar`.
+#[cfg(feature = "progress_bar")]
+pub(crate) use self::indicatif_progress::{Bar, Progress};
+#[cfg(not(feature = "progress_bar"))]
+pub(crate) use self::noop_progress::{Bar, Progress};
+
+pub(crate) const BYTES_TEMPLATE: &str =
+    "{prefix:.bold.dim} {spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}";
+pub(crate) const COUNT_TEMPLATE: &str =
+    "{prefix:.bold.dim} {spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}";
+
+#[cfg(feature = "progress_bar")]
+mod indicatif_progress {
+    use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+    pub(crate) type Bar = ProgressBar;
+
+    /// The bars of the steps of a job, drawn together like the database migrations.
+    pub(crate) struct Progress {
+        multi: MultiProgress,
+        steps: usize,
+    }
+
+    impl Progress {
+        pub(crate) fn new(steps: usize) -> Self {
+            Progress {
+                multi: MultiProgress::with_draw_target(ProgressDrawTarget::stdout()),
+                steps,
+            }
+        }
+
+        /// Adds the bar of the step, which starts from 1.
+        pub(crate) fn add(&self, step: usize, len: u64, template: &str, msg: &'static str) -> Bar {
+            let bar = self.multi.add(ProgressBar::new(len));
+            bar.set_style(
+                ProgressStyle::default_bar()
+                    .template(template)
+                    .progress_chars("#>-"),
+            );
+            bar.set_prefix(format!("[{}/{}]", step, self.steps));
+            bar.set_message(msg);
+            bar
+        }
+
+        /// Draws the bars until all of them are finished.
+        pub(crate) fn join(&self) {
+            if let Err(err) = self.multi.join() {
+                eprintln!("failed to draw the progress bars: {}", err);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "progress_bar"))]
+mod noop_progress {
+    #[derive(Clone)]
+    pub(crate) struct Bar;
+
+    impl Bar {
+        pub(crate) fn inc(&self, _delta: u64) {}
+        pub(crate) fn set_length(&self, _len: u64) {}
+        pub(crate) fn finish_with_message(&self, _msg: &'static str) {}
+        pub(crate) fn abandon(&self) {}
+    }
+
+    pub(crate) struct Progress;
+
+    impl Progress {
+        pub(crate) fn new(_steps: usize) -> Self {
+            Progress
+        }
+
+        pub(crate) fn add(
+            &self,
+            _step: usize,
+            _len: u64,
+            _template: &str,
+            _msg: &'static str,
+        ) -> Bar {
+            Bar
+        }
+
+        pub(crate) fn join(&self) {}
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
     BlockNumber, Byte32, Capacity, Cycle, EpochNumber, EpochNumberWithFraction, ProposalShortId,
     Timestamp, Uint128, Uint32, Uint64, Version,
 };
-use ckb_types::{core, packed, prelude::*, H256};
+use ckb_types::{core, packed, prelude::*, H256, U256};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
@@ -550,6 +550,29 @@ impl TransactionWithStatus {
     }
 }
 
+/// The JSON view of a transaction, its status and the cells consumed by its inputs.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct TransactionAndCells {
+    /// The transaction.
+    pub transaction: TransactionView,
+    /// The Transaction status.
+    pub tx_status: TxStatus,
+    /// The cells consumed by the transaction inputs, in the same order as `inputs`.
+    ///
+    /// An element is `null` when the input is a cellbase input or the previous output cannot be
+    /// found in the chain or the transaction memory pool.
+    pub input_cells: Vec<Option<InputCell>>,
+}
+
+/// The previous output which is consumed by a transaction input.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct InputCell {
+    /// The cell output, including its capacity, lock script and type script.
+    pub output: CellOutput,
+    /// The hash of the cell data.
+    pub data_hash: H256,
+}
+
 /// Status for transaction
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
 #[serde(rename_all = "lowercase")]
@@ -1041,6 +1064,109 @@ impl EpochView {
     }
 }
 
+/// The predicted parameters of an upcoming epoch.
+///
+/// The prediction assumes the hash rate stays the same and the orphan rate meets the target.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::EpochPreview>(r#"
+/// {
+///   "difficulty": "0x1f4003",
+///   "estimated_start_timestamp": "0x5dab7d17",
+///   "length": "0x708",
+///   "max_difficulty": "0x3e8006",
+///   "min_difficulty": "0xfa001",
+///   "number": "0x2",
+///   "primary_reward": "0x71afd498d000",
+///   "secondary_reward": "0x37d0c8e28542",
+///   "start_number": "0xaf0"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct EpochPreview {
+    /// The epoch number.
+    pub number: EpochNumber,
+    /// The predicted block number of the first block in the epoch.
+    pub start_number: BlockNumber,
+    /// The predicted number of blocks in the epoch.
+    pub length: BlockNumber,
+    /// The estimated timestamp of the first block in the epoch, in milliseconds.
+    ///
+    /// It is estimated from the tip block timestamp and the epoch duration target.
+    pub estimated_start_timestamp: Timestamp,
+    /// The primary reward of the epoch, which is halved every
+    /// `primary_epoch_reward_halving_interval` epochs.
+    pub primary_reward: Capacity,
+    /// The secondary reward of the epoch.
+    pub secondary_reward: Capacity,
+    /// The predicted difficulty assuming the current hash rate.
+    pub difficulty: U256,
+    /// The lowest difficulty the epoch can reach under the difficulty adjustment dampening.
+    pub min_difficulty: U256,
+    /// The highest difficulty the epoch can reach under the difficulty adjustment dampening.
+    pub max_difficulty: U256,
+}
+
+/// The statistics of an epoch in the canonical chain.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::EpochStats>(r#"
+/// {
+///   "average_block_interval": "0x1f40",
+///   "blocks_count": "0x708",
+///   "compact_target": "0x1e083126",
+///   "difficulty": "0x1f4003",
+///   "length": "0x708",
+///   "number": "0x1",
+///   "orphan_rate_target": {
+///     "denom": "0x28",
+///     "numer": "0x1"
+///   },
+///   "start_number": "0x3e8",
+///   "uncle_rate": {
+///     "denom": "0x168",
+///     "numer": "0x7"
+///   },
+///   "uncles_count": "0x23"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct EpochStats {
+    /// The epoch number.
+    pub number: EpochNumber,
+    /// The block number of the first block in the epoch.
+    pub start_number: BlockNumber,
+    /// The number of blocks in the epoch.
+    pub length: BlockNumber,
+    /// The number of the blocks of the epoch in the chain.
+    ///
+    /// It is less than `length` for the current epoch.
+    pub blocks_count: Uint64,
+    /// The number of the uncles included in the blocks of the epoch.
+    pub uncles_count: Uint64,
+    /// The uncle rate, which is `uncles_count` divided by `blocks_count`.
+    ///
+    /// The difficulty adjustment at the end of the epoch compares it with `orphan_rate_target`.
+    pub uncle_rate: core::RationalU256,
+    /// The expected orphan rate in the consensus.
+    pub orphan_rate_target: core::RationalU256,
+    /// The average interval between the blocks of the epoch, in milliseconds.
+    ///
+    /// It is computed from the timestamps of the last block of the epoch and the last block of
+    /// the previous epoch.
+    pub average_block_interval: Uint64,
+    /// The difficulty target for any block in this epoch.
+    pub compact_target: Uint32,
+    /// The difficulty decoded from `compact_target`.
+    pub difficulty: U256,
+}
+
 /// Block base rewards.
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
 pub struct BlockIssuance {
@@ -1146,6 +1272,57 @@ impl From<BlockEconomicState> for core::BlockEconomicState {
     }
 }
 
+/// The components of the miner reward.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MinerRewardComponent {
+    /// The primary base block reward, `MinerReward.primary`.
+    Primary,
+    /// The secondary base block reward, `MinerReward.secondary`.
+    Secondary,
+    /// The transaction fees for the committed transactions, `MinerReward.committed`.
+    Committed,
+    /// The transaction fees for the proposed transactions, `MinerReward.proposal`.
+    Proposal,
+}
+
+/// The projected miner reward of a block whose reward is not finalized yet.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::PendingBlockReward>(r#"
+/// {
+///   "block_hash": "0xa5f5c85987a15de25661e5a214f2c1449cd803f071acc7999820f25246471f40",
+///   "block_number": "0x5a",
+///   "estimated": [ "proposal" ],
+///   "finalized_at_number": "0x65",
+///   "miner_reward": {
+///     "committed": "0x0",
+///     "primary": "0x18ce922bca",
+///     "proposal": "0x0",
+///     "secondary": "0x17b93605"
+///   }
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PendingBlockReward {
+    /// The block hash.
+    pub block_hash: H256,
+    /// The block number.
+    pub block_number: BlockNumber,
+    /// The projected miner reward.
+    pub miner_reward: MinerReward,
+    /// The components of `miner_reward` which are estimates.
+    ///
+    /// The estimates may change until the reward is finalized, the other components are final
+    /// unless the block is rolled back.
+    pub estimated: Vec<MinerRewardComponent>,
+    /// The number of the block which creates the rewards as cells in its cellbase transaction.
+    pub finalized_at_number: BlockNumber,
+}
+
 /// Merkle proof for transactions in a block.
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
 pub struct TransactionProof {
@@ -1,4 +1,4 @@
-use crate::{CellOutput, JsonBytes};
+use crate::{Capacity, CellOutput, JsonBytes, Uint64};
 use ckb_types::{
     core::cell::{CellMeta, CellStatus},
     prelude::Unpack,
@@ -115,6 +115,58 @@ pub struct CellData {
     pub hash: H256,
 }
 
+/// A cell output along with the length of its data, the params of the capacity calculators.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::CellOutputWithDataLen>(r#"
+/// {
+///   "output": {
+///     "capacity": "0x2540be400",
+///     "lock": {
+///       "args": "0xc8328aabcd9b9e8e64fbc566c4385c3bdeb219d7",
+///       "code_hash": "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8",
+///       "hash_type": "type"
+///     },
+///     "type": null
+///   },
+///   "data_len": "0x0"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellOutputWithDataLen {
+    /// The cell output.
+    pub output: CellOutput,
+    /// The length of the cell data in bytes.
+    pub data_len: Uint64,
+}
+
+/// The occupied capacity of a cell output.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::OccupiedCapacity>(r#"
+/// {
+///   "is_lack_of_capacity": false,
+///   "occupied_capacity": "0x16b969d00"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OccupiedCapacity {
+    /// The minimal capacity the cell requires to store the output and the data, which is the
+    /// total size in bytes of the capacity field, the lock script, the optional type script and
+    /// the data, in CKBytes.
+    pub occupied_capacity: Capacity,
+    /// Whether the output capacity is less than the occupied capacity.
+    ///
+    /// The tx-pool rejects the transactions which have such outputs.
+    pub is_lack_of_capacity: bool,
+}
+
 impl From<CellMeta> for CellInfo {
     fn from(cell_meta: CellMeta) -> Self {
         let data = cell_meta.mem_cell_data;
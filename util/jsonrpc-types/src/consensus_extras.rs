@@ -0,0 +1,87 @@
+use crate::{Cycle, EpochNumber, ProposalWindow, ScriptHashType, Uint64};
+use serde::{Deserialize, Serialize};
+
+/// Where the value of a consensus limit comes from.
+///
+/// Allowed values: "default", "overridden" and "hardfork".
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitSource {
+    /// The default value of CKB.
+    Default,
+    /// The value is overridden in the chain spec.
+    Overridden,
+    /// The value depends on a hardfork feature and the current epoch.
+    Hardfork,
+}
+
+/// The provenance of a consensus limit.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct LimitProvenance {
+    /// The field name of the limit in [`ConsensusExtras`](#type-consensusextras).
+    pub limit: String,
+    /// Where the value of the limit comes from.
+    pub source: LimitSource,
+    /// The hardfork feature changing the limit, such as "RFC PR 0232".
+    ///
+    /// It is null unless the source is `hardfork`.
+    pub hardfork_feature: Option<String>,
+    /// The first epoch the hardfork feature is active.
+    ///
+    /// It is null when the source is not `hardfork` or the feature is never activated.
+    pub activation_epoch: Option<EpochNumber>,
+}
+
+/// The consensus limits active at the current epoch.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::ConsensusExtras>(r#"
+/// {
+///   "epoch": "0x1",
+///   "max_block_bytes": "0x91c08",
+///   "max_block_cycles": "0xd09dc300",
+///   "max_block_proposals_limit": "0x5dc",
+///   "max_uncles_num": "0x2",
+///   "provenances": [
+///     {
+///       "activation_epoch": null,
+///       "hardfork_feature": null,
+///       "limit": "max_block_cycles",
+///       "source": "default"
+///     },
+///     {
+///       "activation_epoch": "0x0",
+///       "hardfork_feature": "RFC PR 0232",
+///       "limit": "script_hash_types",
+///       "source": "hardfork"
+///     }
+///   ],
+///   "script_hash_types": ["data", "type", "data1"],
+///   "tx_proposal_window": {
+///     "closest": "0x2",
+///     "farthest": "0xa"
+///   }
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ConsensusExtras {
+    /// The epoch number of the tip block, at which the limits are active.
+    pub epoch: EpochNumber,
+    /// Maximum cycles that all the scripts in all the commit transactions can take.
+    pub max_block_cycles: Cycle,
+    /// Maximum number of bytes to use for the entire block.
+    pub max_block_bytes: Uint64,
+    /// The limit to the number of proposals per block.
+    pub max_block_proposals_limit: Uint64,
+    /// The maximum amount of uncles allowed for a block.
+    pub max_uncles_num: Uint64,
+    /// The two-step-transaction-confirmation proposal window.
+    pub tx_proposal_window: ProposalWindow,
+    /// The script hash types allowed in the transactions committed in the next block.
+    pub script_hash_types: Vec<ScriptHashType>,
+    /// The provenances of the limits above.
+    pub provenances: Vec<LimitProvenance>,
+}
@@ -1,4 +1,7 @@
+use crate::{BlockNumber, Uint64};
+use ckb_types::{H256, U256};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Runtime logger config for extra loggers.
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
@@ -54,4 +57,55 @@ pub struct MainLoggerConfig {
     ///
     /// **Optional**, null means keeping the current option unchanged.
     pub color: Option<bool>,
+    /// Rate limiting and sampling of the log records by target prefix, such as `ckb-sync`.
+    ///
+    /// The rate limits replace all the current ones, and apply to the extra loggers as well. Use
+    /// an empty object to remove all the rate limits.
+    ///
+    /// **Optional**, null means keeping the current option unchanged.
+    #[serde(default)]
+    pub rate_limits: Option<HashMap<String, LogRateLimitConfig>>,
+}
+
+/// Runtime rate limiting and sampling config of a log target.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct LogRateLimitConfig {
+    /// Outputs at most this number of records per second.
+    ///
+    /// **Optional**, null means no rate limit.
+    #[serde(default)]
+    pub max_per_second: Option<Uint64>,
+    /// Outputs only one in every `sample` records.
+    ///
+    /// **Optional**, null means outputting all the records.
+    #[serde(default)]
+    pub sample: Option<Uint64>,
+}
+
+/// The trace of a verification rule, returned by the RPC method `explain_transaction`.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct VerificationRuleTrace {
+    /// The rule name, which is the name of the verifier in CKB.
+    pub rule: String,
+    /// The values the rule evaluates, such as `{"size": "1024", "limit": "597000"}`.
+    pub inputs: HashMap<String, String>,
+    /// Whether the rule passed.
+    pub passed: bool,
+    /// The error when the rule is violated, or the result of the rule when it passed, which may
+    /// be empty.
+    pub outcome: String,
+}
+
+/// A reorg paused because it rolls back more blocks than `chain.max_reorg_depth`, returned by the
+/// RPC method `get_pending_reorg`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct PendingReorg {
+    /// The hash of the best block in the fork.
+    pub block_hash: H256,
+    /// The number of the best block in the fork.
+    pub block_number: BlockNumber,
+    /// The total difficulty of the best block in the fork.
+    pub total_difficulty: U256,
+    /// The number of the main chain blocks to roll back.
+    pub depth: Uint64,
 }
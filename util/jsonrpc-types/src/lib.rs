@@ -5,14 +5,18 @@ mod blockchain;
 mod bytes;
 mod cell;
 mod chain_info;
+mod consensus_extras;
 mod debug;
 mod experiment;
 mod fee_rate;
 mod fixed_bytes;
 mod net;
+mod partial_transaction;
 mod pool;
 mod primitive;
 mod proposal_short_id;
+mod rpc_version;
+pub mod schema;
 mod subscription;
 mod uints;
 
@@ -22,26 +26,35 @@ pub use self::block_template::{
 };
 pub use self::blockchain::{
     Block, BlockEconomicState, BlockIssuance, BlockView, CellDep, CellInput, CellOutput, Consensus,
-    DepType, EpochView, Header, HeaderView, MerkleProof, MinerReward, OutPoint, ProposalWindow,
-    Script, ScriptHashType, Status, Transaction, TransactionProof, TransactionView,
+    DepType, EpochPreview, EpochStats, EpochView, Header, HeaderView, InputCell, MerkleProof,
+    MinerReward, MinerRewardComponent, OutPoint, PendingBlockReward, ProposalWindow, Script,
+    ScriptHashType, Status, Transaction, TransactionAndCells, TransactionProof, TransactionView,
     TransactionWithStatus, TxStatus, UncleBlock, UncleBlockView,
 };
 pub use self::bytes::JsonBytes;
-pub use self::cell::{CellData, CellInfo, CellWithStatus};
+pub use self::cell::{CellData, CellInfo, CellOutputWithDataLen, CellWithStatus, OccupiedCapacity};
 pub use self::chain_info::ChainInfo;
-pub use self::debug::{ExtraLoggerConfig, MainLoggerConfig};
+pub use self::consensus_extras::{ConsensusExtras, LimitProvenance, LimitSource};
+pub use self::debug::{
+    ExtraLoggerConfig, LogRateLimitConfig, MainLoggerConfig, PendingReorg, VerificationRuleTrace,
+};
 pub use self::experiment::DryRunResult;
 pub use self::fee_rate::FeeRateDef;
 pub use self::fixed_bytes::Byte32;
 pub use self::net::{
-    BannedAddr, LocalNode, LocalNodeProtocol, NodeAddress, PeerSyncState, RemoteNode,
-    RemoteNodeProtocol, SyncState,
+    BannedAddr, BlockFetchPeer, LocalNode, LocalNodeProtocol, NetworkTime, NodeAddress,
+    PeerAuditEvent, PeerAuditEventType, PeerSyncState, PeerTimeOffset, RemoteNode,
+    RemoteNodeProtocol, SyncProgress, SyncState,
 };
+pub use self::partial_transaction::{PartialTransaction, ScriptGroupType, SigningGroup};
 pub use self::pool::{
-    OutputsValidator, PoolTransactionEntry, PoolTransactionReject, RawTxPool, TxPoolIds,
-    TxPoolInfo, TxPoolVerbosity, TxVerbosity,
+    BlockProposals, OutputsValidator, PoolEntriesOrder, PoolTransactionEntry,
+    PoolTransactionReject, PoolTxConflict, PoolTxConflicts, PoolTxDetail, PoolTxDetails,
+    ProposalWindowInfo, RawTxPool, StagedTransaction, TxPoolIds, TxPoolInfo, TxPoolVerbosity,
+    TxVerbosity,
 };
 pub use self::proposal_short_id::ProposalShortId;
+pub use self::rpc_version::{Deprecation, RpcVersion};
 pub use self::subscription::Topic;
 pub use self::uints::{Uint128, Uint32, Uint64};
 pub use primitive::{
@@ -104,34 +104,82 @@ pub struct LocalNodeProtocol {
 ///   "protocols": [
 ///     {
 ///       "id": "0x4",
+///       "last_received_at": null,
+///       "last_sent_at": null,
+///       "received_bytes": "0x0",
+///       "received_messages": "0x0",
+///       "sent_bytes": "0x0",
+///       "sent_messages": "0x0",
 ///       "version": "0.0.1"
 ///     },
 ///     {
 ///       "id": "0x2",
+///       "last_received_at": null,
+///       "last_sent_at": null,
+///       "received_bytes": "0x0",
+///       "received_messages": "0x0",
+///       "sent_bytes": "0x0",
+///       "sent_messages": "0x0",
 ///       "version": "0.0.1"
 ///     },
 ///     {
 ///       "id": "0x1",
+///       "last_received_at": null,
+///       "last_sent_at": null,
+///       "received_bytes": "0x0",
+///       "received_messages": "0x0",
+///       "sent_bytes": "0x0",
+///       "sent_messages": "0x0",
 ///       "version": "0.0.1"
 ///     },
 ///     {
 ///       "id": "0x64",
+///       "last_received_at": "0x17b9f4a0f80",
+///       "last_sent_at": "0x17b9f4a0e12",
+///       "received_bytes": "0x1d3f2",
+///       "received_messages": "0x4b0",
+///       "sent_bytes": "0x2a8f",
+///       "sent_messages": "0x5dc",
 ///       "version": "1"
 ///     },
 ///     {
 ///       "id": "0x6e",
+///       "last_received_at": null,
+///       "last_sent_at": null,
+///       "received_bytes": "0x0",
+///       "received_messages": "0x0",
+///       "sent_bytes": "0x0",
+///       "sent_messages": "0x0",
 ///       "version": "1"
 ///     },
 ///     {
 ///       "id": "0x66",
+///       "last_received_at": "0x17b9f49fc2c",
+///       "last_sent_at": null,
+///       "received_bytes": "0x10",
+///       "received_messages": "0x1",
+///       "sent_bytes": "0x0",
+///       "sent_messages": "0x0",
 ///       "version": "1"
 ///     },
 ///     {
 ///       "id": "0x65",
+///       "last_received_at": "0x17b9f4a0b5d",
+///       "last_sent_at": "0x17b9f4a0f01",
+///       "received_bytes": "0x3e81",
+///       "received_messages": "0x9c",
+///       "sent_bytes": "0xc5d2",
+///       "sent_messages": "0x1f4",
 ///       "version": "1"
 ///     },
 ///     {
 ///       "id": "0x0",
+///       "last_received_at": null,
+///       "last_sent_at": null,
+///       "received_bytes": "0x0",
+///       "received_messages": "0x0",
+///       "sent_bytes": "0x0",
+///       "sent_messages": "0x0",
 ///       "version": "0.0.1"
 ///     }
 ///   ],
@@ -139,6 +187,7 @@ pub struct LocalNodeProtocol {
 ///     "best_known_header_hash": null,
 ///     "best_known_header_number": null,
 ///     "can_fetch_count": "0x80",
+///     "headers_behind": null,
 ///     "inflight_count": "0xa",
 ///     "last_common_header_hash": null,
 ///     "last_common_header_number": null,
@@ -184,6 +233,24 @@ pub struct RemoteNodeProtocol {
     pub id: Uint64,
     /// Active protocol version.
     pub version: String,
+    /// Count of the messages received from the remote node in this protocol.
+    ///
+    /// Only the CKB protocols, which IDs are greater than or equal to 100, are counted.
+    pub received_messages: Uint64,
+    /// Total size in bytes of the messages received from the remote node in this protocol.
+    pub received_bytes: Uint64,
+    /// Count of the messages sent to the remote node in this protocol.
+    pub sent_messages: Uint64,
+    /// Total size in bytes of the messages sent to the remote node in this protocol.
+    pub sent_bytes: Uint64,
+    /// The time when the last message is received from the remote node in this protocol.
+    ///
+    /// Null means no messages have been received yet.
+    pub last_received_at: Option<Timestamp>,
+    /// The time when the last message is sent to the remote node in this protocol.
+    ///
+    /// Null means no messages have been sent yet.
+    pub last_sent_at: Option<Timestamp>,
 }
 
 /// The chain synchronization state between the local node and a remote node.
@@ -214,6 +281,11 @@ pub struct PeerSyncState {
     pub inflight_count: Uint64,
     /// The count of blocks are available for concurrency download.
     pub can_fetch_count: Uint64,
+    /// How many blocks the best known header of remote peer is behind the best header known by
+    /// the local node.
+    ///
+    /// Null means the best known header of remote peer is unknown.
+    pub headers_behind: Option<Uint64>,
 }
 
 /// Node P2P address and score.
@@ -283,4 +355,155 @@ pub struct SyncState {
     pub normal_time: Uint64,
     /// The download scheduler's time analysis data, the low is the 9/10 of the cut-off point, unit ms
     pub low_time: Uint64,
+    /// The peers chosen to download blocks from, in the order of preference.
+    ///
+    /// The peers are ranked by the expected block throughput, which prefers the peers with more
+    /// download tasks and lower latency. The latency is the ping RTT plus the average block
+    /// response time.
+    pub block_fetch_peers: Vec<BlockFetchPeer>,
+}
+
+/// The block download state of a peer in the chain synchronization.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct BlockFetchPeer {
+    /// The remote node ID which is derived from its P2P private key.
+    pub node_id: String,
+    /// The ping RTT of the remote node in milliseconds.
+    ///
+    /// Null means no ping responses have been received yet.
+    pub ping_rtt: Option<Uint64>,
+    /// The number of the blocks the remote node can download at the same time.
+    pub task_count: Uint64,
+    /// The number of the blocks downloading from the remote node.
+    pub inflight_blocks_count: Uint64,
+    /// The number of the blocks received from the remote node.
+    pub received_blocks_count: Uint64,
+    /// The moving average of the block response time in milliseconds.
+    ///
+    /// Null means no blocks have been received yet.
+    pub average_response_time: Option<Uint64>,
+}
+
+/// The Initial Block Download progress of the local node.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct SyncProgress {
+    /// The number of the best known header observed by the local node from the P2P network.
+    pub header_tip: BlockNumber,
+    /// The number of the tip block in the local chain, which has been downloaded and verified.
+    pub block_tip: BlockNumber,
+    /// The number of the highest block the local node has downloaded.
+    ///
+    /// The blocks between `block_tip` and `unverified_tip` are waiting for verification, they
+    /// are usually orphan blocks which ancestors are still downloading.
+    pub unverified_tip: BlockNumber,
+    /// Count of blocks downloaded in the last minute.
+    pub download_rate: Uint64,
+    /// Count of blocks verified in the last minute.
+    pub verify_rate: Uint64,
+    /// The estimated time to catch up `header_tip` at the current `verify_rate`, unit ms.
+    ///
+    /// It is null when no block has been verified in the last minute.
+    pub estimated_time_left: Option<Uint64>,
+}
+
+/// The local time adjusted by the time offsets from the network peers.
+///
+/// The offsets are measured by the Time protocol when connecting to the outbound peers. An offset
+/// is the local time minus the peer time in milliseconds, a positive offset means the local
+/// clock is ahead of the peer.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct NetworkTime {
+    /// The local time.
+    pub local_time: Timestamp,
+    /// The local time minus `offset`, which is the time agreed by the network peers.
+    ///
+    /// It is the same as `local_time` when `offset` is null.
+    pub adjusted_time: Timestamp,
+    /// The median of the recent time offset samples, unit ms.
+    ///
+    /// It is null when there are not enough samples.
+    pub offset: Option<i64>,
+    /// The node raises an alert when the absolute value of `offset` exceeds this threshold,
+    /// unit ms.
+    pub clock_skew_threshold: Uint64,
+    /// The last time offset sample of each connected peer.
+    pub peers: Vec<PeerTimeOffset>,
+}
+
+/// The time offset of a connected peer.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PeerTimeOffset {
+    /// The remote node ID which is derived from its P2P private key.
+    pub node_id: String,
+    /// The local time minus the peer time, unit ms.
+    pub offset: i64,
+}
+
+/// A peer connection lifecycle event pushed to the subscribers of the topic `peer_event`.
+///
+/// The fields not applicable to the event type are null.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::PeerAuditEvent>(r#"
+/// {
+///   "timestamp": "0x17c2d7a3c1e",
+///   "event": "banned",
+///   "session_id": null,
+///   "address": "/ip4/192.168.0.2/tcp/8112/p2p/QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS",
+///   "inbound": null,
+///   "protocol_id": null,
+///   "version": null,
+///   "reason": "ProtocolError id=100",
+///   "ban_duration": "0x493e0"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PeerAuditEvent {
+    /// The time when the event happened.
+    pub timestamp: Timestamp,
+    /// The event type.
+    pub event: PeerAuditEventType,
+    /// The session ID, which is only unique among the connected peers.
+    pub session_id: Option<Uint64>,
+    /// The remote address, or the banned IP network for the event `banned`.
+    pub address: Option<String>,
+    /// Whether the session is initiated by the remote peer.
+    pub inbound: Option<bool>,
+    /// The protocol ID.
+    pub protocol_id: Option<Uint64>,
+    /// The negotiated protocol version.
+    pub version: Option<String>,
+    /// The error or the reason of the event.
+    pub reason: Option<String>,
+    /// How long the ban lasts, unit ms.
+    pub ban_duration: Option<Uint64>,
+}
+
+/// The types of the peer connection lifecycle events.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerAuditEventType {
+    /// The node starts dialing the address.
+    Dial,
+    /// Dialing the address failed before the handshake.
+    DialFailed,
+    /// The secure handshake or the protocol negotiation with the remote peer failed.
+    HandshakeFailed,
+    /// A session is opened.
+    SessionOpened,
+    /// The opened session is not accepted, for example, because of the connection limits.
+    SessionRejected,
+    /// A session is closed.
+    SessionClosed,
+    /// A protocol is opened in the session.
+    ProtocolOpened,
+    /// A protocol is closed in the session.
+    ProtocolClosed,
+    /// The connected peer is evicted to make room for the others.
+    Evicted,
+    /// An address or an IP network is banned.
+    Banned,
 }
@@ -0,0 +1,205 @@
+use crate::{CellOutput, JsonBytes, Script, Transaction, Uint32};
+use ckb_error::OtherError;
+use ckb_types::{packed, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+
+/// The script type of a signing group.
+///
+/// Allowed values: "lock" and "type".
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptGroupType {
+    /// The group of the inputs sharing the same lock script.
+    Lock = 0,
+    /// The group of the inputs and outputs sharing the same type script.
+    Type = 1,
+}
+
+impl Default for ScriptGroupType {
+    fn default() -> Self {
+        ScriptGroupType::Lock
+    }
+}
+
+impl From<ScriptGroupType> for packed::Byte {
+    fn from(json: ScriptGroupType) -> Self {
+        (json as u8).into()
+    }
+}
+
+impl TryFrom<packed::Byte> for ScriptGroupType {
+    type Error = OtherError;
+
+    fn try_from(input: packed::Byte) -> Result<Self, Self::Error> {
+        match Into::<u8>::into(input) {
+            0 => Ok(ScriptGroupType::Lock),
+            1 => Ok(ScriptGroupType::Type),
+            v => Err(OtherError::new(format!("Invalid script group type {}", v))),
+        }
+    }
+}
+
+/// The inputs and outputs verified by the same script, which are signed together.
+///
+/// It is the same as the script group in the script verification.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct SigningGroup {
+    /// The script of the group.
+    pub script: Script,
+    /// Whether the script is the lock script or the type script of the cells.
+    pub script_type: ScriptGroupType,
+    /// The indices of the inputs in the group.
+    pub input_indices: Vec<Uint32>,
+    /// The indices of the outputs in the group, always empty for the lock script group.
+    pub output_indices: Vec<Uint32>,
+    /// The signer specific hint, such as the multisig config or the derivation path.
+    ///
+    /// The node keeps it as is.
+    pub hint: JsonBytes,
+}
+
+impl TryFrom<packed::SigningGroup> for SigningGroup {
+    type Error = OtherError;
+
+    fn try_from(input: packed::SigningGroup) -> Result<Self, Self::Error> {
+        let input_indices: Vec<u32> = input.input_indices().unpack();
+        let output_indices: Vec<u32> = input.output_indices().unpack();
+        Ok(SigningGroup {
+            script: input.script().into(),
+            script_type: input.script_type().try_into()?,
+            input_indices: input_indices.into_iter().map(Into::into).collect(),
+            output_indices: output_indices.into_iter().map(Into::into).collect(),
+            hint: input.hint().into(),
+        })
+    }
+}
+
+impl From<SigningGroup> for packed::SigningGroup {
+    fn from(json: SigningGroup) -> Self {
+        let SigningGroup {
+            script,
+            script_type,
+            input_indices,
+            output_indices,
+            hint,
+        } = json;
+        let input_indices: Vec<u32> = input_indices.into_iter().map(Into::into).collect();
+        let output_indices: Vec<u32> = output_indices.into_iter().map(Into::into).collect();
+        packed::SigningGroup::new_builder()
+            .script(script.into())
+            .script_type(script_type.into())
+            .input_indices(input_indices.pack())
+            .output_indices(output_indices.pack())
+            .hint(hint.into())
+            .build()
+    }
+}
+
+/// The partially signed CKB transaction (PSCT).
+///
+/// It is the container passed among the parties of the multi-party signing, such as the wallets,
+/// the hardware signers and the multisig co-signers. The parties can sign their groups without
+/// querying a node, because it carries the input cells and the signing groups.
+///
+/// The binary format is the molecule table `PartialTransaction`.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PartialTransaction {
+    /// The transaction, in which the witnesses may be placeholders or absent before signing.
+    pub transaction: Transaction,
+    /// The cells consumed by the inputs, in the same order as the inputs.
+    ///
+    /// It is either empty or has the same length as the inputs.
+    pub input_cells: Vec<CellOutput>,
+    /// The data of the cells consumed by the inputs, in the same order as the inputs.
+    pub input_cells_data: Vec<JsonBytes>,
+    /// The script groups to sign.
+    pub signing_groups: Vec<SigningGroup>,
+}
+
+impl TryFrom<packed::PartialTransaction> for PartialTransaction {
+    type Error = OtherError;
+
+    fn try_from(input: packed::PartialTransaction) -> Result<Self, Self::Error> {
+        Ok(PartialTransaction {
+            transaction: input.transaction().into(),
+            input_cells: input.input_cells().into_iter().map(Into::into).collect(),
+            input_cells_data: input
+                .input_cells_data()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            signing_groups: input
+                .signing_groups()
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<PartialTransaction> for packed::PartialTransaction {
+    fn from(json: PartialTransaction) -> Self {
+        let PartialTransaction {
+            transaction,
+            input_cells,
+            input_cells_data,
+            signing_groups,
+        } = json;
+        packed::PartialTransaction::new_builder()
+            .transaction(transaction.into())
+            .input_cells(input_cells.into_iter().map(Into::into).pack())
+            .input_cells_data(input_cells_data.into_iter().map(Into::into).pack())
+            .signing_groups(signing_groups.into_iter().map(Into::into).pack())
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_types::{bytes::Bytes, core, h256};
+
+    #[test]
+    fn test_partial_transaction_packed_roundtrip() {
+        let lock = packed::Script::new_builder()
+            .code_hash(h256!("0x1").pack())
+            .hash_type(core::ScriptHashType::Type.into())
+            .build();
+        let cell = packed::CellOutput::new_builder()
+            .capacity(core::Capacity::bytes(100).unwrap().pack())
+            .lock(lock.clone())
+            .build();
+        let json = PartialTransaction {
+            transaction: packed::Transaction::default().into(),
+            input_cells: vec![cell.into()],
+            input_cells_data: vec![JsonBytes::from_bytes(Bytes::from(vec![1, 2]))],
+            signing_groups: vec![SigningGroup {
+                script: lock.into(),
+                script_type: ScriptGroupType::Type,
+                input_indices: vec![0u32.into()],
+                output_indices: vec![1u32.into(), 2u32.into()],
+                hint: JsonBytes::from_bytes(Bytes::from(vec![3])),
+            }],
+        };
+
+        let packed: packed::PartialTransaction = json.clone().into();
+        let decoded = packed::PartialTransaction::from_slice(packed.as_slice())
+            .expect("verify the packed partial transaction");
+        assert_eq!(PartialTransaction::try_from(decoded).unwrap(), json);
+    }
+
+    #[test]
+    fn test_partial_transaction_unknown_script_group_type() {
+        let group = packed::SigningGroup::new_builder()
+            .script_type(2u8.into())
+            .build();
+        let packed = packed::PartialTransaction::new_builder()
+            .signing_groups(vec![group].pack())
+            .build();
+        let decoded = packed::PartialTransaction::from_slice(packed.as_slice())
+            .expect("verify the packed partial transaction");
+        let err = PartialTransaction::try_from(decoded).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid script group type 2");
+    }
+}
@@ -1,6 +1,13 @@
-use crate::{BlockNumber, Capacity, Cycle, Timestamp, TransactionView, Uint64};
+use crate::{
+    BlockNumber, Capacity, Cycle, OutPoint, ProposalShortId, Status, Timestamp, TransactionView,
+    Uint32, Uint64,
+};
 use ckb_types::core::service::PoolTransactionEntry as CorePoolTransactionEntry;
-use ckb_types::core::tx_pool::{Reject, TxEntryInfo, TxPoolEntryInfo, TxPoolIds as CoreTxPoolIds};
+use ckb_types::core::tx_pool::{
+    PoolEntriesOrder as CorePoolEntriesOrder, Reject, StagedTxInfo, TxConflict, TxEntryInfo,
+    TxPoolConflicts, TxPoolEntryDetail, TxPoolEntryDetails, TxPoolEntryInfo,
+    TxPoolIds as CoreTxPoolIds, TxStatus as CoreTxStatus,
+};
 use ckb_types::prelude::Unpack;
 use ckb_types::H256;
 use serde::{Deserialize, Serialize};
@@ -37,6 +44,9 @@ pub struct TxPoolInfo {
     /// Fee rate threshold. The pool rejects transactions which fee rate is below this threshold.
     ///
     /// The unit is Shannons per 1000 bytes transaction serialization size in the block.
+    ///
+    /// It is the config option `tx_pool.min_fee_rate`, and rises with the pool usage when
+    /// `tx_pool.dynamic_min_fee_rate` is configured.
     pub min_fee_rate: Uint64,
     /// Last updated time. This is the Unix timestamp in milliseconds.
     pub last_txs_updated_at: Timestamp,
@@ -159,6 +169,270 @@ impl From<TxPoolEntryInfo> for TxPoolVerbosity {
     }
 }
 
+/// An in-pool transaction which spends the same inputs as another transaction, or spends the
+/// cell deps of it, or uses its inputs as cell deps.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PoolTxConflict {
+    /// The conflicting transaction hash.
+    pub tx_hash: H256,
+    /// The conflicting transaction status, allowed values: "pending" and "proposed".
+    pub status: Status,
+    /// The conflicting transaction fee.
+    pub fee: Capacity,
+    /// The conflicting transaction fee rate.
+    ///
+    /// The unit is Shannons per 1000 bytes transaction serialization size in the block.
+    pub fee_rate: Uint64,
+    /// The inputs spent by both transactions.
+    pub inputs: Vec<OutPoint>,
+    /// The cells spent by one transaction and used as cell deps by the other.
+    pub deps: Vec<OutPoint>,
+}
+
+impl From<TxConflict> for PoolTxConflict {
+    fn from(conflict: TxConflict) -> Self {
+        PoolTxConflict {
+            tx_hash: conflict.tx_hash.unpack(),
+            status: conflict.status.into(),
+            fee: conflict.fee.into(),
+            fee_rate: conflict.fee_rate.as_u64().into(),
+            inputs: conflict.inputs.into_iter().map(Into::into).collect(),
+            deps: conflict.deps.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The in-pool transactions conflicting with an in-pool transaction.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PoolTxConflicts {
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// The transaction status, allowed values: "pending" and "proposed".
+    pub status: Status,
+    /// The transaction fee rate.
+    ///
+    /// The unit is Shannons per 1000 bytes transaction serialization size in the block.
+    pub fee_rate: Uint64,
+    /// The in-pool transactions spending the same inputs as this transaction, or spending its
+    /// cell deps, or using its inputs as cell deps.
+    ///
+    /// A transaction spending a cell can not be committed before the transactions using the
+    /// cell as a cell dep, and only one of the transactions spending the same input can be
+    /// committed. The transaction pool does not replace a transaction by another one paying a
+    /// higher fee.
+    pub conflicts: Vec<PoolTxConflict>,
+}
+
+impl From<TxPoolConflicts> for PoolTxConflicts {
+    fn from(conflicts: TxPoolConflicts) -> Self {
+        PoolTxConflicts {
+            tx_hash: conflicts.tx_hash.unpack(),
+            status: conflicts.status.into(),
+            fee_rate: conflicts.fee_rate.as_u64().into(),
+            conflicts: conflicts.conflicts.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<CoreTxStatus> for Status {
+    fn from(status: CoreTxStatus) -> Self {
+        match status {
+            CoreTxStatus::Pending => Status::Pending,
+            CoreTxStatus::Proposed => Status::Proposed,
+        }
+    }
+}
+
+/// The order of the in-pool transactions returned by `get_pool_entries`.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolEntriesOrder {
+    /// "fee_rate": The highest effective fee rate first.
+    FeeRate,
+    /// "timestamp": The oldest transaction first.
+    Timestamp,
+}
+
+impl Default for PoolEntriesOrder {
+    fn default() -> Self {
+        PoolEntriesOrder::FeeRate
+    }
+}
+
+impl From<PoolEntriesOrder> for CorePoolEntriesOrder {
+    fn from(order: PoolEntriesOrder) -> Self {
+        match order {
+            PoolEntriesOrder::FeeRate => CorePoolEntriesOrder::FeeRate,
+            PoolEntriesOrder::Timestamp => CorePoolEntriesOrder::Timestamp,
+        }
+    }
+}
+
+/// The detailed information of an in-pool transaction.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PoolTxDetail {
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// The transaction status, allowed values: "pending" and "proposed".
+    pub status: Status,
+    /// Consumed cycles.
+    pub cycles: Cycle,
+    /// The transaction serialized size in block.
+    pub size: Uint64,
+    /// The transaction fee.
+    pub fee: Capacity,
+    /// The transaction fee rate.
+    ///
+    /// The unit is Shannons per 1000 bytes transaction serialization size in the block.
+    pub fee_rate: Uint64,
+    /// The lower one of the transaction fee rate and the fee rate of the package which consists
+    /// of the transaction and its in-pool ancestors.
+    ///
+    /// A transaction cannot be committed before its ancestors, so the block assembler ranks the
+    /// proposed transactions by this fee rate. The pool only links the proposed transactions, so
+    /// this is the transaction fee rate for the pending ones.
+    pub effective_fee_rate: Uint64,
+    /// Count of the in-pool ancestor transactions, excluding the transaction itself.
+    pub ancestors_count: Uint64,
+    /// Total size of the in-pool ancestor transactions.
+    pub ancestors_size: Uint64,
+    /// Total cycles of the in-pool ancestor transactions.
+    pub ancestors_cycles: Cycle,
+    /// Total fee of the in-pool ancestor transactions.
+    pub ancestors_fee: Capacity,
+    /// Count of the in-pool descendant transactions, excluding the transaction itself.
+    pub descendants_count: Uint64,
+    /// Total size of the in-pool descendant transactions.
+    pub descendants_size: Uint64,
+    /// Total cycles of the in-pool descendant transactions.
+    pub descendants_cycles: Cycle,
+    /// Total fee of the in-pool descendant transactions.
+    pub descendants_fee: Capacity,
+    /// The time when the transaction entered its current state.
+    ///
+    /// This is the Unix timestamp in milliseconds. A transaction is added to the pool again
+    /// when it is proposed or when a chain reorganization detaches it.
+    pub timestamp: Timestamp,
+    /// The estimated risk in percent, from 0 to 100, that the transaction leaves the pool
+    /// without being committed.
+    ///
+    /// The transaction pool does not evict transactions by the fee rate, it rejects the new
+    /// transactions when it is full. This score is the share of the pool size ranking ahead of
+    /// the transaction by the effective fee rate, so the transactions near 100 wait longest for
+    /// the block space. It is 100 when the transaction fee rate is below the current
+    /// `min_fee_rate` of the pool, because the transaction is rejected when it is added to the
+    /// pool again after a chain reorganization.
+    pub eviction_score: Uint32,
+}
+
+impl From<TxPoolEntryDetail> for PoolTxDetail {
+    fn from(detail: TxPoolEntryDetail) -> Self {
+        PoolTxDetail {
+            tx_hash: detail.tx_hash.unpack(),
+            status: detail.status.into(),
+            cycles: detail.cycles.into(),
+            size: detail.size.into(),
+            fee: detail.fee.into(),
+            fee_rate: detail.fee_rate.as_u64().into(),
+            effective_fee_rate: detail.effective_fee_rate.as_u64().into(),
+            ancestors_count: detail.ancestors_count.into(),
+            ancestors_size: detail.ancestors_size.into(),
+            ancestors_cycles: detail.ancestors_cycles.into(),
+            ancestors_fee: detail.ancestors_fee.into(),
+            descendants_count: detail.descendants_count.into(),
+            descendants_size: detail.descendants_size.into(),
+            descendants_cycles: detail.descendants_cycles.into(),
+            descendants_fee: detail.descendants_fee.into(),
+            timestamp: detail.timestamp.into(),
+            eviction_score: u32::from(detail.eviction_score).into(),
+        }
+    }
+}
+
+/// A page of the in-pool transactions returned by `get_pool_entries`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PoolTxDetails {
+    /// The transactions in this page.
+    pub entries: Vec<PoolTxDetail>,
+    /// Count of the transactions in the pool.
+    pub total: Uint64,
+    /// Pass it as the `cursor` to get the next page, null when this is the last page.
+    pub next_cursor: Option<H256>,
+}
+
+impl From<TxPoolEntryDetails> for PoolTxDetails {
+    fn from(details: TxPoolEntryDetails) -> Self {
+        PoolTxDetails {
+            entries: details.entries.into_iter().map(Into::into).collect(),
+            total: details.total.into(),
+            next_cursor: details.next_cursor.map(|cursor| cursor.unpack()),
+        }
+    }
+}
+
+/// A transaction sent by `send_transaction` which is staged until its inputs `since` are
+/// satisfied.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct StagedTransaction {
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// The index of the input whose `since` was not satisfied when the transaction was submitted
+    /// last time.
+    pub immature_input: Uint32,
+    /// The `since` field of the immature input.
+    pub since: Uint64,
+    /// When the transaction was staged.
+    pub staged_at: Timestamp,
+    /// When the transaction is dropped if its inputs `since` are still not satisfied.
+    pub expires_at: Timestamp,
+}
+
+impl From<StagedTxInfo> for StagedTransaction {
+    fn from(info: StagedTxInfo) -> Self {
+        StagedTransaction {
+            tx_hash: info.tx_hash.unpack(),
+            immature_input: (info.immature_input as u32).into(),
+            since: info.since.into(),
+            staged_at: info.staged_at.into(),
+            expires_at: info.expires_at.into(),
+        }
+    }
+}
+
+/// The blocks whose proposals can still be committed by the next block or the later ones.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct ProposalWindowInfo {
+    /// The associated chain tip block hash.
+    pub tip_hash: H256,
+    /// The associated chain tip block number.
+    pub tip_number: BlockNumber,
+    /// The closest distance between the proposal and the commitment of a transaction.
+    pub closest: BlockNumber,
+    /// The farthest distance between the proposal and the commitment of a transaction.
+    pub farthest: BlockNumber,
+    /// The blocks in the window, ordered by the block number.
+    pub blocks: Vec<BlockProposals>,
+}
+
+/// The proposals of a block in the proposal window which are not committed yet.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct BlockProposals {
+    /// The block number.
+    pub number: BlockNumber,
+    /// The block hash.
+    pub hash: H256,
+    /// Whether the next block can commit the proposals of this block.
+    ///
+    /// The proposals of the blocks which are too close to the tip are committable later.
+    pub committable: bool,
+    /// The proposal ids of the block and its uncles which are not committed yet, without
+    /// duplicates.
+    pub proposals: Vec<ProposalShortId>,
+    /// The ids in `proposals` whose transactions are not in the tx-pool, so the miner can not
+    /// commit them.
+    pub missing: Vec<ProposalShortId>,
+}
+
 /// All transactions in tx-pool.
 ///
 /// `RawTxPool` is equivalent to [`TxPoolIds`][] `|` [`TxPoolVerbosity`][].
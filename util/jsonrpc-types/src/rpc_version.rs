@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// The version of the RPC API, and the deprecated methods and fields in it.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct RpcVersion {
+    /// The version of the RPC API, which is the same as the CKB version, such as "0.100.0".
+    pub version: String,
+    /// Whether the node serves the deprecated methods and the removed fields.
+    ///
+    /// It is set by the config option `rpc.enable_deprecated_rpc`.
+    pub deprecated_rpc_enabled: bool,
+    /// The deprecated methods and the removed fields.
+    pub deprecations: Vec<Deprecation>,
+}
+
+/// A deprecated RPC method, or a field removed from the result of an RPC method.
+///
+/// A deprecated method or a removed field is only served when the config option
+/// `rpc.enable_deprecated_rpc` is set, and it is deleted after one release cycle. A deprecated
+/// field without replacement is still served until it is deleted.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct Deprecation {
+    /// The RPC method name.
+    pub method: String,
+    /// The deprecated or removed field in the method result.
+    ///
+    /// The field of a nested object is named by the dotted path, such as
+    /// "sync_state.unknown_header_list_size". It is null when the whole method is deprecated.
+    pub field: Option<String>,
+    /// The version in which the method or the field is deprecated, such as "0.100.0".
+    pub since: String,
+    /// The method or the field to use instead.
+    pub replacement: Option<String>,
+}
@@ -0,0 +1,326 @@
+//! The canonical JSON mappings of the molecule entities.
+//!
+//! The RPC and the P2P protocols share the blockchain entities, which are serialized in molecule
+//! on the wire and in JSON in the RPC. The mappings here let the third-party implementations
+//! check that their serializations are compatible, by comparing the molecule bytes of the JSON
+//! examples, or by round-tripping their own data through [`json_to_molecule`] and
+//! [`molecule_to_json`].
+//!
+//! The molecule schemas are in [`ckb_types::schemas`].
+use crate::JsonBytes;
+use ckb_types::{
+    bytes::Bytes,
+    core::{self, capacity_bytes, Capacity, DepType, EpochNumberWithFraction, ScriptHashType},
+    error::VerificationError,
+    h160, h256, packed,
+    prelude::*,
+    H160, H256,
+};
+use serde::Serialize;
+use std::{convert::TryFrom, fmt};
+
+/// The JSON mapping of a molecule entity.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct EntityMapping {
+    /// The molecule type name in the schemas.
+    pub molecule: &'static str,
+    /// The JSON type name in the RPC.
+    pub json: &'static str,
+    /// The example in JSON.
+    pub json_example: serde_json::Value,
+    /// The molecule serialization of the example.
+    pub molecule_example: JsonBytes,
+}
+
+/// The errors of converting between JSON and molecule.
+#[derive(Debug)]
+pub enum RoundTripError {
+    /// The molecule type has no JSON mapping.
+    UnknownEntity(String),
+    /// The JSON is not a valid value of the JSON type.
+    Json(serde_json::Error),
+    /// The bytes are not a valid molecule serialization of the type.
+    Molecule(VerificationError),
+    /// The molecule entity holds a value which has no JSON representation.
+    Value(String),
+}
+
+impl fmt::Display for RoundTripError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoundTripError::UnknownEntity(name) => write!(f, "{} has no JSON mapping", name),
+            RoundTripError::Json(err) => write!(f, "invalid JSON: {}", err),
+            RoundTripError::Molecule(err) => write!(f, "invalid molecule: {}", err),
+            RoundTripError::Value(err) => write!(f, "invalid value: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RoundTripError {}
+
+macro_rules! define_mappings {
+    ($($molecule:ident => $json:ident, $sample:expr;)*) => {
+        /// Returns the JSON mappings of the entities, the examples are generated from the packed
+        /// types.
+        pub fn entity_mappings() -> Vec<EntityMapping> {
+            vec![$({
+                let sample: packed::$molecule = $sample;
+                EntityMapping {
+                    molecule: stringify!($molecule),
+                    json: stringify!($json),
+                    json_example: serde_json::to_value(crate::$json::from(sample.clone()))
+                        .expect("JSON types are serializable"),
+                    molecule_example: JsonBytes::from_bytes(sample.as_bytes()),
+                }
+            }),*]
+        }
+
+        /// Converts the JSON value to the molecule serialization of the type `molecule`.
+        pub fn json_to_molecule(
+            molecule: &str,
+            json: serde_json::Value,
+        ) -> Result<Bytes, RoundTripError> {
+            match molecule {
+                $(stringify!($molecule) => {
+                    let json: crate::$json =
+                        serde_json::from_value(json).map_err(RoundTripError::Json)?;
+                    Ok(packed::$molecule::from(json).as_bytes())
+                })*
+                _ => Err(RoundTripError::UnknownEntity(molecule.to_owned())),
+            }
+        }
+
+        /// Converts the molecule serialization of the type `molecule` to the JSON value.
+        ///
+        /// The bytes must be in the canonical form, the tables must not have the fields unknown
+        /// to the schemas.
+        pub fn molecule_to_json(
+            molecule: &str,
+            data: &[u8],
+        ) -> Result<serde_json::Value, RoundTripError> {
+            match molecule {
+                $(stringify!($molecule) => {
+                    let entity =
+                        packed::$molecule::from_slice(data).map_err(RoundTripError::Molecule)?;
+                    let json = crate::$json::try_from(entity)
+                        .map_err(|err| RoundTripError::Value(err.to_string()))?;
+                    serde_json::to_value(json).map_err(RoundTripError::Json)
+                })*
+                _ => Err(RoundTripError::UnknownEntity(molecule.to_owned())),
+            }
+        }
+    };
+}
+
+define_mappings! {
+    Script => Script, sample_lock_script();
+    OutPoint => OutPoint, sample_out_point();
+    CellInput => CellInput, sample_cell_input();
+    CellOutput => CellOutput, sample_cell_output();
+    CellDep => CellDep, sample_cell_dep();
+    Transaction => Transaction, sample_transaction().data();
+    Header => Header, sample_block().header().data();
+    UncleBlock => UncleBlock, sample_uncle().as_uncle().data();
+    Block => Block, sample_block().data();
+    ProposalShortId => ProposalShortId, sample_proposal_id();
+    Alert => Alert, sample_alert();
+    PartialTransaction => PartialTransaction, sample_partial_transaction();
+}
+
+fn sample_lock_script() -> packed::Script {
+    let args = h160!("0xc8328aabcd9b9e8e64fbc566c4385c3bdeb219d7");
+    packed::Script::new_builder()
+        .code_hash(
+            h256!("0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8").pack(),
+        )
+        .hash_type(ScriptHashType::Type.into())
+        .args(Bytes::from(args.as_bytes().to_vec()).pack())
+        .build()
+}
+
+fn sample_type_script() -> packed::Script {
+    packed::Script::new_builder()
+        .code_hash(
+            h256!("0x82d76d1b75fe2fd9a27dfbaa65a039221a380d76c926f378d3f81cf3e7e13f2e").pack(),
+        )
+        .hash_type(ScriptHashType::Data.into())
+        .build()
+}
+
+fn sample_out_point() -> packed::OutPoint {
+    packed::OutPoint::new(
+        h256!("0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3").pack(),
+        1,
+    )
+}
+
+fn sample_cell_input() -> packed::CellInput {
+    // The since is an absolute epoch number.
+    packed::CellInput::new(sample_out_point(), 0x2000_0000_0000_0010)
+}
+
+fn sample_cell_output() -> packed::CellOutput {
+    packed::CellOutput::new_builder()
+        .capacity(capacity_bytes!(200).pack())
+        .lock(sample_lock_script())
+        .type_(Some(sample_type_script()).pack())
+        .build()
+}
+
+fn sample_cell_dep() -> packed::CellDep {
+    packed::CellDep::new_builder()
+        .out_point(packed::OutPoint::new(
+            h256!("0x71a7ba8fc96349fea0ed3a5c47992e3b4084b031a42264a018e0072e8172e46c").pack(),
+            0,
+        ))
+        .dep_type(DepType::DepGroup.into())
+        .build()
+}
+
+fn sample_proposal_id() -> packed::ProposalShortId {
+    packed::ProposalShortId::new([0xa0, 0xef, 0x4e, 0xb5, 0xf4, 0xce, 0xea, 0xa2, 0x1a, 0x98])
+}
+
+fn sample_transaction() -> core::TransactionView {
+    core::TransactionBuilder::default()
+        .cell_dep(sample_cell_dep())
+        .header_dep(
+            h256!("0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed").pack(),
+        )
+        .input(sample_cell_input())
+        .output(sample_cell_output())
+        .output_data(Bytes::from_static(b"\x01\x02\x03").pack())
+        .witness(Bytes::from(vec![0x55; 16]).pack())
+        .build()
+}
+
+fn sample_uncle() -> core::BlockView {
+    core::BlockBuilder::default()
+        .parent_hash(
+            h256!("0x8b9c01d6cb4e3c1d0a7e53a0a2d1e1b4b5dd9a0a4e1c0b3a0a3f2f6c8e5b0d1f").pack(),
+        )
+        .timestamp(1_600_000_000_000u64.pack())
+        .number(99u64.pack())
+        .compact_target(0x1e08_3126u32.pack())
+        .epoch(
+            EpochNumberWithFraction::new(1, 99, 1000)
+                .full_value()
+                .pack(),
+        )
+        .nonce(0x2u128.pack())
+        .proposal(sample_proposal_id())
+        .build()
+}
+
+fn sample_block() -> core::BlockView {
+    let cellbase = core::TransactionBuilder::default()
+        .input(packed::CellInput::new_cellbase_input(100))
+        .output(
+            packed::CellOutput::new_builder()
+                .capacity(capacity_bytes!(1000).pack())
+                .lock(sample_lock_script())
+                .build(),
+        )
+        .output_data(Default::default())
+        .witness(Default::default())
+        .build();
+    core::BlockBuilder::default()
+        .parent_hash(
+            h256!("0xa5f5c85987a15de25661e5a214f2c1449cd803f071acc7999820f25246471f40").pack(),
+        )
+        .timestamp(1_600_000_008_000u64.pack())
+        .number(100u64.pack())
+        .compact_target(0x1e08_3126u32.pack())
+        .epoch(
+            EpochNumberWithFraction::new(1, 100, 1000)
+                .full_value()
+                .pack(),
+        )
+        .dao(h256!("0xb5a3e047474401001bc476b9ee573000c0c387962a38000000febffacf030000").pack())
+        .nonce(0x1u128.pack())
+        .uncle(sample_uncle().as_uncle())
+        .transaction(cellbase)
+        .transaction(sample_transaction())
+        .proposal(sample_proposal_id())
+        .build()
+}
+
+fn sample_alert() -> packed::Alert {
+    let raw = packed::RawAlert::new_builder()
+        .id(1u32.pack())
+        .cancel(0u32.pack())
+        .priority(1u32.pack())
+        .notice_until(1_600_000_000_000u64.pack())
+        .message("An example alert message!".pack())
+        .min_version(Some("0.1.0").pack())
+        .max_version(Option::<&str>::None.pack())
+        .build();
+    packed::Alert::new_builder()
+        .raw(raw)
+        .signatures(vec![Bytes::from(vec![0x11; 65]), Bytes::from(vec![0x22; 65])].pack())
+        .build()
+}
+
+fn sample_partial_transaction() -> packed::PartialTransaction {
+    let signing_group = packed::SigningGroup::new_builder()
+        .script(sample_lock_script())
+        .script_type(0u8.into())
+        .input_indices(vec![0u32].pack())
+        .output_indices(Vec::<u32>::new().pack())
+        .hint(Bytes::from_static(b"hint").pack())
+        .build();
+    packed::PartialTransaction::new_builder()
+        .transaction(sample_transaction().data())
+        .input_cells(vec![sample_cell_output()].pack())
+        .input_cells_data(vec![Bytes::new()].pack())
+        .signing_groups(vec![signing_group].pack())
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for mapping in entity_mappings() {
+            let molecule = json_to_molecule(mapping.molecule, mapping.json_example.clone())
+                .unwrap_or_else(|err| panic!("{}: {}", mapping.molecule, err));
+            assert_eq!(
+                molecule,
+                mapping.molecule_example.clone().into_bytes(),
+                "{}",
+                mapping.molecule
+            );
+            let json = molecule_to_json(mapping.molecule, molecule.as_ref())
+                .unwrap_or_else(|err| panic!("{}: {}", mapping.molecule, err));
+            assert_eq!(json, mapping.json_example, "{}", mapping.molecule);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_errors() {
+        assert!(matches!(
+            json_to_molecule("Uint64", serde_json::json!("0x1")),
+            Err(RoundTripError::UnknownEntity(_))
+        ));
+        assert!(matches!(
+            json_to_molecule("Script", serde_json::json!({ "code_hash": "0x" })),
+            Err(RoundTripError::Json(_))
+        ));
+        assert!(matches!(
+            molecule_to_json("OutPoint", &[0u8; 35]),
+            Err(RoundTripError::Molecule(_))
+        ));
+        let group = packed::SigningGroup::new_builder()
+            .script_type(2u8.into())
+            .build();
+        let tx = packed::PartialTransaction::new_builder()
+            .signing_groups(vec![group].pack())
+            .build();
+        assert!(matches!(
+            molecule_to_json("PartialTransaction", tx.as_slice()),
+            Err(RoundTripError::Value(_))
+        ));
+    }
+}
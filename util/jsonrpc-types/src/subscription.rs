@@ -10,8 +10,14 @@ pub enum Topic {
     NewTipBlock,
     /// Subscribe new transactions which are submitted to the pool.
     NewTransaction,
+    /// Subscribe in-pool transactions which proposed on chain but not yet committable.
+    GapTransaction,
     /// Subscribe in-pool transactions which proposed on chain.
     ProposedTransaction,
+    /// Subscribe in-pool transactions which committed on chain.
+    CommittedTransaction,
     /// Subscribe transactions which are abandoned by tx-pool.
     RejectedTransaction,
+    /// Subscribe the peer connection lifecycle events.
+    PeerEvent,
 }
@@ -0,0 +1,112 @@
+//! The startup configuration self-check of `ckb run --check`.
+use std::fmt;
+
+/// The status of a check item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The node can start with it.
+    Pass,
+    /// The node can start with it, but some features are disabled or the data will be changed.
+    Warn,
+    /// The node fails to start with it.
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "PASS"),
+            CheckStatus::Warn => write!(f, "WARN"),
+            CheckStatus::Fail => write!(f, "FAIL"),
+        }
+    }
+}
+
+/// The result of a startup validation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckItem {
+    /// The name of the validation, such as "chain spec".
+    pub name: &'static str,
+    /// The result.
+    pub status: CheckStatus,
+    /// The details of the result.
+    pub message: String,
+}
+
+/// The report of the startup validations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CheckReport {
+    items: Vec<CheckItem>,
+}
+
+impl CheckReport {
+    pub(crate) fn add<S: Into<String>>(
+        &mut self,
+        name: &'static str,
+        status: CheckStatus,
+        message: S,
+    ) {
+        self.items.push(CheckItem {
+            name,
+            status,
+            message: message.into(),
+        });
+    }
+
+    /// Returns the results of all the validations.
+    pub fn items(&self) -> &[CheckItem] {
+        &self.items
+    }
+
+    /// Returns true when no validation fails.
+    pub fn is_ok(&self) -> bool {
+        self.items
+            .iter()
+            .all(|item| item.status != CheckStatus::Fail)
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.status == status)
+            .count()
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for item in &self.items {
+            writeln!(f, "[{}] {}: {}", item.status, item.name, item.message)?;
+        }
+        write!(
+            f,
+            "{} passed, {} warnings, {} failures",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_report() {
+        let mut report = CheckReport::default();
+        report.add("chain spec", CheckStatus::Pass, "matches the database");
+        report.add("block assembler", CheckStatus::Warn, "miner is disabled");
+        assert!(report.is_ok());
+        assert_eq!(
+            report.to_string(),
+            "[PASS] chain spec: matches the database\n\
+             [WARN] block assembler: miner is disabled\n\
+             1 passed, 1 warnings, 0 failures"
+        );
+
+        report.add("rpc", CheckStatus::Fail, "127.0.0.1:8114 is in use");
+        assert!(!report.is_ok());
+        assert_eq!(report.items().len(), 3);
+    }
+}
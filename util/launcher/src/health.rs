@@ -0,0 +1,297 @@
+//! Liveness and readiness endpoints for probes and load balancers.
+use ckb_app_config::HealthConfig;
+use ckb_channel::Receiver;
+use ckb_logger::{debug, info};
+use ckb_network::NetworkController;
+use ckb_shared::Shared;
+use ckb_stop_handler::{SignalSender, StopHandler};
+use ckb_store::ChainStore;
+use ckb_sync::SyncShared;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+// The probes are served concurrently up to this limit, the connections beyond it are dropped.
+const MAX_CONNECTIONS: usize = 16;
+
+pub(crate) struct HealthServer {
+    config: HealthConfig,
+    shared: Shared,
+    network_controller: NetworkController,
+    sync_shared: Arc<SyncShared>,
+}
+
+struct Readiness {
+    db: bool,
+    network: bool,
+    peers: usize,
+    tip: u64,
+    best_known: u64,
+}
+
+impl HealthServer {
+    pub(crate) fn new(
+        config: HealthConfig,
+        shared: Shared,
+        network_controller: NetworkController,
+        sync_shared: Arc<SyncShared>,
+    ) -> Self {
+        HealthServer {
+            config,
+            shared,
+            network_controller,
+            sync_shared,
+        }
+    }
+
+    /// Serves the endpoints in a background thread, does nothing when `listen_address` is not set.
+    ///
+    /// Returns the handler to stop the server.
+    pub(crate) fn start(self) -> io::Result<Option<StopHandler<()>>> {
+        let listen_address = match self.config.listen_address {
+            Some(ref listen_address) => listen_address.clone(),
+            None => return Ok(None),
+        };
+        let listener = TcpListener::bind(&listen_address)?;
+        listener.set_nonblocking(true)?;
+        info!("Listen health check endpoints on {}", listen_address);
+
+        let (stop_sender, stop_receiver) = ckb_channel::bounded(1);
+        let config = self.config.clone();
+        let thread = thread::Builder::new()
+            .name("HealthServer".to_string())
+            .spawn(move || serve(listener, &stop_receiver, config, move || self.readiness()))?;
+        Ok(Some(StopHandler::new(
+            SignalSender::Crossbeam(stop_sender),
+            Some(thread),
+        )))
+    }
+
+    fn readiness(&self) -> Readiness {
+        Readiness {
+            db: self.shared.store().get_tip_header().is_some(),
+            network: self.network_controller.is_active(),
+            peers: self.network_controller.connected_peers().len(),
+            tip: self.shared.snapshot().tip_number(),
+            best_known: self.sync_shared.state().shared_best_header().number(),
+        }
+    }
+}
+
+// Accepts the connections on the non-blocking listener until stopped. Each connection is served
+// in its own thread, so an idle client does not hold up the probes.
+fn serve<F>(listener: TcpListener, stop: &Receiver<()>, config: HealthConfig, readiness: F)
+where
+    F: Fn() -> Readiness + Send + Sync + 'static,
+{
+    let config = Arc::new(config);
+    let readiness = Arc::new(readiness);
+    let connections = Arc::new(AtomicUsize::new(0));
+    loop {
+        match stop.try_recv() {
+            Err(err) if err.is_empty() => {}
+            _ => break,
+        }
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                    debug!(
+                        "health check connections exceed {}, drop it",
+                        MAX_CONNECTIONS
+                    );
+                    continue;
+                }
+                let config = Arc::clone(&config);
+                let readiness = Arc::clone(&readiness);
+                let connections = Arc::clone(&connections);
+                let spawned = thread::Builder::new()
+                    .name("HealthRequest".to_string())
+                    .spawn(move || {
+                        let result = stream
+                            .set_nonblocking(false)
+                            .and_then(|()| handle(stream, &config, || (*readiness)()));
+                        if let Err(err) = result {
+                            debug!("health check request error: {}", err);
+                        }
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                    });
+                if let Err(err) = spawned {
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                    debug!("health check request thread error: {}", err);
+                }
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_INTERVAL);
+            }
+            Err(err) => debug!("health check accept error: {}", err),
+        }
+    }
+}
+
+// Serves a single request, the readiness is only collected for `/ready`.
+fn handle<F>(stream: TcpStream, config: &HealthConfig, readiness: F) -> io::Result<()>
+where
+    F: FnOnce() -> Readiness,
+{
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drains the headers, closing the socket with unread data resets the connection.
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && !line.trim_end().is_empty() {
+        line.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/live")) => ("200 OK", r#"{"status":"alive"}"#.to_string()),
+        (Some("GET"), Some("/ready")) => {
+            let readiness = readiness();
+            if readiness.is_ready(config) {
+                ("200 OK", readiness.to_json("ready"))
+            } else {
+                ("503 Service Unavailable", readiness.to_json("not_ready"))
+            }
+        }
+        (Some("GET"), _) => ("404 Not Found", r#"{"status":"not_found"}"#.to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            r#"{"status":"method_not_allowed"}"#.to_string(),
+        ),
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+impl Readiness {
+    fn is_ready(&self, config: &HealthConfig) -> bool {
+        self.db
+            && self.network
+            && self.peers >= config.min_peers
+            && self.best_known.saturating_sub(self.tip) <= config.max_tip_lag
+    }
+
+    fn to_json(&self, status: &str) -> String {
+        format!(
+            r#"{{"status":"{}","db":{},"network":{},"peers":{},"tip":{},"best_known":{}}}"#,
+            status, self.db, self.network, self.peers, self.tip, self.best_known
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn synced() -> Readiness {
+        Readiness {
+            db: true,
+            network: true,
+            peers: 8,
+            tip: 100,
+            best_known: 100,
+        }
+    }
+
+    // Sends the request to a server which answers with `readiness`, returns the response.
+    fn request(path: &str, readiness: Readiness) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle(stream, &HealthConfig::default(), || readiness).unwrap();
+        });
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, address).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        server.join().unwrap();
+        response
+    }
+
+    #[test]
+    fn test_ready_when_synced() {
+        let response = request("/ready", synced());
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with(
+            r#"{"status":"ready","db":true,"network":true,"peers":8,"tip":100,"best_known":100}"#
+        ));
+
+        // Lagging behind within the limit
+        let readiness = Readiness {
+            tip: 90,
+            ..synced()
+        };
+        assert!(request("/ready", readiness).starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+
+    #[test]
+    fn test_not_ready_when_not_synced() {
+        let readiness = Readiness {
+            tip: 89,
+            ..synced()
+        };
+        let response = request("/ready", readiness);
+        assert!(
+            response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"),
+            "{}",
+            response
+        );
+        assert!(response.contains(r#""status":"not_ready""#));
+
+        let readiness = Readiness {
+            peers: 0,
+            ..synced()
+        };
+        assert!(request("/ready", readiness).starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+    }
+
+    #[test]
+    fn test_live_and_unknown_paths() {
+        let not_synced = Readiness { tip: 0, ..synced() };
+        assert!(request("/live", not_synced).starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(request("/health", synced()).starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn test_idle_connection_does_not_block_probes() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let address = listener.local_addr().unwrap();
+        let (stop_sender, stop_receiver) = ckb_channel::bounded(1);
+        let server =
+            thread::spawn(move || serve(listener, &stop_receiver, HealthConfig::default(), synced));
+
+        // Connects without sending the request
+        let _idle = TcpStream::connect(address).unwrap();
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT / 2)).unwrap();
+        write!(stream, "GET /ready HTTP/1.1\r\nHost: {}\r\n\r\n", address).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+        stop_sender.send(()).unwrap();
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+//! The instant seal miner for the dev chains.
+use ckb_chain::chain::ChainController;
+use ckb_channel::{bounded, select};
+use ckb_error::AnyError;
+use ckb_logger::{debug, error, info, warn};
+use ckb_network::{NetworkController, SupportProtocols};
+use ckb_notify::topic::NewTransaction;
+use ckb_shared::Shared;
+use ckb_stop_handler::{SignalSender, StopHandler};
+use ckb_types::{core::BlockView, packed, prelude::*};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The chain updates the tx-pool asynchronously after a block is processed.
+const POOL_UPDATE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const POOL_UPDATE_MAX_POLLS: usize = 100;
+// Wakes up to check the interval when no transactions arrive.
+const MAX_IDLE: Duration = Duration::from_secs(3600);
+
+/// Mines the blocks with the dummy PoW as soon as the tx-pool has transactions.
+///
+/// The transactions must be proposed and then committed in the proposal window, so the miner
+/// keeps mining until the pending and the proposed transactions are all committed, or until the
+/// proposal window has passed, after which the remaining transactions are never packaged.
+pub(crate) struct InstantSealMiner {
+    shared: Shared,
+    chain: ChainController,
+    network_controller: NetworkController,
+    interval: Option<Duration>,
+}
+
+impl InstantSealMiner {
+    pub(crate) fn new(
+        shared: Shared,
+        chain: ChainController,
+        network_controller: NetworkController,
+        interval: Option<Duration>,
+    ) -> Self {
+        InstantSealMiner {
+            shared,
+            chain,
+            network_controller,
+            interval,
+        }
+    }
+
+    pub(crate) fn start(self) -> StopHandler<()> {
+        let (stop_sender, stop_receiver) = bounded(1);
+        let new_transaction_subscription = self
+            .shared
+            .notify_controller()
+            .subscribe::<NewTransaction>("InstantSeal");
+        let thread = thread::Builder::new()
+            .name("InstantSeal".to_string())
+            .spawn(move || {
+                let mut last_sealed_at = Instant::now();
+                loop {
+                    if self.seal_pending_txs() > 0 {
+                        last_sealed_at = Instant::now();
+                    }
+
+                    let timeout = self
+                        .interval
+                        .map(|interval| interval.saturating_sub(last_sealed_at.elapsed()))
+                        .unwrap_or(MAX_IDLE);
+                    select! {
+                        recv(stop_receiver) -> _ => {
+                            break;
+                        },
+                        recv(new_transaction_subscription) -> msg => {
+                            // the notify service has stopped
+                            if msg.is_err() {
+                                break;
+                            }
+                        },
+                        default(timeout) => {
+                            let expired = self
+                                .interval
+                                .map(|interval| last_sealed_at.elapsed() >= interval)
+                                .unwrap_or(false);
+                            if expired && self.seal_and_log() {
+                                last_sealed_at = Instant::now();
+                            }
+                        },
+                    }
+                }
+            })
+            .expect("Start instant seal miner failed");
+        StopHandler::new(SignalSender::Crossbeam(stop_sender), Some(thread))
+    }
+
+    // Seals the blocks until the pending and the proposed transactions are committed. A pending
+    // transaction is proposed in the next block and committed in the proposal window, so the
+    // transactions left after that many blocks are never packaged and the miner stops sealing
+    // instead of spinning on them. Returns the number of the sealed blocks.
+    pub(crate) fn seal_pending_txs(&self) -> usize {
+        let max_seals = self.shared.consensus().tx_proposal_window().farthest() as usize + 1;
+        let sealed = seal_while(max_seals, || self.has_pending_txs(), || self.seal_and_log());
+        if sealed == max_seals && self.has_pending_txs() {
+            warn!(
+                "instant seal stops after {} blocks, the tx-pool still has transactions not packaged",
+                sealed
+            );
+        }
+        sealed
+    }
+
+    // Whether the tx-pool has transactions to propose or commit, after it has caught up with
+    // the chain tip.
+    fn has_pending_txs(&self) -> bool {
+        let tip_hash = self.shared.snapshot().tip_hash();
+        for _ in 0..POOL_UPDATE_MAX_POLLS {
+            match self.shared.tx_pool_controller().get_tx_pool_info() {
+                Ok(info) if info.tip_hash == tip_hash => {
+                    return info.pending_size + info.proposed_size > 0;
+                }
+                Ok(_) => thread::sleep(POOL_UPDATE_POLL_INTERVAL),
+                Err(err) => {
+                    error!("instant seal get tx-pool info error {}", err);
+                    return false;
+                }
+            }
+        }
+        debug!(
+            "instant seal tx-pool has not caught up with the tip {}",
+            tip_hash
+        );
+        false
+    }
+
+    // Returns whether the block is mined.
+    fn seal_and_log(&self) -> bool {
+        match self.seal() {
+            Ok(block) => {
+                info!(
+                    "instant seal block #{} {} with {} transactions",
+                    block.number(),
+                    block.hash(),
+                    block.transactions().len() - 1
+                );
+                true
+            }
+            Err(err) => {
+                error!("instant seal error {}", err);
+                false
+            }
+        }
+    }
+
+    pub(crate) fn seal(&self) -> Result<Arc<BlockView>, AnyError> {
+        let block_template = self
+            .shared
+            .tx_pool_controller()
+            .get_block_template(None, None, None)??;
+        // the dummy PoW accepts any nonce
+        let block: packed::Block = block_template.into();
+        let block = Arc::new(block.into_view());
+        self.chain.process_block(Arc::clone(&block))?;
+
+        let content = packed::CompactBlock::build_from_block(&block, &HashSet::new());
+        let message = packed::RelayMessage::new_builder().set(content).build();
+        let protocol_id = if self.network_controller.load_ckb2021() {
+            SupportProtocols::RelayV2.protocol_id()
+        } else {
+            SupportProtocols::Relay.protocol_id()
+        };
+        if let Err(err) = self
+            .network_controller
+            .quick_broadcast(protocol_id, message.as_bytes())
+        {
+            error!("Broadcast new block failed: {:?}", err);
+        }
+        Ok(block)
+    }
+}
+
+// Seals while there are pending transactions and the sealing succeeds, at most `max_seals`
+// times. Returns the number of the sealed blocks.
+fn seal_while<P, S>(max_seals: usize, mut has_pending: P, mut seal: S) -> usize
+where
+    P: FnMut() -> bool,
+    S: FnMut() -> bool,
+{
+    let mut sealed = 0;
+    while sealed < max_seals && has_pending() && seal() {
+        sealed += 1;
+    }
+    sealed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_seal_until_committed() {
+        let pending = Cell::new(3);
+        let sealed = seal_while(
+            11,
+            || pending.get() > 0,
+            || {
+                pending.set(pending.get() - 1);
+                true
+            },
+        );
+        assert_eq!(sealed, 3);
+    }
+
+    #[test]
+    fn test_seal_bounded_when_never_packaged() {
+        let seals = Cell::new(0);
+        let sealed = seal_while(
+            11,
+            || true,
+            || {
+                seals.set(seals.get() + 1);
+                true
+            },
+        );
+        assert_eq!(sealed, 11);
+        assert_eq!(seals.get(), 11);
+    }
+
+    #[test]
+    fn test_seal_stops_on_error() {
+        let seals = Cell::new(0);
+        let sealed = seal_while(
+            11,
+            || true,
+            || {
+                seals.set(seals.get() + 1);
+                seals.get() < 2
+            },
+        );
+        assert_eq!(sealed, 1);
+        assert_eq!(seals.get(), 2);
+    }
+
+    #[test]
+    fn test_no_seal_without_pending_txs() {
+        let sealed = seal_while(11, || false, || panic!("nothing to seal"));
+        assert_eq!(sealed, 0);
+    }
+}
@@ -5,37 +5,103 @@
 // declare here for mute ./devtools/ci/check-cargotoml.sh error
 extern crate num_cpus;
 
+pub mod check;
+mod health;
+mod instant_seal;
 pub mod migrate;
 mod migrations;
 mod shared_builder;
+#[cfg(test)]
+mod tests;
 
 use ckb_app_config::{BlockAssemblerConfig, ExitCode, RunArgs};
 use ckb_async_runtime::Handle;
 use ckb_build_info::Version;
 use ckb_chain::chain::{ChainController, ChainService};
 use ckb_channel::Receiver;
+use ckb_db_schema::CHAIN_SPEC_HASH_KEY;
 use ckb_jsonrpc_types::ScriptHashType;
-use ckb_logger::info;
+use ckb_logger::{error, info, warn};
 use ckb_network::{
-    CKBProtocol, DefaultExitHandler, NetworkController, NetworkService, NetworkState, PeerIndex,
-    SupportProtocols,
+    multiaddr_to_socketaddr, CKBProtocol, DefaultExitHandler, NetworkController, NetworkService,
+    NetworkState, PeerIndex, ProtocolId, SupportProtocols,
 };
 use ckb_network_alert::alert_relayer::AlertRelayer;
 use ckb_proposal_table::ProposalTable;
 use ckb_resource::Resource;
 use ckb_rpc::{RpcServer, ServiceBuilder};
-use ckb_shared::Shared;
+use ckb_shared::{shared::FreezerClose, MemoryBudget, Shared};
+use ckb_stop_handler::{ShutdownCoordinator, StopHandler};
 use ckb_store::{ChainDB, ChainStore};
 use ckb_sync::{NetTimeProtocol, Relayer, SyncShared, Synchronizer};
-use ckb_types::{packed::Byte32, prelude::*};
+use ckb_types::{
+    core::{cell::setup_system_cell_cache, FeeRate},
+    packed::{self, Byte32},
+    prelude::*,
+};
+use ckb_util::mock_clock;
 use ckb_verification::GenesisVerifier;
 use ckb_verification_traits::Verifier;
+use health::HealthServer;
+use instant_seal::InstantSealMiner;
+use std::cmp;
+use std::net::{SocketAddr, TcpListener};
 use std::sync::Arc;
+use std::time::Duration;
+
+use crate::check::{CheckReport, CheckStatus};
+use crate::migrate::Migrate;
 
 pub use crate::shared_builder::{SharedBuilder, SharedPackage};
 
 const SECP256K1_BLAKE160_SIGHASH_ALL_ARG_LEN: usize = 20;
 
+// The timeouts to stop the services, the chain may be in the middle of committing a block.
+const HEALTH_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+const RPC_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+const NETWORK_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+const CHAIN_STOP_TIMEOUT: Duration = Duration::from_secs(30);
+const TX_POOL_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+const NOTIFY_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+const INSTANT_SEAL_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+const MEMORY_TRACKER_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The callback building the extra protocols started with the built-in ones, see
+/// [`Launcher::register_protocols`].
+pub type ProtocolRegistrar = Box<dyn Fn(&Shared, &Arc<NetworkState>) -> Vec<CKBProtocol> + Send>;
+
+/// The services started by [`Launcher::start`].
+///
+/// [`Launcher::start`]: struct.Launcher.html#method.start
+pub struct Services {
+    /// The shared state of the node
+    pub shared: Shared,
+    /// The controller of the chain service
+    pub chain_controller: ChainController,
+    /// The controller of the network service
+    pub network_controller: NetworkController,
+    /// The RPC server, which is stopped when dropped
+    pub rpc_server: RpcServer,
+    /// The background services stopped by the shutdown coordinator
+    pub background: BackgroundServices,
+    /// Closes the freezer when dropped
+    pub freezer: Option<FreezerClose>,
+}
+
+/// The optional background services started by [`Launcher::start`], each one is `None` when it
+/// is not enabled.
+///
+/// [`Launcher::start`]: struct.Launcher.html#method.start
+#[derive(Default)]
+pub struct BackgroundServices {
+    /// Stops the health check server
+    pub health_server: Option<StopHandler<()>>,
+    /// Stops the instant seal miner
+    pub instant_seal: Option<StopHandler<()>>,
+    /// Stops the memory tracker
+    pub memory_tracker: Option<StopHandler<()>>,
+}
+
 /// Ckb launcher is helps to launch ckb node.
 pub struct Launcher {
     /// cli `run` subcommand parsed args
@@ -44,58 +110,67 @@ pub struct Launcher {
     pub version: Version,
     /// ckb global runtime handle
     pub async_handle: Handle,
+    protocol_registrars: Vec<ProtocolRegistrar>,
+    memory_budget: Option<Arc<MemoryBudget>>,
 }
 
 impl Launcher {
     /// Construct new Launcher from cli args
-    pub fn new(args: RunArgs, version: Version, async_handle: Handle) -> Self {
+    pub fn new(mut args: RunArgs, version: Version, async_handle: Handle) -> Self {
+        let budget_mb = args.config.memory.budget_mb;
+        let memory_budget = if budget_mb > 0 {
+            let budget = MemoryBudget::new(budget_mb);
+            budget.apply_store_config(&mut args.config.store);
+            args.config.tx_pool.max_mem_size = budget.tx_pool_max_mem_size();
+            args.config.network.sync.header_map.primary_limit = budget.header_map_limit();
+            info!(
+                "Memory budget {} MB, header map limit {}, tx pool max_mem_size {}",
+                budget_mb,
+                args.config.network.sync.header_map.primary_limit,
+                args.config.tx_pool.max_mem_size,
+            );
+            if args.config.memory_tracker.interval == 0 {
+                warn!(
+                    "Memory budget is set but memory_tracker.interval is 0, \
+                     the caches will not shrink under the memory pressure"
+                );
+            }
+            Some(Arc::new(budget))
+        } else {
+            None
+        };
         Launcher {
             args,
             version,
             async_handle,
+            protocol_registrars: Vec::new(),
+            memory_budget,
         }
     }
 
+    /// Registers the callback building the extra protocols, so the downstream builds can run their
+    /// own P2P protocols without forking [`start_network_and_rpc`].
+    ///
+    /// The callbacks are invoked in [`start_network_and_rpc`] before the network service is
+    /// created. The ids of the protocols must not collide with the ids of [`SupportProtocols`].
+    ///
+    /// [`start_network_and_rpc`]: #method.start_network_and_rpc
+    pub fn register_protocols(mut self, registrar: ProtocolRegistrar) -> Self {
+        self.protocol_registrars.push(registrar);
+        self
+    }
+
     /// Sanitize block assembler config
     pub fn sanitize_block_assembler_config(
         &self,
     ) -> Result<Option<BlockAssemblerConfig>, ExitCode> {
+        // the instant seal miner assembles the blocks without the miner RPC
         let block_assembler_config = match (
-            self.args.config.rpc.miner_enable(),
+            self.args.config.rpc.miner_enable() || self.args.config.chain.instant_seal,
             self.args.config.block_assembler.clone(),
         ) {
             (true, Some(mut block_assembler)) => {
-                let check_lock_code_hash = |code_hash| -> Result<bool, ExitCode> {
-                    let secp_cell_data =
-                        Resource::bundled("specs/cells/secp256k1_blake160_sighash_all".to_string())
-                            .get()
-                            .map_err(|err| {
-                                eprintln!(
-                                    "Load specs/cells/secp256k1_blake160_sighash_all error: {:?}",
-                                    err
-                                );
-                                ExitCode::Failure
-                            })?;
-                    let genesis_cellbase = &self.args.consensus.genesis_block().transactions()[0];
-                    Ok(genesis_cellbase
-                        .outputs()
-                        .into_iter()
-                        .zip(genesis_cellbase.outputs_data().into_iter())
-                        .any(|(output, data)| {
-                            data.raw_data() == secp_cell_data.as_ref()
-                                && output
-                                    .type_()
-                                    .to_opt()
-                                    .map(|script| script.calc_script_hash())
-                                    .as_ref()
-                                    == Some(code_hash)
-                        }))
-                };
-                if self.args.block_assembler_advanced
-                    || (block_assembler.hash_type == ScriptHashType::Type
-                        && block_assembler.args.len() == SECP256K1_BLAKE160_SIGHASH_ALL_ARG_LEN
-                        && check_lock_code_hash(&block_assembler.code_hash.pack())?)
-                {
+                if self.is_block_assembler_accepted(&block_assembler)? {
                     if block_assembler.use_binary_version_as_message_prefix {
                         block_assembler.binary_version = self.version.long();
                     }
@@ -119,6 +194,262 @@ impl Launcher {
         Ok(block_assembler_config)
     }
 
+    // Whether the block assembler lock is the recommended format, or any lock is allowed by
+    // `--ba-advanced`
+    fn is_block_assembler_accepted(
+        &self,
+        block_assembler: &BlockAssemblerConfig,
+    ) -> Result<bool, ExitCode> {
+        let check_lock_code_hash = |code_hash| -> Result<bool, ExitCode> {
+            let secp_cell_data =
+                Resource::bundled("specs/cells/secp256k1_blake160_sighash_all".to_string())
+                    .get()
+                    .map_err(|err| {
+                        eprintln!(
+                            "Load specs/cells/secp256k1_blake160_sighash_all error: {:?}",
+                            err
+                        );
+                        ExitCode::Failure
+                    })?;
+            let genesis_cellbase = &self.args.consensus.genesis_block().transactions()[0];
+            Ok(genesis_cellbase
+                .outputs()
+                .into_iter()
+                .zip(genesis_cellbase.outputs_data().into_iter())
+                .any(|(output, data)| {
+                    data.raw_data() == secp_cell_data.as_ref()
+                        && output
+                            .type_()
+                            .to_opt()
+                            .map(|script| script.calc_script_hash())
+                            .as_ref()
+                            == Some(code_hash)
+                }))
+        };
+        Ok(self.args.block_assembler_advanced
+            || (block_assembler.hash_type == ScriptHashType::Type
+                && block_assembler.args.len() == SECP256K1_BLAKE160_SIGHASH_ALL_ARG_LEN
+                && check_lock_code_hash(&block_assembler.code_hash.pack())?))
+    }
+
+    /// Performs the startup validations without starting the node, for `ckb run --check`
+    pub fn check(&self) -> CheckReport {
+        let mut report = CheckReport::default();
+        self.check_genesis(&mut report);
+        self.check_database(&mut report);
+        self.check_block_assembler(&mut report);
+        self.check_listen_addresses(&mut report);
+        report
+    }
+
+    fn check_genesis(&self, report: &mut CheckReport) {
+        match GenesisVerifier::new().verify(&self.args.consensus) {
+            Ok(()) => report.add(
+                "genesis",
+                CheckStatus::Pass,
+                format!("genesis hash {:#x}", self.args.consensus.genesis_hash()),
+            ),
+            Err(err) => report.add("genesis", CheckStatus::Fail, err.to_string()),
+        }
+    }
+
+    fn check_database(&self, report: &mut CheckReport) {
+        let path = &self.args.config.db.path;
+        let migrate = Migrate::new(path);
+        let db = match migrate.open_read_only_db() {
+            Ok(Some(db)) => db,
+            Ok(None) => {
+                report.add(
+                    "database",
+                    CheckStatus::Pass,
+                    format!("a new database will be created in {}", path.display()),
+                );
+                report.add(
+                    "chain spec",
+                    CheckStatus::Pass,
+                    format!(
+                        "the chain spec hash {} will be stored",
+                        self.args.chain_spec_hash
+                    ),
+                );
+                return;
+            }
+            Err(err) => {
+                report.add(
+                    "database",
+                    CheckStatus::Fail,
+                    format!("failed to open {}: {}", path.display(), err),
+                );
+                return;
+            }
+        };
+
+        if migrate.is_downgrade(&db) {
+            report.add(
+                "database",
+                CheckStatus::Fail,
+                "the database is created by a newer version of ckb, downgrade is not supported",
+            );
+        } else if migrate.require_expensive(&db) {
+            report.add(
+                "database",
+                CheckStatus::Fail,
+                "the database requires an expensive migration, run `ckb migrate` first",
+            );
+        } else if migrate.check(&db) {
+            report.add(
+                "database",
+                CheckStatus::Warn,
+                "the database has pending migrations, run `ckb migrate` to apply them",
+            );
+        } else {
+            report.add("database", CheckStatus::Pass, "the database is up to date");
+        }
+
+        let stored_spec_hash = match db.get_pinned_default(CHAIN_SPEC_HASH_KEY) {
+            Ok(raw) => raw
+                .map(|raw| packed::Byte32Reader::from_slice_should_be_ok(raw.as_ref()).to_entity()),
+            Err(err) => {
+                report.add(
+                    "chain spec",
+                    CheckStatus::Fail,
+                    format!("failed to read the stored chain spec hash: {}", err),
+                );
+                return;
+            }
+        };
+        let configured = &self.args.chain_spec_hash;
+        match stored_spec_hash {
+            None => report.add(
+                "chain spec",
+                CheckStatus::Pass,
+                format!("the chain spec hash {} will be stored", configured),
+            ),
+            Some(ref stored) if stored == configured => report.add(
+                "chain spec",
+                CheckStatus::Pass,
+                format!("the chain spec hash {} matches the database", configured),
+            ),
+            Some(stored) if self.args.overwrite_chain_spec => report.add(
+                "chain spec",
+                CheckStatus::Warn,
+                format!(
+                    "the stored chain spec hash {} will be overwritten with {}",
+                    stored, configured
+                ),
+            ),
+            Some(stored) if self.args.skip_chain_spec_check => report.add(
+                "chain spec",
+                CheckStatus::Warn,
+                format!(
+                    "the chain spec hash {} mismatches the stored {}, skipped by --skip-spec-check",
+                    configured, stored
+                ),
+            ),
+            Some(stored) => report.add(
+                "chain spec",
+                CheckStatus::Fail,
+                format!(
+                    "the chain spec hash {} mismatches the stored {}, pass --skip-spec-check \
+                     or --overwrite-spec if it is intended",
+                    configured, stored
+                ),
+            ),
+        }
+    }
+
+    fn check_block_assembler(&self, report: &mut CheckReport) {
+        match (
+            self.args.config.rpc.miner_enable() || self.args.config.chain.instant_seal,
+            &self.args.config.block_assembler,
+        ) {
+            (true, Some(block_assembler)) => {
+                match self.is_block_assembler_accepted(block_assembler) {
+                    Ok(true) => report.add(
+                        "block assembler",
+                        CheckStatus::Pass,
+                        "the block assembler lock is accepted",
+                    ),
+                    Ok(false) => report.add(
+                        "block assembler",
+                        CheckStatus::Warn,
+                        "miner is disabled because the block assembler is not a recommended lock \
+                     format, use `ckb run --ba-advanced` to use other lock scripts",
+                    ),
+                    Err(_) => report.add(
+                        "block assembler",
+                        CheckStatus::Fail,
+                        "failed to load the bundled secp256k1_blake160_sighash_all cell",
+                    ),
+                }
+            }
+            (true, None) => report.add(
+                "block assembler",
+                CheckStatus::Warn,
+                "miner is disabled because the block assembler is not configured",
+            ),
+            (false, _) => report.add(
+                "block assembler",
+                CheckStatus::Pass,
+                "miner is disabled, the Miner RPC module is not enabled",
+            ),
+        }
+    }
+
+    fn check_listen_addresses(&self, report: &mut CheckReport) {
+        let config = &self.args.config;
+        let mut addresses: Vec<(&'static str, String, Option<SocketAddr>)> = config
+            .network
+            .listen_addresses
+            .iter()
+            .map(|addr| {
+                (
+                    "p2p listen address",
+                    addr.to_string(),
+                    multiaddr_to_socketaddr(addr),
+                )
+            })
+            .collect();
+        let rpc_and_health = [
+            ("rpc listen address", Some(&config.rpc.listen_address)),
+            (
+                "rpc tcp listen address",
+                config.rpc.tcp_listen_address.as_ref(),
+            ),
+            (
+                "rpc ws listen address",
+                config.rpc.ws_listen_address.as_ref(),
+            ),
+            (
+                "health listen address",
+                config.health.listen_address.as_ref(),
+            ),
+        ];
+        addresses.extend(rpc_and_health.iter().filter_map(|(name, addr)| {
+            addr.map(|addr| (*name, addr.to_owned(), addr.parse().ok()))
+        }));
+
+        for (name, addr, socket_addr) in addresses {
+            match socket_addr.map(|socket_addr| (socket_addr, TcpListener::bind(socket_addr))) {
+                Some((socket_addr, Ok(_))) => report.add(
+                    name,
+                    CheckStatus::Pass,
+                    format!("{} is available", socket_addr),
+                ),
+                Some((socket_addr, Err(err))) => report.add(
+                    name,
+                    CheckStatus::Fail,
+                    format!("{} is unavailable: {}", socket_addr, err),
+                ),
+                None => report.add(
+                    name,
+                    CheckStatus::Fail,
+                    format!("{} is not a valid socket address", addr),
+                ),
+            }
+        }
+    }
+
     fn write_chain_spec_hash(&self, store: &ChainDB) -> Result<(), ExitCode> {
         store
             .put_chain_spec_hash(&self.args.chain_spec_hash)
@@ -193,6 +524,11 @@ impl Launcher {
             self.async_handle.clone(),
         )?;
 
+        let shared_builder = match self.memory_budget {
+            Some(ref budget) => shared_builder.memory_budget(Arc::clone(budget)),
+            None => shared_builder,
+        };
+
         let (shared, pack) = shared_builder
             .consensus(self.args.consensus.clone())
             .tx_pool_config(self.args.config.tx_pool)
@@ -211,6 +547,19 @@ impl Launcher {
         Ok((shared, pack))
     }
 
+    /// Enables the mock clock in the sandbox mode, see `ckb run --sandbox`
+    ///
+    /// The mock clock starts at the current time, or right after the tip when the tip is from the
+    /// future because the mock clock has been advanced in the previous runs.
+    pub fn enable_sandbox_clock(&self, shared: &Shared) {
+        if self.args.sandbox {
+            let tip_timestamp = shared.snapshot().tip_header().timestamp();
+            let now = cmp::max(mock_clock::unix_time_as_millis(), tip_timestamp + 1);
+            mock_clock::enable(now);
+            info!("Sandbox mode, the mock clock starts at {}", now);
+        }
+    }
+
     /// Check whether the data already exists in the database before starting
     pub fn check_assume_valid_target(&mut self, shared: &Shared) {
         if let Some(ref target) = self.args.config.network.sync.assume_valid_target {
@@ -222,12 +571,47 @@ impl Launcher {
 
     /// Start chain service, return ChainController
     pub fn start_chain_service(&self, shared: &Shared, table: ProposalTable) -> ChainController {
-        let chain_service = ChainService::new(shared.clone(), table);
+        let chain_service = ChainService::new(shared.clone(), table)
+            .with_max_reorg_depth(self.args.config.chain.max_reorg_depth);
         let chain_controller = chain_service.start(Some("ChainService"));
         info!("chain genesis hash: {:#x}", shared.genesis_hash());
         chain_controller
     }
 
+    /// Start the instant seal miner when `chain.instant_seal` is enabled
+    pub fn start_instant_seal(
+        &self,
+        shared: &Shared,
+        chain_controller: ChainController,
+        network_controller: NetworkController,
+        miner_enable: bool,
+    ) -> Result<Option<StopHandler<()>>, ExitCode> {
+        let config = &self.args.config.chain;
+        if !config.instant_seal {
+            return Ok(None);
+        }
+        if !shared.consensus().pow.is_dummy() {
+            eprintln!("chain.instant_seal requires the chain spec using the dummy PoW");
+            return Err(ExitCode::Config);
+        }
+        if !miner_enable {
+            eprintln!("chain.instant_seal requires the block assembler, edit ckb.toml to set it");
+            return Err(ExitCode::Config);
+        }
+        info!(
+            "Instant seal is enabled, interval {:?} seconds",
+            config.instant_seal_interval
+        );
+        let stop_handler = InstantSealMiner::new(
+            shared.clone(),
+            chain_controller,
+            network_controller,
+            config.instant_seal_interval.map(Duration::from_secs),
+        )
+        .start();
+        Ok(Some(stop_handler))
+    }
+
     /// Start network service and rpc servre
     pub fn start_network_and_rpc(
         &self,
@@ -235,13 +619,15 @@ impl Launcher {
         chain_controller: ChainController,
         exit_handler: &DefaultExitHandler,
         miner_enable: bool,
-        relay_tx_receiver: Receiver<(Option<PeerIndex>, bool, Byte32)>,
-    ) -> (NetworkController, RpcServer) {
+        relay_tx_receiver: Receiver<(Option<PeerIndex>, bool, Byte32, FeeRate)>,
+        ask_for_txs_receiver: Receiver<(PeerIndex, Vec<Byte32>)>,
+    ) -> Result<(NetworkController, RpcServer, Option<StopHandler<()>>), ExitCode> {
         let sync_shared = Arc::new(SyncShared::with_tmpdir(
             shared.clone(),
             self.args.config.network.sync.clone(),
             self.args.config.tmp_dir.as_ref(),
             relay_tx_receiver,
+            ask_for_txs_receiver,
         ));
         let fork_enable = {
             let epoch = shared.snapshot().tip_header().epoch().number();
@@ -263,7 +649,10 @@ impl Launcher {
             self.args.config.tx_pool.min_fee_rate,
             self.args.config.tx_pool.max_tx_verify_cycles,
         );
-        let net_timer = NetTimeProtocol::default();
+        let net_timer = NetTimeProtocol::with_net_time(
+            sync_shared.net_time().clone(),
+            shared.notify_controller().clone(),
+        );
         let alert_signature_config = self.args.config.alert_signature.clone().unwrap_or_default();
         let alert_relayer = AlertRelayer::new(
             self.version.to_string(),
@@ -305,6 +694,29 @@ impl Launcher {
             ))
         }
 
+        for registrar in &self.protocol_registrars {
+            for protocol in registrar(shared, &network_state) {
+                assert!(
+                    !is_reserved_protocol_id(protocol.id()),
+                    "the id {} of protocol {} is reserved by the built-in protocols",
+                    protocol.id(),
+                    protocol.protocol_name(),
+                );
+                assert!(
+                    protocols.iter().all(|p| p.id() != protocol.id()),
+                    "the id {} of protocol {} is registered twice",
+                    protocol.id(),
+                    protocol.protocol_name(),
+                );
+                info!(
+                    "Register protocol {} with id {}",
+                    protocol.protocol_name(),
+                    protocol.id()
+                );
+                protocols.push(protocol);
+            }
+        }
+
         let required_protocol_ids = vec![SupportProtocols::Sync.protocol_id()];
 
         let network_controller = NetworkService::new(
@@ -318,11 +730,22 @@ impl Launcher {
         .start(shared.async_handle())
         .expect("Start network service failed");
 
+        let health_server = HealthServer::new(
+            self.args.config.health.clone(),
+            shared.clone(),
+            network_controller.clone(),
+            Arc::clone(&sync_shared),
+        )
+        .start()
+        .map_err(|err| {
+            error!("Start health check server failed: {}", err);
+            ExitCode::Failure
+        })?;
+
         let builder = ServiceBuilder::new(&self.args.config.rpc)
             .enable_chain(shared.clone())
             .enable_pool(
                 shared.clone(),
-                self.args.config.tx_pool.min_fee_rate,
                 self.args.config.rpc.reject_ill_transactions,
                 self.args
                     .config
@@ -345,20 +768,203 @@ impl Launcher {
                 chain_controller.clone(),
                 miner_enable,
             )
-            .enable_net(network_controller.clone(), sync_shared)
+            .enable_net(network_controller.clone(), Arc::clone(&sync_shared))
             .enable_stats(shared.clone(), Arc::clone(&alert_notifier))
             .enable_experiment(shared.clone())
-            .enable_integration_test(shared.clone(), network_controller.clone(), chain_controller)
+            .enable_integration_test(
+                shared.clone(),
+                network_controller.clone(),
+                chain_controller.clone(),
+            )
             .enable_alert(alert_verifier, alert_notifier, network_controller.clone())
-            .enable_debug();
+            .enable_debug(
+                shared.clone(),
+                chain_controller,
+                sync_shared,
+                self.args.config.tx_pool.max_tx_verify_cycles,
+            );
+        let listener_handlers = builder.build_listeners();
         let io_handler = builder.build();
 
         let rpc_server = RpcServer::new(
             self.args.config.rpc.clone(),
             io_handler,
+            listener_handlers,
             shared.notify_controller(),
+            &network_controller,
         );
 
-        (network_controller, rpc_server)
+        Ok((network_controller, rpc_server, health_server))
     }
+
+    /// Starts all the services of the node, the startup sequence shared by `ckb run` and the
+    /// embedded node.
+    ///
+    /// The services are shut down by the coordinator built from the returned
+    /// [`Services`](struct.Services.html), see [`shutdown_coordinator`].
+    ///
+    /// [`shutdown_coordinator`]: #method.shutdown_coordinator
+    pub fn start(&mut self, exit_handler: &DefaultExitHandler) -> Result<Services, ExitCode> {
+        let block_assembler_config = self.sanitize_block_assembler_config()?;
+        let miner_enable = block_assembler_config.is_some();
+
+        let (shared, mut pack) = self.build_shared(block_assembler_config)?;
+
+        // spawn freezer background process
+        let freezer = shared.spawn_freeze();
+
+        // The cache is global, it has been set up when another node with the same genesis is
+        // embedded in this process.
+        if setup_system_cell_cache(
+            shared.consensus().genesis_block(),
+            &shared.store().cell_provider(),
+        )
+        .is_err()
+        {
+            info!("SYSTEM_CELL cache has been set up");
+        }
+
+        let memory_tracker = ckb_memory_tracker::track_current_process(
+            self.args.config.memory_tracker.interval,
+            Some(shared.store().db().inner()),
+            self.args.config.memory_tracker.rocksdb_cf_stats,
+            shared.memory_budget().map(|_| {
+                let shared = shared.clone();
+                Box::new(move |allocated| shared.rebalance_memory(allocated))
+                    as ckb_memory_tracker::AllocatedListener
+            }),
+        );
+
+        self.check_assume_valid_target(&shared);
+        self.enable_sandbox_clock(&shared);
+
+        let chain_controller = self.start_chain_service(&shared, pack.take_proposal_table());
+
+        let (network_controller, rpc_server, health_server) = self.start_network_and_rpc(
+            &shared,
+            chain_controller.clone(),
+            exit_handler,
+            miner_enable,
+            pack.take_relay_tx_receiver(),
+            pack.take_ask_for_txs_receiver(),
+        )?;
+
+        let tx_pool_builder = pack.take_tx_pool_builder();
+        tx_pool_builder.start(network_controller.clone());
+
+        let instant_seal = self.start_instant_seal(
+            &shared,
+            chain_controller.clone(),
+            network_controller.clone(),
+            miner_enable,
+        )?;
+
+        Ok(Services {
+            shared,
+            chain_controller,
+            network_controller,
+            rpc_server,
+            background: BackgroundServices {
+                health_server,
+                instant_seal,
+                memory_tracker,
+            },
+            freezer,
+        })
+    }
+
+    /// Builds the coordinator which shuts down the services in the dependency order.
+    ///
+    /// The background services and the RPC server stop first, then the network, the chain, the
+    /// tx-pool and the notify service, so no service is stopped while another one is still
+    /// feeding it. Then the freezer is closed and the database is flushed as the flush barriers,
+    /// which are skipped when the chain or the tx-pool has not stopped in time, because it may
+    /// still be writing.
+    pub fn shutdown_coordinator(
+        shared: &Shared,
+        chain_controller: &ChainController,
+        network_controller: &NetworkController,
+        rpc_server: Option<RpcServer>,
+        background: BackgroundServices,
+        freezer: Option<FreezerClose>,
+    ) -> ShutdownCoordinator {
+        let mut coordinator = ShutdownCoordinator::new();
+        if let Some(mut memory_tracker) = background.memory_tracker {
+            coordinator.register(
+                "memory-tracker",
+                &[],
+                MEMORY_TRACKER_STOP_TIMEOUT,
+                move || memory_tracker.try_send(()),
+            );
+        }
+        if let Some(mut instant_seal) = background.instant_seal {
+            coordinator.register(
+                "instant-seal",
+                &["network", "chain", "tx-pool"],
+                INSTANT_SEAL_STOP_TIMEOUT,
+                move || instant_seal.try_send(()),
+            );
+        }
+        if let Some(mut health_server) = background.health_server {
+            coordinator.register(
+                "health",
+                &["network", "chain"],
+                HEALTH_STOP_TIMEOUT,
+                move || health_server.try_send(()),
+            );
+        }
+        coordinator.register(
+            "rpc",
+            &["network", "chain", "tx-pool", "notify"],
+            RPC_STOP_TIMEOUT,
+            move || drop(rpc_server),
+        );
+        let network_controller = network_controller.clone();
+        coordinator.register(
+            "network",
+            &["chain", "tx-pool", "notify"],
+            NETWORK_STOP_TIMEOUT,
+            move || network_controller.force_stop(),
+        );
+        let chain_controller = chain_controller.clone();
+        coordinator.register(
+            "chain",
+            &["tx-pool", "notify"],
+            CHAIN_STOP_TIMEOUT,
+            move || chain_controller.force_stop(),
+        );
+        let tx_pool_controller = shared.tx_pool_controller().clone();
+        coordinator.register("tx-pool", &["notify"], TX_POOL_STOP_TIMEOUT, move || {
+            tx_pool_controller.force_stop()
+        });
+        let notify_controller = shared.notify_controller().clone();
+        coordinator.register("notify", &[], NOTIFY_STOP_TIMEOUT, move || {
+            notify_controller.force_stop()
+        });
+        coordinator.register_barrier("freezer", &["chain"], move || drop(freezer));
+        let store = shared.store().clone();
+        coordinator.register_barrier("db", &["chain", "tx-pool", "freezer"], move || {
+            if let Err(err) = store.flush() {
+                error!("Failed to flush the database: {}", err);
+            }
+        });
+        coordinator
+    }
+}
+
+fn is_reserved_protocol_id(id: ProtocolId) -> bool {
+    [
+        SupportProtocols::Ping,
+        SupportProtocols::Discovery,
+        SupportProtocols::Identify,
+        SupportProtocols::Feeler,
+        SupportProtocols::DisconnectMessage,
+        SupportProtocols::Sync,
+        SupportProtocols::Relay,
+        SupportProtocols::RelayV2,
+        SupportProtocols::Time,
+        SupportProtocols::Alert,
+    ]
+    .iter()
+    .any(|protocol| protocol.protocol_id() == id)
 }
@@ -42,6 +42,11 @@ impl Migrate {
         self.migrations.check(&db)
     }
 
+    /// Return true if the database is created by a newer client
+    pub fn is_downgrade(&self, db: &ReadOnlyDB) -> bool {
+        self.migrations.is_downgrade(&db)
+    }
+
     /// Check whether database requires expensive migrations.
     pub fn require_expensive(&self, db: &ReadOnlyDB) -> bool {
         self.migrations.expensive(&db)
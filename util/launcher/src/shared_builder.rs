@@ -16,16 +16,17 @@ use ckb_freezer::Freezer;
 use ckb_notify::{NotifyController, NotifyService, PoolTransactionEntry};
 use ckb_proposal_table::ProposalTable;
 use ckb_proposal_table::ProposalView;
-use ckb_shared::Shared;
+use ckb_shared::{MemoryBudget, Shared};
 use ckb_snapshot::{Snapshot, SnapshotMgr};
 use ckb_stop_handler::StopHandler;
 use ckb_store::ChainDB;
 use ckb_store::ChainStore;
 use ckb_tx_pool::{error::Reject, TokioRwLock, TxEntry, TxPool, TxPoolServiceBuilder};
 use ckb_types::core::EpochExt;
+use ckb_types::core::FeeRate;
 use ckb_types::core::HeaderView;
 use ckb_types::packed::Byte32;
-use ckb_verification::cache::init_cache;
+use ckb_verification::cache::{init_cache, TxVerificationCache};
 use p2p::SessionId as PeerIndex;
 use std::collections::HashSet;
 use std::path::PathBuf;
@@ -41,6 +42,7 @@ pub struct SharedBuilder {
     store_config: Option<StoreConfig>,
     block_assembler_config: Option<BlockAssemblerConfig>,
     notify_config: Option<NotifyConfig>,
+    memory_budget: Option<Arc<MemoryBudget>>,
     async_handle: Handle,
 }
 
@@ -72,7 +74,15 @@ pub fn open_or_create_db(config: &DBConfig) -> Result<RocksDB, ExitCode> {
         }
     }
 
-    let db = RocksDB::open(config, COLUMNS);
+    let db = RocksDB::open_with_check(config, COLUMNS).map_err(|err| {
+        eprintln!(
+            "{}\n\
+             If the database is corrupted, stop the node and run `ckb recover`, \
+             which repairs the database and truncates the chain to the last consistent block.",
+            err
+        );
+        ExitCode::Failure
+    })?;
     if !db_exist {
         migrate.init_db_version(&db).map_err(|e| {
             eprintln!("migrate init_db_version error {}", e);
@@ -100,6 +110,7 @@ impl SharedBuilder {
             notify_config: None,
             store_config: None,
             block_assembler_config: None,
+            memory_budget: None,
             async_handle,
         })
     }
@@ -125,6 +136,7 @@ impl SharedBuilder {
             notify_config: None,
             store_config: None,
             block_assembler_config: None,
+            memory_budget: None,
             async_handle: runtime.borrow().get_or_init(new_global_runtime).0.clone(),
         })
     }
@@ -161,6 +173,12 @@ impl SharedBuilder {
         self
     }
 
+    /// Specifies the memory budget which sizes the transaction verification cache.
+    pub fn memory_budget(mut self, budget: Arc<MemoryBudget>) -> Self {
+        self.memory_budget = Some(budget);
+        self
+    }
+
     /// specifies the async_handle for the shared
     pub fn async_handle(mut self, async_handle: Handle) -> Self {
         self.async_handle = async_handle;
@@ -260,6 +278,7 @@ impl SharedBuilder {
             store_config,
             block_assembler_config,
             notify_config,
+            memory_budget,
             async_handle,
         } = self;
 
@@ -275,7 +294,11 @@ impl SharedBuilder {
             ExitCode::Failure
         })?;
 
-        let txs_verify_cache = Arc::new(TokioRwLock::new(init_cache()));
+        let txs_verify_cache = match memory_budget {
+            Some(ref budget) => TxVerificationCache::new(budget.tx_verify_cache_size()),
+            None => init_cache(),
+        };
+        let txs_verify_cache = Arc::new(TokioRwLock::new(txs_verify_cache));
 
         let (snapshot, table) =
             Self::init_snapshot(&store, Arc::clone(&consensus)).map_err(|e| {
@@ -286,6 +309,7 @@ impl SharedBuilder {
         let snapshot_mgr = Arc::new(SnapshotMgr::new(Arc::clone(&snapshot)));
 
         let (sender, receiver) = ckb_channel::unbounded();
+        let (ask_for_txs_sender, ask_for_txs_receiver) = ckb_channel::unbounded();
 
         let (mut tx_pool_builder, tx_pool_controller) = TxPoolServiceBuilder::new(
             tx_pool_config,
@@ -295,6 +319,7 @@ impl SharedBuilder {
             Arc::clone(&snapshot_mgr),
             &async_handle,
             sender.clone(),
+            ask_for_txs_sender,
         );
 
         register_tx_pool_callback(&mut tx_pool_builder, notify_controller.clone());
@@ -310,12 +335,14 @@ impl SharedBuilder {
             async_handle,
             ibd_finished,
             sender,
+            memory_budget,
         );
 
         let pack = SharedPackage {
             table: Some(table),
             tx_pool_builder: Some(tx_pool_builder),
             relay_tx_receiver: Some(receiver),
+            ask_for_txs_receiver: Some(ask_for_txs_receiver),
         };
 
         Ok((shared, pack))
@@ -327,7 +354,8 @@ impl SharedBuilder {
 pub struct SharedPackage {
     table: Option<ProposalTable>,
     tx_pool_builder: Option<TxPoolServiceBuilder>,
-    relay_tx_receiver: Option<Receiver<(Option<PeerIndex>, bool, Byte32)>>,
+    relay_tx_receiver: Option<Receiver<(Option<PeerIndex>, bool, Byte32, FeeRate)>>,
+    ask_for_txs_receiver: Option<Receiver<(PeerIndex, Vec<Byte32>)>>,
 }
 
 impl SharedPackage {
@@ -342,11 +370,20 @@ impl SharedPackage {
     }
 
     /// Takes the relay_tx_receiver out of the package, leaving a None in its place.
-    pub fn take_relay_tx_receiver(&mut self) -> Receiver<(Option<PeerIndex>, bool, Byte32)> {
+    pub fn take_relay_tx_receiver(
+        &mut self,
+    ) -> Receiver<(Option<PeerIndex>, bool, Byte32, FeeRate)> {
         self.relay_tx_receiver
             .take()
             .expect("take relay_tx_receiver")
     }
+
+    /// Takes the ask_for_txs_receiver out of the package, leaving a None in its place.
+    pub fn take_ask_for_txs_receiver(&mut self) -> Receiver<(PeerIndex, Vec<Byte32>)> {
+        self.ask_for_txs_receiver
+            .take()
+            .expect("take ask_for_txs_receiver")
+    }
 }
 
 fn start_notify_service(notify_config: NotifyConfig) -> NotifyController {
@@ -359,14 +396,40 @@ fn build_store(
     ancient_path: Option<PathBuf>,
 ) -> Result<ChainDB, Error> {
     let store = if store_config.freezer_enable && ancient_path.is_some() {
-        let freezer = Freezer::open(ancient_path.expect("exist checked"))?;
-        ChainDB::new_with_freezer(db, freezer, store_config)
+        let freezer = Freezer::open_with_options(
+            ancient_path.expect("exist checked"),
+            store_config.freezer_threads,
+            store_config.freezer_checksum,
+        )?;
+        let store = ChainDB::new_with_freezer(db, freezer, store_config);
+        check_ancient_data(&store)?;
+        store
     } else {
         ChainDB::new(db, store_config)
     };
     Ok(store)
 }
 
+// The frozen block bodies are wiped out from the database, so an empty freezer with a wiped out
+// block means the freezer data is moved away without updating the config.
+fn check_ancient_data(store: &ChainDB) -> Result<(), Error> {
+    let freezer = store.freezer().expect("freezer enabled");
+    if freezer.number() > 1 {
+        return Ok(());
+    }
+    if let Some(hash) = store.get_block_hash(1) {
+        if store.get_block_txs_hashes(&hash).is_empty() {
+            return Err(InternalErrorKind::Database
+                .other(
+                    "the ancient data is missing, please set `ancient` in ckb.toml to the \
+                     directory which the freezer data was moved to by `ckb move-ancient`",
+                )
+                .into());
+        }
+    }
+    Ok(())
+}
+
 fn register_tx_pool_callback(tx_pool_builder: &mut TxPoolServiceBuilder, notify: NotifyController) {
     let notify_pending = notify.clone();
     tx_pool_builder.register_pending(Box::new(move |tx_pool: &mut TxPool, entry: &TxEntry| {
@@ -383,6 +446,18 @@ fn register_tx_pool_callback(tx_pool_builder: &mut TxPoolServiceBuilder, notify:
         notify_pending.notify_new_transaction(notify_tx_entry);
     }));
 
+    let notify_gap = notify.clone();
+    tx_pool_builder.register_gap(Box::new(move |_tx_pool: &mut TxPool, entry: &TxEntry| {
+        // notify
+        let notify_tx_entry = PoolTransactionEntry {
+            transaction: entry.rtx.transaction.clone(),
+            cycles: entry.cycles,
+            size: entry.size,
+            fee: entry.fee,
+        };
+        notify_gap.notify_gap_transaction(notify_tx_entry);
+    }));
+
     let notify_proposed = notify.clone();
     tx_pool_builder.register_proposed(Box::new(
         move |tx_pool: &mut TxPool, entry: &TxEntry, new: bool| {
@@ -402,9 +477,22 @@ fn register_tx_pool_callback(tx_pool_builder: &mut TxPoolServiceBuilder, notify:
         },
     ));
 
-    tx_pool_builder.register_committed(Box::new(move |tx_pool: &mut TxPool, entry: &TxEntry| {
-        tx_pool.update_statics_for_remove_tx(entry.size, entry.cycles);
-    }));
+    let notify_committed = notify.clone();
+    tx_pool_builder.register_committed(Box::new(
+        move |tx_pool: &mut TxPool, entry: &TxEntry, header: &HeaderView| {
+            // update statics
+            tx_pool.update_statics_for_remove_tx(entry.size, entry.cycles);
+
+            // notify
+            let notify_tx_entry = PoolTransactionEntry {
+                transaction: entry.rtx.transaction.clone(),
+                cycles: entry.cycles,
+                size: entry.size,
+                fee: entry.fee,
+            };
+            notify_committed.notify_committed_transaction(notify_tx_entry, header.clone());
+        },
+    ));
 
     let notify_reject = notify;
     tx_pool_builder.register_reject(Box::new(
@@ -0,0 +1,99 @@
+use crate::{instant_seal::InstantSealMiner, Launcher, SharedBuilder};
+use ckb_app_config::{BlockAssemblerConfig, NetworkConfig};
+use ckb_chain::chain::ChainService;
+use ckb_network::{DefaultExitHandler, NetworkController, NetworkService, NetworkState};
+use ckb_shared::Shared;
+use ckb_store::ChainStore;
+use ckb_types::h256;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+fn dummy_network(shared: &Shared) -> NetworkController {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let config = NetworkConfig {
+        max_peers: 19,
+        max_outbound_peers: 5,
+        path: tmp_dir.path().to_path_buf(),
+        ping_interval_secs: 15,
+        ping_timeout_secs: 20,
+        connect_outbound_interval_secs: 1,
+        discovery_local_address: true,
+        bootnode_mode: true,
+        reuse: true,
+        ..Default::default()
+    };
+
+    let network_state =
+        Arc::new(NetworkState::from_config(config).expect("Init network state failed"));
+    NetworkService::new(
+        network_state,
+        vec![],
+        vec![],
+        shared.consensus().identify_name(),
+        "test".to_string(),
+        DefaultExitHandler::default(),
+    )
+    .start(shared.async_handle())
+    .expect("Start network service failed")
+}
+
+#[test]
+fn test_shutdown_stops_tx_pool_before_barriers() {
+    let (shared, mut pack) = SharedBuilder::with_temp_db().build().unwrap();
+    let network = dummy_network(&shared);
+    pack.take_tx_pool_builder().start(network.clone());
+    let chain_controller =
+        ChainService::new(shared.clone(), pack.take_proposal_table()).start::<&str>(None);
+    let tx_pool_controller = shared.tx_pool_controller().clone();
+    assert!(tx_pool_controller.get_tx_pool_info().is_ok());
+
+    let mut coordinator = Launcher::shutdown_coordinator(
+        &shared,
+        &chain_controller,
+        &network,
+        None,
+        Default::default(),
+        None,
+    );
+    let tx_pool_stopped = Arc::new(AtomicBool::new(false));
+    let tx_pool_stopped_clone = Arc::clone(&tx_pool_stopped);
+    // The barriers run after all the components are stopped.
+    coordinator.register_barrier("check", &[], move || {
+        tx_pool_stopped_clone.store(
+            tx_pool_controller.get_tx_pool_info().is_err(),
+            Ordering::SeqCst,
+        );
+    });
+
+    assert!(coordinator.shutdown().is_empty());
+    assert!(tx_pool_stopped.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_instant_seal() {
+    let (shared, mut pack) = SharedBuilder::with_temp_db()
+        .block_assembler_config(Some(BlockAssemblerConfig {
+            code_hash: h256!("0x1892ea40d82b53c678ff88312450bbb17e164d7a3e0a90941aa58839f56f8df2"),
+            hash_type: ckb_jsonrpc_types::ScriptHashType::Type,
+            args: Default::default(),
+            message: Default::default(),
+            use_binary_version_as_message_prefix: false,
+            binary_version: "TEST".to_string(),
+        }))
+        .build()
+        .unwrap();
+    let network = dummy_network(&shared);
+    pack.take_tx_pool_builder().start(network.clone());
+    let chain_controller =
+        ChainService::new(shared.clone(), pack.take_proposal_table()).start::<&str>(None);
+    let miner = InstantSealMiner::new(shared.clone(), chain_controller, network, None);
+
+    // nothing to seal in an empty tx-pool
+    assert_eq!(miner.seal_pending_txs(), 0);
+    assert_eq!(shared.snapshot().tip_number(), 0);
+
+    let block = miner.seal().unwrap();
+    assert_eq!(block.number(), 1);
+    assert_eq!(shared.snapshot().tip_hash(), block.hash());
+    assert!(shared.store().get_block(&block.hash()).is_some());
+}
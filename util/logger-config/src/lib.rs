@@ -45,6 +45,22 @@ pub struct Config {
     /// Add extra loggers.
     #[serde(default)]
     pub extra: HashMap<String, ExtraLoggerConfig>,
+    /// Rate limiting and sampling of the log records by target.
+    ///
+    /// The key is the target prefix, such as `ckb-sync` or `ckb_sync::synchronizer`. When
+    /// several prefixes match a record, the longest one takes effect.
+    #[serde(default)]
+    pub rate_limits: HashMap<String, RateLimitConfig>,
+    /// Keeps this number of the latest log records in memory, which can be fetched through the
+    /// RPC.
+    ///
+    /// Set it to 0 to disable the in-memory buffer.
+    #[serde(default = "default_ring_buffer_size")]
+    pub ring_buffer_size: usize,
+}
+
+const fn default_ring_buffer_size() -> usize {
+    1000
 }
 
 /// The configuration of an extra CKB logger.
@@ -60,6 +76,22 @@ pub struct ExtraLoggerConfig {
     pub filter: String,
 }
 
+/// The rate limiting and sampling configuration of a log target.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Outputs at most this number of records per second, the excess records are dropped.
+    ///
+    /// If the value is `None`, the records are not rate limited.
+    #[serde(default)]
+    pub max_per_second: Option<u64>,
+    /// Outputs only one in every `sample` records.
+    ///
+    /// If the value is `None` or `1`, all the records are output.
+    #[serde(default)]
+    pub sample: Option<u64>,
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
@@ -71,6 +103,8 @@ impl Default for Config {
             log_to_stdout: true,
             emit_sentry_breadcrumbs: None,
             extra: Default::default(),
+            rate_limits: Default::default(),
+            ring_buffer_size: default_ring_buffer_size(),
         }
     }
 }
@@ -1,28 +1,38 @@
 //! CKB logger and logging service.
 
+mod rate_limit;
+mod ring_buffer;
+
 use ansi_term::Colour;
 use backtrace::Backtrace;
 use chrono::prelude::{DateTime, Local};
 use ckb_channel::{self, unbounded};
 use env_logger::filter::{Builder, Filter};
-use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
 use once_cell::sync::OnceCell;
 use regex::Regex;
 use std::collections::HashMap;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use std::{fs, panic, process, sync, thread};
 
-use ckb_logger_config::Config;
+use ckb_logger_config::{Config, RateLimitConfig};
 use ckb_util::{strings, Mutex, RwLock};
 
+use rate_limit::RateLimiter;
+use ring_buffer::RingBuffer;
+
 static CONTROL_HANDLE: OnceCell<ckb_channel::Sender<Message>> = OnceCell::new();
+static RING_BUFFER: OnceCell<sync::Arc<Mutex<RingBuffer>>> = OnceCell::new();
 static RE: OnceCell<regex::Regex> = OnceCell::new();
 
 enum Message {
     Record {
         is_match: bool,
         extras: Vec<String>,
+        level: Level,
+        target: String,
         data: String,
     },
     UpdateMainLogger {
@@ -30,6 +40,7 @@ enum Message {
         to_stdout: Option<bool>,
         to_file: Option<bool>,
         color: Option<bool>,
+        rate_limiter: Option<RateLimiter>,
     },
     UpdateExtraLogger(String, Filter),
     RemoveExtraLogger(String),
@@ -49,6 +60,7 @@ pub struct Logger {
     #[cfg(feature = "with_sentry")]
     emit_sentry_breadcrumbs: bool,
     extra_loggers: sync::Arc<RwLock<HashMap<String, ExtraLogger>>>,
+    rate_limiter: sync::Arc<RwLock<RateLimiter>>,
 }
 
 #[derive(Debug)]
@@ -185,6 +197,15 @@ impl Logger {
         };
         let extra_loggers_for_update = sync::Arc::clone(&extra_loggers);
 
+        let rate_limiter = sync::Arc::new(RwLock::new(RateLimiter::new(&config.rate_limits)));
+        let rate_limiter_for_update = sync::Arc::clone(&rate_limiter);
+
+        let buffer_enabled = config.ring_buffer_size > 0;
+        let ring_buffer = sync::Arc::new(Mutex::new(RingBuffer::new(config.ring_buffer_size)));
+        RING_BUFFER
+            .set(sync::Arc::clone(&ring_buffer))
+            .expect("RING_BUFFER init once");
+
         let mut extra_files = {
             let extra_files_res = config
                 .extra
@@ -211,11 +232,14 @@ impl Logger {
                         Ok(Message::Record {
                             is_match,
                             extras,
+                            level,
+                            target,
                             data,
                         }) => {
                             let removed_color = if (is_match
                                 && (!main_logger.color || main_logger.to_file))
                                 || !extras.is_empty()
+                                || buffer_enabled
                             {
                                 sanitize_color(data.as_ref())
                             } else {
@@ -243,6 +267,9 @@ impl Logger {
                                     let _ = file.write_all(b"\n");
                                 }
                             }
+                            if buffer_enabled {
+                                ring_buffer.lock().push(level, target, removed_color);
+                            }
                             continue;
                         }
                         Ok(Message::UpdateMainLogger {
@@ -250,6 +277,7 @@ impl Logger {
                             to_stdout,
                             to_file,
                             color,
+                            rate_limiter,
                         }) => {
                             if let Some(filter) = filter {
                                 *filter_for_update.write() = filter;
@@ -271,6 +299,9 @@ impl Logger {
                             if let Some(color) = color {
                                 main_logger.color = color;
                             }
+                            if let Some(rate_limiter) = rate_limiter {
+                                *rate_limiter_for_update.write() = rate_limiter;
+                            }
                         }
                         Ok(Message::UpdateExtraLogger(name, filter)) => {
                             let file = log_dir.clone().join(name.clone() + ".log");
@@ -306,6 +337,7 @@ impl Logger {
             #[cfg(feature = "with_sentry")]
             emit_sentry_breadcrumbs: config.emit_sentry_breadcrumbs.unwrap_or_default(),
             extra_loggers,
+            rate_limiter,
         }
     }
 
@@ -357,18 +389,24 @@ impl Logger {
     }
 
     /// Updates the main logger.
+    ///
+    /// The rate limits, if present, replace all the current rate limits, and apply to the extra
+    /// loggers as well.
     pub fn update_main_logger(
         filter_str: Option<String>,
         to_stdout: Option<bool>,
         to_file: Option<bool>,
         color: Option<bool>,
+        rate_limits: Option<HashMap<String, RateLimitConfig>>,
     ) -> Result<(), String> {
         let filter = filter_str.map(|s| Self::build_filter(&s));
+        let rate_limiter = rate_limits.map(|rate_limits| RateLimiter::new(&rate_limits));
         let message = Message::UpdateMainLogger {
             filter,
             to_stdout,
             to_file,
             color,
+            rate_limiter,
         };
         Self::send_message(message)
     }
@@ -390,6 +428,17 @@ impl Logger {
         let message = Message::RemoveExtraLogger(name);
         Self::send_message(message)
     }
+
+    /// Returns the latest `n` log records kept in memory, the oldest first.
+    ///
+    /// The optional filter uses the same syntax as the logger filter, such as `ckb-sync=debug`.
+    pub fn tail(filter_str: Option<&str>, n: usize) -> Result<Vec<String>, String> {
+        let ring_buffer = RING_BUFFER
+            .get()
+            .ok_or_else(|| "no logger service".to_owned())?;
+        let filter = filter_str.map(Self::build_filter);
+        Ok(ring_buffer.lock().tail(filter.as_ref(), n))
+    }
 }
 
 impl Log for Logger {
@@ -418,6 +467,15 @@ impl Log for Logger {
             })
             .collect::<Vec<_>>();
         if is_match || !extras.is_empty() {
+            let suppressed = match self
+                .rate_limiter
+                .read()
+                .check(record.target(), Instant::now())
+            {
+                Some(suppressed) => suppressed,
+                None => return,
+            };
+
             #[cfg(feature = "with_sentry")]
             if self.emit_sentry_breadcrumbs {
                 use sentry::{add_breadcrumb, integrations::log::breadcrumb_from_record};
@@ -426,23 +484,41 @@ impl Log for Logger {
 
             let thread = thread::current();
             let thread_name = thread.name().unwrap_or("*unnamed*");
+            let thread_name = format!("{}", Colour::Blue.bold().paint(thread_name));
+            let dt: DateTime<Local> = Local::now();
+            let timestamp = dt.format("%Y-%m-%d %H:%M:%S%.3f %Z").to_string();
+            let timestamp = Colour::Black.bold().paint(timestamp);
+
+            if suppressed > 0 {
+                let _ = self.sender.send(Message::Record {
+                    is_match,
+                    extras: extras.clone(),
+                    level: record.level(),
+                    target: record.target().to_owned(),
+                    data: format!(
+                        "{} {} {} {}  {} log records suppressed by the rate limit",
+                        timestamp,
+                        thread_name,
+                        record.level(),
+                        record.target(),
+                        suppressed
+                    ),
+                });
+            }
 
-            let with_color = {
-                let thread_name = format!("{}", Colour::Blue.bold().paint(thread_name));
-                let dt: DateTime<Local> = Local::now();
-                let timestamp = dt.format("%Y-%m-%d %H:%M:%S%.3f %Z").to_string();
-                format!(
-                    "{} {} {} {}  {}",
-                    Colour::Black.bold().paint(timestamp),
-                    thread_name,
-                    record.level(),
-                    record.target(),
-                    record.args()
-                )
-            };
+            let with_color = format!(
+                "{} {} {} {}  {}",
+                timestamp,
+                thread_name,
+                record.level(),
+                record.target(),
+                record.args()
+            );
             let _ = self.sender.send(Message::Record {
                 is_match,
                 extras,
+                level: record.level(),
+                target: record.target().to_owned(),
                 data: with_color,
             });
         }
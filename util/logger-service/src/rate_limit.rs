@@ -0,0 +1,150 @@
+//! Rate limiting and sampling of the log records by target.
+use ckb_logger_config::RateLimitConfig;
+use ckb_util::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Decides whether to output a log record according to the per-target rules.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    // Sorted by the target length in descending order, so the longest matched target wins.
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    target: String,
+    config: RateLimitConfig,
+    state: Mutex<RuleState>,
+}
+
+#[derive(Debug)]
+struct RuleState {
+    window_start: Instant,
+    output_in_window: u64,
+    seen: u64,
+    suppressed: u64,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(configs: &HashMap<String, RateLimitConfig>) -> Self {
+        let now = Instant::now();
+        let mut rules = configs
+            .iter()
+            .map(|(target, config)| Rule {
+                // Parse crate name leniently, the same as the logger filter.
+                target: target.replace("-", "_"),
+                config: config.clone(),
+                state: Mutex::new(RuleState {
+                    window_start: now,
+                    output_in_window: 0,
+                    seen: 0,
+                    suppressed: 0,
+                }),
+            })
+            .collect::<Vec<_>>();
+        rules.sort_by(|a, b| b.target.len().cmp(&a.target.len()));
+        RateLimiter { rules }
+    }
+
+    /// Returns `None` if the record should be dropped.
+    ///
+    /// Otherwise returns the number of records of the same rule dropped by the rate limit since
+    /// the last output record. The records dropped by sampling are not counted.
+    pub(crate) fn check(&self, target: &str, now: Instant) -> Option<u64> {
+        let rule = match self.rules.iter().find(|rule| rule.matches(target)) {
+            Some(rule) => rule,
+            None => return Some(0),
+        };
+        let mut state = rule.state.lock();
+
+        state.seen += 1;
+        if let Some(sample) = rule.config.sample {
+            if sample > 1 && (state.seen - 1) % sample != 0 {
+                return None;
+            }
+        }
+
+        if now.saturating_duration_since(state.window_start) >= WINDOW {
+            state.window_start = now;
+            state.output_in_window = 0;
+        }
+        if let Some(max_per_second) = rule.config.max_per_second {
+            if state.output_in_window >= max_per_second {
+                state.suppressed += 1;
+                return None;
+            }
+        }
+        state.output_in_window += 1;
+        Some(std::mem::replace(&mut state.suppressed, 0))
+    }
+}
+
+impl Rule {
+    fn matches(&self, target: &str) -> bool {
+        target
+            .strip_prefix(self.target.as_str())
+            .map(|rest| rest.is_empty() || rest.starts_with("::"))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(rules: &[(&str, Option<u64>, Option<u64>)]) -> RateLimiter {
+        let configs = rules
+            .iter()
+            .map(|(target, max_per_second, sample)| {
+                let config = RateLimitConfig {
+                    max_per_second: *max_per_second,
+                    sample: *sample,
+                };
+                (target.to_string(), config)
+            })
+            .collect();
+        RateLimiter::new(&configs)
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let limiter = limiter(&[("ckb-sync", Some(2), None)]);
+        let now = Instant::now();
+        assert_eq!(limiter.check("ckb_sync::synchronizer", now), Some(0));
+        assert_eq!(limiter.check("ckb_sync::relayer", now), Some(0));
+        assert_eq!(limiter.check("ckb_sync", now), None);
+        assert_eq!(limiter.check("ckb_sync", now), None);
+        // not matched
+        assert_eq!(limiter.check("ckb_sync_other", now), Some(0));
+        assert_eq!(limiter.check("ckb_network", now), Some(0));
+        // the next window reports the suppressed records
+        let next = now + WINDOW;
+        assert_eq!(limiter.check("ckb_sync", next), Some(2));
+        assert_eq!(limiter.check("ckb_sync", next), Some(0));
+        assert_eq!(limiter.check("ckb_sync", next), None);
+    }
+
+    #[test]
+    fn test_sample_and_longest_prefix() {
+        let limiter = limiter(&[
+            ("ckb_sync", None, Some(3)),
+            ("ckb_sync::relayer", None, None),
+        ]);
+        let now = Instant::now();
+        let outputs = (0..9)
+            .filter(|_| limiter.check("ckb_sync::synchronizer", now).is_some())
+            .count();
+        assert_eq!(outputs, 3);
+        let outputs = (0..9)
+            .filter(|_| {
+                limiter
+                    .check("ckb_sync::relayer::transactions", now)
+                    .is_some()
+            })
+            .count();
+        assert_eq!(outputs, 9);
+    }
+}
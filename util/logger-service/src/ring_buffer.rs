@@ -0,0 +1,97 @@
+//! The bounded in-memory buffer of the recent log records.
+use env_logger::filter::Filter;
+use log::{Level, Record};
+use std::collections::VecDeque;
+
+#[derive(Debug)]
+struct BufferedRecord {
+    level: Level,
+    target: String,
+    // The formatted record without color
+    line: String,
+}
+
+/// Keeps the latest `capacity` log records, the oldest record is evicted when it is full.
+#[derive(Debug)]
+pub(crate) struct RingBuffer {
+    capacity: usize,
+    records: VecDeque<BufferedRecord>,
+}
+
+impl RingBuffer {
+    pub(crate) fn new(capacity: usize) -> Self {
+        RingBuffer {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub(crate) fn push(&mut self, level: Level, target: String, line: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(BufferedRecord {
+            level,
+            target,
+            line,
+        });
+    }
+
+    /// Returns the latest `n` records matched by the filter, the oldest first.
+    pub(crate) fn tail(&self, filter: Option<&Filter>, n: usize) -> Vec<String> {
+        let mut lines = self
+            .records
+            .iter()
+            .rev()
+            .filter(|record| {
+                filter
+                    .map(|filter| {
+                        filter.matches(
+                            &Record::builder()
+                                .level(record.level)
+                                .target(&record.target)
+                                .args(format_args!("{}", record.line))
+                                .build(),
+                        )
+                    })
+                    .unwrap_or(true)
+            })
+            .take(n)
+            .map(|record| record.line.clone())
+            .collect::<Vec<_>>();
+        lines.reverse();
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use env_logger::filter::Builder;
+
+    #[test]
+    fn test_tail() {
+        let mut buffer = RingBuffer::new(3);
+        buffer.push(Level::Info, "ckb_sync".to_owned(), "1".to_owned());
+        buffer.push(Level::Debug, "ckb_sync".to_owned(), "2".to_owned());
+        buffer.push(Level::Info, "ckb_network".to_owned(), "3".to_owned());
+        buffer.push(Level::Info, "ckb_sync".to_owned(), "4".to_owned());
+
+        assert_eq!(buffer.tail(None, 10), vec!["2", "3", "4"]);
+        assert_eq!(buffer.tail(None, 2), vec!["3", "4"]);
+        let filter = Builder::new().parse("ckb_sync=info").build();
+        assert_eq!(buffer.tail(Some(&filter), 10), vec!["4"]);
+        let filter = Builder::new().parse("ckb_sync=debug").build();
+        assert_eq!(buffer.tail(Some(&filter), 10), vec!["2", "4"]);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut buffer = RingBuffer::new(0);
+        buffer.push(Level::Info, "ckb_sync".to_owned(), "1".to_owned());
+        assert!(buffer.tail(None, 10).is_empty());
+    }
+}
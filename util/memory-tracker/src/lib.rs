@@ -27,14 +27,19 @@ mod process {
     use std::sync;
 
     use crate::rocksdb::TrackRocksDBMemory;
+    use crate::AllocatedListener;
     use ckb_logger::info;
+    use ckb_stop_handler::StopHandler;
 
     /// A dummy function which is used when tracking memory usage isn't supported.
     pub fn track_current_process<Tracker: 'static + TrackRocksDBMemory + Sync + Send>(
         _: u64,
         _: Option<sync::Arc<Tracker>>,
-    ) {
+        _: bool,
+        _: Option<AllocatedListener>,
+    ) -> Option<StopHandler<()>> {
         info!("track current process: unsupported");
+        None
     }
 }
 mod rocksdb;
@@ -43,7 +48,13 @@ pub use jemalloc::jemalloc_profiling_dump;
 pub use process::track_current_process;
 pub use rocksdb::TrackRocksDBMemory;
 
+/// A callback which receives the bytes allocated by the application each time the memory usage
+/// is checked.
+pub type AllocatedListener = Box<dyn Fn(u64) + Send>;
+
 /// Track the memory usage of the CKB process and Jemalloc.
-pub fn track_current_process_simple(interval: u64) {
-    track_current_process::<rocksdb::DummyRocksDB>(interval, None);
+///
+/// The tracker stops when the returned handler is stopped or dropped.
+pub fn track_current_process_simple(interval: u64) -> Option<ckb_stop_handler::StopHandler<()>> {
+    track_current_process::<rocksdb::DummyRocksDB>(interval, None, false, None)
 }
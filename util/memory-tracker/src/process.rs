@@ -1,12 +1,15 @@
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::{sync, thread, time};
 
 use ckb_logger::{error, info};
 use ckb_metrics::metrics;
+use ckb_stop_handler::{SignalSender, StopHandler};
 use futures::executor::block_on;
 use heim::units::information::byte;
 use jemalloc_ctl::{epoch, stats};
 
 use crate::rocksdb::TrackRocksDBMemory;
+use crate::AllocatedListener;
 
 macro_rules! je_mib {
     ($key:ty) => {
@@ -14,7 +17,7 @@ macro_rules! je_mib {
             value
         } else {
             error!("failed to lookup jemalloc mib for {}", stringify!($key));
-            return;
+            return None;
         }
     };
 }
@@ -31,12 +34,22 @@ macro_rules! mib_read {
 }
 
 /// Track the memory usage of the CKB process, Jemalloc and RocksDB through [ckb-metrics](../../ckb_metrics/index.html).
+///
+/// The estimates of each RocksDB column family are also gathered when `rocksdb_cf_stats` is enabled.
+///
+/// The `listener` is notified with the bytes allocated by the application after each check.
+///
+/// Returns the handler to stop the tracker, `None` when the tracker is not started. The tracker
+/// also stops when the handler is dropped.
 pub fn track_current_process<Tracker: 'static + TrackRocksDBMemory + Sync + Send>(
     interval: u64,
     tracker_opt: Option<sync::Arc<Tracker>>,
-) {
+    rocksdb_cf_stats: bool,
+    listener: Option<AllocatedListener>,
+) -> Option<StopHandler<()>> {
     if interval == 0 {
         info!("track current process: disable");
+        None
     } else {
         info!("track current process: enable");
         let wait_secs = time::Duration::from_secs(interval);
@@ -56,7 +69,8 @@ pub fn track_current_process<Tracker: 'static + TrackRocksDBMemory + Sync + Send
         // Bytes dedicated to jemalloc metadata.
         let metadata = je_mib!(stats::metadata);
 
-        if let Err(err) = thread::Builder::new()
+        let (stop_sender, stop_receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
             .name("MemoryTracker".to_string())
             .spawn(move || {
                 if let Ok(process) = block_on(heim::process::current()) {
@@ -90,21 +104,39 @@ pub fn track_current_process<Tracker: 'static + TrackRocksDBMemory + Sync + Send
 
                             if let Some(tracker) = tracker_opt.clone() {
                                 tracker.gather_memory_stats();
+                                if rocksdb_cf_stats {
+                                    tracker.gather_cf_stats();
+                                }
+                            }
+
+                            if let Some(ref listener) = listener {
+                                listener(allocated as u64);
                             }
                         } else {
                             error!("failed to fetch the memory information about current process");
                         }
-                        thread::sleep(wait_secs);
+                        match stop_receiver.recv_timeout(wait_secs) {
+                            Err(RecvTimeoutError::Timeout) => {}
+                            // stopped or the handler is dropped
+                            Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                        }
                     }
                 } else {
                     error!("failed to track the currently running program");
                 }
-            })
-        {
-            error!(
-                "failed to spawn the thread to track current process: {}",
-                err
-            );
+            });
+        match spawned {
+            Ok(thread) => Some(StopHandler::new(
+                SignalSender::Std(stop_sender),
+                Some(thread),
+            )),
+            Err(err) => {
+                error!(
+                    "failed to spawn the thread to track current process: {}",
+                    err
+                );
+                None
+            }
         }
     }
 }
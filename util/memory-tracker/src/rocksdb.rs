@@ -1,6 +1,31 @@
 use ckb_db::internal::ops::{GetColumnFamilys, GetProperty, GetPropertyCF};
 use ckb_metrics::metrics;
 
+// The levels which are checked to estimate the read amplification, RocksDB has 7 levels by default.
+const NUM_LEVELS: usize = 7;
+
+// The tickers of the block cache lookups in the RocksDB statistics.
+const BLOCK_CACHE_TICKERS: &[&str] = &[
+    "block.cache.hit",
+    "block.cache.miss",
+    "block.cache.data.hit",
+    "block.cache.data.miss",
+    "block.cache.index.hit",
+    "block.cache.index.miss",
+    "block.cache.filter.hit",
+    "block.cache.filter.miss",
+];
+
+// Finds the count of the ticker in the RocksDB statistics dump, in which each ticker is a line
+// such as "rocksdb.block.cache.hit COUNT : 42".
+fn parse_ticker(stats: &str, ticker: &str) -> Option<u64> {
+    let prefix = format!("rocksdb.{} COUNT : ", ticker);
+    stats
+        .lines()
+        .find_map(|line| line.strip_prefix(prefix.as_str()))
+        .and_then(|count| count.trim().parse().ok())
+}
+
 #[derive(Debug, Clone)]
 enum PropertyValue<T> {
     Value(T),
@@ -40,10 +65,31 @@ pub trait TrackRocksDBMemory {
         self.gather_int_values("block-cache-capacity");
         self.gather_int_values("block-cache-usage");
         self.gather_int_values("block-cache-pinned-usage");
+        self.gather_block_cache_stats();
+    }
+
+    /// Gather the estimates of each column family through [ckb-metrics](../../ckb_metrics/index.html)
+    ///
+    /// These are not memory statistics, but they are useful for capacity planning.
+    fn gather_cf_stats(&self) {
+        self.gather_int_values("estimate-live-data-size");
+        self.gather_int_values("estimate-num-keys");
+        self.gather_int_values("estimate-pending-compaction-bytes");
+        self.gather_read_amplification();
     }
 
     /// Gather integer values through [ckb-metrics](../../ckb_metrics/index.html)
     fn gather_int_values(&self, _: &str) {}
+
+    /// Gather the estimated read amplification through [ckb-metrics](../../ckb_metrics/index.html)
+    ///
+    /// A point lookup may check every file in level 0 and one file in each other non-empty level.
+    fn gather_read_amplification(&self) {}
+
+    /// Gather the block cache hits and misses through [ckb-metrics](../../ckb_metrics/index.html)
+    ///
+    /// They are only available when the RocksDB statistics are enabled by `db.statistics`.
+    fn gather_block_cache_stats(&self) {}
 }
 
 pub(crate) struct DummyRocksDB;
@@ -65,4 +111,62 @@ where
             values.push(value_col);
         }
     }
+
+    fn gather_read_amplification(&self) {
+        for (cf_name, cf) in self.get_cfs() {
+            let mut read_amp = PropertyValue::Value(0);
+            for level in 0..NUM_LEVELS {
+                let files: PropertyValue<u64> = self
+                    .property_value_cf(cf, &format!("rocksdb.num-files-at-level{}", level))
+                    .map_err(|err| format!("{}", err))
+                    .and_then(|value_opt| {
+                        value_opt
+                            .map(|value| value.trim().parse::<u64>().map_err(|err| err.to_string()))
+                            .transpose()
+                    })
+                    .into();
+                read_amp = match (read_amp, files) {
+                    (PropertyValue::Value(sum), PropertyValue::Value(files)) => {
+                        if level == 0 {
+                            PropertyValue::Value(sum + files)
+                        } else {
+                            PropertyValue::Value(sum + files.min(1))
+                        }
+                    }
+                    (PropertyValue::Value(_), other) => other,
+                    (prev, _) => prev,
+                };
+            }
+            metrics!(gauge, "ckb-sys.rocksdb.read_amp", read_amp.as_i64(), "cf" => cf_name.to_owned());
+        }
+    }
+
+    fn gather_block_cache_stats(&self) {
+        if let Ok(Some(stats)) = self.property_value("rocksdb.options-statistics") {
+            for ticker in BLOCK_CACHE_TICKERS {
+                if let Some(count) = parse_ticker(&stats, ticker) {
+                    metrics!(gauge, "ckb-sys.rocksdb.block_cache", count as i64, "ticker" => *ticker);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ticker() {
+        let stats = "rocksdb.block.cache.miss COUNT : 7\n\
+                     rocksdb.block.cache.hit COUNT : 42\n\
+                     rocksdb.block.cache.data.hit COUNT : 40\n\
+                     rocksdb.db.get.micros P50 : 1.000000 P95 : 2.000000 P99 : 3.000000 \
+                     P100 : 4.000000 COUNT : 5 SUM : 6\n";
+        assert_eq!(parse_ticker(stats, "block.cache.hit"), Some(42));
+        assert_eq!(parse_ticker(stats, "block.cache.miss"), Some(7));
+        assert_eq!(parse_ticker(stats, "block.cache.data.hit"), Some(40));
+        assert_eq!(parse_ticker(stats, "block.cache.data.miss"), None);
+        assert_eq!(parse_ticker(stats, "db.get.micros"), None);
+    }
 }
@@ -22,6 +22,9 @@ pub use log::Level as LogLevel;
 /// histogram_window = 60
 /// histogram_granularity = 1
 /// upkeep_interval = 500
+/// prefix = "node1"
+/// [metrics.labels]
+/// network = "mainnet"
 /// [metrics.exporter.prometheus]
 /// target = { type = "http", listen_address = "127.0.0.1:8100" }
 /// format = { type = "prometheus" }
@@ -58,6 +61,15 @@ pub struct Config {
     /// [upkeep interval]: https://docs.rs/metrics-runtime/0.13.1/metrics_runtime/struct.Builder.html#method.upkeep_interval
     #[serde(default)]
     pub upkeep_interval: u64,
+    /// Prepends the prefix to the names of all the metrics, such as `node1.ckb.processed_block`.
+    ///
+    /// It must be a valid identifier, and the names are unchanged when it is empty.
+    #[serde(default)]
+    pub prefix: String,
+    /// Attaches the labels to all the metrics, such as the node name and the network, so the
+    /// dashboards can distinguish the metrics from multiple nodes.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
 
     /// Stores all exporters configurations.
     #[serde(default)]
@@ -1,7 +1,13 @@
 //! The service which handles the metrics data in CKB.
 
-use std::{net::SocketAddr, time::Duration};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
+use metrics::{Key, Label, Recorder as _};
 use metrics_core::Observe;
 use metrics_runtime::{
     exporters::{HttpExporter, LogExporter},
@@ -11,7 +17,12 @@ use metrics_runtime::{
 
 use ckb_async_runtime::Handle;
 use ckb_metrics_config::{Config, Exporter, Format, Target};
-use ckb_util::strings;
+use ckb_util::{strings, RwLock};
+
+// Whether the metrics service is initialized
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+// Whether the metrics data is collected, which can be toggled at runtime
+static ENABLED: AtomicBool = AtomicBool::new(true);
 
 /// Ensures the metrics service can shutdown gracefully.
 #[must_use]
@@ -57,16 +68,111 @@ pub fn init(config: Config, handle: Handle) -> Result<Guard, String> {
     .unwrap();
     let controller = receiver.controller();
 
+    if !config.prefix.is_empty() {
+        strings::check_if_identifier_is_valid(&config.prefix)?;
+    }
+    let mut labels = Vec::with_capacity(config.labels.len());
+    for (key, value) in config.labels {
+        strings::check_if_identifier_is_valid(&key)?;
+        labels.push(Label::new(key, value));
+    }
+
     for (name, exporter) in config.exporter {
         check_exporter_name(&name)?;
         run_exporter(exporter, &handle, controller.clone())?;
     }
 
-    receiver.install();
+    let recorder = Recorder {
+        receiver,
+        rewriter: KeyRewriter::new(config.prefix, labels),
+    };
+    metrics::set_boxed_recorder(Box::new(recorder))
+        .map_err(|err| format!("failed to install the metrics recorder because {}", err))?;
+    INITIALIZED.store(true, Ordering::SeqCst);
 
     Ok(Guard::On)
 }
 
+/// Enables or disables collecting the metrics data at runtime.
+///
+/// Returns an error if the metrics service is not initialized.
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    if !INITIALIZED.load(Ordering::SeqCst) {
+        return Err("the metrics service is disabled, no exporter is configured".to_owned());
+    }
+    ENABLED.store(enabled, Ordering::SeqCst);
+    Ok(())
+}
+
+// Applies the prefix and the labels to the metrics, and drops the metrics data when the
+// collection is disabled.
+struct Recorder {
+    receiver: Receiver,
+    rewriter: KeyRewriter,
+}
+
+// Applies the prefix and the labels to the metric keys.
+//
+// The rewritten keys are cached, the metrics are recorded on the hot paths and the set of the
+// keys is small, so each key is only formatted once.
+struct KeyRewriter {
+    prefix: String,
+    labels: Vec<Label>,
+    cache: RwLock<HashMap<Key, Key>>,
+}
+
+impl KeyRewriter {
+    fn new(prefix: String, labels: Vec<Label>) -> Self {
+        KeyRewriter {
+            prefix,
+            labels,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn rewrite(&self, key: Key) -> Key {
+        if self.prefix.is_empty() && self.labels.is_empty() {
+            return key;
+        }
+        if let Some(rewritten) = self.cache.read().get(&key) {
+            return rewritten.clone();
+        }
+        let (name, mut labels) = key.clone().into_parts();
+        let name = if self.prefix.is_empty() {
+            name
+        } else {
+            format!("{}.{}", self.prefix, name).into()
+        };
+        labels.extend(self.labels.iter().cloned());
+        let rewritten = Key::from_name_and_labels(name, labels);
+        self.cache.write().insert(key, rewritten.clone());
+        rewritten
+    }
+}
+
+impl metrics::Recorder for Recorder {
+    fn increment_counter(&self, key: Key, value: u64) {
+        if ENABLED.load(Ordering::Relaxed) {
+            self.receiver
+                .increment_counter(self.rewriter.rewrite(key), value);
+        }
+    }
+
+    fn update_gauge(&self, key: Key, value: i64) {
+        if ENABLED.load(Ordering::Relaxed) {
+            self.receiver
+                .update_gauge(self.rewriter.rewrite(key), value);
+        }
+    }
+
+    fn record_histogram(&self, key: Key, value: u64) {
+        if ENABLED.load(Ordering::Relaxed) {
+            self.receiver
+                .record_histogram(self.rewriter.rewrite(key), value);
+        }
+    }
+}
+
 fn check_exporter_name(name: &str) -> Result<(), String> {
     strings::check_if_identifier_is_valid(name)
 }
@@ -125,3 +231,34 @@ where
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_key() {
+        let rewriter = KeyRewriter::new(String::new(), Vec::new());
+        let key = Key::from_name("ckb.tx_pool.size");
+        assert_eq!(rewriter.rewrite(key.clone()), key);
+        assert!(rewriter.cache.read().is_empty());
+
+        let rewriter = KeyRewriter::new("node1".to_owned(), vec![Label::new("zone", "east")]);
+        let key = Key::from_name_and_labels("ckb.tx_pool.size", vec![Label::new("k", "v")]);
+        let expected = Key::from_name_and_labels(
+            "node1.ckb.tx_pool.size",
+            vec![Label::new("k", "v"), Label::new("zone", "east")],
+        );
+        assert_eq!(rewriter.rewrite(key.clone()), expected);
+        assert_eq!(rewriter.cache.read().get(&key), Some(&expected));
+        // the cached key is returned again
+        assert_eq!(rewriter.rewrite(key), expected);
+        assert_eq!(rewriter.cache.read().len(), 1);
+
+        let rewriter = KeyRewriter::new(String::new(), vec![Label::new("zone", "east")]);
+        assert_eq!(
+            rewriter.rewrite(Key::from_name("ckb.tx_pool.size")),
+            Key::from_name_and_labels("ckb.tx_pool.size", vec![Label::new("zone", "east")]),
+        );
+    }
+}
@@ -6,7 +6,7 @@
 //! A cli to generate alert message,
 //! A config option to set alert messages to broard cast.
 //
-use crate::notifier::Notifier;
+use crate::notifier::{is_valid_version_range, Notifier};
 use crate::verifier::Verifier;
 use crate::BAD_MESSAGE_BAN_TIME;
 use ckb_app_config::NetworkAlertConfig;
@@ -18,8 +18,11 @@ use ckb_util::Mutex;
 use lru::LruCache;
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 const KNOWN_LIST_SIZE: usize = 64;
+const CLEAR_EXPIRED_ALERTS_TOKEN: u64 = 0;
+const CLEAR_EXPIRED_ALERTS_INTERVAL: Duration = Duration::from_secs(60);
 
 /// AlertRelayer
 /// relay alert messages
@@ -73,7 +76,16 @@ impl AlertRelayer {
 }
 
 impl CKBProtocolHandler for AlertRelayer {
-    fn init(&mut self, _nc: Arc<dyn CKBProtocolContext + Sync>) {}
+    fn init(&mut self, nc: Arc<dyn CKBProtocolContext + Sync>) {
+        nc.set_notify(CLEAR_EXPIRED_ALERTS_INTERVAL, CLEAR_EXPIRED_ALERTS_TOKEN)
+            .expect("set_notify at init is ok");
+    }
+
+    fn notify(&mut self, _nc: Arc<dyn CKBProtocolContext + Sync>, token: u64) {
+        if token == CLEAR_EXPIRED_ALERTS_TOKEN {
+            self.clear_expired_alerts();
+        }
+    }
 
     fn connected(
         &mut self,
@@ -143,6 +155,12 @@ impl CKBProtocolHandler for AlertRelayer {
         if self.notifier.lock().has_received(alert_id) {
             return;
         }
+        // the expired alert has been removed or will be removed soon, don't add it again
+        let notice_until: u64 = alert.as_reader().raw().notice_until().unpack();
+        if notice_until <= faketime::unix_time_as_millis() {
+            trace!("ignore expired alert {} from peer {}", alert_id, peer_index);
+            return;
+        }
         // verify
         if let Err(err) = self.verifier.verify_signatures(&alert) {
             debug!(
@@ -158,6 +176,15 @@ impl CKBProtocolHandler for AlertRelayer {
         }
         // mark sender as known
         self.mark_as_known(peer_index, alert_id);
+        // no client will notice the alert, stop relaying it
+        if !is_valid_version_range(&alert) {
+            debug!(
+                "Peer {} sends us an alert {} with malformed version range",
+                peer_index, alert_id
+            );
+            self.notifier.lock().add(&alert);
+            return;
+        }
         // broadcast message
         let selected_peers: HashSet<PeerIndex> = nc
             .connected_peers()
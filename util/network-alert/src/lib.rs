@@ -9,6 +9,7 @@
 //!
 pub mod alert_relayer;
 pub mod notifier;
+pub mod signer;
 #[cfg(test)]
 mod tests;
 pub mod verifier;
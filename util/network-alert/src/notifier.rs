@@ -1,12 +1,45 @@
 //! notifier module
 use ckb_logger::debug;
-use ckb_notify::NotifyController;
-use ckb_types::{packed::Alert, prelude::*};
+use ckb_notify::{AlertRemovedReason, NotifyController};
+use ckb_types::{
+    packed::{Alert, BytesReader},
+    prelude::*,
+};
 use lru::LruCache;
+use semver::Version;
 use std::collections::HashMap;
 
 const CANCEL_FILTER_SIZE: usize = 128;
 
+/// Checks whether the targeting version range of the alert is well-formed.
+///
+/// Both `min_version` and `max_version` must be valid semantic versions if present, and
+/// `min_version` must not be greater than `max_version`. No client will notice an alert with
+/// malformed version range.
+pub fn is_valid_version_range(alert: &Alert) -> bool {
+    fn parse(v: Option<BytesReader>) -> Result<Option<Version>, ()> {
+        match v {
+            Some(v) => v
+                .as_utf8()
+                .ok()
+                .and_then(|v| Version::parse(v).ok())
+                .map(Some)
+                .ok_or(()),
+            None => Ok(None),
+        }
+    }
+
+    let reader = alert.as_reader();
+    match (
+        parse(reader.raw().min_version().to_opt()),
+        parse(reader.raw().max_version().to_opt()),
+    ) {
+        (Ok(Some(min_v)), Ok(Some(max_v))) => min_v <= max_v,
+        (Ok(_), Ok(_)) => true,
+        _ => false,
+    }
+}
+
 /// Notify other module
 pub struct Notifier {
     /// cancelled alerts
@@ -32,7 +65,6 @@ impl Notifier {
     }
 
     fn is_version_effective(&self, alert: &Alert) -> bool {
-        use semver::Version;
         if let Ok(client_version) = Version::parse(&self.client_version) {
             let test_min_ver_failed = alert
                 .as_reader()
@@ -101,25 +133,44 @@ impl Notifier {
     }
 
     /// Cancel alert id
+    ///
+    /// The subscribers are notified if the alert has been noticed.
     pub fn cancel(&mut self, cancel_id: u32) {
         self.cancel_filter.put(cancel_id, ());
         self.received_alerts.remove(&cancel_id);
-        self.noticed_alerts.retain(|a| {
+        self.remove_noticed_alerts(AlertRemovedReason::Cancelled, |a| {
             let id: u32 = a.raw().id().unpack();
-            id != cancel_id
+            id == cancel_id
         });
     }
 
     /// Clear all expired alerts
+    ///
+    /// The subscribers are notified of the expired alerts which have been noticed.
     pub fn clear_expired_alerts(&mut self, now: u64) {
-        self.received_alerts.retain(|_id, alert| {
+        let is_expired = |alert: &Alert| {
             let notice_until: u64 = alert.raw().notice_until().unpack();
-            notice_until > now
-        });
-        self.noticed_alerts.retain(|a| {
-            let notice_until: u64 = a.raw().notice_until().unpack();
-            notice_until > now
-        });
+            notice_until <= now
+        };
+        self.received_alerts.retain(|_id, alert| !is_expired(alert));
+        self.remove_noticed_alerts(AlertRemovedReason::Expired, is_expired);
+    }
+
+    fn remove_noticed_alerts<F: Fn(&Alert) -> bool>(
+        &mut self,
+        reason: AlertRemovedReason,
+        should_remove: F,
+    ) {
+        let (removed, kept) = self
+            .noticed_alerts
+            .drain(..)
+            .partition(|alert| should_remove(alert));
+        self.noticed_alerts = kept;
+        for alert in removed {
+            debug!("remove the noticed alert {:?}, reason {:?}", alert, reason);
+            self.notify_controller
+                .notify_network_alert_removed(alert, reason);
+        }
     }
 
     /// Whether id received
@@ -136,4 +187,10 @@ impl Notifier {
     pub fn noticed_alerts(&self) -> Vec<Alert> {
         self.noticed_alerts.clone()
     }
+
+    /// Alerts that self node should noticed and are not expired at `now`
+    pub fn active_alerts(&mut self, now: u64) -> Vec<Alert> {
+        self.clear_expired_alerts(now);
+        self.noticed_alerts()
+    }
 }
@@ -0,0 +1,31 @@
+//! sign module
+//!
+//! The alert key holders sign the same raw alert offline one by one, then the alert with enough
+//! signatures is broadcast via the RPC `send_alert`.
+use ckb_error::{AnyError, InternalErrorKind};
+use ckb_multisig::secp256k1::{Privkey, Signature};
+use ckb_types::{packed, prelude::*};
+
+/// Signs the alert and appends the signature to the existing ones.
+///
+/// Returns an error when the alert has been signed by the same key.
+pub fn sign_alert(alert: &packed::Alert, privkey: &Privkey) -> Result<packed::Alert, AnyError> {
+    let message = alert.calc_alert_hash().unpack();
+    let pubkey = privkey.pubkey()?;
+    for sig_data in alert.signatures().into_iter() {
+        let signer = Signature::from_slice(sig_data.as_reader().raw_data())?.recover(&message)?;
+        if signer == pubkey {
+            return Err(InternalErrorKind::Other
+                .other("the alert has been signed by this key")
+                .into());
+        }
+    }
+
+    let signature = privkey.sign_recoverable(&message)?;
+    let signatures = alert
+        .signatures()
+        .as_builder()
+        .push(signature.serialize().pack())
+        .build();
+    Ok(alert.clone().as_builder().signatures(signatures).build())
+}
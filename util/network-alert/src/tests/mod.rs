@@ -1,2 +1,3 @@
 mod test_notifier;
+mod test_signer;
 mod test_verifier;
@@ -1,6 +1,7 @@
-use crate::notifier::Notifier;
-use ckb_notify::NotifyService;
+use crate::notifier::{is_valid_version_range, Notifier};
+use ckb_notify::{topic::NetworkAlertRemoved, AlertRemovedReason, NotifyService};
 use ckb_types::{packed, prelude::*};
+use std::time::Duration;
 
 fn build_alert(
     id: u32,
@@ -24,6 +25,10 @@ fn new_notifier(version: &str) -> Notifier {
     Notifier::new(version.into(), notify_controller)
 }
 
+fn alert_id(alert: &packed::Alert) -> u32 {
+    alert.raw().id().unpack()
+}
+
 #[test]
 fn test_notice_alerts_by_version() {
     let mut notifier = new_notifier("0.9.0");
@@ -89,3 +94,70 @@ fn test_clear_expired_alerts() {
     assert_eq!(notifier.received_alerts().len(), 0);
     assert_eq!(notifier.noticed_alerts().len(), 0);
 }
+
+#[test]
+fn test_valid_version_range() {
+    assert!(is_valid_version_range(&build_alert(1, 0, None, None, 0)));
+    assert!(is_valid_version_range(&build_alert(
+        1,
+        0,
+        Some("0.1.0"),
+        Some("0.1.0"),
+        0
+    )));
+    assert!(!is_valid_version_range(&build_alert(
+        1,
+        0,
+        Some("0.2.0"),
+        Some("0.1.0"),
+        0
+    )));
+    assert!(!is_valid_version_range(&build_alert(
+        1,
+        0,
+        Some("v0.1"),
+        None,
+        0
+    )));
+}
+
+#[test]
+fn test_active_alerts() {
+    let mut notifier = new_notifier("0.1.0");
+    let notice_until = 1_561_084_974_000;
+    notifier.add(&build_alert(1, 0, None, None, notice_until));
+    notifier.add(&build_alert(2, 0, Some("0.2.0"), None, notice_until));
+    assert_eq!(notifier.active_alerts(notice_until - 1000).len(), 1);
+    assert_eq!(notifier.active_alerts(notice_until + 1000).len(), 0);
+}
+
+#[test]
+fn test_notify_removed_alerts() {
+    let notify_controller = NotifyService::new(Default::default()).start(Some("test"));
+    let subscription = notify_controller.subscribe::<NetworkAlertRemoved>("test");
+    let mut notifier = Notifier::new("0.1.0".into(), notify_controller);
+    let notice_until = 1_561_084_974_000;
+    notifier.add(&build_alert(1, 0, None, None, notice_until));
+    notifier.add(&build_alert(2, 0, None, None, notice_until));
+    // not noticed by this version
+    notifier.add(&build_alert(3, 0, Some("0.2.0"), None, notice_until));
+
+    notifier.add(&build_alert(4, 1, None, None, notice_until + 2000));
+    let (alert, reason) = subscription
+        .recv_timeout(Duration::from_secs(5))
+        .expect("alert 1 is cancelled");
+    assert_eq!(
+        (alert_id(&alert), reason),
+        (1, AlertRemovedReason::Cancelled)
+    );
+
+    notifier.clear_expired_alerts(notice_until + 1000);
+    let (alert, reason) = subscription
+        .recv_timeout(Duration::from_secs(5))
+        .expect("alert 2 is expired");
+    assert_eq!((alert_id(&alert), reason), (2, AlertRemovedReason::Expired));
+    assert!(subscription
+        .recv_timeout(Duration::from_millis(100))
+        .is_err());
+    assert_eq!(notifier.noticed_alerts().len(), 1);
+}
@@ -0,0 +1,29 @@
+use crate::signer::sign_alert;
+use crate::verifier::Verifier;
+use ckb_app_config::NetworkAlertConfig;
+use ckb_crypto::secp::Generator;
+use ckb_jsonrpc_types::JsonBytes;
+use ckb_types::{packed, prelude::*};
+
+#[test]
+fn test_sign_alert() {
+    let keypairs: Vec<_> = (0..3).map(move |_| Generator::random_keypair()).collect();
+    let config = NetworkAlertConfig {
+        signatures_threshold: 2,
+        public_keys: keypairs
+            .iter()
+            .map(|(_, pubkey)| JsonBytes::from_vec(pubkey.serialize()))
+            .collect(),
+    };
+    let verifier = Verifier::new(config);
+    let raw_alert = packed::RawAlert::new_builder().id(1u32.pack()).build();
+    let alert = packed::Alert::new_builder().raw(raw_alert).build();
+
+    let alert = sign_alert(&alert, &keypairs[0].0).expect("sign alert");
+    assert!(verifier.verify_signatures(&alert).is_err());
+    assert!(sign_alert(&alert, &keypairs[0].0).is_err());
+
+    let alert = sign_alert(&alert, &keypairs[2].0).expect("sign alert");
+    assert_eq!(alert.signatures().len(), 2);
+    assert!(verifier.verify_signatures(&alert).is_ok());
+}
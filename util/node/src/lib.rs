@@ -0,0 +1,256 @@
+//! Embeds a CKB full node in another program.
+//!
+//! It wraps the [`Launcher`] into a programmatic API, so integration test frameworks and the
+//! products built on top of CKB can start a full node the same way as `ckb run`, and get the
+//! handles to interact with it.
+//!
+//! ```ignore
+//! let config = AppConfig::load_for_subcommand(root_dir, "run")?.into_ckb()?;
+//! let node = NodeBuilder::new(*config).start().await?;
+//! let tip = node.shared().snapshot().tip_number();
+//! node.shutdown();
+//! ```
+//!
+//! [`Launcher`]: ../ckb_launcher/struct.Launcher.html
+
+use ckb_app_config::{CKBAppConfig, ExitCode, RunArgs};
+use ckb_async_runtime::{new_global_runtime, tokio::task::block_in_place, Handle};
+use ckb_build_info::Version;
+use ckb_chain::chain::ChainController;
+use ckb_chain_spec::ChainSpec;
+use ckb_launcher::{BackgroundServices, Launcher, ProtocolRegistrar};
+use ckb_logger::info;
+use ckb_network::{DefaultExitHandler, ExitHandler, NetworkController};
+use ckb_rpc::RpcServer;
+use ckb_shared::{shared::FreezerClose, Shared};
+use ckb_stop_handler::StopHandler;
+use ckb_tx_pool::TxPoolController;
+
+/// Builder of an embedded CKB full node.
+pub struct NodeBuilder {
+    config: CKBAppConfig,
+    spec: Option<ChainSpec>,
+    version: Option<Version>,
+    async_handle: Option<Handle>,
+    block_assembler_advanced: bool,
+    skip_chain_spec_check: bool,
+    protocol_registrars: Vec<ProtocolRegistrar>,
+}
+
+impl NodeBuilder {
+    /// Creates a builder from the parsed `ckb.toml`.
+    ///
+    /// The paths in the config must be resolved already, such as the config returned by
+    /// `AppConfig::load_for_subcommand(root_dir, "run")`.
+    pub fn new(config: CKBAppConfig) -> Self {
+        NodeBuilder {
+            config,
+            spec: None,
+            version: None,
+            async_handle: None,
+            block_assembler_advanced: false,
+            skip_chain_spec_check: false,
+            protocol_registrars: Vec::new(),
+        }
+    }
+
+    /// Uses the chain spec instead of the one specified by `chain.spec` in the config.
+    pub fn with_spec(mut self, spec: ChainSpec) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    /// Sets the version reported to the peers and by the RPC, default is the version of this
+    /// crate.
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Runs the node on the runtime, default is a new runtime owned by the node.
+    pub fn with_async_handle(mut self, async_handle: Handle) -> Self {
+        self.async_handle = Some(async_handle);
+        self
+    }
+
+    /// Allows the block assembler to use the lock scripts other than the recommended one, the
+    /// same as `ckb run --ba-advanced`.
+    pub fn block_assembler_advanced(mut self, enable: bool) -> Self {
+        self.block_assembler_advanced = enable;
+        self
+    }
+
+    /// Skips checking the chain spec against the one stored in the database, the same as
+    /// `ckb run --skip-spec-check`.
+    pub fn skip_chain_spec_check(mut self, skip: bool) -> Self {
+        self.skip_chain_spec_check = skip;
+        self
+    }
+
+    /// Registers the callback building the extra P2P protocols started with the built-in ones,
+    /// see `Launcher::register_protocols`.
+    pub fn with_protocols(mut self, registrar: ProtocolRegistrar) -> Self {
+        self.protocol_registrars.push(registrar);
+        self
+    }
+
+    /// Starts the node.
+    ///
+    /// The startup opens the database and spawns the services, which blocks the current thread,
+    /// so it must be awaited on a multi-thread runtime. Use [`start_blocking`] in the synchronous
+    /// context.
+    ///
+    /// [`start_blocking`]: #method.start_blocking
+    pub async fn start(self) -> Result<Node, ExitCode> {
+        block_in_place(|| self.start_blocking())
+    }
+
+    /// Starts the node, blocking the current thread until all the services are spawned.
+    pub fn start_blocking(self) -> Result<Node, ExitCode> {
+        let spec = match self.spec {
+            Some(spec) => spec,
+            None => ChainSpec::load_from(&self.config.chain.spec).map_err(|err| {
+                eprintln!("{}", err);
+                ExitCode::Config
+            })?,
+        };
+        let consensus = spec.build_consensus().map_err(|err| {
+            eprintln!("chainspec error: {}", err);
+            ExitCode::Config
+        })?;
+        let version = self.version.unwrap_or_else(default_version);
+        let (async_handle, runtime_stop) = match self.async_handle {
+            Some(handle) => (handle, None),
+            None => {
+                let (handle, stop) = new_global_runtime();
+                (handle, Some(stop))
+            }
+        };
+        let args = RunArgs {
+            config: Box::new(self.config),
+            consensus,
+            block_assembler_advanced: self.block_assembler_advanced,
+            skip_chain_spec_check: self.skip_chain_spec_check,
+            overwrite_chain_spec: false,
+            chain_spec_hash: spec.hash,
+            check: false,
+            sandbox: false,
+        };
+
+        info!("ckb version: {}", version);
+        let mut launcher = self.protocol_registrars.into_iter().fold(
+            Launcher::new(args, version, async_handle),
+            |launcher, registrar| launcher.register_protocols(registrar),
+        );
+
+        let exit_handler = DefaultExitHandler::default();
+        let services = launcher.start(&exit_handler)?;
+
+        Ok(Node {
+            shared: services.shared,
+            chain_controller: services.chain_controller,
+            network_controller: services.network_controller,
+            exit_handler,
+            rpc_server: Some(services.rpc_server),
+            background: services.background,
+            freezer: services.freezer,
+            runtime_stop,
+        })
+    }
+}
+
+/// An embedded CKB full node started by [`NodeBuilder`].
+///
+/// The node is shut down when it is dropped.
+///
+/// [`NodeBuilder`]: struct.NodeBuilder.html
+pub struct Node {
+    shared: Shared,
+    chain_controller: ChainController,
+    network_controller: NetworkController,
+    exit_handler: DefaultExitHandler,
+    rpc_server: Option<RpcServer>,
+    background: BackgroundServices,
+    freezer: Option<FreezerClose>,
+    // Stops the runtime created by the builder
+    runtime_stop: Option<StopHandler<()>>,
+}
+
+impl Node {
+    /// Returns the shared state, which gives access to the store, the snapshot and the consensus.
+    pub fn shared(&self) -> &Shared {
+        &self.shared
+    }
+
+    /// Returns the controller of the chain service.
+    pub fn chain_controller(&self) -> &ChainController {
+        &self.chain_controller
+    }
+
+    /// Returns the controller of the tx-pool service.
+    pub fn tx_pool_controller(&self) -> &TxPoolController {
+        self.shared.tx_pool_controller()
+    }
+
+    /// Returns the controller of the network service.
+    pub fn network_controller(&self) -> &NetworkController {
+        &self.network_controller
+    }
+
+    /// Returns the exit handler, which is notified when a service exits on a fatal error.
+    pub fn exit_handler(&self) -> &DefaultExitHandler {
+        &self.exit_handler
+    }
+
+    /// Blocks the current thread until the node is requested to exit.
+    pub fn wait_for_exit(&self) {
+        self.exit_handler.wait_for_exit();
+    }
+
+    /// Shuts down the node.
+    pub fn shutdown(self) {
+        drop(self);
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        info!("Finishing work, please wait...");
+        self.exit_handler.notify_exit();
+        Launcher::shutdown_coordinator(
+            &self.shared,
+            &self.chain_controller,
+            &self.network_controller,
+            self.rpc_server.take(),
+            std::mem::take(&mut self.background),
+            self.freezer.take(),
+        )
+        .shutdown();
+        if let Some(mut stop) = self.runtime_stop.take() {
+            stop.try_send(());
+        }
+    }
+}
+
+fn default_version() -> Version {
+    let pre = env!("CARGO_PKG_VERSION_PRE");
+    Version {
+        major: env!("CARGO_PKG_VERSION_MAJOR")
+            .parse()
+            .expect("CARGO_PKG_VERSION_MAJOR parse success"),
+        minor: env!("CARGO_PKG_VERSION_MINOR")
+            .parse()
+            .expect("CARGO_PKG_VERSION_MINOR parse success"),
+        patch: env!("CARGO_PKG_VERSION_PATCH")
+            .parse()
+            .expect("CARGO_PKG_VERSION_PATCH parse success"),
+        dash_pre: if pre.is_empty() {
+            String::new()
+        } else {
+            format!("-{}", pre)
+        },
+        code_name: None,
+        commit_describe: None,
+        commit_date: None,
+    }
+}
@@ -0,0 +1,40 @@
+use ckb_app_config::{cli, AppConfig};
+use ckb_chain_spec::ChainSpec;
+use ckb_node::NodeBuilder;
+use ckb_resource::{Resource, TemplateContext};
+
+#[test]
+fn test_start_and_shutdown() {
+    let dir = tempfile::Builder::new()
+        .prefix("ckb_node_test")
+        .tempdir()
+        .unwrap();
+    let context = TemplateContext::new(
+        "dev",
+        vec![
+            ("rpc_port", "0"),
+            ("p2p_port", "0"),
+            ("log_to_file", "false"),
+            ("log_to_stdout", "false"),
+            ("block_assembler", ""),
+            ("spec_source", "bundled"),
+        ],
+    );
+    Resource::bundled_ckb_config()
+        .export(&context, dir.path())
+        .expect("export config files");
+    let config = AppConfig::load_for_subcommand(dir.path(), cli::CMD_RUN)
+        .and_then(AppConfig::into_ckb)
+        .expect("load the config");
+    let spec = ChainSpec::load_from(&Resource::bundled("specs/dev.toml".to_string()))
+        .expect("load the dev spec");
+
+    let node = NodeBuilder::new(*config)
+        .with_spec(spec)
+        .start_blocking()
+        .expect("start the node");
+    assert_eq!(node.shared().snapshot().tip_number(), 0);
+    assert!(node.tx_pool_controller().service_started());
+
+    node.shutdown();
+}
@@ -71,6 +71,25 @@ impl<'a, CS: ChainStore<'a>> RewardCalculator<'a, CS> {
         self.block_reward_internal(target, &parent)
     }
 
+    /// Projects the `target` block miner's lock and total block reward before the reward is
+    /// finalized, using `tip` as the last block of the chain.
+    ///
+    /// The primary and secondary block rewards and the committed transactions fees are final. The
+    /// proposals reward only counts the transactions committed up to `tip`, it is an estimate
+    /// which may increase until the block `target + PROPOSAL_WINDOW.farthest` is appended.
+    pub fn pending_block_reward(
+        &self,
+        target: &HeaderView,
+        tip: &HeaderView,
+    ) -> Result<(Script, BlockReward), Error> {
+        debug_assert!(
+            tip.number() >= target.number()
+                && tip.number() < target.number() + self.consensus.finalization_delay_length(),
+            "the reward of the target block is pending"
+        );
+        self.block_reward_internal(target, tip)
+    }
+
     fn block_reward_internal(
         &self,
         target: &HeaderView,
@@ -166,15 +185,34 @@ impl<'a, CS: ChainStore<'a>> RewardCalculator<'a, CS> {
 
         let mut reward = Capacity::zero();
 
+        // The parent is below the commit window of the target when projecting the pending reward
+        if parent.number() < target.number() + proposal_window.closest() {
+            return Ok(reward);
+        }
+
         // Transaction can be committed at height H(c): H(c) > H(w_close)
         let competing_commit_start = cmp::max(
             block_number.saturating_sub(proposal_window.length()),
-            1 + proposal_window.closest(),
+            target.number() + proposal_window.closest(),
         );
 
         let mut proposed: HashSet<ProposalShortId> = HashSet::new();
         let mut index = parent.to_owned();
 
+        // When projecting the pending reward, the parent is below `target + w_far`, so the
+        // proposals between its proposal window start and the target are competing as well.
+        let competing_proposal_start = cmp::max(
+            parent.number().saturating_sub(proposal_window.farthest()),
+            1,
+        );
+        for number in competing_proposal_start..target.number() {
+            let previous_ids = store
+                .get_block_hash(number)
+                .map(|hash| self.get_proposal_ids_by_hash(&hash))
+                .expect("competing proposal block exist");
+            proposed.extend(previous_ids);
+        }
+
         // NOTE: We have to ensure that `committed_idx_proc` and `txs_fees_proc` return in the
         // same order, the order of transactions in block.
         let committed_idx_proc = |hash: &Byte32| -> Vec<ProposalShortId> {
@@ -204,8 +242,9 @@ impl<'a, CS: ChainStore<'a>> RewardCalculator<'a, CS> {
                 .into_iter()
                 .zip(txs_fees_proc(&index.hash()).iter())
             {
-                // target block is the earliest block with effective proposals for the parent block
-                if target_proposals.remove(&id) {
+                // target block is the earliest block with effective proposals for the parent block,
+                // unless the parent is below `target + w_far`
+                if target_proposals.remove(&id) && !proposed.contains(&id) {
                     reward = reward.safe_add(tx_fee.safe_mul_ratio(proposer_ratio)?)?;
                 }
             }
@@ -569,5 +608,17 @@ mod tests {
         let expected: u32 = [300u32, 41u32, 999u32].iter().map(|x| x * 4 / 10).sum();
 
         assert_eq!(proposal_reward, expected.into_capacity());
+
+        // The pending proposal reward projected at block 14 is zero, it's below the commit window
+        let pending_proposal_reward = reward_calculator
+            .proposal_reward(&block_14.header(), &block_13.header())
+            .unwrap();
+        assert_eq!(pending_proposal_reward, 0u32.into_capacity());
+
+        // The pending proposal reward projected at block 15 only counts p4
+        let pending_proposal_reward = reward_calculator
+            .proposal_reward(&block_15.header(), &block_13.header())
+            .unwrap();
+        assert_eq!(pending_proposal_reward, (300u32 * 4 / 10).into_capacity());
     }
 }
@@ -16,7 +16,7 @@ use ckb_traits::HeaderProvider;
 use ckb_types::core::error::OutPointError;
 use ckb_types::{
     core::{
-        cell::{CellChecker, CellProvider, CellStatus, HeaderChecker},
+        cell::{CellChecker, CellMeta, CellProvider, CellStatus, HeaderChecker},
         BlockNumber, BlockReward, EpochExt, HeaderView,
     },
     packed::{Byte32, OutPoint, Script},
@@ -145,6 +145,20 @@ impl Snapshot {
     ) -> Result<(Script, BlockReward), Error> {
         RewardCalculator::new(self.consensus(), self).block_reward_to_finalize(parent)
     }
+
+    /// Iterates the live cells of the chain at the tip of the snapshot, without the indexer.
+    ///
+    /// The iterator reads the cell set from the same DB snapshot, so a full scan is consistent
+    /// with [`tip_header`](#method.tip_header) even when new blocks are committed meanwhile. See
+    /// [`StoreSnapshot::live_cells_iter`](../ckb_store/struct.StoreSnapshot.html#method.live_cells_iter)
+    /// for the parameters.
+    pub fn live_cells_iter<'a>(
+        &'a self,
+        lock_prefix: &'a [u8],
+        cursor: Option<&OutPoint>,
+    ) -> impl Iterator<Item = CellMeta> + 'a {
+        self.store.live_cells_iter(lock_prefix, cursor)
+    }
 }
 
 impl<'a> ChainStore<'a> for Snapshot {
@@ -2,6 +2,7 @@
 //!
 //! Collection of frequently used utilities.
 mod linked_hash_set;
+pub mod mock_clock;
 mod shrink_to_fit;
 pub mod strings;
 
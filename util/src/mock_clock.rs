@@ -0,0 +1,63 @@
+//! The process-wide mock clock used by the sandbox simulation, see `ckb run --sandbox`.
+//!
+//! When the mock clock is enabled, the time read by the block assembler, the header verifier and
+//! the initial block download check only moves when it is advanced explicitly, so the block
+//! timestamps, the median times and the epoch durations are deterministic.
+use std::cmp;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// The mock time in milliseconds, zero means the mock clock is disabled.
+static MOCK_MILLIS: AtomicU64 = AtomicU64::new(0);
+
+/// Enables the mock clock starting at the timestamp in milliseconds.
+pub fn enable(millis: u64) {
+    MOCK_MILLIS.store(cmp::max(millis, 1), Ordering::SeqCst);
+}
+
+/// Whether the mock clock is enabled.
+pub fn is_enabled() -> bool {
+    MOCK_MILLIS.load(Ordering::SeqCst) != 0
+}
+
+/// Advances the mock clock by the milliseconds and returns the new time.
+///
+/// Returns `None` when the mock clock is disabled.
+pub fn advance(millis: u64) -> Option<u64> {
+    MOCK_MILLIS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |now| {
+            if now == 0 {
+                None
+            } else {
+                Some(now.saturating_add(millis))
+            }
+        })
+        .ok()
+        .map(|now| now.saturating_add(millis))
+}
+
+/// Returns the mock time when the mock clock is enabled, otherwise the system time.
+///
+/// The system time can be faked in tests via [`faketime`](https://docs.rs/faketime).
+pub fn unix_time_as_millis() -> u64 {
+    match MOCK_MILLIS.load(Ordering::SeqCst) {
+        0 => faketime::unix_time_as_millis(),
+        now => now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        assert!(!is_enabled());
+        assert_eq!(advance(1000), None);
+
+        enable(5000);
+        assert!(is_enabled());
+        assert_eq!(unix_time_as_millis(), 5000);
+        assert_eq!(advance(1000), Some(6000));
+        assert_eq!(unix_time_as_millis(), 6000);
+    }
+}
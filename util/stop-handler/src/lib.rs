@@ -8,6 +8,10 @@ use std::thread::JoinHandle;
 use tokio::sync::oneshot as tokio_oneshot;
 use tokio::sync::watch as tokio_watch;
 
+mod shutdown;
+
+pub use shutdown::ShutdownCoordinator;
+
 /// init flags
 pub const WATCH_INIT: u8 = 0;
 /// stop flags
@@ -58,6 +62,27 @@ impl<T: Debug> SignalSender<T> {
 struct Handler<T> {
     signal: SignalSender<T>,
     thread: Option<JoinHandle<T>>,
+    done: Option<ckb_channel::Receiver<()>>,
+}
+
+impl<T: Debug> Handler<T> {
+    fn stop(self, cmd: T) {
+        let Handler {
+            signal,
+            thread,
+            done,
+        } = self;
+        signal.send(cmd);
+        if let Some(thread) = thread {
+            if let Err(e) = thread.join() {
+                error!("handler thread join error {:?}", e);
+            };
+        }
+        if let Some(done) = done {
+            // Disconnected when all the senders are dropped
+            let _ = done.recv();
+        }
+    }
 }
 
 /// TODO(doc): @keroro520
@@ -71,7 +96,26 @@ pub struct StopHandler<T> {
 impl<T: Debug> StopHandler<T> {
     /// TODO(doc): @keroro520
     pub fn new(signal: SignalSender<T>, thread: Option<JoinHandle<T>>) -> StopHandler<T> {
-        let handler = Handler { signal, thread };
+        let handler = Handler {
+            signal,
+            thread,
+            done: None,
+        };
+        StopHandler {
+            inner: Some(Arc::new(Mutex::new(Some(handler)))),
+        }
+    }
+
+    /// Creates a handler of the service running in the async tasks, which has no thread to join.
+    ///
+    /// The stop waits until all the senders of `done` are dropped, so each task of the service
+    /// should hold a clone of the sender until it exits.
+    pub fn with_done(signal: SignalSender<T>, done: ckb_channel::Receiver<()>) -> StopHandler<T> {
+        let handler = Handler {
+            signal,
+            thread: None,
+            done: Some(done),
+        };
         StopHandler {
             inner: Some(Arc::new(Mutex::new(Some(handler)))),
         }
@@ -84,14 +128,49 @@ impl<T: Debug> StopHandler<T> {
             .take()
             .expect("Stop signal can only be sent once");
         if let Ok(lock) = Arc::try_unwrap(inner) {
-            let handler = lock.lock().take().expect("Handler can only be taken once");
-            let Handler { signal, thread } = handler;
-            signal.send(cmd);
-            if let Some(thread) = thread {
-                if let Err(e) = thread.join() {
-                    error!("handler thread join error {:?}", e);
-                };
+            // The handler has been taken when the signal is forced by `force_send`.
+            if let Some(handler) = lock.into_inner() {
+                handler.stop(cmd);
             }
         };
     }
+
+    /// Sends the stop signal and waits for the thread, even if the handler is still shared by
+    /// other clones.
+    ///
+    /// The signal is sent only once, the later `try_send` and `force_send` of all the clones do
+    /// nothing.
+    pub fn force_send(&self, cmd: T) {
+        let handler = self.inner.as_ref().and_then(|inner| inner.lock().take());
+        if let Some(handler) = handler {
+            handler.stop(cmd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stop_waits_for_done() {
+        let (signal_sender, signal_receiver) = ckb_channel::bounded(1);
+        let (done_sender, done_receiver) = ckb_channel::bounded(0);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stopped_clone = Arc::clone(&stopped);
+        thread::spawn(move || {
+            let _done = done_sender;
+            signal_receiver.recv().unwrap();
+            thread::sleep(Duration::from_millis(100));
+            stopped_clone.store(true, Ordering::SeqCst);
+        });
+
+        let handler =
+            StopHandler::with_done(SignalSender::Crossbeam(signal_sender), done_receiver);
+        handler.force_send(());
+        assert!(stopped.load(Ordering::SeqCst));
+    }
 }
@@ -0,0 +1,303 @@
+//! Coordinated shutdown of the services.
+use ckb_logger::{error, info, warn};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+type StopFn = Box<dyn FnOnce() + Send + 'static>;
+
+struct Component {
+    name: &'static str,
+    dependencies: Vec<&'static str>,
+    timeout: Duration,
+    stop: StopFn,
+}
+
+struct Barrier {
+    name: &'static str,
+    dependencies: Vec<&'static str>,
+    flush: StopFn,
+}
+
+/// Stops the components in the dependency order, then runs the flush barriers.
+///
+/// A component is stopped only after all the components depending on it have been stopped, for
+/// example, the chain service depends on the tx-pool, so the chain stops committing blocks before
+/// the tx-pool is stopped.
+///
+/// Each component is stopped in its own thread and waited for at most its timeout. The shutdown
+/// moves on when a component times out, so a stuck service does not block the others.
+///
+/// The flush barriers run in the registration order after all the components are stopped or
+/// timed out, such as closing the freezer. A barrier is skipped when a component or a barrier it
+/// depends on is not stopped cleanly, because that component may still be writing.
+///
+/// ## Examples
+///
+/// ```
+/// use ckb_stop_handler::ShutdownCoordinator;
+/// use std::time::Duration;
+///
+/// let mut coordinator = ShutdownCoordinator::new();
+/// coordinator.register("tx-pool", &[], Duration::from_secs(1), || {});
+/// coordinator.register("chain", &["tx-pool"], Duration::from_secs(1), || {});
+/// assert_eq!(coordinator.stop_order(), vec!["chain", "tx-pool"]);
+/// assert!(coordinator.shutdown().is_empty());
+/// ```
+#[derive(Default)]
+pub struct ShutdownCoordinator {
+    components: Vec<Component>,
+    barriers: Vec<Barrier>,
+}
+
+impl ShutdownCoordinator {
+    /// Creates a coordinator without any components.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a component.
+    ///
+    /// * `name` - the component name in the logs and the dependencies.
+    /// * `dependencies` - the names of the components which must be stopped after this one. The
+    ///   names not registered are ignored.
+    /// * `timeout` - how long to wait for `stop`.
+    /// * `stop` - stops the component and returns when it is stopped.
+    pub fn register<F>(
+        &mut self,
+        name: &'static str,
+        dependencies: &[&'static str],
+        timeout: Duration,
+        stop: F,
+    ) where
+        F: FnOnce() + Send + 'static,
+    {
+        self.components.push(Component {
+            name,
+            dependencies: dependencies.to_vec(),
+            timeout,
+            stop: Box::new(stop),
+        });
+    }
+
+    /// Registers a flush barrier, which runs after all the components.
+    ///
+    /// * `name` - the barrier name in the logs and the dependencies.
+    /// * `dependencies` - the names of the components and the earlier barriers which must be
+    ///   stopped cleanly before the barrier runs, otherwise the barrier is skipped.
+    /// * `flush` - flushes the data.
+    pub fn register_barrier<F>(
+        &mut self,
+        name: &'static str,
+        dependencies: &[&'static str],
+        flush: F,
+    ) where
+        F: FnOnce() + Send + 'static,
+    {
+        self.barriers.push(Barrier {
+            name,
+            dependencies: dependencies.to_vec(),
+            flush: Box::new(flush),
+        });
+    }
+
+    /// Returns the component names in the stop order.
+    pub fn stop_order(&self) -> Vec<&'static str> {
+        self.stop_order_indices()
+            .into_iter()
+            .map(|i| self.components[i].name)
+            .collect()
+    }
+
+    // Picks the first component in the registration order whose dependents are all stopped. The
+    // components in a dependency cycle are stopped in the registration order.
+    fn stop_order_indices(&self) -> Vec<usize> {
+        let count = self.components.len();
+        let mut dependents: Vec<usize> = self
+            .components
+            .iter()
+            .map(|component| {
+                self.components
+                    .iter()
+                    .filter(|other| other.dependencies.contains(&component.name))
+                    .count()
+            })
+            .collect();
+        let mut stopped = vec![false; count];
+        let mut order = Vec::with_capacity(count);
+
+        while order.len() < count {
+            let next = match (0..count).find(|&i| !stopped[i] && dependents[i] == 0) {
+                Some(next) => next,
+                None => {
+                    let next = (0..count)
+                        .find(|&i| !stopped[i])
+                        .expect("there are components not stopped");
+                    warn!(
+                        "Shutdown dependency cycle found, stop {} first",
+                        self.components[next].name
+                    );
+                    next
+                }
+            };
+            stopped[next] = true;
+            order.push(next);
+            for (i, component) in self.components.iter().enumerate() {
+                if !stopped[i] && self.components[next].dependencies.contains(&component.name) {
+                    dependents[i] = dependents[i].saturating_sub(1);
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Stops all the components and runs the flush barriers.
+    ///
+    /// Returns the names of the components which are not stopped cleanly, because they timed out
+    /// or panicked, followed by the names of the skipped barriers.
+    pub fn shutdown(self) -> Vec<&'static str> {
+        let order = self.stop_order_indices();
+        let mut components: Vec<_> = self.components.into_iter().map(Some).collect();
+        let mut unclean = Vec::new();
+
+        for i in order {
+            let Component {
+                name,
+                timeout,
+                stop,
+                ..
+            } = components[i]
+                .take()
+                .expect("each component is stopped once");
+            info!("Stopping {}", name);
+            let (done_sender, done_receiver) = mpsc::channel();
+            let spawned = thread::Builder::new()
+                .name(format!("Stop-{}", name))
+                .spawn(move || {
+                    stop();
+                    let _ = done_sender.send(());
+                });
+            if let Err(err) = spawned {
+                error!("Failed to spawn the thread to stop {}: {}", name, err);
+                unclean.push(name);
+                continue;
+            }
+            match done_receiver.recv_timeout(timeout) {
+                Ok(()) => info!("{} stopped", name),
+                Err(RecvTimeoutError::Timeout) => {
+                    error!("{} is not stopped in {:?}, skip it", name, timeout);
+                    unclean.push(name);
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    error!("{} panicked while stopping", name);
+                    unclean.push(name);
+                }
+            }
+        }
+
+        for Barrier {
+            name,
+            dependencies,
+            flush,
+        } in self.barriers
+        {
+            if let Some(dependency) = dependencies.iter().find(|dep| unclean.contains(*dep)) {
+                error!(
+                    "Skip flushing {} because {} is not stopped cleanly",
+                    name, dependency
+                );
+                unclean.push(name);
+                continue;
+            }
+            info!("Flushing {}", name);
+            flush();
+        }
+
+        unclean
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_stop_order() {
+        let mut coordinator = ShutdownCoordinator::new();
+        coordinator.register("notify", &[], Duration::from_secs(1), || {});
+        coordinator.register("tx-pool", &["notify"], Duration::from_secs(1), || {});
+        coordinator.register(
+            "chain",
+            &["tx-pool", "notify"],
+            Duration::from_secs(1),
+            || {},
+        );
+        coordinator.register("rpc", &["chain", "unknown"], Duration::from_secs(1), || {});
+        assert_eq!(
+            coordinator.stop_order(),
+            vec!["rpc", "chain", "tx-pool", "notify"]
+        );
+
+        // The cycle falls back to the registration order.
+        let mut coordinator = ShutdownCoordinator::new();
+        coordinator.register("a", &["b"], Duration::from_secs(1), || {});
+        coordinator.register("b", &["a"], Duration::from_secs(1), || {});
+        coordinator.register("c", &["a"], Duration::from_secs(1), || {});
+        assert_eq!(coordinator.stop_order(), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_shutdown() {
+        let stopped = Arc::new(Mutex::new(Vec::new()));
+        let mut coordinator = ShutdownCoordinator::new();
+
+        let stopped_clone = Arc::clone(&stopped);
+        coordinator.register_barrier("db", &["tx-pool", "chain"], move || {
+            stopped_clone.lock().unwrap().push("db")
+        });
+        let stopped_clone = Arc::clone(&stopped);
+        coordinator.register("tx-pool", &[], Duration::from_secs(5), move || {
+            stopped_clone.lock().unwrap().push("tx-pool")
+        });
+        coordinator.register("stuck", &["tx-pool"], Duration::from_millis(10), || {
+            thread::sleep(Duration::from_secs(1))
+        });
+        coordinator.register("panic", &["stuck"], Duration::from_secs(5), || {
+            panic!("panic")
+        });
+        let stopped_clone = Arc::clone(&stopped);
+        coordinator.register("chain", &["tx-pool"], Duration::from_secs(5), move || {
+            stopped_clone.lock().unwrap().push("chain")
+        });
+
+        assert_eq!(coordinator.shutdown(), vec!["panic", "stuck"]);
+        assert_eq!(*stopped.lock().unwrap(), vec!["chain", "tx-pool", "db"]);
+    }
+
+    #[test]
+    fn test_skip_barriers_after_timeout() {
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let mut coordinator = ShutdownCoordinator::new();
+        coordinator.register("chain", &[], Duration::from_millis(10), || {
+            thread::sleep(Duration::from_secs(1))
+        });
+        coordinator.register("notify", &[], Duration::from_secs(5), || {});
+        let flushed_clone = Arc::clone(&flushed);
+        coordinator.register_barrier("freezer", &["chain"], move || {
+            flushed_clone.lock().unwrap().push("freezer")
+        });
+        let flushed_clone = Arc::clone(&flushed);
+        coordinator.register_barrier("db", &["freezer"], move || {
+            flushed_clone.lock().unwrap().push("db")
+        });
+        let flushed_clone = Arc::clone(&flushed);
+        coordinator.register_barrier("logs", &["notify"], move || {
+            flushed_clone.lock().unwrap().push("logs")
+        });
+
+        assert_eq!(coordinator.shutdown(), vec!["chain", "freezer", "db"]);
+        assert_eq!(*flushed.lock().unwrap(), vec!["logs"]);
+    }
+}
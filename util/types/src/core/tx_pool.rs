@@ -3,7 +3,7 @@ use crate::core::{
     error::{OutPointError, TransactionError},
     Capacity, Cycle, FeeRate,
 };
-use crate::packed::Byte32;
+use crate::packed::{Byte32, OutPoint};
 use ckb_error::{
     impl_error_conversion_with_kind, prelude::*, Error, ErrorKind, InternalError, InternalErrorKind,
 };
@@ -68,12 +68,26 @@ impl Reject {
             _ => false,
         }
     }
+
+    /// Returns the index of the input whose `since` is not satisfied yet if the reject reason is
+    /// the immature transaction.
+    pub fn immature_input(&self) -> Option<usize> {
+        match self {
+            Reject::Verification(err) if err.kind() == ErrorKind::Transaction => {
+                match err.downcast_ref::<TransactionError>() {
+                    Some(TransactionError::Immature { index }) => Some(*index),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
 impl_error_conversion_with_kind!(Reject, ErrorKind::SubmitTransaction, Error);
 
 /// Tx-pool transaction status
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TxStatus {
     /// Status "pending". The transaction is in the pool, and not proposed yet.
     Pending,
@@ -115,3 +129,108 @@ pub struct TxPoolEntryInfo {
     /// Proposed transaction entry info
     pub proposed: HashMap<Byte32, TxEntryInfo>,
 }
+
+/// An in-pool transaction which spends the same inputs as another transaction
+#[derive(Debug, PartialEq, Eq)]
+pub struct TxConflict {
+    /// The conflicting transaction hash
+    pub tx_hash: Byte32,
+    /// The status of the conflicting transaction
+    pub status: TxStatus,
+    /// The conflicting transaction fee
+    pub fee: Capacity,
+    /// The conflicting transaction fee rate, computed from the fee and the virtual bytes
+    pub fee_rate: FeeRate,
+    /// The inputs spent by both transactions
+    pub inputs: Vec<OutPoint>,
+    /// The cells spent by one transaction and used as cell deps by the other
+    pub deps: Vec<OutPoint>,
+}
+
+/// The in-pool transactions conflicting with an in-pool transaction
+#[derive(Debug, PartialEq, Eq)]
+pub struct TxPoolConflicts {
+    /// The transaction hash
+    pub tx_hash: Byte32,
+    /// The transaction status
+    pub status: TxStatus,
+    /// The transaction fee rate, computed from the fee and the virtual bytes
+    pub fee_rate: FeeRate,
+    /// The conflicting transactions
+    pub conflicts: Vec<TxConflict>,
+}
+
+/// The order of the entries returned by the pool entries inspection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolEntriesOrder {
+    /// The highest effective fee rate first
+    FeeRate,
+    /// The oldest entry first
+    Timestamp,
+}
+
+/// The detailed info of an in-pool transaction
+#[derive(Debug, PartialEq, Eq)]
+pub struct TxPoolEntryDetail {
+    /// The transaction hash
+    pub tx_hash: Byte32,
+    /// The transaction status
+    pub status: TxStatus,
+    /// Consumed cycles
+    pub cycles: Cycle,
+    /// The transaction serialized size in block
+    pub size: u64,
+    /// The transaction fee
+    pub fee: Capacity,
+    /// The transaction fee rate, computed from the fee and the virtual bytes
+    pub fee_rate: FeeRate,
+    /// The lower one of the transaction fee rate and the fee rate of the package containing the
+    /// transaction and its in-pool ancestors, which are only linked in the proposed queue
+    pub effective_fee_rate: FeeRate,
+    /// Number of in-pool ancestor transactions, excluding the transaction itself
+    pub ancestors_count: u64,
+    /// Size of in-pool ancestor transactions
+    pub ancestors_size: u64,
+    /// Cycles of in-pool ancestor transactions
+    pub ancestors_cycles: Cycle,
+    /// Fee of in-pool ancestor transactions
+    pub ancestors_fee: Capacity,
+    /// Number of in-pool descendant transactions, excluding the transaction itself
+    pub descendants_count: u64,
+    /// Size of in-pool descendant transactions
+    pub descendants_size: u64,
+    /// Cycles of in-pool descendant transactions
+    pub descendants_cycles: Cycle,
+    /// Fee of in-pool descendant transactions
+    pub descendants_fee: Capacity,
+    /// The unix timestamp in milliseconds when the transaction entered its current queue
+    pub timestamp: u64,
+    /// The estimated risk in percent that the transaction leaves the pool without being committed
+    pub eviction_score: u8,
+}
+
+/// A page of the in-pool transaction details
+#[derive(Debug, PartialEq, Eq)]
+pub struct TxPoolEntryDetails {
+    /// The entries in the page
+    pub entries: Vec<TxPoolEntryDetail>,
+    /// Number of transactions in the pool
+    pub total: u64,
+    /// The cursor to get the next page, `None` when this is the last page
+    pub next_cursor: Option<Byte32>,
+}
+
+/// A local transaction staged until its inputs `since` are satisfied
+#[derive(Debug, PartialEq, Eq)]
+pub struct StagedTxInfo {
+    /// The transaction hash
+    pub tx_hash: Byte32,
+    /// The index of the input whose `since` was not satisfied in the last submission
+    pub immature_input: usize,
+    /// The `since` of the immature input
+    pub since: u64,
+    /// The unix timestamp in milliseconds when the transaction was staged
+    pub staged_at: u64,
+    /// The unix timestamp in milliseconds when the transaction is dropped if still immature
+    pub expires_at: u64,
+}
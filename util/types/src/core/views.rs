@@ -10,7 +10,7 @@ use crate::{
     core::{BlockNumber, Capacity, EpochNumberWithFraction, Version},
     packed,
     prelude::*,
-    utilities::merkle_root,
+    utilities::{merkle_root, par_merkle_root},
     U256,
 };
 
@@ -758,12 +758,12 @@ impl BlockView {
 
     /// Calculates the merkel root for transactions without witnesses.
     pub fn calc_raw_transactions_root(&self) -> packed::Byte32 {
-        merkle_root(&self.tx_hashes[..])
+        par_merkle_root(&self.tx_hashes[..])
     }
 
     /// Calculates the merkel root for transaction witnesses.
     pub fn calc_witnesses_root(&self) -> packed::Byte32 {
-        merkle_root(&self.tx_witness_hashes[..])
+        par_merkle_root(&self.tx_witness_hashes[..])
     }
 }
 
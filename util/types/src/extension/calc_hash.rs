@@ -1,7 +1,13 @@
 use ckb_hash::{blake2b_256, new_blake2b};
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
 
 use crate::{core, packed, prelude::*};
 
+/// The transactions of a block are hashed in parallel from this count.
+#[cfg(not(target_arch = "wasm32"))]
+const PARALLEL_TX_HASHES_THRESHOLD: usize = 64;
+
 /*
  * Calculate simple hash for packed bytes wrappers.
  */
@@ -265,19 +271,41 @@ impl<'r> packed::BlockReader<'r> {
     }
 
     /// Calculates transaction hashes for all transactions in the block.
+    ///
+    /// The transactions are hashed in parallel when there are many of them.
     pub fn calc_tx_hashes(&self) -> Vec<packed::Byte32> {
-        self.transactions()
-            .iter()
-            .map(|tx| tx.calc_tx_hash())
-            .collect::<Vec<_>>()
+        self.map_transactions(|tx| tx.calc_tx_hash())
     }
 
     /// Calculates transaction witness hashes for all transactions in the block.
+    ///
+    /// The transactions are hashed in parallel when there are many of them.
     pub fn calc_tx_witness_hashes(&self) -> Vec<packed::Byte32> {
-        self.transactions()
-            .iter()
-            .map(|tx| tx.calc_witness_hash())
-            .collect::<Vec<_>>()
+        self.map_transactions(|tx| tx.calc_witness_hash())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn map_transactions<F>(&self, f: F) -> Vec<packed::Byte32>
+    where
+        F: Fn(packed::TransactionReader<'r>) -> packed::Byte32 + Sync + Send,
+    {
+        let txs = self.transactions();
+        if txs.len() < PARALLEL_TX_HASHES_THRESHOLD {
+            txs.iter().map(f).collect()
+        } else {
+            (0..txs.len())
+                .into_par_iter()
+                .map(|i| f(txs.get_unchecked(i)))
+                .collect()
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn map_transactions<F>(&self, f: F) -> Vec<packed::Byte32>
+    where
+        F: Fn(packed::TransactionReader<'r>) -> packed::Byte32,
+    {
+        self.transactions().iter().map(f).collect()
     }
 }
 
@@ -5,7 +5,7 @@ use crate::{
     core::{self, BlockNumber},
     packed,
     prelude::*,
-    utilities::{compact_to_difficulty, merkle_root},
+    utilities::{compact_to_difficulty, merkle_root, par_merkle_root},
     U256,
 };
 
@@ -189,8 +189,8 @@ impl packed::Block {
         tx_hashes: &[packed::Byte32],
         tx_witness_hashes: &[packed::Byte32],
     ) -> packed::Block {
-        let raw_transactions_root = merkle_root(tx_hashes);
-        let witnesses_root = merkle_root(tx_witness_hashes);
+        let raw_transactions_root = par_merkle_root(tx_hashes);
+        let witnesses_root = par_merkle_root(tx_witness_hashes);
         let transactions_root = merkle_root(&[raw_transactions_root, witnesses_root]);
         let proposals_hash = self.as_reader().calc_proposals_hash();
         let extra_hash = self.as_reader().calc_extra_hash().extra_hash();
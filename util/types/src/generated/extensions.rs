@@ -4723,7 +4723,7 @@ impl ::core::default::Default for RelayMessage {
     }
 }
 impl RelayMessage {
-    pub const ITEMS_COUNT: usize = 8;
+    pub const ITEMS_COUNT: usize = 9;
     pub fn item_id(&self) -> molecule::Number {
         molecule::unpack_number(self.as_slice())
     }
@@ -4738,6 +4738,7 @@ impl RelayMessage {
             5 => BlockTransactions::new_unchecked(inner).into(),
             6 => GetBlockProposal::new_unchecked(inner).into(),
             7 => BlockProposal::new_unchecked(inner).into(),
+            8 => RelayFeeFilter::new_unchecked(inner).into(),
             _ => panic!("{}: invalid data", Self::NAME),
         }
     }
@@ -4794,7 +4795,7 @@ impl<'r> ::core::fmt::Display for RelayMessageReader<'r> {
     }
 }
 impl<'r> RelayMessageReader<'r> {
-    pub const ITEMS_COUNT: usize = 8;
+    pub const ITEMS_COUNT: usize = 9;
     pub fn item_id(&self) -> molecule::Number {
         molecule::unpack_number(self.as_slice())
     }
@@ -4809,6 +4810,7 @@ impl<'r> RelayMessageReader<'r> {
             5 => BlockTransactionsReader::new_unchecked(inner).into(),
             6 => GetBlockProposalReader::new_unchecked(inner).into(),
             7 => BlockProposalReader::new_unchecked(inner).into(),
+            8 => RelayFeeFilterReader::new_unchecked(inner).into(),
             _ => panic!("{}: invalid data", Self::NAME),
         }
     }
@@ -4842,6 +4844,7 @@ impl<'r> molecule::prelude::Reader<'r> for RelayMessageReader<'r> {
             5 => BlockTransactionsReader::verify(inner_slice, compatible),
             6 => GetBlockProposalReader::verify(inner_slice, compatible),
             7 => BlockProposalReader::verify(inner_slice, compatible),
+            8 => RelayFeeFilterReader::verify(inner_slice, compatible),
             _ => ve!(Self, UnknownItem, Self::ITEMS_COUNT, item_id),
         }?;
         Ok(())
@@ -4850,7 +4853,7 @@ impl<'r> molecule::prelude::Reader<'r> for RelayMessageReader<'r> {
 #[derive(Debug, Default)]
 pub struct RelayMessageBuilder(pub(crate) RelayMessageUnion);
 impl RelayMessageBuilder {
-    pub const ITEMS_COUNT: usize = 8;
+    pub const ITEMS_COUNT: usize = 9;
     pub fn set<I>(mut self, v: I) -> Self
     where
         I: ::core::convert::Into<RelayMessageUnion>,
@@ -4886,6 +4889,7 @@ pub enum RelayMessageUnion {
     BlockTransactions(BlockTransactions),
     GetBlockProposal(GetBlockProposal),
     BlockProposal(BlockProposal),
+    RelayFeeFilter(RelayFeeFilter),
 }
 #[derive(Debug, Clone, Copy)]
 pub enum RelayMessageUnionReader<'r> {
@@ -4897,6 +4901,7 @@ pub enum RelayMessageUnionReader<'r> {
     BlockTransactions(BlockTransactionsReader<'r>),
     GetBlockProposal(GetBlockProposalReader<'r>),
     BlockProposal(BlockProposalReader<'r>),
+    RelayFeeFilter(RelayFeeFilterReader<'r>),
 }
 impl ::core::default::Default for RelayMessageUnion {
     fn default() -> Self {
@@ -4948,6 +4953,9 @@ impl ::core::fmt::Display for RelayMessageUnion {
             RelayMessageUnion::BlockProposal(ref item) => {
                 write!(f, "{}::{}({})", Self::NAME, BlockProposal::NAME, item)
             }
+            RelayMessageUnion::RelayFeeFilter(ref item) => {
+                write!(f, "{}::{}({})", Self::NAME, RelayFeeFilter::NAME, item)
+            }
         }
     }
 }
@@ -4996,6 +5004,9 @@ impl<'r> ::core::fmt::Display for RelayMessageUnionReader<'r> {
             RelayMessageUnionReader::BlockProposal(ref item) => {
                 write!(f, "{}::{}({})", Self::NAME, BlockProposal::NAME, item)
             }
+            RelayMessageUnionReader::RelayFeeFilter(ref item) => {
+                write!(f, "{}::{}({})", Self::NAME, RelayFeeFilter::NAME, item)
+            }
         }
     }
 }
@@ -5010,6 +5021,7 @@ impl RelayMessageUnion {
             RelayMessageUnion::BlockTransactions(ref item) => write!(f, "{}", item),
             RelayMessageUnion::GetBlockProposal(ref item) => write!(f, "{}", item),
             RelayMessageUnion::BlockProposal(ref item) => write!(f, "{}", item),
+            RelayMessageUnion::RelayFeeFilter(ref item) => write!(f, "{}", item),
         }
     }
 }
@@ -5024,6 +5036,7 @@ impl<'r> RelayMessageUnionReader<'r> {
             RelayMessageUnionReader::BlockTransactions(ref item) => write!(f, "{}", item),
             RelayMessageUnionReader::GetBlockProposal(ref item) => write!(f, "{}", item),
             RelayMessageUnionReader::BlockProposal(ref item) => write!(f, "{}", item),
+            RelayMessageUnionReader::RelayFeeFilter(ref item) => write!(f, "{}", item),
         }
     }
 }
@@ -5067,6 +5080,11 @@ impl ::core::convert::From<BlockProposal> for RelayMessageUnion {
         RelayMessageUnion::BlockProposal(item)
     }
 }
+impl ::core::convert::From<RelayFeeFilter> for RelayMessageUnion {
+    fn from(item: RelayFeeFilter) -> Self {
+        RelayMessageUnion::RelayFeeFilter(item)
+    }
+}
 impl<'r> ::core::convert::From<CompactBlockReader<'r>> for RelayMessageUnionReader<'r> {
     fn from(item: CompactBlockReader<'r>) -> Self {
         RelayMessageUnionReader::CompactBlock(item)
@@ -5107,6 +5125,11 @@ impl<'r> ::core::convert::From<BlockProposalReader<'r>> for RelayMessageUnionRea
         RelayMessageUnionReader::BlockProposal(item)
     }
 }
+impl<'r> ::core::convert::From<RelayFeeFilterReader<'r>> for RelayMessageUnionReader<'r> {
+    fn from(item: RelayFeeFilterReader<'r>) -> Self {
+        RelayMessageUnionReader::RelayFeeFilter(item)
+    }
+}
 impl RelayMessageUnion {
     pub const NAME: &'static str = "RelayMessageUnion";
     pub fn as_bytes(&self) -> molecule::bytes::Bytes {
@@ -5119,6 +5142,7 @@ impl RelayMessageUnion {
             RelayMessageUnion::BlockTransactions(item) => item.as_bytes(),
             RelayMessageUnion::GetBlockProposal(item) => item.as_bytes(),
             RelayMessageUnion::BlockProposal(item) => item.as_bytes(),
+            RelayMessageUnion::RelayFeeFilter(item) => item.as_bytes(),
         }
     }
     pub fn as_slice(&self) -> &[u8] {
@@ -5131,6 +5155,7 @@ impl RelayMessageUnion {
             RelayMessageUnion::BlockTransactions(item) => item.as_slice(),
             RelayMessageUnion::GetBlockProposal(item) => item.as_slice(),
             RelayMessageUnion::BlockProposal(item) => item.as_slice(),
+            RelayMessageUnion::RelayFeeFilter(item) => item.as_slice(),
         }
     }
     pub fn item_id(&self) -> molecule::Number {
@@ -5143,6 +5168,7 @@ impl RelayMessageUnion {
             RelayMessageUnion::BlockTransactions(_) => 5,
             RelayMessageUnion::GetBlockProposal(_) => 6,
             RelayMessageUnion::BlockProposal(_) => 7,
+            RelayMessageUnion::RelayFeeFilter(_) => 8,
         }
     }
     pub fn item_name(&self) -> &str {
@@ -5155,6 +5181,7 @@ impl RelayMessageUnion {
             RelayMessageUnion::BlockTransactions(_) => "BlockTransactions",
             RelayMessageUnion::GetBlockProposal(_) => "GetBlockProposal",
             RelayMessageUnion::BlockProposal(_) => "BlockProposal",
+            RelayMessageUnion::RelayFeeFilter(_) => "RelayFeeFilter",
         }
     }
     pub fn as_reader<'r>(&'r self) -> RelayMessageUnionReader<'r> {
@@ -5167,6 +5194,7 @@ impl RelayMessageUnion {
             RelayMessageUnion::BlockTransactions(item) => item.as_reader().into(),
             RelayMessageUnion::GetBlockProposal(item) => item.as_reader().into(),
             RelayMessageUnion::BlockProposal(item) => item.as_reader().into(),
+            RelayMessageUnion::RelayFeeFilter(item) => item.as_reader().into(),
         }
     }
 }
@@ -5182,6 +5210,7 @@ impl<'r> RelayMessageUnionReader<'r> {
             RelayMessageUnionReader::BlockTransactions(item) => item.as_slice(),
             RelayMessageUnionReader::GetBlockProposal(item) => item.as_slice(),
             RelayMessageUnionReader::BlockProposal(item) => item.as_slice(),
+            RelayMessageUnionReader::RelayFeeFilter(item) => item.as_slice(),
         }
     }
     pub fn item_id(&self) -> molecule::Number {
@@ -5194,6 +5223,7 @@ impl<'r> RelayMessageUnionReader<'r> {
             RelayMessageUnionReader::BlockTransactions(_) => 5,
             RelayMessageUnionReader::GetBlockProposal(_) => 6,
             RelayMessageUnionReader::BlockProposal(_) => 7,
+            RelayMessageUnionReader::RelayFeeFilter(_) => 8,
         }
     }
     pub fn item_name(&self) -> &str {
@@ -5206,6 +5236,7 @@ impl<'r> RelayMessageUnionReader<'r> {
             RelayMessageUnionReader::BlockTransactions(_) => "BlockTransactions",
             RelayMessageUnionReader::GetBlockProposal(_) => "GetBlockProposal",
             RelayMessageUnionReader::BlockProposal(_) => "BlockProposal",
+            RelayMessageUnionReader::RelayFeeFilter(_) => "RelayFeeFilter",
         }
     }
 }
@@ -8342,6 +8373,241 @@ impl molecule::prelude::Builder for BlockProposalBuilder {
     }
 }
 #[derive(Clone)]
+pub struct RelayFeeFilter(molecule::bytes::Bytes);
+impl ::core::fmt::LowerHex for RelayFeeFilter {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl ::core::fmt::Debug for RelayFeeFilter {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl ::core::fmt::Display for RelayFeeFilter {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "fee_rate", self.fee_rate())?;
+        let extra_count = self.count_extra_fields();
+        if extra_count != 0 {
+            write!(f, ", .. ({} fields)", extra_count)?;
+        }
+        write!(f, " }}")
+    }
+}
+impl ::core::default::Default for RelayFeeFilter {
+    fn default() -> Self {
+        let v: Vec<u8> = vec![16, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        RelayFeeFilter::new_unchecked(v.into())
+    }
+}
+impl RelayFeeFilter {
+    pub const FIELD_COUNT: usize = 1;
+    pub fn total_size(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn field_count(&self) -> usize {
+        if self.total_size() == molecule::NUMBER_SIZE {
+            0
+        } else {
+            (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+        }
+    }
+    pub fn count_extra_fields(&self) -> usize {
+        self.field_count() - Self::FIELD_COUNT
+    }
+    pub fn has_extra_fields(&self) -> bool {
+        Self::FIELD_COUNT != self.field_count()
+    }
+    pub fn fee_rate(&self) -> Uint64 {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[4..]) as usize;
+        if self.has_extra_fields() {
+            let end = molecule::unpack_number(&slice[8..]) as usize;
+            Uint64::new_unchecked(self.0.slice(start..end))
+        } else {
+            Uint64::new_unchecked(self.0.slice(start..))
+        }
+    }
+    pub fn as_reader<'r>(&'r self) -> RelayFeeFilterReader<'r> {
+        RelayFeeFilterReader::new_unchecked(self.as_slice())
+    }
+}
+impl molecule::prelude::Entity for RelayFeeFilter {
+    type Builder = RelayFeeFilterBuilder;
+    const NAME: &'static str = "RelayFeeFilter";
+    fn new_unchecked(data: molecule::bytes::Bytes) -> Self {
+        RelayFeeFilter(data)
+    }
+    fn as_bytes(&self) -> molecule::bytes::Bytes {
+        self.0.clone()
+    }
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..]
+    }
+    fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        RelayFeeFilterReader::from_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn from_compatible_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        RelayFeeFilterReader::from_compatible_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn new_builder() -> Self::Builder {
+        ::core::default::Default::default()
+    }
+    fn as_builder(self) -> Self::Builder {
+        Self::new_builder().fee_rate(self.fee_rate())
+    }
+}
+#[derive(Clone, Copy)]
+pub struct RelayFeeFilterReader<'r>(&'r [u8]);
+impl<'r> ::core::fmt::LowerHex for RelayFeeFilterReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl<'r> ::core::fmt::Debug for RelayFeeFilterReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl<'r> ::core::fmt::Display for RelayFeeFilterReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "fee_rate", self.fee_rate())?;
+        let extra_count = self.count_extra_fields();
+        if extra_count != 0 {
+            write!(f, ", .. ({} fields)", extra_count)?;
+        }
+        write!(f, " }}")
+    }
+}
+impl<'r> RelayFeeFilterReader<'r> {
+    pub const FIELD_COUNT: usize = 1;
+    pub fn total_size(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn field_count(&self) -> usize {
+        if self.total_size() == molecule::NUMBER_SIZE {
+            0
+        } else {
+            (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+        }
+    }
+    pub fn count_extra_fields(&self) -> usize {
+        self.field_count() - Self::FIELD_COUNT
+    }
+    pub fn has_extra_fields(&self) -> bool {
+        Self::FIELD_COUNT != self.field_count()
+    }
+    pub fn fee_rate(&self) -> Uint64Reader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[4..]) as usize;
+        if self.has_extra_fields() {
+            let end = molecule::unpack_number(&slice[8..]) as usize;
+            Uint64Reader::new_unchecked(&self.as_slice()[start..end])
+        } else {
+            Uint64Reader::new_unchecked(&self.as_slice()[start..])
+        }
+    }
+}
+impl<'r> molecule::prelude::Reader<'r> for RelayFeeFilterReader<'r> {
+    type Entity = RelayFeeFilter;
+    const NAME: &'static str = "RelayFeeFilterReader";
+    fn to_entity(&self) -> Self::Entity {
+        Self::Entity::new_unchecked(self.as_slice().to_owned().into())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        RelayFeeFilterReader(slice)
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0
+    }
+    fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len < molecule::NUMBER_SIZE {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+        }
+        let total_size = molecule::unpack_number(slice) as usize;
+        if slice_len != total_size {
+            return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+        }
+        if slice_len == molecule::NUMBER_SIZE && Self::FIELD_COUNT == 0 {
+            return Ok(());
+        }
+        if slice_len < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE * 2, slice_len);
+        }
+        let offset_first = molecule::unpack_number(&slice[molecule::NUMBER_SIZE..]) as usize;
+        if offset_first % molecule::NUMBER_SIZE != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        if slice_len < offset_first {
+            return ve!(Self, HeaderIsBroken, offset_first, slice_len);
+        }
+        let field_count = offset_first / molecule::NUMBER_SIZE - 1;
+        if field_count < Self::FIELD_COUNT {
+            return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
+        } else if !compatible && field_count > Self::FIELD_COUNT {
+            return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
+        };
+        let mut offsets: Vec<usize> = slice[molecule::NUMBER_SIZE..offset_first]
+            .chunks_exact(molecule::NUMBER_SIZE)
+            .map(|x| molecule::unpack_number(x) as usize)
+            .collect();
+        offsets.push(total_size);
+        if offsets.windows(2).any(|i| i[0] > i[1]) {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        Uint64Reader::verify(&slice[offsets[0]..offsets[1]], compatible)?;
+        Ok(())
+    }
+}
+#[derive(Debug, Default)]
+pub struct RelayFeeFilterBuilder {
+    pub(crate) fee_rate: Uint64,
+}
+impl RelayFeeFilterBuilder {
+    pub const FIELD_COUNT: usize = 1;
+    pub fn fee_rate(mut self, v: Uint64) -> Self {
+        self.fee_rate = v;
+        self
+    }
+}
+impl molecule::prelude::Builder for RelayFeeFilterBuilder {
+    type Entity = RelayFeeFilter;
+    const NAME: &'static str = "RelayFeeFilterBuilder";
+    fn expected_length(&self) -> usize {
+        molecule::NUMBER_SIZE * (Self::FIELD_COUNT + 1) + self.fee_rate.as_slice().len()
+    }
+    fn write<W: molecule::io::Write>(&self, writer: &mut W) -> molecule::io::Result<()> {
+        let mut total_size = molecule::NUMBER_SIZE * (Self::FIELD_COUNT + 1);
+        let mut offsets = Vec::with_capacity(Self::FIELD_COUNT);
+        offsets.push(total_size);
+        total_size += self.fee_rate.as_slice().len();
+        writer.write_all(&molecule::pack_number(total_size as molecule::Number))?;
+        for offset in offsets.into_iter() {
+            writer.write_all(&molecule::pack_number(offset as molecule::Number))?;
+        }
+        writer.write_all(self.fee_rate.as_slice())?;
+        Ok(())
+    }
+    fn build(&self) -> Self::Entity {
+        let mut inner = Vec::with_capacity(self.expected_length());
+        self.write(&mut inner)
+            .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME));
+        RelayFeeFilter::new_unchecked(inner.into())
+    }
+}
+#[derive(Clone)]
 pub struct IndexTransaction(molecule::bytes::Bytes);
 impl ::core::fmt::LowerHex for IndexTransaction {
     fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
@@ -13071,3 +13337,996 @@ impl molecule::prelude::Builder for IdentifyBuilder {
         Identify::new_unchecked(inner.into())
     }
 }
+#[derive(Clone)]
+pub struct SigningGroup(molecule::bytes::Bytes);
+impl ::core::fmt::LowerHex for SigningGroup {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl ::core::fmt::Debug for SigningGroup {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl ::core::fmt::Display for SigningGroup {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "script", self.script())?;
+        write!(f, ", {}: {}", "script_type", self.script_type())?;
+        write!(f, ", {}: {}", "input_indices", self.input_indices())?;
+        write!(f, ", {}: {}", "output_indices", self.output_indices())?;
+        write!(f, ", {}: {}", "hint", self.hint())?;
+        let extra_count = self.count_extra_fields();
+        if extra_count != 0 {
+            write!(f, ", .. ({} fields)", extra_count)?;
+        }
+        write!(f, " }}")
+    }
+}
+impl ::core::default::Default for SigningGroup {
+    fn default() -> Self {
+        let v: Vec<u8> = vec![
+            90, 0, 0, 0, 24, 0, 0, 0, 77, 0, 0, 0, 78, 0, 0, 0, 82, 0, 0, 0, 86, 0, 0, 0, 53, 0, 0,
+            0, 16, 0, 0, 0, 48, 0, 0, 0, 49, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0,
+        ];
+        SigningGroup::new_unchecked(v.into())
+    }
+}
+impl SigningGroup {
+    pub const FIELD_COUNT: usize = 5;
+    pub fn total_size(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn field_count(&self) -> usize {
+        if self.total_size() == molecule::NUMBER_SIZE {
+            0
+        } else {
+            (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+        }
+    }
+    pub fn count_extra_fields(&self) -> usize {
+        self.field_count() - Self::FIELD_COUNT
+    }
+    pub fn has_extra_fields(&self) -> bool {
+        Self::FIELD_COUNT != self.field_count()
+    }
+    pub fn script(&self) -> Script {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[4..]) as usize;
+        let end = molecule::unpack_number(&slice[8..]) as usize;
+        Script::new_unchecked(self.0.slice(start..end))
+    }
+    pub fn script_type(&self) -> Byte {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[8..]) as usize;
+        let end = molecule::unpack_number(&slice[12..]) as usize;
+        Byte::new_unchecked(self.0.slice(start..end))
+    }
+    pub fn input_indices(&self) -> Uint32Vec {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[12..]) as usize;
+        let end = molecule::unpack_number(&slice[16..]) as usize;
+        Uint32Vec::new_unchecked(self.0.slice(start..end))
+    }
+    pub fn output_indices(&self) -> Uint32Vec {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[16..]) as usize;
+        let end = molecule::unpack_number(&slice[20..]) as usize;
+        Uint32Vec::new_unchecked(self.0.slice(start..end))
+    }
+    pub fn hint(&self) -> Bytes {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[20..]) as usize;
+        if self.has_extra_fields() {
+            let end = molecule::unpack_number(&slice[24..]) as usize;
+            Bytes::new_unchecked(self.0.slice(start..end))
+        } else {
+            Bytes::new_unchecked(self.0.slice(start..))
+        }
+    }
+    pub fn as_reader<'r>(&'r self) -> SigningGroupReader<'r> {
+        SigningGroupReader::new_unchecked(self.as_slice())
+    }
+}
+impl molecule::prelude::Entity for SigningGroup {
+    type Builder = SigningGroupBuilder;
+    const NAME: &'static str = "SigningGroup";
+    fn new_unchecked(data: molecule::bytes::Bytes) -> Self {
+        SigningGroup(data)
+    }
+    fn as_bytes(&self) -> molecule::bytes::Bytes {
+        self.0.clone()
+    }
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..]
+    }
+    fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        SigningGroupReader::from_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn from_compatible_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        SigningGroupReader::from_compatible_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn new_builder() -> Self::Builder {
+        ::core::default::Default::default()
+    }
+    fn as_builder(self) -> Self::Builder {
+        Self::new_builder()
+            .script(self.script())
+            .script_type(self.script_type())
+            .input_indices(self.input_indices())
+            .output_indices(self.output_indices())
+            .hint(self.hint())
+    }
+}
+#[derive(Clone, Copy)]
+pub struct SigningGroupReader<'r>(&'r [u8]);
+impl<'r> ::core::fmt::LowerHex for SigningGroupReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl<'r> ::core::fmt::Debug for SigningGroupReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl<'r> ::core::fmt::Display for SigningGroupReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "script", self.script())?;
+        write!(f, ", {}: {}", "script_type", self.script_type())?;
+        write!(f, ", {}: {}", "input_indices", self.input_indices())?;
+        write!(f, ", {}: {}", "output_indices", self.output_indices())?;
+        write!(f, ", {}: {}", "hint", self.hint())?;
+        let extra_count = self.count_extra_fields();
+        if extra_count != 0 {
+            write!(f, ", .. ({} fields)", extra_count)?;
+        }
+        write!(f, " }}")
+    }
+}
+impl<'r> SigningGroupReader<'r> {
+    pub const FIELD_COUNT: usize = 5;
+    pub fn total_size(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn field_count(&self) -> usize {
+        if self.total_size() == molecule::NUMBER_SIZE {
+            0
+        } else {
+            (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+        }
+    }
+    pub fn count_extra_fields(&self) -> usize {
+        self.field_count() - Self::FIELD_COUNT
+    }
+    pub fn has_extra_fields(&self) -> bool {
+        Self::FIELD_COUNT != self.field_count()
+    }
+    pub fn script(&self) -> ScriptReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[4..]) as usize;
+        let end = molecule::unpack_number(&slice[8..]) as usize;
+        ScriptReader::new_unchecked(&self.as_slice()[start..end])
+    }
+    pub fn script_type(&self) -> ByteReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[8..]) as usize;
+        let end = molecule::unpack_number(&slice[12..]) as usize;
+        ByteReader::new_unchecked(&self.as_slice()[start..end])
+    }
+    pub fn input_indices(&self) -> Uint32VecReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[12..]) as usize;
+        let end = molecule::unpack_number(&slice[16..]) as usize;
+        Uint32VecReader::new_unchecked(&self.as_slice()[start..end])
+    }
+    pub fn output_indices(&self) -> Uint32VecReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[16..]) as usize;
+        let end = molecule::unpack_number(&slice[20..]) as usize;
+        Uint32VecReader::new_unchecked(&self.as_slice()[start..end])
+    }
+    pub fn hint(&self) -> BytesReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[20..]) as usize;
+        if self.has_extra_fields() {
+            let end = molecule::unpack_number(&slice[24..]) as usize;
+            BytesReader::new_unchecked(&self.as_slice()[start..end])
+        } else {
+            BytesReader::new_unchecked(&self.as_slice()[start..])
+        }
+    }
+}
+impl<'r> molecule::prelude::Reader<'r> for SigningGroupReader<'r> {
+    type Entity = SigningGroup;
+    const NAME: &'static str = "SigningGroupReader";
+    fn to_entity(&self) -> Self::Entity {
+        Self::Entity::new_unchecked(self.as_slice().to_owned().into())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        SigningGroupReader(slice)
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0
+    }
+    fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len < molecule::NUMBER_SIZE {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+        }
+        let total_size = molecule::unpack_number(slice) as usize;
+        if slice_len != total_size {
+            return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+        }
+        if slice_len == molecule::NUMBER_SIZE && Self::FIELD_COUNT == 0 {
+            return Ok(());
+        }
+        if slice_len < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE * 2, slice_len);
+        }
+        let offset_first = molecule::unpack_number(&slice[molecule::NUMBER_SIZE..]) as usize;
+        if offset_first % molecule::NUMBER_SIZE != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        if slice_len < offset_first {
+            return ve!(Self, HeaderIsBroken, offset_first, slice_len);
+        }
+        let field_count = offset_first / molecule::NUMBER_SIZE - 1;
+        if field_count < Self::FIELD_COUNT {
+            return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
+        } else if !compatible && field_count > Self::FIELD_COUNT {
+            return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
+        };
+        let mut offsets: Vec<usize> = slice[molecule::NUMBER_SIZE..offset_first]
+            .chunks_exact(molecule::NUMBER_SIZE)
+            .map(|x| molecule::unpack_number(x) as usize)
+            .collect();
+        offsets.push(total_size);
+        if offsets.windows(2).any(|i| i[0] > i[1]) {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        ScriptReader::verify(&slice[offsets[0]..offsets[1]], compatible)?;
+        ByteReader::verify(&slice[offsets[1]..offsets[2]], compatible)?;
+        Uint32VecReader::verify(&slice[offsets[2]..offsets[3]], compatible)?;
+        Uint32VecReader::verify(&slice[offsets[3]..offsets[4]], compatible)?;
+        BytesReader::verify(&slice[offsets[4]..offsets[5]], compatible)?;
+        Ok(())
+    }
+}
+#[derive(Debug, Default)]
+pub struct SigningGroupBuilder {
+    pub(crate) script: Script,
+    pub(crate) script_type: Byte,
+    pub(crate) input_indices: Uint32Vec,
+    pub(crate) output_indices: Uint32Vec,
+    pub(crate) hint: Bytes,
+}
+impl SigningGroupBuilder {
+    pub const FIELD_COUNT: usize = 5;
+    pub fn script(mut self, v: Script) -> Self {
+        self.script = v;
+        self
+    }
+    pub fn script_type(mut self, v: Byte) -> Self {
+        self.script_type = v;
+        self
+    }
+    pub fn input_indices(mut self, v: Uint32Vec) -> Self {
+        self.input_indices = v;
+        self
+    }
+    pub fn output_indices(mut self, v: Uint32Vec) -> Self {
+        self.output_indices = v;
+        self
+    }
+    pub fn hint(mut self, v: Bytes) -> Self {
+        self.hint = v;
+        self
+    }
+}
+impl molecule::prelude::Builder for SigningGroupBuilder {
+    type Entity = SigningGroup;
+    const NAME: &'static str = "SigningGroupBuilder";
+    fn expected_length(&self) -> usize {
+        molecule::NUMBER_SIZE * (Self::FIELD_COUNT + 1)
+            + self.script.as_slice().len()
+            + self.script_type.as_slice().len()
+            + self.input_indices.as_slice().len()
+            + self.output_indices.as_slice().len()
+            + self.hint.as_slice().len()
+    }
+    fn write<W: molecule::io::Write>(&self, writer: &mut W) -> molecule::io::Result<()> {
+        let mut total_size = molecule::NUMBER_SIZE * (Self::FIELD_COUNT + 1);
+        let mut offsets = Vec::with_capacity(Self::FIELD_COUNT);
+        offsets.push(total_size);
+        total_size += self.script.as_slice().len();
+        offsets.push(total_size);
+        total_size += self.script_type.as_slice().len();
+        offsets.push(total_size);
+        total_size += self.input_indices.as_slice().len();
+        offsets.push(total_size);
+        total_size += self.output_indices.as_slice().len();
+        offsets.push(total_size);
+        total_size += self.hint.as_slice().len();
+        writer.write_all(&molecule::pack_number(total_size as molecule::Number))?;
+        for offset in offsets.into_iter() {
+            writer.write_all(&molecule::pack_number(offset as molecule::Number))?;
+        }
+        writer.write_all(self.script.as_slice())?;
+        writer.write_all(self.script_type.as_slice())?;
+        writer.write_all(self.input_indices.as_slice())?;
+        writer.write_all(self.output_indices.as_slice())?;
+        writer.write_all(self.hint.as_slice())?;
+        Ok(())
+    }
+    fn build(&self) -> Self::Entity {
+        let mut inner = Vec::with_capacity(self.expected_length());
+        self.write(&mut inner)
+            .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME));
+        SigningGroup::new_unchecked(inner.into())
+    }
+}
+#[derive(Clone)]
+pub struct SigningGroupVec(molecule::bytes::Bytes);
+impl ::core::fmt::LowerHex for SigningGroupVec {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl ::core::fmt::Debug for SigningGroupVec {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl ::core::fmt::Display for SigningGroupVec {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} [", Self::NAME)?;
+        for i in 0..self.len() {
+            if i == 0 {
+                write!(f, "{}", self.get_unchecked(i))?;
+            } else {
+                write!(f, ", {}", self.get_unchecked(i))?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+impl ::core::default::Default for SigningGroupVec {
+    fn default() -> Self {
+        let v: Vec<u8> = vec![4, 0, 0, 0];
+        SigningGroupVec::new_unchecked(v.into())
+    }
+}
+impl SigningGroupVec {
+    pub fn total_size(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn item_count(&self) -> usize {
+        if self.total_size() == molecule::NUMBER_SIZE {
+            0
+        } else {
+            (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.item_count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn get(&self, idx: usize) -> Option<SigningGroup> {
+        if idx >= self.len() {
+            None
+        } else {
+            Some(self.get_unchecked(idx))
+        }
+    }
+    pub fn get_unchecked(&self, idx: usize) -> SigningGroup {
+        let slice = self.as_slice();
+        let start_idx = molecule::NUMBER_SIZE * (1 + idx);
+        let start = molecule::unpack_number(&slice[start_idx..]) as usize;
+        if idx == self.len() - 1 {
+            SigningGroup::new_unchecked(self.0.slice(start..))
+        } else {
+            let end_idx = start_idx + molecule::NUMBER_SIZE;
+            let end = molecule::unpack_number(&slice[end_idx..]) as usize;
+            SigningGroup::new_unchecked(self.0.slice(start..end))
+        }
+    }
+    pub fn as_reader<'r>(&'r self) -> SigningGroupVecReader<'r> {
+        SigningGroupVecReader::new_unchecked(self.as_slice())
+    }
+}
+impl molecule::prelude::Entity for SigningGroupVec {
+    type Builder = SigningGroupVecBuilder;
+    const NAME: &'static str = "SigningGroupVec";
+    fn new_unchecked(data: molecule::bytes::Bytes) -> Self {
+        SigningGroupVec(data)
+    }
+    fn as_bytes(&self) -> molecule::bytes::Bytes {
+        self.0.clone()
+    }
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..]
+    }
+    fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        SigningGroupVecReader::from_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn from_compatible_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        SigningGroupVecReader::from_compatible_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn new_builder() -> Self::Builder {
+        ::core::default::Default::default()
+    }
+    fn as_builder(self) -> Self::Builder {
+        Self::new_builder().extend(self.into_iter())
+    }
+}
+#[derive(Clone, Copy)]
+pub struct SigningGroupVecReader<'r>(&'r [u8]);
+impl<'r> ::core::fmt::LowerHex for SigningGroupVecReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl<'r> ::core::fmt::Debug for SigningGroupVecReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl<'r> ::core::fmt::Display for SigningGroupVecReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} [", Self::NAME)?;
+        for i in 0..self.len() {
+            if i == 0 {
+                write!(f, "{}", self.get_unchecked(i))?;
+            } else {
+                write!(f, ", {}", self.get_unchecked(i))?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+impl<'r> SigningGroupVecReader<'r> {
+    pub fn total_size(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn item_count(&self) -> usize {
+        if self.total_size() == molecule::NUMBER_SIZE {
+            0
+        } else {
+            (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.item_count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn get(&self, idx: usize) -> Option<SigningGroupReader<'r>> {
+        if idx >= self.len() {
+            None
+        } else {
+            Some(self.get_unchecked(idx))
+        }
+    }
+    pub fn get_unchecked(&self, idx: usize) -> SigningGroupReader<'r> {
+        let slice = self.as_slice();
+        let start_idx = molecule::NUMBER_SIZE * (1 + idx);
+        let start = molecule::unpack_number(&slice[start_idx..]) as usize;
+        if idx == self.len() - 1 {
+            SigningGroupReader::new_unchecked(&self.as_slice()[start..])
+        } else {
+            let end_idx = start_idx + molecule::NUMBER_SIZE;
+            let end = molecule::unpack_number(&slice[end_idx..]) as usize;
+            SigningGroupReader::new_unchecked(&self.as_slice()[start..end])
+        }
+    }
+}
+impl<'r> molecule::prelude::Reader<'r> for SigningGroupVecReader<'r> {
+    type Entity = SigningGroupVec;
+    const NAME: &'static str = "SigningGroupVecReader";
+    fn to_entity(&self) -> Self::Entity {
+        Self::Entity::new_unchecked(self.as_slice().to_owned().into())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        SigningGroupVecReader(slice)
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0
+    }
+    fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len < molecule::NUMBER_SIZE {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+        }
+        let total_size = molecule::unpack_number(slice) as usize;
+        if slice_len != total_size {
+            return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+        }
+        if slice_len == molecule::NUMBER_SIZE {
+            return Ok(());
+        }
+        if slice_len < molecule::NUMBER_SIZE * 2 {
+            return ve!(
+                Self,
+                TotalSizeNotMatch,
+                molecule::NUMBER_SIZE * 2,
+                slice_len
+            );
+        }
+        let offset_first = molecule::unpack_number(&slice[molecule::NUMBER_SIZE..]) as usize;
+        if offset_first % molecule::NUMBER_SIZE != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        if slice_len < offset_first {
+            return ve!(Self, HeaderIsBroken, offset_first, slice_len);
+        }
+        let mut offsets: Vec<usize> = slice[molecule::NUMBER_SIZE..offset_first]
+            .chunks_exact(molecule::NUMBER_SIZE)
+            .map(|x| molecule::unpack_number(x) as usize)
+            .collect();
+        offsets.push(total_size);
+        if offsets.windows(2).any(|i| i[0] > i[1]) {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        for pair in offsets.windows(2) {
+            let start = pair[0];
+            let end = pair[1];
+            SigningGroupReader::verify(&slice[start..end], compatible)?;
+        }
+        Ok(())
+    }
+}
+#[derive(Debug, Default)]
+pub struct SigningGroupVecBuilder(pub(crate) Vec<SigningGroup>);
+impl SigningGroupVecBuilder {
+    pub fn set(mut self, v: Vec<SigningGroup>) -> Self {
+        self.0 = v;
+        self
+    }
+    pub fn push(mut self, v: SigningGroup) -> Self {
+        self.0.push(v);
+        self
+    }
+    pub fn extend<T: ::core::iter::IntoIterator<Item = SigningGroup>>(mut self, iter: T) -> Self {
+        for elem in iter {
+            self.0.push(elem);
+        }
+        self
+    }
+}
+impl molecule::prelude::Builder for SigningGroupVecBuilder {
+    type Entity = SigningGroupVec;
+    const NAME: &'static str = "SigningGroupVecBuilder";
+    fn expected_length(&self) -> usize {
+        molecule::NUMBER_SIZE * (self.0.len() + 1)
+            + self
+                .0
+                .iter()
+                .map(|inner| inner.as_slice().len())
+                .sum::<usize>()
+    }
+    fn write<W: molecule::io::Write>(&self, writer: &mut W) -> molecule::io::Result<()> {
+        let item_count = self.0.len();
+        if item_count == 0 {
+            writer.write_all(&molecule::pack_number(
+                molecule::NUMBER_SIZE as molecule::Number,
+            ))?;
+        } else {
+            let (total_size, offsets) = self.0.iter().fold(
+                (
+                    molecule::NUMBER_SIZE * (item_count + 1),
+                    Vec::with_capacity(item_count),
+                ),
+                |(start, mut offsets), inner| {
+                    offsets.push(start);
+                    (start + inner.as_slice().len(), offsets)
+                },
+            );
+            writer.write_all(&molecule::pack_number(total_size as molecule::Number))?;
+            for offset in offsets.into_iter() {
+                writer.write_all(&molecule::pack_number(offset as molecule::Number))?;
+            }
+            for inner in self.0.iter() {
+                writer.write_all(inner.as_slice())?;
+            }
+        }
+        Ok(())
+    }
+    fn build(&self) -> Self::Entity {
+        let mut inner = Vec::with_capacity(self.expected_length());
+        self.write(&mut inner)
+            .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME));
+        SigningGroupVec::new_unchecked(inner.into())
+    }
+}
+pub struct SigningGroupVecIterator(SigningGroupVec, usize, usize);
+impl ::core::iter::Iterator for SigningGroupVecIterator {
+    type Item = SigningGroup;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.1 >= self.2 {
+            None
+        } else {
+            let ret = self.0.get_unchecked(self.1);
+            self.1 += 1;
+            Some(ret)
+        }
+    }
+}
+impl ::core::iter::ExactSizeIterator for SigningGroupVecIterator {
+    fn len(&self) -> usize {
+        self.2 - self.1
+    }
+}
+impl ::core::iter::IntoIterator for SigningGroupVec {
+    type Item = SigningGroup;
+    type IntoIter = SigningGroupVecIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len();
+        SigningGroupVecIterator(self, 0, len)
+    }
+}
+impl<'r> SigningGroupVecReader<'r> {
+    pub fn iter<'t>(&'t self) -> SigningGroupVecReaderIterator<'t, 'r> {
+        SigningGroupVecReaderIterator(&self, 0, self.len())
+    }
+}
+pub struct SigningGroupVecReaderIterator<'t, 'r>(&'t SigningGroupVecReader<'r>, usize, usize);
+impl<'t: 'r, 'r> ::core::iter::Iterator for SigningGroupVecReaderIterator<'t, 'r> {
+    type Item = SigningGroupReader<'t>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.1 >= self.2 {
+            None
+        } else {
+            let ret = self.0.get_unchecked(self.1);
+            self.1 += 1;
+            Some(ret)
+        }
+    }
+}
+impl<'t: 'r, 'r> ::core::iter::ExactSizeIterator for SigningGroupVecReaderIterator<'t, 'r> {
+    fn len(&self) -> usize {
+        self.2 - self.1
+    }
+}
+#[derive(Clone)]
+pub struct PartialTransaction(molecule::bytes::Bytes);
+impl ::core::fmt::LowerHex for PartialTransaction {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl ::core::fmt::Debug for PartialTransaction {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl ::core::fmt::Display for PartialTransaction {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "transaction", self.transaction())?;
+        write!(f, ", {}: {}", "input_cells", self.input_cells())?;
+        write!(f, ", {}: {}", "input_cells_data", self.input_cells_data())?;
+        write!(f, ", {}: {}", "signing_groups", self.signing_groups())?;
+        let extra_count = self.count_extra_fields();
+        if extra_count != 0 {
+            write!(f, ", .. ({} fields)", extra_count)?;
+        }
+        write!(f, " }}")
+    }
+}
+impl ::core::default::Default for PartialTransaction {
+    fn default() -> Self {
+        let v: Vec<u8> = vec![
+            100, 0, 0, 0, 20, 0, 0, 0, 88, 0, 0, 0, 92, 0, 0, 0, 96, 0, 0, 0, 68, 0, 0, 0, 12, 0,
+            0, 0, 64, 0, 0, 0, 52, 0, 0, 0, 28, 0, 0, 0, 32, 0, 0, 0, 36, 0, 0, 0, 40, 0, 0, 0, 44,
+            0, 0, 0, 48, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 4, 0,
+            0, 0, 4, 0, 0, 0, 4, 0, 0, 0, 4, 0, 0, 0, 4, 0, 0, 0,
+        ];
+        PartialTransaction::new_unchecked(v.into())
+    }
+}
+impl PartialTransaction {
+    pub const FIELD_COUNT: usize = 4;
+    pub fn total_size(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn field_count(&self) -> usize {
+        if self.total_size() == molecule::NUMBER_SIZE {
+            0
+        } else {
+            (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+        }
+    }
+    pub fn count_extra_fields(&self) -> usize {
+        self.field_count() - Self::FIELD_COUNT
+    }
+    pub fn has_extra_fields(&self) -> bool {
+        Self::FIELD_COUNT != self.field_count()
+    }
+    pub fn transaction(&self) -> Transaction {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[4..]) as usize;
+        let end = molecule::unpack_number(&slice[8..]) as usize;
+        Transaction::new_unchecked(self.0.slice(start..end))
+    }
+    pub fn input_cells(&self) -> CellOutputVec {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[8..]) as usize;
+        let end = molecule::unpack_number(&slice[12..]) as usize;
+        CellOutputVec::new_unchecked(self.0.slice(start..end))
+    }
+    pub fn input_cells_data(&self) -> BytesVec {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[12..]) as usize;
+        let end = molecule::unpack_number(&slice[16..]) as usize;
+        BytesVec::new_unchecked(self.0.slice(start..end))
+    }
+    pub fn signing_groups(&self) -> SigningGroupVec {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[16..]) as usize;
+        if self.has_extra_fields() {
+            let end = molecule::unpack_number(&slice[20..]) as usize;
+            SigningGroupVec::new_unchecked(self.0.slice(start..end))
+        } else {
+            SigningGroupVec::new_unchecked(self.0.slice(start..))
+        }
+    }
+    pub fn as_reader<'r>(&'r self) -> PartialTransactionReader<'r> {
+        PartialTransactionReader::new_unchecked(self.as_slice())
+    }
+}
+impl molecule::prelude::Entity for PartialTransaction {
+    type Builder = PartialTransactionBuilder;
+    const NAME: &'static str = "PartialTransaction";
+    fn new_unchecked(data: molecule::bytes::Bytes) -> Self {
+        PartialTransaction(data)
+    }
+    fn as_bytes(&self) -> molecule::bytes::Bytes {
+        self.0.clone()
+    }
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..]
+    }
+    fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        PartialTransactionReader::from_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn from_compatible_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        PartialTransactionReader::from_compatible_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn new_builder() -> Self::Builder {
+        ::core::default::Default::default()
+    }
+    fn as_builder(self) -> Self::Builder {
+        Self::new_builder()
+            .transaction(self.transaction())
+            .input_cells(self.input_cells())
+            .input_cells_data(self.input_cells_data())
+            .signing_groups(self.signing_groups())
+    }
+}
+#[derive(Clone, Copy)]
+pub struct PartialTransactionReader<'r>(&'r [u8]);
+impl<'r> ::core::fmt::LowerHex for PartialTransactionReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl<'r> ::core::fmt::Debug for PartialTransactionReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl<'r> ::core::fmt::Display for PartialTransactionReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "transaction", self.transaction())?;
+        write!(f, ", {}: {}", "input_cells", self.input_cells())?;
+        write!(f, ", {}: {}", "input_cells_data", self.input_cells_data())?;
+        write!(f, ", {}: {}", "signing_groups", self.signing_groups())?;
+        let extra_count = self.count_extra_fields();
+        if extra_count != 0 {
+            write!(f, ", .. ({} fields)", extra_count)?;
+        }
+        write!(f, " }}")
+    }
+}
+impl<'r> PartialTransactionReader<'r> {
+    pub const FIELD_COUNT: usize = 4;
+    pub fn total_size(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn field_count(&self) -> usize {
+        if self.total_size() == molecule::NUMBER_SIZE {
+            0
+        } else {
+            (molecule::unpack_number(&self.as_slice()[molecule::NUMBER_SIZE..]) as usize / 4) - 1
+        }
+    }
+    pub fn count_extra_fields(&self) -> usize {
+        self.field_count() - Self::FIELD_COUNT
+    }
+    pub fn has_extra_fields(&self) -> bool {
+        Self::FIELD_COUNT != self.field_count()
+    }
+    pub fn transaction(&self) -> TransactionReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[4..]) as usize;
+        let end = molecule::unpack_number(&slice[8..]) as usize;
+        TransactionReader::new_unchecked(&self.as_slice()[start..end])
+    }
+    pub fn input_cells(&self) -> CellOutputVecReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[8..]) as usize;
+        let end = molecule::unpack_number(&slice[12..]) as usize;
+        CellOutputVecReader::new_unchecked(&self.as_slice()[start..end])
+    }
+    pub fn input_cells_data(&self) -> BytesVecReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[12..]) as usize;
+        let end = molecule::unpack_number(&slice[16..]) as usize;
+        BytesVecReader::new_unchecked(&self.as_slice()[start..end])
+    }
+    pub fn signing_groups(&self) -> SigningGroupVecReader<'r> {
+        let slice = self.as_slice();
+        let start = molecule::unpack_number(&slice[16..]) as usize;
+        if self.has_extra_fields() {
+            let end = molecule::unpack_number(&slice[20..]) as usize;
+            SigningGroupVecReader::new_unchecked(&self.as_slice()[start..end])
+        } else {
+            SigningGroupVecReader::new_unchecked(&self.as_slice()[start..])
+        }
+    }
+}
+impl<'r> molecule::prelude::Reader<'r> for PartialTransactionReader<'r> {
+    type Entity = PartialTransaction;
+    const NAME: &'static str = "PartialTransactionReader";
+    fn to_entity(&self) -> Self::Entity {
+        Self::Entity::new_unchecked(self.as_slice().to_owned().into())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        PartialTransactionReader(slice)
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0
+    }
+    fn verify(slice: &[u8], compatible: bool) -> molecule::error::VerificationResult<()> {
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len < molecule::NUMBER_SIZE {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+        }
+        let total_size = molecule::unpack_number(slice) as usize;
+        if slice_len != total_size {
+            return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+        }
+        if slice_len == molecule::NUMBER_SIZE && Self::FIELD_COUNT == 0 {
+            return Ok(());
+        }
+        if slice_len < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE * 2, slice_len);
+        }
+        let offset_first = molecule::unpack_number(&slice[molecule::NUMBER_SIZE..]) as usize;
+        if offset_first % molecule::NUMBER_SIZE != 0 || offset_first < molecule::NUMBER_SIZE * 2 {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        if slice_len < offset_first {
+            return ve!(Self, HeaderIsBroken, offset_first, slice_len);
+        }
+        let field_count = offset_first / molecule::NUMBER_SIZE - 1;
+        if field_count < Self::FIELD_COUNT {
+            return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
+        } else if !compatible && field_count > Self::FIELD_COUNT {
+            return ve!(Self, FieldCountNotMatch, Self::FIELD_COUNT, field_count);
+        };
+        let mut offsets: Vec<usize> = slice[molecule::NUMBER_SIZE..offset_first]
+            .chunks_exact(molecule::NUMBER_SIZE)
+            .map(|x| molecule::unpack_number(x) as usize)
+            .collect();
+        offsets.push(total_size);
+        if offsets.windows(2).any(|i| i[0] > i[1]) {
+            return ve!(Self, OffsetsNotMatch);
+        }
+        TransactionReader::verify(&slice[offsets[0]..offsets[1]], compatible)?;
+        CellOutputVecReader::verify(&slice[offsets[1]..offsets[2]], compatible)?;
+        BytesVecReader::verify(&slice[offsets[2]..offsets[3]], compatible)?;
+        SigningGroupVecReader::verify(&slice[offsets[3]..offsets[4]], compatible)?;
+        Ok(())
+    }
+}
+#[derive(Debug, Default)]
+pub struct PartialTransactionBuilder {
+    pub(crate) transaction: Transaction,
+    pub(crate) input_cells: CellOutputVec,
+    pub(crate) input_cells_data: BytesVec,
+    pub(crate) signing_groups: SigningGroupVec,
+}
+impl PartialTransactionBuilder {
+    pub const FIELD_COUNT: usize = 4;
+    pub fn transaction(mut self, v: Transaction) -> Self {
+        self.transaction = v;
+        self
+    }
+    pub fn input_cells(mut self, v: CellOutputVec) -> Self {
+        self.input_cells = v;
+        self
+    }
+    pub fn input_cells_data(mut self, v: BytesVec) -> Self {
+        self.input_cells_data = v;
+        self
+    }
+    pub fn signing_groups(mut self, v: SigningGroupVec) -> Self {
+        self.signing_groups = v;
+        self
+    }
+}
+impl molecule::prelude::Builder for PartialTransactionBuilder {
+    type Entity = PartialTransaction;
+    const NAME: &'static str = "PartialTransactionBuilder";
+    fn expected_length(&self) -> usize {
+        molecule::NUMBER_SIZE * (Self::FIELD_COUNT + 1)
+            + self.transaction.as_slice().len()
+            + self.input_cells.as_slice().len()
+            + self.input_cells_data.as_slice().len()
+            + self.signing_groups.as_slice().len()
+    }
+    fn write<W: molecule::io::Write>(&self, writer: &mut W) -> molecule::io::Result<()> {
+        let mut total_size = molecule::NUMBER_SIZE * (Self::FIELD_COUNT + 1);
+        let mut offsets = Vec::with_capacity(Self::FIELD_COUNT);
+        offsets.push(total_size);
+        total_size += self.transaction.as_slice().len();
+        offsets.push(total_size);
+        total_size += self.input_cells.as_slice().len();
+        offsets.push(total_size);
+        total_size += self.input_cells_data.as_slice().len();
+        offsets.push(total_size);
+        total_size += self.signing_groups.as_slice().len();
+        writer.write_all(&molecule::pack_number(total_size as molecule::Number))?;
+        for offset in offsets.into_iter() {
+            writer.write_all(&molecule::pack_number(offset as molecule::Number))?;
+        }
+        writer.write_all(self.transaction.as_slice())?;
+        writer.write_all(self.input_cells.as_slice())?;
+        writer.write_all(self.input_cells_data.as_slice())?;
+        writer.write_all(self.signing_groups.as_slice())?;
+        Ok(())
+    }
+    fn build(&self) -> Self::Entity {
+        let mut inner = Vec::with_capacity(self.expected_length());
+        self.write(&mut inner)
+            .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME));
+        PartialTransaction::new_unchecked(inner.into())
+    }
+}
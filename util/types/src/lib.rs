@@ -17,4 +17,5 @@ pub mod core;
 pub mod constants;
 mod conversion;
 mod extension;
+pub mod schemas;
 pub mod utilities;
@@ -0,0 +1,17 @@
+//! The molecule schemas of the packed types.
+//!
+//! The [`packed`](../packed/index.html) types are generated from these schemas.
+
+/// The schema of the blockchain entities, such as blocks and transactions.
+pub const BLOCKCHAIN: &str = include_str!("../schemas/blockchain.mol");
+/// The schema of the storage entities and the P2P entities shared by the protocols.
+pub const EXTENSIONS: &str = include_str!("../schemas/extensions.mol");
+/// The schema of the P2P protocol messages.
+pub const PROTOCOLS: &str = include_str!("../schemas/protocols.mol");
+
+/// All the schemas with their file names, a schema imports the ones before it.
+pub const ALL: &[(&str, &str)] = &[
+    ("blockchain.mol", BLOCKCHAIN),
+    ("extensions.mol", EXTENSIONS),
+    ("protocols.mol", PROTOCOLS),
+];
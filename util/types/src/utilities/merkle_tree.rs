@@ -1,21 +1,22 @@
-use ckb_hash::new_blake2b;
+use ckb_hash::blake2b_256_chunks;
 use merkle_cbt::{merkle_tree::Merge, MerkleProof as ExMerkleProof, CBMT as ExCBMT};
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
 
 use crate::{packed::Byte32, prelude::*};
 
+/// The parallel merkle root computation falls back to the sequential one below this count of
+/// leaves, where the thread pool overhead outweighs the hashing.
+#[cfg(not(target_arch = "wasm32"))]
+const PARALLEL_MERKLE_LEAVES_THRESHOLD: usize = 512;
+
 /// TODO(doc): @quake
 pub struct MergeByte32;
 
 impl Merge for MergeByte32 {
     type Item = Byte32;
     fn merge(left: &Self::Item, right: &Self::Item) -> Self::Item {
-        let mut ret = [0u8; 32];
-        let mut blake2b = new_blake2b();
-
-        blake2b.update(left.as_slice());
-        blake2b.update(right.as_slice());
-        blake2b.finalize(&mut ret);
-        ret.pack()
+        blake2b_256_chunks(&[left.as_slice(), right.as_slice()]).pack()
     }
 }
 
@@ -28,3 +29,74 @@ pub type MerkleProof = ExMerkleProof<Byte32, MergeByte32>;
 pub fn merkle_root(leaves: &[Byte32]) -> Byte32 {
     CBMT::build_merkle_root(leaves)
 }
+
+/// Computes the same root as [`merkle_root`], hashing the nodes of each tree level in parallel.
+///
+/// The CBMT stores the `n` leaves at the indices `n - 1 .. 2n - 1` of a complete binary tree,
+/// and the node at index `i` merges its children at `2i + 1` and `2i + 2`. The children of a node
+/// are always one level deeper, so the nodes of a level can be merged concurrently once the
+/// deeper level is done.
+///
+/// It is the same as [`merkle_root`] on `wasm32`, which has no threads.
+#[cfg(target_arch = "wasm32")]
+pub fn par_merkle_root(leaves: &[Byte32]) -> Byte32 {
+    merkle_root(leaves)
+}
+
+/// Computes the same root as [`merkle_root`], hashing the nodes of each tree level in parallel.
+///
+/// The CBMT stores the `n` leaves at the indices `n - 1 .. 2n - 1` of a complete binary tree,
+/// and the node at index `i` merges its children at `2i + 1` and `2i + 2`. The children of a node
+/// are always one level deeper, so the nodes of a level can be merged concurrently once the
+/// deeper level is done.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn par_merkle_root(leaves: &[Byte32]) -> Byte32 {
+    if leaves.len() < PARALLEL_MERKLE_LEAVES_THRESHOLD {
+        return merkle_root(leaves);
+    }
+
+    let internal_count = leaves.len() - 1;
+    let mut nodes = vec![Byte32::zero(); internal_count];
+    nodes.extend_from_slice(leaves);
+
+    // The levels start at the indices 2^k - 1, merge the internal nodes of the deepest level first.
+    let mut level_starts = Vec::new();
+    let mut start = 0;
+    while start < internal_count {
+        level_starts.push(start);
+        start = start * 2 + 1;
+    }
+    for &start in level_starts.iter().rev() {
+        let end = std::cmp::min(start * 2 + 1, internal_count);
+        let (parents, children) = nodes.split_at_mut(end);
+        parents[start..]
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(offset, node)| {
+                let left = (start + offset) * 2 + 1 - end;
+                *node = MergeByte32::merge(&children[left], &children[left + 1]);
+            });
+    }
+    nodes.swap_remove(0)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use ckb_hash::blake2b_256;
+
+    #[test]
+    fn test_par_merkle_root() {
+        let leaves: Vec<Byte32> = (0..2_100u32)
+            .map(|i| blake2b_256(i.to_le_bytes()).pack())
+            .collect();
+        for &count in &[0, 1, 2, 3, 511, 512, 513, 1_000, 1_024, 1_025, 2_100] {
+            assert_eq!(
+                par_merkle_root(&leaves[..count]),
+                merkle_root(&leaves[..count]),
+                "{} leaves",
+                count
+            );
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Explain mode of the transaction verification.
+//!
+//! The normal verifiers stop at the first violated rule and return a terse error. The explain
+//! mode evaluates every rule and records what each rule looked at and how it ended, which helps
+//! debugging the consensus rule rejections.
+use crate::transaction_verifier::{StepOutcome, TransactionVerifier, TransactionVerifyStep};
+use crate::TxVerifyEnv;
+use ckb_chain_spec::consensus::Consensus;
+use ckb_traits::{CellDataProvider, EpochProvider, HeaderProvider};
+use ckb_types::{
+    core::{cell::ResolvedTransaction, Capacity, Cycle},
+    prelude::*,
+};
+
+/// The trace of a verification rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleTrace {
+    /// The rule name, which is the name of the verifier
+    pub rule: &'static str,
+    /// The values the rule evaluates, as name and value pairs
+    pub inputs: Vec<(&'static str, String)>,
+    /// Whether the rule passed
+    pub passed: bool,
+    /// The error when the rule is violated, or the result of the rule when it passed
+    pub outcome: String,
+}
+
+/// Evaluates all the verification rules of the resolved transaction and returns the traces in
+/// the order they are evaluated by [`TransactionVerifier`].
+///
+/// Unlike the verifiers, it continues after a rule is violated, so a trace may contain several
+/// failed rules. `max_cycles` limits the script verification, it should be the same limit the
+/// transaction is going to be verified with, such as `tx_pool.max_tx_verify_cycles`.
+///
+/// [`TransactionVerifier`]: ../struct.TransactionVerifier.html
+pub fn explain_transaction<DL>(
+    rtx: &ResolvedTransaction,
+    consensus: &Consensus,
+    data_loader: &DL,
+    tx_env: &TxVerifyEnv,
+    max_cycles: Cycle,
+) -> Vec<RuleTrace>
+where
+    DL: CellDataProvider + HeaderProvider + EpochProvider,
+{
+    let verifier = TransactionVerifier::new(rtx, consensus, data_loader, tx_env);
+    TransactionVerifyStep::steps()
+        .map(|step| {
+            let (passed, outcome) = match verifier.verify_step(step, max_cycles) {
+                Ok(StepOutcome::Passed) => (true, String::new()),
+                Ok(StepOutcome::Cycles(cycles)) => (true, format!("cycles: {}", cycles)),
+                Ok(StepOutcome::Fee(fee)) => (true, format!("fee: {}", fee)),
+                Err(err) => (false, err.to_string()),
+            };
+            RuleTrace {
+                rule: step.name(),
+                inputs: step_inputs(step, rtx, consensus, tx_env, max_cycles),
+                passed,
+                outcome,
+            }
+        })
+        .collect()
+}
+
+// The values a step evaluates
+fn step_inputs(
+    step: TransactionVerifyStep,
+    rtx: &ResolvedTransaction,
+    consensus: &Consensus,
+    tx_env: &TxVerifyEnv,
+    max_cycles: Cycle,
+) -> Vec<(&'static str, String)> {
+    let tx = &rtx.transaction;
+    let proposal_window = consensus.tx_proposal_window();
+    match step {
+        TransactionVerifyStep::Version => vec![
+            ("version", tx.version().to_string()),
+            ("expected", consensus.tx_version().to_string()),
+        ],
+        TransactionVerifyStep::Size => vec![
+            ("size", tx.data().serialized_size_in_block().to_string()),
+            ("limit", consensus.max_block_bytes().to_string()),
+        ],
+        TransactionVerifyStep::Empty => vec![
+            ("inputs", tx.inputs().len().to_string()),
+            ("outputs", tx.outputs().len().to_string()),
+        ],
+        TransactionVerifyStep::DuplicateDeps => vec![
+            ("cell_deps", tx.cell_deps().len().to_string()),
+            ("header_deps", tx.header_deps().len().to_string()),
+        ],
+        TransactionVerifyStep::OutputsData => vec![
+            ("outputs", tx.outputs().len().to_string()),
+            ("outputs_data", tx.outputs_data().len().to_string()),
+        ],
+        TransactionVerifyStep::Compatible => vec![(
+            "epoch_number",
+            tx_env.epoch_number(proposal_window).to_string(),
+        )],
+        TransactionVerifyStep::Maturity => vec![
+            ("epoch", tx_env.epoch().to_string()),
+            (
+                "cellbase_maturity",
+                consensus.cellbase_maturity().to_string(),
+            ),
+        ],
+        TransactionVerifyStep::Since => vec![
+            (
+                "block_number",
+                tx_env.block_number(proposal_window).to_string(),
+            ),
+            (
+                "since",
+                format!(
+                    "{:?}",
+                    tx.inputs()
+                        .into_iter()
+                        .map(|input| Unpack::<u64>::unpack(&input.since()))
+                        .collect::<Vec<_>>()
+                ),
+            ),
+        ],
+        TransactionVerifyStep::Capacity => vec![
+            ("inputs_capacity", inputs_capacity(rtx)),
+            ("outputs_capacity", outputs_capacity(rtx)),
+        ],
+        TransactionVerifyStep::Script => vec![("max_cycles", max_cycles.to_string())],
+        TransactionVerifyStep::Fee => vec![("outputs_capacity", outputs_capacity(rtx))],
+    }
+}
+
+fn inputs_capacity(rtx: &ResolvedTransaction) -> String {
+    rtx.resolved_inputs
+        .iter()
+        .try_fold(Capacity::zero(), |total, cell_meta| {
+            total.safe_add(Unpack::<Capacity>::unpack(
+                &cell_meta.cell_output.capacity(),
+            ))
+        })
+        .map(|capacity| capacity.to_string())
+        .unwrap_or_else(|err| err.to_string())
+}
+
+fn outputs_capacity(rtx: &ResolvedTransaction) -> String {
+    rtx.transaction
+        .outputs_capacity()
+        .map(|capacity| capacity.to_string())
+        .unwrap_or_else(|err| err.to_string())
+}
@@ -7,8 +7,8 @@ use ckb_error::Error;
 use ckb_pow::PowEngine;
 use ckb_traits::HeaderProvider;
 use ckb_types::core::{HeaderView, Version};
+use ckb_util::mock_clock::unix_time_as_millis;
 use ckb_verification_traits::Verifier;
-use faketime::unix_time_as_millis;
 
 /// Context-dependent verification checks for block header
 ///
@@ -16,6 +16,7 @@ use faketime::unix_time_as_millis;
 pub struct HeaderVerifier<'a, DL> {
     data_loader: &'a DL,
     consensus: &'a Consensus,
+    pow_verified: bool,
 }
 
 impl<'a, DL: HeaderProvider> HeaderVerifier<'a, DL> {
@@ -24,8 +25,16 @@ impl<'a, DL: HeaderProvider> HeaderVerifier<'a, DL> {
         HeaderVerifier {
             consensus,
             data_loader,
+            pow_verified: false,
         }
     }
+
+    /// Skips the PoW check when the PoW of the header has been verified by the caller, such as
+    /// the header sync which verifies the PoW in batches via `PowEngine::verify_batch`.
+    pub fn pow_verified(mut self, pow_verified: bool) -> Self {
+        self.pow_verified = pow_verified;
+        self
+    }
 }
 
 impl<'a, DL: HeaderProvider> Verifier for HeaderVerifier<'a, DL> {
@@ -33,7 +42,9 @@ impl<'a, DL: HeaderProvider> Verifier for HeaderVerifier<'a, DL> {
     fn verify(&self, header: &Self::Target) -> Result<(), Error> {
         VersionVerifier::new(header, self.consensus.block_version()).verify()?;
         // POW check first
-        PowVerifier::new(header, self.consensus.pow_engine().as_ref()).verify()?;
+        if !self.pow_verified {
+            PowVerifier::new(header, self.consensus.pow_engine().as_ref()).verify()?;
+        }
         let parent = self
             .data_loader
             .get_header(&header.parent_hash())
@@ -5,8 +5,10 @@ mod block_verifier;
 pub mod cache;
 mod convert;
 mod error;
+mod explain;
 mod genesis_verifier;
 mod header_verifier;
+mod standalone;
 mod transaction_verifier;
 
 #[cfg(test)]
@@ -18,8 +20,10 @@ pub use crate::error::{
     CommitError, EpochError, HeaderError, HeaderErrorKind, InvalidParentError, NumberError,
     PowError, TimestampError, TransactionError, UnclesError, UnknownParentError,
 };
+pub use crate::explain::{explain_transaction, RuleTrace};
 pub use crate::genesis_verifier::GenesisVerifier;
 pub use crate::header_verifier::HeaderVerifier;
+pub use crate::standalone::{verify_transaction_standalone, StandaloneContext};
 pub use crate::transaction_verifier::{
     ContextualTransactionVerifier, ContextualWithoutScriptTransactionVerifier,
     NonContextualTransactionVerifier, ScriptVerifier, Since, SinceMetric,
@@ -0,0 +1,168 @@
+//! Standalone transaction verification.
+//!
+//! Verifies a transaction against the cells and headers supplied by the caller instead of the
+//! chain store, so wallets and off-chain services can pre-validate transactions with the same
+//! rules and the same cycle measurement as the node.
+use crate::cache::Completed;
+use crate::transaction_verifier::{Since, SinceMetric, TransactionVerifier};
+use crate::TxVerifyEnv;
+use ckb_chain_spec::consensus::Consensus;
+use ckb_error::Error;
+use ckb_traits::{BlockEpoch, CellDataProvider, EpochProvider, HeaderProvider};
+use ckb_types::{
+    bytes::Bytes,
+    core::{
+        cell::{resolve_transaction, CellMeta, CellProvider, CellStatus, HeaderChecker},
+        error::OutPointError,
+        EpochExt, HeaderView, TransactionView,
+    },
+    packed::{Byte32, OutPoint},
+    prelude::*,
+};
+use std::collections::{HashMap, HashSet};
+
+/// The chain data read by [`verify_transaction_standalone`].
+///
+/// It must contain:
+///
+/// * the live cells referenced by the inputs and the cell deps, and the members of the dep groups;
+/// * the headers in the header deps;
+/// * when an input has a timestamp since, the tip header and its ancestors up to the median time
+///   block count, and for a relative one also the header of the block containing the input cell
+///   and its ancestors.
+#[derive(Default, Clone)]
+pub struct StandaloneContext {
+    cells: HashMap<OutPoint, CellMeta>,
+    headers: HashMap<Byte32, HeaderView>,
+}
+
+impl StandaloneContext {
+    /// Creates an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a live cell, the cell data must be loaded in `mem_cell_data`.
+    pub fn insert_cell(&mut self, cell: CellMeta) {
+        self.cells.insert(cell.out_point.clone(), cell);
+    }
+
+    /// Adds a header.
+    pub fn insert_header(&mut self, header: HeaderView) {
+        self.headers.insert(header.hash(), header);
+    }
+
+    // Checks the headers read by the median time of the block and its ancestors are present,
+    // the verifiers expect them to exist.
+    fn check_median_time_headers(
+        &self,
+        block_hash: &Byte32,
+        median_block_count: usize,
+    ) -> Result<(), OutPointError> {
+        let mut block_hash = block_hash.clone();
+        for _ in 0..median_block_count {
+            let header = self
+                .headers
+                .get(&block_hash)
+                .ok_or_else(|| OutPointError::InvalidHeader(block_hash.clone()))?;
+            if header.number() == 0 {
+                break;
+            }
+            block_hash = header.parent_hash();
+        }
+        Ok(())
+    }
+
+    fn check_since_headers(
+        &self,
+        tx: &TransactionView,
+        resolved_inputs: &[CellMeta],
+        consensus: &Consensus,
+        tx_env: &TxVerifyEnv,
+    ) -> Result<(), OutPointError> {
+        let median_block_count = consensus.median_time_block_count();
+        for (input, cell_meta) in tx.inputs().into_iter().zip(resolved_inputs) {
+            let since = Since(input.since().unpack());
+            if let Some(SinceMetric::Timestamp(_)) = since.extract_metric() {
+                self.check_median_time_headers(&tx_env.parent_hash(), median_block_count)?;
+                if since.is_relative() {
+                    if let Some(ref info) = cell_meta.transaction_info {
+                        let header = self
+                            .headers
+                            .get(&info.block_hash)
+                            .ok_or_else(|| OutPointError::InvalidHeader(info.block_hash.clone()))?;
+                        self.check_median_time_headers(&header.parent_hash(), median_block_count)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CellProvider for StandaloneContext {
+    fn cell(&self, out_point: &OutPoint, _eager_load: bool) -> CellStatus {
+        match self.cells.get(out_point) {
+            Some(cell_meta) => CellStatus::live_cell(cell_meta.clone()),
+            None => CellStatus::Unknown,
+        }
+    }
+}
+
+impl HeaderChecker for StandaloneContext {
+    fn check_valid(&self, block_hash: &Byte32) -> Result<(), OutPointError> {
+        if self.headers.contains_key(block_hash) {
+            Ok(())
+        } else {
+            Err(OutPointError::InvalidHeader(block_hash.clone()))
+        }
+    }
+}
+
+impl CellDataProvider for StandaloneContext {
+    fn get_cell_data(&self, out_point: &OutPoint) -> Option<Bytes> {
+        self.cells
+            .get(out_point)
+            .and_then(|cell_meta| cell_meta.mem_cell_data.clone())
+    }
+
+    fn get_cell_data_hash(&self, out_point: &OutPoint) -> Option<Byte32> {
+        self.cells
+            .get(out_point)
+            .and_then(|cell_meta| cell_meta.mem_cell_data_hash.clone())
+    }
+}
+
+impl HeaderProvider for StandaloneContext {
+    fn get_header(&self, hash: &Byte32) -> Option<HeaderView> {
+        self.headers.get(hash).cloned()
+    }
+}
+
+// The transaction verification does not read the epochs.
+impl EpochProvider for StandaloneContext {
+    fn get_epoch_ext(&self, _block_header: &HeaderView) -> Option<EpochExt> {
+        None
+    }
+
+    fn get_block_epoch(&self, _block_header: &HeaderView) -> Option<BlockEpoch> {
+        None
+    }
+}
+
+/// Verifies the transaction without the chain store, and returns the consumed cycles and the fee.
+///
+/// The transaction is resolved from the cells in the context and then verified by
+/// [`TransactionVerifier`] with the max block cycles of the consensus, which are the same rules
+/// the node applies. `tx_env` describes the tip which the transaction is verified against, see
+/// [`TxVerifyEnv`].
+pub fn verify_transaction_standalone(
+    tx: TransactionView,
+    context: &StandaloneContext,
+    consensus: &Consensus,
+    tx_env: &TxVerifyEnv,
+) -> Result<Completed, Error> {
+    let rtx = resolve_transaction(tx, &mut HashSet::new(), context, context)?;
+    context.check_since_headers(&rtx.transaction, &rtx.resolved_inputs, consensus, tx_env)?;
+    TransactionVerifier::new(&rtx, consensus, context, tx_env).verify(consensus.max_block_cycles())
+}
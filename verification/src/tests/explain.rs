@@ -0,0 +1,124 @@
+use super::build_context;
+use crate::{explain_transaction, RuleTrace, StandaloneContext, TxVerifyEnv};
+use ckb_chain_spec::consensus::Consensus;
+use ckb_test_chain_utils::{always_success_cell, always_success_consensus};
+use ckb_types::{
+    core::{
+        capacity_bytes, cell::resolve_transaction, Capacity, Cycle, HeaderBuilder,
+        TransactionBuilder, TransactionView,
+    },
+    packed::{CellDep, CellInput, CellOutput, OutPoint},
+    prelude::*,
+};
+use std::collections::HashSet;
+
+const STEPS: [&str; 11] = [
+    "VersionVerifier",
+    "SizeVerifier",
+    "EmptyVerifier",
+    "DuplicateDepsVerifier",
+    "OutputsDataVerifier",
+    "CompatibleVerifier",
+    "MaturityVerifier",
+    "SinceVerifier",
+    "CapacityVerifier",
+    "ScriptVerifier",
+    "FeeCalculator",
+];
+
+fn build_tx(cell_deps: Vec<OutPoint>, input: OutPoint, capacity: Capacity) -> TransactionView {
+    TransactionBuilder::default()
+        .cell_deps(
+            cell_deps
+                .into_iter()
+                .map(|out_point| CellDep::new_builder().out_point(out_point).build()),
+        )
+        .input(CellInput::new(input, 0))
+        .output(
+            CellOutput::new_builder()
+                .capacity(capacity.pack())
+                .lock(always_success_cell().2.clone())
+                .build(),
+        )
+        .output_data(Default::default())
+        .build()
+}
+
+fn explain(
+    tx: TransactionView,
+    context: &StandaloneContext,
+    consensus: &Consensus,
+    max_cycles: Cycle,
+) -> Vec<RuleTrace> {
+    let tip = HeaderBuilder::default().number(10.pack()).build();
+    let tx_env = TxVerifyEnv::new_submit(&tip);
+    let rtx = resolve_transaction(tx, &mut HashSet::new(), context, context).unwrap();
+    explain_transaction(&rtx, consensus, context, &tx_env, max_cycles)
+}
+
+fn failed_rules(traces: &[RuleTrace]) -> Vec<&'static str> {
+    traces
+        .iter()
+        .filter(|trace| !trace.passed)
+        .map(|trace| trace.rule)
+        .collect()
+}
+
+#[test]
+fn test_explain_valid_tx() {
+    let consensus = always_success_consensus();
+    let (context, code_out_point, input_out_point) = build_context();
+    let tx = build_tx(vec![code_out_point], input_out_point, capacity_bytes!(50));
+
+    let traces = explain(tx, &context, &consensus, consensus.max_block_cycles());
+    let rules: Vec<_> = traces.iter().map(|trace| trace.rule).collect();
+    assert_eq!(rules, STEPS);
+    assert!(failed_rules(&traces).is_empty());
+    assert_eq!(traces[10].outcome, format!("fee: {}", capacity_bytes!(50)));
+}
+
+#[test]
+fn test_explain_first_step_failed() {
+    let consensus = always_success_consensus();
+    let (context, code_out_point, input_out_point) = build_context();
+    let tx = build_tx(vec![code_out_point], input_out_point, capacity_bytes!(50))
+        .as_advanced_builder()
+        .version((consensus.tx_version() + 1).pack())
+        .build();
+
+    // the later steps are still evaluated
+    let traces = explain(tx, &context, &consensus, consensus.max_block_cycles());
+    assert_eq!(traces.len(), STEPS.len());
+    assert_eq!(failed_rules(&traces), vec!["VersionVerifier"]);
+}
+
+#[test]
+fn test_explain_later_step_failed() {
+    let consensus = always_success_consensus();
+    let (context, code_out_point, input_out_point) = build_context();
+    let tx = build_tx(vec![code_out_point], input_out_point, capacity_bytes!(150));
+
+    let traces = explain(tx, &context, &consensus, consensus.max_block_cycles());
+    assert_eq!(traces.len(), STEPS.len());
+    assert_eq!(
+        failed_rules(&traces),
+        vec!["CapacityVerifier", "FeeCalculator"]
+    );
+}
+
+#[test]
+fn test_explain_script_step_failed() {
+    let consensus = always_success_consensus();
+    let (context, code_out_point, input_out_point) = build_context();
+
+    // the code of the lock script is missing
+    let tx = build_tx(vec![], input_out_point.clone(), capacity_bytes!(50));
+    let traces = explain(tx, &context, &consensus, consensus.max_block_cycles());
+    assert_eq!(failed_rules(&traces), vec!["ScriptVerifier"]);
+
+    // the script exceeds the cycle limit
+    let tx = build_tx(vec![code_out_point], input_out_point, capacity_bytes!(50));
+    let traces = explain(tx, &context, &consensus, 1);
+    assert_eq!(failed_rules(&traces), vec!["ScriptVerifier"]);
+    assert_eq!(traces[9].inputs, vec![("max_cycles", "1".to_owned())]);
+}
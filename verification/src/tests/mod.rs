@@ -1,4 +1,50 @@
+use crate::StandaloneContext;
+use ckb_test_chain_utils::always_success_cell;
+use ckb_types::{
+    core::{capacity_bytes, cell::CellMetaBuilder, Capacity},
+    packed::{Byte32, CellOutput, OutPoint},
+    prelude::*,
+};
+
 mod block_verifier;
+mod explain;
 mod genesis_verifier;
 mod header_verifier;
+mod standalone;
 mod transaction_verifier;
+
+fn h256_byte32(n: u8) -> Byte32 {
+    let mut hash = [0u8; 32];
+    hash[0] = n;
+    hash.pack()
+}
+
+// Returns the context and the out points of the always success code cell and a 100 CKB cell
+// locked by it
+fn build_context() -> (StandaloneContext, OutPoint, OutPoint) {
+    let (always_success_output, always_success_data, always_success_script) = always_success_cell();
+    let code_out_point = OutPoint::new(h256_byte32(1), 0);
+    let input_out_point = OutPoint::new(h256_byte32(2), 0);
+
+    let mut context = StandaloneContext::new();
+    context.insert_cell(
+        CellMetaBuilder::from_cell_output(
+            always_success_output.clone(),
+            always_success_data.clone(),
+        )
+        .out_point(code_out_point.clone())
+        .build(),
+    );
+    context.insert_cell(
+        CellMetaBuilder::from_cell_output(
+            CellOutput::new_builder()
+                .capacity(capacity_bytes!(100).pack())
+                .lock(always_success_script.clone())
+                .build(),
+            Default::default(),
+        )
+        .out_point(input_out_point.clone())
+        .build(),
+    );
+    (context, code_out_point, input_out_point)
+}
@@ -0,0 +1,64 @@
+use super::{build_context, h256_byte32};
+use crate::{verify_transaction_standalone, TxVerifyEnv};
+use ckb_error::assert_error_eq;
+use ckb_test_chain_utils::{always_success_cell, always_success_consensus};
+use ckb_types::{
+    core::{
+        capacity_bytes, error::OutPointError, Capacity, HeaderBuilder, TransactionBuilder,
+        TransactionView,
+    },
+    packed::{CellDep, CellInput, CellOutput, OutPoint},
+    prelude::*,
+};
+
+fn build_tx(code_out_point: OutPoint, input_out_point: OutPoint, since: u64) -> TransactionView {
+    let (_, _, always_success_script) = always_success_cell();
+    TransactionBuilder::default()
+        .cell_dep(CellDep::new_builder().out_point(code_out_point).build())
+        .input(CellInput::new(input_out_point, since))
+        .output(
+            CellOutput::new_builder()
+                .capacity(capacity_bytes!(50).pack())
+                .lock(always_success_script.clone())
+                .build(),
+        )
+        .output_data(Default::default())
+        .build()
+}
+
+#[test]
+fn test_verify_transaction_standalone() {
+    let consensus = always_success_consensus();
+    let tip = HeaderBuilder::default().number(10.pack()).build();
+    let tx_env = TxVerifyEnv::new_submit(&tip);
+    let (context, code_out_point, input_out_point) = build_context();
+
+    let tx = build_tx(code_out_point, input_out_point, 0);
+    let completed = verify_transaction_standalone(tx, &context, &consensus, &tx_env).unwrap();
+    assert!(completed.cycles > 0);
+    assert_eq!(completed.fee, capacity_bytes!(50));
+}
+
+#[test]
+fn test_verify_transaction_standalone_missing_data() {
+    let consensus = always_success_consensus();
+    let tip = HeaderBuilder::default().number(10.pack()).build();
+    let tx_env = TxVerifyEnv::new_submit(&tip);
+    let (context, code_out_point, input_out_point) = build_context();
+
+    let unknown_out_point = OutPoint::new(h256_byte32(3), 0);
+    let tx = build_tx(code_out_point.clone(), unknown_out_point.clone(), 0);
+    assert_error_eq!(
+        verify_transaction_standalone(tx, &context, &consensus, &tx_env).unwrap_err(),
+        OutPointError::Unknown(unknown_out_point),
+    );
+
+    // the absolute timestamp since reads the median time ending at the tip, which is the parent
+    // of the block committing a submitted transaction, and the tip header is missing
+    let since = 0x4000_0000_0000_0000;
+    let tx = build_tx(code_out_point, input_out_point, since);
+    assert_error_eq!(
+        verify_transaction_standalone(tx, &context, &consensus, &tx_env).unwrap_err(),
+        OutPointError::InvalidHeader(tip.hash()),
+    );
+}
@@ -18,6 +18,73 @@ use ckb_types::{
 use std::collections::HashSet;
 use std::convert::TryInto;
 
+/// A step of the transaction verification, named after the verifier which runs it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TransactionVerifyStep {
+    Version,
+    Size,
+    Empty,
+    DuplicateDeps,
+    OutputsData,
+    Compatible,
+    Maturity,
+    Since,
+    Capacity,
+    Script,
+    Fee,
+}
+
+const NON_CONTEXTUAL_STEPS: [TransactionVerifyStep; 5] = [
+    TransactionVerifyStep::Version,
+    TransactionVerifyStep::Size,
+    TransactionVerifyStep::Empty,
+    TransactionVerifyStep::DuplicateDeps,
+    TransactionVerifyStep::OutputsData,
+];
+
+const CONTEXTUAL_STEPS: [TransactionVerifyStep; 6] = [
+    TransactionVerifyStep::Compatible,
+    TransactionVerifyStep::Maturity,
+    TransactionVerifyStep::Since,
+    TransactionVerifyStep::Capacity,
+    TransactionVerifyStep::Script,
+    TransactionVerifyStep::Fee,
+];
+
+impl TransactionVerifyStep {
+    /// All the steps, in the order [`TransactionVerifier`] runs them
+    pub(crate) fn steps() -> impl Iterator<Item = TransactionVerifyStep> {
+        NON_CONTEXTUAL_STEPS
+            .iter()
+            .chain(CONTEXTUAL_STEPS.iter())
+            .copied()
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            TransactionVerifyStep::Version => "VersionVerifier",
+            TransactionVerifyStep::Size => "SizeVerifier",
+            TransactionVerifyStep::Empty => "EmptyVerifier",
+            TransactionVerifyStep::DuplicateDeps => "DuplicateDepsVerifier",
+            TransactionVerifyStep::OutputsData => "OutputsDataVerifier",
+            TransactionVerifyStep::Compatible => "CompatibleVerifier",
+            TransactionVerifyStep::Maturity => "MaturityVerifier",
+            TransactionVerifyStep::Since => "SinceVerifier",
+            TransactionVerifyStep::Capacity => "CapacityVerifier",
+            TransactionVerifyStep::Script => "ScriptVerifier",
+            TransactionVerifyStep::Fee => "FeeCalculator",
+        }
+    }
+}
+
+/// The result of a passed verification step
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum StepOutcome {
+    Passed,
+    Cycles(Cycle),
+    Fee(Capacity),
+}
+
 /// The time-related TX verification
 ///
 /// Contains:
@@ -81,12 +148,22 @@ impl<'a> NonContextualTransactionVerifier<'a> {
 
     /// Perform context-independent verification
     pub fn verify(&self) -> Result<(), Error> {
-        self.version.verify()?;
-        self.size.verify()?;
-        self.empty.verify()?;
-        self.duplicate_deps.verify()?;
-        self.outputs_data_verifier.verify()?;
-        Ok(())
+        NON_CONTEXTUAL_STEPS
+            .iter()
+            .try_for_each(|step| self.verify_step(*step))
+    }
+
+    pub(crate) fn verify_step(&self, step: TransactionVerifyStep) -> Result<(), Error> {
+        match step {
+            TransactionVerifyStep::Version => self.version.verify(),
+            TransactionVerifyStep::Size => self.size.verify(),
+            TransactionVerifyStep::Empty => self.empty.verify(),
+            TransactionVerifyStep::DuplicateDeps => self.duplicate_deps.verify(),
+            TransactionVerifyStep::OutputsData => {
+                self.outputs_data_verifier.verify().map_err(Into::into)
+            }
+            _ => unreachable!("{:?} is a contextual step", step),
+        }
     }
 }
 
@@ -148,17 +225,52 @@ where
     /// skip script verify will result in the return value cycle always is zero
     pub fn verify(&self, max_cycles: Cycle, skip_script_verify: bool) -> Result<Completed, Error> {
         let timer = Timer::start();
-        self.compatible.verify()?;
-        self.time_relative.verify()?;
-        self.capacity.verify()?;
-        let cycles = if skip_script_verify {
-            0
-        } else {
-            self.script.verify(max_cycles)?
+        let mut completed = Completed {
+            cycles: 0,
+            fee: Capacity::zero(),
         };
-        let fee = self.fee_calculator.transaction_fee()?;
+        for step in CONTEXTUAL_STEPS.iter() {
+            if skip_script_verify && *step == TransactionVerifyStep::Script {
+                continue;
+            }
+            match self.verify_step(*step, max_cycles)? {
+                StepOutcome::Passed => {}
+                StepOutcome::Cycles(cycles) => completed.cycles = cycles,
+                StepOutcome::Fee(fee) => completed.fee = fee,
+            }
+        }
         metrics!(timing, "ckb.contextual_verified_tx", timer.stop());
-        Ok(Completed { cycles, fee })
+        Ok(completed)
+    }
+
+    pub(crate) fn verify_step(
+        &self,
+        step: TransactionVerifyStep,
+        max_cycles: Cycle,
+    ) -> Result<StepOutcome, Error> {
+        match step {
+            TransactionVerifyStep::Compatible => {
+                self.compatible.verify().map(|_| StepOutcome::Passed)
+            }
+            TransactionVerifyStep::Maturity => self
+                .time_relative
+                .maturity
+                .verify()
+                .map(|_| StepOutcome::Passed),
+            TransactionVerifyStep::Since => self
+                .time_relative
+                .since
+                .verify()
+                .map(|_| StepOutcome::Passed),
+            TransactionVerifyStep::Capacity => self.capacity.verify().map(|_| StepOutcome::Passed),
+            TransactionVerifyStep::Script => {
+                self.script.verify(max_cycles).map(StepOutcome::Cycles)
+            }
+            TransactionVerifyStep::Fee => {
+                self.fee_calculator.transaction_fee().map(StepOutcome::Fee)
+            }
+            _ => unreachable!("{:?} is a non-contextual step", step),
+        }
     }
 
     /// Perform complete a suspend context-dependent verification, return a `Result` to `CacheEntry`
@@ -214,6 +326,21 @@ impl<'a, DL: HeaderProvider + CellDataProvider + EpochProvider> TransactionVerif
         self.non_contextual.verify()?;
         self.contextual.verify(max_cycles, false)
     }
+
+    /// Runs a single step of [`verify`](#method.verify)
+    pub(crate) fn verify_step(
+        &self,
+        step: TransactionVerifyStep,
+        max_cycles: Cycle,
+    ) -> Result<StepOutcome, Error> {
+        if NON_CONTEXTUAL_STEPS.contains(&step) {
+            self.non_contextual
+                .verify_step(step)
+                .map(|_| StepOutcome::Passed)
+        } else {
+            self.contextual.verify_step(step, max_cycles)
+        }
+    }
 }
 
 pub struct FeeCalculator<'a, DL> {